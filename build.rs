@@ -0,0 +1,14 @@
+//! Build script: compiles the gRPC service definition when the `grpc` feature is enabled.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/lytt.proto");
+
+        let fds = protox::compile(["proto/lytt.proto"], ["proto"]).expect("Failed to parse proto/lytt.proto");
+        tonic_prost_build::configure()
+            .build_client(false)
+            .compile_fds(fds)
+            .expect("Failed to generate gRPC code");
+    }
+}
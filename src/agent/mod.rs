@@ -6,6 +6,8 @@
 
 mod runner;
 mod tools;
+mod web;
 
 pub use runner::{Agent, AgentResponse, ToolCallRecord};
 pub use tools::{parse_tool_call, tool_definitions, ToolCall, ToolContext};
+pub use web::WebTools;
@@ -1,6 +1,6 @@
 //! Agent runner with tool calling loop.
 
-use super::tools::{parse_tool_call, tool_definitions, ToolContext};
+use super::tools::{parse_tool_call, ToolContext};
 use crate::error::{LyttError, Result};
 use async_openai::types::{
     ChatCompletionMessageToolCall, ChatCompletionRequestAssistantMessageArgs,
@@ -8,7 +8,8 @@ use async_openai::types::{
     ChatCompletionRequestToolMessageArgs, ChatCompletionRequestUserMessageArgs,
     CreateChatCompletionRequestArgs,
 };
-use crate::openai::create_client;
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
 use tracing::{debug, info};
 
 /// Default system prompt for the agent.
@@ -23,6 +24,7 @@ Guidelines:
 - Use 'get_transcript' to get a full video transcript for summaries, quizzes, or deep analysis
 - Use 'get_segment' to get content from a specific time range
 - Use 'get_video_info' to get metadata about a video
+- If 'web_search' and 'fetch_url' are available, use them for information the videos do not cover, and cite URLs for anything taken from the web
 
 When you have gathered enough information, provide your final response.
 Always cite your sources with video titles and timestamps when relevant.
@@ -30,7 +32,7 @@ Format your responses clearly with appropriate structure (headers, lists, etc.).
 
 /// Agent that can use tools to interact with the video knowledge base.
 pub struct Agent {
-    client: async_openai::Client<async_openai::config::OpenAIConfig>,
+    client: ChatClient,
     model: String,
     tools: ToolContext,
     max_iterations: usize,
@@ -41,7 +43,7 @@ impl Agent {
     /// Create a new agent with the given tool context and model.
     pub fn new(tools: ToolContext, model: &str) -> Self {
         Self {
-            client: create_client(),
+            client: ChatClient::openai(),
             model: model.to_string(),
             tools,
             max_iterations: 15,
@@ -55,6 +57,12 @@ impl Agent {
         self
     }
 
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Set maximum iterations for the agent loop.
     pub fn with_max_iterations(mut self, max: usize) -> Self {
         self.max_iterations = max;
@@ -103,16 +111,13 @@ impl Agent {
             let request = CreateChatCompletionRequestArgs::default()
                 .model(&self.model)
                 .messages(messages.clone())
-                .tools(tool_definitions())
+                .tools(self.tools.definitions())
                 .build()
                 .map_err(|e| LyttError::Agent(e.to_string()))?;
 
-            let response = self
-                .client
-                .chat()
-                .create(request)
-                .await
-                .map_err(|e| LyttError::OpenAI(format!("Agent API error: {}", e)))?;
+            let response = self.client.create(request).await?;
+
+            usage::record_chat(UsageStage::Agent, &self.model, response.usage.as_ref());
 
             let choice = response
                 .choices
@@ -1,5 +1,7 @@
 //! Tool definitions and implementations for the agent system.
 
+use super::web::WebTools;
+use crate::config::WebToolSettings;
 use crate::embedding::Embedder;
 use crate::error::{LyttError, Result};
 use crate::vector_store::VectorStore;
@@ -32,6 +34,16 @@ pub enum ToolCall {
 
     /// Get video metadata.
     GetVideoInfo { video_id: String },
+
+    /// Search the web (only when web tools are enabled).
+    WebSearch {
+        query: String,
+        #[serde(default = "default_limit")]
+        limit: u32,
+    },
+
+    /// Fetch a web page as text (only when web tools are enabled).
+    FetchUrl { url: String },
 }
 
 fn default_limit() -> u32 {
@@ -39,9 +51,11 @@ fn default_limit() -> u32 {
 }
 
 /// Tool execution context with access to vector store and embedder.
+#[derive(Clone)]
 pub struct ToolContext {
     pub vector_store: Arc<dyn VectorStore>,
     pub embedder: Arc<dyn Embedder>,
+    web: Option<Arc<WebTools>>,
 }
 
 impl ToolContext {
@@ -50,7 +64,31 @@ impl ToolContext {
         Self {
             vector_store,
             embedder,
+            web: None,
+        }
+    }
+
+    /// Add the `web_search` and `fetch_url` tools if enabled in settings.
+    pub fn with_web(mut self, settings: &WebToolSettings) -> Result<Self> {
+        if settings.enabled {
+            self.web = Some(Arc::new(WebTools::new(settings)?));
         }
+        Ok(self)
+    }
+
+    /// Tool definitions for this context (web tools only when enabled).
+    pub fn definitions(&self) -> Vec<async_openai::types::ChatCompletionTool> {
+        let mut definitions = tool_definitions();
+        if self.web.is_some() {
+            definitions.extend(web_tool_definitions());
+        }
+        definitions
+    }
+
+    fn web(&self) -> Result<&WebTools> {
+        self.web
+            .as_deref()
+            .ok_or_else(|| LyttError::Agent("Web tools are disabled (set agent.web.enabled)".to_string()))
     }
 
     /// Execute a tool call and return the result as a string.
@@ -68,6 +106,8 @@ impl ToolContext {
             }
             ToolCall::ListVideos => self.execute_list_videos().await,
             ToolCall::GetVideoInfo { video_id } => self.execute_get_video_info(video_id).await,
+            ToolCall::WebSearch { query, limit } => self.web()?.search(query, *limit).await,
+            ToolCall::FetchUrl { url } => self.web()?.fetch(url).await,
         }
     }
 
@@ -346,6 +386,63 @@ pub fn tool_definitions() -> Vec<async_openai::types::ChatCompletionTool> {
     ]
 }
 
+/// Definitions of the optional web tools.
+fn web_tool_definitions() -> Vec<async_openai::types::ChatCompletionTool> {
+    use async_openai::types::{ChatCompletionTool, ChatCompletionToolType, FunctionObject};
+
+    vec![
+        ChatCompletionTool {
+            r#type: ChatCompletionToolType::Function,
+            function: FunctionObject {
+                name: "web_search".to_string(),
+                description: Some(
+                    "Search the web for current or external information. \
+                    Use this when the indexed videos do not cover something or may be out of date."
+                        .to_string(),
+                ),
+                parameters: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "The search query"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of results (default: 5)",
+                            "default": 5
+                        }
+                    },
+                    "required": ["query"]
+                })),
+                strict: None,
+            },
+        },
+        ChatCompletionTool {
+            r#type: ChatCompletionToolType::Function,
+            function: FunctionObject {
+                name: "fetch_url".to_string(),
+                description: Some(
+                    "Fetch a web page and return its text. \
+                    Use this to read a page found with web_search."
+                        .to_string(),
+                ),
+                parameters: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "url": {
+                            "type": "string",
+                            "description": "The http(s) URL to fetch"
+                        }
+                    },
+                    "required": ["url"]
+                })),
+                strict: None,
+            },
+        },
+    ]
+}
+
 /// Parse a tool call from the OpenAI response format.
 pub fn parse_tool_call(name: &str, arguments: &str) -> Result<ToolCall> {
     // Parse the arguments JSON and construct the appropriate ToolCall variant
@@ -393,6 +490,21 @@ pub fn parse_tool_call(name: &str, arguments: &str) -> Result<ToolCall> {
                 .to_string();
             Ok(ToolCall::GetVideoInfo { video_id })
         }
+        "web_search" => {
+            let query = args["query"]
+                .as_str()
+                .ok_or_else(|| LyttError::Agent("Missing 'query' argument".to_string()))?
+                .to_string();
+            let limit = args["limit"].as_u64().unwrap_or(5) as u32;
+            Ok(ToolCall::WebSearch { query, limit })
+        }
+        "fetch_url" => {
+            let url = args["url"]
+                .as_str()
+                .ok_or_else(|| LyttError::Agent("Missing 'url' argument".to_string()))?
+                .to_string();
+            Ok(ToolCall::FetchUrl { url })
+        }
         _ => Err(LyttError::Agent(format!("Unknown tool: {}", name))),
     }
 }
@@ -0,0 +1,337 @@
+//! Web search and page fetching for the agent.
+//!
+//! Both tools are off unless `agent.web.enabled` is set. When
+//! `agent.web.allowed_domains` is non-empty, search results and fetched pages
+//! (including redirects) are limited to those domains and their subdomains.
+//! Either way, hosts that are or resolve to loopback, private, link-local or
+//! otherwise non-public addresses are refused, so the agent can't be pointed
+//! at services on the local machine or network.
+
+use crate::config::WebToolSettings;
+use crate::error::{LyttError, Result};
+use serde::Deserialize;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
+/// Results requested from the search API before allowlist filtering.
+const SEARCH_FETCH_COUNT: u32 = 20;
+
+#[derive(Deserialize)]
+struct BraveResponse {
+    #[serde(default)]
+    web: Option<BraveWebResults>,
+}
+
+#[derive(Deserialize)]
+struct BraveWebResults {
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Web tools shared by the agent's tool context.
+pub struct WebTools {
+    client: reqwest::Client,
+    settings: WebToolSettings,
+}
+
+impl WebTools {
+    /// Create web tools from settings.
+    pub fn new(settings: &WebToolSettings) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_seconds))
+            .user_agent(concat!("lytt/", env!("CARGO_PKG_VERSION")))
+            .redirect(redirect_policy(settings.allowed_domains.clone()))
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()?;
+
+        Ok(Self {
+            client,
+            settings: settings.clone(),
+        })
+    }
+
+    /// Search the web and format the results for the model.
+    pub async fn search(&self, query: &str, limit: u32) -> Result<String> {
+        let key_env = &self.settings.search_api_key_env;
+        let api_key = std::env::var(key_env)
+            .map_err(|_| LyttError::Agent(format!("{} is not set; web search needs a Brave Search API key", key_env)))?;
+
+        let response = self
+            .client
+            .get(BRAVE_SEARCH_URL)
+            .query(&[("q", query), ("count", &SEARCH_FETCH_COUNT.to_string())])
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", api_key)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(LyttError::Agent(format!("Web search failed: HTTP {}", response.status())));
+        }
+
+        let body: BraveResponse = response.json().await?;
+        let results: Vec<BraveResult> = body
+            .web
+            .map(|w| w.results)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| {
+                Url::parse(&r.url)
+                    .map(|url| is_allowed(&url, &self.settings.allowed_domains))
+                    .unwrap_or(false)
+            })
+            .take(limit as usize)
+            .collect();
+
+        if results.is_empty() {
+            return Ok("No web results found.".to_string());
+        }
+
+        let formatted = results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| format!("{}. {}\n   {}\n   {}", i + 1, r.title, r.url, html_to_text(&r.description)))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(format!("Found {} web results:\n\n{}", results.len(), formatted))
+    }
+
+    /// Fetch a page and return its readable text.
+    pub async fn fetch(&self, url: &str) -> Result<String> {
+        let parsed = Url::parse(url)
+            .map_err(|e| LyttError::Agent(format!("Invalid URL '{}': {}", url, e)))?;
+        if !is_allowed(&parsed, &self.settings.allowed_domains) {
+            return Err(LyttError::Agent(format!(
+                "{} is not in agent.web.allowed_domains",
+                parsed.host_str().unwrap_or(url)
+            )));
+        }
+
+        debug!("Fetching {}", parsed);
+        let response = self.client.get(parsed.clone()).send().await?;
+        if !response.status().is_success() {
+            return Err(LyttError::Agent(format!("Fetching {} failed: HTTP {}", parsed, response.status())));
+        }
+
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("html"));
+        let body = response.text().await?;
+        let text = if is_html { html_to_text(&body) } else { body };
+
+        let mut truncated: String = text.chars().take(self.settings.max_chars).collect();
+        if truncated.len() < text.len() {
+            truncated.push_str("\n\n[truncated]");
+        }
+
+        Ok(format!("Content of {}:\n\n{}", parsed, truncated))
+    }
+}
+
+/// Whether a URL is http(s), its host is in the allowlist (or the list is
+/// empty) and isn't `localhost` or a non-public IP address. Hostnames are
+/// checked again once resolved, by [`PublicResolver`].
+fn is_allowed(url: &Url, allowed_domains: &[String]) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    match url.host() {
+        Some(url::Host::Ipv4(ip)) if !is_public_ip(IpAddr::V4(ip)) => return false,
+        Some(url::Host::Ipv6(ip)) if !is_public_ip(IpAddr::V6(ip)) => return false,
+        Some(_) => {}
+        None => return false,
+    }
+    let Some(host) = url.host_str().map(|h| h.trim_end_matches('.').to_lowercase()) else {
+        return false;
+    };
+    if host == "localhost" || host.ends_with(".localhost") {
+        return false;
+    }
+
+    allowed_domains.is_empty()
+        || allowed_domains.iter().any(|domain| {
+            let domain = domain.trim().trim_start_matches('.').to_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+}
+
+/// Follow redirects only to URLs [`is_allowed`] accepts.
+fn redirect_policy(allowed: Vec<String>) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= 5 {
+            attempt.error("too many redirects")
+        } else if is_allowed(attempt.url(), &allowed) {
+            attempt.follow()
+        } else {
+            attempt.error("redirect to a domain outside agent.web.allowed_domains")
+        }
+    })
+}
+
+/// Whether an address is reachable on the public internet: not loopback,
+/// private, link-local, unique-local, shared (CGNAT), unspecified, broadcast
+/// or documentation space.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(v4);
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || ip.segments()[..2] == [0x2001, 0x0db8])
+        }
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)))
+}
+
+/// DNS resolver that drops non-public addresses, so hostnames pointing at
+/// the local network are refused on every request and redirect.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Strip tags, scripts and styles from HTML and collapse whitespace.
+fn html_to_text(html: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so indices carry over to `html`
+    let lower = html.to_ascii_lowercase();
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut pos = 0;
+
+    while let Some(offset) = lower[pos..].find('<') {
+        let start = pos + offset;
+        text.push_str(&html[pos..start]);
+        text.push(' ');
+
+        let skip_to = ["script", "style"]
+            .iter()
+            .find(|tag| lower[start + 1..].starts_with(*tag))
+            .and_then(|tag| lower[start..].find(&format!("</{}", tag)).map(|end| start + end));
+        let tag_start = skip_to.unwrap_or(start);
+
+        pos = match lower[tag_start..].find('>') {
+            Some(end) => tag_start + end + 1,
+            None => html.len(),
+        };
+    }
+    text.push_str(&html[pos..]);
+
+    let decoded = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_allowed() {
+        let allowed = vec!["wikipedia.org".to_string(), ".arxiv.org".to_string()];
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        assert!(is_allowed(&url("https://en.wikipedia.org/wiki/Entropy"), &allowed));
+        assert!(is_allowed(&url("https://arxiv.org/abs/1706.03762"), &allowed));
+        assert!(!is_allowed(&url("https://notwikipedia.org/"), &allowed));
+        assert!(!is_allowed(&url("file:///etc/passwd"), &[]));
+        assert!(is_allowed(&url("http://example.com/"), &[]));
+        assert!(is_allowed(&url("http://93.184.215.14/"), &[]));
+        assert!(!is_allowed(&url("http://localhost:8080/"), &[]));
+        assert!(!is_allowed(&url("http://127.0.0.1/"), &[]));
+        assert!(!is_allowed(&url("http://169.254.169.254/latest/meta-data/"), &[]));
+        assert!(!is_allowed(&url("http://10.0.0.5/"), &[]));
+        assert!(!is_allowed(&url("http://[::1]/"), &[]));
+        assert!(!is_allowed(&url("http://[fd00::1]/"), &[]));
+        assert!(!is_allowed(&url("http://[::ffff:192.168.1.1]/"), &[]));
+    }
+
+    #[tokio::test]
+    async fn test_redirects_to_local_addresses_are_refused() {
+        use axum::{response::Redirect, routing::get, Router};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        static REACHED: AtomicBool = AtomicBool::new(false);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = Router::new()
+            .route("/start", get(move || async move { Redirect::temporary(&format!("http://localhost:{}/secret", port)) }))
+            .route("/secret", get(|| async { REACHED.store(true, Ordering::SeqCst) }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        // The first hop is local too, so go around `fetch`'s own check
+        let client = reqwest::Client::builder().redirect(redirect_policy(Vec::new())).build().unwrap();
+        let err = client.get(format!("http://127.0.0.1:{}/start", port)).send().await.unwrap_err();
+        assert!(err.is_redirect(), "{}", err);
+        assert!(!REACHED.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_resolver_drops_local_addresses() {
+        use reqwest::dns::Resolve;
+
+        for host in ["localhost", "127.0.0.1"] {
+            assert!(PublicResolver.resolve(host.parse().unwrap()).await.is_err(), "{}", host);
+        }
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = r#"<html><head><style>p { color: red; }</style><SCRIPT>alert("x")</SCRIPT></head>
+            <body><h1>Entropy</h1><p>Average&nbsp;surprise &amp; information.</p></body></html>"#;
+        assert_eq!(html_to_text(html), "Entropy Average surprise & information.");
+    }
+}
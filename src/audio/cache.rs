@@ -0,0 +1,202 @@
+//! Cache of downloaded audio.
+//!
+//! Downloads are kept by media ID in the cache directory, so transcribing
+//! media again (`--force`, another provider) doesn't download it again. The
+//! cache is capped in size; the least recently used audio is evicted first,
+//! going by file modification times, which are updated on every hit.
+
+use crate::config::Settings;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{debug, warn};
+
+const MB: u64 = 1024 * 1024;
+
+/// Audio held in the cache.
+#[derive(Debug, Clone)]
+pub struct CachedAudio {
+    /// Media the audio belongs to.
+    pub video_id: String,
+    /// Cached file.
+    pub path: PathBuf,
+    /// File size in bytes.
+    pub bytes: u64,
+    /// When the audio was last downloaded or used.
+    pub last_used: DateTime<Utc>,
+}
+
+/// A size-capped, least-recently-used cache of downloaded audio.
+#[derive(Debug, Clone)]
+pub struct AudioCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl AudioCache {
+    /// Create a cache in `dir` holding at most `max_size_mb` (0 disables it).
+    pub fn new(dir: impl Into<PathBuf>, max_size_mb: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: max_size_mb * MB,
+        }
+    }
+
+    /// The audio cache configured in settings.
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self::new(settings.cache_dir().join("audio"), settings.general.audio_cache_max_mb)
+    }
+
+    /// Whether audio is cached at all.
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes > 0
+    }
+
+    /// Directory the audio is kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, video_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.mp3", video_id))
+    }
+
+    /// Cached audio of `video_id`, marked as just used.
+    pub fn get(&self, video_id: &str) -> Option<PathBuf> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let path = self.path(video_id);
+        if !path.is_file() {
+            return None;
+        }
+        if let Err(e) = File::options().append(true).open(&path).and_then(|f| f.set_modified(SystemTime::now())) {
+            warn!("Failed to mark cached audio as used: {}", e);
+        }
+        Some(path)
+    }
+
+    /// Copy `audio` into the cache as the audio of `video_id`, then evict
+    /// the least recently used audio until the cache fits its cap.
+    pub fn put(&self, video_id: &str, audio: &Path) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.path(video_id);
+        // Copy under a temporary name so a half-written file is never served
+        let partial = self.dir.join(format!("{}.mp3.part", video_id));
+        std::fs::copy(audio, &partial)?;
+        std::fs::rename(&partial, &path)?;
+        debug!("Cached audio of {}", video_id);
+        self.evict(video_id);
+        Ok(())
+    }
+
+    /// Everything in the cache, most recently used first.
+    pub fn entries(&self) -> Vec<CachedAudio> {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<CachedAudio> = dir
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension().is_none_or(|e| e != "mp3") {
+                    return None;
+                }
+                let metadata = entry.metadata().ok()?;
+                Some(CachedAudio {
+                    video_id: path.file_stem()?.to_string_lossy().into_owned(),
+                    bytes: metadata.len(),
+                    last_used: metadata.modified().ok()?.into(),
+                    path,
+                })
+            })
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+        entries
+    }
+
+    /// Total bytes cached.
+    pub fn size(&self) -> u64 {
+        self.entries().iter().map(|e| e.bytes).sum()
+    }
+
+    /// Remove the cached audio of `video_id`. Returns whether there was any.
+    pub fn remove(&self, video_id: &str) -> Result<bool> {
+        let path = self.path(video_id);
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    /// Empty the cache. Returns the files and bytes removed.
+    pub fn clear(&self) -> Result<(usize, u64)> {
+        let entries = self.entries();
+        for entry in &entries {
+            std::fs::remove_file(&entry.path)?;
+        }
+        Ok((entries.len(), entries.iter().map(|e| e.bytes).sum()))
+    }
+
+    /// Evict the least recently used audio, other than `keep`'s while
+    /// possible, until the cache fits its cap.
+    fn evict(&self, keep: &str) {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|e| e.bytes).sum();
+        // Least recently used first, with the audio just added last
+        entries.reverse();
+        entries.sort_by_key(|e| e.video_id == keep);
+        for entry in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            match std::fs::remove_file(&entry.path) {
+                Ok(()) => {
+                    debug!("Evicted cached audio of {}", entry.video_id);
+                    total -= entry.bytes;
+                }
+                Err(e) => warn!("Failed to evict {}: {}", entry.path.display(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_audio_cache_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path().join("audio"), 1);
+        let source = dir.path().join("source.mp3");
+        std::fs::write(&source, vec![0u8; 400 * 1024]).unwrap();
+
+        let backdate = |id: &str, secs: u64| {
+            let file = File::options().append(true).open(cache.path(id)).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(secs)).unwrap();
+        };
+        cache.put("a", &source).unwrap();
+        backdate("a", 30);
+        cache.put("b", &source).unwrap();
+        backdate("b", 20);
+
+        // Using "a" makes "b" the least recently used, evicted for "c"
+        assert!(cache.get("a").is_some());
+        cache.put("c", &source).unwrap();
+        let cached: Vec<String> = cache.entries().into_iter().map(|e| e.video_id).collect();
+        assert_eq!(cached.len(), 2);
+        assert!(cached.contains(&"a".to_string()) && cached.contains(&"c".to_string()));
+        assert!(cache.get("b").is_none());
+
+        assert_eq!(cache.clear().unwrap().0, 2);
+        assert!(!AudioCache::new(dir.path(), 0).is_enabled());
+    }
+}
@@ -0,0 +1,190 @@
+//! Live audio capture from a microphone.
+//!
+//! ffmpeg records the input device to an MP3 kept as the media file, and at
+//! the same time cuts it into fixed-length WAV segments that can be
+//! transcribed while the recording goes on.
+
+use crate::config::RecordSettings;
+use crate::error::{LyttError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, Command};
+use tracing::{debug, warn};
+
+/// How long ffmpeg gets to open the device before the capture counts as started.
+const STARTUP_GRACE: Duration = Duration::from_millis(750);
+
+/// How long ffmpeg gets to finish its files after being asked to stop.
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A running capture.
+pub struct Capture {
+    child: Child,
+    segments_dir: PathBuf,
+    /// Segments returned so far.
+    taken: usize,
+    segment_seconds: u32,
+}
+
+impl Capture {
+    /// Start recording the configured input to `recording`, with segments
+    /// written to `segments_dir`.
+    pub async fn start(settings: &RecordSettings, recording: &Path, segments_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(segments_dir)?;
+        if let Some(parent) = recording.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let (input_format, device) = input(settings);
+        let segment_seconds = settings.segment_seconds.max(1);
+        let args = capture_args(&input_format, &device, recording, segments_dir, segment_seconds);
+        debug!("Capturing with ffmpeg {:?}", args);
+
+        let mut child = match Command::new("ffmpeg")
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(LyttError::ToolNotFound("ffmpeg".into()));
+            }
+            Err(e) => return Err(LyttError::ToolFailed(format!("ffmpeg error: {e}"))),
+        };
+
+        // A missing device or input format makes ffmpeg exit straight away
+        tokio::time::sleep(STARTUP_GRACE).await;
+        if let Some(status) = child.try_wait()? {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                pipe.read_to_string(&mut stderr).await?;
+            }
+            return Err(LyttError::ToolFailed(format!(
+                "ffmpeg couldn't capture from {} device '{}' ({}): {}",
+                input_format,
+                device,
+                status,
+                stderr.trim()
+            )));
+        }
+
+        Ok(Self {
+            child,
+            segments_dir: segments_dir.to_path_buf(),
+            taken: 0,
+            segment_seconds,
+        })
+    }
+
+    /// Length of each segment in seconds; segment `n` starts at `n` times this.
+    pub fn segment_seconds(&self) -> u32 {
+        self.segment_seconds
+    }
+
+    /// Whether ffmpeg is still recording.
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Segments finished since the last call, in order. The segment ffmpeg
+    /// is still writing is left until the next one begins.
+    pub fn finished_segments(&mut self) -> Vec<PathBuf> {
+        let written = self.written_segments();
+        self.take(written.saturating_sub(1))
+    }
+
+    /// Stop recording and return the segments not yet returned, the last
+    /// partial one included.
+    pub async fn stop(mut self) -> Result<Vec<PathBuf>> {
+        // "q" on stdin makes ffmpeg finish its files; it may already have
+        // stopped on the terminal's Ctrl+C
+        if let Some(mut stdin) = self.child.stdin.take() {
+            let _ = stdin.write_all(b"q").await;
+        }
+        match tokio::time::timeout(STOP_TIMEOUT, self.child.wait()).await {
+            Ok(status) => debug!("ffmpeg exited with {}", status?),
+            Err(_) => {
+                warn!("ffmpeg didn't stop in time; killing it");
+                self.child.kill().await?;
+            }
+        }
+
+        let written = self.written_segments();
+        Ok(self.take(written))
+    }
+
+    fn take(&mut self, upto: usize) -> Vec<PathBuf> {
+        let segments = (self.taken..upto).map(|n| segment_path(&self.segments_dir, n)).collect();
+        self.taken = self.taken.max(upto);
+        segments
+    }
+
+    /// Number of segment files written so far.
+    fn written_segments(&self) -> usize {
+        let mut n = self.taken;
+        while segment_path(&self.segments_dir, n).exists() {
+            n += 1;
+        }
+        n
+    }
+}
+
+/// The input format and device to capture from, with the platform's
+/// defaults for those not configured.
+fn input(settings: &RecordSettings) -> (String, String) {
+    let (format, device) = if cfg!(target_os = "macos") {
+        ("avfoundation", ":default")
+    } else if cfg!(target_os = "windows") {
+        ("dshow", "audio=default")
+    } else {
+        ("pulse", "default")
+    };
+    let pick = |value: &str, default: &str| {
+        if value.trim().is_empty() { default.to_string() } else { value.trim().to_string() }
+    };
+    (pick(&settings.input_format, format), pick(&settings.device, device))
+}
+
+fn segment_path(dir: &Path, n: usize) -> PathBuf {
+    dir.join(format!("segment_{:05}.wav", n))
+}
+
+/// ffmpeg arguments recording the input to an MP3 and to 16 kHz mono WAV
+/// segments, which is all speech recognition needs.
+fn capture_args(input_format: &str, device: &str, recording: &Path, segments_dir: &Path, segment_seconds: u32) -> Vec<String> {
+    let mut args: Vec<String> = ["-hide_banner", "-loglevel", "error", "-nostats", "-y", "-f", input_format, "-i", device]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    args.extend(["-map", "0:a", "-ac", "1", "-codec:a", "libmp3lame", "-qscale:a", "4"].map(String::from));
+    args.push(recording.to_string_lossy().into_owned());
+    args.extend(["-map", "0:a", "-ac", "1", "-ar", "16000", "-codec:a", "pcm_s16le", "-f", "segment"].map(String::from));
+    args.extend(["-segment_time".to_string(), segment_seconds.to_string()]);
+    args.extend(["-reset_timestamps", "1"].map(String::from));
+    args.push(segments_dir.join("segment_%05d.wav").to_string_lossy().into_owned());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_args() {
+        let settings = RecordSettings { device: "hw:1".to_string(), ..Default::default() };
+        let (format, device) = input(&settings);
+        assert_eq!(device, "hw:1");
+        assert!(!format.is_empty());
+
+        let args = capture_args("alsa", "hw:1", Path::new("/r/talk.mp3"), Path::new("/tmp/seg"), 20);
+        let joined = args.join(" ");
+        assert!(joined.contains("-f alsa -i hw:1"));
+        assert!(joined.contains("-qscale:a 4 /r/talk.mp3"));
+        assert!(joined.ends_with("-f segment -segment_time 20 -reset_timestamps 1 /tmp/seg/segment_%05d.wav"));
+        assert_eq!(segment_path(Path::new("/tmp/seg"), 3), Path::new("/tmp/seg/segment_00003.wav"));
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides functions for downloading audio from URLs using yt-dlp
 //! and processing audio files using ffmpeg.
 
+use super::{ytdlp_command, AudioCache};
+use crate::config::{AudioSettings, Settings};
 use crate::error::{LyttError, Result};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
@@ -28,7 +30,7 @@ pub async fn download_audio(url: &str, video_id: &str, output_dir: &Path) -> Res
 
     let template = output_dir.join(format!("{}.%(ext)s", video_id));
 
-    let result = Command::new("yt-dlp")
+    let result = ytdlp_command()
         .arg("--extract-audio")
         .arg("--audio-format").arg("mp3")
         .arg("--audio-quality").arg("0")
@@ -68,6 +70,42 @@ pub async fn download_audio(url: &str, video_id: &str, output_dir: &Path) -> Res
     Ok(target_path)
 }
 
+/// Like [`download_audio`], but copies the audio out of `cache` when it holds
+/// it, and adds downloaded audio to it.
+pub async fn download_audio_cached(url: &str, video_id: &str, output_dir: &Path, cache: &AudioCache) -> Result<PathBuf> {
+    let target_path = output_dir.join(format!("{}.mp3", video_id));
+    if let Some(cached) = cache.get(video_id) {
+        info!("Using cached audio from {}", cached.display());
+        std::fs::create_dir_all(output_dir)?;
+        std::fs::copy(&cached, &target_path)?;
+        return Ok(target_path);
+    }
+
+    let path = download_audio(url, video_id, output_dir).await?;
+    if let Err(e) = cache.put(video_id, &path) {
+        warn!("Failed to cache the audio: {}", e);
+    }
+    Ok(path)
+}
+
+/// Remove every file a download, conversion or caption fetch for `video_id`
+/// left in `dir`: complete or partial audio, yt-dlp `.part` files and
+/// caption tracks. Used after a cancelled run, whose files may be truncated
+/// and would otherwise be picked up as cached audio next time.
+pub fn remove_partial_files(dir: &Path, video_id: &str) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let prefix = format!("{}.", video_id);
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            if let Err(e) = std::fs::remove_file(entry.path()) {
+                warn!("Failed to remove {}: {}", entry.path().display(), e);
+            }
+        }
+    }
+}
+
 /// Locates a downloaded audio file by video ID.
 fn find_audio_file(dir: &Path, video_id: &str) -> Result<PathBuf> {
     // Common audio formats that yt-dlp may produce
@@ -97,6 +135,7 @@ async fn normalize_to_mp3(source: &Path, dest: &Path) -> Result<()> {
     debug!("Converting {:?} to MP3", source);
 
     let result = Command::new("ffmpeg")
+        .kill_on_drop(true)
         .arg("-i").arg(source)
         .arg("-vn")
         .arg("-codec:a").arg("libmp3lame")
@@ -122,10 +161,125 @@ async fn normalize_to_mp3(source: &Path, dest: &Path) -> Result<()> {
     }
 }
 
+/// Apply the configured preprocessing (high-pass, noise reduction, loudness
+/// normalization) to an audio file in place.
+///
+/// Does nothing when no step is enabled. Meant for the temporary copies
+/// made by [`download_audio`], never for the user's original files.
+#[instrument(skip(settings))]
+pub async fn preprocess_audio(path: &Path, settings: &AudioSettings) -> Result<()> {
+    let Some(filters) = filter_chain(settings) else {
+        return Ok(());
+    };
+    info!("Preprocessing audio: {}", filters);
+
+    let processed = path.with_extension("preprocessed.mp3");
+    let result = Command::new("ffmpeg")
+        .kill_on_drop(true)
+        .arg("-i").arg(path)
+        .arg("-af").arg(&filters)
+        .arg("-codec:a").arg("libmp3lame")
+        .arg("-qscale:a").arg("2")
+        .arg("-y")
+        .arg("-loglevel").arg("error")
+        .arg(&processed)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    match result {
+        Ok(out) if out.status.success() => {
+            std::fs::rename(&processed, path)?;
+            Ok(())
+        }
+        Ok(out) => {
+            let _ = std::fs::remove_file(&processed);
+            let err = String::from_utf8_lossy(&out.stderr);
+            Err(LyttError::AudioDownload(format!("Audio preprocessing failed: {err}")))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(LyttError::ToolNotFound("ffmpeg".into()))
+        }
+        Err(e) => Err(LyttError::AudioDownload(format!("ffmpeg error: {e}"))),
+    }
+}
+
+/// Cut the time ranges (start, end in seconds) out of the audio file at
+/// `path`, in place.
+pub async fn remove_ranges(path: &Path, ranges: &[(f64, f64)]) -> Result<()> {
+    if ranges.is_empty() {
+        return Ok(());
+    }
+    let skipped: Vec<String> = ranges.iter().map(|(start, end)| format!("between(t,{:.3},{:.3})", start, end)).collect();
+    let filter = format!("aselect='not({})',asetpts=N/SR/TB", skipped.join("+"));
+
+    let cut = path.with_extension("cut.mp3");
+    let result = Command::new("ffmpeg")
+        .kill_on_drop(true)
+        .arg("-i").arg(path)
+        .arg("-af").arg(&filter)
+        .arg("-codec:a").arg("libmp3lame")
+        .arg("-qscale:a").arg("2")
+        .arg("-y")
+        .arg("-loglevel").arg("error")
+        .arg(&cut)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    match result {
+        Ok(out) if out.status.success() => {
+            std::fs::rename(&cut, path)?;
+            Ok(())
+        }
+        Ok(out) => {
+            let _ = std::fs::remove_file(&cut);
+            let err = String::from_utf8_lossy(&out.stderr);
+            Err(LyttError::AudioDownload(format!("Cutting skipped segments failed: {err}")))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(LyttError::ToolNotFound("ffmpeg".into()))
+        }
+        Err(e) => Err(LyttError::AudioDownload(format!("ffmpeg error: {e}"))),
+    }
+}
+
+/// ffmpeg filter chain for the enabled preprocessing steps, in order:
+/// high-pass, noise reduction, then loudness normalization.
+fn filter_chain(settings: &AudioSettings) -> Option<String> {
+    let mut filters = Vec::new();
+    if settings.highpass_hz > 0 {
+        filters.push(format!("highpass=f={}", settings.highpass_hz));
+    }
+    if let Some(model) = settings.denoise_model.as_deref().filter(|m| !m.is_empty()) {
+        // Quoted, so ':' in the path doesn't end the option
+        filters.push(format!("arnndn=m='{}'", Settings::expand_path(model).display()));
+    }
+    if settings.normalize {
+        filters.push(format!("loudnorm=I={}:TP=-1.5:LRA=11", settings.target_lufs));
+    }
+    (!filters.is_empty()).then(|| filters.join(","))
+}
+
+/// Noise level below which audio counts as silence.
+const SILENCE_NOISE_DB: i32 = -30;
+
+/// Shortest pause used as a cut point, in seconds.
+const SILENCE_MIN_SECONDS: f64 = 0.3;
+
+/// How far before the target length a cut may move to land on silence, as a
+/// fraction of the chunk length.
+const SILENCE_SEARCH_FRACTION: f64 = 0.25;
+
 /// Segments a long audio file into smaller chunks for processing.
 ///
-/// Each chunk will be approximately `chunk_seconds` long. Returns tuples of
-/// (chunk_path, offset_seconds) for each segment.
+/// Each chunk will be at most `chunk_seconds` long. Cuts are moved back to
+/// the nearest pause (found with ffmpeg's `silencedetect`) so they don't
+/// fall mid-word; where there is no pause close enough, the chunk is cut at
+/// full length. Returns tuples of (chunk_path, offset_seconds) for each
+/// segment.
 #[instrument(skip_all)]
 pub async fn split_audio(
     source: &Path,
@@ -144,6 +298,15 @@ pub async fn split_audio(
         return Ok(vec![(source.to_path_buf(), 0.0)]);
     }
 
+    let silences = match detect_silences(source).await {
+        Ok(silences) => silences,
+        Err(e) => {
+            warn!("Silence detection failed, splitting at fixed lengths: {}", e);
+            Vec::new()
+        }
+    };
+    let cuts = plan_cuts(total_duration, chunk_len, &silences);
+
     let base_name = source
         .file_stem()
         .and_then(|s| s.to_str())
@@ -151,29 +314,100 @@ pub async fn split_audio(
 
     let mut segments = Vec::new();
     let mut offset = 0.0;
-    let mut idx = 0u32;
 
-    while offset < total_duration {
+    for (idx, &cut) in cuts.iter().chain(std::iter::once(&total_duration)).enumerate() {
         let segment_path = output_dir.join(format!("{}_{:04}.mp3", base_name, idx));
-        let segment_len = chunk_len.min(total_duration - offset);
 
-        extract_segment(source, &segment_path, offset, segment_len).await?;
+        extract_segment(source, &segment_path, offset, cut - offset).await?;
 
-        debug!("Created segment {} at offset {:.1}s", idx, offset);
+        debug!("Created segment {} at offset {:.1}s ({:.1}s)", idx, offset, cut - offset);
         segments.push((segment_path, offset));
 
-        offset += chunk_len;
-        idx += 1;
+        offset = cut;
     }
 
     info!("Created {} audio segments", segments.len());
     Ok(segments)
 }
 
+/// Find pauses in the audio as (start, end) seconds.
+async fn detect_silences(source: &Path) -> Result<Vec<(f64, f64)>> {
+    let output = Command::new("ffmpeg")
+        .kill_on_drop(true)
+        .arg("-i").arg(source)
+        .arg("-af").arg(format!("silencedetect=noise={}dB:d={}", SILENCE_NOISE_DB, SILENCE_MIN_SECONDS))
+        .arg("-f").arg("null")
+        .arg("-")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    match output {
+        Ok(out) if out.status.success() => Ok(parse_silences(&String::from_utf8_lossy(&out.stderr))),
+        Ok(out) => Err(LyttError::AudioDownload(format!(
+            "silencedetect failed: {}",
+            String::from_utf8_lossy(&out.stderr).lines().last().unwrap_or_default()
+        ))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(LyttError::ToolNotFound("ffmpeg".into()))
+        }
+        Err(e) => Err(LyttError::AudioDownload(format!("ffmpeg error: {e}"))),
+    }
+}
+
+/// Parse `silence_start: X` / `silence_end: Y` lines from silencedetect's log.
+fn parse_silences(log: &str) -> Vec<(f64, f64)> {
+    let value = |line: &str, key: &str| {
+        line.split(key)
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|v| v.parse::<f64>().ok())
+    };
+
+    let mut silences = Vec::new();
+    let mut start = None;
+    for line in log.lines() {
+        if let Some(s) = value(line, "silence_start:") {
+            start = Some(s.max(0.0));
+        } else if let Some(end) = value(line, "silence_end:") {
+            if let Some(s) = start.take() {
+                silences.push((s, end));
+            }
+        }
+    }
+    silences
+}
+
+/// Choose cut points so no chunk is longer than `chunk_len`, each placed in
+/// the middle of the latest pause that keeps the chunk at least
+/// `1 - SILENCE_SEARCH_FRACTION` of full length.
+fn plan_cuts(total_duration: f64, chunk_len: f64, silences: &[(f64, f64)]) -> Vec<f64> {
+    let mut cuts = Vec::new();
+    let mut offset = 0.0;
+
+    while total_duration - offset > chunk_len {
+        let target = offset + chunk_len;
+        let earliest = target - chunk_len * SILENCE_SEARCH_FRACTION;
+
+        let cut = silences
+            .iter()
+            .map(|(start, end)| (start + end) / 2.0)
+            .filter(|mid| *mid >= earliest && *mid <= target)
+            .fold(None, |best: Option<f64>, mid| Some(best.map_or(mid, |b| b.max(mid))))
+            .unwrap_or(target);
+
+        cuts.push(cut);
+        offset = cut;
+    }
+    cuts
+}
+
 /// Extracts a time segment from an audio file.
-async fn extract_segment(source: &Path, dest: &Path, start: f64, length: f64) -> Result<()> {
+pub async fn extract_segment(source: &Path, dest: &Path, start: f64, length: f64) -> Result<()> {
     // First attempt: stream copy (fast, no quality loss)
     let copy_result = Command::new("ffmpeg")
+        .kill_on_drop(true)
         .arg("-ss").arg(format!("{:.3}", start))
         .arg("-i").arg(source)
         .arg("-t").arg(format!("{:.3}", length))
@@ -196,6 +430,7 @@ async fn extract_segment(source: &Path, dest: &Path, start: f64, length: f64) ->
     warn!("Stream copy failed, re-encoding segment");
 
     let encode_result = Command::new("ffmpeg")
+        .kill_on_drop(true)
         .arg("-ss").arg(format!("{:.3}", start))
         .arg("-i").arg(source)
         .arg("-t").arg(format!("{:.3}", length))
@@ -222,9 +457,63 @@ async fn extract_segment(source: &Path, dest: &Path, start: f64, length: f64) ->
     }
 }
 
+/// Concatenates audio files, in order, into a single MP3.
+///
+/// Every part is resampled to 44.1 kHz stereo first, so clips from sources
+/// with different codecs or sample rates can be joined.
+pub async fn concat_audio(parts: &[PathBuf], dest: &Path) -> Result<()> {
+    if parts.is_empty() {
+        return Err(LyttError::InvalidInput("No audio to concatenate".into()));
+    }
+
+    let mut command = Command::new("ffmpeg");
+    command.kill_on_drop(true);
+    for part in parts {
+        command.arg("-i").arg(part);
+    }
+    let result = command
+        .arg("-filter_complex").arg(concat_filter(parts.len()))
+        .arg("-map").arg("[out]")
+        .arg("-codec:a").arg("libmp3lame")
+        .arg("-qscale:a").arg("2")
+        .arg("-y")
+        .arg("-loglevel").arg("error")
+        .arg(dest)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await;
+
+    match result {
+        Ok(out) if out.status.success() => Ok(()),
+        Ok(out) => {
+            let err = String::from_utf8_lossy(&out.stderr);
+            Err(LyttError::ToolFailed(format!("ffmpeg concat failed: {err}")))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(LyttError::ToolNotFound("ffmpeg".into()))
+        }
+        Err(e) => Err(LyttError::ToolFailed(format!("ffmpeg error: {e}"))),
+    }
+}
+
+/// ffmpeg filter graph that resamples `inputs` audio streams and joins them.
+fn concat_filter(inputs: usize) -> String {
+    let mut graph = String::new();
+    for i in 0..inputs {
+        graph.push_str(&format!("[{i}:a]aresample=44100,aformat=channel_layouts=stereo[a{i}];"));
+    }
+    for i in 0..inputs {
+        graph.push_str(&format!("[a{i}]"));
+    }
+    graph.push_str(&format!("concat=n={inputs}:v=0:a=1[out]"));
+    graph
+}
+
 /// Queries the duration of an audio file using ffprobe with JSON output.
-async fn probe_duration(path: &Path) -> Result<f64> {
+pub async fn probe_duration(path: &Path) -> Result<f64> {
     let result = Command::new("ffprobe")
+        .kill_on_drop(true)
         .arg("-v").arg("quiet")
         .arg("-print_format").arg("json")
         .arg("-show_format")
@@ -261,6 +550,67 @@ async fn probe_duration(path: &Path) -> Result<f64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_filter_chain() {
+        assert_eq!(filter_chain(&AudioSettings::default()), None);
+
+        let settings = AudioSettings {
+            normalize: true,
+            highpass_hz: 80,
+            denoise_model: Some("/models/std.rnnn".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter_chain(&settings).unwrap(),
+            "highpass=f=80,arnndn=m='/models/std.rnnn',loudnorm=I=-16:TP=-1.5:LRA=11"
+        );
+    }
+
+    #[test]
+    fn test_concat_filter() {
+        assert_eq!(
+            concat_filter(2),
+            "[0:a]aresample=44100,aformat=channel_layouts=stereo[a0];\
+             [1:a]aresample=44100,aformat=channel_layouts=stereo[a1];\
+             [a0][a1]concat=n=2:v=0:a=1[out]"
+        );
+    }
+
+    #[test]
+    fn test_parse_silences() {
+        let log = "[silencedetect @ 0x1] silence_start: -0.01\n\
+                   [silencedetect @ 0x1] silence_end: 1.5 | silence_duration: 1.51\n\
+                   size=N/A time=00:01:00.00\n\
+                   [silencedetect @ 0x1] silence_start: 58.2\n\
+                   [silencedetect @ 0x1] silence_end: 59 | silence_duration: 0.8\n";
+        assert_eq!(parse_silences(log), vec![(0.0, 1.5), (58.2, 59.0)]);
+    }
+
+    #[test]
+    fn test_plan_cuts() {
+        // Pauses near the 120s boundary move the cut; none near 240s keeps it
+        let silences = [(50.0, 51.0), (100.0, 101.0), (112.0, 113.0), (125.0, 126.0)];
+        assert_eq!(plan_cuts(300.0, 120.0, &silences), vec![112.5, 232.5]);
+
+        assert_eq!(plan_cuts(250.0, 120.0, &[]), vec![120.0, 240.0]);
+        assert!(plan_cuts(100.0, 120.0, &silences).is_empty());
+    }
+
+    #[test]
+    fn test_remove_partial_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["abc.webm.part", "abc.mp3", "abc.captions.en.vtt", "abcd.mp3", "other.mp3"] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        remove_partial_files(dir.path(), "abc");
+        let mut left: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["abcd.mp3", "other.mp3"]);
+    }
+
     #[tokio::test]
     async fn test_required_tools_exist() {
         // Verify external dependencies are available
@@ -270,10 +620,8 @@ mod tests {
             Command::new("which").arg("ffprobe").output().await,
         ];
 
-        for check in checks {
-            if let Ok(output) = check {
-                assert!(output.status.success() || true); // Soft check
-            }
+        for output in checks.into_iter().flatten() {
+            assert!(output.status.code().is_some());
         }
     }
 }
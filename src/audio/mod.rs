@@ -1,5 +1,16 @@
-//! Audio download and processing module.
+//! Audio download, capture and processing module.
 
+mod cache;
+mod capture;
 mod downloader;
+mod workspace;
+pub mod ytdlp;
 
-pub use downloader::{download_audio, split_audio};
+pub use cache::{AudioCache, CachedAudio};
+pub use capture::Capture;
+pub use downloader::{
+    concat_audio, download_audio, download_audio_cached, extract_segment, preprocess_audio, probe_duration,
+    remove_partial_files, remove_ranges, split_audio,
+};
+pub use workspace::{clean_orphaned_workspaces, segments_dir, Workspace, AUDIO_BYTES_PER_SECOND};
+pub use ytdlp::ytdlp_command;
@@ -0,0 +1,212 @@
+//! Temporary workspace for downloads and audio segments.
+//!
+//! Each pipeline run works in its own `run-*` directory under the configured
+//! temp directory and holds a lock on it for as long as it lives. Everything
+//! the run downloads, converts or splits goes in there, so the space it uses
+//! can be checked and capped, and it is removed when the run ends. A
+//! directory whose lock is free was left by a run that crashed.
+
+use crate::config::Settings;
+use crate::error::{LyttError, Result};
+use fs2::FileExt;
+use std::fs::File;
+use std::path::Path;
+use tempfile::TempDir;
+use tracing::{debug, warn};
+
+const RUN_PREFIX: &str = "run-";
+const LOCK_FILE: &str = ".lock";
+
+/// Rough disk use per second of downloaded audio: the MP3 at yt-dlp's best
+/// quality and the source it is converted from, which exist side by side.
+pub const AUDIO_BYTES_PER_SECOND: u64 = 64_000;
+
+const MB: u64 = 1024 * 1024;
+
+/// The temporary directory of one pipeline run.
+#[derive(Debug)]
+pub struct Workspace {
+    dir: TempDir,
+    /// Held until the workspace is dropped, marking the directory in use.
+    _lock: File,
+    /// Most the directory may hold, in bytes (0 for no cap).
+    max_bytes: u64,
+    /// Free space to leave on the disk, in bytes.
+    min_free_bytes: u64,
+}
+
+impl Workspace {
+    /// Create a workspace under `root`.
+    pub fn new(root: &Path) -> Result<Self> {
+        std::fs::create_dir_all(root)?;
+        let dir = tempfile::Builder::new().prefix(RUN_PREFIX).tempdir_in(root)?;
+        let lock = File::create(dir.path().join(LOCK_FILE))?;
+        lock.try_lock_exclusive()?;
+        debug!("Created workspace {}", dir.path().display());
+        Ok(Self {
+            dir,
+            _lock: lock,
+            max_bytes: 0,
+            min_free_bytes: 0,
+        })
+    }
+
+    /// Create a workspace under the configured temp directory, with its
+    /// configured size cap and free space margin.
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        Ok(Self::new(&settings.temp_dir())?
+            .with_max_size_mb(settings.general.temp_max_size_mb)
+            .with_min_free_mb(settings.general.min_free_disk_mb))
+    }
+
+    /// Cap the size of the workspace (0 for no cap).
+    pub fn with_max_size_mb(mut self, mb: u64) -> Self {
+        self.max_bytes = mb * MB;
+        self
+    }
+
+    /// Leave at least this much free space on the disk.
+    pub fn with_min_free_mb(mut self, mb: u64) -> Self {
+        self.min_free_bytes = mb * MB;
+        self
+    }
+
+    /// Directory to write temporary files in.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Bytes held by the files in the workspace.
+    pub fn size(&self) -> u64 {
+        dir_size(self.path())
+    }
+
+    /// Fail unless `bytes` more fit in the workspace and on the disk.
+    /// `purpose` names what needs the space, for the error message.
+    pub fn ensure_space(&self, bytes: u64, purpose: &str) -> Result<()> {
+        if self.max_bytes > 0 {
+            let used = self.size();
+            if used + bytes > self.max_bytes {
+                return Err(LyttError::DiskSpace(format!(
+                    "{} needs about {} MB, but the temp directory is capped at {} MB ({} MB in use). \
+                     Raise general.temp_max_size_mb to allow it.",
+                    purpose,
+                    bytes.div_ceil(MB),
+                    self.max_bytes / MB,
+                    used.div_ceil(MB)
+                )));
+            }
+        }
+
+        let available = fs2::available_space(self.path())?;
+        if available < bytes + self.min_free_bytes {
+            return Err(LyttError::DiskSpace(format!(
+                "{} needs about {} MB in {}, but only {} MB are free (keeping {} MB free). \
+                 Free up space or point general.temp_dir at a larger disk.",
+                purpose,
+                bytes.div_ceil(MB),
+                self.path().display(),
+                available / MB,
+                self.min_free_bytes / MB
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Remove the workspaces left under `root` by runs that crashed. Returns the
+/// bytes freed.
+pub fn clean_orphaned_workspaces(root: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return 0;
+    };
+
+    let mut freed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_workspace = entry.file_name().to_string_lossy().starts_with(RUN_PREFIX) && path.is_dir();
+        if !is_workspace || in_use(&path) {
+            continue;
+        }
+        let size = dir_size(&path);
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => {
+                debug!("Removed orphaned workspace {}", path.display());
+                freed += size;
+            }
+            Err(e) => warn!("Failed to remove orphaned workspace {}: {}", path.display(), e),
+        }
+    }
+    freed
+}
+
+/// Whether a live run holds the lock of the workspace at `dir`.
+fn in_use(dir: &Path) -> bool {
+    let Ok(lock) = File::open(dir.join(LOCK_FILE)) else {
+        // Not created yet, or by an older version: only remove it once stale
+        return !is_stale(dir);
+    };
+    match lock.try_lock_exclusive() {
+        Ok(()) => {
+            let _ = FileExt::unlock(&lock);
+            false
+        }
+        Err(_) => true,
+    }
+}
+
+/// Whether `dir` hasn't changed for a day.
+fn is_stale(dir: &Path) -> bool {
+    std::fs::metadata(dir)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age.as_secs() > 24 * 60 * 60)
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// A directory for the segments `audio` is split into, next to it and
+/// removed when dropped.
+pub fn segments_dir(audio: &Path) -> Result<TempDir> {
+    let parent = audio.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    Ok(tempfile::Builder::new().prefix("segments-").tempdir_in(parent)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_lifecycle() {
+        let root = tempfile::tempdir().unwrap();
+
+        let workspace = Workspace::new(root.path()).unwrap().with_max_size_mb(1);
+        std::fs::write(workspace.path().join("audio.mp3"), vec![0u8; 1000]).unwrap();
+        assert_eq!(workspace.size(), 1000);
+        assert!(workspace.ensure_space(1000, "Downloading").is_ok());
+        let err = workspace.ensure_space(MB, "Downloading").unwrap_err();
+        assert!(matches!(err, LyttError::DiskSpace(_)));
+
+        // A live workspace is kept, one left by a crash is removed
+        let orphan = root.path().join("run-crashed");
+        std::fs::create_dir(&orphan).unwrap();
+        File::create(orphan.join(LOCK_FILE)).unwrap();
+        std::fs::write(orphan.join("audio.mp3"), vec![0u8; 500]).unwrap();
+        assert_eq!(clean_orphaned_workspaces(root.path()), 500);
+        assert!(!orphan.exists());
+        assert!(workspace.path().exists());
+
+        let dir = workspace.path().to_path_buf();
+        drop(workspace);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,27 @@
+//! Running yt-dlp.
+//!
+//! Cookies, proxy, rate limit and extra arguments from `[youtube]` are set
+//! once per process and passed to every yt-dlp run: probes, playlist
+//! listings, caption and audio downloads.
+
+use crate::config::YoutubeSettings;
+use std::sync::OnceLock;
+use tokio::process::Command;
+
+static OPTIONS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Pass the options in `settings` to every yt-dlp run from now on. Only the
+/// first call has an effect.
+pub fn configure(settings: &YoutubeSettings) {
+    let _ = OPTIONS.set(settings.ytdlp_args());
+}
+
+/// A yt-dlp command with the configured options, killed when dropped.
+pub fn ytdlp_command() -> Command {
+    let mut command = Command::new("yt-dlp");
+    command.kill_on_drop(true);
+    if let Some(options) = OPTIONS.get() {
+        command.args(options);
+    }
+    command
+}
@@ -0,0 +1,162 @@
+//! Generic URL source implementation.
+//!
+//! Handles any URL yt-dlp supports that isn't YouTube: Vimeo, SoundCloud,
+//! Twitch VODs, podcast pages, news sites and so on.
+
+use super::youtube::{dump_json, metadata_from_json};
+use super::{AudioSource, MediaMetadata, SourceType};
+use crate::error::{LyttError, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+/// Prefix of media IDs from generic URLs.
+pub const WEB_ID_PREFIX: &str = "web_";
+
+/// Hex digits of the URL hash kept in IDs of media yt-dlp gives no ID for.
+const URL_HASH_CHARS: usize = 16;
+
+/// Source for any URL yt-dlp can extract audio from.
+pub struct GenericUrlSource;
+
+impl GenericUrlSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// A stable media ID: the yt-dlp extractor and the media's ID on that
+    /// site (`web_vimeo_76979871`), so every URL of the same media maps to
+    /// the same ID. Without them, a hash of the normalized URL.
+    pub fn media_id(extractor: Option<&str>, id: Option<&str>, url: &str) -> String {
+        let clean = |s: &str| -> String {
+            s.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                .collect()
+        };
+        match (extractor.map(clean), id.map(clean)) {
+            (Some(extractor), Some(id)) if !extractor.is_empty() && !id.is_empty() => {
+                format!("{}{}_{}", WEB_ID_PREFIX, extractor.to_lowercase(), id)
+            }
+            _ => {
+                let hash: String = Sha256::digest(normalize_url(url).as_bytes())
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                format!("{}{}", WEB_ID_PREFIX, &hash[..URL_HASH_CHARS])
+            }
+        }
+    }
+
+    /// A readable title for media yt-dlp reports no title for: the URL's
+    /// host and path with everything but letters and digits replaced.
+    pub fn title_from_url(url: &str) -> String {
+        let normalized = normalize_url(url);
+        let without_query = normalized.split('?').next().unwrap_or_default();
+        without_query
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn is_url(input: &str) -> bool {
+        let input = input.trim();
+        (input.starts_with("https://") || input.starts_with("http://")) && url::Url::parse(input).is_ok()
+    }
+}
+
+/// A URL without scheme, `www.` or fragment, with a lowercase host.
+fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let mut normalized = match url::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            parsed[url::Position::BeforeHost..].to_string()
+        }
+        Err(_) => url.split_once("://").map_or(url, |(_, rest)| rest).to_string(),
+    };
+    if let Some(rest) = normalized.strip_prefix("www.") {
+        normalized = rest.to_string();
+    }
+    normalized
+}
+
+impl Default for GenericUrlSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AudioSource for GenericUrlSource {
+    fn source_type(&self) -> SourceType {
+        SourceType::Web
+    }
+
+    async fn fetch_media(&self, id: &str) -> Result<MediaMetadata> {
+        let url = id.trim();
+        if !Self::is_url(url) {
+            return Err(LyttError::InvalidInput(format!("Not a URL: {}", id)));
+        }
+
+        let json = dump_json(url).await.map_err(|e| match e {
+            LyttError::VideoNotFound(stderr) => {
+                LyttError::VideoNotFound(format!("No media yt-dlp can extract at {}: {}", url, stderr))
+            }
+            e => e,
+        })?;
+
+        // Prefer the canonical page URL yt-dlp reports over the one given
+        let source_url = json["webpage_url"].as_str().unwrap_or(url).to_string();
+        let media_id = Self::media_id(json["extractor_key"].as_str(), json["id"].as_str(), url);
+        let mut metadata = metadata_from_json(&json, media_id, SourceType::Web, source_url);
+        if json["title"].as_str().is_none() {
+            metadata.title = Self::title_from_url(url);
+        }
+        if metadata.channel.is_none() {
+            metadata.channel = json["extractor_key"].as_str().map(String::from);
+        }
+        Ok(metadata)
+    }
+
+    async fn list_media(&self, source: &str, _limit: Option<usize>) -> Result<Vec<MediaMetadata>> {
+        Ok(vec![self.fetch_media(source).await?])
+    }
+
+    fn can_handle(&self, input: &str) -> bool {
+        Self::is_url(input)
+    }
+
+    fn extract_id(&self, input: &str) -> Option<String> {
+        Self::is_url(input).then(|| input.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generic_url_source() {
+        let source = GenericUrlSource::new();
+        assert!(source.can_handle("https://vimeo.com/76979871"));
+        assert!(source.can_handle("http://soundcloud.com/artist/track"));
+        assert!(!source.can_handle("/path/to/video.mp4"));
+        assert!(!source.can_handle("dQw4w9WgXcQ"));
+
+        assert_eq!(
+            GenericUrlSource::media_id(Some("Vimeo"), Some("76979871"), "https://vimeo.com/76979871"),
+            "web_vimeo_76979871"
+        );
+
+        // Without yt-dlp's ID, the same page hashes to the same ID however it's written
+        let id = GenericUrlSource::media_id(None, None, "https://www.Example.com/ep/12?t=5#chat");
+        assert_eq!(id, GenericUrlSource::media_id(None, None, "http://example.com/ep/12?t=5"));
+        assert_ne!(id, GenericUrlSource::media_id(None, None, "https://example.com/ep/12?t=6"));
+        assert_eq!(id.len(), WEB_ID_PREFIX.len() + URL_HASH_CHARS);
+        let long = format!("https://example.com/{}", "a".repeat(5000));
+        assert_eq!(GenericUrlSource::media_id(None, None, &long).len(), id.len());
+
+        assert_eq!(GenericUrlSource::title_from_url("https://www.twitch.tv/videos/123/?t=5#chat"), "twitch tv videos 123");
+        assert_eq!(SourceType::of_media_id("web_vimeo_76979871"), SourceType::Web);
+    }
+}
@@ -1,15 +1,21 @@
 //! Local file source implementation.
 //!
-//! Supports both audio and video files.
+//! Supports both audio and video files, single files as well as directories
+//! (optionally recursive) and glob patterns such as `archive/**/*.m4a`.
 
 use super::{AudioSource, MediaMetadata, SourceType};
 use crate::error::{Result, LyttError};
+use crate::transcription::Chapter;
 use async_trait::async_trait;
-use std::path::Path;
+use chrono::{DateTime, NaiveDate, Utc};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
 
 /// Supported audio file extensions.
 const AUDIO_EXTENSIONS: &[&str] = &[
-    "mp3", "wav", "flac", "aac", "ogg", "opus", "m4a", "wma", "aiff", "alac",
+    "mp3", "wav", "flac", "aac", "ogg", "opus", "m4a", "m4b", "wma", "aiff", "alac",
 ];
 
 /// Supported video file extensions (audio will be extracted).
@@ -17,12 +23,149 @@ const VIDEO_EXTENSIONS: &[&str] = &[
     "mp4", "mkv", "avi", "mov", "webm", "flv", "wmv", "m4v", "mpeg", "mpg", "3gp",
 ];
 
+/// Prefix of local media IDs.
+const ID_PREFIX: &str = "local_";
+
+/// Hex characters of the content hash kept in a local media ID.
+const ID_HASH_CHARS: usize = 16;
+
 /// Local file source for audio and video files.
-pub struct LocalSource;
+pub struct LocalSource {
+    recursive: bool,
+    excludes: Vec<String>,
+}
 
 impl LocalSource {
     pub fn new() -> Self {
-        Self
+        Self {
+            recursive: false,
+            excludes: Vec::new(),
+        }
+    }
+
+    /// Include files in subdirectories when listing a directory. Patterns
+    /// with `**` always search subdirectories.
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Skip files and directories matching any of these glob patterns.
+    /// Patterns without a `/` match any single path component (e.g. `drafts`
+    /// or `*.tmp.wav`); others match the path relative to the listed
+    /// directory.
+    pub fn with_excludes(mut self, excludes: Vec<String>) -> Self {
+        self.excludes = excludes;
+        self
+    }
+
+    /// The media ID of a file with this content hash.
+    pub fn media_id(content_hash: &str) -> String {
+        format!("{}{}", ID_PREFIX, &content_hash[..ID_HASH_CHARS.min(content_hash.len())])
+    }
+
+    /// Whether a local media ID is derived from the file's contents, rather
+    /// than its path as in older versions.
+    pub fn is_content_id(id: &str) -> bool {
+        id.strip_prefix(ID_PREFIX)
+            .is_some_and(|hash| hash.len() == ID_HASH_CHARS && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+    }
+
+    /// The path-based media ID older versions gave a file.
+    pub fn legacy_media_id(path: &Path) -> String {
+        format!(
+            "{}{}",
+            ID_PREFIX,
+            path.canonicalize()
+                .unwrap_or_else(|_| path.to_path_buf())
+                .to_string_lossy()
+                .replace(['/', '\\', ' '], "_")
+        )
+    }
+
+    /// Metadata for a file whose transcript comes from outside the usual
+    /// pipeline (`lytt record`, the server's `/stream`, an imported
+    /// transcript), identified by its content like any local file. Without
+    /// a title the file name is used, so one is generated from the
+    /// transcript.
+    pub fn file_metadata(
+        path: &Path,
+        title: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+        duration_seconds: f64,
+    ) -> Result<MediaMetadata> {
+        let hash = content_hash(path)?;
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+
+        Ok(MediaMetadata {
+            id: Self::media_id(&hash),
+            title: title.map(str::to_string).unwrap_or(stem),
+            description: None,
+            duration_seconds: Some(duration_seconds.ceil() as u32),
+            source_type: SourceType::Local,
+            source_url: path.to_string_lossy().into_owned(),
+            published_at,
+            channel: None,
+            thumbnail_url: None,
+            album: None,
+            track: None,
+            chapters: Vec::new(),
+        })
+    }
+
+    /// Whether the input names several files: a directory or a glob pattern
+    /// below an existing directory. URLs never are, even with a `?` query.
+    pub fn is_collection(input: &str) -> bool {
+        if input.contains("://") {
+            return false;
+        }
+        Path::new(input).is_dir() || (has_glob(input) && split_glob(input).0.is_dir())
+    }
+
+    /// Media files in a directory or matching a glob pattern, sorted by path.
+    /// Hidden files and directories are skipped.
+    pub fn find_media_files(&self, source: &str) -> Result<Vec<PathBuf>> {
+        let (base, pattern) = split_glob(source);
+        if !base.is_dir() {
+            return Err(LyttError::VideoNotFound(format!("Directory not found: {}", base.display())));
+        }
+
+        let max_depth = match &pattern {
+            Some(p) if p.contains("**") => usize::MAX,
+            Some(p) => p.split('/').count(),
+            None if self.recursive => usize::MAX,
+            None => 1,
+        };
+        let patterns = pattern.as_deref().map(expand_braces).unwrap_or_default();
+
+        let walker = WalkDir::new(&base)
+            .max_depth(max_depth)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !(is_hidden(e) || self.is_excluded(&relative(e, &base))));
+
+        let mut files = Vec::new();
+        for entry in walker {
+            let entry = entry.map_err(|e| LyttError::Io(e.into()))?;
+            if !entry.file_type().is_file() || !Self::is_media_file(entry.path()) {
+                continue;
+            }
+            let rel = relative(&entry, &base);
+            if patterns.is_empty() || patterns.iter().any(|p| glob_match(p, &rel)) {
+                files.push(entry.into_path());
+            }
+        }
+        Ok(files)
+    }
+
+    fn is_excluded(&self, rel: &str) -> bool {
+        self.excludes.iter().flat_map(|e| expand_braces(e)).any(|exclude| {
+            if exclude.contains('/') {
+                glob_match(exclude.trim_start_matches("./"), rel)
+            } else {
+                rel.split('/').any(|component| glob_match(&exclude, component))
+            }
+        })
     }
 
     /// Check if path is a supported audio file.
@@ -42,20 +185,21 @@ impl LocalSource {
     }
 
     /// Check if path is a supported media file (audio or video).
-    fn is_media_file(path: &Path) -> bool {
+    pub fn is_media_file(path: &Path) -> bool {
         Self::is_audio_file(path) || Self::is_video_file(path)
     }
 
-    /// Get media metadata using ffprobe.
-    async fn get_metadata_ffprobe(path: &Path) -> Result<(Option<u32>, Option<String>)> {
+    /// Read the duration, tags and chapters of a file with ffprobe.
+    async fn probe(path: &Path) -> Result<FileTags> {
         let output = tokio::process::Command::new("ffprobe")
+            .kill_on_drop(true)
             .args([
                 "-v",
                 "quiet",
                 "-print_format",
                 "json",
                 "-show_format",
-                "-show_streams",
+                "-show_chapters",
                 path.to_str().unwrap_or(""),
             ])
             .output()
@@ -70,23 +214,95 @@ impl LocalSource {
 
         if !output.status.success() {
             // ffprobe failed, but we can still proceed without metadata
-            return Ok((None, None));
+            return Ok(FileTags::default());
         }
 
-        let json_str = String::from_utf8_lossy(&output.stdout);
-        let json: serde_json::Value = serde_json::from_str(&json_str).unwrap_or_default();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+        Ok(FileTags::from_ffprobe(&json))
+    }
+}
 
-        let duration = json["format"]["duration"]
-            .as_str()
-            .and_then(|d| d.parse::<f64>().ok())
-            .map(|d| d as u32);
+/// What ffprobe reads from a file's container and tags (ID3, MP4, Vorbis
+/// comments).
+#[derive(Debug, Default, PartialEq)]
+struct FileTags {
+    duration: Option<u32>,
+    title: Option<String>,
+    /// Artist, album artist or podcast show.
+    artist: Option<String>,
+    album: Option<String>,
+    track: Option<u32>,
+    date: Option<DateTime<Utc>>,
+    /// Description, synopsis or comment.
+    description: Option<String>,
+    chapters: Vec<Chapter>,
+}
 
-        let title = json["format"]["tags"]["title"]
-            .as_str()
-            .map(|s| s.to_string());
+impl FileTags {
+    fn from_ffprobe(json: &serde_json::Value) -> Self {
+        // Tag names are lowercase for ID3 and MP4 but uppercase for Vorbis comments
+        let tags = json["format"]["tags"].as_object();
+        let tag = |names: &[&str]| -> Option<String> {
+            names.iter().find_map(|name| {
+                tags?.iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                    .and_then(|(_, value)| value.as_str())
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+            })
+        };
+
+        let chapters = json["chapters"]
+            .as_array()
+            .map(|chapters| {
+                chapters
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, chapter)| {
+                        let time = |key: &str| chapter[key].as_str().and_then(|t| t.parse::<f64>().ok());
+                        let title = chapter["tags"]["title"]
+                            .as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("Chapter {}", i + 1));
+                        Some(Chapter {
+                            title,
+                            start_seconds: time("start_time")?,
+                            end_seconds: time("end_time")?,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            duration: json["format"]["duration"]
+                .as_str()
+                .and_then(|d| d.parse::<f64>().ok())
+                .map(|d| d as u32),
+            title: tag(&["title"]),
+            artist: tag(&["artist", "album_artist", "show", "composer"]),
+            album: tag(&["album", "show"]),
+            // "3/12" is track 3 of 12
+            track: tag(&["track"]).and_then(|t| t.split('/').next()?.trim().parse().ok()),
+            date: tag(&["date", "creation_time", "year"]).and_then(|d| parse_tag_date(&d)),
+            description: tag(&["description", "synopsis", "comment"]),
+            chapters,
+        }
+    }
+}
 
-        Ok((duration, title))
+/// Parse a tag date: a year, a date or a full timestamp.
+fn parse_tag_date(date: &str) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(date) {
+        return Some(timestamp.with_timezone(&Utc));
     }
+    let day = if date.len() == 4 { format!("{}-01-01", date) } else { date.chars().take(10).collect() };
+    ["%Y-%m-%d", "%Y%m%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&day, format).ok())
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
 }
 
 impl Default for LocalSource {
@@ -118,52 +334,48 @@ impl AudioSource for LocalSource {
             )));
         }
 
-        let (duration, metadata_title) = Self::get_metadata_ffprobe(path).await?;
+        let tags = Self::probe(path).await?;
 
-        let title = metadata_title.unwrap_or_else(|| {
+        let title = tags.title.unwrap_or_else(|| {
             path.file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or("Unknown")
                 .to_string()
         });
 
-        // Generate a stable ID from the file path
-        let media_id = format!(
-            "local_{}",
-            path.canonicalize()
-                .unwrap_or_else(|_| path.to_path_buf())
-                .to_string_lossy()
-                .replace(['/', '\\', ' '], "_")
-        );
+        // Identify the file by its contents, so moving or renaming it keeps its ID
+        let hash = {
+            let path = path.to_path_buf();
+            tokio::task::spawn_blocking(move || content_hash(&path))
+                .await
+                .map_err(|e| LyttError::VideoSource(format!("Failed to hash {}: {}", id, e)))??
+        };
+        let media_id = Self::media_id(&hash);
 
         Ok(MediaMetadata {
             id: media_id,
             title,
-            description: None,
-            duration_seconds: duration,
+            description: tags.description,
+            duration_seconds: tags.duration,
             source_type: SourceType::Local,
             source_url: path
                 .canonicalize()
                 .unwrap_or_else(|_| path.to_path_buf())
                 .to_string_lossy()
                 .to_string(),
-            published_at: None,
-            channel: None,
+            published_at: tags.date,
+            channel: tags.artist,
             thumbnail_url: None,
+            chapters: tags.chapters,
+            album: tags.album,
+            track: tags.track,
         })
     }
 
     async fn list_media(&self, source: &str, limit: Option<usize>) -> Result<Vec<MediaMetadata>> {
         let path = Path::new(source);
 
-        if !path.exists() {
-            return Err(LyttError::VideoNotFound(format!(
-                "Directory not found: {}",
-                source
-            )));
-        }
-
-        if !path.is_dir() {
+        if path.exists() && !path.is_dir() {
             return Err(LyttError::InvalidInput(format!(
                 "Not a directory: {}",
                 source
@@ -173,22 +385,15 @@ impl AudioSource for LocalSource {
         let mut media_files = Vec::new();
         let limit = limit.unwrap_or(usize::MAX);
 
-        let entries: Vec<_> = std::fs::read_dir(path)?
-            .filter_map(|e| e.ok())
-            .collect();
-
-        for entry in entries {
+        for entry_path in self.find_media_files(source)? {
             if media_files.len() >= limit {
                 break;
             }
 
-            let entry_path = entry.path();
-            if Self::is_media_file(&entry_path) {
-                match self.fetch_media(entry_path.to_str().unwrap_or("")).await {
-                    Ok(metadata) => media_files.push(metadata),
-                    Err(e) => {
-                        tracing::warn!("Failed to get metadata for {:?}: {}", entry_path, e);
-                    }
+            match self.fetch_media(entry_path.to_str().unwrap_or("")).await {
+                Ok(metadata) => media_files.push(metadata),
+                Err(e) => {
+                    tracing::warn!("Failed to get metadata for {:?}: {}", entry_path, e);
                 }
             }
         }
@@ -211,6 +416,116 @@ impl AudioSource for LocalSource {
     }
 }
 
+/// SHA-256 of a file's contents, as hex.
+pub fn content_hash(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn has_glob(input: &str) -> bool {
+    input.contains(['*', '?', '{'])
+}
+
+/// Split a source into the directory to walk and the glob pattern (if any)
+/// to match paths below it against.
+fn split_glob(source: &str) -> (PathBuf, Option<String>) {
+    if !has_glob(source) {
+        return (PathBuf::from(source), None);
+    }
+    let components: Vec<&str> = source.split(['/', '\\']).collect();
+    let literal = components.iter().take_while(|c| !has_glob(c)).count();
+    let base = components[..literal].join("/");
+    let base = match base.as_str() {
+        "" if source.starts_with('/') => PathBuf::from("/"),
+        "" => PathBuf::from("."),
+        _ => PathBuf::from(base),
+    };
+    (base, Some(components[literal..].join("/")))
+}
+
+/// Expand one level of `{a,b}` alternatives.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let (Some(open), Some(close)) = (pattern.find('{'), pattern.find('}')) else {
+        return vec![pattern.to_string()];
+    };
+    if close < open {
+        return vec![pattern.to_string()];
+    }
+    pattern[open + 1..close]
+        .split(',')
+        .map(|alt| format!("{}{}{}", &pattern[..open], alt, &pattern[close + 1..]))
+        .collect()
+}
+
+/// Match a `/`-separated path against a glob: `**` matches any number of
+/// directories, `*` any characters within one component and `?` one
+/// character.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    match_components(&pattern, &path)
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => (0..=path.len()).any(|skip| match_components(&pattern[1..], &path[skip..])),
+        Some(component) => {
+            !path.is_empty() && wildcard_match(component, path[0]) && match_components(&pattern[1..], &path[1..])
+        }
+    }
+}
+
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry.file_name().to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+/// An entry's path relative to the walked directory, `/`-separated.
+fn relative(entry: &DirEntry, base: &Path) -> String {
+    let rel = entry.path().strip_prefix(base).unwrap_or(entry.path());
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +552,93 @@ mod tests {
         assert!(LocalSource::is_media_file(Path::new("audio.mp3")));
         assert!(!LocalSource::is_media_file(Path::new("document.pdf")));
     }
+
+    #[test]
+    fn test_media_ids() {
+        let hash = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+        assert_eq!(LocalSource::media_id(hash), "local_0123456789abcdef");
+        assert!(LocalSource::is_content_id("local_0123456789abcdef"));
+        assert!(!LocalSource::is_content_id("local__home_me_talk.mp3"));
+        assert_eq!(LocalSource::legacy_media_id(Path::new("/no/such dir/a.mp3")), "local__no_such_dir_a.mp3");
+    }
+
+    #[test]
+    fn test_file_tags_from_ffprobe() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{
+                "chapters": [
+                    {"start_time": "0.000000", "end_time": "754.500000", "tags": {"title": "Opening Credits"}},
+                    {"start_time": "754.500000", "end_time": "2040.000000", "tags": {}}
+                ],
+                "format": {
+                    "duration": "2040.120000",
+                    "tags": {"TITLE": "Part 1", "album_artist": "Jane Author", "album": "The Book", "track": "3/12", "date": "2019-05-02"}
+                }
+            }"#,
+        )
+        .unwrap();
+        let tags = FileTags::from_ffprobe(&json);
+
+        assert_eq!(tags.duration, Some(2040));
+        assert_eq!(tags.title.as_deref(), Some("Part 1"));
+        assert_eq!(tags.artist.as_deref(), Some("Jane Author"));
+        assert_eq!((tags.album.as_deref(), tags.track), (Some("The Book"), Some(3)));
+        assert_eq!(tags.date.unwrap().format("%Y-%m-%d").to_string(), "2019-05-02");
+        assert_eq!(tags.chapters.len(), 2);
+        assert_eq!(tags.chapters[1].title, "Chapter 2");
+        assert_eq!(tags.chapters[1].start_seconds, 754.5);
+
+        assert_eq!(parse_tag_date("2021").unwrap().format("%Y-%m-%d").to_string(), "2021-01-01");
+        assert_eq!(FileTags::from_ffprobe(&serde_json::Value::Null), FileTags::default());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("**/*.m4a", "2023/march/call.m4a"));
+        assert!(glob_match("**/*.m4a", "call.m4a"));
+        assert!(!glob_match("*.m4a", "2023/call.m4a"));
+        assert!(glob_match("20??/*/call*.mp3", "2023/march/call-2.mp3"));
+        assert_eq!(expand_braces("*.{m4a,mp3}"), ["*.m4a", "*.mp3"]);
+        assert_eq!(split_glob("archive/**/*.m4a"), (PathBuf::from("archive"), Some("**/*.m4a".to_string())));
+        assert_eq!(split_glob("*.mp3"), (PathBuf::from("."), Some("*.mp3".to_string())));
+    }
+
+    #[test]
+    fn test_find_media_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["a.mp3", "notes.txt", "2023/b.m4a", "2023/drafts/c.m4a", "2024/d.wav", ".cache/e.mp3"] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, file).unwrap();
+        }
+        let root = dir.path().to_str().unwrap();
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| f.strip_prefix(dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+
+        assert_eq!(names(LocalSource::new().find_media_files(root).unwrap()), ["a.mp3"]);
+        let recursive = LocalSource::new().with_recursive(true);
+        assert_eq!(
+            names(recursive.find_media_files(root).unwrap()),
+            ["2023/b.m4a", "2023/drafts/c.m4a", "2024/d.wav", "a.mp3"]
+        );
+        let excluding = recursive.with_excludes(vec!["drafts".to_string(), "*.wav".to_string()]);
+        assert_eq!(names(excluding.find_media_files(root).unwrap()), ["2023/b.m4a", "a.mp3"]);
+        let pattern = format!("{}/**/*.m4a", root);
+        assert_eq!(names(LocalSource::new().find_media_files(&pattern).unwrap()), ["2023/b.m4a", "2023/drafts/c.m4a"]);
+
+        assert!(LocalSource::is_collection(root));
+        assert!(LocalSource::is_collection(&pattern));
+        assert!(!LocalSource::is_collection("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!LocalSource::is_collection("no-such-dir/*.m4a"));
+
+        assert_eq!(content_hash(&dir.path().join("a.mp3")).unwrap().len(), 64);
+        assert_ne!(
+            content_hash(&dir.path().join("a.mp3")).unwrap(),
+            content_hash(&dir.path().join("2024/d.wav")).unwrap()
+        );
+    }
 }
@@ -1,14 +1,19 @@
 //! Audio source abstraction for Lytt.
 //!
-//! Provides a trait-based interface for different audio sources (YouTube, local files, etc.).
+//! Provides a trait-based interface for different audio sources (YouTube, other
+//! sites yt-dlp supports, local files).
 
+mod generic;
 mod local;
+pub mod sponsorblock;
 mod youtube;
 
-pub use local::LocalSource;
+pub use generic::{GenericUrlSource, WEB_ID_PREFIX};
+pub use local::{content_hash, LocalSource};
 pub use youtube::YoutubeSource;
 
 use crate::error::Result;
+use crate::transcription::Chapter;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -19,18 +24,47 @@ use std::path::{Path, PathBuf};
 #[serde(rename_all = "lowercase")]
 pub enum SourceType {
     YouTube,
+    /// Any other site yt-dlp supports (Vimeo, SoundCloud, ...).
+    Web,
     Local,
 }
 
+impl SourceType {
+    /// The source a stored media ID came from.
+    pub fn of_media_id(id: &str) -> Self {
+        if id.starts_with("local_") {
+            SourceType::Local
+        } else if id.starts_with(WEB_ID_PREFIX) {
+            SourceType::Web
+        } else {
+            SourceType::YouTube
+        }
+    }
+}
+
 impl std::fmt::Display for SourceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             SourceType::YouTube => write!(f, "youtube"),
+            SourceType::Web => write!(f, "web"),
             SourceType::Local => write!(f, "local"),
         }
     }
 }
 
+impl std::str::FromStr for SourceType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "youtube" => Ok(SourceType::YouTube),
+            "web" => Ok(SourceType::Web),
+            "local" => Ok(SourceType::Local),
+            _ => Err(format!("Unknown source: {}. Use youtube, web, or local.", s)),
+        }
+    }
+}
+
 /// Metadata about a media file (audio or video).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaMetadata {
@@ -52,16 +86,60 @@ pub struct MediaMetadata {
     pub channel: Option<String>,
     /// Thumbnail URL (if available).
     pub thumbnail_url: Option<String>,
+    /// Creator-defined chapters (empty if the source has none).
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    /// Album, audiobook or podcast show, from a local file's tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    /// Track number within the album, from a local file's tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<u32>,
 }
 
 impl MediaMetadata {
+    /// Metadata for indexed media from its stored title and source, enough
+    /// to link to it. YouTube media indexed before sources were recorded
+    /// is found by its ID; other media without a source gives `None`.
+    pub fn stored(id: &str, title: &str, source: Option<(SourceType, String)>) -> Option<Self> {
+        let (source_type, source_url) = match source {
+            Some(source) => source,
+            None if SourceType::of_media_id(id) == SourceType::YouTube => {
+                (SourceType::YouTube, format!("https://www.youtube.com/watch?v={}", id))
+            }
+            None => return None,
+        };
+        Some(Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: None,
+            duration_seconds: None,
+            source_type,
+            source_url,
+            published_at: None,
+            channel: None,
+            thumbnail_url: None,
+            album: None,
+            track: None,
+            chapters: Vec::new(),
+        })
+    }
+
+    /// Whether the title is just the file name because the media has no title of its own.
+    pub fn has_fallback_title(&self) -> bool {
+        self.source_type == SourceType::Local
+            && std::path::Path::new(&self.source_url)
+                .file_stem()
+                .is_some_and(|stem| stem.to_string_lossy() == self.title)
+    }
+
     /// Create a URL with timestamp for a specific point in the media.
     pub fn url_with_timestamp(&self, seconds: f64) -> String {
         match self.source_type {
             SourceType::YouTube => {
                 format!("https://youtube.com/watch?v={}&t={}s", self.id, seconds as u32)
             }
-            SourceType::Local => {
+            SourceType::Web | SourceType::Local => {
                 format!("{}#t={}", self.source_url, seconds as u32)
             }
         }
@@ -116,6 +194,11 @@ pub fn detect_source(input: &str) -> Option<Box<dyn AudioSource>> {
         return Some(Box::new(youtube));
     }
 
+    let web = GenericUrlSource::new();
+    if web.can_handle(input) {
+        return Some(Box::new(web));
+    }
+
     let local = LocalSource::new();
     if local.can_handle(input) {
         return Some(Box::new(local));
@@ -0,0 +1,181 @@
+//! SponsorBlock: crowd-sourced sponsor, intro and outro segments of YouTube
+//! videos.
+//!
+//! Skipped ranges are cut from the audio before it is transcribed, and the
+//! transcript is then moved back onto the video's own timeline, so
+//! timestamps still point at the right place. Captions, which come with the
+//! video's timeline, just lose the segments inside skipped ranges.
+
+use super::{MediaMetadata, SourceType};
+use crate::config::YoutubeSettings;
+use crate::error::{LyttError, Result};
+use crate::transcription::Transcript;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Time range to skip, in seconds, on the video's timeline.
+pub type SkipRange = (f64, f64);
+
+#[derive(Debug, Deserialize)]
+struct Segment {
+    segment: (f64, f64),
+}
+
+/// Ranges of `video_id` submitted to SponsorBlock in the configured
+/// categories, sorted and merged. Videos nobody has submitted segments for
+/// have none.
+pub async fn skip_ranges(settings: &YoutubeSettings, video_id: &str) -> Result<Vec<SkipRange>> {
+    let categories = serde_json::to_string(&settings.sponsorblock_categories)?;
+    let url = format!("{}/api/skipSegments", settings.sponsorblock_api.trim_end_matches('/'));
+    let response = crate::openai::http_client(Duration::from_secs(10))
+        .get(&url)
+        .query(&[("videoID", video_id), ("categories", categories.as_str())])
+        .send()
+        .await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        debug!("No SponsorBlock segments for {}", video_id);
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(LyttError::VideoSource(format!("SponsorBlock returned {}", response.status())));
+    }
+
+    let segments: Vec<Segment> = response.json().await?;
+    Ok(merge_ranges(segments.into_iter().map(|s| s.segment).collect()))
+}
+
+/// Ranges to skip in media when SponsorBlock is enabled and it is a YouTube
+/// video. Lookups that fail skip nothing.
+pub async fn ranges_for(settings: &YoutubeSettings, metadata: &MediaMetadata) -> Vec<SkipRange> {
+    if !settings.sponsorblock || metadata.source_type != SourceType::YouTube {
+        return Vec::new();
+    }
+    match skip_ranges(settings, &metadata.id).await {
+        Ok(ranges) => ranges,
+        Err(e) => {
+            warn!("SponsorBlock lookup failed, transcribing everything: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Sort ranges and merge those that overlap or touch.
+fn merge_ranges(mut ranges: Vec<SkipRange>) -> Vec<SkipRange> {
+    ranges.retain(|(start, end)| end > start);
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut merged: Vec<SkipRange> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Seconds covered by `ranges`.
+pub fn skipped_seconds(ranges: &[SkipRange]) -> f64 {
+    ranges.iter().map(|(start, end)| end - start).sum()
+}
+
+/// Time on the video's timeline of `seconds` into the audio with `ranges`
+/// cut out.
+fn original_time(ranges: &[SkipRange], seconds: f64) -> f64 {
+    let mut time = seconds;
+    for (start, end) in ranges {
+        if *start > time {
+            break;
+        }
+        time += end - start;
+    }
+    time
+}
+
+/// Move a transcript of the audio with `ranges` cut out onto the video's
+/// timeline.
+pub fn restore_timeline(transcript: &mut Transcript, ranges: &[SkipRange]) {
+    for segment in &mut transcript.segments {
+        segment.start_seconds = original_time(ranges, segment.start_seconds);
+        // Ends falling on a cut belong before it
+        segment.end_seconds = original_time(ranges, (segment.end_seconds - 0.001).max(0.0)) + 0.001;
+    }
+    if let Some(last) = transcript.segments.last() {
+        transcript.duration_seconds = transcript.duration_seconds.max(last.end_seconds);
+    }
+}
+
+/// Remove the segments of a transcript on the video's timeline whose middle
+/// falls inside `ranges`. Returns how many were removed.
+pub fn drop_skipped(transcript: &mut Transcript, ranges: &[SkipRange]) -> usize {
+    let before = transcript.segments.len();
+    transcript.segments.retain(|segment| {
+        let middle = (segment.start_seconds + segment.end_seconds) / 2.0;
+        !ranges.iter().any(|(start, end)| middle >= *start && middle < *end)
+    });
+    let removed = before - transcript.segments.len();
+    if removed > 0 {
+        transcript.full_text = transcript.segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptSegment;
+
+    #[test]
+    fn test_skip_ranges() {
+        let segments: Vec<Segment> = serde_json::from_str(
+            r#"[{"segment": [60.0, 90.0], "category": "sponsor", "UUID": "a"},
+                {"segment": [0.0, 10.0], "category": "intro", "UUID": "b"},
+                {"segment": [85.0, 100.0], "category": "sponsor", "UUID": "c"}]"#,
+        )
+        .unwrap();
+        let ranges = merge_ranges(segments.into_iter().map(|s| s.segment).collect());
+        assert_eq!(ranges, vec![(0.0, 10.0), (60.0, 100.0)]);
+        assert_eq!(skipped_seconds(&ranges), 50.0);
+
+        // 0-50s of the cut audio is 10-60s of the video, then it jumps to 100s
+        let segment = |start: f64, end: f64| TranscriptSegment::new(start, end, format!("{}", start));
+        let mut transcript = Transcript::new("v".to_string(), vec![segment(0.0, 5.0), segment(45.0, 50.0), segment(50.0, 55.0)]);
+        restore_timeline(&mut transcript, &ranges);
+        let times: Vec<(f64, f64)> = transcript.segments.iter().map(|s| (s.start_seconds, s.end_seconds.round())).collect();
+        assert_eq!(times, vec![(10.0, 15.0), (55.0, 60.0), (100.0, 105.0)]);
+
+        let mut captions = Transcript::new("v".to_string(), vec![segment(2.0, 6.0), segment(20.0, 25.0), segment(70.0, 75.0)]);
+        assert_eq!(drop_skipped(&mut captions, &ranges), 2);
+        assert_eq!(captions.full_text, "20");
+    }
+
+    #[tokio::test]
+    async fn test_ranges_for_youtube_videos_only() {
+        let app = axum::Router::new().route(
+            "/api/skipSegments",
+            axum::routing::get(|| async { r#"[{"segment": [60.0, 90.0], "category": "sponsor", "UUID": "a"}]"# }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut settings = YoutubeSettings {
+            sponsorblock: true,
+            sponsorblock_api: format!("http://{}", addr),
+            ..Default::default()
+        };
+        let video = MediaMetadata::stored("dQw4w9WgXcQ", "Video", None).unwrap();
+        let file = MediaMetadata::stored("local_abc", "File", Some((SourceType::Local, "/tmp/a.mp3".to_string()))).unwrap();
+        assert_eq!(ranges_for(&settings, &video).await, vec![(60.0, 90.0)]);
+        assert!(ranges_for(&settings, &file).await.is_empty());
+
+        settings.sponsorblock = false;
+        assert!(ranges_for(&settings, &video).await.is_empty());
+
+        // A lookup that fails skips nothing
+        settings.sponsorblock = true;
+        settings.sponsorblock_api = "http://127.0.0.1:1".to_string();
+        assert!(ranges_for(&settings, &video).await.is_empty());
+    }
+}
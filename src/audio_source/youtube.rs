@@ -1,7 +1,9 @@
 //! YouTube source implementation.
 
 use super::{AudioSource, MediaMetadata, SourceType};
+use crate::audio::ytdlp_command;
 use crate::error::{Result, LyttError};
+use crate::transcription::Chapter;
 use async_trait::async_trait;
 use regex::Regex;
 
@@ -45,80 +47,100 @@ impl YoutubeSource {
     /// Fetch metadata using yt-dlp.
     async fn fetch_metadata_ytdlp(&self, video_id: &str) -> Result<MediaMetadata> {
         let url = format!("https://www.youtube.com/watch?v={}", video_id);
-
-        let output = tokio::process::Command::new("yt-dlp")
-            .args([
-                "--dump-json",
-                "--no-download",
-                "--no-warnings",
-                "--ignore-errors",
-                &url,
-            ])
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LyttError::ToolNotFound("yt-dlp".to_string())
-                } else {
-                    LyttError::VideoSource(format!("Failed to run yt-dlp: {}", e))
-                }
-            })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(LyttError::VideoNotFound(format!(
-                "Video {} not found or unavailable: {}",
-                video_id, stderr
-            )));
-        }
-
-        let json_str = String::from_utf8_lossy(&output.stdout);
-        let json: serde_json::Value = serde_json::from_str(&json_str).map_err(|e| {
-            LyttError::VideoSource(format!("Failed to parse yt-dlp output: {}", e))
+        let json = dump_json(&url).await.map_err(|e| match e {
+            LyttError::VideoNotFound(stderr) => {
+                LyttError::VideoNotFound(format!("Video {} not found or unavailable: {}", video_id, stderr))
+            }
+            e => e,
         })?;
 
-        let title = json["title"]
-            .as_str()
-            .unwrap_or("Unknown Title")
-            .to_string();
-
-        let description = json["description"].as_str().map(|s| s.to_string());
-
-        let duration = json["duration"].as_f64().map(|d| d as u32);
-
-        let channel = json["channel"]
-            .as_str()
-            .or_else(|| json["uploader"].as_str())
-            .map(|s| s.to_string());
-
-        let thumbnail = json["thumbnail"].as_str().map(|s| s.to_string());
+        Ok(metadata_from_json(&json, video_id.to_string(), SourceType::YouTube, url))
+    }
+}
 
-        let published_at = json["upload_date"].as_str().and_then(|date_str| {
-            // yt-dlp returns date as YYYYMMDD
-            if date_str.len() == 8 {
-                chrono::NaiveDate::parse_from_str(date_str, "%Y%m%d")
-                    .ok()
-                    .map(|d| {
-                        d.and_hms_opt(0, 0, 0)
-                            .unwrap()
-                            .and_utc()
-                    })
+/// Probe a URL with `yt-dlp --dump-json`. A failed probe is reported as not
+/// found, with yt-dlp's error output.
+pub(super) async fn dump_json(url: &str) -> Result<serde_json::Value> {
+    let output = ytdlp_command()
+        .args([
+            "--dump-json",
+            "--no-download",
+            "--no-playlist",
+            "--no-warnings",
+            "--ignore-errors",
+            url,
+        ])
+        .output()
+        .await
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                LyttError::ToolNotFound("yt-dlp".to_string())
             } else {
-                None
+                LyttError::VideoSource(format!("Failed to run yt-dlp: {}", e))
             }
-        });
+        })?;
 
-        Ok(MediaMetadata {
-            id: video_id.to_string(),
-            title,
-            description,
-            duration_seconds: duration,
-            source_type: SourceType::YouTube,
-            source_url: url,
-            published_at,
-            channel,
-            thumbnail_url: thumbnail,
-        })
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(LyttError::VideoNotFound(stderr.trim().to_string()));
+    }
+
+    let json_str = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(&json_str)
+        .map_err(|e| LyttError::VideoSource(format!("Failed to parse yt-dlp output: {}", e)))
+}
+
+/// Build metadata from yt-dlp's JSON for one item.
+pub(super) fn metadata_from_json(
+    json: &serde_json::Value,
+    id: String,
+    source_type: SourceType,
+    source_url: String,
+) -> MediaMetadata {
+    let title = json["title"]
+        .as_str()
+        .unwrap_or("Unknown Title")
+        .to_string();
+
+    let description = json["description"].as_str().map(|s| s.to_string());
+
+    let duration = json["duration"].as_f64().map(|d| d as u32);
+
+    let channel = json["channel"]
+        .as_str()
+        .or_else(|| json["uploader"].as_str())
+        .map(|s| s.to_string());
+
+    let thumbnail = json["thumbnail"].as_str().map(|s| s.to_string());
+
+    let published_at = json["upload_date"].as_str().and_then(|date_str| {
+        // yt-dlp returns date as YYYYMMDD
+        if date_str.len() == 8 {
+            chrono::NaiveDate::parse_from_str(date_str, "%Y%m%d")
+                .ok()
+                .map(|d| {
+                    d.and_hms_opt(0, 0, 0)
+                        .unwrap()
+                        .and_utc()
+                })
+        } else {
+            None
+        }
+    });
+
+    MediaMetadata {
+        id,
+        title,
+        description,
+        duration_seconds: duration,
+        source_type,
+        source_url,
+        published_at,
+        channel,
+        thumbnail_url: thumbnail,
+        album: None,
+        track: None,
+        chapters: parse_chapters(json),
     }
 }
 
@@ -146,7 +168,7 @@ impl AudioSource for YoutubeSource {
         // For playlists/channels, use yt-dlp to get video list
         let limit_str = limit.map(|l| l.to_string()).unwrap_or_else(|| "50".to_string());
 
-        let output = tokio::process::Command::new("yt-dlp")
+        let output = ytdlp_command()
             .args([
                 "--dump-json",
                 "--no-download",
@@ -207,6 +229,9 @@ impl AudioSource for YoutubeSource {
                         published_at: None,
                         channel: json["channel"].as_str().map(|s| s.to_string()),
                         thumbnail_url: None,
+                        album: None,
+                        track: None,
+                        chapters: Vec::new(),
                     });
                 }
             }
@@ -227,6 +252,28 @@ impl AudioSource for YoutubeSource {
     }
 }
 
+/// Read chapters from yt-dlp's `chapters` list, dropping untitled or empty ones.
+fn parse_chapters(json: &serde_json::Value) -> Vec<Chapter> {
+    json["chapters"]
+        .as_array()
+        .map(|chapters| {
+            chapters
+                .iter()
+                .filter_map(|c| {
+                    let title = c["title"].as_str()?.trim();
+                    let start_seconds = c["start_time"].as_f64()?;
+                    let end_seconds = c["end_time"].as_f64()?;
+                    (!title.is_empty() && end_seconds > start_seconds).then(|| Chapter {
+                        title: title.to_string(),
+                        start_seconds,
+                        end_seconds,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,4 +314,21 @@ mod tests {
         assert!(source.can_handle("https://youtube.com/playlist?list=PLtest"));
         assert!(!source.can_handle("/path/to/video.mp4"));
     }
+
+    #[test]
+    fn test_parse_chapters() {
+        let json = serde_json::json!({
+            "chapters": [
+                {"title": "Intro", "start_time": 0.0, "end_time": 95.0},
+                {"title": "", "start_time": 95.0, "end_time": 100.0},
+                {"title": "Ownership", "start_time": 100.0, "end_time": 720.5}
+            ]
+        });
+        let chapters = parse_chapters(&json);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].title, "Ownership");
+        assert_eq!(chapters[1].end_seconds, 720.5);
+
+        assert!(parse_chapters(&serde_json::json!({"chapters": null})).is_empty());
+    }
 }
@@ -0,0 +1,208 @@
+//! Audiobooks.
+//!
+//! An audiobook is indexed as one media item, whether it comes as a single
+//! M4B with chapter markers or as a folder of MP3 parts. Parts are put in
+//! track order and joined into one timeline; each part becomes a chapter
+//! unless it has chapter markers of its own, which are moved onto the book's
+//! timeline. The chapters give the sections the book is chunked into, and
+//! each indexed chunk records its book, author and chapter, so answers can
+//! cite "Chapter 7 @ 12:34".
+
+use crate::audio_source::{AudioSource, LocalSource, MediaMetadata, SourceType};
+use crate::error::{LyttError, Result};
+use crate::transcription::Chapter;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// An audiobook made of one or more local files.
+#[derive(Debug, Clone)]
+pub struct Audiobook {
+    /// Book title.
+    pub title: String,
+    /// Author or narrator.
+    pub author: Option<String>,
+    /// The files the book is made of, in playing order.
+    pub parts: Vec<MediaMetadata>,
+    /// Chapters on the book's timeline.
+    pub chapters: Vec<Chapter>,
+}
+
+impl Audiobook {
+    /// Read an audiobook from a file, a directory or a glob of its parts.
+    pub async fn load(input: &str, source: &LocalSource) -> Result<Self> {
+        let paths = if LocalSource::is_collection(input) {
+            source.find_media_files(input)?
+        } else {
+            vec![PathBuf::from(input)]
+        };
+        if paths.is_empty() {
+            return Err(LyttError::InvalidInput(format!("No audio files found in {}", input)));
+        }
+
+        let mut parts = Vec::with_capacity(paths.len());
+        for path in &paths {
+            parts.push(source.fetch_media(&path.to_string_lossy()).await?);
+        }
+
+        // The folder an audiobook comes in is usually named after it
+        let fallback = Path::new(input.trim_end_matches('/'))
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Audiobook".to_string());
+        Ok(Self::from_parts(parts, &fallback))
+    }
+
+    /// Assemble an audiobook from the metadata of its parts, in any order.
+    /// `fallback_title` is used when the parts' tags don't name the book.
+    pub fn from_parts(mut parts: Vec<MediaMetadata>, fallback_title: &str) -> Self {
+        // Tagged track numbers first, then by file name
+        parts.sort_by(|a, b| {
+            (a.track.is_none(), a.track, &a.source_url).cmp(&(b.track.is_none(), b.track, &b.source_url))
+        });
+
+        let title = parts
+            .iter()
+            .find_map(|p| p.album.clone())
+            .or_else(|| (parts.len() == 1).then(|| parts[0].title.clone()))
+            .unwrap_or_else(|| fallback_title.to_string());
+        let author = parts.iter().find_map(|p| p.channel.clone());
+
+        let mut chapters = Vec::new();
+        let mut offset = 0.0;
+        for part in &parts {
+            let duration = match part.duration_seconds {
+                Some(duration) => f64::from(duration),
+                None => {
+                    warn!("Unknown duration of {}; chapters after it may be off", part.source_url);
+                    0.0
+                }
+            };
+            if !part.chapters.is_empty() {
+                chapters.extend(part.chapters.iter().map(|c| Chapter {
+                    title: c.title.clone(),
+                    start_seconds: offset + c.start_seconds,
+                    end_seconds: offset + c.end_seconds,
+                }));
+            } else if parts.len() > 1 {
+                chapters.push(Chapter {
+                    title: part.title.clone(),
+                    start_seconds: offset,
+                    end_seconds: offset + duration,
+                });
+            }
+            offset += duration;
+        }
+
+        Self {
+            title,
+            author,
+            parts,
+            chapters,
+        }
+    }
+
+    /// ID of the book: that of its file, or for a book in parts, one
+    /// derived from the parts' IDs so the same parts give the same ID.
+    pub fn media_id(&self) -> String {
+        if let [part] = self.parts.as_slice() {
+            return part.id.clone();
+        }
+        let mut hasher = Sha256::new();
+        for part in &self.parts {
+            hasher.update(part.id.as_bytes());
+            hasher.update(b"\n");
+        }
+        let hash: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        LocalSource::media_id(&hash)
+    }
+
+    /// Paths of the parts, in playing order.
+    pub fn part_paths(&self) -> Vec<PathBuf> {
+        self.parts.iter().map(|p| PathBuf::from(&p.source_url)).collect()
+    }
+
+    /// Metadata of the book as one media item. A book in parts is found at
+    /// the directory of its first part.
+    pub fn metadata(&self) -> MediaMetadata {
+        let first = &self.parts[0];
+        let source_url = if self.parts.len() == 1 {
+            first.source_url.clone()
+        } else {
+            Path::new(&first.source_url)
+                .parent()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|| first.source_url.clone())
+        };
+
+        MediaMetadata {
+            id: self.media_id(),
+            title: self.title.clone(),
+            description: first.description.clone(),
+            duration_seconds: self.parts.iter().map(|p| p.duration_seconds).sum(),
+            source_type: SourceType::Local,
+            source_url,
+            published_at: first.published_at,
+            channel: self.author.clone(),
+            thumbnail_url: None,
+            chapters: self.chapters.clone(),
+            album: Some(self.title.clone()),
+            track: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(file: &str, track: Option<u32>, duration: u32, chapters: Vec<Chapter>) -> MediaMetadata {
+        MediaMetadata {
+            id: format!("local_{}", file),
+            title: format!("Part {}", file),
+            description: None,
+            duration_seconds: Some(duration),
+            source_type: SourceType::Local,
+            source_url: format!("/books/dune/{}.mp3", file),
+            published_at: None,
+            channel: Some("Frank Herbert".to_string()),
+            thumbnail_url: None,
+            chapters,
+            album: Some("Dune".to_string()),
+            track,
+        }
+    }
+
+    #[test]
+    fn test_audiobook_from_parts() {
+        let chapter = |title: &str, start: f64, end: f64| Chapter {
+            title: title.to_string(),
+            start_seconds: start,
+            end_seconds: end,
+        };
+        // Listed out of order; the second part has chapter markers
+        let parts = vec![
+            part("b", Some(2), 600, vec![chapter("Chapter 2", 0.0, 300.0), chapter("Chapter 3", 300.0, 600.0)]),
+            part("a", Some(1), 900, Vec::new()),
+        ];
+        let book = Audiobook::from_parts(parts, "dune");
+
+        assert_eq!(book.title, "Dune");
+        assert_eq!(book.author.as_deref(), Some("Frank Herbert"));
+        assert_eq!(book.part_paths()[0], PathBuf::from("/books/dune/a.mp3"));
+        assert_eq!(
+            book.chapters,
+            vec![
+                chapter("Part a", 0.0, 900.0),
+                chapter("Chapter 2", 900.0, 1200.0),
+                chapter("Chapter 3", 1200.0, 1500.0),
+            ]
+        );
+
+        let metadata = book.metadata();
+        assert_eq!(metadata.duration_seconds, Some(1500));
+        assert_eq!(metadata.source_url, "/books/dune");
+        assert!(LocalSource::is_content_id(&metadata.id));
+        assert_eq!(metadata.id, Audiobook::from_parts(book.parts.clone(), "dune").media_id());
+    }
+}
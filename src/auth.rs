@@ -0,0 +1,179 @@
+//! API key authentication and rate limiting for the servers.
+//!
+//! Keys are configured in `[server] api_keys`. Clients send one as
+//! `Authorization: Bearer <key>` (or `X-API-Key: <key>`). Each key, or every
+//! caller when no keys are configured, gets its own token bucket allowing
+//! `rate_limit_per_minute` requests with bursts up to the same amount.
+
+use crate::config::ServerSettings;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Why a request was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessDenied {
+    /// No key or an unknown key was presented.
+    Unauthorized,
+    /// The key used up its rate limit; retry after the given delay.
+    RateLimited { retry_after: Duration },
+}
+
+impl std::fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessDenied::Unauthorized => write!(f, "Missing or invalid API key"),
+            AccessDenied::RateLimited { retry_after } => {
+                write!(f, "Rate limit exceeded, retry in {}s", retry_after.as_secs().max(1))
+            }
+        }
+    }
+}
+
+/// Checks API keys and per-key rate limits.
+pub struct AccessControl {
+    keys: Vec<String>,
+    limiter: Option<RateLimiter>,
+}
+
+impl AccessControl {
+    /// Create access control from settings.
+    pub fn from_settings(settings: &ServerSettings) -> Self {
+        let mut keys: Vec<String> = settings.api_keys.iter().filter(|k| !k.is_empty()).cloned().collect();
+        if let Some(var) = &settings.api_keys_env {
+            if let Ok(value) = std::env::var(var) {
+                keys.extend(value.split(',').map(|k| k.trim().to_string()).filter(|k| !k.is_empty()));
+            }
+        }
+
+        Self {
+            keys,
+            limiter: (settings.rate_limit_per_minute > 0)
+                .then(|| RateLimiter::new(settings.rate_limit_per_minute)),
+        }
+    }
+
+    /// Whether requests must present an API key.
+    pub fn requires_key(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Whether requests are rate limited.
+    pub fn is_rate_limited(&self) -> bool {
+        self.limiter.is_some()
+    }
+
+    /// Check a request's credentials. `authorization` is the raw `Authorization`
+    /// header, `api_key` the raw `X-API-Key` header.
+    pub fn check(&self, authorization: Option<&str>, api_key: Option<&str>) -> Result<(), AccessDenied> {
+        let presented = authorization
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .or(api_key)
+            .map(str::trim);
+
+        let bucket = if self.requires_key() {
+            let key = presented
+                .filter(|p| self.keys.iter().any(|k| constant_time_eq(k, p)))
+                .ok_or(AccessDenied::Unauthorized)?;
+            key.to_string()
+        } else {
+            String::new()
+        };
+
+        match &self.limiter {
+            Some(limiter) => limiter.acquire(&bucket, Instant::now()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Compare without stopping at the first differing byte.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Token bucket per key.
+struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn acquire(&self, key: &str, now: Instant) -> Result<(), AccessDenied> {
+        let capacity = self.per_minute as f64;
+        let per_second = capacity / 60.0;
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(AccessDenied::RateLimited {
+                retry_after: Duration::from_secs_f64((1.0 - bucket.tokens) / per_second),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(keys: &[&str], per_minute: u32) -> ServerSettings {
+        ServerSettings {
+            api_keys: keys.iter().map(|k| k.to_string()).collect(),
+            api_keys_env: None,
+            rate_limit_per_minute: per_minute,
+        }
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let open = AccessControl::from_settings(&settings(&[], 0));
+        assert!(open.check(None, None).is_ok());
+
+        let access = AccessControl::from_settings(&settings(&["secret"], 0));
+        assert_eq!(access.check(None, None), Err(AccessDenied::Unauthorized));
+        assert_eq!(access.check(Some("Bearer wrong"), None), Err(AccessDenied::Unauthorized));
+        assert!(access.check(Some("Bearer secret"), None).is_ok());
+        assert!(access.check(None, Some("secret")).is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit_per_key() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(limiter.acquire("a", start).is_ok());
+        assert!(limiter.acquire("a", start).is_ok());
+        let Err(AccessDenied::RateLimited { retry_after }) = limiter.acquire("a", start) else {
+            panic!("third request should be limited");
+        };
+        assert_eq!(retry_after.as_secs(), 30);
+
+        // Other keys have their own budget, and tokens refill over time
+        assert!(limiter.acquire("b", start).is_ok());
+        assert!(limiter.acquire("a", start + Duration::from_secs(30)).is_ok());
+    }
+}
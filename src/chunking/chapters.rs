@@ -0,0 +1,157 @@
+//! Chapter-based chunking implementation.
+//!
+//! Uses creator-defined chapters (e.g. YouTube chapters) as chunk boundaries
+//! and titles. Chapters longer than the maximum chunk duration are split
+//! further, by an LLM if a refiner is set and otherwise into equal parts.
+
+use super::{chunk_from_segments, split_evenly, Chunker, ChunkingConfig, ContentChunk};
+use crate::error::Result;
+use crate::transcription::{Transcript, TranscriptSegment};
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+/// Chapter-based chunker.
+///
+/// Transcripts without chapters are passed to the fallback chunker.
+pub struct ChapterChunker {
+    fallback: Box<dyn Chunker>,
+    refiner: Option<Box<dyn Chunker>>,
+}
+
+impl ChapterChunker {
+    /// Create a chapter chunker that hands transcripts without chapters to `fallback`.
+    pub fn new(fallback: Box<dyn Chunker>) -> Self {
+        Self { fallback, refiner: None }
+    }
+
+    /// Split chapters longer than the maximum chunk duration with this chunker.
+    pub fn with_refiner(mut self, refiner: Box<dyn Chunker>) -> Self {
+        self.refiner = Some(refiner);
+        self
+    }
+
+    /// Split a long chapter, naming each part after the chapter.
+    async fn split_chapter(
+        &self,
+        title: &str,
+        transcript: &Transcript,
+        config: &ChunkingConfig,
+    ) -> Vec<ContentChunk> {
+        if let Some(refiner) = &self.refiner {
+            match refiner.chunk(transcript, config).await {
+                Ok(parts) if !parts.is_empty() => {
+                    return parts
+                        .into_iter()
+                        .map(|mut part| {
+                            part.title = Some(match part.title {
+                                Some(sub) => format!("{}: {}", title, sub),
+                                None => title.to_string(),
+                            });
+                            part
+                        })
+                        .collect();
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Refining chapter '{}' failed, splitting evenly: {}", title, e),
+            }
+        }
+
+        let segments: Vec<&TranscriptSegment> = transcript.segments.iter().collect();
+        let parts = split_evenly(&segments, config.target_duration as f64);
+        let count = parts.len();
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, segments)| {
+                let title = if count > 1 { format!("{} ({}/{})", title, i + 1, count) } else { title.to_string() };
+                chunk_from_segments(Some(title), segments)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Chunker for ChapterChunker {
+    async fn chunk(&self, transcript: &Transcript, config: &ChunkingConfig) -> Result<Vec<ContentChunk>> {
+        if transcript.chapters.is_empty() {
+            return self.fallback.chunk(transcript, config).await;
+        }
+
+        let mut chunks = Vec::new();
+        let last = transcript.chapters.len() - 1;
+        for (i, chapter) in transcript.chapters.iter().enumerate() {
+            // Segments belong to the chapter their midpoint falls in; the last
+            // chapter takes everything after its start
+            let segments: Vec<&TranscriptSegment> = transcript
+                .segments
+                .iter()
+                .filter(|s| {
+                    let mid = (s.start_seconds + s.end_seconds) / 2.0;
+                    let before_end = i == last || mid < chapter.end_seconds;
+                    let after_start = i == 0 || mid >= chapter.start_seconds;
+                    after_start && before_end
+                })
+                .collect();
+            if segments.is_empty() {
+                continue;
+            }
+
+            let start = segments[0].start_seconds;
+            let end = segments[segments.len() - 1].end_seconds;
+            if end - start <= config.max_duration as f64 {
+                chunks.push(chunk_from_segments(Some(chapter.title.clone()), segments));
+            } else {
+                let sub = Transcript::new(
+                    transcript.video_id.clone(),
+                    segments.into_iter().cloned().collect(),
+                );
+                chunks.extend(self.split_chapter(&chapter.title, &sub, config).await);
+            }
+        }
+
+        for (order, chunk) in chunks.iter_mut().enumerate() {
+            chunk.order = order as i32;
+        }
+        info!("Created {} chunks from {} chapters", chunks.len(), transcript.chapters.len());
+        Ok(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunking::TemporalChunker;
+    use crate::transcription::Chapter;
+
+    fn transcript() -> Transcript {
+        let segments = (0..20)
+            .map(|i| TranscriptSegment::new(i as f64 * 60.0, (i + 1) as f64 * 60.0, format!("Segment {}", i)))
+            .collect();
+        Transcript::new("test".to_string(), segments).with_chapters(vec![
+            Chapter { title: "Intro".to_string(), start_seconds: 0.0, end_seconds: 120.0 },
+            Chapter { title: "Deep dive".to_string(), start_seconds: 120.0, end_seconds: 1200.0 },
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_chapter_chunking() {
+        let chunker = ChapterChunker::new(Box::new(TemporalChunker::new()));
+        let config = ChunkingConfig { target_duration: 300, min_duration: 60, max_duration: 600 };
+
+        let chunks = chunker.chunk(&transcript(), &config).await.unwrap();
+
+        assert_eq!(chunks[0].title.as_deref(), Some("Intro"));
+        assert_eq!(chunks[0].content, "Segment 0 Segment 1");
+        assert_eq!(chunks[0].end_seconds, 120.0);
+        // 18 minutes of "Deep dive" split into about 5-minute parts
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[1].title.as_deref(), Some("Deep dive (1/4)"));
+        assert_eq!(chunks[1].start_seconds, 120.0);
+        assert!(chunks.iter().all(|c| c.duration() <= 600.0));
+        assert_eq!(chunks[4].order, 4);
+
+        let without_chapters = Transcript::new("test".to_string(), transcript().segments);
+        let chunks = chunker.chunk(&without_chapters, &config).await.unwrap();
+        assert!(chunks.iter().all(|c| c.title.is_none()));
+    }
+}
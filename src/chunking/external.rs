@@ -0,0 +1,54 @@
+//! Chunking via an external plugin command.
+//!
+//! Request (one line on stdin):
+//! `{"type": "chunk", "transcript": {...}, "config": {"target_duration": 180, ...}}`
+//!
+//! Response (one line on stdout):
+//! `{"chunks": [{"title": "...", "content": "...", "start_seconds": 0.0, "end_seconds": 42.5, "order": 0}]}`
+
+use super::{Chunker, ChunkingConfig, ContentChunk};
+use crate::error::{LyttError, Result};
+use crate::plugin::PluginProcess;
+use crate::transcription::Transcript;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+/// Chunker backed by an external command.
+pub struct ExternalChunker {
+    plugin: PluginProcess,
+}
+
+#[derive(Deserialize)]
+struct ChunkResponse {
+    chunks: Vec<ContentChunk>,
+}
+
+impl ExternalChunker {
+    /// Create a chunker that runs the given command.
+    pub fn new(command: &str, args: Vec<String>) -> Self {
+        Self {
+            plugin: PluginProcess::new(command, args),
+        }
+    }
+}
+
+#[async_trait]
+impl Chunker for ExternalChunker {
+    async fn chunk(&self, transcript: &Transcript, config: &ChunkingConfig) -> Result<Vec<ContentChunk>> {
+        let request = json!({
+            "type": "chunk",
+            "transcript": transcript,
+            "config": config,
+        });
+
+        let response: ChunkResponse = serde_json::from_value(self.plugin.request(&request).await?)
+            .map_err(|e| {
+                LyttError::ToolFailed(format!("{}: invalid chunk response: {}", self.plugin.command(), e))
+            })?;
+
+        info!("External chunker {} created {} chunks", self.plugin.command(), response.chunks.len());
+        Ok(response.chunks)
+    }
+}
@@ -0,0 +1,103 @@
+//! Hybrid chunking implementation.
+//!
+//! Runs semantic chunking and repairs the sections one by one (see
+//! [`validate_chunks`]): overlaps are trimmed, stretches no section covers get
+//! temporal chunks, and sections outside the duration bounds are split or
+//! merged. Only if semantic chunking fails outright, including LLM errors, is
+//! the whole transcript chunked temporally.
+
+use super::{validate_chunks, Chunker, ChunkingConfig, ContentChunk, TemporalChunker};
+use crate::error::Result;
+use crate::transcription::Transcript;
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+/// Semantic chunker with per-section validation and temporal repair.
+pub struct HybridChunker {
+    primary: Box<dyn Chunker>,
+}
+
+impl HybridChunker {
+    /// Validate and repair the chunks produced by `primary` (usually semantic).
+    pub fn new(primary: Box<dyn Chunker>) -> Self {
+        Self { primary }
+    }
+}
+
+#[async_trait]
+impl Chunker for HybridChunker {
+    async fn chunk(&self, transcript: &Transcript, config: &ChunkingConfig) -> Result<Vec<ContentChunk>> {
+        let sections = match self.primary.chunk(transcript, config).await {
+            Ok(sections) if !sections.is_empty() => sections,
+            Ok(_) if transcript.segments.is_empty() => return Ok(Vec::new()),
+            Ok(_) => {
+                warn!("Semantic chunking returned no sections, chunking temporally");
+                return TemporalChunker::new().chunk(transcript, config).await;
+            }
+            Err(e) => {
+                warn!("Semantic chunking failed, chunking temporally: {}", e);
+                return TemporalChunker::new().chunk(transcript, config).await;
+            }
+        };
+
+        let chunks = validate_chunks(sections, transcript, config);
+        info!("Created {} hybrid chunks", chunks.len());
+        Ok(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptSegment;
+
+    /// Returns fixed sections, like a semantic chunker with a sloppy LLM.
+    struct FixedChunker(Vec<(f64, f64, &'static str)>);
+
+    #[async_trait]
+    impl Chunker for FixedChunker {
+        async fn chunk(&self, _transcript: &Transcript, _config: &ChunkingConfig) -> Result<Vec<ContentChunk>> {
+            Ok(self
+                .0
+                .iter()
+                .map(|(start, end, title)| ContentChunk::new(Some(title.to_string()), String::new(), *start, *end, 0))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_repairs_sections() {
+        let segments = (0..41)
+            .map(|i| TranscriptSegment::new(i as f64 * 30.0, (i + 1) as f64 * 30.0, format!("s{}", i)))
+            .collect();
+        let transcript = Transcript::new("test".to_string(), segments);
+        let config = ChunkingConfig { target_duration: 180, min_duration: 60, max_duration: 500 };
+
+        let chunker = HybridChunker::new(Box::new(FixedChunker(vec![
+            (0.0, 240.0, "Intro"),
+            (200.0, 400.0, "Overlapping"), // overlaps Intro
+            // gap from 400 to 600
+            (600.0, 1200.0, "Long"),
+            (1200.0, 1230.0, "Tiny"), // under the minimum
+        ])));
+        let chunks = chunker.chunk(&transcript, &config).await.unwrap();
+
+        // Every segment appears exactly once, in order
+        let all: Vec<&str> = chunks.iter().flat_map(|c| c.content.split(' ')).collect();
+        let expected: Vec<String> = (0..41).map(|i| format!("s{}", i)).collect();
+        assert_eq!(all, expected);
+
+        assert_eq!(chunks[0].title.as_deref(), Some("Intro"));
+        assert_eq!(chunks[0].end_seconds, 240.0);
+        assert_eq!(chunks[1].title.as_deref(), Some("Overlapping"));
+        assert_eq!(chunks[1].start_seconds, 240.0);
+        assert!(chunks[2].title.is_none(), "gap gets an untitled chunk");
+        assert_eq!(chunks[3].title.as_deref(), Some("Long (1/3)"));
+        // "Tiny" is merged into the last part of "Long"
+        assert_eq!(chunks.len(), 6);
+        assert_eq!(chunks[5].end_seconds, 1230.0);
+        assert!(chunks.iter().all(|c| c.duration() <= 500.0));
+        assert!(chunks.windows(2).all(|w| w[0].end_seconds <= w[1].start_seconds));
+        assert_eq!(chunks.last().unwrap().order as usize, chunks.len() - 1);
+    }
+}
@@ -2,15 +2,26 @@
 //!
 //! Provides different strategies for splitting transcripts into meaningful chunks.
 
+mod chapters;
+mod external;
+mod hybrid;
 mod semantic;
+mod sliding;
 mod temporal;
+mod validate;
 
+pub use chapters::ChapterChunker;
+pub use external::ExternalChunker;
+pub use hybrid::HybridChunker;
 pub use semantic::SemanticChunker;
+pub use sliding::SlidingChunker;
 pub use temporal::TemporalChunker;
+pub use validate::{check_chunks, repair_chunks, validate_chunks, ChunkIssues, ValidatedChunker};
 
-use crate::config::Prompts;
-use crate::error::Result;
-use crate::transcription::Transcript;
+use crate::config::{ChunkingSettings, Prompts};
+use crate::llm::ChatClient;
+use crate::error::{LyttError, Result};
+use crate::transcription::{Transcript, TranscriptSegment};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +42,17 @@ pub struct ContentChunk {
     pub summary: Option<String>,
 }
 
+/// A section's title and summary followed by its content, the text embedded
+/// with `embedding.composite`.
+pub fn composite_text(title: Option<&str>, summary: Option<&str>, content: &str) -> String {
+    let header: Vec<&str> = [title, summary].into_iter().flatten().filter(|s| !s.trim().is_empty()).collect();
+    if header.is_empty() {
+        content.to_string()
+    } else {
+        format!("{}\n\n{}", header.join("\n"), content)
+    }
+}
+
 impl ContentChunk {
     /// Create a new content chunk.
     pub fn new(
@@ -50,6 +72,16 @@ impl ContentChunk {
         }
     }
 
+    /// Text to embed for this chunk: the content alone, or with `composite`
+    /// the section title and summary followed by the content.
+    pub fn embedding_text(&self, composite: bool) -> String {
+        if composite {
+            composite_text(self.title.as_deref(), self.summary.as_deref(), &self.content)
+        } else {
+            self.content.clone()
+        }
+    }
+
     /// Duration of this chunk in seconds.
     pub fn duration(&self) -> f64 {
         self.end_seconds - self.start_seconds
@@ -78,8 +110,14 @@ pub enum ChunkingStrategy {
     Semantic,
     /// Time-based chunking.
     Temporal,
-    /// Combination of both strategies.
+    /// Semantic chunking validated against the transcript, repaired with temporal splits.
     Hybrid,
+    /// External plugin command (`chunking.command`).
+    External,
+    /// Creator-defined chapters, semantic when there are none.
+    Chapters,
+    /// Fixed-size token windows with overlap.
+    Sliding,
 }
 
 impl std::str::FromStr for ChunkingStrategy {
@@ -90,13 +128,16 @@ impl std::str::FromStr for ChunkingStrategy {
             "semantic" => Ok(ChunkingStrategy::Semantic),
             "temporal" => Ok(ChunkingStrategy::Temporal),
             "hybrid" => Ok(ChunkingStrategy::Hybrid),
+            "external" => Ok(ChunkingStrategy::External),
+            "chapters" => Ok(ChunkingStrategy::Chapters),
+            "sliding" => Ok(ChunkingStrategy::Sliding),
             _ => Err(format!("Unknown chunking strategy: {}", s)),
         }
     }
 }
 
 /// Configuration for chunking.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ChunkingConfig {
     /// Target chunk duration in seconds.
     pub target_duration: u32,
@@ -123,26 +164,141 @@ pub trait Chunker: Send + Sync {
     async fn chunk(&self, transcript: &Transcript, config: &ChunkingConfig) -> Result<Vec<ContentChunk>>;
 }
 
+/// Build a chunk from consecutive transcript segments.
+fn chunk_from_segments(title: Option<String>, segments: Vec<&TranscriptSegment>) -> ContentChunk {
+    let content = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+    let start = segments.first().map_or(0.0, |s| s.start_seconds);
+    let end = segments.last().map_or(start, |s| s.end_seconds);
+    ContentChunk::new(title, content.trim().to_string(), start, end, 0)
+}
+
+/// Split segments into parts of about `target` seconds, of equal length.
+fn split_evenly<'a>(segments: &[&'a TranscriptSegment], target: f64) -> Vec<Vec<&'a TranscriptSegment>> {
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return Vec::new();
+    };
+    let start = first.start_seconds;
+    let count = ((last.end_seconds - start) / target.max(1.0)).round().max(1.0);
+    let part_len = (last.end_seconds - start) / count;
+
+    let mut parts: Vec<Vec<&TranscriptSegment>> = vec![Vec::new(); count as usize];
+    for &segment in segments {
+        let index = (((segment.start_seconds - start) / part_len) as usize).min(count as usize - 1);
+        parts[index].push(segment);
+    }
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
 /// Create a chunker based on the strategy.
+///
+/// External chunkers need a command, so `External` falls back to semantic
+/// here; use [`create_chunker_from_settings`] for them.
 pub fn create_chunker(strategy: ChunkingStrategy) -> Box<dyn Chunker> {
     match strategy {
-        ChunkingStrategy::Semantic => Box::new(SemanticChunker::new()),
+        ChunkingStrategy::Semantic | ChunkingStrategy::External => Box::new(SemanticChunker::new()),
         ChunkingStrategy::Temporal => Box::new(TemporalChunker::new()),
-        ChunkingStrategy::Hybrid => {
-            // For hybrid, we use semantic as the primary chunker
-            // with temporal as a fallback if semantic fails
-            Box::new(SemanticChunker::new())
-        }
+        ChunkingStrategy::Hybrid => Box::new(HybridChunker::new(Box::new(SemanticChunker::new()))),
+        ChunkingStrategy::Chapters => Box::new(ChapterChunker::new(Box::new(SemanticChunker::new()))),
+        ChunkingStrategy::Sliding => Box::new(SlidingChunker::default()),
     }
 }
 
 /// Create a chunker with custom prompts (including user-defined variables).
 pub fn create_chunker_with_prompts(strategy: ChunkingStrategy, prompts: Prompts) -> Box<dyn Chunker> {
     match strategy {
-        ChunkingStrategy::Semantic => Box::new(SemanticChunker::new().with_prompts(prompts)),
+        ChunkingStrategy::Semantic | ChunkingStrategy::External => {
+            Box::new(SemanticChunker::new().with_prompts(prompts))
+        }
         ChunkingStrategy::Temporal => Box::new(TemporalChunker::new()),
         ChunkingStrategy::Hybrid => {
-            Box::new(SemanticChunker::new().with_prompts(prompts))
+            Box::new(HybridChunker::new(Box::new(SemanticChunker::new().with_prompts(prompts))))
+        }
+        ChunkingStrategy::Chapters => {
+            Box::new(ChapterChunker::new(Box::new(SemanticChunker::new().with_prompts(prompts))))
         }
+        ChunkingStrategy::Sliding => Box::new(SlidingChunker::default()),
     }
 }
+
+/// Create a chunker from chunking settings (strategy, model, LLM backend or plugin command).
+pub fn create_chunker_from_settings(settings: &ChunkingSettings, prompts: Prompts) -> Result<Box<dyn Chunker>> {
+    let strategy: ChunkingStrategy = settings.strategy.parse().unwrap_or(ChunkingStrategy::Semantic);
+    let semantic = || {
+        SemanticChunker::with_model(&settings.model)
+            .with_prompts(prompts.clone())
+            .with_client(ChatClient::from_settings(&settings.llm))
+    };
+
+    let chunker: Box<dyn Chunker> = match strategy {
+        ChunkingStrategy::Temporal => Box::new(TemporalChunker::new()),
+        ChunkingStrategy::Sliding => Box::new(SlidingChunker::new(settings.chunk_tokens, settings.overlap_tokens)),
+        ChunkingStrategy::Semantic => Box::new(semantic()),
+        ChunkingStrategy::Hybrid => Box::new(HybridChunker::new(Box::new(semantic()))),
+        ChunkingStrategy::Chapters => {
+            let chunker = ChapterChunker::new(Box::new(semantic()));
+            if settings.refine_chapters {
+                Box::new(chunker.with_refiner(Box::new(semantic())))
+            } else {
+                Box::new(chunker)
+            }
+        }
+        ChunkingStrategy::External => {
+            let command = settings.command.as_deref().ok_or_else(|| {
+                LyttError::Config("chunking.strategy = \"external\" requires chunking.command".to_string())
+            })?;
+            Box::new(ExternalChunker::new(command, settings.args.clone()))
+        }
+    };
+
+    // Temporal chunks are valid by construction, sliding windows overlap on
+    // purpose and hybrid validates its own output
+    let validate = matches!(
+        strategy,
+        ChunkingStrategy::Semantic | ChunkingStrategy::Chapters | ChunkingStrategy::External
+    );
+    Ok(if settings.validate && validate { Box::new(ValidatedChunker::new(chunker)) } else { chunker })
+}
+
+/// Fingerprint of the settings and prompts that decide how transcripts are
+/// chunked, recorded per media item so `lytt rechunk --stale` can find media
+/// chunked under an older configuration.
+///
+/// Only what the configured strategy uses counts: changing the chunking
+/// prompts doesn't make temporally chunked media stale. The LLM endpoint and
+/// API key aren't part of it.
+pub fn chunking_fingerprint(settings: &ChunkingSettings, prompts: &Prompts) -> String {
+    use sha2::{Digest, Sha256};
+
+    let strategy: ChunkingStrategy = settings.strategy.parse().unwrap_or(ChunkingStrategy::Semantic);
+    let mut parts = vec![
+        format!("strategy={:?}", strategy),
+        format!(
+            "seconds={}/{}/{}",
+            settings.min_chunk_seconds, settings.target_chunk_seconds, settings.max_chunk_seconds
+        ),
+        format!("keywords={}", settings.keywords_per_chunk),
+        format!("validate={}", settings.validate),
+    ];
+    match strategy {
+        ChunkingStrategy::Temporal => {}
+        ChunkingStrategy::Sliding => parts.push(format!("tokens={}/{}", settings.chunk_tokens, settings.overlap_tokens)),
+        ChunkingStrategy::External => {
+            parts.push(format!("command={:?} {:?}", settings.command, settings.args));
+        }
+        ChunkingStrategy::Semantic | ChunkingStrategy::Hybrid | ChunkingStrategy::Chapters => {
+            let mut variables: Vec<_> = prompts.variables.iter().collect();
+            variables.sort();
+            parts.push(format!("model={}", settings.model));
+            if strategy == ChunkingStrategy::Chapters {
+                parts.push(format!("refine_chapters={}", settings.refine_chapters));
+            }
+            parts.push(format!("system={}", prompts.chunking.system));
+            parts.push(format!("user={}", prompts.chunking.user));
+            parts.push(format!("variables={:?}", variables));
+        }
+    }
+
+    let digest = Sha256::digest(parts.join("\n").as_bytes());
+    digest.iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
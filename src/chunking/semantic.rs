@@ -10,7 +10,8 @@ use async_openai::types::{
     ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
     ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
 };
-use crate::openai::create_client;
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,7 +19,7 @@ use tracing::{debug, info, warn};
 
 /// LLM-based semantic chunker.
 pub struct SemanticChunker {
-    client: async_openai::Client<async_openai::config::OpenAIConfig>,
+    client: ChatClient,
     model: String,
     prompts: Prompts,
 }
@@ -30,7 +31,7 @@ impl SemanticChunker {
 
     pub fn with_model(model: &str) -> Self {
         Self {
-            client: create_client(),
+            client: ChatClient::openai(),
             model: model.to_string(),
             prompts: Prompts::default(),
         }
@@ -42,6 +43,12 @@ impl SemanticChunker {
         self
     }
 
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Parse the LLM response into sections.
     fn parse_sections(response: &str) -> Result<Vec<LLMSection>> {
         // Try to extract JSON from the response
@@ -173,9 +180,9 @@ impl Chunker for SemanticChunker {
             .build()
             .map_err(|e| LyttError::Transcription(e.to_string()))?;
 
-        let response = self.client.chat().create(request).await.map_err(|e| {
-            LyttError::OpenAI(format!("Failed to get chunking response: {}", e))
-        })?;
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Chunking, &self.model, response.usage.as_ref());
 
         let content = response
             .choices
@@ -0,0 +1,111 @@
+//! Token-window chunking implementation.
+//!
+//! Packs transcript segments into chunks of about a fixed number of tokens,
+//! with consecutive chunks sharing some segments, like the text splitters
+//! of classic RAG pipelines. Needs no LLM calls.
+
+use super::{Chunker, ChunkingConfig, ContentChunk};
+use crate::error::Result;
+use crate::rag::count_tokens;
+use crate::transcription::Transcript;
+use async_trait::async_trait;
+
+/// Sliding-window chunker.
+///
+/// Segments are never split, so a chunk can run over `chunk_tokens` by up to
+/// one segment.
+pub struct SlidingChunker {
+    chunk_tokens: usize,
+    overlap_tokens: usize,
+}
+
+impl SlidingChunker {
+    /// Create a chunker with chunks of about `chunk_tokens` tokens, each
+    /// repeating at least `overlap_tokens` tokens of the previous one.
+    pub fn new(chunk_tokens: usize, overlap_tokens: usize) -> Self {
+        let chunk_tokens = chunk_tokens.max(1);
+        Self {
+            chunk_tokens,
+            overlap_tokens: overlap_tokens.min(chunk_tokens / 2),
+        }
+    }
+}
+
+impl Default for SlidingChunker {
+    fn default() -> Self {
+        Self::new(400, 80)
+    }
+}
+
+#[async_trait]
+impl Chunker for SlidingChunker {
+    async fn chunk(&self, transcript: &Transcript, _config: &ChunkingConfig) -> Result<Vec<ContentChunk>> {
+        let segments: Vec<_> = transcript.segments.iter().filter(|s| !s.text.trim().is_empty()).collect();
+        let tokens: Vec<usize> = segments.iter().map(|s| count_tokens(&s.text)).collect();
+        let mut chunks = Vec::new();
+
+        let mut start = 0;
+        while start < segments.len() {
+            // Grow the window until it holds enough tokens
+            let mut end = start;
+            let mut total = 0;
+            while end < segments.len() && (end == start || total < self.chunk_tokens) {
+                total += tokens[end];
+                end += 1;
+            }
+
+            let content = segments[start..end].iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" ");
+            chunks.push(ContentChunk::new(
+                None,
+                content,
+                segments[start].start_seconds,
+                segments[end - 1].end_seconds,
+                chunks.len() as i32,
+            ));
+            if end == segments.len() {
+                break;
+            }
+
+            // Step back over enough segments to cover the overlap, always moving forward
+            let mut next = end;
+            let mut overlap = 0;
+            while next > start + 1 && overlap < self.overlap_tokens {
+                next -= 1;
+                overlap += tokens[next];
+            }
+            start = next;
+        }
+
+        Ok(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptSegment;
+
+    #[tokio::test]
+    async fn test_sliding_chunking() {
+        let segments = (0..10)
+            .map(|i| TranscriptSegment::new(i as f64 * 10.0, (i + 1) as f64 * 10.0, format!("word{} ", i).repeat(10)))
+            .collect();
+        let transcript = Transcript::new("test".to_string(), segments);
+        let per_segment = count_tokens(&transcript.segments[0].text);
+
+        let chunker = SlidingChunker::new(per_segment * 4, per_segment);
+        let chunks = chunker.chunk(&transcript, &ChunkingConfig::default()).await.unwrap();
+
+        // Windows of 4 segments stepping by 3: 0-3, 3-6, 6-9
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].start_seconds, 0.0);
+        assert_eq!(chunks[0].end_seconds, 40.0);
+        assert_eq!(chunks[1].start_seconds, 30.0);
+        assert!(chunks[1].content.starts_with("word3"));
+        assert_eq!(chunks[2].end_seconds, 100.0);
+        assert_eq!(chunks[2].order, 2);
+
+        let chunks = SlidingChunker::new(1, 0).chunk(&transcript, &ChunkingConfig::default()).await.unwrap();
+        assert_eq!(chunks.len(), 10);
+    }
+}
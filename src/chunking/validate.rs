@@ -0,0 +1,237 @@
+//! Chunk boundary validation and repair.
+//!
+//! Chunk boundaries from an LLM or a plugin can overlap, leave parts of the
+//! transcript out, or ignore the duration bounds. [`validate_chunks`] checks
+//! them against the transcript and, if anything is wrong, logs the problems
+//! and rebuilds the chunks from the transcript segments.
+
+use super::{chunk_from_segments, split_evenly, Chunker, ChunkingConfig, ContentChunk};
+use crate::error::Result;
+use crate::transcription::{Transcript, TranscriptSegment};
+use async_trait::async_trait;
+use std::fmt;
+use tracing::warn;
+
+/// Problems found in a set of chunks.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChunkIssues {
+    /// Chunks that start before the previous chunk.
+    pub out_of_order: usize,
+    /// Chunks that start before the previous chunk ends.
+    pub overlapping: usize,
+    /// Chunks longer than the maximum duration.
+    pub too_long: usize,
+    /// Chunks shorter than the minimum duration (when there is more than one).
+    pub too_short: usize,
+    /// Transcript segments that fall in no chunk.
+    pub uncovered_segments: usize,
+}
+
+impl ChunkIssues {
+    /// Whether the chunks passed every check.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for ChunkIssues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = [
+            (self.out_of_order, "out of order"),
+            (self.overlapping, "overlapping"),
+            (self.too_long, "too long"),
+            (self.too_short, "too short"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, problem)| format!("{} {}", count, problem))
+        .chain((self.uncovered_segments > 0).then(|| format!("{} transcript segments not covered", self.uncovered_segments)))
+        .collect();
+        write!(f, "{}", problems.join(", "))
+    }
+}
+
+/// What a transcript segment was assigned to.
+#[derive(Clone, Copy, PartialEq)]
+enum Owner {
+    Section(usize),
+    Gap,
+}
+
+/// Assign each segment to the chunk its midpoint falls in.
+fn owners(chunks: &[ContentChunk], transcript: &Transcript) -> Vec<Owner> {
+    transcript
+        .segments
+        .iter()
+        .map(|s| {
+            let mid = (s.start_seconds + s.end_seconds) / 2.0;
+            chunks
+                .iter()
+                .position(|c| c.start_seconds < c.end_seconds && mid >= c.start_seconds && mid < c.end_seconds)
+                .map_or(Owner::Gap, Owner::Section)
+        })
+        .collect()
+}
+
+/// Check chunks against the transcript and the duration bounds.
+pub fn check_chunks(chunks: &[ContentChunk], transcript: &Transcript, config: &ChunkingConfig) -> ChunkIssues {
+    let mut issues = ChunkIssues::default();
+    for pair in chunks.windows(2) {
+        if pair[1].start_seconds < pair[0].start_seconds {
+            issues.out_of_order += 1;
+        } else if pair[1].start_seconds < pair[0].end_seconds {
+            issues.overlapping += 1;
+        }
+    }
+    issues.too_long = chunks.iter().filter(|c| c.duration() > config.max_duration as f64).count();
+    if chunks.len() > 1 {
+        issues.too_short = chunks.iter().filter(|c| c.duration() < config.min_duration as f64).count();
+    }
+    issues.uncovered_segments = owners(chunks, transcript)
+        .into_iter()
+        .zip(&transcript.segments)
+        .filter(|(owner, segment)| *owner == Owner::Gap && !segment.text.trim().is_empty())
+        .count();
+    issues
+}
+
+/// Check chunks and repair them if anything is wrong. Valid chunks are
+/// returned unchanged.
+pub fn validate_chunks(chunks: Vec<ContentChunk>, transcript: &Transcript, config: &ChunkingConfig) -> Vec<ContentChunk> {
+    let issues = check_chunks(&chunks, transcript, config);
+    if issues.is_empty() {
+        return chunks;
+    }
+    warn!("Repairing chunk boundaries for {}: {}", transcript.video_id, issues);
+    repair_chunks(chunks, transcript, config)
+}
+
+/// Turn proposed sections into chunks that cover every transcript segment
+/// exactly once and respect the configured duration bounds.
+pub fn repair_chunks(mut sections: Vec<ContentChunk>, transcript: &Transcript, config: &ChunkingConfig) -> Vec<ContentChunk> {
+    // Trim overlaps: a section starts no earlier than the previous one ends
+    sections.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+    for i in 1..sections.len() {
+        let previous_end = sections[i - 1].end_seconds;
+        if sections[i].start_seconds < previous_end {
+            sections[i].start_seconds = previous_end;
+        }
+    }
+
+    // Consecutive runs of segments with the same owner
+    let mut runs: Vec<(Owner, Vec<&TranscriptSegment>)> = Vec::new();
+    for (segment, owner) in transcript.segments.iter().zip(owners(&sections, transcript)) {
+        match runs.last_mut() {
+            Some((last, segments)) if *last == owner => segments.push(segment),
+            _ => runs.push((owner, vec![segment])),
+        }
+    }
+
+    let max = config.max_duration as f64;
+    let mut chunks: Vec<ContentChunk> = Vec::new();
+    for (owner, segments) in runs {
+        let (title, summary) = match owner {
+            Owner::Section(i) => (sections[i].title.clone(), sections[i].summary.clone()),
+            Owner::Gap => (None, None),
+        };
+        let duration = segments.last().map_or(0.0, |s| s.end_seconds) - segments.first().map_or(0.0, |s| s.start_seconds);
+
+        // Too long (or an uncovered stretch): split temporally
+        let parts = if duration > max || owner == Owner::Gap {
+            split_evenly(&segments, config.target_duration as f64)
+        } else {
+            vec![segments]
+        };
+        let count = parts.len();
+        for (i, part) in parts.into_iter().enumerate() {
+            let title = match &title {
+                Some(title) if count > 1 => Some(format!("{} ({}/{})", title, i + 1, count)),
+                other => other.clone(),
+            };
+            let mut chunk = chunk_from_segments(title, part);
+            chunk.summary = summary.clone();
+            push_or_merge(&mut chunks, chunk, config);
+        }
+    }
+
+    for (order, chunk) in chunks.iter_mut().enumerate() {
+        chunk.order = order as i32;
+    }
+    chunks
+}
+
+/// Add a chunk, merging it into the previous one if either is too short and
+/// the result stays within the maximum duration.
+fn push_or_merge(chunks: &mut Vec<ContentChunk>, chunk: ContentChunk, config: &ChunkingConfig) {
+    let min = config.min_duration as f64;
+    if let Some(last) = chunks.last_mut() {
+        let too_short = chunk.duration() < min || last.duration() < min;
+        if too_short && chunk.end_seconds - last.start_seconds <= config.max_duration as f64 {
+            last.content.push(' ');
+            last.content.push_str(&chunk.content);
+            last.end_seconds = chunk.end_seconds;
+            if last.title.is_none() {
+                last.title = chunk.title;
+            }
+            return;
+        }
+    }
+    chunks.push(chunk);
+}
+
+/// Wraps a chunker and validates its output.
+pub struct ValidatedChunker {
+    inner: Box<dyn Chunker>,
+}
+
+impl ValidatedChunker {
+    /// Validate the chunks `inner` produces.
+    pub fn new(inner: Box<dyn Chunker>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Chunker for ValidatedChunker {
+    async fn chunk(&self, transcript: &Transcript, config: &ChunkingConfig) -> Result<Vec<ContentChunk>> {
+        let chunks = self.inner.chunk(transcript, config).await?;
+        Ok(validate_chunks(chunks, transcript, config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript() -> Transcript {
+        let segments = (0..10)
+            .map(|i| TranscriptSegment::new(i as f64 * 30.0, (i + 1) as f64 * 30.0, format!("s{}", i)))
+            .collect();
+        Transcript::new("test".to_string(), segments)
+    }
+
+    fn chunk(start: f64, end: f64) -> ContentChunk {
+        ContentChunk::new(Some("Section".to_string()), "custom".to_string(), start, end, 0)
+    }
+
+    #[test]
+    fn test_check_and_validate_chunks() {
+        let config = ChunkingConfig { target_duration: 120, min_duration: 60, max_duration: 200 };
+
+        let valid = vec![chunk(0.0, 150.0), chunk(150.0, 300.0)];
+        assert!(check_chunks(&valid, &transcript(), &config).is_empty());
+        // Valid chunks keep their content
+        assert_eq!(validate_chunks(valid, &transcript(), &config)[0].content, "custom");
+
+        let broken = vec![chunk(0.0, 100.0), chunk(90.0, 150.0), chunk(180.0, 300.0)];
+        let issues = check_chunks(&broken, &transcript(), &config);
+        assert_eq!(issues.overlapping, 1);
+        assert_eq!(issues.uncovered_segments, 1);
+        assert_eq!(issues.to_string(), "1 overlapping, 1 transcript segments not covered");
+
+        let repaired = validate_chunks(broken, &transcript(), &config);
+        assert!(check_chunks(&repaired, &transcript(), &config).is_empty(), "{:?}", repaired);
+        let text: Vec<&str> = repaired.iter().flat_map(|c| c.content.split(' ')).collect();
+        assert_eq!(text, ["s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9"]);
+    }
+}
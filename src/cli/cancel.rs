@@ -0,0 +1,22 @@
+//! Ctrl+C handling.
+
+use crate::cli::Output;
+use tokio_util::sync::CancellationToken;
+
+/// A token cancelled by the first Ctrl+C, so the run in progress can stop
+/// and clean up. A second Ctrl+C exits straight away.
+pub fn ctrl_c_token() -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        Output::warning("Cancelling... (press Ctrl+C again to quit immediately)");
+        cancel.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    });
+    token
+}
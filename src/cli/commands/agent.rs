@@ -3,11 +3,11 @@
 use crate::cli::preflight::{self, Operation};
 use crate::cli::Output;
 use crate::config::Settings;
-use crate::embedding::OpenAIEmbedder;
+use crate::llm::ChatClient;
 use crate::orchestrator::Orchestrator;
 use crate::agent::{Agent, ToolContext};
+use crate::usage::UsageTracker;
 use anyhow::Result;
-use std::sync::Arc;
 
 /// Run the agent command.
 pub async fn run_agent(
@@ -17,7 +17,7 @@ pub async fn run_agent(
     settings: Settings,
 ) -> Result<()> {
     // Pre-flight checks
-    if let Err(e) = preflight::check(Operation::Ask) {
+    if let Err(e) = preflight::check(Operation::Ask, &settings) {
         Output::error(&format!("{}", e));
         Output::info("Run 'lytt doctor' for detailed diagnostics.");
         return Err(e.into());
@@ -26,23 +26,24 @@ pub async fn run_agent(
     let orchestrator = Orchestrator::new(settings.clone())?;
     let model = model.unwrap_or_else(|| settings.rag.model.clone());
 
-    let embedder = Arc::new(OpenAIEmbedder::with_config(
-        &settings.embedding.model,
-        settings.embedding.dimensions as usize,
-    ));
+    let embedder = orchestrator.embedder();
 
     // Build context if video_id is provided
     let context = video_id.as_ref().map(|id| format!("Focus on video ID: {}", id));
 
     // Create tool context
-    let tool_context = ToolContext::new(orchestrator.vector_store(), embedder);
+    let tool_context = ToolContext::new(orchestrator.vector_store(), embedder).with_web(&settings.agent.web)?;
 
     // Create and run agent
-    let agent = Agent::new(tool_context, &model);
+    let agent = Agent::new(tool_context, &model).with_client(ChatClient::from_settings(&settings.rag.llm));
 
     let spinner = Output::spinner("Agent working...");
 
-    match agent.run(task, context.as_deref()).await {
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(agent.run(task, context.as_deref())).await;
+    orchestrator.record_usage("agent", video_id.as_deref(), &tracker).await;
+
+    match result {
         Ok(response) => {
             spinner.finish_and_clear();
 
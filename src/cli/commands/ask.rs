@@ -3,21 +3,77 @@
 use crate::cli::preflight::{self, Operation};
 use crate::cli::Output;
 use crate::config::Settings;
-use crate::embedding::OpenAIEmbedder;
+use crate::consensus::VideoComparator;
+use crate::error::LyttError;
+use crate::llm::ChatClient;
 use crate::orchestrator::Orchestrator;
-use crate::rag::RagEngine;
+use crate::rag::{AnswerVerifier, ConfidenceScale, QueryExpander, RagEngine, RagResponse, RecencyRanking};
+use crate::tts::Speaker;
+use crate::usage::UsageTracker;
+use crate::vector_store::{DerivedFilter, MemoryVectorStore, SearchFilter, VectorStore};
 use anyhow::Result;
+use chrono::Utc;
+use std::path::Path;
 use std::sync::Arc;
 
 /// Run the ask command.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_ask(
     question: &str,
     model: Option<String>,
     max_chunks: usize,
-    settings: Settings,
+    derived: &str,
+    since: Option<&str>,
+    video: Option<&str>,
+    series: Option<&str>,
+    compare: bool,
+    videos: &[String],
+    attach: Option<&str>,
+    save: bool,
+    no_cache: bool,
+    multi_query: bool,
+    recent: bool,
+    verify: bool,
+    speak: bool,
+    speak_to: Option<&str>,
+    mut settings: Settings,
 ) -> Result<()> {
+    if multi_query {
+        settings.rag.multi_query.enabled = true;
+    }
+    if recent {
+        settings.rag.recency.enabled = true;
+    }
+    if verify {
+        settings.rag.verify = true;
+    }
+
+    let derived: DerivedFilter = derived.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+    let mut filter = SearchFilter::default().with_derived(derived);
+    if let Some(window) = since {
+        filter = filter.with_since_window(window, Utc::now()).map_err(|e| {
+            Output::error(&e);
+            LyttError::InvalidInput(e)
+        })?;
+    }
+    if let Some(video_id) = video {
+        filter = filter.with_video(video_id);
+    }
+    if let Some(series) = series {
+        filter = filter.with_series(series);
+    }
+
+    let speaker = if speak || speak_to.is_some() { Some(Speaker::from_settings(&settings.tts)?) } else { None };
+
     // Pre-flight checks
-    if let Err(e) = preflight::check(Operation::Ask) {
+    let checks = preflight::check(Operation::Ask, &settings).and_then(|()| match attach {
+        Some(_) => preflight::check(Operation::Transcribe, &settings),
+        None => Ok(()),
+    });
+    if let Err(e) = checks {
         Output::error(&format!("{}", e));
         Output::info("Run 'lytt doctor' for detailed diagnostics.");
         return Err(e.into());
@@ -27,45 +83,236 @@ pub async fn run_ask(
 
     let model = model.unwrap_or_else(|| settings.rag.model.clone());
 
-    let embedder = Arc::new(OpenAIEmbedder::with_config(
-        &settings.embedding.model,
-        settings.embedding.dimensions as usize,
-    ));
+    if compare {
+        return run_compare(&orchestrator, question, videos, &model, &settings).await;
+    }
+
+    if let Some(input) = attach {
+        let result = ask_attached(&orchestrator, input, save, question, &model, max_chunks, &settings).await;
+        let response = print_response(result)?;
+        return speak_answer(speaker.as_ref(), &response, speak_to).await;
+    }
+
+    if let Some(video_id) = video {
+        if !orchestrator.vector_store().is_video_indexed(video_id).await? {
+            Output::error(&format!("No indexed media with ID: {}", video_id));
+            return Err(LyttError::VideoNotFound(video_id.to_string()).into());
+        }
+    }
+    if let Some(series) = series {
+        if !orchestrator.sqlite_store().has_series(series)? {
+            Output::error(&format!("No series named '{}'. See 'lytt series ls'.", series));
+            return Err(LyttError::InvalidInput(format!("no series named {}", series)).into());
+        }
+    }
+
+    if let Err(e) = orchestrator.check_embedding().await {
+        Output::error(&e.to_string());
+        return Err(e.into());
+    }
+
+    let embedder = orchestrator.embedder();
 
     let engine = RagEngine::new(
         orchestrator.vector_store(),
         embedder,
         &model,
         max_chunks,
-    );
+    )
+    .with_client(ChatClient::from_settings(&settings.rag.llm))
+    .with_filter(filter)
+    .with_token_budget(settings.rag.context_budget())
+    .with_neighbor_window(settings.rag.neighbor_window)
+    .with_min_confidence(settings.rag.min_confidence)
+    .with_confidence_scale(ConfidenceScale::from_settings(&settings.rag))
+    .with_expansion(QueryExpander::from_settings(&settings.rag, &model, orchestrator.prompts()))
+    .with_recency(RecencyRanking::from_settings(&settings.rag))
+    .with_diversity(settings.rag.diversity)
+    .with_routing(settings.rag.routing)
+    .with_verifier(AnswerVerifier::from_settings(&settings.rag, &model, orchestrator.prompts()))
+    .with_cache(if no_cache { None } else { settings.rag.cache.ttl() });
 
     let spinner = Output::spinner("Searching knowledge base...");
 
-    match engine.ask(question).await {
-        Ok(response) => {
-            spinner.finish_and_clear();
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(engine.ask(question)).await;
+    orchestrator.record_usage("ask", None, &tracker).await;
+    spinner.finish_and_clear();
+
+    let response = print_response(result.map_err(Into::into))?;
+    speak_answer(speaker.as_ref(), &response, speak_to).await
+}
+
+/// Compare how the media in `videos` answer `question`.
+async fn run_compare(
+    orchestrator: &Orchestrator,
+    question: &str,
+    videos: &[String],
+    model: &str,
+    settings: &Settings,
+) -> Result<()> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in videos.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        if !ids.iter().any(|i| i == id) {
+            ids.push(id.to_string());
+        }
+    }
+    if ids.len() < 2 {
+        Output::error("--compare needs at least two media IDs in --videos");
+        return Err(LyttError::InvalidInput("--compare needs at least two media IDs".to_string()).into());
+    }
+    if let Err(e) = orchestrator.check_embedding().await {
+        Output::error(&e.to_string());
+        return Err(e.into());
+    }
 
+    let comparator = VideoComparator::new(orchestrator.vector_store(), orchestrator.embedder(), model)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.rag.llm));
+
+    let spinner = Output::spinner("Comparing media...");
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(comparator.compare(question, &ids)).await;
+    orchestrator.record_usage("ask", None, &tracker).await;
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(comparison) => {
+            println!("{}", comparison.to_markdown());
+            Ok(())
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to compare: {}", e));
+            Err(e.into())
+        }
+    }
+}
+
+/// Read the answer aloud, or save the speech to `save_to`.
+async fn speak_answer(speaker: Option<&Speaker>, response: &RagResponse, save_to: Option<&str>) -> Result<()> {
+    let Some(speaker) = speaker else {
+        return Ok(());
+    };
+    let spinner = Output::spinner("Speaking...");
+    let spoken = speaker.speak(&response.answer, save_to.map(Path::new)).await;
+    spinner.finish_and_clear();
+    spoken?;
+    if let Some(path) = save_to {
+        Output::success(&format!("Saved the spoken answer to {}", path));
+    }
+    Ok(())
+}
+
+/// Answer a question from one media item. Unless `save` is set, the media is
+/// transcribed into a temporary in-memory store that is dropped afterwards.
+async fn ask_attached(
+    orchestrator: &Orchestrator,
+    input: &str,
+    save: bool,
+    question: &str,
+    model: &str,
+    max_chunks: usize,
+    settings: &Settings,
+) -> Result<RagResponse> {
+    let documents = if save {
+        Output::info("Transcribing and adding to the library...");
+        let result = orchestrator.process_media(input, false).await?;
+        orchestrator.vector_store().get_by_video_id(&result.media_id).await?
+    } else {
+        let spinner = Output::spinner("Transcribing attachment (not saved)...");
+        let tracker = UsageTracker::new();
+        let documents = tracker.scope(orchestrator.transcribe_ephemeral(input)).await;
+        orchestrator.record_usage("ask", None, &tracker).await;
+        spinner.finish_and_clear();
+        documents?
+    };
+
+    if documents.is_empty() {
+        anyhow::bail!("No speech found in {}", input);
+    }
+    let video_id = documents[0].video_id.clone();
+
+    let store = MemoryVectorStore::new();
+    store.upsert_batch(&documents).await?;
+
+    // Everything in the attachment is in scope, so don't drop weak matches
+    let engine = RagEngine::new(Arc::new(store), orchestrator.embedder(), model, max_chunks)
+        .with_client(ChatClient::from_settings(&settings.rag.llm))
+        .with_token_budget(settings.rag.context_budget())
+        .with_neighbor_window(settings.rag.neighbor_window)
+        .with_min_confidence(settings.rag.min_confidence)
+        .with_confidence_scale(ConfidenceScale::from_settings(&settings.rag))
+        .with_expansion(QueryExpander::from_settings(&settings.rag, model, orchestrator.prompts()))
+        .with_diversity(settings.rag.diversity)
+        .with_verifier(AnswerVerifier::from_settings(&settings.rag, model, orchestrator.prompts()))
+        .with_min_score(0.0);
+
+    let spinner = Output::spinner("Answering from attachment...");
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(engine.ask(question)).await;
+    orchestrator.record_usage("ask", Some(&video_id), &tracker).await;
+    spinner.finish_and_clear();
+
+    Ok(result?)
+}
+
+/// Print an answer and its sources.
+fn print_response(result: Result<RagResponse>) -> Result<RagResponse> {
+    match result {
+        Ok(response) => {
             println!("\n{}\n", response.answer);
+            if response.cached {
+                Output::info("Cached answer (use --no-cache for a fresh one)");
+            }
+            if let Some(confidence) = &response.confidence {
+                let percent = format!("{:.0}%", confidence.score * 100.0);
+                if response.abstained {
+                    Output::warning(&format!(
+                        "Confidence {} is below rag.min_confidence; the closest excerpts are listed below",
+                        percent
+                    ));
+                } else {
+                    Output::kv("Confidence", &percent);
+                }
+            }
+
+            if let Some(verification) = &response.verification {
+                let flagged: Vec<_> = verification.flagged().collect();
+                if flagged.is_empty() {
+                    Output::success(&format!("All {} claims are supported by the sources", verification.claims.len()));
+                } else {
+                    Output::warning(&format!(
+                        "{} of {} claims are not supported by the sources:",
+                        flagged.len(),
+                        verification.claims.len()
+                    ));
+                    for check in flagged {
+                        Output::list_item(&format!("({}) {}", check.verdict, check.claim));
+                        if !check.explanation.is_empty() {
+                            Output::kv("why", &check.explanation);
+                        }
+                    }
+                }
+            }
 
             if !response.sources.is_empty() {
                 Output::header("Sources");
-                for source in &response.sources {
+                for (i, source) in response.sources.iter().enumerate() {
                     Output::search_result(
-                        &source.video_title,
+                        &format!("[{}] {}", i + 1, source.source_label()),
                         &source.timestamp,
                         source.score,
+                        None,
                         &source.content[..source.content.len().min(100)],
                         source.url.as_deref(),
                     );
                 }
             }
+            Ok(response)
         }
         Err(e) => {
-            spinner.finish_and_clear();
             Output::error(&format!("Failed to generate answer: {}", e));
-            return Err(e.into());
+            Err(e)
         }
     }
-
-    Ok(())
 }
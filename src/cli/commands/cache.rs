@@ -0,0 +1,59 @@
+//! Audio cache command implementation.
+
+use super::doctor::format_size;
+use crate::audio::AudioCache;
+use crate::cli::{CacheAction, Output};
+use crate::config::Settings;
+use anyhow::Result;
+use chrono::Local;
+
+/// Run the cache command.
+pub fn run_cache(action: &CacheAction, settings: &Settings) -> Result<()> {
+    let cache = AudioCache::from_settings(settings);
+    match action {
+        CacheAction::Ls => list(&cache),
+        CacheAction::Clear { video_id } => clear(&cache, video_id.as_deref()),
+    }
+}
+
+fn list(cache: &AudioCache) -> Result<()> {
+    if !cache.is_enabled() {
+        Output::info("The audio cache is disabled (general.audio_cache_max_mb = 0).");
+    }
+    let entries = cache.entries();
+    if entries.is_empty() {
+        Output::info(&format!("No audio cached in {}.", cache.dir().display()));
+        return Ok(());
+    }
+
+    Output::header(&format!("Audio cache ({})", cache.dir().display()));
+    println!();
+    for entry in &entries {
+        println!(
+            "  {:<24} {:>10}  last used {}",
+            entry.video_id,
+            format_size(entry.bytes),
+            entry.last_used.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        );
+    }
+    println!();
+    Output::kv("Total", &format!("{} in {} files", format_size(cache.size()), entries.len()));
+    Ok(())
+}
+
+fn clear(cache: &AudioCache, video_id: Option<&str>) -> Result<()> {
+    match video_id {
+        Some(video_id) => {
+            if cache.remove(video_id)? {
+                Output::success(&format!("Removed the cached audio of {}", video_id));
+            } else {
+                Output::info(&format!("No audio cached for {}.", video_id));
+            }
+        }
+        None => {
+            let (files, bytes) = cache.clear()?;
+            Output::success(&format!("Removed {} cached files ({})", files, format_size(bytes)));
+        }
+    }
+    Ok(())
+}
@@ -1,10 +1,9 @@
 //! Interactive chat command with tool calling support.
 
-use crate::agent::{parse_tool_call, tool_definitions, ToolContext};
+use crate::agent::{parse_tool_call, ToolContext};
 use crate::cli::preflight::{self, Operation};
 use crate::cli::Output;
 use crate::config::Settings;
-use crate::embedding::OpenAIEmbedder;
 use crate::error::{LyttError, Result};
 use crate::orchestrator::Orchestrator;
 use async_openai::types::{
@@ -12,10 +11,11 @@ use async_openai::types::{
     ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestToolMessageArgs,
     ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
 };
-use crate::openai::create_client;
+use crate::llm::ChatClient;
+use crate::tts::Speaker;
+use crate::usage::{self, UsageStage, UsageTracker};
 use console::style;
 use std::io::{self, BufRead, Write};
-use std::sync::Arc;
 use tracing::{debug, info};
 
 /// System prompt for the chat agent.
@@ -34,25 +34,30 @@ Always cite your sources with titles and timestamps when relevant.
 Be conversational and helpful. Remember context from earlier in the conversation."#;
 
 /// Run the interactive chat command.
-pub async fn run_chat(model: Option<String>, settings: Settings) -> Result<()> {
+pub async fn run_chat(model: Option<String>, speak: bool, settings: Settings) -> Result<()> {
     // Pre-flight checks
-    if let Err(e) = preflight::check(Operation::Ask) {
+    if let Err(e) = preflight::check(Operation::Ask, &settings) {
         Output::error(&format!("{}", e));
         Output::info("Run 'lytt doctor' for detailed diagnostics.");
-        return Err(e.into());
+        return Err(e);
     }
 
+    let speaker = if speak { Some(Speaker::from_settings(&settings.tts)?) } else { None };
+
     let orchestrator = Orchestrator::new(settings.clone())?;
     let model = model.unwrap_or_else(|| settings.rag.model.clone());
 
-    let embedder = Arc::new(OpenAIEmbedder::with_config(
-        &settings.embedding.model,
-        settings.embedding.dimensions as usize,
-    ));
+    if let Err(e) = orchestrator.check_embedding().await {
+        Output::error(&e.to_string());
+        return Err(e);
+    }
+
+    let embedder = orchestrator.embedder();
 
-    let tool_context = ToolContext::new(orchestrator.vector_store(), embedder);
+    let tool_context = ToolContext::new(orchestrator.vector_store(), embedder).with_web(&settings.agent.web)?;
 
-    let mut chat = ChatSession::new(tool_context, &model);
+    let client = ChatClient::from_settings(&settings.rag.llm);
+    let mut chat = ChatSession::new(client, tool_context, &model);
 
     println!("\n{}", style("Lytt Chat").bold().cyan());
     println!(
@@ -87,9 +92,18 @@ pub async fn run_chat(model: Option<String>, settings: Settings) -> Result<()> {
             continue;
         }
 
-        match chat.send_message(input).await {
+        let tracker = UsageTracker::new();
+        let result = tracker.scope(chat.send_message(input)).await;
+        orchestrator.record_usage("chat", None, &tracker).await;
+
+        match result {
             Ok(response) => {
                 println!("\n{} {}\n", style("Lytt:").cyan().bold(), response);
+                if let Some(speaker) = &speaker {
+                    if let Err(e) = speaker.speak(&response, None).await {
+                        Output::warning(&format!("Couldn't read the answer aloud: {}", e));
+                    }
+                }
             }
             Err(e) => {
                 Output::error(&format!("Error: {}", e));
@@ -102,7 +116,7 @@ pub async fn run_chat(model: Option<String>, settings: Settings) -> Result<()> {
 
 /// Interactive chat session with tool calling support.
 struct ChatSession {
-    client: async_openai::Client<async_openai::config::OpenAIConfig>,
+    client: ChatClient,
     model: String,
     tools: ToolContext,
     messages: Vec<ChatCompletionRequestMessage>,
@@ -111,14 +125,14 @@ struct ChatSession {
 
 impl ChatSession {
     /// Create a new chat session.
-    fn new(tools: ToolContext, model: &str) -> Self {
+    fn new(client: ChatClient, tools: ToolContext, model: &str) -> Self {
         let system_message = ChatCompletionRequestSystemMessageArgs::default()
             .content(CHAT_SYSTEM_PROMPT)
             .build()
             .expect("Failed to build system message");
 
         Self {
-            client: create_client(),
+            client,
             model: model.to_string(),
             tools,
             messages: vec![system_message.into()],
@@ -145,7 +159,7 @@ impl ChatSession {
         loop {
             iterations += 1;
             if iterations > self.max_tool_iterations {
-                return Err(LyttError::Agent("Too many tool iterations".to_string()).into());
+                return Err(LyttError::Agent("Too many tool iterations".to_string()));
             }
 
             debug!("Chat iteration {}, {} messages", iterations, self.messages.len());
@@ -154,16 +168,13 @@ impl ChatSession {
             let request = CreateChatCompletionRequestArgs::default()
                 .model(&self.model)
                 .messages(self.messages.clone())
-                .tools(tool_definitions())
+                .tools(self.tools.definitions())
                 .build()
                 .map_err(|e| LyttError::Agent(e.to_string()))?;
 
-            let response = self
-                .client
-                .chat()
-                .create(request)
-                .await
-                .map_err(|e| LyttError::OpenAI(format!("Chat API error: {}", e)))?;
+            let response = self.client.create(request).await?;
+
+            usage::record_chat(UsageStage::Agent, &self.model, response.usage.as_ref());
 
             let choice = response
                 .choices
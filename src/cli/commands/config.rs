@@ -8,6 +8,9 @@ use anyhow::Result;
 pub fn run_config(action: &ConfigAction, settings: Settings) -> Result<()> {
     match action {
         ConfigAction::Show => {
+            if let Some(profile) = &settings.active_profile {
+                println!("# profile: {}\n", profile);
+            }
             let toml_str = toml::to_string_pretty(&settings)
                 .map_err(|e| anyhow::anyhow!("Failed to serialize config: {}", e))?;
             println!("{}", toml_str);
@@ -55,6 +58,25 @@ pub fn run_config(action: &ConfigAction, settings: Settings) -> Result<()> {
             let config_path = Settings::default_config_path();
             println!("{}", config_path.display());
         }
+
+        ConfigAction::Profiles => {
+            if settings.profiles.is_empty() {
+                Output::info("No profiles configured. Add a [profiles.<name>] section to your config.");
+                return Ok(());
+            }
+
+            let mut names: Vec<&String> = settings.profiles.keys().collect();
+            names.sort();
+
+            Output::header("Profiles");
+            for name in names {
+                if settings.active_profile.as_ref() == Some(name) {
+                    Output::list_item(&format!("{} (active)", name));
+                } else {
+                    Output::list_item(name);
+                }
+            }
+        }
     }
 
     Ok(())
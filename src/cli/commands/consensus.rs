@@ -0,0 +1,81 @@
+//! Consensus command implementation.
+
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::consensus::ConsensusAnalyzer;
+use crate::error::LyttError;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::usage::UsageTracker;
+use crate::vector_store::SearchFilter;
+use anyhow::Result;
+use chrono::Utc;
+
+/// Run the consensus command.
+pub async fn run_consensus(
+    topic: &str,
+    since: Option<&str>,
+    limit: usize,
+    format: &str,
+    model: Option<String>,
+    settings: Settings,
+) -> Result<()> {
+    let json = match format.to_lowercase().as_str() {
+        "markdown" | "md" => false,
+        "json" => true,
+        _ => {
+            let e = format!("Unknown report format: {}. Use markdown or json.", format);
+            Output::error(&e);
+            return Err(LyttError::InvalidInput(e).into());
+        }
+    };
+
+    let mut filter = SearchFilter::default();
+    if let Some(window) = since {
+        filter = filter.with_since_window(window, Utc::now()).map_err(|e| {
+            Output::error(&e);
+            LyttError::InvalidInput(e)
+        })?;
+    }
+
+    // Pre-flight checks
+    if let Err(e) = preflight::check(Operation::Ask, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let model = model.unwrap_or_else(|| settings.rag.model.clone());
+
+    let analyzer = ConsensusAnalyzer::new(orchestrator.vector_store(), orchestrator.embedder(), &model)
+        .with_max_excerpts(limit)
+        .with_filter(filter)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.rag.llm));
+
+    let spinner = Output::spinner("Comparing sources...");
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(analyzer.analyze(topic)).await;
+    orchestrator.record_usage("consensus", None, &tracker).await;
+
+    spinner.finish_and_clear();
+
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            Output::error(&format!("Failed to analyse sources: {}", e));
+            return Err(e.into());
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report.to_markdown());
+    }
+
+    Ok(())
+}
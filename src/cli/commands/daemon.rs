@@ -0,0 +1,43 @@
+//! Daemon command implementation.
+
+use super::serve::serve;
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::daemon::{Daemon, JobKind};
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Run the API server together with the scheduled jobs in `[daemon]`.
+pub async fn run_daemon(host: &str, port: u16, grpc_port: Option<u16>, settings: Settings) -> Result<()> {
+    if settings.vector_store.read_only {
+        let e = "The daemon ingests media and can't run on a read-only database. Use 'lytt serve' for replicas.";
+        Output::error(e);
+        return Err(LyttError::Config(e.to_string()).into());
+    }
+
+    let orchestrator = Arc::new(Orchestrator::new(settings.clone())?);
+    let daemon = Arc::new(Daemon::new(orchestrator.clone()).inspect_err(|e| Output::error(&e.to_string()))?);
+
+    // Pre-flight checks for the jobs that will run
+    let mut operations = Vec::new();
+    for (job, _) in daemon.jobs() {
+        match job {
+            JobKind::Watch | JobKind::Subscriptions => operations.push(Operation::Transcribe),
+            JobKind::Digest => operations.push(Operation::Digest),
+        }
+    }
+    for operation in operations {
+        if let Err(e) = preflight::check(operation, &settings) {
+            Output::error(&format!("{}", e));
+            Output::info("Run 'lytt doctor' for detailed diagnostics.");
+            return Err(e.into());
+        }
+    }
+
+    orchestrator.spawn_checkpoints();
+    daemon.spawn();
+    serve(host, port, grpc_port, orchestrator, Some(daemon)).await
+}
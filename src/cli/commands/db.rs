@@ -0,0 +1,225 @@
+//! Database maintenance command implementation.
+
+use super::doctor::format_size;
+use crate::audio_source::{content_hash, LocalSource, SourceType};
+use crate::cli::{DbAction, Output};
+use crate::config::Settings;
+use crate::vector_store::{DatabaseSize, SqliteVectorStore, VectorStore};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Run the db command.
+pub async fn run_db(action: &DbAction, settings: Settings) -> Result<()> {
+    match action {
+        DbAction::Maintain { no_vacuum, check } => maintain(!*no_vacuum, *check, &settings),
+        DbAction::MigrateIds { search, dry_run } => migrate_ids(search, *dry_run, &settings).await,
+    }
+}
+
+/// Checkpoint, vacuum, analyze and check the database, then report its size.
+fn maintain(vacuum: bool, check: bool, settings: &Settings) -> Result<()> {
+    let read_only = settings.vector_store.read_only;
+    let store = if read_only {
+        SqliteVectorStore::open_read_only(&settings.sqlite_path())?
+    } else {
+        SqliteVectorStore::new(&settings.sqlite_path())?
+    };
+
+    Output::header("Database Maintenance");
+    println!();
+    Output::kv("Database", &settings.sqlite_path().display().to_string());
+    Output::kv("Schema version", &store.schema_version()?.to_string());
+    let before = store.database_size()?;
+    print_size("Size", &before);
+
+    if read_only && !check {
+        Output::info("The database is read-only; only checking it.");
+    }
+    if !read_only && !check {
+        if vacuum {
+            let spinner = Output::spinner("Vacuuming...");
+            let result = store.vacuum();
+            spinner.finish_and_clear();
+            result?;
+            let after = store.database_size()?;
+            Output::success(&format!(
+                "Vacuumed: {} reclaimed",
+                format_size(before.file_bytes.saturating_sub(after.file_bytes))
+            ));
+        }
+
+        store.analyze()?;
+        Output::success("Updated query planner statistics");
+
+        // Last, so the WAL written by the steps above is emptied too
+        let checkpoint = store.checkpoint(true)?;
+        if checkpoint.busy {
+            Output::warning("WAL checkpoint incomplete: the database is in use by another process.");
+        } else {
+            Output::success(&format!("Checkpointed {} WAL frames", checkpoint.checkpointed));
+        }
+    }
+
+    let spinner = Output::spinner("Checking integrity...");
+    let problems = store.integrity_check();
+    spinner.finish_and_clear();
+    let problems = problems?;
+    if problems.is_empty() {
+        Output::success("Integrity check passed");
+    } else {
+        Output::error(&format!("Integrity check found {} problems:", problems.len()));
+        for problem in &problems {
+            println!("  {}", problem);
+        }
+    }
+
+    println!();
+    Output::header("Tables");
+    println!();
+    for table in store.table_sizes()? {
+        Output::kv(&table.name, &format!("{} ({} rows)", format_size(table.bytes), table.rows));
+    }
+
+    if !check && !read_only {
+        println!();
+        print_size("Size now", &store.database_size()?);
+    }
+
+    if !problems.is_empty() {
+        return Err(anyhow::anyhow!("The database failed its integrity check; restore it from a backup"));
+    }
+    Ok(())
+}
+
+/// Move local media indexed under the old path-based IDs to content-hash IDs.
+///
+/// Files are looked for where they were indexed from, then by name in the
+/// `search` directories.
+async fn migrate_ids(search: &[String], dry_run: bool, settings: &Settings) -> Result<()> {
+    if matches!(settings.vector_store.provider.as_str(), "postgres" | "columnar") {
+        return Err(anyhow::anyhow!(
+            "Migrating IDs works on the SQLite store only; re-index local media into the {} store instead",
+            settings.vector_store.provider
+        ));
+    }
+    if settings.vector_store.read_only {
+        return Err(anyhow::anyhow!("The database is read-only"));
+    }
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+
+    let legacy: Vec<String> = store
+        .list_videos()
+        .await?
+        .into_iter()
+        .map(|v| v.video_id)
+        .filter(|id| SourceType::of_media_id(id) == SourceType::Local && !LocalSource::is_content_id(id))
+        .collect();
+    if legacy.is_empty() {
+        Output::success("No local media uses path-based IDs.");
+        return Ok(());
+    }
+
+    // Older versions recorded some details under the path as given
+    let sources = store.media_sources(SourceType::Local)?;
+    let mut candidates: Vec<PathBuf> = sources.iter().map(|(_, url)| PathBuf::from(url)).collect();
+    let finder = LocalSource::new().with_recursive(true);
+    for dir in search {
+        candidates.extend(finder.find_media_files(dir)?);
+    }
+
+    Output::header(if dry_run { "ID Migration (dry run)" } else { "ID Migration" });
+    println!();
+    let (mut migrated, mut missing) = (0, Vec::new());
+    for old_id in &legacy {
+        let Some(path) = locate_legacy(old_id, &candidates).filter(|p| p.is_file()) else {
+            missing.push(old_id);
+            continue;
+        };
+        let hash = content_hash(path)?;
+        let new_id = LocalSource::media_id(&hash);
+        if store.is_video_indexed(&new_id).await? {
+            Output::warning(&format!("{} is the same file as {}; left as is", old_id, new_id));
+            continue;
+        }
+
+        Output::list_item(&format!("{} -> {} ({})", old_id, new_id, path.display()));
+        migrated += 1;
+        if dry_run {
+            continue;
+        }
+        store.rename_media(old_id, &new_id)?;
+        for (key, url) in &sources {
+            if key != old_id && LocalSource::legacy_media_id(Path::new(url)) == *old_id {
+                store.rename_media(key, &new_id)?;
+            }
+        }
+        let path = path.to_string_lossy();
+        store.set_media_source(&new_id, SourceType::Local, &path)?;
+        store.record_ingested_file(&hash, &path, &new_id)?;
+    }
+
+    println!();
+    let verb = if dry_run { "Would migrate" } else { "Migrated" };
+    Output::success(&format!("{} {} of {} local media", verb, migrated, legacy.len()));
+    if !missing.is_empty() {
+        Output::warning(&format!("{} files weren't found; pass --search DIR where they are now:", missing.len()));
+        for id in missing {
+            println!("  {}", id);
+        }
+    }
+    Ok(())
+}
+
+/// The file a path-based media ID was made from: one whose current path gives
+/// that ID, else the only one whose name it ends with.
+fn locate_legacy<'a>(id: &str, candidates: &'a [PathBuf]) -> Option<&'a PathBuf> {
+    if let Some(path) = candidates.iter().find(|p| LocalSource::legacy_media_id(p) == id) {
+        return Some(path);
+    }
+
+    let mut by_name = candidates.iter().filter(|p| {
+        p.file_name()
+            .map(|name| name.to_string_lossy().replace(['\\', ' '], "_"))
+            .is_some_and(|name| id.ends_with(&format!("_{}", name)))
+    });
+    match (by_name.next(), by_name.next()) {
+        (Some(path), None) => Some(path),
+        _ => None,
+    }
+}
+
+/// Print file, free-page and WAL sizes.
+fn print_size(label: &str, size: &DatabaseSize) {
+    Output::kv(
+        label,
+        &format!(
+            "{} ({} free, WAL {})",
+            format_size(size.file_bytes),
+            format_size(size.free_bytes),
+            format_size(size.wal_bytes)
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_legacy() {
+        let candidates = vec![
+            PathBuf::from("/old/place/talk one.mp3"),
+            PathBuf::from("/new/place/interview.m4a"),
+            PathBuf::from("/a/notes.mp3"),
+            PathBuf::from("/b/notes.mp3"),
+        ];
+        let found = |id: &str| locate_legacy(id, &candidates).map(|p| p.to_string_lossy().into_owned());
+
+        assert_eq!(found("local__old_place_talk_one.mp3").as_deref(), Some("/old/place/talk one.mp3"));
+        // Moved since indexed: matched by name
+        assert_eq!(found("local__home_me_interview.m4a").as_deref(), Some("/new/place/interview.m4a"));
+        // Two files by that name: ambiguous
+        assert_eq!(found("local__home_me_notes.mp3"), None);
+        assert_eq!(found("local__home_me_other.mp3"), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Dedupe command implementation.
+
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::dedupe::{DuplicateFinder, DuplicatePair};
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use anyhow::Result;
+
+/// Run the dedupe command.
+pub async fn run_dedupe(
+    threshold: Option<f32>,
+    min_overlap: Option<f32>,
+    delete: bool,
+    yes: bool,
+    format: &str,
+    settings: Settings,
+) -> Result<()> {
+    let json = match format.to_lowercase().as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            let e = format!("Unknown output format: {}. Use text or json.", format);
+            Output::error(&e);
+            return Err(LyttError::InvalidInput(e).into());
+        }
+    };
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+
+    let finder = DuplicateFinder::new(orchestrator.vector_store())
+        .with_chunk_threshold(threshold.unwrap_or(settings.dedupe.chunk_threshold))
+        .with_min_overlap(min_overlap.unwrap_or(settings.dedupe.min_overlap));
+
+    let spinner = Output::spinner("Comparing media...");
+    let pairs = finder.find().await;
+    spinner.finish_and_clear();
+    let pairs = pairs?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pairs)?);
+    } else if pairs.is_empty() {
+        Output::success("No duplicates found.");
+        return Ok(());
+    } else {
+        print_pairs(&pairs);
+    }
+
+    if !delete || pairs.is_empty() {
+        return Ok(());
+    }
+
+    // Each later copy is deleted once, even if it duplicates several items
+    let mut duplicates: Vec<(&str, &str)> = pairs
+        .iter()
+        .map(|p| (p.duplicate.video_id.as_str(), p.duplicate.video_title.as_str()))
+        .collect();
+    duplicates.sort();
+    duplicates.dedup();
+
+    if !yes && !Output::confirm(&format!("Delete {} duplicate media items?", duplicates.len()))? {
+        Output::info("Nothing deleted.");
+        return Ok(());
+    }
+
+    let mut chunks = 0;
+    for (video_id, title) in &duplicates {
+        chunks += orchestrator.delete_media(video_id).await?;
+        Output::list_item(&format!("Deleted {} ({})", title, video_id));
+    }
+    Output::success(&format!("Deleted {} media items ({} chunks)", duplicates.len(), chunks));
+
+    Ok(())
+}
+
+/// Print each duplicate pair with the copy that would be kept.
+fn print_pairs(pairs: &[DuplicatePair]) {
+    Output::header(&format!("Duplicates ({} pairs)", pairs.len()));
+    println!();
+
+    for pair in pairs {
+        Output::list_item(&format!(
+            "{} ({}) duplicates {} ({}): {:.0}% of chunks match",
+            pair.duplicate.video_title,
+            pair.duplicate.video_id,
+            pair.original.video_title,
+            pair.original.video_id,
+            pair.overlap * 100.0
+        ));
+    }
+
+    println!();
+    Output::info("Run with --delete to remove the later copies.");
+}
@@ -0,0 +1,100 @@
+//! Digest command implementation.
+
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::digest::{send_email, DigestGenerator};
+use crate::error::LyttError;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::usage::UsageTracker;
+use crate::vector_store::parse_since;
+use anyhow::Result;
+use chrono::Utc;
+
+/// Run the digest command.
+pub async fn run_digest(
+    since: &str,
+    format: &str,
+    output: Option<String>,
+    email: bool,
+    model: Option<String>,
+    settings: Settings,
+) -> Result<()> {
+    let json = match format.to_lowercase().as_str() {
+        "markdown" | "md" => false,
+        "json" => true,
+        _ => {
+            let e = format!("Unknown digest format: {}. Use markdown or json.", format);
+            Output::error(&e);
+            return Err(LyttError::InvalidInput(e).into());
+        }
+    };
+
+    let since = parse_since(since, Utc::now()).map_err(|e| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+
+    if email && settings.digest.email_to.is_empty() {
+        Output::error("No recipients configured. Set [digest] email_to in your config.");
+        return Err(LyttError::Config("no [digest] email_to recipients".to_string()).into());
+    }
+
+    // Pre-flight checks
+    if let Err(e) = preflight::check(Operation::Digest, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let model = model.unwrap_or_else(|| settings.digest.model.clone());
+
+    let generator = DigestGenerator::new(orchestrator.sqlite_store(), &model)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.digest.llm))
+        .with_max_input_chars(settings.digest.max_input_chars);
+
+    let spinner = Output::spinner(&format!("Generating digest since {}...", since.format("%Y-%m-%d")));
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(generator.generate(since)).await;
+    orchestrator.record_usage("digest", None, &tracker).await;
+
+    spinner.finish_and_clear();
+
+    let digest = match result {
+        Ok(digest) => digest,
+        Err(e) => {
+            Output::error(&format!("Failed to generate digest: {}", e));
+            return Err(e.into());
+        }
+    };
+
+    let rendered = if json {
+        serde_json::to_string_pretty(&digest)?
+    } else {
+        digest.to_markdown()
+    };
+
+    match output {
+        Some(path) if path != "-" => {
+            std::fs::write(&path, &rendered)?;
+            Output::success(&format!("Wrote digest of {} media to {}", digest.media.len(), path));
+        }
+        // Printing is skipped when the digest is only being emailed
+        _ if email => {}
+        _ => println!("{}", rendered),
+    }
+
+    if email {
+        if let Err(e) = send_email(&digest, &settings.digest).await {
+            Output::error(&format!("Failed to email digest: {}", e));
+            return Err(e.into());
+        }
+        Output::success(&format!("Emailed digest to {}", settings.digest.email_to.join(", ")));
+    }
+
+    Ok(())
+}
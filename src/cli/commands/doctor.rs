@@ -1,7 +1,7 @@
 //! Doctor command - verify system requirements and configuration.
 
 use crate::cli::Output;
-use crate::config::Settings;
+use crate::config::{LlmProvider, Settings};
 use console::style;
 use std::process::Command;
 
@@ -89,6 +89,12 @@ pub fn run_doctor(settings: &Settings) -> anyhow::Result<()> {
     api_check.print();
     checks.push(api_check);
 
+    let llm_checks = check_llm_endpoints(settings);
+    for check in &llm_checks {
+        check.print();
+    }
+    checks.extend(llm_checks);
+
     println!();
 
     // Check directories
@@ -189,6 +195,39 @@ fn check_openai_api_key() -> CheckResult {
     }
 }
 
+/// Report the backend used by each LLM role.
+fn check_llm_endpoints(settings: &Settings) -> Vec<CheckResult> {
+    let roles = [
+        ("RAG LLM", &settings.rag.llm),
+        ("Chunking LLM", &settings.chunking.llm),
+        ("Cleanup LLM", &settings.transcription.processing.cleanup_llm),
+        ("Rollup LLM", &settings.rollup.llm),
+        ("Summary LLM", &settings.summary.llm),
+    ];
+
+    roles
+        .into_iter()
+        .map(|(name, llm)| {
+            let key_var = match (&llm.api_key_env, llm.provider) {
+                (Some(var), _) => Some(var.as_str()),
+                (None, LlmProvider::Anthropic) => Some("ANTHROPIC_API_KEY"),
+                _ => None,
+            };
+
+            match key_var {
+                Some(var) if std::env::var(var).map(|k| k.is_empty()).unwrap_or(true) => {
+                    CheckResult::error(
+                        name,
+                        &format!("{} - {} not set", llm.describe(), var),
+                        &format!("Set with: export {}='...'", var),
+                    )
+                }
+                _ => CheckResult::ok(name, &llm.describe()),
+            }
+        })
+        .collect()
+}
+
 /// Check data directories.
 fn check_directories(settings: &Settings) -> Vec<CheckResult> {
     let mut results = Vec::new();
@@ -242,7 +281,7 @@ fn check_config_file() -> CheckResult {
 }
 
 /// Format file size in human-readable format.
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
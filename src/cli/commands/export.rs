@@ -1,11 +1,18 @@
 //! Export command implementation.
 
+use crate::audio_source::MediaMetadata;
+use crate::cli::output::format_duration;
 use crate::cli::Output;
 use crate::config::Settings;
+use crate::error::LyttError;
+use crate::export::TranscriptDocument;
+use crate::rollup::CHANNEL_TAG_PREFIX;
 use crate::transcription::{OutputFormat, Transcript, TranscriptSegment};
-use crate::vector_store::{SqliteVectorStore, VectorStore};
+use crate::vector_store::{Document, SqliteVectorStore, VectorStore};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::Serialize;
+use std::io::{BufWriter, Write};
 
 /// Exportable transcript with metadata.
 #[derive(Debug, Serialize)]
@@ -25,14 +32,158 @@ pub struct ExportedSegment {
     pub end_seconds: f64,
 }
 
+/// One chunk per line of a JSONL export, with what a RAG pipeline needs to
+/// cite it.
+#[derive(Debug, Serialize)]
+pub struct ExportedChunk {
+    pub id: String,
+    pub video_id: String,
+    pub video_title: String,
+    pub section_title: Option<String>,
+    pub text: String,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub chunk_order: i32,
+    pub doc_type: String,
+    pub url: Option<String>,
+    pub source_type: Option<String>,
+    pub channel: Option<String>,
+    pub tags: Vec<String>,
+    pub language: Option<String>,
+    pub keywords: Vec<String>,
+    pub summary: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub indexed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Source, channel and tags of the media whose chunks are being written.
+struct MediaInfo {
+    video_id: String,
+    link: Option<MediaMetadata>,
+    channel: Option<String>,
+    tags: Vec<String>,
+}
+
+/// Writes chunks as JSON lines, looking up each media item's details once.
+struct JsonlWriter<'a> {
+    store: &'a SqliteVectorStore,
+    out: Box<dyn Write>,
+    /// Model to name when embeddings are included.
+    embedding_model: Option<Option<String>>,
+    media: Option<MediaInfo>,
+}
+
+impl<'a> JsonlWriter<'a> {
+    fn new(store: &'a SqliteVectorStore, output: Option<&str>, embeddings: bool) -> Result<Self> {
+        let out: Box<dyn Write> = match output {
+            Some(path) if path != "-" => Box::new(BufWriter::new(std::fs::File::create(path)?)),
+            _ => Box::new(BufWriter::new(std::io::stdout())),
+        };
+        let embedding_model = if embeddings {
+            Some(store.embedding_info()?.and_then(|info| info.model))
+        } else {
+            None
+        };
+        Ok(Self {
+            store,
+            out,
+            embedding_model,
+            media: None,
+        })
+    }
+
+    fn write(&mut self, doc: Document) -> crate::error::Result<()> {
+        if self.media.as_ref().is_none_or(|m| m.video_id != doc.video_id) {
+            let tags = self.store.get_tags(&doc.video_id)?;
+            self.media = Some(MediaInfo {
+                link: MediaMetadata::stored(&doc.video_id, &doc.video_title, self.store.get_media_source(&doc.video_id)?),
+                channel: tags.iter().find_map(|t| t.strip_prefix(CHANNEL_TAG_PREFIX)).map(str::to_string),
+                video_id: doc.video_id.clone(),
+                tags,
+            });
+        }
+        let media = self.media.as_ref().expect("media info loaded above");
+
+        let chunk = ExportedChunk {
+            id: doc.id.to_string(),
+            url: media.link.as_ref().map(|m| m.url_with_timestamp(doc.start_seconds)),
+            source_type: media.link.as_ref().map(|m| m.source_type.to_string()),
+            channel: media.channel.clone(),
+            tags: media.tags.clone(),
+            section_title: doc.section_title,
+            text: doc.content,
+            start_seconds: doc.start_seconds,
+            end_seconds: doc.end_seconds,
+            chunk_order: doc.chunk_order,
+            doc_type: doc.doc_type.as_str().to_string(),
+            language: doc.language,
+            keywords: doc.keywords,
+            summary: doc.summary,
+            published_at: doc.source_created_at,
+            indexed_at: doc.indexed_at,
+            embedding_model: self.embedding_model.clone().flatten(),
+            embedding: self.embedding_model.is_some().then_some(doc.embedding),
+            video_id: doc.video_id,
+            video_title: doc.video_title,
+        };
+        serde_json::to_writer(&mut self.out, &chunk)?;
+        self.out.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Export every indexed chunk as JSON lines.
+pub async fn run_export_all(output: Option<String>, format: &str, embeddings: bool, settings: Settings) -> Result<()> {
+    if !format.eq_ignore_ascii_case("jsonl") {
+        Output::error("--all exports the whole library as JSON lines; use --format jsonl");
+        return Err(LyttError::InvalidInput(format!("--all doesn't support the {} format", format)).into());
+    }
+
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+    let mut writer = JsonlWriter::new(&store, output.as_deref(), embeddings)?;
+    let count = store.for_each_document(&mut |doc| writer.write(doc))?;
+    writer.finish()?;
+
+    if let Some(path) = output.filter(|p| p != "-") {
+        Output::success(&format!("Exported {} chunks to {}", count, path));
+    }
+    Ok(())
+}
+
 /// Run the export command.
 pub async fn run_export(
     video_id: &str,
     output: Option<String>,
     format: &str,
+    embeddings: bool,
     settings: Settings,
 ) -> Result<()> {
-    let output_format: OutputFormat = format.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+    let jsonl = format.eq_ignore_ascii_case("jsonl");
+    let document_format = match format.to_lowercase().as_str() {
+        "docx" | "word" => Some("docx"),
+        "pdf" => Some("pdf"),
+        _ => None,
+    };
+    let output_format: Option<OutputFormat> = match document_format {
+        Some(_) => None,
+        None if jsonl => None,
+        None => Some(format.parse().map_err(|_| {
+            LyttError::InvalidInput(format!("Unknown format: {}. Use json, jsonl, srt, vtt, docx, or pdf.", format))
+        })?),
+    };
+    if embeddings && !jsonl {
+        Output::error("--embeddings only applies to --format jsonl");
+        return Err(LyttError::InvalidInput("--embeddings needs --format jsonl".to_string()).into());
+    }
 
     // Open vector store
     let store = SqliteVectorStore::new(&settings.sqlite_path())?;
@@ -43,7 +194,7 @@ pub async fn run_export(
     if chunks.is_empty() {
         Output::error(&format!("No indexed content found for video ID: {}", video_id));
         Output::info("Use 'lytt list' to see indexed media.");
-        return Ok(());
+        return Err(LyttError::VideoNotFound(video_id.to_string()).into());
     }
 
     // Get video title from first chunk
@@ -59,8 +210,49 @@ pub async fn run_export(
         .map(|c| c.end_seconds)
         .fold(0.0f64, |a, b| a.max(b));
 
+    if jsonl {
+        let mut writer = JsonlWriter::new(&store, output.as_deref(), embeddings)?;
+        for chunk in chunks.iter().cloned() {
+            writer.write(chunk)?;
+        }
+        writer.finish()?;
+        if let Some(path) = output.filter(|p| p != "-") {
+            Output::success(&format!("Exported '{}' to {} ({} chunks)", video_title, path, chunks.len()));
+        }
+        return Ok(());
+    }
+
+    if let Some(extension) = document_format {
+        let mut document = TranscriptDocument::from_chunks(&video_title, &chunks);
+        if let Some((_, source_url)) = store.get_media_source(video_id)? {
+            document = document.with_detail("Source", source_url);
+        }
+        let document = document
+            .with_detail("Duration", format_duration(total_duration))
+            .with_detail("Media ID", video_id)
+            .with_detail("Exported", chrono::Local::now().format("%Y-%m-%d").to_string());
+        let bytes = if extension == "pdf" { document.to_pdf()? } else { document.to_docx()? };
+
+        // Documents are binary, so they go to a file unless stdout is asked for
+        match output.as_deref() {
+            Some("-") => std::io::Write::write_all(&mut std::io::stdout(), &bytes)?,
+            _ => {
+                let path = output.unwrap_or_else(|| format!("{}.{}", video_id, extension));
+                std::fs::write(&path, &bytes)?;
+                Output::success(&format!(
+                    "Exported '{}' to {} ({} sections, {} chunks)",
+                    video_title,
+                    path,
+                    document.sections.len(),
+                    chunks.len()
+                ));
+            }
+        }
+        return Ok(());
+    }
+
     // Format based on requested format
-    let output_str = match output_format {
+    let output_str = match output_format.expect("text format when not a document") {
         OutputFormat::Json => {
             let export = ExportedTranscript {
                 video_id: video_id.to_string(),
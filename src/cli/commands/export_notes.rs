@@ -0,0 +1,48 @@
+//! Export-notes command implementation.
+
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::notes::NoteExporter;
+use crate::orchestrator::Orchestrator;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Run the export-notes command.
+pub async fn run_export_notes(
+    vault: &str,
+    folder: &str,
+    related: usize,
+    settings: Settings,
+) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?;
+
+    let vault = PathBuf::from(shellexpand::tilde(vault).to_string());
+    if !vault.is_dir() {
+        Output::error(&format!("Vault directory not found: {}", vault.display()));
+        return Err(LyttError::InvalidInput("Vault directory not found".to_string()).into());
+    }
+    let dir = vault.join(folder);
+
+    let spinner = Output::spinner("Writing notes...");
+    let result = NoteExporter::new(orchestrator.sqlite_store())
+        .with_related(related)
+        .export(&dir)
+        .await;
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(paths) if paths.is_empty() => {
+            Output::info("No media indexed yet. Use 'lytt transcribe <input>' to add content.");
+        }
+        Ok(paths) => {
+            Output::success(&format!("Exported {} notes to {}", paths.len(), dir.display()));
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to export notes: {}", e));
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Glossary command implementation.
+
+use crate::agent::ToolContext;
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::glossary::{merge_terms, render, GlossaryFormat, GlossaryGenerator};
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::usage::UsageTracker;
+use crate::vector_store::VectorStore;
+use anyhow::Result;
+
+/// Run the glossary command.
+pub async fn run_glossary(
+    video: Option<String>,
+    tag: Option<String>,
+    format: &str,
+    output: Option<String>,
+    refresh: bool,
+    model: Option<String>,
+    settings: Settings,
+) -> Result<()> {
+    let format: GlossaryFormat = format.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let store = orchestrator.sqlite_store();
+
+    let video_ids: Vec<String> = match (&video, &tag) {
+        (Some(video_id), _) => vec![video_id.clone()],
+        (None, Some(tag)) => store.videos_with_tag(tag)?,
+        (None, None) => store
+            .list_videos()
+            .await?
+            .into_iter()
+            .map(|v| v.video_id)
+            .collect(),
+    };
+
+    if video_ids.is_empty() {
+        Output::warning("No indexed media to build a glossary from.");
+        return Ok(());
+    }
+
+    // Stored terms are reused; only media without a glossary go to the LLM
+    let mut pending = Vec::new();
+    let mut terms = Vec::new();
+    for video_id in &video_ids {
+        let stored = store.get_glossary(video_id)?;
+        if stored.is_empty() || refresh {
+            pending.push(video_id.clone());
+        } else {
+            terms.extend(stored);
+        }
+    }
+
+    if !pending.is_empty() {
+        // Pre-flight checks
+        if let Err(e) = preflight::check(Operation::Ask, &settings) {
+            Output::error(&format!("{}", e));
+            Output::info("Run 'lytt doctor' for detailed diagnostics.");
+            return Err(e.into());
+        }
+
+        let model = model.unwrap_or_else(|| settings.rag.model.clone());
+        let tool_context = ToolContext::new(orchestrator.vector_store(), orchestrator.embedder());
+        let generator = GlossaryGenerator::new(tool_context, &model)
+            .with_prompts(orchestrator.prompts().clone())
+            .with_client(ChatClient::from_settings(&settings.rag.llm));
+
+        let tracker = UsageTracker::new();
+        for (i, video_id) in pending.iter().enumerate() {
+            let spinner = Output::spinner(&format!(
+                "Extracting terms ({}/{}) from {}...",
+                i + 1,
+                pending.len(),
+                video_id
+            ));
+            let result = tracker.scope(generator.generate(video_id)).await;
+            spinner.finish_and_clear();
+
+            match result {
+                Ok(extracted) => {
+                    store.store_glossary(video_id, &extracted)?;
+                    terms.extend(extracted);
+                }
+                Err(e) => Output::warning(&format!("Skipping {}: {}", video_id, e)),
+            }
+        }
+        orchestrator.record_usage("glossary", video.as_deref(), &tracker).await;
+    }
+
+    let terms = merge_terms(terms);
+    let rendered = render(&terms, format);
+
+    match output {
+        Some(path) if path != "-" => {
+            std::fs::write(&path, &rendered)?;
+            Output::success(&format!(
+                "Wrote {} terms from {} media to {}",
+                terms.len(),
+                video_ids.len(),
+                path
+            ));
+            if settings.transcription.glossary_vocabulary {
+                Output::info("Future transcriptions will be biased towards these terms.");
+            }
+        }
+        _ => println!("{}", rendered),
+    }
+
+    Ok(())
+}
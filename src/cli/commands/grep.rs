@@ -0,0 +1,159 @@
+//! Grep command implementation.
+
+use crate::audio_source::MediaMetadata;
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use crate::transcription::Transcript;
+use anyhow::Result;
+use console::style;
+use regex::{Regex, RegexBuilder};
+
+/// A matching segment with the segments around it.
+#[derive(Debug)]
+struct Hit {
+    /// Index of the matching segment.
+    segment: usize,
+    /// Range of segments to print, the match included.
+    context: std::ops::Range<usize>,
+}
+
+/// Run the grep command.
+///
+/// Searches the stored transcripts for `pattern`, literally unless `regex`
+/// is set, and prints each matching segment with `context` segments either
+/// side.
+pub async fn run_grep(
+    pattern: &str,
+    regex: bool,
+    ignore_case: bool,
+    context: usize,
+    video: Option<&str>,
+    limit: usize,
+    settings: Settings,
+) -> Result<()> {
+    let matcher = RegexBuilder::new(&if regex { pattern.to_string() } else { regex::escape(pattern) })
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| {
+            Output::error(&format!("Invalid pattern: {}", e));
+            LyttError::InvalidInput(e.to_string())
+        })?;
+
+    let orchestrator = Orchestrator::new(settings)?;
+    let store = orchestrator.sqlite_store();
+
+    // A literal pattern lets SQLite skip transcripts that can't match.
+    // JSON escapes quotes and backslashes, and lower() only folds ASCII
+    let prefilter = (!regex && pattern.is_ascii() && !pattern.contains(['"', '\\'])).then_some(pattern);
+    let transcripts = match video {
+        Some(id) => match store.get_transcript(id)? {
+            Some((title, transcript)) => vec![(id.to_string(), title, transcript)],
+            None => {
+                Output::error(&format!("No stored transcript for: {}", id));
+                return Err(LyttError::VideoNotFound(id.to_string()).into());
+            }
+        },
+        None => store.transcripts_containing(prefilter, ignore_case)?,
+    };
+
+    let mut matches = 0;
+    let mut media = 0;
+    for (video_id, title, transcript) in &transcripts {
+        if matches >= limit {
+            break;
+        }
+        let hits = grep_transcript(transcript, &matcher, context);
+        if hits.is_empty() {
+            continue;
+        }
+        media += 1;
+
+        let media_link = MediaMetadata::stored(video_id, title, store.get_media_source(video_id)?);
+        println!("\n{} {}", style(title).bold(), style(format!("({})", video_id)).dim());
+        for hit in hits.iter().take(limit - matches) {
+            for index in hit.context.clone() {
+                let segment = &transcript.segments[index];
+                let text = segment.text.trim();
+                let timestamp = MediaMetadata::format_timestamp(segment.start_seconds);
+                if index == hit.segment {
+                    println!("{} [{}] {}", style(">").green().bold(), style(timestamp).cyan(), highlight(text, &matcher));
+                } else {
+                    println!("  {}", style(format!("[{}] {}", timestamp, text)).dim());
+                }
+            }
+            if let Some(media) = &media_link {
+                println!("  {}", style(media.url_with_timestamp(transcript.segments[hit.segment].start_seconds)).dim());
+            }
+            matches += 1;
+        }
+    }
+
+    println!();
+    if matches == 0 {
+        Output::warning(&format!("No transcript contains '{}'", pattern));
+    } else if matches >= limit {
+        Output::info(&format!("Showing the first {} matches; use --limit for more", limit));
+    } else {
+        let noun = if matches == 1 { "match" } else { "matches" };
+        Output::success(&format!("{} {} in {} media", matches, noun, media));
+    }
+    Ok(())
+}
+
+/// Find the segments matching `matcher`, with up to `context` segments
+/// before and after each.
+fn grep_transcript(transcript: &Transcript, matcher: &Regex, context: usize) -> Vec<Hit> {
+    let count = transcript.segments.len();
+    transcript
+        .segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| matcher.is_match(&segment.text))
+        .map(|(index, _)| Hit {
+            segment: index,
+            context: index.saturating_sub(context)..(index + context + 1).min(count),
+        })
+        .collect()
+}
+
+/// Make the matched parts of `text` stand out.
+fn highlight(text: &str, matcher: &Regex) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for found in matcher.find_iter(text) {
+        out.push_str(&text[last..found.start()]);
+        out.push_str(&style(found.as_str()).yellow().bold().to_string());
+        last = found.end();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptSegment;
+
+    #[test]
+    fn test_grep_transcript() {
+        let texts = ["Restart the service.", "It failed with error code 0x80070005.", "Check the permissions.", "Then ERROR CODE 0x80070005 again."];
+        let segments = texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TranscriptSegment::new(i as f64 * 5.0, i as f64 * 5.0 + 5.0, text.to_string()))
+            .collect();
+        let transcript = Transcript::new("v".to_string(), segments);
+
+        let literal = Regex::new(&regex::escape("error code 0x80070005")).unwrap();
+        let hits = grep_transcript(&transcript, &literal, 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].segment, hits[0].context.clone()), (1, 0..3));
+
+        let any_case = RegexBuilder::new("error code 0x[0-9a-f]+").case_insensitive(true).build().unwrap();
+        let hits = grep_transcript(&transcript, &any_case, 2);
+        assert_eq!(hits.iter().map(|h| h.segment).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(hits[1].context, 1..4);
+    }
+}
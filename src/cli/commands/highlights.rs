@@ -0,0 +1,226 @@
+//! Highlights command implementation.
+
+use crate::audio::{concat_audio, download_audio_cached, extract_segment, AudioCache, Workspace};
+use crate::audio_source::{MediaMetadata, SourceType};
+use crate::cli::output::{format_duration, truncate};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use crate::tts::Speaker;
+use crate::vector_store::{DerivedFilter, SearchFilter, SearchResult};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Candidates searched per requested clip, so overlapping hits can be merged.
+const CANDIDATES_PER_CLIP: usize = 3;
+
+/// Seconds between hits in the same media that are played as one clip.
+const MERGE_GAP_SECONDS: f64 = 2.0;
+
+/// A span of media audio in the reel.
+#[derive(Debug, Clone, PartialEq)]
+struct Clip {
+    video_id: String,
+    title: String,
+    start: f64,
+    end: f64,
+    score: f32,
+}
+
+/// Run the highlights command.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_highlights(
+    query: &str,
+    output: &str,
+    count: usize,
+    max_clip: f64,
+    min_score: f32,
+    announce: bool,
+    settings: Settings,
+) -> Result<()> {
+    if count == 0 || max_clip <= 0.0 {
+        let e = "--count and --max-clip must be greater than zero".to_string();
+        Output::error(&e);
+        return Err(LyttError::InvalidInput(e).into());
+    }
+    let speaker = if announce { Some(Speaker::from_settings(&settings.tts)?) } else { None };
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    if let Err(e) = orchestrator.check_embedding().await {
+        Output::error(&e.to_string());
+        return Err(e.into());
+    }
+
+    let spinner = Output::spinner("Searching...");
+    let embedding = orchestrator.embedder().embed(query).await;
+    let results = match embedding {
+        Ok(embedding) => {
+            let filter = SearchFilter::default().with_derived(DerivedFilter::Exclude);
+            orchestrator
+                .vector_store()
+                .search_filtered(&embedding, count * CANDIDATES_PER_CLIP, min_score, &filter)
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    spinner.finish_and_clear();
+    let results = results.inspect_err(|e| Output::error(&format!("Search failed: {}", e)))?;
+
+    let clips = select_clips(&results, count, max_clip);
+    if clips.is_empty() {
+        Output::warning("No segments matched your query.");
+        return Err(LyttError::VideoNotFound(format!("segments matching '{}'", query)).into());
+    }
+
+    let workspace = Workspace::from_settings(&settings)?;
+    let cache = AudioCache::from_settings(&settings);
+    let store = orchestrator.sqlite_store();
+    let mut sources: HashMap<String, PathBuf> = HashMap::new();
+    let mut parts = Vec::new();
+    let mut played = Vec::new();
+    let progress = Output::progress_bar(clips.len() as u64, "Extracting clips");
+
+    for (i, clip) in clips.iter().enumerate() {
+        let source = match sources.get(&clip.video_id) {
+            Some(path) => path.clone(),
+            None => {
+                let path = match store.get_media_source(&clip.video_id)? {
+                    Some((SourceType::Local, path)) => PathBuf::from(path),
+                    Some((SourceType::YouTube | SourceType::Web, url)) => {
+                        download_audio_cached(&url, &clip.video_id, workspace.path(), &cache).await?
+                    }
+                    None if SourceType::of_media_id(&clip.video_id) == SourceType::YouTube => {
+                        let url = format!("https://www.youtube.com/watch?v={}", clip.video_id);
+                        download_audio_cached(&url, &clip.video_id, workspace.path(), &cache).await?
+                    }
+                    None => {
+                        Output::warning(&format!("Skipping '{}': its file location wasn't recorded", clip.title));
+                        progress.inc(1);
+                        continue;
+                    }
+                };
+                sources.insert(clip.video_id.clone(), path.clone());
+                path
+            }
+        };
+        if !source.exists() {
+            Output::warning(&format!("Skipping '{}': {} no longer exists", clip.title, source.display()));
+            progress.inc(1);
+            continue;
+        }
+
+        if let Some(speaker) = &speaker {
+            let intro = workspace.path().join(format!("intro_{:03}.{}", i, speaker.extension()));
+            speaker.speak_to_file(&announcement(clip), &intro).await?;
+            parts.push(intro);
+        }
+        let dest = workspace.path().join(format!("clip_{:03}.mp3", i));
+        extract_segment(&source, &dest, clip.start, clip.end - clip.start).await?;
+        parts.push(dest);
+        played.push(clip);
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    if parts.is_empty() {
+        Output::error("None of the matching media could be played.");
+        return Err(LyttError::VideoNotFound("audio for the matching segments".to_string()).into());
+    }
+
+    let output = Path::new(output);
+    concat_audio(&parts, output).await?;
+
+    Output::success(&format!("Wrote {} clips to {}", played.len(), output.display()));
+    for clip in played {
+        println!(
+            "  {} {} ({})",
+            MediaMetadata::format_timestamp(clip.start),
+            truncate(&clip.title, 60),
+            format_duration(clip.end - clip.start)
+        );
+    }
+    Ok(())
+}
+
+/// Pick the best `count` clips from search results, best first.
+///
+/// Hits that overlap or nearly touch in the same media become one clip, and
+/// every clip is cut to `max_clip` seconds.
+fn select_clips(results: &[SearchResult], count: usize, max_clip: f64) -> Vec<Clip> {
+    let mut clips: Vec<Clip> = Vec::new();
+    for result in results {
+        let doc = &result.document;
+        let overlapping = clips.iter().position(|c| {
+            c.video_id == doc.video_id
+                && doc.start_seconds <= c.end + MERGE_GAP_SECONDS
+                && doc.end_seconds >= c.start - MERGE_GAP_SECONDS
+        });
+        match overlapping {
+            Some(i) => {
+                let clip = &mut clips[i];
+                clip.start = clip.start.min(doc.start_seconds);
+                clip.end = clip.end.max(doc.end_seconds);
+                clip.score = clip.score.max(result.score);
+            }
+            None if clips.len() < count => clips.push(Clip {
+                video_id: doc.video_id.clone(),
+                title: doc.video_title.clone(),
+                start: doc.start_seconds,
+                end: doc.end_seconds,
+                score: result.score,
+            }),
+            None => {}
+        }
+    }
+
+    for clip in &mut clips {
+        clip.end = clip.end.min(clip.start + max_clip);
+    }
+    clips.retain(|c| c.end > c.start);
+    clips.sort_by(|a, b| b.score.total_cmp(&a.score));
+    clips
+}
+
+/// Spoken line introducing a clip.
+fn announcement(clip: &Clip) -> String {
+    format!("From {}, at {}.", clip.title, MediaMetadata::format_timestamp(clip.start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::Document;
+
+    #[test]
+    fn test_select_clips() {
+        let hit = |video: &str, start: f64, end: f64, score: f32| {
+            let document = Document::new(
+                video.to_string(),
+                format!("Title {}", video),
+                None,
+                "text".to_string(),
+                start,
+                end,
+                vec![],
+                0,
+                None,
+            );
+            SearchResult { document, score }
+        };
+        let results = vec![
+            hit("a", 100.0, 130.0, 0.9),
+            hit("b", 0.0, 200.0, 0.8),
+            hit("a", 131.0, 160.0, 0.7),
+            hit("c", 10.0, 20.0, 0.6),
+        ];
+
+        let clips = select_clips(&results, 2, 90.0);
+        assert_eq!(clips.len(), 2);
+        // Adjacent hits in "a" merge; "b" is cut to the clip limit; "c" misses out
+        assert_eq!((clips[0].video_id.as_str(), clips[0].start, clips[0].end), ("a", 100.0, 160.0));
+        assert_eq!((clips[1].video_id.as_str(), clips[1].start, clips[1].end), ("b", 0.0, 90.0));
+        assert_eq!(announcement(&clips[0]), "From Title a, at 01:40.");
+    }
+}
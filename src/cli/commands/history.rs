@@ -0,0 +1,80 @@
+//! History command implementation.
+
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::usage::{ProcessingRun, RunStatus};
+use crate::vector_store::SqliteVectorStore;
+use anyhow::Result;
+use chrono::Local;
+use console::style;
+
+/// Run the history command.
+pub fn run_history(video_id: &str, limit: usize, json: bool, settings: Settings) -> Result<()> {
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+    let runs = store.processing_history(video_id, limit)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+
+    if runs.is_empty() {
+        Output::info(&format!("No processing runs recorded for {}.", video_id));
+        return Ok(());
+    }
+
+    Output::header(&format!("Processing history of {}", video_id));
+    for run in &runs {
+        println!();
+        print_run(run);
+    }
+
+    Ok(())
+}
+
+fn print_run(run: &ProcessingRun) {
+    let status = match run.status {
+        RunStatus::Completed => style(run.status.to_string()).green(),
+        RunStatus::Skipped | RunStatus::Cancelled => style(run.status.to_string()).yellow(),
+        RunStatus::Failed => style(run.status.to_string()).red(),
+    };
+    println!(
+        "{}  {} {} in {:.1}s",
+        style(run.started_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")).bold(),
+        run.operation,
+        status,
+        run.duration_seconds()
+    );
+
+    if let Some(error) = &run.error {
+        Output::kv("  Error", error);
+    }
+    if let Some(run_id) = &run.run_id {
+        Output::kv("  Run ID", run_id);
+    }
+    if let Some(provider) = &run.transcription_provider {
+        let model = run.transcription_model.as_deref().unwrap_or_default();
+        Output::kv("  Transcription", &format!("{} ({})", provider, model));
+    }
+    let chunking = match &run.chunking_model {
+        Some(model) => format!("{} ({}), config {}", run.chunking_strategy, model, run.chunking_fingerprint),
+        None => format!("{}, config {}", run.chunking_strategy, run.chunking_fingerprint),
+    };
+    Output::kv("  Chunking", &chunking);
+    Output::kv("  Embedding", &run.embedding_model);
+    if run.status == RunStatus::Completed {
+        Output::kv("  Chunks", &run.chunks.to_string());
+    }
+    if run.input_tokens + run.output_tokens > 0 || run.audio_seconds > 0.0 {
+        Output::kv(
+            "  Usage",
+            &format!(
+                "{} in / {} out tokens, {:.1}m audio, ${:.4}",
+                run.input_tokens,
+                run.output_tokens,
+                run.audio_seconds / 60.0,
+                run.cost_usd
+            ),
+        );
+    }
+}
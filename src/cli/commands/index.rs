@@ -0,0 +1,100 @@
+//! Index command implementation.
+
+use crate::cli::{IndexAction, Output};
+use crate::config::Settings;
+use crate::orchestrator::Orchestrator;
+use crate::vector_store::{IndexKind, IndexStatus};
+use anyhow::Result;
+
+/// Run the index command.
+pub fn run_index(action: &IndexAction, settings: Settings) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?;
+    let store = orchestrator.sqlite_store();
+    let enabled = store.index_kinds();
+
+    match action {
+        IndexAction::Rebuild { ann, fts, topics, quantized } => {
+            let requested: Vec<IndexKind> = [
+                (*ann, IndexKind::Ann),
+                (*fts, IndexKind::Fts),
+                (*topics, IndexKind::Topics),
+                (*quantized, IndexKind::Quantized),
+            ]
+                .into_iter()
+                .filter_map(|(flag, kind)| flag.then_some(kind))
+                .collect();
+
+            // No flags means every enabled index
+            let kinds = if requested.is_empty() { enabled.clone() } else { requested };
+
+            if kinds.is_empty() {
+                Output::info("No derived indexes are enabled.");
+                return Ok(());
+            }
+
+            let mut failed = false;
+            for kind in kinds {
+                if !enabled.contains(&kind) {
+                    Output::warning(&format!("The {} index is not enabled; skipping.", kind));
+                    failed = true;
+                    continue;
+                }
+
+                let pb = Output::progress_bar(0, &format!("Rebuilding {} index", kind));
+                let result = store.rebuild_index(kind, &mut |done, total| {
+                    pb.set_length(total as u64);
+                    pb.set_position(done as u64);
+                });
+                pb.finish_and_clear();
+
+                match result {
+                    Ok(status) if status.is_consistent() => {
+                        Output::success(&format!("Rebuilt {} index ({} documents)", kind, status.entries));
+                    }
+                    Ok(status) => {
+                        print_status(&status);
+                        failed = true;
+                    }
+                    Err(e) => {
+                        Output::error(&format!("Failed to rebuild {} index: {}", kind, e));
+                        failed = true;
+                    }
+                }
+            }
+
+            if failed {
+                return Err(anyhow::anyhow!("Some indexes could not be rebuilt"));
+            }
+        }
+
+        IndexAction::Status => {
+            if enabled.is_empty() {
+                Output::info("No derived indexes are enabled.");
+                return Ok(());
+            }
+
+            Output::header("Derived Indexes");
+            println!();
+            for status in store.index_status()? {
+                print_status(&status);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the consistency of one index.
+fn print_status(status: &IndexStatus) {
+    if status.is_consistent() {
+        Output::kv(&status.kind.to_string(), &format!("ok ({} documents)", status.entries));
+    } else {
+        Output::kv(
+            &status.kind.to_string(),
+            &format!(
+                "out of date ({} of {} documents); run 'lytt index rebuild --{}'",
+                status.entries, status.documents, status.kind
+            ),
+        );
+    }
+}
@@ -3,7 +3,6 @@
 use crate::cli::Output;
 use crate::config::Settings;
 use console::style;
-use std::io::{self, Write};
 
 /// Simple check result for init command.
 struct CheckIssue {
@@ -32,7 +31,7 @@ pub fn run_init(settings: &Settings) -> anyhow::Result<()> {
         }
         println!();
 
-        if !prompt_continue("Continue anyway?")? {
+        if !Output::confirm("Continue anyway?")? {
             println!();
             Output::info("Setup cancelled. Install the missing tools and run 'lytt init' again.");
             return Ok(());
@@ -57,7 +56,7 @@ pub fn run_init(settings: &Settings) -> anyhow::Result<()> {
         println!("  {}", style("export OPENAI_API_KEY='sk-...'").green());
         println!();
 
-        if !prompt_continue("Continue without API key?")? {
+        if !Output::confirm("Continue without API key?")? {
             println!();
             Output::info("Setup cancelled. Set your API key and run 'lytt init' again.");
             return Ok(());
@@ -98,7 +97,7 @@ pub fn run_init(settings: &Settings) -> anyhow::Result<()> {
     let config_path = Settings::default_config_path();
     if config_path.exists() {
         Output::info(&format!("Config file exists: {}", config_path.display()));
-    } else if prompt_continue("Create default configuration file?")? {
+    } else if Output::confirm("Create default configuration file?")? {
         // Create parent directory if needed
         if let Some(parent) = config_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -184,18 +183,6 @@ fn install_hint(tool: &str) -> &'static str {
     }
 }
 
-/// Prompt user for yes/no confirmation.
-fn prompt_continue(message: &str) -> io::Result<bool> {
-    print!("{} {} ", style("?").cyan(), message);
-    print!("{} ", style("[y/N]").dim());
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    Ok(input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,42 +1,301 @@
 //! List command implementation.
 
+use crate::audio_source::SourceType;
+use crate::cli::output::{format_duration, truncate};
 use crate::cli::Output;
 use crate::config::Settings;
+use crate::error::LyttError;
 use crate::orchestrator::Orchestrator;
+use crate::rollup::CHANNEL_TAG_PREFIX;
+use crate::transcription::language_code;
+use crate::vector_store::{parse_since, IndexedVideo};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use console::style;
+use serde::Serialize;
+
+/// Keywords shown per item with `--detail`.
+const DETAIL_KEYWORDS: usize = 10;
+
+/// Order of listed media.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListSort {
+    /// Most recently indexed first.
+    Date,
+    /// Longest first.
+    Duration,
+    /// Most chunks first.
+    Chunks,
+    /// Alphabetical by title.
+    Title,
+}
+
+impl std::str::FromStr for ListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "date" => Ok(ListSort::Date),
+            "duration" => Ok(ListSort::Duration),
+            "chunks" => Ok(ListSort::Chunks),
+            "title" => Ok(ListSort::Title),
+            _ => Err(format!("Unknown sort order: {}. Use date, duration, chunks, or title.", s)),
+        }
+    }
+}
+
+/// A listed media item.
+#[derive(Debug, Clone, Serialize)]
+struct ListedMedia {
+    video_id: String,
+    title: String,
+    source: SourceType,
+    channel: Option<String>,
+    chunk_count: u32,
+    duration_seconds: f64,
+    indexed_at: DateTime<Utc>,
+    language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_title: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+}
 
 /// Run the list command.
-pub async fn run_list(settings: Settings) -> Result<()> {
+///
+/// JSON output lists every match unless a page is requested.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_list(
+    detail: bool,
+    language: Option<&str>,
+    sort: &str,
+    source: Option<&str>,
+    channel: Option<&str>,
+    since: Option<&str>,
+    json: bool,
+    table: bool,
+    page: Option<usize>,
+    page_size: usize,
+    settings: Settings,
+) -> Result<()> {
+    let sort: ListSort = sort.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+    let source = source
+        .map(|s| s.parse::<SourceType>())
+        .transpose()
+        .map_err(|e| {
+            Output::error(&e);
+            LyttError::InvalidInput(e)
+        })?;
+    let since = match since {
+        Some(window) => Some(parse_since(window, Utc::now()).map_err(|e| {
+            Output::error(&e);
+            LyttError::InvalidInput(e)
+        })?),
+        None => None,
+    };
+    let language = language.map(|l| language_code(l).unwrap_or_else(|| l.to_lowercase()));
+
     let orchestrator = Orchestrator::new(settings)?;
+    let store = orchestrator.sqlite_store();
 
-    match orchestrator.vector_store().list_videos().await {
-        Ok(media) => {
-            if media.is_empty() {
-                Output::info("No media indexed yet. Use 'lytt transcribe <input>' to add content.");
-            } else {
-                Output::header(&format!("Indexed Media ({})", media.len()));
-                println!();
-
-                for item in &media {
-                    Output::media_info(
-                        &item.video_title,
-                        &item.video_id,
-                        item.chunk_count,
-                        item.total_duration_seconds,
-                    );
-                }
-
-                let total_chunks: u32 = media.iter().map(|m| m.chunk_count).sum();
-                println!();
-                Output::kv("Total items", &media.len().to_string());
-                Output::kv("Total chunks", &total_chunks.to_string());
-            }
-        }
+    let mut media = match orchestrator.vector_store().list_videos().await {
+        Ok(media) => media,
         Err(e) => {
             Output::error(&format!("Failed to list media: {}", e));
             return Err(e.into());
         }
+    };
+    let filtered = language.is_some() || source.is_some() || since.is_some() || channel.is_some();
+    media.retain(|m| {
+        language
+            .as_ref()
+            .is_none_or(|language| m.language.as_ref().is_some_and(|l| l.eq_ignore_ascii_case(language)))
+            && source.is_none_or(|source| SourceType::of_media_id(&m.video_id) == source)
+            && since.is_none_or(|since| m.indexed_at >= since)
+    });
+
+    let mut items = Vec::with_capacity(media.len());
+    for item in media {
+        let item_channel = store
+            .get_tags(&item.video_id)?
+            .into_iter()
+            .find_map(|t| t.strip_prefix(CHANNEL_TAG_PREFIX).map(String::from));
+        if let Some(wanted) = channel {
+            if !item_channel.as_ref().is_some_and(|c| c.eq_ignore_ascii_case(wanted.trim())) {
+                continue;
+            }
+        }
+        items.push(listed(item, item_channel));
+    }
+    sort_media(&mut items, sort);
+
+    let total = items.len();
+    let page_size = page_size.max(1);
+    let pages = total.div_ceil(page_size).max(1);
+    let requested_page = page;
+    let page = page.unwrap_or(1).clamp(1, pages);
+    if !json || requested_page.is_some() {
+        items = items.into_iter().skip((page - 1) * page_size).take(page_size).collect();
+    }
+
+    if detail {
+        for item in &mut items {
+            item.generated_title = store.get_generated_title(&item.video_id)?.map(|t| t.provenance());
+            item.keywords = store
+                .media_keywords(&item.video_id, DETAIL_KEYWORDS)?
+                .into_iter()
+                .map(|k| k.keyword)
+                .collect();
+            item.summary = store.get_summary(&item.video_id)?.map(|s| s.overview);
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        return Ok(());
+    }
+
+    if total == 0 && filtered {
+        Output::info("No indexed media matches those filters.");
+        return Ok(());
+    } else if total == 0 {
+        Output::info("No media indexed yet. Use 'lytt transcribe <input>' to add content.");
+        return Ok(());
+    }
+
+    Output::header(&format!("Indexed Media ({})", total));
+    println!();
+    if table {
+        print_table(&items);
+    } else {
+        for item in &items {
+            print_item(item);
+        }
     }
 
+    println!();
+    if pages > 1 {
+        Output::info(&format!(
+            "Page {} of {}{}",
+            page,
+            pages,
+            if page < pages { format!(". Use --page {} for more.", page + 1) } else { String::new() }
+        ));
+    }
+    let total_chunks: u32 = items.iter().map(|m| m.chunk_count).sum();
+    Output::kv("Total items", &total.to_string());
+    Output::kv(if pages > 1 { "Chunks on page" } else { "Total chunks" }, &total_chunks.to_string());
+
     Ok(())
 }
+
+fn listed(item: IndexedVideo, channel: Option<String>) -> ListedMedia {
+    ListedMedia {
+        source: SourceType::of_media_id(&item.video_id),
+        video_id: item.video_id,
+        title: item.video_title,
+        channel,
+        chunk_count: item.chunk_count,
+        duration_seconds: item.total_duration_seconds,
+        indexed_at: item.indexed_at,
+        language: item.language,
+        generated_title: None,
+        keywords: Vec::new(),
+        summary: None,
+    }
+}
+
+fn sort_media(items: &mut [ListedMedia], sort: ListSort) {
+    match sort {
+        ListSort::Date => items.sort_by_key(|m| std::cmp::Reverse(m.indexed_at)),
+        ListSort::Duration => items.sort_by(|a, b| b.duration_seconds.total_cmp(&a.duration_seconds)),
+        ListSort::Chunks => items.sort_by_key(|m| std::cmp::Reverse(m.chunk_count)),
+        ListSort::Title => items.sort_by_cached_key(|m| m.title.to_lowercase()),
+    }
+}
+
+fn print_item(item: &ListedMedia) {
+    Output::media_info(
+        &item.title,
+        &item.video_id,
+        item.chunk_count,
+        item.duration_seconds,
+        item.language.as_deref(),
+    );
+    if let Some(title) = &item.generated_title {
+        println!("    {}", title);
+    }
+    if !item.keywords.is_empty() {
+        println!("    Keywords: {}", item.keywords.join(", "));
+    }
+    if let Some(summary) = &item.summary {
+        println!("    {}", summary.replace('\n', "\n    "));
+        println!();
+    }
+}
+
+fn print_table(items: &[ListedMedia]) {
+    println!(
+        "  {:<40} {:<24} {:<8} {:<20} {:>6} {:>10} {:<10}",
+        style("Title").bold(),
+        style("ID").bold(),
+        style("Source").bold(),
+        style("Channel").bold(),
+        style("Chunks").bold(),
+        style("Duration").bold(),
+        style("Indexed").bold()
+    );
+    for item in items {
+        println!(
+            "  {:<40} {:<24} {:<8} {:<20} {:>6} {:>10} {:<10}",
+            truncate(&item.title, 40),
+            truncate(&item.video_id, 24),
+            item.source.to_string(),
+            truncate(item.channel.as_deref().unwrap_or("-"), 20),
+            item.chunk_count,
+            format_duration(item.duration_seconds),
+            item.indexed_at.format("%Y-%m-%d").to_string()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_media() {
+        let item = |title: &str, chunks: u32, duration: f64, days_ago: i64| ListedMedia {
+            video_id: title.to_lowercase(),
+            title: title.to_string(),
+            source: SourceType::YouTube,
+            channel: None,
+            chunk_count: chunks,
+            duration_seconds: duration,
+            indexed_at: Utc::now() - chrono::Duration::days(days_ago),
+            language: None,
+            generated_title: None,
+            keywords: Vec::new(),
+            summary: None,
+        };
+        let mut items = vec![item("beta", 5, 600.0, 2), item("Alpha", 10, 300.0, 1), item("gamma", 1, 900.0, 3)];
+
+        let order = |items: &[ListedMedia]| items.iter().map(|m| m.video_id.clone()).collect::<Vec<_>>();
+        sort_media(&mut items, ListSort::Title);
+        assert_eq!(order(&items), ["alpha", "beta", "gamma"]);
+        sort_media(&mut items, ListSort::Duration);
+        assert_eq!(order(&items), ["gamma", "beta", "alpha"]);
+        sort_media(&mut items, ListSort::Chunks);
+        assert_eq!(order(&items), ["alpha", "beta", "gamma"]);
+        sort_media(&mut items, ListSort::Date);
+        assert_eq!(order(&items), ["alpha", "beta", "gamma"]);
+
+        assert!("size".parse::<ListSort>().is_err());
+        assert_eq!(SourceType::of_media_id("local__tmp_a.mp3"), SourceType::Local);
+    }
+}
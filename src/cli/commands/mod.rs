@@ -2,28 +2,84 @@
 
 mod agent;
 mod ask;
+mod cache;
 mod chat;
 mod config;
+mod consensus;
+mod db;
+mod daemon;
+mod dedupe;
+mod digest;
 mod doctor;
 mod export;
+mod export_notes;
+mod glossary;
+mod grep;
+mod highlights;
+mod history;
+mod index;
 mod init;
 mod list;
 mod mcp;
+mod open;
+mod pack;
+mod prune;
+mod quiz;
 mod rechunk;
+mod record;
+mod reembed;
+mod rollup;
 mod search;
+mod series;
 mod serve;
+mod stats;
+mod summarize;
+mod tag;
+mod timeline;
+mod topics;
 mod transcribe;
+mod transcript;
+mod tui;
+mod usage;
 
 pub use agent::run_agent;
 pub use ask::run_ask;
+pub use cache::run_cache;
 pub use chat::run_chat;
 pub use config::run_config;
+pub use consensus::run_consensus;
+pub use db::run_db;
+pub use daemon::run_daemon;
+pub use dedupe::run_dedupe;
+pub use digest::run_digest;
 pub use doctor::run_doctor;
-pub use export::run_export;
+pub use export::{run_export, run_export_all};
+pub use export_notes::run_export_notes;
+pub use glossary::run_glossary;
+pub use grep::run_grep;
+pub use highlights::run_highlights;
+pub use history::run_history;
+pub use index::run_index;
 pub use init::run_init;
 pub use list::run_list;
+pub use open::run_open;
 pub use mcp::run_mcp;
-pub use rechunk::run_rechunk;
+pub use pack::run_pack;
+pub use prune::run_prune;
+pub use quiz::run_quiz;
+pub use rechunk::{run_rechunk, run_rechunk_stale};
+pub use record::run_record;
+pub use reembed::run_reembed;
+pub use rollup::run_rollup;
 pub use search::run_search;
+pub use series::run_series;
 pub use serve::run_serve;
-pub use transcribe::run_transcribe;
+pub use stats::run_stats;
+pub use summarize::run_summarize;
+pub use tag::run_tag;
+pub use timeline::run_timeline;
+pub use topics::run_topics;
+pub use transcribe::{run_dry_run, run_estimate, run_transcribe};
+pub use transcript::run_transcript;
+pub use tui::run_tui;
+pub use usage::run_usage;
@@ -0,0 +1,150 @@
+//! Open command implementation.
+
+use crate::audio_source::{MediaMetadata, SourceType};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use anyhow::Result;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use uuid::Uuid;
+
+/// Players tried for local files, with the flag that sets the start time.
+const PLAYERS: [(&str, &str); 2] = [("mpv", "--start="), ("vlc", "--start-time=")];
+
+/// Run the open command.
+///
+/// `target` is a media ID or a chunk ID; a chunk opens at its start unless a
+/// timestamp is given.
+pub async fn run_open(target: &str, timestamp: Option<&str>, print: bool, settings: Settings) -> Result<()> {
+    let requested = timestamp
+        .map(|t| parse_timestamp(t).ok_or_else(|| invalid_timestamp(t)))
+        .transpose()?;
+
+    let orchestrator = Orchestrator::new(settings)?;
+    let store = orchestrator.sqlite_store();
+
+    let chunk = match Uuid::parse_str(target) {
+        Ok(id) => store.get_documents(&[id])?.into_iter().next(),
+        Err(_) => None,
+    };
+    let (video_id, seconds) = match &chunk {
+        Some(doc) => (doc.video_id.clone(), requested.unwrap_or(doc.start_seconds)),
+        None => (target.to_string(), requested.unwrap_or(0.0)),
+    };
+    let Some(video) = orchestrator.vector_store().get_video(&video_id).await? else {
+        Output::error(&format!("No indexed media with ID: {}", video_id));
+        return Err(LyttError::VideoNotFound(video_id).into());
+    };
+
+    let Some(metadata) = MediaMetadata::stored(&video_id, &video.video_title, store.get_media_source(&video_id)?) else {
+        Output::error("The file's location wasn't recorded when it was indexed.");
+        Output::info(&format!("Re-index it with 'lytt transcribe <file> --force' to open {}.", video_id));
+        return Err(LyttError::VideoNotFound(format!("source file of {}", video_id)).into());
+    };
+
+    if print {
+        println!("{}", metadata.url_with_timestamp(seconds));
+        return Ok(());
+    }
+
+    Output::info(&format!(
+        "Opening {} at {}",
+        metadata.title,
+        MediaMetadata::format_timestamp(seconds)
+    ));
+    match metadata.source_type {
+        SourceType::YouTube | SourceType::Web => open_with_system(&metadata.url_with_timestamp(seconds))?,
+        SourceType::Local => play_local(&metadata.source_url, seconds)?,
+    }
+    Ok(())
+}
+
+/// Parse a timestamp given as seconds ("90", "90s"), MM:SS or HH:MM:SS.
+pub(crate) fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let timestamp = timestamp.trim();
+    if let Ok(seconds) = timestamp.strip_suffix('s').unwrap_or(timestamp).parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then_some(seconds);
+    }
+
+    let parts: Vec<u32> = timestamp.split(':').map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [minutes, seconds] if *seconds < 60 => Some((minutes * 60 + seconds) as f64),
+        [hours, minutes, seconds] if *minutes < 60 && *seconds < 60 => {
+            Some((hours * 3600 + minutes * 60 + seconds) as f64)
+        }
+        _ => None,
+    }
+}
+
+fn invalid_timestamp(timestamp: &str) -> LyttError {
+    LyttError::InvalidInput(format!(
+        "Invalid timestamp: {}. Use seconds (90), MM:SS (1:30) or HH:MM:SS.",
+        timestamp
+    ))
+}
+
+/// Play a local file in the first installed player, seeked to `seconds`.
+/// Falls back to the system's default application, which starts at the
+/// beginning.
+fn play_local(path: &str, seconds: f64) -> Result<()> {
+    if !Path::new(path).exists() {
+        Output::error(&format!("The file has moved or been deleted: {}", path));
+        return Err(LyttError::VideoNotFound(path.to_string()).into());
+    }
+
+    for (player, start_flag) in PLAYERS {
+        let spawned = Command::new(player)
+            .arg(format!("{}{}", start_flag, seconds as u32))
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(_) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(LyttError::ToolFailed(format!("{}: {}", player, e)).into()),
+        }
+    }
+
+    Output::warning("Neither mpv nor vlc is installed; opening from the start with the default application.");
+    open_with_system(path)
+}
+
+/// Open a URL or file with the system's default application.
+fn open_with_system(target: &str) -> Result<()> {
+    let (program, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
+        ("open", vec![target])
+    } else if cfg!(windows) {
+        ("cmd", vec!["/C", "start", "", target])
+    } else {
+        ("xdg-open", vec![target])
+    };
+
+    match Command::new(program).args(&args).stdout(Stdio::null()).stderr(Stdio::null()).spawn() {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Output::info(target);
+            Err(LyttError::ToolNotFound(program.to_string()).into())
+        }
+        Err(e) => Err(LyttError::ToolFailed(format!("{}: {}", program, e)).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("90"), Some(90.0));
+        assert_eq!(parse_timestamp("90s"), Some(90.0));
+        assert_eq!(parse_timestamp("1:30"), Some(90.0));
+        assert_eq!(parse_timestamp("01:02:03"), Some(3723.0));
+        assert_eq!(parse_timestamp("1:75"), None);
+        assert_eq!(parse_timestamp("-5"), None);
+        assert_eq!(parse_timestamp("soon"), None);
+    }
+}
@@ -0,0 +1,69 @@
+//! Pack command implementation.
+
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::pack::PackGenerator;
+use crate::usage::UsageTracker;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Run the pack command.
+pub async fn run_pack(
+    tag: &str,
+    output: &str,
+    cards: usize,
+    quotes: usize,
+    model: Option<String>,
+    settings: Settings,
+) -> Result<()> {
+    // Pre-flight checks
+    if let Err(e) = preflight::check(Operation::Ask, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let model = model.unwrap_or_else(|| settings.rag.model.clone());
+
+    let generator = PackGenerator::new(orchestrator.sqlite_store(), orchestrator.embedder(), &model)
+        .with_cards(cards)
+        .with_quotes(quotes)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.rag.llm));
+
+    let spinner = Output::spinner(&format!("Building study pack for '{}'...", tag));
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(generator.generate(tag)).await;
+    orchestrator.record_usage("pack", None, &tracker).await;
+
+    spinner.finish_and_clear();
+
+    let pack = match result {
+        Ok(pack) => pack,
+        Err(e) => {
+            Output::error(&format!("Failed to build study pack: {}", e));
+            return Err(e.into());
+        }
+    };
+
+    let dir = PathBuf::from(shellexpand::tilde(output).to_string());
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("study-pack.md"), pack.to_markdown())?;
+    std::fs::write(dir.join("flashcards.txt"), pack.to_anki())?;
+
+    Output::success(&format!(
+        "Wrote a study pack of {} items to {}",
+        pack.sections.len(),
+        dir.display()
+    ));
+    Output::kv("Pack", &dir.join("study-pack.md").display().to_string());
+    Output::kv("Flashcards (Anki)", &dir.join("flashcards.txt").display().to_string());
+    Output::kv("Glossary terms", &pack.glossary.len().to_string());
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Prune command implementation.
+
+use crate::cli::Output;
+use crate::config::{PruneAction, Settings};
+use crate::orchestrator::Orchestrator;
+use crate::retention::{self, PruneItem};
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Run the prune command.
+pub async fn run_prune(dry_run: bool, settings: Settings) -> Result<()> {
+    if settings.retention.policies.is_empty() {
+        Output::info("No retention policies configured. Add [[retention.policies]] to your config.");
+        return Ok(());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let store = orchestrator.sqlite_store();
+
+    let items = retention::plan_for_store(&store, &settings.retention)?;
+    if items.is_empty() {
+        Output::success("Library is within its retention policies.");
+        return Ok(());
+    }
+
+    print_plan(&items);
+
+    if dry_run {
+        println!();
+        Output::info(&format!(
+            "Dry run: {} chunks would be {}d. Run without --dry-run to apply.",
+            items.len(),
+            settings.retention.action
+        ));
+        return Ok(());
+    }
+
+    let outcome = retention::apply(&store, &items, settings.retention.action, &settings.archive_dir()).await?;
+
+    println!();
+    match (settings.retention.action, outcome.archive_path) {
+        (PruneAction::Archive, Some(path)) => Output::success(&format!(
+            "Archived {} chunks to {}",
+            outcome.removed,
+            path.display()
+        )),
+        _ => Output::success(&format!("Deleted {} chunks", outcome.removed)),
+    }
+    Output::info("Transcripts are kept; use 'lytt rechunk <video_id>' to re-index pruned media.");
+
+    Ok(())
+}
+
+/// Print the chunks to prune, grouped by media.
+fn print_plan(items: &[PruneItem]) {
+    let mut by_media: BTreeMap<(&str, &str), Vec<&PruneItem>> = BTreeMap::new();
+    for item in items {
+        by_media
+            .entry((item.video_title.as_str(), item.video_id.as_str()))
+            .or_default()
+            .push(item);
+    }
+
+    Output::header(&format!("Prune Plan ({} chunks, {} media)", items.len(), by_media.len()));
+    println!();
+
+    for ((title, video_id), items) in &by_media {
+        let mut reasons: Vec<String> = items.iter().map(|i| i.reason.to_string()).collect();
+        reasons.sort();
+        reasons.dedup();
+        let never = items.iter().filter(|i| i.retrieval_count == 0).count();
+
+        Output::list_item(&format!(
+            "{} ({}): {} chunks, {} never retrieved [{}]",
+            title,
+            video_id,
+            items.len(),
+            never,
+            reasons.join(", ")
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+//! Quiz command implementation.
+
+use crate::agent::ToolContext;
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::quiz::{QuizFormat, QuizGenerator};
+use crate::usage::UsageTracker;
+use anyhow::Result;
+
+/// Run the quiz command.
+pub async fn run_quiz(
+    video_id: &str,
+    format: &str,
+    count: usize,
+    output: Option<String>,
+    model: Option<String>,
+    settings: Settings,
+) -> Result<()> {
+    let format: QuizFormat = format.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+
+    // Pre-flight checks
+    if let Err(e) = preflight::check(Operation::Ask, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let model = model.unwrap_or_else(|| settings.rag.model.clone());
+
+    let tool_context = ToolContext::new(orchestrator.vector_store(), orchestrator.embedder());
+    let generator = QuizGenerator::new(tool_context, &model)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.rag.llm));
+
+    let spinner = Output::spinner("Writing flashcards...");
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(generator.generate(video_id, count)).await;
+    orchestrator.record_usage("quiz", Some(video_id), &tracker).await;
+
+    spinner.finish_and_clear();
+
+    let quiz = match result {
+        Ok(quiz) => quiz,
+        Err(e) => {
+            Output::error(&format!("Failed to generate quiz: {}", e));
+            return Err(e.into());
+        }
+    };
+
+    let rendered = quiz.render(format)?;
+
+    match output {
+        Some(path) if path != "-" => {
+            std::fs::write(&path, &rendered)?;
+            Output::success(&format!(
+                "Wrote {} cards for '{}' to {}",
+                quiz.cards.len(),
+                quiz.video_title,
+                path
+            ));
+            if format == QuizFormat::Anki {
+                Output::info("Import it in Anki with File > Import.");
+            }
+        }
+        _ => println!("{}", rendered),
+    }
+
+    Ok(())
+}
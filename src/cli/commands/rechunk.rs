@@ -1,17 +1,19 @@
 //! Rechunk command implementation.
 
+use crate::cli::cancel::ctrl_c_token;
 use crate::cli::Output;
 use crate::config::Settings;
+use crate::error::LyttError;
 use crate::orchestrator::Orchestrator;
 use anyhow::Result;
 
 /// Run the rechunk command.
 pub async fn run_rechunk(video_id: &str, settings: Settings) -> Result<()> {
-    let orchestrator = Orchestrator::new(settings)?;
+    let orchestrator = Orchestrator::new(settings)?.with_cancellation(ctrl_c_token());
 
     if video_id == "all" {
         // Rechunk all videos with stored transcripts
-        let videos = orchestrator.list_rechunkable()?;
+        let videos = orchestrator.list_rechunkable().await?;
 
         if videos.is_empty() {
             Output::warning("No videos with stored transcripts found.");
@@ -22,30 +24,7 @@ pub async fn run_rechunk(video_id: &str, settings: Settings) -> Result<()> {
 
         Output::info(&format!("Found {} videos to rechunk", videos.len()));
         println!();
-
-        let mut success_count = 0;
-        let mut error_count = 0;
-
-        for (i, (vid_id, title, _duration)) in videos.iter().enumerate() {
-            Output::info(&format!("[{}/{}] Rechunking: {}", i + 1, videos.len(), title));
-
-            match orchestrator.rechunk_media(vid_id).await {
-                Ok(result) => {
-                    Output::success(&format!("  Rechunked ({} chunks)", result.chunks_indexed));
-                    success_count += 1;
-                }
-                Err(e) => {
-                    Output::error(&format!("  Failed: {}", e));
-                    error_count += 1;
-                }
-            }
-        }
-
-        println!();
-        Output::info(&format!(
-            "Rechunking complete: {} succeeded, {} failed",
-            success_count, error_count
-        ));
+        rechunk_each(&orchestrator, &videos).await?;
     } else {
         // Rechunk single video
         Output::info(&format!("Rechunking video: {}", video_id));
@@ -70,3 +49,61 @@ pub async fn run_rechunk(video_id: &str, settings: Settings) -> Result<()> {
 
     Ok(())
 }
+
+/// Rechunk only media chunked with other chunking settings or prompts than
+/// the configured ones.
+pub async fn run_rechunk_stale(settings: Settings) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?.with_cancellation(ctrl_c_token());
+
+    let stale = orchestrator.list_stale().await?;
+    if stale.is_empty() {
+        Output::success("All stored transcripts are chunked with the current settings and prompts.");
+        return Ok(());
+    }
+
+    let total = orchestrator.list_rechunkable().await?.len();
+    Output::info(&format!("{} of {} videos were chunked with an older configuration", stale.len(), total));
+    println!();
+    rechunk_each(&orchestrator, &stale).await?;
+
+    Ok(())
+}
+
+/// Rechunk each (video_id, title, duration), reporting progress and carrying
+/// on past failures until cancelled.
+async fn rechunk_each(orchestrator: &Orchestrator, videos: &[(String, String, f64)]) -> Result<()> {
+    let mut success_count = 0;
+    let mut error_count = 0;
+    let mut cancelled = false;
+
+    for (i, (vid_id, title, _duration)) in videos.iter().enumerate() {
+        Output::info(&format!("[{}/{}] Rechunking: {}", i + 1, videos.len(), title));
+
+        match orchestrator.rechunk_media(vid_id).await {
+            Ok(result) => {
+                Output::success(&format!("  Rechunked ({} chunks)", result.chunks_indexed));
+                success_count += 1;
+            }
+            Err(LyttError::Cancelled) => {
+                Output::warning(&format!("  Cancelled; {} videos not rechunked", videos.len() - i));
+                cancelled = true;
+                break;
+            }
+            Err(e) => {
+                Output::error(&format!("  Failed: {}", e));
+                error_count += 1;
+            }
+        }
+    }
+
+    println!();
+    Output::info(&format!(
+        "Rechunking complete: {} succeeded, {} failed",
+        success_count, error_count
+    ));
+
+    if cancelled {
+        return Err(LyttError::Cancelled.into());
+    }
+    Ok(())
+}
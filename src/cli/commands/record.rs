@@ -0,0 +1,150 @@
+//! Record command implementation.
+
+use crate::audio::Capture;
+use crate::audio_source::{LocalSource, MediaMetadata};
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use crate::transcription::{Transcript, TranscriptSegment};
+use crate::usage::UsageTracker;
+use anyhow::Result;
+use chrono::{Local, Utc};
+use std::path::Path;
+use std::time::Duration;
+
+/// How often to look for finished segments.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Run the record command.
+pub async fn run_record(title: Option<&str>, device: Option<&str>, tags: &[String], mut settings: Settings) -> Result<()> {
+    if let Err(e) = preflight::check(Operation::Transcribe, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+    if let Some(device) = device {
+        settings.record.device = device.to_string();
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let started_at = Utc::now();
+    let recording = settings
+        .recordings_dir()
+        .join(format!("recording-{}.mp3", Local::now().format("%Y-%m-%d-%H%M%S")));
+    let segments_dir = tempfile::tempdir_in(settings.temp_dir())?;
+
+    let mut capture = match Capture::start(&settings.record, &recording, segments_dir.path()).await {
+        Ok(capture) => capture,
+        Err(e) => {
+            Output::error(&e.to_string());
+            Output::info("Set [record] input_format and device to choose another microphone.");
+            return Err(e.into());
+        }
+    };
+    Output::info(&format!("Recording to {}. Press Ctrl+C to stop.", recording.display()));
+    println!();
+
+    let tracker = UsageTracker::new();
+    let segment_seconds = capture.segment_seconds() as f64;
+    let mut segments: Vec<TranscriptSegment> = Vec::new();
+    let mut transcribed = 0;
+
+    let stop = tokio::signal::ctrl_c();
+    tokio::pin!(stop);
+    loop {
+        tokio::select! {
+            biased;
+            _ = &mut stop => break,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {
+                for path in capture.finished_segments() {
+                    let offset = transcribed as f64 * segment_seconds;
+                    segments.extend(transcribe_segment(&orchestrator, &tracker, &path, offset).await);
+                    transcribed += 1;
+                }
+                if !capture.is_running() {
+                    Output::warning("The recording stopped unexpectedly.");
+                    break;
+                }
+            }
+        }
+    }
+
+    println!();
+    let spinner = Output::spinner("Finishing the recording...");
+    let rest = capture.stop().await;
+    spinner.finish_and_clear();
+    for path in rest? {
+        let offset = transcribed as f64 * segment_seconds;
+        segments.extend(transcribe_segment(&orchestrator, &tracker, &path, offset).await);
+        transcribed += 1;
+    }
+
+    if segments.is_empty() {
+        Output::warning(&format!("Nothing was transcribed; the audio is kept at {}", recording.display()));
+        return Ok(());
+    }
+
+    let duration = segments.last().map_or(0.0, |s| s.end_seconds);
+    let metadata = LocalSource::file_metadata(&recording, title, Some(started_at), duration)
+        .map_err(|e| LyttError::AudioDownload(format!("The recording wasn't saved to {}: {}", recording.display(), e)))?;
+    orchestrator.record_usage("record", Some(&metadata.id), &tracker).await;
+    let transcript = Transcript::new(metadata.id.clone(), segments);
+
+    Output::info(&format!("Indexing {}", metadata.title));
+    match orchestrator.index_transcript(metadata, transcript).await {
+        Ok(result) => {
+            if !tags.is_empty() {
+                if let Err(e) = orchestrator.sqlite_store().add_tags(&result.media_id, tags) {
+                    Output::warning(&format!("Failed to tag '{}': {}", result.media_id, e));
+                }
+            }
+            Output::success(&format!(
+                "Indexed '{}' as {} ({} chunks)",
+                result.title, result.media_id, result.chunks_indexed
+            ));
+            Ok(())
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to index the recording (kept at {}): {}", recording.display(), e));
+            Err(e.into())
+        }
+    }
+}
+
+/// Transcribe one segment, print its text and return its segments shifted
+/// to where it starts in the recording. Failures are shown and skipped so
+/// the recording goes on.
+async fn transcribe_segment(
+    orchestrator: &Orchestrator,
+    tracker: &UsageTracker,
+    path: &Path,
+    offset: f64,
+) -> Vec<TranscriptSegment> {
+    let transcriber = orchestrator.transcriber();
+    let result = tracker.scope(transcriber.transcribe(path)).await;
+    let _ = std::fs::remove_file(path);
+
+    match result {
+        Ok(transcript) => transcript
+            .segments
+            .into_iter()
+            .filter(|s| !s.text.trim().is_empty())
+            .map(|s| {
+                let segment = TranscriptSegment::new(s.start_seconds + offset, s.end_seconds + offset, s.text);
+                println!("[{}] {}", MediaMetadata::format_timestamp(segment.start_seconds), segment.text.trim());
+                segment
+            })
+            .collect(),
+        Err(e) => {
+            Output::warning(&format!(
+                "Couldn't transcribe {} from {}: {}",
+                MediaMetadata::format_timestamp(offset),
+                path.display(),
+                e
+            ));
+            Vec::new()
+        }
+    }
+}
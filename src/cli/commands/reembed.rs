@@ -0,0 +1,51 @@
+//! Reembed command implementation.
+
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::orchestrator::Orchestrator;
+use anyhow::Result;
+
+/// Run the reembed command.
+pub async fn run_reembed(local: bool, yes: bool, settings: Settings) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?;
+    let store = orchestrator.sqlite_store();
+
+    let count = orchestrator.vector_store().document_count().await?;
+    if count == 0 {
+        Output::info("The library is empty; nothing to re-embed.");
+        return Ok(());
+    }
+
+    let configured = orchestrator.configured_embedding();
+    let indexed = store.embedding_info()?;
+    Output::kv("Indexed with", &indexed.map_or_else(|| "unknown".to_string(), |i| i.to_string()));
+    Output::kv("Configured", &configured.to_string());
+    Output::kv("Vector format", &orchestrator.settings().vector_store.vector_format);
+
+    let action = if local { "Rewrite" } else { "Re-embed" };
+    if !yes && !Output::confirm(&format!("{} {} documents as {}?", action, count, configured))? {
+        Output::info("Cancelled.");
+        return Ok(());
+    }
+
+    let pb = Output::progress_bar(count as u64, "Re-embedding");
+    let result = orchestrator
+        .reembed_library(local, &mut |done, total| {
+            pb.set_length(total as u64);
+            pb.set_position(done as u64);
+        })
+        .await;
+    pb.finish_and_clear();
+
+    match result {
+        Ok(done) => {
+            Output::success(&format!("Rewrote {} documents as {}", done, configured));
+            Ok(())
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to re-embed: {}", e));
+            Output::info("Documents re-embedded so far are kept; run 'lytt reembed' again to finish.");
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Rollup command implementation.
+
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::rollup::{channel_tag, RollupGenerator, RollupPeriod};
+use crate::usage::UsageTracker;
+use anyhow::Result;
+
+/// Run the rollup command.
+pub async fn run_rollup(
+    tag: Option<String>,
+    channel: Option<String>,
+    period: &str,
+    model: Option<String>,
+    list: bool,
+    settings: Settings,
+) -> Result<()> {
+    if list {
+        return list_rollups(settings);
+    }
+
+    let period: RollupPeriod = period.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+
+    let tag = match (tag, channel) {
+        (Some(tag), _) => tag,
+        (None, Some(channel)) => channel_tag(&channel),
+        (None, None) => return Err(LyttError::InvalidInput("Either --tag or --channel is required".to_string()).into()),
+    };
+
+    // Pre-flight checks
+    if let Err(e) = preflight::check(Operation::Rollup, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let model = model.unwrap_or_else(|| settings.rollup.model.clone());
+
+    let generator = RollupGenerator::new(orchestrator.sqlite_store(), orchestrator.embedder(), &model)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.rollup.llm))
+        .with_max_input_chars(settings.rollup.max_input_chars);
+
+    let spinner = Output::spinner(&format!("Generating {} rollup for '{}'...", period, tag));
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(generator.generate(&tag, period)).await;
+    orchestrator.record_usage("rollup", None, &tracker).await;
+
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(rollup) => {
+            println!("\n{}\n", rollup.content);
+            Output::success(&format!(
+                "Stored and indexed '{}' ({} media)",
+                rollup.id,
+                rollup.source_video_ids.len()
+            ));
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to generate rollup: {}", e));
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// List stored rollups.
+fn list_rollups(settings: Settings) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?;
+    let rollups = orchestrator.sqlite_store().list_rollups()?;
+
+    if rollups.is_empty() {
+        Output::info("No rollups yet. Use 'lytt rollup --tag <tag>' to create one.");
+        return Ok(());
+    }
+
+    Output::header(&format!("Rollups ({})", rollups.len()));
+    println!();
+    for rollup in &rollups {
+        Output::kv(
+            &rollup.id,
+            &format!(
+                "{} media, generated {}",
+                rollup.source_video_ids.len(),
+                rollup.created_at.format("%Y-%m-%d %H:%M")
+            ),
+        );
+    }
+
+    Ok(())
+}
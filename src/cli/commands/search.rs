@@ -2,29 +2,68 @@
 
 use crate::cli::Output;
 use crate::config::Settings;
-use crate::embedding::OpenAIEmbedder;
+use crate::error::LyttError;
 use crate::orchestrator::Orchestrator;
-use crate::rag::context::ContextBuilder;
+use crate::rag::{ContextBuilder, QueryExpander, RecencyRanking};
+use crate::transcription::language_code;
+use crate::vector_store::{DerivedFilter, SearchFilter};
 use anyhow::Result;
-use std::sync::Arc;
 
 /// Run the search command.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_search(
     query: &str,
     limit: usize,
     min_score: f32,
-    settings: Settings,
+    derived: &str,
+    keyword: Option<&str>,
+    topic: Option<i64>,
+    series: Option<&str>,
+    language: Option<&str>,
+    recent: bool,
+    mut settings: Settings,
 ) -> Result<()> {
+    if recent {
+        settings.rag.recency.enabled = true;
+    }
+    let derived: DerivedFilter = derived.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+
     let orchestrator = Orchestrator::new(settings.clone())?;
+    if let Err(e) = orchestrator.check_embedding().await {
+        Output::error(&e.to_string());
+        return Err(e.into());
+    }
 
-    let embedder = Arc::new(OpenAIEmbedder::with_config(
-        &settings.embedding.model,
-        settings.embedding.dimensions as usize,
-    ));
+    let embedder = orchestrator.embedder();
+
+    let mut filter = SearchFilter::default().with_derived(derived);
+    if let Some(keyword) = keyword {
+        filter = filter.with_keyword(keyword);
+    }
+    if let Some(topic) = topic {
+        filter = filter.with_topic(topic);
+    }
+    if let Some(series) = series {
+        if !orchestrator.sqlite_store().has_series(series)? {
+            Output::error(&format!("No series named '{}'. See 'lytt series ls'.", series));
+            return Err(LyttError::InvalidInput(format!("no series named {}", series)).into());
+        }
+        filter = filter.with_series(series);
+    }
+    if let Some(language) = language {
+        filter = filter.with_language(language_code(language).unwrap_or_else(|| language.to_string()));
+    }
 
     let context_builder = ContextBuilder::new(orchestrator.vector_store(), embedder)
         .with_max_chunks(limit)
-        .with_min_score(min_score);
+        .with_min_score(min_score)
+        .with_filter(filter)
+        .with_expansion(QueryExpander::from_settings(&settings.rag, &settings.rag.model, orchestrator.prompts()))
+        .with_recency(RecencyRanking::from_settings(&settings.rag))
+        .with_diversity(settings.rag.diversity);
 
     let spinner = Output::spinner("Searching...");
 
@@ -40,9 +79,10 @@ pub async fn run_search(
 
                 for chunk in &chunks {
                     Output::search_result(
-                        &chunk.video_title,
+                        &chunk.source_label(),
                         &chunk.timestamp,
                         chunk.score,
+                        chunk.summary.as_deref(),
                         &chunk.content,
                         chunk.url.as_deref(),
                     );
@@ -0,0 +1,149 @@
+//! Series command implementation.
+
+use crate::cli::output::{format_duration, truncate};
+use crate::cli::{Output, SeriesAction};
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::vector_store::SqliteVectorStore;
+use anyhow::Result;
+use console::style;
+
+/// Run the series command.
+pub fn run_series(action: &SeriesAction, settings: &Settings) -> Result<()> {
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+    match action {
+        SeriesAction::Ls { json } => list(&store, *json),
+        SeriesAction::Show { name, json } => show(&store, name, *json),
+        SeriesAction::Add {
+            name,
+            video_ids,
+            episode,
+            description,
+        } => add(&store, name, video_ids, *episode, description.as_deref()),
+        SeriesAction::Remove { video_ids } => remove(&store, video_ids),
+        SeriesAction::Delete { name } => {
+            if store.delete_series(name)? {
+                Output::success(&format!("Deleted series '{}'", name));
+                Ok(())
+            } else {
+                Err(not_found(name))
+            }
+        }
+    }
+}
+
+fn not_found(name: &str) -> anyhow::Error {
+    Output::error(&format!("No series named '{}'. See 'lytt series ls'.", name));
+    LyttError::InvalidInput(format!("no series named {}", name)).into()
+}
+
+fn list(store: &SqliteVectorStore, json: bool) -> Result<()> {
+    let series = store.list_series()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&series)?);
+        return Ok(());
+    }
+    if series.is_empty() {
+        Output::info("No series yet. Create one with 'lytt series add <name> <id>...' or 'lytt transcribe --series'.");
+        return Ok(());
+    }
+
+    Output::header("Series");
+    println!();
+    println!(
+        "  {:<32} {:>8} {:>12} {:>7} {:>10} {:>6}",
+        style("Series").bold(),
+        style("Episodes").bold(),
+        style("Duration").bold(),
+        style("Chunks").bold(),
+        style("Retrieved").bold(),
+        style("Cited").bold()
+    );
+    for s in &series {
+        println!(
+            "  {:<32} {:>8} {:>12} {:>7} {:>10} {:>6}",
+            truncate(&s.name, 32),
+            s.episodes,
+            format_duration(s.duration_seconds),
+            s.chunk_count,
+            s.retrievals,
+            s.citations
+        );
+    }
+    Ok(())
+}
+
+fn show(store: &SqliteVectorStore, name: &str, json: bool) -> Result<()> {
+    let Some(stats) = store.list_series()?.into_iter().find(|s| s.name == name) else {
+        return Err(not_found(name));
+    };
+    let episodes = store.series_episodes(name)?;
+
+    if json {
+        let detail = serde_json::json!({ "series": stats, "episodes": episodes });
+        println!("{}", serde_json::to_string_pretty(&detail)?);
+        return Ok(());
+    }
+
+    Output::header(&stats.name);
+    if let Some(description) = &stats.description {
+        println!("{}", style(description).italic());
+    }
+    println!();
+    Output::kv("Episodes", &stats.episodes.to_string());
+    Output::kv("Duration", &format_duration(stats.duration_seconds));
+    Output::kv("Chunks", &stats.chunk_count.to_string());
+    Output::kv("Retrievals", &stats.retrievals.to_string());
+    Output::kv("Citations", &stats.citations.to_string());
+
+    if !episodes.is_empty() {
+        println!();
+        for e in &episodes {
+            println!(
+                "  {:>3}. {:<44} {} {:>10} {:>5} chunks, {} cited",
+                e.episode,
+                truncate(&e.video_title, 44),
+                style(format!("[{}]", e.video_id)).dim(),
+                format_duration(e.duration_seconds),
+                e.chunk_count,
+                e.citations
+            );
+        }
+    }
+    Ok(())
+}
+
+fn add(
+    store: &SqliteVectorStore,
+    name: &str,
+    video_ids: &[String],
+    episode: Option<u32>,
+    description: Option<&str>,
+) -> Result<()> {
+    if episode.is_some() && video_ids.len() > 1 {
+        Output::error("--episode numbers a single media item");
+        return Err(LyttError::InvalidInput("--episode with several media IDs".to_string()).into());
+    }
+
+    for video_id in video_ids {
+        if !store.has_transcript(video_id)? {
+            Output::warning(&format!("{} has no stored transcript; adding it anyway", video_id));
+        }
+        let number = store.add_to_series(name, video_id, episode)?;
+        Output::success(&format!("Added {} to '{}' as episode {}", video_id, name, number));
+    }
+    if let Some(description) = description {
+        store.describe_series(name, description)?;
+    }
+    Ok(())
+}
+
+fn remove(store: &SqliteVectorStore, video_ids: &[String]) -> Result<()> {
+    for video_id in video_ids {
+        match store.remove_from_series(video_id)? {
+            Some(series) => Output::success(&format!("Removed {} from '{}'", video_id, series)),
+            None => Output::info(&format!("{} isn't in a series.", video_id)),
+        }
+    }
+    Ok(())
+}
@@ -2,35 +2,104 @@
 //!
 //! Provides REST endpoints for transcription, search, and RAG queries.
 
+use crate::audio_source::LocalSource;
+use crate::auth::{AccessControl, AccessDenied};
 use crate::cli::Output;
-use crate::config::Settings;
-use crate::embedding::{Embedder, OpenAIEmbedder};
+use crate::config::{RagSettings, Settings};
+use crate::daemon::{Daemon, DaemonStatus};
+use crate::digest::spawn_schedule;
+use crate::error::LyttError;
+use crate::llm::ChatClient;
+use crate::logging;
 use crate::orchestrator::Orchestrator;
-use crate::rag::RagEngine;
+use crate::rag::{
+    AnswerVerifier, Citation, Confidence, ConfidenceScale, ContextChunk, QueryExpander, RagEngine, RecencyRanking, Verdict, Verification,
+};
+use crate::vector_store::{cosine_similarity, DerivedFilter, DocType, Document, PartialResults, SearchFilter};
+use crate::transcription::{language_code, LiveTranscriber, Transcript};
+use crate::usage::UsageTracker;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Json, Router,
 };
+use chrono::{Local, Utc};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tower_http::cors::{Any, CorsLayer};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityRequirement, SecurityScheme};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
 /// Shared application state.
 struct AppState {
-    orchestrator: Orchestrator,
+    orchestrator: Arc<Orchestrator>,
     settings: Settings,
+    /// Scheduled jobs, when running as `lytt daemon`.
+    daemon: Option<Arc<Daemon>>,
 }
 
 /// Run the HTTP API server.
-pub async fn run_serve(host: &str, port: u16, settings: Settings) -> anyhow::Result<()> {
-    let orchestrator = Orchestrator::new(settings.clone())?;
+pub async fn run_serve(
+    host: &str,
+    port: u16,
+    replica_of: Option<&str>,
+    grpc_port: Option<u16>,
+    mut settings: Settings,
+) -> anyhow::Result<()> {
+    // A replica is kept up to date by Litestream/LiteFS; never write to it
+    if let Some(replica) = replica_of {
+        settings.vector_store.sqlite_path = replica.to_string();
+        settings.vector_store.read_only = true;
+    }
+
+    let orchestrator = Arc::new(Orchestrator::new(settings)?);
+    orchestrator.spawn_checkpoints();
+    serve(host, port, grpc_port, orchestrator, None).await
+}
+
+/// Serve the REST API (and the gRPC API, if a port is given) until stopped.
+/// With a daemon, its status is served at `/status`; without one, digests
+/// are scheduled from `[digest] schedule`.
+pub(crate) async fn serve(
+    host: &str,
+    port: u16,
+    grpc_port: Option<u16>,
+    orchestrator: Arc<Orchestrator>,
+    daemon: Option<Arc<Daemon>>,
+) -> anyhow::Result<()> {
+    if grpc_port.is_some() && !cfg!(feature = "grpc") {
+        return Err(LyttError::Config(
+            "gRPC support is not compiled in. Rebuild with: cargo install lytt --features grpc".to_string(),
+        )
+        .into());
+    }
+
+    let settings = orchestrator.settings().clone();
+    let read_only = settings.vector_store.read_only;
+    let db_path = settings.sqlite_path();
+
+    let digests = match daemon {
+        Some(_) => None,
+        None => spawn_schedule(orchestrator.clone())?,
+    };
 
     let state = Arc::new(AppState {
-        orchestrator,
+        orchestrator: orchestrator.clone(),
         settings,
+        daemon: daemon.clone(),
     });
 
     let cors = CorsLayer::new()
@@ -38,41 +107,256 @@ pub async fn run_serve(host: &str, port: u16, settings: Settings) -> anyhow::Res
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let app = Router::new()
-        .route("/health", get(health))
-        .route("/transcribe", post(transcribe))
+    let access = Arc::new(AccessControl::from_settings(&state.settings.server));
+
+    // Everything except /health requires a key when keys are configured
+    let mut api = Router::new();
+    if !read_only {
+        api = api.route("/transcribe", post(transcribe)).route("/stream", get(stream));
+    }
+    if daemon.is_some() {
+        api = api.route("/status", get(status));
+    }
+    let api = api
         .route("/search", post(search))
         .route("/ask", post(ask))
+        .route("/ask/stream", post(ask_stream))
         .route("/media", get(list_media))
         .route("/media/{video_id}", get(get_media))
+        .route("/media/{video_id}/summary", get(get_summary))
+        .route("/media/{video_id}/heatmap", get(get_heatmap))
+        .route("/media/{video_id}/keywords", get(get_keywords))
+        .route("/media/{video_id}/history", get(get_history))
+        .route_layer(middleware::from_fn_with_state(access.clone(), require_access));
+
+    let app = Router::new()
+        .route("/health", get(health))
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", openapi_spec(&access, read_only, daemon.is_some())))
+        .merge(api)
         .layer(cors)
+        .layer(middleware::from_fn(trace_request))
         .with_state(state);
 
     let addr = format!("{}:{}", host, port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
-    Output::header("Lytt API Server");
+    Output::header(if daemon.is_some() { "Lytt Daemon" } else { "Lytt API Server" });
     println!();
     Output::success(&format!("Listening on http://{}", addr));
+    if read_only {
+        Output::info(&format!("Read-only replica of {}", db_path.display()));
+    }
+    if access.requires_key() {
+        Output::info("API key required (Authorization: Bearer <key>).");
+    } else if !is_loopback(host) {
+        Output::warning("No [server] api_keys configured; anyone who can reach this address can use your API budget.");
+    }
     println!();
     println!("Endpoints:");
     Output::kv("Health", "GET  /health");
-    Output::kv("Transcribe", "POST /transcribe");
+    if daemon.is_some() {
+        Output::kv("Status", "GET  /status");
+    }
+    Output::kv("API docs", "GET  /docs (spec: /openapi.json)");
+    if !read_only {
+        Output::kv("Transcribe", "POST /transcribe");
+        Output::kv("Live transcription", "GET  /stream (WebSocket)");
+    }
     Output::kv("Search", "POST /search");
     Output::kv("Ask (RAG)", "POST /ask");
+    Output::kv("Ask (streaming)", "POST /ask/stream");
     Output::kv("List Media", "GET  /media");
     Output::kv("Get Media", "GET  /media/:video_id");
+    Output::kv("Get Summary", "GET  /media/:video_id/summary");
+    Output::kv("Get Keywords", "GET  /media/:video_id/keywords");
+    Output::kv("Get History", "GET  /media/:video_id/history");
+    if let Some(grpc_port) = grpc_port {
+        Output::kv("gRPC", &format!("{}:{} (lytt.v1.Lytt)", host, grpc_port));
+    }
+    if let Some(daemon) = &daemon {
+        println!();
+        println!("Jobs:");
+        let mut any = false;
+        for (job, schedule) in daemon.jobs() {
+            Output::kv(&job.to_string(), schedule.expression());
+            any = true;
+        }
+        if !any {
+            Output::info("No jobs configured; see [daemon] in the config.");
+        }
+    }
+    if digests.is_some() {
+        println!();
+        Output::info(&format!(
+            "Writing {} digests to {}",
+            orchestrator.settings().digest.schedule.trim(),
+            orchestrator.settings().digests_dir().display()
+        ));
+    }
     println!();
     Output::info("Press Ctrl+C to stop the server.");
 
-    axum::serve(listener, app).await?;
+    let rest = async { axum::serve(listener, app).await.map_err(anyhow::Error::from) };
+    match grpc_port {
+        Some(grpc_port) => {
+            tokio::try_join!(rest, serve_grpc(host, grpc_port, orchestrator, access))?;
+        }
+        None => rest.await?,
+    }
 
     Ok(())
 }
 
+/// Serve the gRPC API next to the REST API.
+#[cfg(feature = "grpc")]
+async fn serve_grpc(
+    host: &str,
+    port: u16,
+    orchestrator: Arc<Orchestrator>,
+    access: Arc<AccessControl>,
+) -> anyhow::Result<()> {
+    let addr = tokio::net::lookup_host((host, port))
+        .await?
+        .next()
+        .ok_or_else(|| LyttError::InvalidInput(format!("Could not resolve {}", host)))?;
+    crate::grpc::serve(addr, orchestrator, access).await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn serve_grpc(
+    _host: &str,
+    _port: u16,
+    _orchestrator: Arc<Orchestrator>,
+    _access: Arc<AccessControl>,
+) -> anyhow::Result<()> {
+    Err(LyttError::Config("gRPC support is not compiled in".to_string()).into())
+}
+
+/// OpenAPI description of the REST API.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Lytt API",
+        description = "Transcription, semantic search and question answering over your audio library."
+    ),
+    paths(health, status, transcribe, search, ask, ask_stream, list_media, get_media, get_summary, get_heatmap, get_keywords, get_history)
+)]
+struct ApiDoc;
+
+/// The OpenAPI spec for this server's configuration.
+fn openapi_spec(access: &AccessControl, read_only: bool, daemon: bool) -> utoipa::openapi::OpenApi {
+    let mut spec = ApiDoc::openapi();
+
+    if read_only {
+        spec.paths.paths.remove("/transcribe");
+    }
+    if !daemon {
+        spec.paths.paths.remove("/status");
+    }
+
+    if access.requires_key() {
+        if let Some(components) = spec.components.as_mut() {
+            components.add_security_scheme("bearer", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+        }
+        spec.security = Some(vec![SecurityRequirement::new("bearer", Vec::<String>::new())]);
+    }
+
+    spec
+}
+
+fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Run ID of a request: taken from the request when the client sets it, and
+/// always returned on the response.
+const X_REQUEST_ID: header::HeaderName = header::HeaderName::from_static("x-request-id");
+
+/// Handle each request under its own run ID, in a `request` span, so what
+/// it logs (through the orchestrator and transcription) can be correlated.
+async fn trace_request(request: Request, next: Next) -> Response {
+    let run_id = request
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= 64)
+        .map(str::to_string)
+        .unwrap_or_else(logging::new_run_id);
+    let span = tracing::info_span!(
+        parent: None,
+        "request",
+        run_id = %run_id,
+        method = %request.method(),
+        path = %request.uri().path()
+    );
+
+    let started = Instant::now();
+    let mut response = logging::scope(run_id.clone(), span.clone(), next.run(request)).await;
+    span.in_scope(|| {
+        tracing::info!(
+            status = response.status().as_u16(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Handled request"
+        )
+    });
+    if let Ok(value) = header::HeaderValue::from_str(&run_id) {
+        response.headers_mut().insert(X_REQUEST_ID, value);
+    }
+    response
+}
+
+/// Alternative to `Authorization: Bearer <key>`.
+const X_API_KEY: header::HeaderName = header::HeaderName::from_static("x-api-key");
+
+/// Reject requests without a valid API key or over their rate limit.
+async fn require_access(
+    State(access): State<Arc<AccessControl>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let checked = {
+        let header_value = |name| request.headers().get(name).and_then(|v| v.to_str().ok());
+        // Browsers can't set headers on WebSocket connections
+        let query_key = header_value(header::UPGRADE)
+            .is_some_and(|u| u.eq_ignore_ascii_case("websocket"))
+            .then(|| query_param(request.uri().query(), "api_key"))
+            .flatten();
+        access.check(header_value(header::AUTHORIZATION), header_value(X_API_KEY).or(query_key.as_deref()))
+    };
+
+    match checked {
+        Ok(()) => next.run(request).await,
+        Err(denied @ AccessDenied::Unauthorized) => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer".to_string())],
+            Json(ErrorResponse {
+                error: denied.to_string(),
+            }),
+        )
+            .into_response(),
+        Err(denied @ AccessDenied::RateLimited { retry_after }) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.as_secs().max(1).to_string())],
+            Json(ErrorResponse {
+                error: denied.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// A parameter from a query string, percent-decoded.
+fn query_param(query: Option<&str>, name: &str) -> Option<String> {
+    url::form_urlencoded::parse(query?.as_bytes()).find(|(key, _)| key == name).map(|(_, value)| value.into_owned())
+}
+
 // === Request/Response Types ===
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct TranscribeRequest {
     /// YouTube URL/ID or local file path
     input: String,
@@ -81,7 +365,7 @@ struct TranscribeRequest {
     force: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct TranscribeResponse {
     success: bool,
     media_id: String,
@@ -92,12 +376,49 @@ struct TranscribeResponse {
 }
 
 #[derive(Deserialize)]
+struct StreamQuery {
+    /// Sample rate of the PCM frames, in Hz
+    #[serde(default = "default_sample_rate")]
+    sample_rate: u32,
+    /// Title to index the session under (default: generated from the transcript)
+    title: Option<String>,
+    /// Index the session as media when the stream ends
+    #[serde(default = "default_index")]
+    index: bool,
+}
+
+fn default_sample_rate() -> u32 {
+    16000
+}
+
+/// Sample rates `/stream` accepts, in Hz.
+const MIN_SAMPLE_RATE: u32 = 8000;
+const MAX_SAMPLE_RATE: u32 = 48000;
+
+fn default_index() -> bool {
+    true
+}
+
+#[derive(Deserialize, ToSchema)]
 struct SearchRequest {
     query: String,
     #[serde(default = "default_limit")]
     limit: usize,
     #[serde(default = "default_min_score")]
     min_score: f32,
+    /// Derived documents (rollups, summaries): include, exclude, or only.
+    #[serde(default)]
+    #[schema(value_type = String, example = "include")]
+    derived: DerivedFilter,
+    /// Latency budget; the best results found within it are returned.
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    /// Only chunks tagged with this extracted keyword.
+    #[serde(default)]
+    keyword: Option<String>,
+    /// Only media in this spoken language (ISO 639-1 code).
+    #[serde(default)]
+    language: Option<String>,
 }
 
 fn default_limit() -> usize {
@@ -108,43 +429,134 @@ fn default_min_score() -> f32 {
     0.3
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct SearchResponse {
     results: Vec<SearchResult>,
+    /// True if the latency budget ran out before the whole library was searched.
+    partial: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct SearchResult {
     video_id: String,
     video_title: String,
     chunk_title: String,
+    /// Section summary, if the chunker wrote one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
     content: String,
     start_seconds: f64,
     end_seconds: f64,
     timestamp: String,
     score: f32,
+    /// "source" for transcript content, "derived" for rollups and summaries.
+    #[schema(value_type = String, example = "source")]
+    doc_type: DocType,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct AskRequest {
     question: String,
+    /// Maximum context chunks; only used when `rag.context_tokens` is 0.
     #[serde(default = "default_max_chunks")]
     max_chunks: usize,
     #[serde(default)]
     model: Option<String>,
+    /// Derived documents (rollups, summaries): include, exclude, or only.
+    #[serde(default)]
+    #[schema(value_type = String, example = "include")]
+    derived: DerivedFilter,
+    /// Generate a fresh answer instead of reusing a cached one.
+    #[serde(default)]
+    no_cache: bool,
+    /// Only search this video (by ID).
+    #[serde(default)]
+    video_id: Option<String>,
+    /// Check each claim against the sources (default: `rag.verify`).
+    #[serde(default)]
+    verify: Option<bool>,
 }
 
 fn default_max_chunks() -> usize {
     10
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct AskResponse {
+    /// Answer text with `[n]` citation markers.
     answer: String,
+    /// Sources used for the answer; marker `[n]` refers to the n-th.
     sources: Vec<SourceInfo>,
+    /// Sources the answer cites, in order of first mention.
+    citations: Vec<CitationInfo>,
+    /// Claim-by-claim grounding check, when verification is on.
+    verification: Option<VerificationInfo>,
+    /// Answer confidence, 0-1 (`null` when nothing relevant was found).
+    confidence: Option<ConfidenceInfo>,
+    /// Whether the answer was withheld because confidence was below
+    /// `rag.min_confidence` (or nothing relevant was found).
+    abstained: bool,
+    /// Whether the answer came from the answer cache.
+    cached: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
+struct ConfidenceInfo {
+    /// Combined confidence.
+    score: f32,
+    /// Confidence from the similarity of the retrieved excerpts.
+    retrieval: f32,
+    /// The model's own rating, when it gave one.
+    model: Option<f32>,
+}
+
+impl From<Confidence> for ConfidenceInfo {
+    fn from(confidence: Confidence) -> Self {
+        Self {
+            score: confidence.score,
+            retrieval: confidence.retrieval,
+            model: confidence.model,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+struct VerificationInfo {
+    /// Whether every claim is supported by the sources.
+    grounded: bool,
+    claims: Vec<ClaimInfo>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ClaimInfo {
+    claim: String,
+    /// supported, unsupported, or contradicted.
+    #[schema(value_type = String, example = "supported")]
+    verdict: Verdict,
+    /// Numbers (`[n]` markers) of the sources that support or contradict the claim.
+    sources: Vec<usize>,
+    explanation: String,
+}
+
+impl From<Verification> for VerificationInfo {
+    fn from(verification: Verification) -> Self {
+        Self {
+            grounded: verification.is_grounded(),
+            claims: verification
+                .claims
+                .into_iter()
+                .map(|c| ClaimInfo {
+                    claim: c.claim,
+                    verdict: c.verdict,
+                    sources: c.sources,
+                    explanation: c.explanation,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
 struct SourceInfo {
     video_id: String,
     video_title: String,
@@ -153,13 +565,78 @@ struct SourceInfo {
     content: String,
 }
 
+impl From<ContextChunk> for SourceInfo {
+    fn from(chunk: ContextChunk) -> Self {
+        Self {
+            video_id: chunk.video_id,
+            video_title: chunk.video_title,
+            timestamp: chunk.timestamp,
+            score: chunk.score,
+            content: chunk.content,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+struct CitationInfo {
+    /// The `[n]` marker in the answer.
+    marker: usize,
+    document_id: String,
+    video_id: String,
+    video_title: String,
+    /// Chapter the source starts in, for media with chapters.
+    chapter: Option<String>,
+    timestamp: String,
+    start_seconds: f64,
+    end_seconds: f64,
+    url: Option<String>,
+    /// Supporting quote, located in the source's `content`.
+    quote: Option<QuoteInfo>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct QuoteInfo {
+    text: String,
+    /// Byte offset of the quote in the source content.
+    start: usize,
+    /// Byte offset just past the quote.
+    end: usize,
+}
+
+impl From<Citation> for CitationInfo {
+    fn from(citation: Citation) -> Self {
+        Self {
+            marker: citation.marker,
+            document_id: citation.document_id.to_string(),
+            video_id: citation.video_id,
+            video_title: citation.video_title,
+            chapter: citation.chapter,
+            timestamp: citation.timestamp,
+            start_seconds: citation.start_seconds,
+            end_seconds: citation.end_seconds,
+            url: citation.url,
+            quote: citation.quote.map(|q| QuoteInfo {
+                text: q.text,
+                start: q.start,
+                end: q.end,
+            }),
+        }
+    }
+}
+
+/// Data of a streamed `token` event.
 #[derive(Serialize)]
+struct TokenEvent {
+    text: String,
+}
+
+#[derive(Serialize, ToSchema)]
 struct MediaListResponse {
     media: Vec<MediaInfo>,
     total: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct MediaInfo {
     video_id: String,
     video_title: String,
@@ -167,7 +644,7 @@ struct MediaInfo {
     total_duration_seconds: f64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct MediaDetailResponse {
     video_id: String,
     video_title: String,
@@ -176,25 +653,99 @@ struct MediaDetailResponse {
     chunks: Vec<ChunkInfo>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ChunkInfo {
     title: String,
+    /// Section summary, if the chunker wrote one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
     content: String,
     start_seconds: f64,
     end_seconds: f64,
 }
 
-#[derive(Serialize)]
+/// Default number of heatmap intervals.
+const DEFAULT_HEATMAP_BINS: usize = 100;
+
+/// Most heatmap intervals a request may ask for.
+const MAX_HEATMAP_BINS: usize = 1000;
+
+#[derive(Deserialize, IntoParams)]
+struct HeatmapQuery {
+    /// Score each interval against this query
+    query: Option<String>,
+    /// Number of equal-length intervals (default 100, max 1000)
+    bins: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct HeatmapResponse {
+    video_id: String,
+    video_title: String,
+    duration_seconds: f64,
+    interval_seconds: f64,
+    /// Chunk boundaries and titles, in order.
+    chunks: Vec<HeatmapChunk>,
+    /// Equal-length intervals covering the media.
+    intervals: Vec<HeatmapInterval>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct HeatmapChunk {
+    title: String,
+    start_seconds: f64,
+    end_seconds: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+struct HeatmapInterval {
+    start_seconds: f64,
+    end_seconds: f64,
+    /// Words per second relative to the densest interval (0.0-1.0).
+    density: f32,
+    /// Best similarity of an overlapping chunk to the query (only with `query`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relevance: Option<f32>,
+}
+
+#[derive(Serialize, ToSchema)]
 struct ErrorResponse {
     error: String,
 }
 
 // === Handlers ===
 
+#[utoipa::path(get, path = "/health", tag = "system",
+    responses((status = 200, description = "Server is up", body = Object, example = json!({"status": "ok"}))))]
 async fn health() -> impl IntoResponse {
     Json(serde_json::json!({ "status": "ok" }))
 }
 
+/// Status of the daemon's scheduled jobs (`lytt daemon` only).
+#[utoipa::path(get, path = "/status", tag = "daemon",
+    responses(
+        (status = 200, description = "Daemon and job status", body = DaemonStatus),
+        (status = 404, description = "Not running as a daemon", body = ErrorResponse)
+    ))]
+async fn status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match &state.daemon {
+        Some(daemon) => Json(daemon.status()).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Not running as a daemon".to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Transcribe and index media.
+#[utoipa::path(post, path = "/transcribe", tag = "media", request_body = TranscribeRequest,
+    responses(
+        (status = 200, description = "Media indexed (or already indexed)", body = TranscribeResponse),
+        (status = 500, description = "Processing failed", body = TranscribeResponse)
+    ))]
 async fn transcribe(
     State(state): State<Arc<AppState>>,
     Json(req): Json<TranscribeRequest>,
@@ -222,19 +773,170 @@ async fn transcribe(
     }
 }
 
+/// Live transcription over a WebSocket.
+///
+/// The client sends binary frames of 16-bit little-endian mono PCM and gets
+/// back `interim` and `final` segments as JSON text frames. Sending `stop`
+/// (or closing) finalizes the transcript; unless `index=false`, the session
+/// is then saved and indexed, and an `indexed` message names the new media.
+async fn stream(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<StreamQuery>,
+) -> Response {
+    if !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&query.sample_rate) {
+        let error = format!(
+            "sample_rate must be between {} and {} Hz, got {}",
+            MIN_SAMPLE_RATE, MAX_SAMPLE_RATE, query.sample_rate
+        );
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+    ws.on_upgrade(move |mut socket| async move {
+        if let Err(e) = stream_session(&mut socket, &state, &query).await {
+            let _ = send_json(&mut socket, serde_json::json!({ "type": "error", "message": e.to_string() })).await;
+        }
+        let _ = socket.send(Message::Close(None)).await;
+    })
+}
+
+async fn stream_session(socket: &mut WebSocket, state: &AppState, query: &StreamQuery) -> anyhow::Result<()> {
+    let record = &state.settings.record;
+    let mut live = LiveTranscriber::new(
+        state.orchestrator.transcriber(),
+        query.sample_rate,
+        record.interim_seconds,
+        record.segment_seconds,
+    );
+    let scratch_dir = tempfile::tempdir_in(state.settings.temp_dir())?;
+    let scratch = scratch_dir.path().join("pending.wav");
+    let started_at = Utc::now();
+    let tracker = UsageTracker::new();
+    let max_seconds = f64::from(record.max_stream_seconds);
+    let max_bytes = record.max_stream_mb as usize * 1024 * 1024;
+    let mut received = 0;
+    let limit_message = format!(
+        "Stream limit reached ({} seconds or {} MB); ending the session",
+        record.max_stream_seconds, record.max_stream_mb
+    );
+    // Idle clients are held to the same limit as ones sending audio
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(record.max_stream_seconds.into());
+
+    // The client may hang up instead of sending "stop"; the session is still kept
+    let mut open = true;
+    loop {
+        let message = match tokio::time::timeout_at(deadline, socket.recv()).await {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(_) => {
+                send_json(socket, serde_json::json!({ "type": "error", "message": limit_message })).await?;
+                let close = CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "stream limit reached".into(),
+                };
+                socket.send(Message::Close(Some(close))).await?;
+                open = false;
+                break;
+            }
+        };
+        match message? {
+            Message::Binary(pcm) => {
+                received += pcm.len();
+                let seconds = received as f64 / 2.0 / f64::from(query.sample_rate);
+                let elapsed = (Utc::now() - started_at).num_seconds() as f64;
+                if received > max_bytes || seconds > max_seconds || elapsed > max_seconds {
+                    // End the session with the audio received so far
+                    send_json(socket, serde_json::json!({ "type": "error", "message": limit_message })).await?;
+                    break;
+                }
+                live.push(&pcm);
+                for event in tracker.scope(live.poll(&scratch)).await? {
+                    send_json(socket, serde_json::to_value(&event)?).await?;
+                }
+            }
+            Message::Text(text) if is_stop(text.as_str()) => break,
+            Message::Close(_) => {
+                open = false;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let events = tracker.scope(live.finish(&scratch)).await;
+    state.orchestrator.record_usage("stream", None, &tracker).await;
+    for event in events? {
+        if open {
+            open = send_json(socket, serde_json::to_value(&event)?).await.is_ok();
+        }
+    }
+
+    if !query.index || live.segments().is_empty() {
+        return Ok(());
+    }
+    let dir = state.settings.recordings_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("stream-{}.wav", Local::now().format("%Y-%m-%d-%H%M%S")));
+    live.write_wav(&path)?;
+
+    let metadata = LocalSource::file_metadata(&path, query.title.as_deref(), Some(started_at), live.duration())?;
+    let transcript = Transcript::new(metadata.id.clone(), live.segments().to_vec());
+    let result = state.orchestrator.index_transcript(metadata, transcript).await?;
+    if open {
+        let indexed = serde_json::json!({
+            "type": "indexed",
+            "media_id": result.media_id,
+            "title": result.title,
+            "chunks_indexed": result.chunks_indexed,
+        });
+        send_json(socket, indexed).await?;
+    }
+    Ok(())
+}
+
+async fn send_json(socket: &mut WebSocket, value: serde_json::Value) -> Result<(), axum::Error> {
+    socket.send(Message::Text(value.to_string().into())).await
+}
+
+/// Whether a text frame asks to end the stream: `stop` or `{"type": "stop"}`.
+fn is_stop(text: &str) -> bool {
+    text.trim() == "stop"
+        || serde_json::from_str::<serde_json::Value>(text).is_ok_and(|v| v["type"] == "stop")
+}
+
+/// Refuse queries when the configured embedder doesn't match the library.
+async fn embedding_conflict(state: &AppState) -> Option<Response> {
+    let e = state.orchestrator.check_embedding().await.err()?;
+    Some((StatusCode::CONFLICT, Json(ErrorResponse { error: e.to_string() })).into_response())
+}
+
+/// Semantic search over indexed content.
+#[utoipa::path(post, path = "/search", tag = "search", request_body = SearchRequest,
+    responses(
+        (status = 200, description = "Matching chunks, best first", body = SearchResponse),
+        (status = 409, description = "The library was indexed with a different embedding model", body = ErrorResponse),
+        (status = 500, description = "Search failed", body = ErrorResponse)
+    ))]
 async fn search(
     State(state): State<Arc<AppState>>,
     Json(req): Json<SearchRequest>,
 ) -> impl IntoResponse {
-    let embedder = OpenAIEmbedder::with_config(
-        &state.settings.embedding.model,
-        state.settings.embedding.dimensions as usize,
-    );
+    if let Some(conflict) = embedding_conflict(&state).await {
+        return conflict;
+    }
+    let embedder = state.orchestrator.embedder();
+    let deadline = req
+        .timeout_ms
+        .map(|ms| Instant::now() + Duration::from_millis(ms));
 
     // Generate query embedding
-    let query_embedding = match embedder.embed(&req.query).await {
-        Ok(emb) => emb,
-        Err(e) => {
+    let embedding = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), embedder.embed(&req.query)).await,
+        None => Ok(embedder.embed(&req.query).await),
+    };
+
+    let query_embedding = match embedding {
+        Ok(Ok(emb)) => emb,
+        Ok(Err(e)) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
@@ -243,17 +945,42 @@ async fn search(
             )
                 .into_response()
         }
+        // Budget spent before anything could be searched
+        Err(_) => {
+            return Json(SearchResponse {
+                results: Vec::new(),
+                partial: true,
+            })
+            .into_response()
+        }
     };
 
     // Search vector store
-    match state
-        .orchestrator
-        .vector_store()
-        .search_with_threshold(&query_embedding, req.limit, req.min_score)
-        .await
-    {
-        Ok(results) => Json(SearchResponse {
-            results: results
+    let mut filter = SearchFilter::default().with_derived(req.derived);
+    if let Some(keyword) = &req.keyword {
+        filter = filter.with_keyword(keyword.clone());
+    }
+    if let Some(language) = &req.language {
+        filter = filter.with_language(language_code(language).unwrap_or_else(|| language.clone()));
+    }
+    let vector_store = state.orchestrator.vector_store();
+    let search = match deadline {
+        Some(deadline) => {
+            vector_store
+                .search_with_deadline(&query_embedding, req.limit, req.min_score, &filter, deadline)
+                .await
+        }
+        None => vector_store
+            .search_filtered(&query_embedding, req.limit, req.min_score, &filter)
+            .await
+            .map(|results| PartialResults { results, partial: false }),
+    };
+
+    match search {
+        Ok(found) => Json(SearchResponse {
+            partial: found.partial,
+            results: found
+                .results
                 .into_iter()
                 .map(|r| {
                     let timestamp = r.document.format_timestamp();
@@ -261,11 +988,13 @@ async fn search(
                         video_id: r.document.video_id,
                         video_title: r.document.video_title,
                         chunk_title: r.document.section_title.unwrap_or_default(),
+                        summary: r.document.summary,
                         content: r.document.content,
                         start_seconds: r.document.start_seconds,
                         end_seconds: r.document.end_seconds,
                         timestamp,
                         score: r.score,
+                        doc_type: r.document.doc_type,
                     }
                 })
                 .collect(),
@@ -281,40 +1010,96 @@ async fn search(
     }
 }
 
-async fn ask(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<AskRequest>,
-) -> impl IntoResponse {
+/// Build the RAG engine for an ask request.
+fn ask_engine(state: &AppState, req: &AskRequest) -> RagEngine {
     let model = req
         .model
+        .clone()
         .unwrap_or_else(|| state.settings.rag.model.clone());
+    let mut filter = SearchFilter::default().with_derived(req.derived);
+    if let Some(video_id) = &req.video_id {
+        filter = filter.with_video(video_id.clone());
+    }
 
-    let embedder = Arc::new(OpenAIEmbedder::with_config(
-        &state.settings.embedding.model,
-        state.settings.embedding.dimensions as usize,
-    ));
-
-    let engine = RagEngine::new(
+    RagEngine::new(
         state.orchestrator.vector_store(),
-        embedder,
+        state.orchestrator.embedder(),
         &model,
         req.max_chunks,
-    );
+    )
+    .with_client(ChatClient::from_settings(&state.settings.rag.llm))
+    .with_filter(filter)
+    .with_token_budget(state.settings.rag.context_budget())
+    .with_neighbor_window(state.settings.rag.neighbor_window)
+    .with_min_confidence(state.settings.rag.min_confidence)
+    .with_confidence_scale(ConfidenceScale::from_settings(&state.settings.rag))
+    .with_expansion(QueryExpander::from_settings(&state.settings.rag, &model, state.orchestrator.prompts()))
+    .with_recency(RecencyRanking::from_settings(&state.settings.rag))
+    .with_diversity(state.settings.rag.diversity)
+    .with_routing(state.settings.rag.routing)
+    .with_verifier(AnswerVerifier::from_settings(
+        &RagSettings {
+            verify: req.verify.unwrap_or(state.settings.rag.verify),
+            ..state.settings.rag.clone()
+        },
+        &model,
+        state.orchestrator.prompts(),
+    ))
+    .with_cache(if req.no_cache { None } else { state.settings.rag.cache.ttl() })
+}
+
+/// Answer a question from the library, with sources.
+#[utoipa::path(post, path = "/ask", tag = "search", request_body = AskRequest,
+    responses(
+        (status = 200, description = "Answer with sources", body = AskResponse),
+        (status = 404, description = "`video_id` is not indexed", body = ErrorResponse),
+        (status = 409, description = "The library was indexed with a different embedding model", body = ErrorResponse),
+        (status = 500, description = "Answer generation failed", body = ErrorResponse)
+    ))]
+async fn ask(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<AskRequest>,
+) -> impl IntoResponse {
+    if let Some(conflict) = embedding_conflict(&state).await {
+        return conflict;
+    }
+    if let Some(video_id) = &req.video_id {
+        match state.orchestrator.vector_store().is_video_indexed(video_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        error: format!("No indexed media with ID: {}", video_id),
+                    }),
+                )
+                    .into_response()
+            }
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse { error: e.to_string() }),
+                )
+                    .into_response()
+            }
+        }
+    }
+
+    let engine = ask_engine(&state, &req);
 
-    match engine.ask(&req.question).await {
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(engine.ask(&req.question)).await;
+    state.orchestrator.record_usage("ask", req.video_id.as_deref(), &tracker).await;
+
+    match result {
         Ok(response) => Json(AskResponse {
             answer: response.answer,
-            sources: response
-                .sources
-                .into_iter()
-                .map(|s| SourceInfo {
-                    video_id: s.video_id,
-                    video_title: s.video_title,
-                    timestamp: s.timestamp,
-                    score: s.score,
-                    content: s.content,
-                })
-                .collect(),
+            sources: response.sources.into_iter().map(SourceInfo::from).collect(),
+            citations: response.citations.into_iter().map(CitationInfo::from).collect(),
+            verification: response.verification.map(VerificationInfo::from),
+            confidence: response.confidence.map(ConfidenceInfo::from),
+            abstained: response.abstained,
+            cached: response.cached,
         })
         .into_response(),
         Err(e) => (
@@ -327,6 +1112,85 @@ async fn ask(
     }
 }
 
+/// Stream an answer as Server-Sent Events: `token` events with answer text
+/// fragments, then one `sources` event and one `citations` event. Failures
+/// end the stream with `error`.
+#[utoipa::path(post, path = "/ask/stream", tag = "search", request_body = AskRequest,
+    responses((status = 200, content_type = "text/event-stream",
+        description = "`token` events (`{\"text\": ...}`), then one `sources` event (array of sources) and one `citations` event (array of citations), or an `error` event",
+        body = String),
+        (status = 409, description = "The library was indexed with a different embedding model", body = ErrorResponse)))]
+async fn ask_stream(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<AskRequest>,
+) -> impl IntoResponse {
+    if let Some(conflict) = embedding_conflict(&state).await {
+        return conflict;
+    }
+    let engine = ask_engine(&state, &req);
+    let (tx, rx) = futures::channel::mpsc::unbounded::<Event>();
+    let tracker = UsageTracker::new();
+    let state = state.clone();
+
+    tokio::spawn(logging::in_current_run(async move {
+        tracker.scope(stream_answer(engine, &req.question, tx)).await;
+        state.orchestrator.record_usage("ask", req.video_id.as_deref(), &tracker).await;
+    }));
+
+    Sse::new(rx.map(Ok::<_, Infallible>)).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// Send an answer's `token`, `sources` and `citations` events for [`ask_stream`].
+async fn stream_answer(engine: RagEngine, question: &str, tx: futures::channel::mpsc::UnboundedSender<Event>) {
+    let streamed = match engine.ask_stream(question).await {
+        Ok(streamed) => streamed,
+        Err(e) => {
+            let _ = tx.unbounded_send(json_event("error", &ErrorResponse { error: e.to_string() }));
+            return;
+        }
+    };
+
+    let mut answer = String::new();
+    let mut tokens = streamed.answer;
+    while let Some(token) = tokens.next().await {
+        match token {
+            Ok(text) => {
+                answer.push_str(&text);
+                // Stop generating once the client has gone away
+                if tx.unbounded_send(json_event("token", &TokenEvent { text })).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                let _ = tx.unbounded_send(json_event("error", &ErrorResponse { error: e.to_string() }));
+                return;
+            }
+        }
+    }
+
+    let citations = engine.record_citations(&answer, &streamed.sources).await;
+
+    let sources: Vec<SourceInfo> = streamed.sources.into_iter().map(SourceInfo::from).collect();
+    let _ = tx.unbounded_send(json_event("sources", &sources));
+
+    let citations: Vec<CitationInfo> = citations.into_iter().map(CitationInfo::from).collect();
+    let _ = tx.unbounded_send(json_event("citations", &citations));
+}
+
+/// A named SSE event with a JSON payload.
+fn json_event(name: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|e| Event::default().event("error").data(e.to_string()))
+}
+
+/// List indexed media.
+#[utoipa::path(get, path = "/media", tag = "media",
+    responses(
+        (status = 200, description = "Indexed media", body = MediaListResponse),
+        (status = 500, description = "Store error", body = ErrorResponse)
+    ))]
 async fn list_media(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     match state.orchestrator.vector_store().list_videos().await {
         Ok(media) => Json(MediaListResponse {
@@ -352,6 +1216,13 @@ async fn list_media(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     }
 }
 
+/// Get a media item with all its chunks.
+#[utoipa::path(get, path = "/media/{video_id}", tag = "media",
+    params(("video_id" = String, Path, description = "Media ID")),
+    responses(
+        (status = 200, description = "Media with chunks in order", body = MediaDetailResponse),
+        (status = 404, description = "Media not found", body = ErrorResponse)
+    ))]
 async fn get_media(
     State(state): State<Arc<AppState>>,
     axum::extract::Path(video_id): axum::extract::Path<String>,
@@ -388,6 +1259,7 @@ async fn get_media(
                     .into_iter()
                     .map(|c| ChunkInfo {
                         title: c.section_title.unwrap_or_default(),
+                        summary: c.summary,
                         content: c.content,
                         start_seconds: c.start_seconds,
                         end_seconds: c.end_seconds,
@@ -405,3 +1277,490 @@ async fn get_media(
             .into_response(),
     }
 }
+
+/// Get the stored summary of a media item.
+#[utoipa::path(get, path = "/media/{video_id}/summary", tag = "media",
+    params(("video_id" = String, Path, description = "Media ID")),
+    responses(
+        (status = 200, description = "Overview and per-section summaries", body = Object),
+        (status = 404, description = "No summary stored", body = ErrorResponse)
+    ))]
+async fn get_summary(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(video_id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let id = video_id.clone();
+    match state.orchestrator.sqlite_store().blocking(move |store| store.get_summary(&id)).await {
+        Ok(Some(summary)) => Json(summary).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No summary for {}. Run 'lytt summarize {}' first.", video_id, video_id),
+            }),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct KeywordsQuery {
+    /// Maximum keywords to return (default 25).
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct KeywordsResponse {
+    video_id: String,
+    keywords: Vec<KeywordInfo>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct KeywordInfo {
+    keyword: String,
+    /// Number of chunks the keyword was extracted from.
+    count: usize,
+}
+
+/// The most common chunk keywords of a media item, for faceted browsing.
+#[utoipa::path(get, path = "/media/{video_id}/keywords", tag = "media",
+    params(("video_id" = String, Path, description = "Media ID"), KeywordsQuery),
+    responses(
+        (status = 200, description = "Keywords, most frequent first", body = KeywordsResponse),
+        (status = 404, description = "Media not found", body = ErrorResponse)
+    ))]
+async fn get_keywords(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(video_id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<KeywordsQuery>,
+) -> impl IntoResponse {
+    match state.orchestrator.vector_store().is_video_indexed(&video_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Media not found: {}", video_id),
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+
+    let limit = params.limit.unwrap_or(25);
+    let id = video_id.clone();
+    match state.orchestrator.sqlite_store().blocking(move |store| store.media_keywords(&id, limit)).await {
+        Ok(keywords) => Json(KeywordsResponse {
+            video_id,
+            keywords: keywords
+                .into_iter()
+                .map(|k| KeywordInfo { keyword: k.keyword, count: k.count })
+                .collect(),
+        })
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct HistoryQuery {
+    /// Most recent runs to return (default 20).
+    limit: Option<usize>,
+}
+
+/// The processing runs of a media item, most recent first, with the
+/// configuration, duration, usage and error of each.
+#[utoipa::path(get, path = "/media/{video_id}/history", tag = "media",
+    params(("video_id" = String, Path, description = "Media ID"), HistoryQuery),
+    responses(
+        (status = 200, description = "Processing runs, most recent first", body = Object),
+        (status = 404, description = "No runs recorded", body = ErrorResponse)
+    ))]
+async fn get_history(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(video_id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<HistoryQuery>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(20);
+    let id = video_id.clone();
+    match state.orchestrator.sqlite_store().blocking(move |store| store.processing_history(&id, limit)).await {
+        Ok(runs) if runs.is_empty() => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No processing runs recorded for {}", video_id),
+            }),
+        )
+            .into_response(),
+        Ok(runs) => Json(serde_json::json!({ "video_id": video_id, "runs": runs })).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Timeline data for rendering a media item as a seekable heatmap.
+#[utoipa::path(get, path = "/media/{video_id}/heatmap", tag = "media",
+    params(("video_id" = String, Path, description = "Media ID"), HeatmapQuery),
+    responses(
+        (status = 200, description = "Chunk boundaries and per-interval density and relevance", body = HeatmapResponse),
+        (status = 404, description = "Media not found", body = ErrorResponse),
+        (status = 500, description = "Query embedding failed", body = ErrorResponse)
+    ))]
+async fn get_heatmap(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(video_id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<HeatmapQuery>,
+) -> impl IntoResponse {
+    let chunks = match state.orchestrator.vector_store().get_by_video_id(&video_id).await {
+        Ok(chunks) if chunks.is_empty() => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Media not found: {}", video_id),
+                }),
+            )
+                .into_response()
+        }
+        Ok(chunks) => chunks,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    };
+
+    let query_embedding = match params.query.as_deref().filter(|q| !q.trim().is_empty()) {
+        Some(query) => match state.orchestrator.embedder().embed(query).await {
+            Ok(embedding) => Some(embedding),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: e.to_string(),
+                    }),
+                )
+                    .into_response()
+            }
+        },
+        None => None,
+    };
+
+    let bins = params
+        .bins
+        .unwrap_or(DEFAULT_HEATMAP_BINS)
+        .clamp(1, MAX_HEATMAP_BINS);
+
+    Json(build_heatmap(video_id, chunks, bins, query_embedding.as_deref())).into_response()
+}
+
+/// Split a media item into `bins` equal intervals and score each one.
+fn build_heatmap(
+    video_id: String,
+    mut chunks: Vec<Document>,
+    bins: usize,
+    query_embedding: Option<&[f32]>,
+) -> HeatmapResponse {
+    chunks.sort_by(|a, b| a.start_seconds.partial_cmp(&b.start_seconds).unwrap());
+
+    let video_title = chunks.first().map(|c| c.video_title.clone()).unwrap_or_default();
+    let duration = chunks.iter().map(|c| c.end_seconds).fold(0.0f64, f64::max);
+    let interval = if duration > 0.0 { duration / bins as f64 } else { 0.0 };
+
+    let relevance: Vec<Option<f32>> = chunks
+        .iter()
+        .map(|c| query_embedding.map(|q| cosine_similarity(q, &c.embedding)))
+        .collect();
+
+    let mut intervals: Vec<HeatmapInterval> = (0..bins)
+        .map(|i| {
+            let start = i as f64 * interval;
+            let end = start + interval;
+
+            let mut words = 0.0;
+            let mut best: Option<f32> = None;
+            for (chunk, score) in chunks.iter().zip(&relevance) {
+                let overlap = chunk.end_seconds.min(end) - chunk.start_seconds.max(start);
+                if overlap <= 0.0 {
+                    continue;
+                }
+                // Spread the chunk's words evenly over its span
+                let span = (chunk.end_seconds - chunk.start_seconds).max(f64::EPSILON);
+                words += chunk.content.split_whitespace().count() as f64 * overlap / span;
+                if let Some(score) = score {
+                    best = Some(best.map_or(*score, |b| b.max(*score)));
+                }
+            }
+
+            HeatmapInterval {
+                start_seconds: start,
+                end_seconds: end,
+                density: if interval > 0.0 { (words / interval) as f32 } else { 0.0 },
+                relevance: query_embedding.map(|_| best.unwrap_or(0.0)),
+            }
+        })
+        .collect();
+
+    let max_density = intervals.iter().map(|i| i.density).fold(0.0f32, f32::max);
+    if max_density > 0.0 {
+        for interval in &mut intervals {
+            interval.density /= max_density;
+        }
+    }
+
+    HeatmapResponse {
+        video_id,
+        video_title,
+        duration_seconds: duration,
+        interval_seconds: interval,
+        chunks: chunks
+            .into_iter()
+            .map(|c| HeatmapChunk {
+                title: c.section_title.unwrap_or_default(),
+                start_seconds: c.start_seconds,
+                end_seconds: c.end_seconds,
+            })
+            .collect(),
+        intervals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ServerSettings;
+    use crate::embedding::Embedder;
+    use crate::vector_store::SqliteVectorStore;
+    use crate::testing::{self, MockEmbedder, MockTranscriber};
+
+    /// App state over a fresh library in `dir`, with mock providers.
+    fn test_state(dir: &std::path::Path, configure: impl FnOnce(&mut Settings)) -> Arc<AppState> {
+        let embedder = Arc::new(MockEmbedder::default());
+        let mut settings = testing::test_settings(dir);
+        settings.embedding.dimensions = embedder.dimensions() as u32;
+        configure(&mut settings);
+
+        let transcriber = Arc::new(MockTranscriber::new(Transcript::new("mock".to_string(), Vec::new())));
+        let store = Arc::new(SqliteVectorStore::new(&settings.sqlite_path()).unwrap());
+        let orchestrator =
+            Orchestrator::with_components(settings.clone(), Default::default(), transcriber, embedder, store).unwrap();
+        Arc::new(AppState {
+            orchestrator: Arc::new(orchestrator),
+            settings,
+            daemon: None,
+        })
+    }
+
+    /// Serve `app` on a free local port.
+    async fn spawn_app(app: Router) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    /// An OpenAI-compatible server streaming `tokens` as the answer to any chat request.
+    async fn fake_llm(tokens: &'static [&'static str]) -> std::net::SocketAddr {
+        let completions = move || async move {
+            let mut body = String::new();
+            for token in tokens {
+                let chunk = serde_json::json!({
+                    "id": "chatcmpl-test",
+                    "object": "chat.completion.chunk",
+                    "created": 0,
+                    "model": "test",
+                    "choices": [{ "index": 0, "delta": { "content": token }, "finish_reason": null }],
+                });
+                body.push_str(&format!("data: {}\n\n", chunk));
+            }
+            body.push_str("data: [DONE]\n\n");
+            ([(header::CONTENT_TYPE, "text/event-stream")], body)
+        };
+        spawn_app(Router::new().route("/chat/completions", post(completions))).await
+    }
+
+    /// Index a chunk of a talk that `question` retrieves first.
+    async fn index_chunk(state: &AppState, question: &str, content: &str) {
+        let embedding = MockEmbedder::default().embed(question).await.unwrap();
+        let doc = Document::new(
+            "talk".to_string(),
+            "Rust Talk".to_string(),
+            None,
+            content.to_string(),
+            0.0,
+            30.0,
+            embedding,
+            0,
+            None,
+        );
+        state.orchestrator.vector_store().upsert(&doc).await.unwrap();
+    }
+
+    /// Names of the SSE events `/ask/stream` sends for `question`, in order.
+    async fn ask_stream_events(state: Arc<AppState>, question: &str) -> Vec<String> {
+        let addr = spawn_app(Router::new().route("/ask/stream", post(ask_stream)).with_state(state)).await;
+        let body = reqwest::Client::new()
+            .post(format!("http://{}/ask/stream", addr))
+            .json(&serde_json::json!({ "question": question }))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        body.lines()
+            .filter_map(|line| line.strip_prefix("event:"))
+            .map(|name| name.trim().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_ask_stream_event_order() {
+        let question = "What is ownership in Rust?";
+        let dir = tempfile::tempdir().unwrap();
+        let llm = fake_llm(&["Ownership ", "moves values [1]."]).await;
+        let state = test_state(dir.path(), |s| s.rag.llm.base_url = Some(format!("http://{}", llm)));
+
+        index_chunk(&state, question, "Ownership moves values between bindings.").await;
+
+        // Tokens as they arrive, then what the finished answer drew on
+        let events = ask_stream_events(state, question).await;
+        assert_eq!(events, ["token", "token", "sources", "citations"]);
+    }
+
+    #[tokio::test]
+    async fn test_ask_stream_reports_errors() {
+        let question = "What is ownership in Rust?";
+        let dir = tempfile::tempdir().unwrap();
+        // Nothing listens on the LLM's port
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let state = test_state(dir.path(), |s| s.rag.llm.base_url = Some(format!("http://{}", closed)));
+        index_chunk(&state, question, "Ownership moves values between bindings.").await;
+
+        let events = ask_stream_events(state, question).await;
+        assert_eq!(events, ["error"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_closes_idle_clients() {
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path(), |s| s.record.max_stream_seconds = 1);
+        let addr = spawn_app(Router::new().route("/stream", get(stream)).with_state(state)).await;
+
+        // The client connects and never sends a frame
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/stream", addr)).await.unwrap();
+        let mut messages = Vec::new();
+        let closed = tokio::time::timeout(Duration::from_secs(10), async {
+            while let Some(message) = client.next().await {
+                match message.unwrap() {
+                    ClientMessage::Close(frame) => return frame,
+                    message => messages.push(message),
+                }
+            }
+            None
+        })
+        .await
+        .expect("idle session was never closed");
+
+        assert_eq!(closed.unwrap().code, tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode::Policy);
+        let error: serde_json::Value = serde_json::from_str(messages[0].to_text().unwrap()).unwrap();
+        assert_eq!(error["type"], "error");
+        assert!(error["message"].as_str().unwrap().contains("Stream limit"));
+    }
+
+    #[test]
+    fn test_stream_control() {
+        assert!(is_stop("stop"));
+        assert!(is_stop(r#"{"type": "stop"}"#));
+        assert!(!is_stop(r#"{"type": "pause"}"#));
+        assert_eq!(query_param(Some("sample_rate=48000&api_key=a%2Bb"), "api_key").as_deref(), Some("a+b"));
+        assert_eq!(query_param(None, "api_key"), None);
+    }
+
+    #[test]
+    fn test_build_heatmap() {
+        let chunk = |title: &str, content: &str, start: f64, end: f64, embedding: Vec<f32>| {
+            Document::new(
+                "video1".to_string(),
+                "Lecture".to_string(),
+                Some(title.to_string()),
+                content.to_string(),
+                start,
+                end,
+                embedding,
+                0,
+                None,
+            )
+        };
+        let chunks = vec![
+            chunk("Outro", "bye", 50.0, 100.0, vec![0.0, 1.0]),
+            chunk("Intro", "one two three four", 0.0, 50.0, vec![1.0, 0.0]),
+        ];
+
+        let heatmap = build_heatmap("video1".to_string(), chunks.clone(), 4, None);
+        assert_eq!(heatmap.duration_seconds, 100.0);
+        assert_eq!(heatmap.interval_seconds, 25.0);
+        assert_eq!(heatmap.chunks[0].title, "Intro");
+        assert_eq!(heatmap.intervals.len(), 4);
+        assert_eq!(heatmap.intervals[0].density, 1.0);
+        assert!(heatmap.intervals[3].density < 0.5);
+        assert!(heatmap.intervals[0].relevance.is_none());
+
+        let heatmap = build_heatmap("video1".to_string(), chunks, 2, Some(&[0.0, 1.0]));
+        assert!(heatmap.intervals[0].relevance.unwrap() < 0.01);
+        assert!(heatmap.intervals[1].relevance.unwrap() > 0.99);
+    }
+
+    #[test]
+    fn test_openapi_spec() {
+        let open = AccessControl::from_settings(&ServerSettings::default());
+        let spec = openapi_spec(&open, false, false);
+        assert!(spec.paths.paths.contains_key("/transcribe"));
+        assert!(!spec.paths.paths.contains_key("/status"));
+        assert!(spec.paths.paths.contains_key("/media/{video_id}/summary"));
+        assert!(spec.security.is_none());
+
+        let keyed = AccessControl::from_settings(&ServerSettings {
+            api_keys: vec!["secret".to_string()],
+            ..Default::default()
+        });
+        let spec = openapi_spec(&keyed, true, true);
+        assert!(!spec.paths.paths.contains_key("/transcribe"));
+        assert!(spec.paths.paths.contains_key("/status"));
+        assert!(spec.security.is_some());
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert!(json["components"]["schemas"]["AskRequest"].is_object());
+        assert!(json["components"]["schemas"]["DaemonStatus"].is_object());
+    }
+}
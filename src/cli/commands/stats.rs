@@ -0,0 +1,99 @@
+//! Stats command implementation.
+
+use crate::cli::output::truncate;
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::vector_store::SqliteVectorStore;
+use anyhow::Result;
+use console::style;
+
+/// Run the stats command.
+pub fn run_stats(top_sources: bool, never_retrieved: bool, limit: usize, settings: Settings) -> Result<()> {
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+    let stats = store.video_retrieval_stats()?;
+
+    if stats.is_empty() {
+        Output::info("No media indexed yet.");
+        return Ok(());
+    }
+
+    let show_all = !top_sources && !never_retrieved;
+
+    if show_all {
+        let chunks: u32 = stats.iter().map(|s| s.chunk_count).sum();
+        let retrieved: u32 = stats.iter().map(|s| s.retrieved_chunks).sum();
+        let unused = stats.iter().filter(|s| s.retrievals == 0).count();
+
+        Output::header("Retrieval Statistics");
+        println!();
+        Output::kv("Media", &stats.len().to_string());
+        Output::kv(
+            "Chunks retrieved",
+            &format!("{} of {} ({:.0}%)", retrieved, chunks, percent(retrieved, chunks)),
+        );
+        Output::kv("Retrievals", &stats.iter().map(|s| s.retrievals).sum::<u64>().to_string());
+        Output::kv("Citations", &stats.iter().map(|s| s.citations).sum::<u64>().to_string());
+        Output::kv("Never retrieved", &format!("{} media", unused));
+    }
+
+    if show_all || top_sources {
+        let top: Vec<_> = stats.iter().filter(|s| s.retrievals > 0).take(limit).collect();
+
+        println!();
+        Output::header("Top Sources");
+        println!();
+        if top.is_empty() {
+            Output::info("Nothing has been retrieved yet.");
+        } else {
+            println!(
+                "  {:<40} {:>9} {:>10} {:>8}",
+                style("Media").bold(),
+                style("Cited").bold(),
+                style("Retrieved").bold(),
+                style("Chunks").bold()
+            );
+            for s in top {
+                println!(
+                    "  {:<40} {:>9} {:>10} {:>8}",
+                    truncate(&s.video_title, 40),
+                    s.citations,
+                    s.retrievals,
+                    format!("{}/{}", s.retrieved_chunks, s.chunk_count)
+                );
+            }
+        }
+    }
+
+    if show_all || never_retrieved {
+        let unused: Vec<_> = stats.iter().filter(|s| s.retrievals == 0).collect();
+
+        println!();
+        Output::header("Never Retrieved");
+        println!();
+        if unused.is_empty() {
+            Output::success("Every media item has been retrieved at least once.");
+        } else {
+            for s in unused.iter().take(limit) {
+                Output::list_item(&format!(
+                    "{} [{}] ({} chunks)",
+                    s.video_title,
+                    style(&s.video_id).dim(),
+                    s.chunk_count
+                ));
+            }
+            if unused.len() > limit {
+                Output::info(&format!("...and {} more", unused.len() - limit));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn percent(part: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
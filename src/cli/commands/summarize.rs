@@ -0,0 +1,82 @@
+//! Summarize command implementation.
+
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::summary::SummaryGenerator;
+use crate::usage::UsageTracker;
+use anyhow::Result;
+
+/// Run the summarize command.
+pub async fn run_summarize(
+    video_id: &str,
+    model: Option<String>,
+    show: bool,
+    settings: Settings,
+) -> Result<()> {
+    if show {
+        return show_summary(video_id, settings);
+    }
+
+    // Pre-flight checks
+    if let Err(e) = preflight::check(Operation::Summarize, &settings) {
+        Output::error(&format!("{}", e));
+        Output::info("Run 'lytt doctor' for detailed diagnostics.");
+        return Err(e.into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let model = model.unwrap_or_else(|| settings.summary.model.clone());
+
+    let generator = SummaryGenerator::new(orchestrator.sqlite_store(), orchestrator.embedder(), &model)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.summary.llm));
+
+    let spinner = Output::spinner(&format!("Summarizing {}...", video_id));
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(generator.generate(video_id)).await;
+    orchestrator.record_usage("summarize", Some(video_id), &tracker).await;
+
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(summary) => {
+            println!("\n{}", summary.to_markdown());
+            Output::success(&format!(
+                "Stored and indexed summary of '{}' ({} sections)",
+                summary.video_title,
+                summary.chunks.len()
+            ));
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to summarize: {}", e));
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a stored summary.
+fn show_summary(video_id: &str, settings: Settings) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?;
+
+    match orchestrator.sqlite_store().get_summary(video_id)? {
+        Some(summary) => {
+            println!("\n{}", summary.to_markdown());
+            Output::kv(
+                "Generated",
+                &format!("{} with {}", summary.created_at.format("%Y-%m-%d %H:%M"), summary.model),
+            );
+        }
+        None => Output::info(&format!(
+            "No summary for {}. Use 'lytt summarize {}' to create one.",
+            video_id, video_id
+        )),
+    }
+
+    Ok(())
+}
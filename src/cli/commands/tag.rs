@@ -0,0 +1,30 @@
+//! Tag command implementation.
+
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::vector_store::SqliteVectorStore;
+use anyhow::Result;
+
+/// Run the tag command.
+pub fn run_tag(video_id: &str, tags: &[String], remove: bool, settings: Settings) -> Result<()> {
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+
+    if !tags.is_empty() {
+        if remove {
+            let removed = store.remove_tags(video_id, tags)?;
+            Output::success(&format!("Removed {} tag(s) from {}", removed, video_id));
+        } else {
+            store.add_tags(video_id, tags)?;
+            Output::success(&format!("Tagged {}", video_id));
+        }
+    }
+
+    let current = store.get_tags(video_id)?;
+    if current.is_empty() {
+        Output::info(&format!("{} has no tags", video_id));
+    } else {
+        Output::kv("Tags", &current.join(", "));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Topics command implementation.
+
+use crate::cli::preflight::{self, Operation};
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::llm::ChatClient;
+use crate::orchestrator::Orchestrator;
+use crate::topics::TopicMapper;
+use crate::usage::UsageTracker;
+use anyhow::Result;
+
+/// Media listed per topic.
+const MEDIA_PER_TOPIC: usize = 5;
+
+/// Run the topics command.
+pub async fn run_topics(
+    clusters: Option<usize>,
+    format: &str,
+    list: bool,
+    model: Option<String>,
+    settings: Settings,
+) -> Result<()> {
+    let json = match format.to_lowercase().as_str() {
+        "markdown" | "md" => false,
+        "json" => true,
+        _ => {
+            let e = format!("Unknown report format: {}. Use markdown or json.", format);
+            Output::error(&e);
+            return Err(LyttError::InvalidInput(e).into());
+        }
+    };
+
+    if clusters == Some(0) {
+        let e = "--clusters must be at least 1".to_string();
+        Output::error(&e);
+        return Err(LyttError::InvalidInput(e).into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let store = orchestrator.sqlite_store();
+
+    let map = if list {
+        match store.get_topic_map(MEDIA_PER_TOPIC)? {
+            Some(map) => map,
+            None => {
+                Output::warning("No stored topics. Set topics.persist = true and run 'lytt topics'.");
+                return Ok(());
+            }
+        }
+    } else {
+        // Pre-flight checks
+        if let Err(e) = preflight::check(Operation::Ask, &settings) {
+            Output::error(&format!("{}", e));
+            Output::info("Run 'lytt doctor' for detailed diagnostics.");
+            return Err(e.into());
+        }
+
+        let model = model.unwrap_or_else(|| settings.topics.model.clone());
+        let mapper = TopicMapper::new(orchestrator.vector_store(), &model)
+            .with_clusters(clusters)
+            .with_media_per_topic(MEDIA_PER_TOPIC)
+            .with_prompts(orchestrator.prompts().clone())
+            .with_client(ChatClient::from_settings(&settings.topics.llm));
+
+        let spinner = Output::spinner("Finding topics...");
+
+        let tracker = UsageTracker::new();
+        let result = tracker.scope(mapper.map()).await;
+        orchestrator.record_usage("topics", None, &tracker).await;
+
+        spinner.finish_and_clear();
+
+        let model = match result {
+            Ok(model) => model,
+            Err(e) => {
+                Output::error(&format!("Failed to find topics: {}", e));
+                return Err(e.into());
+            }
+        };
+
+        if settings.topics.persist && !settings.vector_store.read_only {
+            store.store_topics(&model)?;
+            Output::info(&format!(
+                "Stored {} topics. Filter searches with 'lytt search --topic <id>'.",
+                model.map.topics.len()
+            ));
+        }
+
+        model.map
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&map)?);
+    } else {
+        println!("{}", map.to_markdown());
+    }
+
+    Ok(())
+}
@@ -1,18 +1,232 @@
 //! Transcribe command implementation.
 
-use crate::audio::download_audio;
-use crate::audio_source::{parse_input, YoutubeSource, AudioSource};
-use crate::chunking::{ChunkingConfig, ChunkingStrategy, create_chunker};
+use crate::audio::{
+    download_audio, download_audio_cached, preprocess_audio, remove_ranges, AudioCache, Workspace,
+    AUDIO_BYTES_PER_SECOND,
+};
+use crate::audio_source::sponsorblock;
+use crate::audio_source::{content_hash, parse_input, AudioSource, LocalSource, MediaMetadata, SourceType, YoutubeSource};
+use crate::audiobook::Audiobook;
+use crate::chunking::{ChunkingConfig, create_chunker_from_settings};
+use crate::cli::cancel::ctrl_c_token;
 use crate::cli::preflight::{self, Operation};
 use crate::cli::Output;
-use crate::config::{TranscriptionProcessingSettings, Prompts, Settings, TranscriptionProvider};
-use crate::embedding::{Embedder, OpenAIEmbedder};
+use crate::config::{Prompts, Settings, TranscriptionProvider};
+use crate::dedupe::likely_duplicate;
+use crate::embedding::create_embedder;
+use crate::error::LyttError;
+use crate::hooks::{HookContext, HookRegistry};
 use crate::orchestrator::Orchestrator;
-use crate::transcription::{format_transcript, TranscriptionProcessor, OutputFormat, Transcriber};
+use crate::retention;
+use crate::transcription::{
+    captions_transcript, format_transcript, is_transcript_file, read_transcript_file, TranscriptionProcessor, OutputFormat,
+    Transcriber,
+};
+use crate::usage::{estimate_chunks, estimate_transcription};
 use anyhow::Result;
+use console::style;
 use serde::Serialize;
+use std::path::Path;
+
+/// Fetch the metadata of the input, or of every video in a playlist or
+/// channel, without downloading anything.
+async fn fetch_media_list(input: &str, playlist: bool, limit: Option<usize>) -> Result<Vec<MediaMetadata>> {
+    let spinner = Output::spinner("Fetching metadata...");
+    let media = if playlist {
+        YoutubeSource::new().list_media(input, limit).await
+    } else {
+        match parse_input(input) {
+            Some((source, id)) => source.fetch_media(&id).await.map(|m| vec![m]),
+            None => {
+                spinner.finish_and_clear();
+                Output::error(&format!("Could not parse input: {}", input));
+                return Err(LyttError::InvalidInput(input.to_string()).into());
+            }
+        }
+    };
+    spinner.finish_and_clear();
+    Ok(media?)
+}
+
+/// Estimate the API cost of transcribing and indexing the input.
+///
+/// Only fetches metadata, so no API key is required.
+pub async fn run_estimate(
+    input: &str,
+    playlist: bool,
+    limit: Option<usize>,
+    settings: Settings,
+) -> Result<()> {
+    let media = fetch_media_list(input, playlist, limit).await?;
+
+    let unknown = media.iter().filter(|m| m.duration_seconds.is_none()).count();
+    let duration: u32 = media.iter().filter_map(|m| m.duration_seconds).sum();
+
+    let estimate = estimate_transcription(&settings, &settings.pricing(), duration as f64);
+
+    Output::header("Cost Estimate");
+    Output::kv("Media", &media.len().to_string());
+    Output::kv("Duration", &format!("{:.1} min", duration as f64 / 60.0));
+    println!();
+    for line in &estimate.lines {
+        Output::kv(&format!("{} ({})", line.stage, line.model), &format!("${:.4}", line.cost_usd));
+    }
+    println!();
+    Output::kv("Estimated total", &format!("${:.4}", estimate.total()));
+
+    if unknown > 0 {
+        Output::warning(&format!(
+            "{} item(s) have unknown duration and are not included",
+            unknown
+        ));
+    }
+
+    Ok(())
+}
+
+/// Show what `lytt transcribe` would do with the input: the pipeline it
+/// would run, which media it would transcribe or skip, and the estimated
+/// chunks and cost of each.
+///
+/// Only fetches metadata and reads the library; nothing is downloaded and no
+/// paid API is called.
+pub async fn run_dry_run(
+    input: &str,
+    force: bool,
+    playlist: bool,
+    limit: Option<usize>,
+    settings: Settings,
+) -> Result<()> {
+    let path = Path::new(input);
+    if !playlist && (LocalSource::is_collection(input) || (path.is_file() && is_transcript_file(path))) {
+        Output::error("--dry-run takes a single media URL or file, or a playlist or channel with --playlist");
+        return Err(LyttError::InvalidInput("--dry-run doesn't support directories or transcript files".to_string()).into());
+    }
+
+    let media = fetch_media_list(input, playlist, limit).await?;
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let store = orchestrator.vector_store();
+    let indexed = if !force && settings.dedupe.check_on_transcribe {
+        store.list_videos().await?
+    } else {
+        Vec::new()
+    };
+    let pricing = settings.pricing();
+    let max_duration = settings.transcription.max_duration_seconds;
+
+    Output::header("Dry run");
+    Output::info("Nothing will be downloaded, transcribed or indexed.");
+    println!();
+    print_pipeline(&settings);
+    println!();
+
+    let (mut planned, mut skipped, mut too_long, mut unknown) = (0, 0, 0, 0);
+    let (mut duration, mut chunks) = (0u32, 0usize);
+    for (i, item) in media.iter().enumerate() {
+        let length = item
+            .duration_seconds
+            .map(|d| MediaMetadata::format_timestamp(d as f64))
+            .unwrap_or_else(|| "?".to_string());
+        println!(
+            "  {} {} {}",
+            style(format!("[{}/{}]", i + 1, media.len())).dim(),
+            item.title,
+            style(format!("({}, {})", item.id, length)).dim()
+        );
+
+        if !force && store.is_video_indexed(&item.id).await? {
+            println!("      {}", style("skip: already indexed").yellow());
+            skipped += 1;
+            continue;
+        }
+        let duplicate = likely_duplicate(item, &indexed);
+        if let Some(original) = duplicate.filter(|_| settings.dedupe.skip_on_transcribe) {
+            println!("      {}", style(format!("skip: likely duplicate of {}", original.video_id)).yellow());
+            skipped += 1;
+            continue;
+        }
+
+        let note = duplicate.map(|d| format!(" (possible duplicate of {})", d.video_id)).unwrap_or_default();
+        match item.duration_seconds {
+            Some(seconds) if seconds > max_duration => {
+                println!(
+                    "      {}",
+                    style(format!("fail: longer than transcription.max_duration_seconds ({})", max_duration)).red()
+                );
+                too_long += 1;
+            }
+            Some(seconds) => {
+                let item_chunks = estimate_chunks(&settings, seconds as f64);
+                let cost = estimate_transcription(&settings, &pricing, seconds as f64).total();
+                println!(
+                    "      {}",
+                    style(format!("transcribe: ~{} chunks, ~${:.4}{}", item_chunks, cost, note)).green()
+                );
+                planned += 1;
+                duration += seconds;
+                chunks += item_chunks;
+            }
+            None => {
+                println!("      {}", style(format!("transcribe: duration unknown{}", note)).green());
+                planned += 1;
+                unknown += 1;
+            }
+        }
+    }
+
+    let estimate = estimate_transcription(&settings, &pricing, duration as f64);
+    println!();
+    Output::kv("To transcribe", &format!("{} ({:.1} min)", planned, duration as f64 / 60.0));
+    Output::kv("Skipped", &skipped.to_string());
+    Output::kv("Estimated chunks", &format!("~{}", chunks));
+    for line in &estimate.lines {
+        Output::kv(&format!("{} ({})", line.stage, line.model), &format!("${:.4}", line.cost_usd));
+    }
+    Output::kv("Estimated total", &format!("${:.4}", estimate.total()));
+
+    if unknown > 0 {
+        Output::warning(&format!("{} item(s) have unknown duration and are not included", unknown));
+    }
+    if too_long > 0 {
+        Output::warning(&format!("{} item(s) are longer than the maximum duration and would fail", too_long));
+    }
+    if let Err(e) = preflight::check(Operation::Transcribe, &settings) {
+        Output::warning(&format!("A real run would stop before starting: {}", e));
+    }
+
+    Ok(())
+}
+
+/// Print the providers and models a transcription would use.
+fn print_pipeline(settings: &Settings) {
+    let transcription = &settings.transcription;
+    let text_model = transcription.processing.text_model.as_deref().unwrap_or("gpt-4o-transcribe");
+    let speech = match transcription.provider {
+        TranscriptionProvider::Whisper => format!("whisper ({})", transcription.model),
+        TranscriptionProvider::Captions => format!("YouTube captions, {} when there are none", transcription.model),
+        TranscriptionProvider::Fusion => {
+            format!("fusion ({} timestamps, {} text)", transcription.processing.timestamp_model, text_model)
+        }
+        TranscriptionProvider::Gpt4o => format!("gpt4o ({})", text_model),
+    };
+    Output::kv("Transcription", &speech);
+    if transcription.provider != TranscriptionProvider::Gpt4o {
+        Output::kv("Cleanup", &transcription.processing.cleanup_model);
+    }
+
+    let chunking = &settings.chunking;
+    let chunker = match chunking.strategy.as_str() {
+        "temporal" => format!("temporal, ~{}s chunks", chunking.target_chunk_seconds),
+        "sliding" => format!("sliding, {} tokens with {} overlap", chunking.chunk_tokens, chunking.overlap_tokens),
+        "external" => format!("external ({})", chunking.command.as_deref().unwrap_or("no command")),
+        strategy => format!("{} ({}), ~{}s chunks", strategy, chunking.model, chunking.target_chunk_seconds),
+    };
+    Output::kv("Chunking", &chunker);
+    Output::kv("Embedding", &settings.embedding.model);
+}
 
 /// Run the transcribe command.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_transcribe(
     input: &str,
     force: bool,
@@ -22,10 +236,30 @@ pub async fn run_transcribe(
     embed: bool,
     playlist: bool,
     limit: Option<usize>,
+    recursive: bool,
+    excludes: &[String],
+    meeting: bool,
+    audiobook: bool,
+    tags: &[String],
+    series: Option<&str>,
+    title: Option<&str>,
     settings: Settings,
 ) -> Result<()> {
+    let path = Path::new(input);
+    if !playlist && path.is_file() && is_transcript_file(path) {
+        if output.is_some() || chunk || embed || meeting {
+            Output::error("An imported transcript is indexed; --output, --chunk, --embed and --meeting don't apply");
+            return Err(LyttError::InvalidInput("options that need audio were given with a transcript file".to_string()).into());
+        }
+        return run_import(path, title, force, tags, series, settings).await;
+    }
+    if title.is_some() {
+        Output::error("--title only applies to imported transcript files (.srt, .vtt, .json)");
+        return Err(LyttError::InvalidInput("--title needs a transcript file".to_string()).into());
+    }
+
     // Pre-flight checks
-    if let Err(e) = preflight::check(Operation::Transcribe) {
+    if let Err(e) = preflight::check(Operation::Transcribe, &settings) {
         Output::error(&format!("{}", e));
         Output::info("Run 'lytt doctor' for detailed diagnostics.");
         return Err(e.into());
@@ -34,22 +268,43 @@ pub async fn run_transcribe(
     // Validate flags
     if embed && !chunk {
         Output::error("--embed requires --chunk flag");
-        return Err(anyhow::anyhow!("--embed requires --chunk"));
+        return Err(LyttError::InvalidInput("--embed requires --chunk".to_string()).into());
     }
 
     if (chunk || embed) && output.is_none() {
         Output::error("--chunk and --embed require --output flag");
-        return Err(anyhow::anyhow!("--chunk/--embed require --output"));
+        return Err(LyttError::InvalidInput("--chunk/--embed require --output".to_string()).into());
+    }
+
+    if series.is_some() && output.is_some() {
+        Output::error("--series cannot be combined with --output; only indexed media joins a series");
+        return Err(LyttError::InvalidInput("--series cannot be combined with --output".to_string()).into());
     }
 
     if playlist && output.is_some() {
         Output::error("--playlist cannot be combined with --output");
-        return Err(anyhow::anyhow!("--playlist cannot be combined with --output"));
+        return Err(LyttError::InvalidInput("--playlist cannot be combined with --output".to_string()).into());
+    }
+
+    if audiobook {
+        let source = LocalSource::new().with_recursive(recursive).with_excludes(excludes.to_vec());
+        return run_transcribe_audiobook(input, &source, force, tags, series, settings).await;
+    }
+
+    let directory = !playlist && LocalSource::is_collection(input);
+    if directory && output.is_some() {
+        Output::error("A directory or glob cannot be combined with --output");
+        return Err(LyttError::InvalidInput("--output takes a single file".to_string()).into());
     }
 
     // Handle playlist mode
     if playlist {
-        return run_transcribe_playlist(input, force, limit, settings).await;
+        return run_transcribe_playlist(input, force, limit, tags, series, settings).await;
+    }
+
+    if directory {
+        let source = LocalSource::new().with_recursive(recursive).with_excludes(excludes.to_vec());
+        return run_transcribe_directory(input, &source, force, limit, meeting, tags, series, settings).await;
     }
 
     // If --output is specified, just transcribe and export (no indexing)
@@ -58,18 +313,122 @@ pub async fn run_transcribe(
     }
 
     // Standard flow: transcribe and index
-    run_transcribe_single(input, force, settings).await
+    run_transcribe_single(input, force, meeting, tags, series, settings).await
+}
+
+/// Index an existing transcript file, skipping audio entirely.
+async fn run_import(
+    path: &Path,
+    title: Option<&str>,
+    force: bool,
+    tags: &[String],
+    series: Option<&str>,
+    settings: Settings,
+) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?.with_cancellation(ctrl_c_token());
+    if let Err(e) = orchestrator.check_embedding().await {
+        Output::error(&e.to_string());
+        return Err(e.into());
+    }
+
+    let mut metadata = LocalSource::file_metadata(path, title, None, 0.0)?;
+    if !force && orchestrator.vector_store().is_video_indexed(&metadata.id).await? {
+        Output::warning(&format!("'{}' is already indexed as {}. Use --force to reprocess.", path.display(), metadata.id));
+        return Ok(());
+    }
+
+    let transcript = match read_transcript_file(path, &metadata.id) {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            Output::error(&format!("Couldn't read {}: {}", path.display(), e));
+            return Err(e.into());
+        }
+    };
+    metadata.duration_seconds = Some(transcript.duration_seconds.ceil() as u32);
+    Output::info(&format!(
+        "Importing {} ({} segments)",
+        path.display(),
+        transcript.segments.len()
+    ));
+
+    match orchestrator.index_transcript(metadata, transcript).await {
+        Ok(result) => {
+            apply_tags(&orchestrator, &result.media_id, tags);
+            apply_series(&orchestrator, &result.media_id, series);
+            Output::success(&format!(
+                "Indexed '{}' as {} ({} chunks)",
+                result.title, result.media_id, result.chunks_indexed
+            ));
+            warn_if_over_quota(&orchestrator);
+            Ok(())
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to index {}: {}", path.display(), e));
+            Err(e.into())
+        }
+    }
+}
+
+/// Tag processed media, warning rather than failing on errors.
+fn apply_tags(orchestrator: &Orchestrator, media_id: &str, tags: &[String]) {
+    if tags.is_empty() {
+        return;
+    }
+    if let Err(e) = orchestrator.sqlite_store().add_tags(media_id, tags) {
+        Output::warning(&format!("Failed to tag '{}': {}", media_id, e));
+    }
+}
+
+/// Add processed media to a series, warning rather than failing on errors.
+/// Media already in the series keeps its episode number.
+fn apply_series(orchestrator: &Orchestrator, media_id: &str, series: Option<&str>) {
+    let Some(series) = series else {
+        return;
+    };
+    if let Err(e) = orchestrator.sqlite_store().add_to_series(series, media_id, None) {
+        Output::warning(&format!("Failed to add '{}' to series '{}': {}", media_id, series, e));
+    }
+}
+
+/// Warn when the library has grown past its retention policies (soft quota).
+fn warn_if_over_quota(orchestrator: &Orchestrator) {
+    match retention::plan_for_store(&orchestrator.sqlite_store(), &orchestrator.settings().retention) {
+        Ok(items) if !items.is_empty() => Output::warning(&format!(
+            "Library exceeds its retention policies ({} chunks over). Run 'lytt prune --dry-run' to review.",
+            items.len()
+        )),
+        Ok(_) => {}
+        Err(e) => Output::warning(&format!("Failed to check retention policies: {}", e)),
+    }
 }
 
 /// Transcribe a single video and index it.
-async fn run_transcribe_single(input: &str, force: bool, settings: Settings) -> Result<()> {
+async fn run_transcribe_single(
+    input: &str,
+    force: bool,
+    meeting: bool,
+    tags: &[String],
+    series: Option<&str>,
+    settings: Settings,
+) -> Result<()> {
     Output::info(&format!("Processing: {}", input));
 
-    let orchestrator = Orchestrator::new(settings)?;
+    let orchestrator = Orchestrator::new(settings)?
+        .with_meetings(meeting)
+        .with_cancellation(ctrl_c_token());
 
     match orchestrator.process_media(input, force).await {
         Ok(result) => {
-            if result.skipped {
+            apply_tags(&orchestrator, &result.media_id, tags);
+            if result.duplicate_of.is_none() {
+                apply_series(&orchestrator, &result.media_id, series);
+            }
+            if let Some(original) = &result.duplicate_of {
+                Output::warning(&format!(
+                    "'{}' looks like a duplicate of {}. Use --force to transcribe it anyway.",
+                    result.title, original
+                ));
+            } else if result.skipped {
                 Output::warning(&format!(
                     "'{}' is already indexed. Use --force to reprocess.",
                     result.title
@@ -79,8 +438,68 @@ async fn run_transcribe_single(input: &str, force: bool, settings: Settings) ->
                     "Successfully indexed '{}' ({} chunks)",
                     result.title, result.chunks_indexed
                 ));
+                warn_if_over_quota(&orchestrator);
             }
         }
+        Err(LyttError::Cancelled) => {
+            Output::warning("Cancelled. If the transcript was saved, 'lytt rechunk --stale' indexes it without transcribing again.");
+            return Err(LyttError::Cancelled.into());
+        }
+        Err(e) => {
+            Output::error(&format!("Failed to process: {}", e));
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Transcribe an audiobook and index it as one media item.
+async fn run_transcribe_audiobook(
+    input: &str,
+    source: &LocalSource,
+    force: bool,
+    tags: &[String],
+    series: Option<&str>,
+    settings: Settings,
+) -> Result<()> {
+    let spinner = Output::spinner("Reading audiobook...");
+    let book = Audiobook::load(input, source).await;
+    spinner.finish_and_clear();
+    let book = match book {
+        Ok(book) => book,
+        Err(e) => {
+            Output::error(&format!("Couldn't read audiobook {}: {}", input, e));
+            return Err(e.into());
+        }
+    };
+
+    Output::info(&format!("Processing audiobook: {}", book.title));
+    if let Some(author) = &book.author {
+        Output::kv("Author", author);
+    }
+    Output::kv("Parts", &book.parts.len().to_string());
+    Output::kv("Chapters", &book.chapters.len().to_string());
+
+    let orchestrator = Orchestrator::new(settings)?.with_cancellation(ctrl_c_token());
+    match orchestrator.process_audiobook(&book, force).await {
+        Ok(result) if result.skipped => {
+            apply_series(&orchestrator, &result.media_id, series);
+            Output::warning(&format!("'{}' is already indexed. Use --force to reprocess.", result.title));
+        }
+        Ok(result) => {
+            apply_tags(&orchestrator, &result.media_id, tags);
+            apply_series(&orchestrator, &result.media_id, series);
+            Output::success(&format!(
+                "Successfully indexed '{}' as {} ({} chunks)",
+                result.title, result.media_id, result.chunks_indexed
+            ));
+            warn_if_over_quota(&orchestrator);
+        }
+        Err(LyttError::Cancelled) => {
+            Output::warning("Cancelled. If the transcript was saved, 'lytt rechunk --stale' indexes it without transcribing again.");
+            return Err(LyttError::Cancelled.into());
+        }
         Err(e) => {
             Output::error(&format!("Failed to process: {}", e));
             return Err(e.into());
@@ -95,6 +514,8 @@ async fn run_transcribe_playlist(
     input: &str,
     force: bool,
     limit: Option<usize>,
+    tags: &[String],
+    series: Option<&str>,
     settings: Settings,
 ) -> Result<()> {
     Output::info(&format!("Fetching playlist: {}", input));
@@ -104,7 +525,7 @@ async fn run_transcribe_playlist(
 
     if !source.can_handle(input) {
         Output::error("Input doesn't appear to be a valid YouTube playlist or channel URL");
-        return Err(anyhow::anyhow!("Invalid playlist URL"));
+        return Err(LyttError::InvalidInput("Invalid playlist URL".to_string()).into());
     }
 
     let spinner = Output::spinner("Fetching video list...");
@@ -120,19 +541,31 @@ async fn run_transcribe_playlist(
     Output::info(&format!("Found {} videos to transcribe", total));
     println!();
 
-    let orchestrator = Orchestrator::new(settings)?;
+    let cancel = ctrl_c_token();
+    let orchestrator = Orchestrator::new(settings)?.with_cancellation(cancel.clone());
 
     let mut success_count = 0;
     let mut skip_count = 0;
     let mut error_count = 0;
 
     for (i, video) in videos.iter().enumerate() {
+        if cancel.is_cancelled() {
+            Output::warning(&format!("Cancelled; {} videos not processed", total - i));
+            break;
+        }
         let progress = format!("[{}/{}]", i + 1, total);
         Output::info(&format!("{} Processing: {}", progress, video.title));
 
         match orchestrator.process_media(&video.id, force).await {
             Ok(result) => {
-                if result.skipped {
+                apply_tags(&orchestrator, &video.id, tags);
+                if result.duplicate_of.is_none() {
+                    apply_series(&orchestrator, &video.id, series);
+                }
+                if let Some(original) = &result.duplicate_of {
+                    Output::warning(&format!("  Skipped (likely duplicate of {})", original));
+                    skip_count += 1;
+                } else if result.skipped {
                     Output::warning("  Skipped (already indexed)");
                     skip_count += 1;
                 } else {
@@ -140,6 +573,10 @@ async fn run_transcribe_playlist(
                     success_count += 1;
                 }
             }
+            Err(LyttError::Cancelled) => {
+                Output::warning(&format!("  Cancelled; {} videos not processed", total - i));
+                break;
+            }
             Err(e) => {
                 Output::error(&format!("  Failed: {}", e));
                 error_count += 1;
@@ -153,6 +590,136 @@ async fn run_transcribe_playlist(
         success_count, skip_count, error_count
     ));
 
+    if success_count > 0 {
+        warn_if_over_quota(&orchestrator);
+    }
+
+    if cancel.is_cancelled() {
+        return Err(LyttError::Cancelled.into());
+    }
+    Ok(())
+}
+
+/// Transcribe every media file in a directory or matching a glob.
+///
+/// Files already ingested (by content hash, so moved and renamed files count)
+/// are skipped unless `force` is set.
+#[allow(clippy::too_many_arguments)]
+async fn run_transcribe_directory(
+    input: &str,
+    source: &LocalSource,
+    force: bool,
+    limit: Option<usize>,
+    meeting: bool,
+    tags: &[String],
+    series: Option<&str>,
+    settings: Settings,
+) -> Result<()> {
+    let files = match source.find_media_files(input) {
+        Ok(files) => files,
+        Err(e) => {
+            Output::error(&e.to_string());
+            return Err(e.into());
+        }
+    };
+    if files.is_empty() {
+        Output::warning("No audio or video files found");
+        return Ok(());
+    }
+
+    let total = files.len();
+    Output::info(&format!("Found {} files", total));
+    println!();
+
+    let cancel = ctrl_c_token();
+    let orchestrator = Orchestrator::new(settings)?
+        .with_meetings(meeting)
+        .with_cancellation(cancel.clone());
+    let store = orchestrator.sqlite_store();
+    let limit = limit.unwrap_or(usize::MAX);
+
+    let mut success_count = 0;
+    let mut skip_count = 0;
+    let mut error_count = 0;
+
+    for (i, file) in files.iter().enumerate() {
+        if cancel.is_cancelled() {
+            Output::warning(&format!("Cancelled; {} files not processed", total - i));
+            break;
+        }
+        if success_count + error_count >= limit {
+            Output::info(&format!("Reached --limit; {} files not processed", total - i));
+            break;
+        }
+        let path = file.to_string_lossy();
+        let progress = format!("[{}/{}]", i + 1, total);
+        Output::info(&format!("{} Processing: {}", progress, path));
+
+        let hash = {
+            let file = file.clone();
+            tokio::task::spawn_blocking(move || content_hash(&file)).await?
+        };
+        let hash = match hash {
+            Ok(hash) => hash,
+            Err(e) => {
+                Output::error(&format!("  Failed to read: {}", e));
+                error_count += 1;
+                continue;
+            }
+        };
+        if !force {
+            if let Some((ingested_path, media_id)) = store.ingested_file(&hash)? {
+                let note = if ingested_path == path { String::new() } else { format!(" from {}", ingested_path) };
+                Output::warning(&format!("  Skipped (already ingested as {}{})", media_id, note));
+                skip_count += 1;
+                continue;
+            }
+        }
+
+        match orchestrator.process_media(&path, force).await {
+            Ok(result) => {
+                apply_tags(&orchestrator, &result.media_id, tags);
+                if let Some(original) = &result.duplicate_of {
+                    Output::warning(&format!("  Skipped (likely duplicate of {})", original));
+                    skip_count += 1;
+                    continue;
+                }
+                apply_series(&orchestrator, &result.media_id, series);
+                if let Err(e) = store.record_ingested_file(&hash, &path, &result.media_id) {
+                    Output::warning(&format!("  Failed to record the file as ingested: {}", e));
+                }
+                if result.skipped {
+                    Output::warning("  Skipped (already indexed)");
+                    skip_count += 1;
+                } else {
+                    Output::success(&format!("  Indexed ({} chunks)", result.chunks_indexed));
+                    success_count += 1;
+                }
+            }
+            Err(LyttError::Cancelled) => {
+                Output::warning(&format!("  Cancelled; {} files not processed", total - i));
+                break;
+            }
+            Err(e) => {
+                Output::error(&format!("  Failed: {}", e));
+                error_count += 1;
+            }
+        }
+    }
+
+    println!();
+    Output::info(&format!(
+        "Directory complete: {} indexed, {} skipped, {} failed",
+        success_count, skip_count, error_count
+    ));
+
+    if success_count > 0 {
+        warn_if_over_quota(&orchestrator);
+    }
+
+    if cancel.is_cancelled() {
+        return Err(LyttError::Cancelled.into());
+    }
     Ok(())
 }
 
@@ -174,6 +741,8 @@ struct ChunkedOutput {
 struct ChunkOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
     content: String,
     start_seconds: f64,
     end_seconds: f64,
@@ -190,69 +759,94 @@ async fn run_transcribe_only(
     embed: bool,
     settings: &Settings,
 ) -> Result<()> {
-    let output_format: OutputFormat = format.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+    let output_format: OutputFormat = format.parse().map_err(LyttError::InvalidInput)?;
 
     // SRT/VTT don't support chunking or embeddings
     if chunk && output_format != OutputFormat::Json {
         Output::error("--chunk only supports JSON format");
-        return Err(anyhow::anyhow!("--chunk requires --format json"));
+        return Err(LyttError::InvalidInput("--chunk requires --format json".to_string()).into());
     }
 
     Output::info(&format!("Transcribing: {}", input));
 
     // Parse input to get source and media ID
     let (source, media_id) = parse_input(input)
-        .ok_or_else(|| anyhow::anyhow!("Could not parse input: {}", input))?;
+        .ok_or_else(|| LyttError::InvalidInput(format!("Could not parse input: {}", input)))?;
 
     // Fetch metadata
     let metadata = source.fetch_media(&media_id).await?;
     Output::info(&format!("Title: {}", metadata.title));
 
-    // Create temp directory
-    let temp_dir = settings.temp_dir();
-    std::fs::create_dir_all(&temp_dir)?;
+    let workspace = Workspace::from_settings(settings)?;
+    let temp_dir = workspace.path();
 
-    // Download audio
-    let spinner = Output::spinner("Downloading audio...");
-    let audio_path = download_audio(&metadata.source_url, &media_id, &temp_dir).await?;
-    spinner.finish_and_clear();
-
-    // Create transcriber - always use FusionTranscriber for LLM cleanup
     let prompts = Prompts::load(
         settings.prompts.custom_dir.as_deref(),
         Some(&settings.prompts.variables),
     )?;
-    let processing_settings = match settings.transcription.provider {
-        TranscriptionProvider::Whisper => TranscriptionProcessingSettings {
-            timestamp_model: settings.transcription.model.clone(),
-            text_model: None,
-            cleanup_model: settings.transcription.processing.cleanup_model.clone(),
-            max_concurrent: settings.transcription.max_concurrent_chunks,
-        },
-        TranscriptionProvider::Fusion => settings.transcription.processing.clone(),
+
+    let skipped = sponsorblock::ranges_for(&settings.youtube, &metadata).await;
+    let mut transcript = match captions_transcript(&settings.transcription, &metadata, temp_dir).await {
+        Some(mut transcript) => {
+            sponsorblock::drop_skipped(&mut transcript, &skipped);
+            transcript
+        }
+        None => {
+            // Download audio
+            let spinner = Output::spinner("Downloading audio...");
+            if let Some(duration) = metadata.duration_seconds {
+                workspace.ensure_space(u64::from(duration) * AUDIO_BYTES_PER_SECOND, "Downloading the audio")?;
+            }
+            let audio_path = if metadata.source_type == SourceType::Local {
+                download_audio(&metadata.source_url, &media_id, temp_dir).await?
+            } else {
+                download_audio_cached(&metadata.source_url, &media_id, temp_dir, &AudioCache::from_settings(settings)).await?
+            };
+            preprocess_audio(&audio_path, &settings.audio).await?;
+            if !skipped.is_empty() {
+                remove_ranges(&audio_path, &skipped).await?;
+            }
+            spinner.finish_and_clear();
+            if !skipped.is_empty() {
+                Output::info(&format!(
+                    "Skipping {:.0}s of SponsorBlock segments",
+                    sponsorblock::skipped_seconds(&skipped)
+                ));
+            }
+
+            // Create transcriber - always use FusionTranscriber for LLM cleanup
+            let transcriber: Box<dyn Transcriber> = Box::new(TranscriptionProcessor::from_settings(
+                &settings.transcription,
+                &prompts.cleanup.system,
+            )?);
+
+            // Transcribe
+            let spinner = Output::spinner("Transcribing...");
+            let mut transcript = transcriber.transcribe(&audio_path).await?;
+            sponsorblock::restore_timeline(&mut transcript, &skipped);
+            spinner.finish_and_clear();
+
+            // Cleanup
+            if let Err(e) = std::fs::remove_file(&audio_path) {
+                tracing::warn!("Failed to cleanup audio file: {}", e);
+            }
+            transcript
+        }
     };
-    let transcriber: Box<dyn Transcriber> = Box::new(TranscriptionProcessor::with_config(
-        &processing_settings,
-        &prompts.cleanup.system,
-    )?);
-
-    // Transcribe
-    let spinner = Output::spinner("Transcribing...");
-    let transcript = transcriber.transcribe(&audio_path).await?;
-    spinner.finish_and_clear();
+    if transcript.chapters.is_empty() {
+        transcript.chapters = metadata.chapters.clone();
+    }
+
+    let hooks = HookRegistry::from_settings(settings);
+    let hook_ctx = HookContext::new(&media_id, &metadata.title);
+    hooks.run_transcript(&hook_ctx, &mut transcript).await?;
 
     // Format output based on flags
     let output_str = if chunk {
         // Apply semantic chunking
         let spinner = Output::spinner("Chunking...");
 
-        let strategy: ChunkingStrategy = settings
-            .chunking
-            .strategy
-            .parse()
-            .unwrap_or(ChunkingStrategy::Semantic);
-
-        let chunker = create_chunker(strategy);
+        let chunker = create_chunker_from_settings(&settings.chunking, prompts.clone())?;
 
         let config = ChunkingConfig {
             target_duration: settings.chunking.target_chunk_seconds,
@@ -260,19 +854,18 @@ async fn run_transcribe_only(
             max_duration: settings.chunking.max_chunk_seconds,
         };
 
-        let chunks = chunker.chunk(&transcript, &config).await?;
+        let mut chunks = chunker.chunk(&transcript, &config).await?;
+        hooks.run_chunks(&hook_ctx, &mut chunks).await?;
         spinner.finish_and_clear();
 
         // Generate embeddings if requested
         let (chunks_with_embeddings, embedding_model, embedding_dims) = if embed {
             let spinner = Output::spinner("Generating embeddings...");
 
-            let embedder = OpenAIEmbedder::with_config(
-                &settings.embedding.model,
-                settings.embedding.dimensions as usize,
-            );
+            let embedder = create_embedder(&settings.embedding)?;
 
-            let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+            let composite = settings.embedding.composite;
+            let texts: Vec<String> = chunks.iter().map(|c| c.embedding_text(composite)).collect();
             let embeddings = embedder.embed_batch(&texts).await?;
 
             spinner.finish_and_clear();
@@ -282,6 +875,7 @@ async fn run_transcribe_only(
                 .zip(embeddings)
                 .map(|(c, emb)| ChunkOutput {
                     title: c.title,
+                    summary: c.summary,
                     content: c.content,
                     start_seconds: c.start_seconds,
                     end_seconds: c.end_seconds,
@@ -299,6 +893,7 @@ async fn run_transcribe_only(
                 .into_iter()
                 .map(|c| ChunkOutput {
                     title: c.title,
+                    summary: c.summary,
                     content: c.content,
                     start_seconds: c.start_seconds,
                     end_seconds: c.end_seconds,
@@ -344,10 +939,5 @@ async fn run_transcribe_only(
         Output::success(&msg);
     }
 
-    // Cleanup
-    if let Err(e) = std::fs::remove_file(&audio_path) {
-        tracing::warn!("Failed to cleanup audio file: {}", e);
-    }
-
     Ok(())
 }
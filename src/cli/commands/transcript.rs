@@ -0,0 +1,173 @@
+//! Transcript command implementation.
+
+use super::open::parse_timestamp;
+use crate::audio_source::MediaMetadata;
+use crate::cli::output::format_duration;
+use crate::cli::{picker, Output, TranscriptAction};
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::meeting::split_speaker;
+use crate::orchestrator::Orchestrator;
+use crate::transcription::Transcript;
+use anyhow::Result;
+use console::{style, Term};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Pager used when `$PAGER` isn't set.
+const DEFAULT_PAGER: &str = "less";
+
+/// Run the transcript command.
+pub async fn run_transcript(action: &TranscriptAction, settings: Settings) -> Result<()> {
+    match action {
+        TranscriptAction::Show {
+            video,
+            from,
+            to,
+            speakers,
+            no_pager,
+        } => {
+            let from = from.as_deref().map(timestamp).transpose()?;
+            let to = to.as_deref().map(timestamp).transpose()?;
+            if let (Some(from), Some(to)) = (from, to) {
+                if to <= from {
+                    Output::error("--to must be after --from");
+                    return Err(LyttError::InvalidInput("--to must be after --from".to_string()).into());
+                }
+            }
+
+            let video_id = picker::media_id_or_pick(video.as_deref(), "Transcript to show", &settings).await?;
+            let orchestrator = Orchestrator::new(settings)?;
+            let Some((title, transcript)) = orchestrator.sqlite_store().get_transcript(&video_id)? else {
+                Output::error(&format!("No stored transcript for: {}", video_id));
+                return Err(LyttError::VideoNotFound(video_id).into());
+            };
+
+            let text = render(&title, &transcript, from, to, *speakers);
+            if *no_pager {
+                print!("{}", text);
+                Ok(())
+            } else {
+                page(&text)
+            }
+        }
+    }
+}
+
+fn timestamp(value: &str) -> Result<f64> {
+    parse_timestamp(value).ok_or_else(|| {
+        LyttError::InvalidInput(format!("Invalid timestamp: {}. Use seconds (90), MM:SS (1:30) or HH:MM:SS.", value)).into()
+    })
+}
+
+/// Lay out the transcript between `from` and `to` with a header, a heading
+/// at each chapter and a timestamp on every segment. With `speakers`, each
+/// change of speaker gets its own line instead of a `Name: ` prefix.
+fn render(title: &str, transcript: &Transcript, from: Option<f64>, to: Option<f64>, speakers: bool) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{}\n", style(title).bold()));
+    let mut details = vec![transcript.video_id.clone(), format_duration(transcript.duration_seconds)];
+    details.extend(transcript.language.clone());
+    out.push_str(&format!("{}\n", style(details.join(" · ")).dim()));
+
+    let from = from.unwrap_or(0.0);
+    let to = to.unwrap_or(f64::INFINITY);
+    let mut chapter = None;
+    let mut printed = 0;
+    for segment in transcript.segments.iter().filter(|s| s.end_seconds > from && s.start_seconds < to) {
+        let current = transcript.chapters.iter().rposition(|c| c.start_seconds <= segment.start_seconds);
+        if current != chapter {
+            if let Some(c) = current.map(|i| &transcript.chapters[i]) {
+                let start = MediaMetadata::format_timestamp(c.start_seconds);
+                out.push_str(&format!("\n{} {}\n", style(format!("## {}", c.title)).bold(), style(start).dim()));
+            }
+            chapter = current;
+        }
+
+        let mut text = segment.text.trim();
+        if speakers {
+            if let Some((name, rest)) = split_speaker(text) {
+                out.push_str(&format!("\n{}\n", style(name).bold().cyan()));
+                text = rest;
+            }
+        }
+        let start = MediaMetadata::format_timestamp(segment.start_seconds);
+        out.push_str(&format!("{} {}\n", style(format!("[{}]", start)).cyan(), text));
+        printed += 1;
+    }
+
+    if printed == 0 {
+        out.push_str(&format!("\n{}\n", style("No segments in this range.").yellow()));
+    }
+    out
+}
+
+/// Show `text` in `$PAGER` (or less) when writing to a terminal, otherwise
+/// print it.
+fn page(text: &str) -> Result<()> {
+    if !Term::stdout().is_term() {
+        print!("{}", text);
+        return Ok(());
+    }
+
+    let pager = std::env::var("PAGER").ok().filter(|p| !p.trim().is_empty());
+    let pager = pager.as_deref().unwrap_or(DEFAULT_PAGER);
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_PAGER);
+    let mut command = Command::new(program);
+    command.args(parts).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        // Keep colours, and don't page what fits on one screen
+        command.env("LESS", "FRX");
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_) => {
+            print!("{}", text);
+            return Ok(());
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes its input
+        match stdin.write_all(text.as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::{Chapter, TranscriptSegment};
+
+    #[test]
+    fn test_render() {
+        console::set_colors_enabled(false);
+        let segments = vec![
+            TranscriptSegment::new(0.0, 5.0, "Alice: Welcome everyone.".to_string()),
+            TranscriptSegment::new(5.0, 10.0, "First the budget.".to_string()),
+            TranscriptSegment::new(60.0, 70.0, "Bob: Numbers look fine.".to_string()),
+            TranscriptSegment::new(70.0, 80.0, "Alice: Good, let's ship it.".to_string()),
+        ];
+        let transcript = Transcript::new("local_abc".to_string(), segments).with_chapters(vec![
+            Chapter { title: "Intro".to_string(), start_seconds: 0.0, end_seconds: 60.0 },
+            Chapter { title: "Budget".to_string(), start_seconds: 60.0, end_seconds: 80.0 },
+        ]);
+
+        let all = render("Weekly Sync", &transcript, None, None, false);
+        assert!(all.starts_with("Weekly Sync\nlocal_abc · "));
+        assert!(all.contains("\n## Intro 00:00\n[00:00] Alice: Welcome everyone.\n[00:05] First the budget.\n"));
+        assert!(all.contains("\n## Budget 01:00\n[01:00] Bob: Numbers look fine.\n"));
+
+        let later = render("Weekly Sync", &transcript, Some(65.0), None, true);
+        assert!(!later.contains("Intro"));
+        assert!(later.contains("## Budget 01:00\n\nBob\n[01:00] Numbers look fine.\n\nAlice\n[01:10] Good, let's ship it.\n"));
+
+        let empty = render("Weekly Sync", &transcript, Some(20.0), Some(30.0), false);
+        assert!(empty.contains("No segments in this range."));
+    }
+}
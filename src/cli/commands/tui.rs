@@ -0,0 +1,12 @@
+//! TUI command implementation.
+
+use crate::config::Settings;
+use crate::orchestrator::Orchestrator;
+use anyhow::Result;
+
+/// Run the tui command.
+pub async fn run_tui(settings: Settings) -> Result<()> {
+    let orchestrator = Orchestrator::new(settings)?;
+    crate::tui::run(&orchestrator, 20).await?;
+    Ok(())
+}
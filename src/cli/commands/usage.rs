@@ -0,0 +1,66 @@
+//! Usage command implementation.
+
+use crate::cli::Output;
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::usage::UsageGroupBy;
+use crate::vector_store::SqliteVectorStore;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use console::style;
+
+/// Run the usage command.
+pub fn run_usage(by: &str, days: Option<u32>, settings: Settings) -> Result<()> {
+    let group_by: UsageGroupBy = by.parse().map_err(|e: String| {
+        Output::error(&e);
+        LyttError::InvalidInput(e)
+    })?;
+
+    let store = SqliteVectorStore::new(&settings.sqlite_path())?;
+    let since = days.map(|d| Utc::now() - Duration::days(d as i64));
+    let summary = store.usage_summary(group_by, since)?;
+
+    if summary.is_empty() {
+        Output::info("No usage recorded yet.");
+        return Ok(());
+    }
+
+    Output::header(&format!("API Usage by {}", by));
+    println!();
+    println!(
+        "  {:<32} {:>5} {:>12} {:>12} {:>9} {:>10}",
+        style("Key").bold(),
+        style("Runs").bold(),
+        style("In tokens").bold(),
+        style("Out tokens").bold(),
+        style("Audio").bold(),
+        style("Cost").bold()
+    );
+
+    for row in &summary {
+        println!(
+            "  {:<32} {:>5} {:>12} {:>12} {:>8.1}m {:>10}",
+            truncate(&row.key, 32),
+            row.runs,
+            row.input_tokens,
+            row.output_tokens,
+            row.audio_seconds / 60.0,
+            format!("${:.4}", row.cost_usd)
+        );
+    }
+
+    let total: f64 = summary.iter().map(|r| r.cost_usd).sum();
+    println!();
+    Output::kv("Total cost", &format!("${:.4}", total));
+
+    Ok(())
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", truncated)
+    }
+}
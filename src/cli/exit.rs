@@ -0,0 +1,85 @@
+//! Exit codes for failed commands.
+//!
+//! Every command reports failure through the same mapping, so scripts can
+//! branch on the class of failure rather than parse messages.
+
+use crate::error::LyttError;
+use std::process::ExitCode;
+
+/// Class of failure, reported as the process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// Anything not covered below.
+    Other,
+    /// Invalid arguments, input or configuration. Matches clap's usage errors.
+    InvalidInput,
+    /// The requested media or file doesn't exist.
+    NotFound,
+    /// A required external tool (ffmpeg, yt-dlp, ...) isn't installed.
+    ToolMissing,
+    /// An API or network request failed.
+    Api,
+    /// Stopped by Ctrl+C.
+    Cancelled,
+}
+
+impl Failure {
+    /// Classify an error by the first `LyttError` in its chain.
+    pub fn of(error: &anyhow::Error) -> Self {
+        let Some(error) = error.chain().find_map(|e| e.downcast_ref::<LyttError>()) else {
+            return Failure::Other;
+        };
+        match error {
+            LyttError::InvalidInput(_) | LyttError::Config(_) | LyttError::TomlParse(_) => Failure::InvalidInput,
+            LyttError::VideoNotFound(_) => Failure::NotFound,
+            LyttError::Io(e) if e.kind() == std::io::ErrorKind::NotFound => Failure::NotFound,
+            LyttError::ToolNotFound(_) => Failure::ToolMissing,
+            LyttError::OpenAI(_) | LyttError::Llm(_) | LyttError::Http(_) | LyttError::Embedding(_) => Failure::Api,
+            LyttError::Cancelled => Failure::Cancelled,
+            _ => Failure::Other,
+        }
+    }
+
+    /// The exit code for this class of failure.
+    pub fn code(self) -> u8 {
+        match self {
+            Failure::Other => 1,
+            Failure::InvalidInput => 2,
+            Failure::NotFound => 3,
+            Failure::ToolMissing => 4,
+            Failure::Api => 5,
+            // 128 + SIGINT, as shells report a process killed by Ctrl+C
+            Failure::Cancelled => 130,
+        }
+    }
+}
+
+/// The exit code for a command's result.
+pub fn exit_code(result: &anyhow::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => ExitCode::from(Failure::of(e).code()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_classes() {
+        let class = |e: LyttError| Failure::of(&anyhow::Error::from(e));
+        assert_eq!(class(LyttError::InvalidInput("x".into())), Failure::InvalidInput);
+        assert_eq!(class(LyttError::VideoNotFound("x".into())), Failure::NotFound);
+        assert_eq!(class(LyttError::ToolNotFound("ffmpeg".into())), Failure::ToolMissing);
+        assert_eq!(class(LyttError::OpenAI("rate limited".into())), Failure::Api);
+        assert_eq!(class(LyttError::VectorStore("x".into())), Failure::Other);
+
+        // Context added on top doesn't hide the cause
+        let wrapped = anyhow::Error::from(LyttError::ToolNotFound("yt-dlp".into())).context("Download failed");
+        assert_eq!(Failure::of(&wrapped), Failure::ToolMissing);
+        assert_eq!(Failure::of(&anyhow::anyhow!("plain")), Failure::Other);
+        assert_eq!(Failure::Api.code(), 5);
+        assert_eq!(class(LyttError::Cancelled).code(), 130);
+    }
+}
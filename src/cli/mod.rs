@@ -1,7 +1,10 @@
 //! CLI module for Lytt.
 
+pub mod cancel;
 pub mod commands;
+pub mod exit;
 mod output;
+pub mod picker;
 pub mod preflight;
 
 pub use output::Output;
@@ -20,10 +23,23 @@ pub struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Only print results, warnings and errors (no status messages or progress)
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
     /// Path to configuration file
     #[arg(short, long, global = true)]
     pub config: Option<String>,
 
+    /// Named configuration profile to use (from [profiles.<name>])
+    #[arg(long, global = true, env = "LYTT_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Log format: text, or json for one JSON object per line with the run ID
+    /// of the command, request or job on every line
+    #[arg(long, global = true, default_value = "text", env = "LYTT_LOG_FORMAT")]
+    pub log_format: String,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -38,7 +54,8 @@ pub enum Commands {
 
     /// Transcribe and index audio/video content
     Transcribe {
-        /// YouTube URL/ID, or local audio/video file path
+        /// YouTube URL/ID, another URL yt-dlp supports, local audio/video file, directory or glob ("archive/**/*.m4a"),
+        /// or an existing transcript (.srt, .vtt, .json) to index without audio
         input: String,
 
         /// Force re-processing even if already indexed
@@ -65,9 +82,63 @@ pub enum Commands {
         #[arg(long)]
         playlist: bool,
 
-        /// Maximum number of videos to transcribe from playlist (default: all)
+        /// Maximum number of videos to transcribe from playlist, or new files from a directory (default: all)
         #[arg(long)]
         limit: Option<usize>,
+
+        /// With a directory, also transcribe files in subdirectories
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// With a directory or glob, skip paths matching this pattern (repeatable)
+        #[arg(long = "exclude", value_name = "PATTERN")]
+        excludes: Vec<String>,
+
+        /// Treat local files as meeting recordings: read speakers, attendees and the title
+        /// from Zoom, Teams or Meet files exported alongside
+        #[arg(long)]
+        meeting: bool,
+
+        /// Index an audiobook (an .m4b, or a directory or glob of its parts) as one media item,
+        /// chunked by chapter
+        #[arg(long, conflicts_with_all = ["playlist", "output", "meeting"])]
+        audiobook: bool,
+
+        /// Estimate API cost from media duration without transcribing
+        #[arg(long)]
+        estimate: bool,
+
+        /// Show the planned pipeline, the media it would transcribe or skip and the
+        /// estimated chunks and cost, without downloading or calling any paid API
+        #[arg(long, conflicts_with_all = ["estimate", "output"])]
+        dry_run: bool,
+
+        /// Tag the transcribed media (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+
+        /// Add the transcribed media to this series, in order, creating it if needed
+        #[arg(long)]
+        series: Option<String>,
+
+        /// Title for an imported transcript file (default: generated from the transcript)
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Record from the microphone with live transcription; indexed when stopped (Ctrl+C)
+    Record {
+        /// Title for the recording (default: generated from the transcript)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Capture device, overriding [record] device (e.g. hw:1 for ALSA, :1 on macOS)
+        #[arg(long)]
+        device: Option<String>,
+
+        /// Tag the recording (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
     },
 
     /// Ask a question and get an answer from your audio library
@@ -79,9 +150,66 @@ pub enum Commands {
         #[arg(short, long)]
         model: Option<String>,
 
-        /// Maximum number of context chunks to include
+        /// Maximum number of context chunks to include (when rag.context_tokens is 0)
         #[arg(short = 'c', long, default_value = "10")]
         max_chunks: usize,
+
+        /// Derived documents (rollups, summaries): include, exclude, or only
+        #[arg(long, default_value = "include")]
+        derived: String,
+
+        /// Only use media published (or indexed) within this window, e.g. 30d, 2w, 12h or 2025-01-31
+        #[arg(long, conflicts_with = "attach")]
+        since: Option<String>,
+
+        /// Only search this video (by ID)
+        #[arg(long, conflicts_with = "attach")]
+        video: Option<String>,
+
+        /// Only search media in this series (see `lytt series`)
+        #[arg(long, conflicts_with = "attach")]
+        series: Option<String>,
+
+        /// Compare how the media in --videos answer the question: agreements,
+        /// contradictions and unique points
+        #[arg(long, requires = "videos", conflicts_with_all = ["attach", "video", "series"])]
+        compare: bool,
+
+        /// Media to compare, as comma-separated IDs (with --compare)
+        #[arg(long, value_delimiter = ',', requires = "compare")]
+        videos: Vec<String>,
+
+        /// Answer from this audio file (or URL) only, without adding it to the library
+        #[arg(long)]
+        attach: Option<String>,
+
+        /// With --attach, also index the media into the library
+        #[arg(long, requires = "attach")]
+        save: bool,
+
+        /// Always generate a fresh answer instead of reusing a cached one
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Also search LLM rephrasings of the question (see rag.multi_query)
+        #[arg(long)]
+        multi_query: bool,
+
+        /// Rank newer media above older media on similar matches (see rag.recency)
+        #[arg(long)]
+        recent: bool,
+
+        /// Check each claim of the answer against the sources (see rag.verify)
+        #[arg(long)]
+        verify: bool,
+
+        /// Read the answer aloud (engine and voice in [tts])
+        #[arg(long)]
+        speak: bool,
+
+        /// Save the spoken answer to this file instead of playing it
+        #[arg(long, value_name = "FILE")]
+        speak_to: Option<String>,
     },
 
     /// Search for relevant audio segments
@@ -96,6 +224,56 @@ pub enum Commands {
         /// Minimum similarity score (0.0-1.0)
         #[arg(short, long, default_value = "0.3")]
         min_score: f32,
+
+        /// Derived documents (rollups, summaries): include, exclude, or only
+        #[arg(long, default_value = "include")]
+        derived: String,
+
+        /// Only chunks tagged with this keyword (see `lytt list --detail`)
+        #[arg(short, long)]
+        keyword: Option<String>,
+
+        /// Only chunks in this stored topic (see `lytt topics --list`)
+        #[arg(long)]
+        topic: Option<i64>,
+
+        /// Only media in this series (see `lytt series`)
+        #[arg(long)]
+        series: Option<String>,
+
+        /// Only media in this spoken language (e.g. "en" or "german")
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Rank newer media above older media on similar matches (see rag.recency)
+        #[arg(long)]
+        recent: bool,
+    },
+
+    /// Find exact words in the stored transcripts
+    Grep {
+        /// Text to find (a regular expression with --regex)
+        pattern: String,
+
+        /// Treat the pattern as a regular expression
+        #[arg(short = 'E', long)]
+        regex: bool,
+
+        /// Match regardless of case
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// Segments of context shown before and after each match
+        #[arg(short = 'C', long, default_value = "1")]
+        context: usize,
+
+        /// Only search this media ID
+        #[arg(long)]
+        video: Option<String>,
+
+        /// Maximum number of matches
+        #[arg(short = 'n', long, default_value = "50")]
+        limit: usize,
     },
 
     /// Start an interactive chat session
@@ -103,6 +281,10 @@ pub enum Commands {
         /// LLM model to use
         #[arg(short, long)]
         model: Option<String>,
+
+        /// Read each answer aloud (engine and voice in [tts])
+        #[arg(long)]
+        speak: bool,
     },
 
     /// Run an AI agent to perform tasks (summarize, create quiz, research, etc.)
@@ -120,26 +302,424 @@ pub enum Commands {
     },
 
     /// List indexed media
-    List,
+    List {
+        /// Show stored summaries under each item
+        #[arg(long)]
+        detail: bool,
+
+        /// Only media in this spoken language (e.g. "en" or "german")
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Sort order (date, duration, chunks, title)
+        #[arg(long, default_value = "date")]
+        sort: String,
+
+        /// Only media from this source (youtube, web, local)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Only media from this channel
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only media indexed within this window (e.g. "7d", "2w", "1m")
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Print JSON (every match unless --page is given)
+        #[arg(long, conflicts_with = "table")]
+        json: bool,
+
+        /// Print a table with one row per item
+        #[arg(long)]
+        table: bool,
+
+        /// Page to show, starting at 1
+        #[arg(long)]
+        page: Option<usize>,
+
+        /// Items per page
+        #[arg(long, default_value = "50")]
+        page_size: usize,
+    },
+
+    /// Browse the library in an interactive terminal UI
+    Tui,
 
     /// Rechunk indexed media without re-transcribing
     Rechunk {
-        /// Video ID to rechunk (use 'all' to rechunk everything)
+        /// Video ID to rechunk (use 'all' to rechunk everything; omit to pick interactively)
+        video_id: Option<String>,
+
+        /// Rechunk only media chunked with different chunking settings or prompts
+        #[arg(long, conflicts_with = "video_id")]
+        stale: bool,
+    },
+
+    /// Re-embed the whole library with the configured embedding model
+    Reembed {
+        /// Shorten and re-encode the stored vectors instead of calling the embedding API
+        #[arg(long)]
+        local: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Add or remove tags on indexed media
+    Tag {
+        /// Video ID to tag
         video_id: String,
+
+        /// Tags to add (or remove with --remove). Shows current tags if omitted
+        tags: Vec<String>,
+
+        /// Remove the given tags instead of adding them
+        #[arg(long)]
+        remove: bool,
     },
 
-    /// Export transcript from indexed media
-    Export {
-        /// Video ID to export
+    /// Generate a summary rollup across all media with a tag or channel
+    Rollup {
+        /// Tag to roll up
+        #[arg(short, long, conflicts_with = "channel", required_unless_present_any = ["channel", "list"])]
+        tag: Option<String>,
+
+        /// Channel to roll up (media are tagged with their channel automatically)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Period to cover: week, month, quarter, year, or all
+        #[arg(short, long, default_value = "month")]
+        period: String,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// List stored rollups instead of generating one
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Summarize the media indexed in a period into one briefing
+    Digest {
+        /// Period to cover, e.g. 1d, 7d, 2w or 2025-01-31
+        #[arg(short, long, default_value = "7d")]
+        since: String,
+
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// Output file (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Email the digest to the [digest] email_to recipients
+        #[arg(long)]
+        email: bool,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Generate and store per-section and whole-media summaries
+    Summarize {
+        /// Video ID to summarize (omit to pick interactively)
+        video_id: Option<String>,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// Show the stored summary instead of generating a new one
+        #[arg(long)]
+        show: bool,
+    },
+
+    /// Prune the library according to the retention policies
+    Prune {
+        /// Show what would be pruned without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Find re-uploads and other near-duplicate media
+    Dedupe {
+        /// Similarity at which two chunks count as the same (default: dedupe.chunk_threshold)
+        #[arg(long)]
+        threshold: Option<f32>,
+
+        /// Fraction of matching chunks that makes a duplicate (default: dedupe.min_overlap)
+        #[arg(long)]
+        min_overlap: Option<f32>,
+
+        /// Delete the later copy of each duplicate
+        #[arg(long)]
+        delete: bool,
+
+        /// Delete without asking for confirmation
+        #[arg(short, long, requires = "delete")]
+        yes: bool,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Generate flashcards from indexed media
+    Quiz {
+        /// Video ID to quiz on
         video_id: String,
 
+        /// Output format (anki, json, markdown)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// Number of cards to generate
+        #[arg(short = 'n', long, default_value = "10")]
+        count: usize,
+
+        /// Output file (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Bundle summaries, key quotes, a glossary and a quiz for all media with a tag
+    Pack {
+        /// Tag to build the pack from
+        #[arg(short, long)]
+        tag: String,
+
+        /// Output directory
+        #[arg(short, long, default_value = "pack")]
+        output: String,
+
+        /// Flashcards per media item
+        #[arg(long, default_value = "5")]
+        cards: usize,
+
+        /// Key quotes per media item
+        #[arg(long, default_value = "5")]
+        quotes: usize,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Report where sources agree, disagree, or changed position on a topic
+    Consensus {
+        /// Topic to compare sources on
+        topic: String,
+
+        /// Only use media published (or indexed) within this window, e.g. 30d, 2w, 12h or 2025-01-31
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Maximum excerpts to compare
+        #[arg(short = 'n', long, default_value = "24")]
+        limit: usize,
+
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Trace how a topic evolved across the library as a dated timeline
+    Timeline {
+        /// Topic to trace
+        topic: String,
+
+        /// Only use media published (or indexed) within this window, e.g. 30d, 2w, 12h or 2025-01-31
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Maximum excerpts to order
+        #[arg(short = 'n', long, default_value = "40")]
+        limit: usize,
+
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Cluster the library into topics and show a map of them
+    Topics {
+        /// Number of topics (default: based on library size)
+        #[arg(short = 'k', long)]
+        clusters: Option<usize>,
+
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// Show the stored topics instead of recomputing them
+        #[arg(long, conflicts_with_all = ["clusters", "model"])]
+        list: bool,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Extract domain terms and their definitions across the library
+    Glossary {
+        /// Only this video
+        #[arg(long, conflicts_with = "tag")]
+        video: Option<String>,
+
+        /// Only media with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Output format (markdown, csv)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
         /// Output file (stdout if not specified)
         #[arg(short, long)]
         output: Option<String>,
 
-        /// Output format (json, srt, vtt)
+        /// Re-extract terms even for media with a stored glossary
+        #[arg(long)]
+        refresh: bool,
+
+        /// LLM model to use
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Export transcript from indexed media
+    Export {
+        /// Video ID to export (omit to pick interactively)
+        video_id: Option<String>,
+
+        /// Export every indexed chunk (with --format jsonl)
+        #[arg(long, conflicts_with = "video_id")]
+        all: bool,
+
+        /// Output file (stdout if not specified; <ID>.docx or <ID>.pdf for documents)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Output format (json, jsonl, srt, vtt, docx, pdf)
         #[arg(long, default_value = "json")]
         format: String,
+
+        /// Include each chunk's embedding (jsonl only)
+        #[arg(long)]
+        embeddings: bool,
+    },
+
+    /// Cut the best-matching segments for a query into one audio file
+    Highlights {
+        /// What the clips should be about
+        query: String,
+
+        /// Output file (MP3)
+        #[arg(short, long, default_value = "highlights.mp3")]
+        output: String,
+
+        /// Number of clips
+        #[arg(short = 'n', long, default_value = "5")]
+        count: usize,
+
+        /// Longest clip, in seconds
+        #[arg(long, default_value = "90")]
+        max_clip: f64,
+
+        /// Minimum similarity score
+        #[arg(long, default_value = "0.3")]
+        min_score: f32,
+
+        /// Announce each clip's source with text-to-speech (settings in [tts])
+        #[arg(long)]
+        announce: bool,
+    },
+
+    /// Open indexed media at a timestamp (browser for YouTube, mpv/vlc for local files)
+    Open {
+        /// Media ID or chunk ID (omit to pick interactively)
+        target: Option<String>,
+
+        /// Where to start: seconds (90), MM:SS or HH:MM:SS (default: the chunk's start, or 0)
+        timestamp: Option<String>,
+
+        /// Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Export one Markdown note per media item into an Obsidian vault
+    ExportNotes {
+        /// Vault directory
+        #[arg(long)]
+        vault: String,
+
+        /// Folder inside the vault for the notes
+        #[arg(long, default_value = "Lytt")]
+        folder: String,
+
+        /// Maximum related media linked from each note
+        #[arg(long, default_value = "5")]
+        related: usize,
+    },
+
+    /// Show API usage and cost
+    Usage {
+        /// Group by: video, day, or model
+        #[arg(long, default_value = "video")]
+        by: String,
+
+        /// Only include usage from the last N days
+        #[arg(long)]
+        days: Option<u32>,
+    },
+
+    /// Show the processing runs of a media item and the configuration each ran under
+    History {
+        /// Media ID (omit to pick interactively)
+        video_id: Option<String>,
+
+        /// Most recent runs to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// Print JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show how often indexed media is retrieved and cited
+    Stats {
+        /// Only show the most retrieved and cited media
+        #[arg(long)]
+        top_sources: bool,
+
+        /// Only show media that has never been retrieved
+        #[arg(long)]
+        never_retrieved: bool,
+
+        /// Maximum media per list
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
     },
 
     /// Start HTTP API server for integration with other systems
@@ -151,6 +731,29 @@ pub enum Commands {
         /// Port to bind to
         #[arg(short, long, default_value = "3000")]
         port: u16,
+
+        /// Serve read-only from a replicated database file (Litestream/LiteFS)
+        #[arg(long)]
+        replica_of: Option<String>,
+
+        /// Also serve the gRPC API on this port (requires the `grpc` feature)
+        #[arg(long)]
+        grpc_port: Option<u16>,
+    },
+
+    /// Run the API server with scheduled jobs: watch folders, subscriptions and digests
+    Daemon {
+        /// Host to bind to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to bind to
+        #[arg(short, long, default_value = "3000")]
+        port: u16,
+
+        /// Also serve the gRPC API on this port (requires the `grpc` feature)
+        #[arg(long)]
+        grpc_port: Option<u16>,
     },
 
     /// Start MCP server for AI assistant integration (Claude, etc.)
@@ -161,6 +764,174 @@ pub enum Commands {
         #[command(subcommand)]
         action: ConfigAction,
     },
+
+    /// Rebuild and check derived indexes (ANN, full-text, topics, quantized)
+    Index {
+        #[command(subcommand)]
+        action: IndexAction,
+    },
+
+    /// Database maintenance
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// Manage the cache of downloaded audio
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Group media into series (a course, a podcast season, a meeting series)
+    Series {
+        #[command(subcommand)]
+        action: SeriesAction,
+    },
+
+    /// Read stored transcripts
+    Transcript {
+        #[command(subcommand)]
+        action: TranscriptAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TranscriptAction {
+    /// Print a transcript with chapter headings and timestamps, in a pager
+    Show {
+        /// Media ID (omit to pick interactively)
+        video: Option<String>,
+
+        /// Start at this time: seconds (90), MM:SS or HH:MM:SS
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Stop at this time: seconds (90), MM:SS or HH:MM:SS
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Put each change of speaker on its own line (meeting recordings)
+        #[arg(long)]
+        speakers: bool,
+
+        /// Print straight to the terminal instead of through $PAGER
+        #[arg(long)]
+        no_pager: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbAction {
+    /// Checkpoint the WAL, vacuum, analyze and check integrity, then report table sizes
+    Maintain {
+        /// Skip VACUUM, which rewrites the whole file and needs as much free disk space
+        #[arg(long)]
+        no_vacuum: bool,
+
+        /// Only check integrity and report sizes; change nothing
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Re-identify local media indexed under path-based IDs by file content
+    MigrateIds {
+        /// Directory to look for files that have moved since they were indexed (repeatable)
+        #[arg(long = "search", value_name = "DIR")]
+        search: Vec<String>,
+
+        /// Show what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// List cached audio, most recently used first
+    Ls,
+
+    /// Remove cached audio
+    Clear {
+        /// Only remove the audio of this media
+        video_id: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SeriesAction {
+    /// List series with their episodes, duration, chunks and retrievals
+    Ls {
+        /// Print JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the episodes of a series and their stats
+    Show {
+        /// Series name
+        name: String,
+
+        /// Print JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Add media to a series, as its next episodes in the order given (creates the series)
+    Add {
+        /// Series name
+        name: String,
+
+        /// Media IDs
+        #[arg(required = true)]
+        video_ids: Vec<String>,
+
+        /// Episode number (a single media item only)
+        #[arg(long)]
+        episode: Option<u32>,
+
+        /// Describe the series
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Take media out of its series
+    Remove {
+        /// Media IDs
+        #[arg(required = true)]
+        video_ids: Vec<String>,
+    },
+
+    /// Delete a series (its media stays indexed)
+    Delete {
+        /// Series name
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IndexAction {
+    /// Rebuild derived indexes from the stored documents (all enabled indexes if none given)
+    Rebuild {
+        /// Rebuild the approximate nearest-neighbour index
+        #[arg(long)]
+        ann: bool,
+
+        /// Rebuild the full-text search index
+        #[arg(long)]
+        fts: bool,
+
+        /// Rebuild topic clusters
+        #[arg(long)]
+        topics: bool,
+
+        /// Rebuild the quantized embeddings
+        #[arg(long)]
+        quantized: bool,
+    },
+
+    /// Check that derived indexes cover every stored document
+    Status,
 }
 
 #[derive(Subcommand, Debug)]
@@ -181,4 +952,7 @@ pub enum ConfigAction {
 
     /// Show configuration file path
     Path,
+
+    /// List configured profiles
+    Profiles,
 }
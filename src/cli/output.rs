@@ -1,30 +1,72 @@
 //! CLI output formatting utilities.
 
+use crate::logging;
 use console::{style, Style};
 use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--quiet`.
+static QUIET: AtomicBool = AtomicBool::new(false);
 
 /// Output helper for CLI formatting.
+///
+/// With `--log-format json`, info, success, warning and error messages are
+/// logged as events instead of printed, and progress indicators are hidden.
 pub struct Output;
 
 impl Output {
+    /// Hide info and success messages and progress indicators (`--quiet`).
+    /// Results, warnings and errors are still printed.
+    pub fn set_quiet(quiet: bool) {
+        QUIET.store(quiet, Ordering::Relaxed);
+    }
+
+    /// Whether `--quiet` is in effect.
+    pub fn is_quiet() -> bool {
+        QUIET.load(Ordering::Relaxed)
+    }
+
     /// Print an info message.
     pub fn info(msg: &str) {
-        println!("{} {}", style(">>").cyan().bold(), msg);
+        if Self::is_quiet() {
+            return;
+        }
+        if logging::is_json() {
+            tracing::info!("{}", msg);
+        } else {
+            println!("{} {}", style(">>").cyan().bold(), msg);
+        }
     }
 
     /// Print a success message.
     pub fn success(msg: &str) {
-        println!("{} {}", style(">>").green().bold(), msg);
+        if Self::is_quiet() {
+            return;
+        }
+        if logging::is_json() {
+            tracing::info!("{}", msg);
+        } else {
+            println!("{} {}", style(">>").green().bold(), msg);
+        }
     }
 
     /// Print a warning message.
     pub fn warning(msg: &str) {
-        eprintln!("{} {}", style(">>").yellow().bold(), msg);
+        if logging::is_json() {
+            tracing::warn!("{}", msg);
+        } else {
+            eprintln!("{} {}", style(">>").yellow().bold(), msg);
+        }
     }
 
     /// Print an error message.
     pub fn error(msg: &str) {
-        eprintln!("{} {}", style(">>").red().bold(), msg);
+        if logging::is_json() {
+            tracing::error!("{}", msg);
+        } else {
+            eprintln!("{} {}", style(">>").red().bold(), msg);
+        }
     }
 
     /// Print a header.
@@ -37,26 +79,47 @@ impl Output {
         println!("  {}: {}", style(key).dim(), value);
     }
 
+    /// Prompt for yes/no confirmation (default no).
+    pub fn confirm(message: &str) -> io::Result<bool> {
+        print!("{} {} ", style("?").cyan(), message);
+        print!("{} ", style("[y/N]").dim());
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes")
+    }
+
     /// Print a list item.
     pub fn list_item(msg: &str) {
         println!("  {} {}", style("*").cyan(), msg);
     }
 
     /// Print media info.
-    pub fn media_info(title: &str, id: &str, chunks: u32, duration: f64) {
+    pub fn media_info(title: &str, id: &str, chunks: u32, duration: f64, language: Option<&str>) {
         let duration_str = format_duration(duration);
+        let language_str = language.map(|l| format!(", {}", l)).unwrap_or_default();
         println!(
-            "  {} {} ({}, {} chunks, {})",
+            "  {} {} ({}, {} chunks, {}{})",
             style("*").cyan(),
             style(title).bold(),
             style(id).dim(),
             chunks,
-            duration_str
+            duration_str,
+            language_str
         );
     }
 
     /// Print search result.
-    pub fn search_result(title: &str, timestamp: &str, score: f32, content: &str, url: Option<&str>) {
+    pub fn search_result(
+        title: &str,
+        timestamp: &str,
+        score: f32,
+        summary: Option<&str>,
+        content: &str,
+        url: Option<&str>,
+    ) {
         println!(
             "\n{} {} @ {} (score: {:.2})",
             style(">>").green(),
@@ -64,6 +127,9 @@ impl Output {
             style(timestamp).cyan(),
             score
         );
+        if let Some(summary) = summary {
+            println!("   {}", style(summary).italic());
+        }
         println!("   {}", content_preview(content, 200));
         if let Some(u) = url {
             println!("   {}", style(u).dim());
@@ -72,6 +138,9 @@ impl Output {
 
     /// Create a progress bar.
     pub fn progress_bar(len: u64, msg: &str) -> ProgressBar {
+        if Self::is_quiet() || logging::is_json() {
+            return ProgressBar::hidden();
+        }
         let pb = ProgressBar::new(len);
         pb.set_style(
             ProgressStyle::default_bar()
@@ -85,6 +154,9 @@ impl Output {
 
     /// Create a spinner.
     pub fn spinner(msg: &str) -> ProgressBar {
+        if Self::is_quiet() || logging::is_json() {
+            return ProgressBar::hidden();
+        }
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::default_spinner()
@@ -108,7 +180,7 @@ impl Output {
 }
 
 /// Format duration in seconds to a human-readable string.
-fn format_duration(seconds: f64) -> String {
+pub(crate) fn format_duration(seconds: f64) -> String {
     let total_seconds = seconds as u32;
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;
@@ -123,6 +195,16 @@ fn format_duration(seconds: f64) -> String {
     }
 }
 
+/// Shorten a string to at most `max_len` characters, ending in "...".
+pub(crate) fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", truncated)
+    }
+}
+
 /// Truncate content with ellipsis.
 fn content_preview(content: &str, max_len: usize) -> String {
     let content = content.replace('\n', " ");
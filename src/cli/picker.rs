@@ -0,0 +1,83 @@
+//! Interactive fuzzy picker for indexed media.
+//!
+//! Commands that take a media ID can be run without one; the user then
+//! searches the indexed titles instead of typing a YouTube or `local_...` ID.
+
+use crate::cli::output::{format_duration, truncate};
+use crate::config::Settings;
+use crate::error::LyttError;
+use crate::orchestrator::Orchestrator;
+use crate::vector_store::IndexedVideo;
+use anyhow::Result;
+use console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::FuzzySelect;
+
+/// Return `video_id` if given, otherwise let the user pick indexed media.
+///
+/// Fails with invalid input when no ID is given and the terminal isn't
+/// interactive (e.g. in scripts).
+pub async fn media_id_or_pick(video_id: Option<&str>, prompt: &str, settings: &Settings) -> Result<String> {
+    if let Some(id) = video_id {
+        return Ok(id.to_string());
+    }
+    if !Term::stderr().is_term() {
+        return Err(LyttError::InvalidInput("A media ID is required when not running in a terminal".to_string()).into());
+    }
+
+    let orchestrator = Orchestrator::new(settings.clone())?;
+    let media = orchestrator.vector_store().list_videos().await?;
+    if media.is_empty() {
+        return Err(LyttError::VideoNotFound("no media indexed yet".to_string()).into());
+    }
+
+    pick(&media, prompt)?
+        .map(|i| media[i].video_id.clone())
+        .ok_or_else(|| LyttError::InvalidInput("No media selected".to_string()).into())
+}
+
+/// Show the picker, returning the index of the chosen item (None if cancelled).
+fn pick(media: &[IndexedVideo], prompt: &str) -> Result<Option<usize>> {
+    let items = labels(media);
+    let choice = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&items)
+        .default(0)
+        .max_length(15)
+        .interact_on_opt(&Term::stderr())?;
+    Ok(choice)
+}
+
+/// One line per item: title, duration and ID, so either can be searched.
+fn labels(media: &[IndexedVideo]) -> Vec<String> {
+    media
+        .iter()
+        .map(|m| {
+            format!(
+                "{}  ({}, {})",
+                truncate(&m.video_title, 60),
+                format_duration(m.total_duration_seconds),
+                m.video_id
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_labels_include_title_and_id() {
+        let media = vec![IndexedVideo {
+            video_id: "dQw4w9WgXcQ".to_string(),
+            video_title: "Never Gonna Give You Up".to_string(),
+            chunk_count: 3,
+            total_duration_seconds: 213.0,
+            indexed_at: Utc::now(),
+            language: None,
+        }];
+        assert_eq!(labels(&media), ["Never Gonna Give You Up  (3m 33s, dQw4w9WgXcQ)"]);
+    }
+}
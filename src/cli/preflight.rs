@@ -3,6 +3,7 @@
 //! Validates that required tools and configuration are available
 //! before starting operations that would otherwise fail midway.
 
+use crate::config::{LlmProvider, LlmSettings, Settings, TranscriptionProvider};
 use crate::error::{Result, LyttError};
 use std::process::Command;
 
@@ -11,25 +12,50 @@ use std::process::Command;
 pub enum Operation {
     /// Transcription requires tools and API key.
     Transcribe,
-    /// Asking questions requires API key and database.
+    /// Asking questions requires the RAG LLM and embeddings.
     Ask,
+    /// Rollups require the rollup LLM and embeddings.
+    Rollup,
+    /// Digests require the digest LLM.
+    Digest,
+    /// Summaries require the summary LLM and embeddings.
+    Summarize,
     /// Search requires database.
     Search,
 }
 
 /// Run pre-flight checks for the given operation.
 ///
+/// API keys are only required for the providers the configured roles use, so
+/// asking questions against a local LLM and embedder needs no keys at all.
 /// Returns Ok(()) if all checks pass, or an error describing what's missing.
-pub fn check(operation: Operation) -> Result<()> {
+pub fn check(operation: Operation, settings: &Settings) -> Result<()> {
     match operation {
         Operation::Transcribe => {
+            // Speech-to-text always runs on OpenAI
             check_api_key()?;
+            if settings.transcription.provider != TranscriptionProvider::Gpt4o {
+                check_llm(&settings.transcription.processing.cleanup_llm)?;
+            }
+            check_llm(&settings.chunking.llm)?;
             check_tool("yt-dlp")?;
             check_tool("ffmpeg")?;
             check_tool("ffprobe")?;
         }
         Operation::Ask => {
-            check_api_key()?;
+            check_llm(&settings.rag.llm)?;
+            check_embedder(settings)?;
+        }
+        Operation::Rollup => {
+            check_llm(&settings.rollup.llm)?;
+            check_embedder(settings)?;
+        }
+        Operation::Digest => {
+            check_llm(&settings.digest.llm)?;
+        }
+        Operation::Summarize => {
+            check_llm(&settings.summary.llm)?;
+            check_embedder(settings)?;
         }
         Operation::Search => {
             // No external requirements for search
@@ -38,6 +64,39 @@ pub fn check(operation: Operation) -> Result<()> {
     Ok(())
 }
 
+/// Check the API key needed by an LLM role, if any.
+fn check_llm(llm: &LlmSettings) -> Result<()> {
+    if let Some(var) = &llm.api_key_env {
+        return check_env_key(var);
+    }
+
+    match llm.provider {
+        LlmProvider::OpenAI if llm.uses_openai_api() => check_api_key(),
+        LlmProvider::Anthropic => check_env_key("ANTHROPIC_API_KEY"),
+        _ => Ok(()),
+    }
+}
+
+/// Check the API key needed by the configured embedder, if any.
+fn check_embedder(settings: &Settings) -> Result<()> {
+    if settings.embedding.provider == "external" {
+        Ok(())
+    } else {
+        check_api_key()
+    }
+}
+
+/// Check that an API key environment variable is set.
+fn check_env_key(var: &str) -> Result<()> {
+    match std::env::var(var) {
+        Ok(key) if !key.is_empty() => Ok(()),
+        _ => Err(LyttError::Config(format!(
+            "{} not set. Set it with: export {}='...'",
+            var, var
+        ))),
+    }
+}
+
 /// Check if OpenAI API key is configured.
 fn check_api_key() -> Result<()> {
     match std::env::var("OPENAI_API_KEY") {
@@ -81,6 +140,18 @@ mod tests {
     #[test]
     fn test_check_search_no_requirements() {
         // Search should always pass pre-flight (no external requirements)
-        assert!(check(Operation::Search).is_ok());
+        assert!(check(Operation::Search, &Settings::default()).is_ok());
+    }
+
+    #[test]
+    fn test_check_ask_offline() {
+        // A local LLM and external embedder need no API keys
+        let mut settings = Settings::default();
+        settings.rag.llm.provider = LlmProvider::Ollama;
+        settings.embedding.provider = "external".to_string();
+        assert!(check(Operation::Ask, &settings).is_ok());
+
+        settings.rag.llm.api_key_env = Some("LYTT_TEST_UNSET_KEY".to_string());
+        assert!(check(Operation::Ask, &settings).is_err());
     }
 }
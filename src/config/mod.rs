@@ -5,9 +5,9 @@
 mod prompts;
 mod settings;
 
-pub use prompts::{ChunkingPrompts, CleanupPrompts, Prompts, RagPrompts};
+pub use prompts::{ChunkingPrompts, CleanupPrompts, ConsensusPrompts, ExpansionPrompts, GlossaryPrompts, PackPrompts, Prompts, QuizPrompts, RagPrompts, RollupPrompts, SummaryPrompts, TitlePrompts, TopicPrompts, VerifyPrompts};
 pub use settings::{
-    ChunkingSettings, EmbeddingSettings, GeneralSettings, PromptSettings,
-    RagSettings, Settings, TranscriptionProcessingSettings, TranscriptionProvider,
-    TranscriptionSettings, VectorStoreSettings, YoutubeSettings,
+    AgentSettings, AnswerCacheSettings, AudioSettings, ChunkingSettings, DaemonScheduleSettings, DaemonSettings, DedupeSettings, DigestSettings, EmbeddingSettings, GeneralSettings, HookSettings, LlmProvider, LlmSettings, MultiQuerySettings, NotificationSettings, PromptSettings, PruneAction,
+    RagSettings, RecencySettings, RecordSettings, ReplicationSettings, RetentionPolicy, RetentionSettings, RollupSettings, ServerSettings, Settings, SummarySettings, TitleSettings, TopicSettings, TranscriptionProcessingSettings, TranscriptionProvider,
+    TranscriptionSettings, TtsSettings, UsageSettings, VectorStoreSettings, WebToolSettings, YoutubeSettings,
 };
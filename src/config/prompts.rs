@@ -14,6 +14,32 @@ pub struct Prompts {
     pub rag: RagPrompts,
     /// Prompts for transcription cleanup and segment structuring.
     pub cleanup: CleanupPrompts,
+    /// Prompts for tag/channel rollups.
+    pub rollup: RollupPrompts,
+    /// Prompts for per-media summaries.
+    pub summary: SummaryPrompts,
+    /// Prompts for quiz and flashcard generation.
+    pub quiz: QuizPrompts,
+    /// Prompts for titling untitled media.
+    pub titles: TitlePrompts,
+    /// Prompts for study pack quotes and glossary terms.
+    pub pack: PackPrompts,
+    /// Prompts for library glossary extraction.
+    pub glossary: GlossaryPrompts,
+    /// Prompts for consensus/contradiction analysis.
+    pub consensus: ConsensusPrompts,
+    /// Prompts for comparing chosen videos (`lytt ask --compare`).
+    pub compare: ComparePrompts,
+    /// Prompts for topic timelines.
+    pub timeline: TimelinePrompts,
+    /// Prompts for periodic digests.
+    pub digest: DigestPrompts,
+    /// Prompts for multi-query retrieval (question reformulations).
+    pub expansion: ExpansionPrompts,
+    /// Prompts for labelling topic clusters.
+    pub topics: TopicPrompts,
+    /// Prompts for checking answers against their sources.
+    pub verify: VerifyPrompts,
     /// Custom variables from config, available in all prompts.
     #[serde(skip)]
     pub variables: std::collections::HashMap<String, String>,
@@ -97,28 +123,18 @@ impl Default for RagPrompts {
 
 Guidelines:
 - Answer questions using only the provided context from video transcripts
-- Always cite your sources with video titles and timestamps
-- Use the format [Video Title @ MM:SS] for citations
+- Always cite your sources using the numbers of the excerpts, e.g. [2] or [1, 3]
+- For key claims, add a short verbatim quote from the excerpt: [2: "exact words from the excerpt"]
 - If the context doesn't contain relevant information, say so clearly
 - Be concise but thorough in your responses
 - When multiple sources are relevant, synthesize information across them"#.to_string(),
 
             user: r#"Question: {{question}}
 
-{{#if user_context}}
-Additional context provided by user:
-{{user_context}}
-
-{{/if}}
 Relevant excerpts from your video knowledge base:
 
-{{#each chunks}}
----
-Source: {{this.video_title}} @ {{this.timestamp}}
-{{this.content}}
----
+{{context}}
 
-{{/each}}
 Please answer the question based on the above context."#.to_string(),
 
             chat_system: r#"You are a helpful assistant for exploring video content. You have access to transcripts from the user's video library.
@@ -126,7 +142,7 @@ Please answer the question based on the above context."#.to_string(),
 In this conversation:
 - Answer questions using the video context provided
 - Remember previous questions in the conversation for follow-ups
-- Cite sources with [Video Title @ MM:SS] format
+- Cite sources using the numbers of the excerpts, e.g. [2] or [2: "short verbatim quote"]
 - Ask clarifying questions if the user's intent is unclear
 - If asked about something not in the videos, say so honestly"#.to_string(),
         }
@@ -170,6 +186,457 @@ JSON object with "segments" array. Each segment has:
     }
 }
 
+/// Prompts for summary rollups across many media.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RollupPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for RollupPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a research assistant who synthesizes many video and audio transcripts into a single overview document.
+
+Guidelines:
+- Identify the recurring themes and how they developed over time
+- Note key ideas, decisions, and conclusions, and where sources disagree
+- Cite sources with [Video Title @ MM:SS] format
+- Only use information present in the provided excerpts
+- Write in Markdown, with a level-two heading (## Theme) for each theme or section"#.to_string(),
+
+            user: r#"Write a rollup of the {{media_count}} items tagged "{{tag}}" from the last {{period}}.
+
+Start with a short overview paragraph, then one ## section per major theme, and end with an ## Open Questions section.
+
+Sources (oldest first):
+
+{{sources}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for per-media summaries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryPrompts {
+    /// System prompt for summarizing a single chunk.
+    pub chunk_system: String,
+    /// User prompt for summarizing a single chunk.
+    pub chunk_user: String,
+    /// System prompt for the whole-media overview.
+    pub system: String,
+    /// User prompt for the whole-media overview.
+    pub user: String,
+}
+
+impl Default for SummaryPrompts {
+    fn default() -> Self {
+        Self {
+            chunk_system: r#"You summarize one section of a video or audio transcript.
+
+Guidelines:
+- Write one or two sentences covering the substantive content
+- Only use information present in the section
+- Do not start with "This section" or similar preamble"#.to_string(),
+
+            chunk_user: r#"Video Title: {{title}}
+Section: {{section}} (starts at {{timestamp}})
+
+{{content}}"#.to_string(),
+
+            system: r#"You write concise overviews of video and audio content from summaries of its sections.
+
+Guidelines:
+- Describe what the content covers and its main conclusions
+- Only use information present in the section summaries
+- Write one or two plain paragraphs, without headings or lists"#.to_string(),
+
+            user: r#"Write an overview of "{{title}}" ({{duration_minutes}} minutes).
+
+Section summaries:
+
+{{sections}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for quiz and flashcard generation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuizPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for QuizPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a teacher writing study flashcards from a lecture or video transcript.
+
+Guidelines:
+- Each card tests one idea: a definition, a fact, a reason, or a worked step
+- Questions must be answerable without seeing the video
+- Answers are short (one to three sentences) and come from the transcript
+- Prefer the substantive ideas over trivia, names of speakers, or housekeeping
+- Include the [MM:SS] timestamp of the transcript part that covers the answer
+
+Respond with JSON only, in this shape:
+{"cards": [{"question": "...", "answer": "...", "timestamp": "MM:SS"}]}"#.to_string(),
+
+            user: r#"Write {{count}} flashcards for "{{title}}".
+
+Transcript:
+
+{{transcript}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for titling untitled media.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TitlePrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for TitlePrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You name audio recordings from the opening of their transcript.
+
+Guidelines:
+- The title is specific and descriptive, at most 10 words (e.g. "Weekly Standup: Q3 Release Planning")
+- Do not invent names, dates, or details that are not in the transcript
+- Suggest up to 5 short, lowercase tags for the topic or kind of recording (e.g. "meeting", "lecture")
+
+Respond with JSON only, in this shape:
+{"title": "...", "tags": ["..."]}"#.to_string(),
+
+            user: r#"The recording is currently named "{{original_title}}".
+
+Opening of the transcript:
+
+{{transcript}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for study pack quotes and glossary terms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for PackPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a teacher preparing study notes from a lecture or video transcript.
+
+Guidelines:
+- Quotes are verbatim sentences from the transcript that state a key idea memorably
+- Glossary terms are the technical terms a student must know, defined in one sentence as the transcript uses them
+- Include the [MM:SS] timestamp of the transcript part each quote or term comes from
+- Only use information present in the transcript
+
+Respond with JSON only, in this shape:
+{"quotes": [{"quote": "...", "timestamp": "MM:SS"}], "glossary": [{"term": "...", "definition": "...", "timestamp": "MM:SS"}]}"#.to_string(),
+
+            user: r#"Pick up to {{quotes}} key quotes and the glossary terms for "{{title}}".
+
+Transcript:
+
+{{transcript}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for library glossary extraction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlossaryPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for GlossaryPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a terminologist building a glossary from a lecture, talk or podcast transcript.
+
+Guidelines:
+- Pick domain terms: jargon, acronyms, named methods, tools, people and products specific to the subject
+- Skip everyday words and terms the speaker never explains or uses meaningfully
+- Define each term in one sentence, the way the speaker uses or explains it
+- Spell terms the way the field writes them, fixing obvious transcription errors
+- Include the [MM:SS] timestamp where the term is defined or first explained
+- Only use information present in the transcript
+
+Respond with JSON only, in this shape:
+{"terms": [{"term": "...", "definition": "...", "timestamp": "MM:SS"}]}"#.to_string(),
+
+            user: r#"Extract the glossary terms from "{{title}}".
+
+Transcript:
+
+{{transcript}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for consensus/contradiction analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsensusPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for ConsensusPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a research analyst comparing what several sources say about a topic.
+
+You receive numbered transcript excerpts from different videos or podcasts, oldest first, each with its title, publication date and timestamp.
+
+Report:
+- agreements: claims that two or more different sources make
+- disagreements: questions the sources answer differently, with each position and who holds it
+- changes: positions a source, speaker or channel held earlier and revised in a later excerpt
+
+Guidelines:
+- Cite every claim with the numbers of the excerpts that support it
+- Only report what the excerpts actually say; do not add outside knowledge
+- State each claim or position in one or two neutral sentences
+- Leave a list empty rather than stretching weak evidence
+
+Respond with JSON only, in this shape:
+{"agreements": [{"claim": "...", "sources": [1, 4]}], "disagreements": [{"question": "...", "positions": [{"claim": "...", "sources": [2]}]}], "changes": [{"claim": "...", "sources": [3, 7]}]}"#.to_string(),
+
+            user: r#"Topic: {{topic}}
+
+Excerpts:
+
+{{excerpts}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for comparing what chosen videos say in answer to a question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComparePrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for ComparePrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a research analyst comparing how several videos or talks answer the same question.
+
+You receive numbered transcript excerpts grouped by video, each with its title, publication date and timestamp.
+
+Report:
+- agreements: points that two or more of the videos make
+- contradictions: questions the videos answer differently, with each video's position
+- unique: points only one video makes
+
+Guidelines:
+- Cite every point with the numbers of the excerpts that support it
+- Only report what the excerpts actually say; do not add outside knowledge
+- State each point or position in one or two neutral sentences
+- Leave a list empty rather than stretching weak evidence
+
+Respond with JSON only, in this shape:
+{"agreements": [{"claim": "...", "sources": [1, 4]}], "contradictions": [{"question": "...", "positions": [{"claim": "...", "sources": [2]}]}], "unique": [{"claim": "...", "sources": [3]}]}"#.to_string(),
+
+            user: r#"Question: {{question}}
+
+Excerpts:
+
+{{excerpts}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for timelines of how a topic evolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimelinePrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for TimelinePrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a research analyst tracing how a topic developed over time.
+
+You receive numbered transcript excerpts from videos or podcasts, oldest first, each with its title, publication date and timestamp.
+
+Group the excerpts into events: the topic first coming up, new ideas, changes of position, milestones, predictions and how they turned out. For each event give:
+- headline: what happened, in a few words
+- summary: one or two sentences on the development
+- sources: the numbers of the excerpts it is based on
+
+Guidelines:
+- Only report what the excerpts actually say; do not add outside knowledge
+- Keep each event to excerpts from around the same time
+- Skip excerpts that add nothing new
+- Leave the list empty rather than stretching weak evidence
+
+Respond with JSON only, in this shape:
+{"events": [{"headline": "...", "summary": "...", "sources": [1, 2]}]}"#.to_string(),
+
+            user: r#"Topic: {{topic}}
+
+Excerpts:
+
+{{excerpts}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for digests of newly indexed media.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for DigestPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are an analyst writing a briefing on recently added videos and podcasts.
+
+You receive numbered media items, each with its title, the date it was added, and a summary or transcript excerpts.
+
+Report:
+- media: for each item, its number and the three to five key points it makes
+- themes: topics, ideas or questions that come up in two or more items, each with a one to three sentence summary of how the items treat it and the numbers of the items it comes up in
+
+Guidelines:
+- Write each key point as one self-contained sentence
+- Only report what the material actually says; do not add outside knowledge
+- Leave themes empty rather than stretching weak connections
+
+Respond with JSON only, in this shape:
+{"media": [{"source": 1, "key_points": ["..."]}], "themes": [{"theme": "...", "summary": "...", "sources": [1, 3]}]}"#.to_string(),
+
+            user: r#"Media added from {{since}} to {{until}} ({{media_count}} items):
+
+{{sources}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for multi-query retrieval (question reformulations).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpansionPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for ExpansionPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You help search a library of spoken content (video and podcast transcripts).
+
+Rewrite the user's question as alternative search queries that would match how the answer is likely phrased when spoken aloud.
+
+Guidelines:
+- Keep the meaning of the question; do not answer it
+- Vary vocabulary: synonyms, informal phrasings, spelled-out abbreviations
+- Prefer short, statement-like queries over full questions
+- Each query must stand on its own
+
+Respond with JSON only, in this shape:
+{"queries": ["...", "..."]}"#.to_string(),
+
+            user: r#"Write {{count}} alternative search queries for:
+
+{{question}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for labelling topic clusters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopicPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for TopicPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You name the topics of a library of spoken content (video and podcast transcripts).
+
+You receive numbered clusters of related transcript chunks, each with the media it comes from and sample excerpts.
+
+Guidelines:
+- Give each cluster a short label (2-5 words) naming its subject, not its format
+- Describe each cluster in one sentence
+- Make labels distinct from each other; name what sets similar clusters apart
+- Only use what the samples show
+
+Respond with JSON only, in this shape:
+{"topics": [{"cluster": 1, "label": "...", "description": "..."}]}"#.to_string(),
+
+            user: r#"Label these clusters:
+
+{{clusters}}"#.to_string(),
+        }
+    }
+}
+
+/// Prompts for checking answers against their sources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerifyPrompts {
+    pub system: String,
+    pub user: String,
+}
+
+impl Default for VerifyPrompts {
+    fn default() -> Self {
+        Self {
+            system: r#"You are a fact checker. You check an answer against the numbered transcript excerpts it was written from.
+
+Split the answer into its factual claims and judge each one:
+- supported: the excerpts state it or directly imply it
+- unsupported: the excerpts don't mention it, or only back up part of it
+- contradicted: the excerpts say otherwise
+
+Guidelines:
+- Judge only against the excerpts, never against your own knowledge
+- Skip statements that aren't claims (greetings, "the sources don't say")
+- Quote each claim briefly, as it appears in the answer
+- List the numbers of the excerpts that support or contradict each claim
+- Explain unsupported and contradicted verdicts in one sentence
+
+Respond with JSON only, in this shape:
+{"claims": [{"claim": "...", "verdict": "supported", "sources": [1], "explanation": "..."}]}"#.to_string(),
+
+            user: r#"Excerpts:
+
+{{context}}
+
+Answer to check:
+
+{{answer}}"#.to_string(),
+        }
+    }
+}
+
 impl Prompts {
     /// Load prompts from the default location, with optional custom directory and variables.
     pub fn load(
@@ -206,6 +673,97 @@ impl Prompts {
                 let content = std::fs::read_to_string(&cleanup_path)?;
                 prompts.cleanup = toml::from_str(&content)?;
             }
+
+            // Load rollup prompts if file exists
+            let rollup_path = custom_path.join("rollup.toml");
+            if rollup_path.exists() {
+                let content = std::fs::read_to_string(&rollup_path)?;
+                prompts.rollup = toml::from_str(&content)?;
+            }
+
+            // Load summary prompts if file exists
+            let summary_path = custom_path.join("summary.toml");
+            if summary_path.exists() {
+                let content = std::fs::read_to_string(&summary_path)?;
+                prompts.summary = toml::from_str(&content)?;
+            }
+
+            // Load quiz prompts if file exists
+            let quiz_path = custom_path.join("quiz.toml");
+            if quiz_path.exists() {
+                let content = std::fs::read_to_string(&quiz_path)?;
+                prompts.quiz = toml::from_str(&content)?;
+            }
+
+            // Load title prompts if file exists
+            let titles_path = custom_path.join("titles.toml");
+            if titles_path.exists() {
+                let content = std::fs::read_to_string(&titles_path)?;
+                prompts.titles = toml::from_str(&content)?;
+            }
+
+            // Load study pack prompts if file exists
+            let pack_path = custom_path.join("pack.toml");
+            if pack_path.exists() {
+                let content = std::fs::read_to_string(&pack_path)?;
+                prompts.pack = toml::from_str(&content)?;
+            }
+
+            // Load glossary prompts if file exists
+            let glossary_path = custom_path.join("glossary.toml");
+            if glossary_path.exists() {
+                let content = std::fs::read_to_string(&glossary_path)?;
+                prompts.glossary = toml::from_str(&content)?;
+            }
+
+            // Load consensus prompts if file exists
+            let consensus_path = custom_path.join("consensus.toml");
+            if consensus_path.exists() {
+                let content = std::fs::read_to_string(&consensus_path)?;
+                prompts.consensus = toml::from_str(&content)?;
+            }
+
+            // Load comparison prompts if file exists
+            let compare_path = custom_path.join("compare.toml");
+            if compare_path.exists() {
+                let content = std::fs::read_to_string(&compare_path)?;
+                prompts.compare = toml::from_str(&content)?;
+            }
+
+            // Load timeline prompts if file exists
+            let timeline_path = custom_path.join("timeline.toml");
+            if timeline_path.exists() {
+                let content = std::fs::read_to_string(&timeline_path)?;
+                prompts.timeline = toml::from_str(&content)?;
+            }
+
+            // Load digest prompts if file exists
+            let digest_path = custom_path.join("digest.toml");
+            if digest_path.exists() {
+                let content = std::fs::read_to_string(&digest_path)?;
+                prompts.digest = toml::from_str(&content)?;
+            }
+
+            // Load query expansion prompts if file exists
+            let expansion_path = custom_path.join("expansion.toml");
+            if expansion_path.exists() {
+                let content = std::fs::read_to_string(&expansion_path)?;
+                prompts.expansion = toml::from_str(&content)?;
+            }
+
+            // Load topic prompts if file exists
+            let topics_path = custom_path.join("topics.toml");
+            if topics_path.exists() {
+                let content = std::fs::read_to_string(&topics_path)?;
+                prompts.topics = toml::from_str(&content)?;
+            }
+
+            // Load verification prompts if file exists
+            let verify_path = custom_path.join("verify.toml");
+            if verify_path.exists() {
+                let content = std::fs::read_to_string(&verify_path)?;
+                prompts.verify = toml::from_str(&content)?;
+            }
         }
 
         Ok(prompts)
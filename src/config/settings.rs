@@ -10,12 +10,36 @@ use std::path::PathBuf;
 pub struct Settings {
     pub general: GeneralSettings,
     pub transcription: TranscriptionSettings,
+    pub audio: AudioSettings,
     pub embedding: EmbeddingSettings,
     pub chunking: ChunkingSettings,
     pub vector_store: VectorStoreSettings,
     pub youtube: YoutubeSettings,
     pub rag: RagSettings,
+    pub agent: AgentSettings,
     pub prompts: PromptSettings,
+    pub usage: UsageSettings,
+    pub rollup: RollupSettings,
+    pub digest: DigestSettings,
+    pub summary: SummarySettings,
+    pub titles: TitleSettings,
+    pub topics: TopicSettings,
+    pub dedupe: DedupeSettings,
+    pub retention: RetentionSettings,
+    pub notifications: NotificationSettings,
+    pub server: ServerSettings,
+    pub daemon: DaemonSettings,
+    pub tts: TtsSettings,
+    pub record: RecordSettings,
+    /// External command hooks (`[[hooks]]`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<HookSettings>,
+    /// Named profiles that override the settings above (e.g. `[profiles.work]`).
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub profiles: std::collections::HashMap<String, toml::Table>,
+    /// Name of the active profile, if any.
+    #[serde(skip)]
+    pub active_profile: Option<String>,
 }
 
 
@@ -27,6 +51,14 @@ pub struct GeneralSettings {
     pub data_dir: String,
     /// Directory for temporary files.
     pub temp_dir: String,
+    /// Most a run may keep in the temp directory, in MB (0 for no cap).
+    pub temp_max_size_mb: u64,
+    /// Free disk space to leave when downloading and splitting audio, in MB.
+    pub min_free_disk_mb: u64,
+    /// Directory for cached downloads, kept between runs.
+    pub cache_dir: String,
+    /// Most downloaded audio to keep in the cache, in MB (0 disables it).
+    pub audio_cache_max_mb: u64,
     /// Log level (trace, debug, info, warn, error).
     pub log_level: String,
 }
@@ -36,6 +68,10 @@ impl Default for GeneralSettings {
         Self {
             data_dir: "~/.lytt".to_string(),
             temp_dir: "/tmp/lytt".to_string(),
+            temp_max_size_mb: 10240,
+            min_free_disk_mb: 1024,
+            cache_dir: "~/.lytt/cache".to_string(),
+            audio_cache_max_mb: 5120,
             log_level: "info".to_string(),
         }
     }
@@ -50,6 +86,10 @@ pub enum TranscriptionProvider {
     Whisper,
     /// Fusion mode: Whisper timestamps + GPT-4o text + LLM fusion.
     Fusion,
+    /// GPT-4o transcription only, timed by short audio windows (no Whisper).
+    Gpt4o,
+    /// Existing YouTube captions, falling back to Whisper when there are none.
+    Captions,
 }
 
 impl std::str::FromStr for TranscriptionProvider {
@@ -59,6 +99,8 @@ impl std::str::FromStr for TranscriptionProvider {
         match s.to_lowercase().as_str() {
             "whisper" | "openai" => Ok(TranscriptionProvider::Whisper),
             "fusion" => Ok(TranscriptionProvider::Fusion),
+            "gpt4o" | "gpt-4o" | "gpt-4o-transcribe" => Ok(TranscriptionProvider::Gpt4o),
+            "captions" | "subtitles" => Ok(TranscriptionProvider::Captions),
             _ => Err(format!("Unknown transcription provider: {}", s)),
         }
     }
@@ -69,6 +111,87 @@ impl std::fmt::Display for TranscriptionProvider {
         match self {
             TranscriptionProvider::Whisper => write!(f, "whisper"),
             TranscriptionProvider::Fusion => write!(f, "fusion"),
+            TranscriptionProvider::Gpt4o => write!(f, "gpt4o"),
+            TranscriptionProvider::Captions => write!(f, "captions"),
+        }
+    }
+}
+
+/// Chat-completion backend for LLM roles (RAG, chunking, cleanup, rollups, summaries).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    /// OpenAI or any OpenAI-compatible endpoint (default).
+    #[default]
+    OpenAI,
+    /// Anthropic Messages API (Claude models).
+    Anthropic,
+    /// Local Ollama server (OpenAI-compatible, no API key needed).
+    Ollama,
+}
+
+impl std::str::FromStr for LlmProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "openai" => Ok(LlmProvider::OpenAI),
+            "anthropic" | "claude" => Ok(LlmProvider::Anthropic),
+            "ollama" => Ok(LlmProvider::Ollama),
+            _ => Err(format!("Unknown LLM provider: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for LlmProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmProvider::OpenAI => write!(f, "openai"),
+            LlmProvider::Anthropic => write!(f, "anthropic"),
+            LlmProvider::Ollama => write!(f, "ollama"),
+        }
+    }
+}
+
+/// Connection settings for an LLM role.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct LlmSettings {
+    /// Backend provider (openai, anthropic).
+    pub provider: LlmProvider,
+    /// API base URL. None = the provider's public API.
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key.
+    /// None = OPENAI_API_KEY or ANTHROPIC_API_KEY depending on the provider.
+    pub api_key_env: Option<String>,
+}
+
+impl LlmSettings {
+    /// Default base URL of Ollama's OpenAI-compatible API.
+    pub const OLLAMA_BASE_URL: &'static str = "http://localhost:11434/v1";
+
+    /// Whether this role calls the public OpenAI API (and needs `OPENAI_API_KEY`).
+    ///
+    /// OpenAI-compatible endpoints with a custom `base_url` (llama.cpp server,
+    /// vLLM, etc.) are assumed not to.
+    pub fn uses_openai_api(&self) -> bool {
+        self.provider == LlmProvider::OpenAI && self.base_url.is_none()
+    }
+
+    /// Base URL requests go to, if not the provider's public API.
+    pub fn effective_base_url(&self) -> Option<&str> {
+        match (self.provider, self.base_url.as_deref()) {
+            (_, Some(url)) => Some(url),
+            (LlmProvider::Ollama, None) => Some(Self::OLLAMA_BASE_URL),
+            _ => None,
+        }
+    }
+
+    /// Short description for diagnostics, e.g. `ollama (http://localhost:11434/v1)`.
+    pub fn describe(&self) -> String {
+        match self.effective_base_url() {
+            Some(url) => format!("{} ({})", self.provider, url),
+            None => self.provider.to_string(),
         }
     }
 }
@@ -79,12 +202,15 @@ impl std::fmt::Display for TranscriptionProvider {
 pub struct TranscriptionProcessingSettings {
     /// Model for word-level timestamps (default: whisper-1).
     pub timestamp_model: String,
-    /// Model for secondary text transcription. None = Whisper-only with cleanup.
+    /// Model for secondary text transcription (and the only model for the
+    /// gpt4o provider). None = Whisper-only with cleanup.
     pub text_model: Option<String>,
     /// Model for LLM cleanup and segment structuring (default: gpt-4.1).
     pub cleanup_model: String,
     /// Maximum concurrent API calls.
     pub max_concurrent: usize,
+    /// LLM backend for cleanup and fusion.
+    pub cleanup_llm: LlmSettings,
 }
 
 impl Default for TranscriptionProcessingSettings {
@@ -94,6 +220,7 @@ impl Default for TranscriptionProcessingSettings {
             text_model: Some("gpt-4o-transcribe".to_string()),
             cleanup_model: "gpt-4.1".to_string(),
             max_concurrent: 2,
+            cleanup_llm: LlmSettings::default(),
         }
     }
 }
@@ -109,7 +236,7 @@ impl TranscriptionProcessingSettings {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TranscriptionSettings {
-    /// Transcription provider (whisper, fusion).
+    /// Transcription provider (whisper, fusion, gpt4o, captions).
     pub provider: TranscriptionProvider,
     /// Whisper model to use (for whisper provider or fusion timestamps).
     pub model: String,
@@ -117,8 +244,17 @@ pub struct TranscriptionSettings {
     pub chunk_duration_seconds: u32,
     /// Maximum media duration to process (in seconds).
     pub max_duration_seconds: u32,
+    /// Maximum audiobook duration to process (in seconds).
+    pub max_audiobook_seconds: u32,
     /// Maximum concurrent chunk processing.
     pub max_concurrent_chunks: usize,
+    /// Spoken language as an ISO 639-1 code (e.g. "en"). Unset or "auto"
+    /// detects it for each file.
+    pub language: Option<String>,
+    /// Terms (names, jargon, acronyms) the speech-to-text model should expect.
+    pub vocabulary: Vec<String>,
+    /// Also bias towards terms stored by `lytt glossary`.
+    pub glossary_vocabulary: bool,
     /// Processing pipeline settings (cleanup model, text model, etc.).
     pub processing: TranscriptionProcessingSettings,
 }
@@ -130,22 +266,61 @@ impl Default for TranscriptionSettings {
             model: "whisper-1".to_string(),
             chunk_duration_seconds: 120,
             max_duration_seconds: 7200, // 2 hours
+            max_audiobook_seconds: 86400, // 24 hours
             max_concurrent_chunks: 3,
+            language: None,
+            vocabulary: Vec::new(),
+            glossary_vocabulary: true,
             processing: TranscriptionProcessingSettings::default(),
         }
     }
 }
 
+/// Audio preprocessing applied before transcription.
+///
+/// Quiet or noisy recordings (conference rooms, phone calls) transcribe
+/// better after loudness normalization and noise reduction. All steps are off
+/// by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Normalize loudness (ffmpeg `loudnorm`, EBU R128).
+    pub normalize: bool,
+    /// Integrated loudness target in LUFS when normalizing.
+    pub target_lufs: f64,
+    /// Cut rumble and hum below this frequency in Hz (0 = off).
+    pub highpass_hz: u32,
+    /// Path to an RNNoise model (`.rnnn`) for noise reduction (ffmpeg `arnndn`).
+    pub denoise_model: Option<String>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            normalize: false,
+            target_lufs: -16.0,
+            highpass_hz: 0,
+            denoise_model: None,
+        }
+    }
+}
+
 /// Embedding generation settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct EmbeddingSettings {
-    /// Embedding provider (openai).
+    /// Embedding provider (openai, external).
     pub provider: String,
     /// Embedding model to use.
     pub model: String,
     /// Embedding dimensions.
     pub dimensions: u32,
+    /// Plugin command (for the external provider).
+    pub command: Option<String>,
+    /// Arguments passed to the plugin command.
+    pub args: Vec<String>,
+    /// Embed each chunk's section title and summary along with its content.
+    pub composite: bool,
 }
 
 impl Default for EmbeddingSettings {
@@ -154,6 +329,9 @@ impl Default for EmbeddingSettings {
             provider: "openai".to_string(),
             model: "text-embedding-3-small".to_string(),
             dimensions: 1536,
+            command: None,
+            args: Vec::new(),
+            composite: false,
         }
     }
 }
@@ -162,7 +340,7 @@ impl Default for EmbeddingSettings {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ChunkingSettings {
-    /// Chunking strategy (semantic, temporal, hybrid).
+    /// Chunking strategy (semantic, temporal, hybrid, external, chapters, sliding).
     pub strategy: String,
     /// Target chunk duration in seconds.
     pub target_chunk_seconds: u32,
@@ -172,6 +350,24 @@ pub struct ChunkingSettings {
     pub max_chunk_seconds: u32,
     /// Model to use for semantic chunking.
     pub model: String,
+    /// LLM backend for semantic chunking.
+    pub llm: LlmSettings,
+    /// Plugin command (for the external strategy).
+    pub command: Option<String>,
+    /// Arguments passed to the plugin command.
+    pub args: Vec<String>,
+    /// Keywords extracted from each chunk at index time (0 disables).
+    pub keywords_per_chunk: usize,
+    /// With the chapters strategy, split chapters longer than
+    /// `max_chunk_seconds` with the LLM (otherwise into equal parts).
+    pub refine_chapters: bool,
+    /// Tokens per chunk for the sliding strategy.
+    pub chunk_tokens: usize,
+    /// Tokens repeated from the previous chunk for the sliding strategy.
+    pub overlap_tokens: usize,
+    /// Check chunks for overlaps, gaps and durations outside the bounds, and
+    /// repair them.
+    pub validate: bool,
 }
 
 impl Default for ChunkingSettings {
@@ -182,6 +378,14 @@ impl Default for ChunkingSettings {
             min_chunk_seconds: 60,
             max_chunk_seconds: 600,
             model: "gpt-4o-mini".to_string(),
+            llm: LlmSettings::default(),
+            command: None,
+            args: Vec::new(),
+            keywords_per_chunk: 8,
+            refine_chapters: true,
+            chunk_tokens: 400,
+            overlap_tokens: 80,
+            validate: true,
         }
     }
 }
@@ -190,10 +394,37 @@ impl Default for ChunkingSettings {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct VectorStoreSettings {
-    /// Vector store provider (sqlite, memory).
+    /// Where documents and embeddings are stored: "sqlite", "postgres" or
+    /// "columnar".
+    /// Transcripts and other metadata are always kept in SQLite.
     pub provider: String,
-    /// Path to SQLite database (for sqlite provider).
+    /// Path to SQLite database.
     pub sqlite_path: String,
+    /// Connection string for the postgres provider (requires the
+    /// `postgres` feature and the pgvector extension).
+    pub postgres_url: String,
+    /// Directory of the columnar provider's Arrow files (requires the
+    /// `columnar` feature).
+    pub columnar_path: String,
+    /// Open the database read-only (e.g. a Litestream/LiteFS replica).
+    pub read_only: bool,
+    /// Maximum open connections, shared by concurrent searches and writes.
+    pub pool_size: u32,
+    /// Seconds between WAL checkpoints in `lytt serve` and `lytt mcp`
+    /// (0 = off; replicated databases use `replication` instead).
+    pub checkpoint_interval_secs: u64,
+    /// Replication-friendly SQLite behavior.
+    pub replication: ReplicationSettings,
+    /// Encoding of stored embeddings: "f32" or "f16" (half the size).
+    pub vector_format: String,
+    /// Quantized copies of the embeddings for two-stage search: "none",
+    /// "binary" or "int8".
+    pub quantization: String,
+    /// Candidates rescored with full embeddings per search result.
+    pub rescore_factor: usize,
+    /// In-memory approximate nearest-neighbour index built at startup:
+    /// "none" or "hnsw".
+    pub index: String,
 }
 
 impl Default for VectorStoreSettings {
@@ -201,6 +432,39 @@ impl Default for VectorStoreSettings {
         Self {
             provider: "sqlite".to_string(),
             sqlite_path: "~/.lytt/vectors.db".to_string(),
+            postgres_url: String::new(),
+            columnar_path: "~/.lytt/columnar".to_string(),
+            read_only: false,
+            pool_size: 8,
+            checkpoint_interval_secs: 300,
+            replication: ReplicationSettings::default(),
+            vector_format: "f32".to_string(),
+            quantization: "none".to_string(),
+            rescore_factor: 10,
+            index: "none".to_string(),
+        }
+    }
+}
+
+/// SQLite settings for databases replicated with Litestream or LiteFS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplicationSettings {
+    /// Disable SQLite's automatic checkpoints and keep read transactions short.
+    pub enabled: bool,
+    /// Seconds between passive WAL checkpoints run after writes
+    /// (0 leaves checkpointing to the replication tool).
+    pub checkpoint_interval_secs: u64,
+    /// How long writers wait for a lock held by the replication tool.
+    pub busy_timeout_ms: u64,
+}
+
+impl Default for ReplicationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            checkpoint_interval_secs: 0,
+            busy_timeout_ms: 5000,
         }
     }
 }
@@ -208,10 +472,60 @@ impl Default for VectorStoreSettings {
 /// YouTube-specific settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
-#[derive(Default)]
 pub struct YoutubeSettings {
     /// YouTube Data API key (optional, for playlist/channel fetching).
     pub api_key: Option<String>,
+    /// Netscape-format cookies file for yt-dlp, for age-restricted and
+    /// member-only videos.
+    pub cookies_file: Option<String>,
+    /// Proxy URL for yt-dlp (e.g. "socks5://127.0.0.1:1080").
+    pub proxy: Option<String>,
+    /// Maximum download rate for yt-dlp (e.g. "2M").
+    pub rate_limit: Option<String>,
+    /// Further arguments passed to every yt-dlp run.
+    pub extra_args: Vec<String>,
+    /// Skip segments submitted to SponsorBlock when transcribing.
+    pub sponsorblock: bool,
+    /// SponsorBlock categories to skip.
+    pub sponsorblock_categories: Vec<String>,
+    /// SponsorBlock API server.
+    pub sponsorblock_api: String,
+}
+
+impl Default for YoutubeSettings {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            cookies_file: None,
+            proxy: None,
+            rate_limit: None,
+            extra_args: Vec::new(),
+            sponsorblock: false,
+            sponsorblock_categories: vec!["sponsor".to_string(), "intro".to_string(), "outro".to_string()],
+            sponsorblock_api: "https://sponsor.ajay.app".to_string(),
+        }
+    }
+}
+
+impl YoutubeSettings {
+    /// yt-dlp arguments for the cookies, proxy, rate limit and extra arguments.
+    pub fn ytdlp_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(cookies) = self.cookies_file.as_deref().filter(|c| !c.is_empty()) {
+            args.push("--cookies".to_string());
+            args.push(Settings::expand_path(cookies).display().to_string());
+        }
+        if let Some(proxy) = self.proxy.as_deref().filter(|p| !p.is_empty()) {
+            args.push("--proxy".to_string());
+            args.push(proxy.to_string());
+        }
+        if let Some(rate) = self.rate_limit.as_deref().filter(|r| !r.is_empty()) {
+            args.push("--limit-rate".to_string());
+            args.push(rate.to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
 }
 
 
@@ -225,8 +539,50 @@ pub struct RagSettings {
     pub model: String,
     /// Maximum number of context chunks to include.
     pub max_context_chunks: u32,
+    /// Token budget for retrieved context. Chunks are packed by score until
+    /// the budget is spent; 0 uses a fixed number of chunks instead.
+    pub context_tokens: usize,
+    /// Chunks to add either side of each retrieved chunk. Adjacent chunks
+    /// are stitched into one passage either way.
+    pub neighbor_window: usize,
     /// Include video timestamps in citations.
     pub include_timestamps: bool,
+    /// LLM backend for answers, chat and the agent.
+    pub llm: LlmSettings,
+    /// Cache of answers to repeated questions.
+    pub cache: AnswerCacheSettings,
+    /// Multi-query retrieval (query expansion).
+    pub multi_query: MultiQuerySettings,
+    /// Check each claim of an answer against the retrieved excerpts and
+    /// flag unsupported statements (one extra LLM call per answer).
+    pub verify: bool,
+    /// Decline to answer when answer confidence (0-1, from retrieval scores
+    /// and the model's own rating) is below this; 0 (the default) always
+    /// answers.
+    pub min_confidence: f32,
+    /// Similarity of the retrieved excerpts at or below which retrieval
+    /// confidence is 0. Lower it for embedding models that score low overall.
+    pub confidence_floor: f32,
+    /// Similarity at or above which retrieval confidence is 1.
+    pub confidence_ceiling: f32,
+    /// Rank newer media above stale media on similar matches.
+    pub recency: RecencySettings,
+    /// Diversity of the retrieved context (the maximal marginal relevance
+    /// trade-off, 0-1): 0 picks chunks by relevance alone, higher values pass
+    /// over chunks much like those already picked, so context isn't several
+    /// near-identical passages from one video.
+    pub diversity: f32,
+    /// Answer questions about the library itself ("how many videos do I
+    /// have about Rust?", "which episode is the longest?") from the list of
+    /// indexed media instead of retrieved chunks.
+    pub routing: bool,
+}
+
+impl RagSettings {
+    /// Context token budget, or `None` to use a fixed number of chunks.
+    pub fn context_budget(&self) -> Option<usize> {
+        (self.context_tokens > 0).then_some(self.context_tokens)
+    }
 }
 
 impl Default for RagSettings {
@@ -235,7 +591,92 @@ impl Default for RagSettings {
             enabled: true,
             model: "gpt-4o-mini".to_string(),
             max_context_chunks: 10,
+            context_tokens: 4000,
+            neighbor_window: 0,
             include_timestamps: true,
+            llm: LlmSettings::default(),
+            cache: AnswerCacheSettings::default(),
+            multi_query: MultiQuerySettings::default(),
+            verify: false,
+            min_confidence: 0.0,
+            confidence_floor: 0.3,
+            confidence_ceiling: 0.8,
+            recency: RecencySettings::default(),
+            diversity: 0.0,
+            routing: true,
+        }
+    }
+}
+
+/// Answer cache for `lytt ask` and `POST /ask`.
+///
+/// Entries are keyed by the normalized question, model and retrieval settings,
+/// and are invalidated whenever indexed documents change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnswerCacheSettings {
+    /// Reuse answers to repeated questions.
+    pub enabled: bool,
+    /// How long an answer stays valid, in seconds.
+    pub ttl_seconds: u64,
+}
+
+impl Default for AnswerCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_seconds: 3600,
+        }
+    }
+}
+
+impl AnswerCacheSettings {
+    /// Cache lifetime, or `None` when caching is disabled.
+    pub fn ttl(&self) -> Option<std::time::Duration> {
+        (self.enabled && self.ttl_seconds > 0).then(|| std::time::Duration::from_secs(self.ttl_seconds))
+    }
+}
+
+/// Multi-query retrieval: the question is rephrased by the LLM and every
+/// variant is searched, so spoken phrasings the question doesn't use still match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultiQuerySettings {
+    /// Expand questions before retrieval.
+    pub enabled: bool,
+    /// Number of reformulations to generate (3-5 works well).
+    pub variants: usize,
+}
+
+impl Default for MultiQuerySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            variants: 4,
+        }
+    }
+}
+
+/// Recency-aware ranking: each result's score blends similarity with how
+/// recently its media was published (or indexed, when the date is unknown).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecencySettings {
+    /// Blend recency into retrieval scores.
+    pub enabled: bool,
+    /// Share of the score given to recency (0-1).
+    pub weight: f32,
+    /// Age in days at which media counts as half as recent; lower values
+    /// favor new media more strongly.
+    pub half_life_days: f64,
+}
+
+impl Default for RecencySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weight: 0.3,
+            half_life_days: 180.0,
         }
     }
 }
@@ -251,6 +692,461 @@ pub struct PromptSettings {
     pub variables: std::collections::HashMap<String, String>,
 }
 
+/// Usage tracking and cost reporting settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageSettings {
+    /// Record token and audio usage for each run.
+    pub enabled: bool,
+    /// Per-model price overrides (USD), keyed by model name.
+    pub prices: std::collections::HashMap<String, crate::usage::ModelPrice>,
+}
+
+impl Default for UsageSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            prices: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// Summary rollup settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RollupSettings {
+    /// LLM model for rollup generation.
+    pub model: String,
+    /// Maximum characters of source material sent to the model.
+    pub max_input_chars: usize,
+    /// LLM backend for rollup generation.
+    pub llm: LlmSettings,
+}
+
+impl Default for RollupSettings {
+    fn default() -> Self {
+        Self {
+            model: "gpt-4o-mini".to_string(),
+            max_input_chars: 60_000,
+            llm: LlmSettings::default(),
+        }
+    }
+}
+
+/// Digest settings (`lytt digest`, and scheduled digests in `lytt serve`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestSettings {
+    /// LLM model for digest generation.
+    pub model: String,
+    /// Maximum characters of source material sent to the model.
+    pub max_input_chars: usize,
+    /// LLM backend for digest generation.
+    pub llm: LlmSettings,
+    /// How often `lytt serve` generates a digest (daily, weekly). Empty
+    /// disables scheduled digests.
+    pub schedule: String,
+    /// Directory scheduled digests are written to.
+    pub output_dir: String,
+    /// Addresses digests are emailed to.
+    pub email_to: Vec<String>,
+    /// Sender address of digest emails.
+    pub email_from: String,
+    /// Sendmail-compatible command that sends the email (sendmail, msmtp).
+    /// It reads the message, headers included, on stdin.
+    pub sendmail_command: String,
+    /// Arguments for the sendmail command.
+    pub sendmail_args: Vec<String>,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            model: "gpt-4o-mini".to_string(),
+            max_input_chars: 60_000,
+            llm: LlmSettings::default(),
+            schedule: String::new(),
+            output_dir: "~/.lytt/digests".to_string(),
+            email_to: Vec::new(),
+            email_from: "lytt@localhost".to_string(),
+            sendmail_command: "sendmail".to_string(),
+            sendmail_args: vec!["-t".to_string(), "-i".to_string()],
+        }
+    }
+}
+
+/// Per-media summary settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummarySettings {
+    /// LLM model for summaries.
+    pub model: String,
+    /// LLM backend for summaries.
+    pub llm: LlmSettings,
+}
+
+impl Default for SummarySettings {
+    fn default() -> Self {
+        Self {
+            model: "gpt-4o-mini".to_string(),
+            llm: LlmSettings::default(),
+        }
+    }
+}
+
+/// Agent settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AgentSettings {
+    /// Web search and page fetching tools.
+    pub web: WebToolSettings,
+}
+
+/// `web_search` and `fetch_url` agent tools.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebToolSettings {
+    /// Give the agent the web tools.
+    pub enabled: bool,
+    /// Domains the agent may search and fetch (subdomains included); empty allows any.
+    pub allowed_domains: Vec<String>,
+    /// Environment variable holding the Brave Search API key.
+    pub search_api_key_env: String,
+    /// Characters of page text returned by `fetch_url`.
+    pub max_chars: usize,
+    /// Request timeout in seconds.
+    pub timeout_seconds: u64,
+}
+
+impl Default for WebToolSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_domains: Vec::new(),
+            search_api_key_env: "BRAVE_API_KEY".to_string(),
+            max_chars: 20_000,
+            timeout_seconds: 15,
+        }
+    }
+}
+
+/// Automatic titles for untitled local media.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TitleSettings {
+    /// Generate a title when a local file has no title tag.
+    pub auto: bool,
+    /// LLM model for titles.
+    pub model: String,
+    /// Seconds from the start of the transcript sent to the model.
+    pub excerpt_seconds: f64,
+    /// LLM backend for titles.
+    pub llm: LlmSettings,
+}
+
+impl Default for TitleSettings {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            model: "gpt-4o-mini".to_string(),
+            excerpt_seconds: 180.0,
+            llm: LlmSettings::default(),
+        }
+    }
+}
+
+/// Topic clustering (`lytt topics`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopicSettings {
+    /// LLM model for topic labels.
+    pub model: String,
+    /// Store topics so new documents are assigned to them and searches can
+    /// filter by topic.
+    pub persist: bool,
+    /// LLM backend for topic labels.
+    pub llm: LlmSettings,
+}
+
+impl Default for TopicSettings {
+    fn default() -> Self {
+        Self {
+            model: "gpt-4o-mini".to_string(),
+            persist: false,
+            llm: LlmSettings::default(),
+        }
+    }
+}
+
+/// Duplicate detection (`lytt dedupe` and before transcription).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupeSettings {
+    /// Similarity at which two chunks count as the same content.
+    pub chunk_threshold: f32,
+    /// Fraction of matching chunks that makes two media items duplicates.
+    pub min_overlap: f32,
+    /// Before transcribing, warn when an indexed item has the same title and duration.
+    pub check_on_transcribe: bool,
+    /// Skip such likely duplicates instead of only warning (`--force` transcribes anyway).
+    pub skip_on_transcribe: bool,
+}
+
+impl Default for DedupeSettings {
+    fn default() -> Self {
+        Self {
+            chunk_threshold: 0.92,
+            min_overlap: 0.8,
+            check_on_transcribe: true,
+            skip_on_transcribe: false,
+        }
+    }
+}
+
+/// What `lytt prune` does with pruned documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PruneAction {
+    /// Write pruned documents to a JSONL file in the archive directory, then delete them.
+    #[default]
+    Archive,
+    /// Delete pruned documents.
+    Delete,
+}
+
+impl std::fmt::Display for PruneAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PruneAction::Archive => write!(f, "archive"),
+            PruneAction::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+/// Library retention settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    /// What to do with pruned documents.
+    pub action: PruneAction,
+    /// Directory for archived documents.
+    pub archive_dir: String,
+    /// Retention policies (`[[retention.policies]]`); none means nothing is pruned.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub policies: Vec<RetentionPolicy>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            action: PruneAction::default(),
+            archive_dir: "~/.lytt/archive".to_string(),
+            policies: Vec::new(),
+        }
+    }
+}
+
+/// Pipeline event notifications.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// URL that receives a JSON POST for each pipeline event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    /// Maximum time to wait for the webhook to respond.
+    pub timeout_seconds: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+/// Background daemon settings (`lytt daemon`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonSettings {
+    /// Directories watched for new media files (searched recursively).
+    pub watch_dirs: Vec<String>,
+    /// Seconds a file must go unmodified before it's ingested, so files
+    /// still being copied or recorded are left alone.
+    pub settle_seconds: u64,
+    /// Playlists and channels whose new media is ingested on each sync.
+    pub subscriptions: Vec<String>,
+    /// Newest entries of each subscription checked on each sync.
+    pub subscription_limit: usize,
+    /// Attempts at a file or video that keeps failing before the daemon
+    /// gives up on it until restarted.
+    pub max_attempts: u32,
+    /// When each job runs.
+    pub schedule: DaemonScheduleSettings,
+}
+
+impl Default for DaemonSettings {
+    fn default() -> Self {
+        Self {
+            watch_dirs: Vec::new(),
+            settle_seconds: 60,
+            subscriptions: Vec::new(),
+            subscription_limit: 10,
+            max_attempts: 3,
+            schedule: DaemonScheduleSettings::default(),
+        }
+    }
+}
+
+/// Cron schedules (`minute hour day month weekday`, or `@hourly`, `@daily`,
+/// `@weekly`, `@monthly`) of the daemon's jobs. Empty disables a job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonScheduleSettings {
+    /// Scan the watch directories.
+    pub watch: String,
+    /// Sync the subscriptions.
+    pub subscriptions: String,
+    /// Generate a digest of the media indexed since the last one. Empty
+    /// falls back to `[digest] schedule`.
+    pub digest: String,
+}
+
+impl Default for DaemonScheduleSettings {
+    fn default() -> Self {
+        Self {
+            watch: "*/5 * * * *".to_string(),
+            subscriptions: "0 * * * *".to_string(),
+            digest: String::new(),
+        }
+    }
+}
+
+/// Text-to-speech settings (`ask --speak`, `chat --speak`, highlight reel
+/// announcements).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TtsSettings {
+    /// Speech engine (openai, local).
+    pub engine: String,
+    /// OpenAI speech model (tts-1, tts-1-hd).
+    pub model: String,
+    /// Voice (alloy, echo, fable, onyx, nova, shimmer).
+    pub voice: String,
+    /// Speaking speed, from 0.25 to 4.0.
+    pub speed: f32,
+    /// Command for the local engine (e.g. piper or espeak-ng). It reads the
+    /// text on stdin and writes a WAV file.
+    pub command: Option<String>,
+    /// Arguments for the local command; `{output}` is replaced by the WAV path.
+    pub args: Vec<String>,
+}
+
+impl Default for TtsSettings {
+    fn default() -> Self {
+        Self {
+            engine: "openai".to_string(),
+            model: "tts-1".to_string(),
+            voice: "alloy".to_string(),
+            speed: 1.0,
+            command: None,
+            args: Vec::new(),
+        }
+    }
+}
+
+/// Live capture settings (`lytt record`, and `lytt serve`'s `/stream`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordSettings {
+    /// ffmpeg input format (pulse, alsa, avfoundation, dshow). Empty means
+    /// the platform's usual one.
+    pub input_format: String,
+    /// Capture device, in the input format's naming. Empty means the default
+    /// microphone.
+    pub device: String,
+    /// Seconds of audio transcribed at a time; shorter shows text sooner,
+    /// longer transcribes more accurately.
+    pub segment_seconds: u32,
+    /// For the server's `/stream`, seconds between interim results of the
+    /// audio not yet final (0 = finals only). Each one transcribes that
+    /// audio again.
+    pub interim_seconds: u32,
+    /// Longest `/stream` session, in seconds of audio and of connection
+    /// time, whether or not the client is sending. The session is ended and
+    /// indexed when it's reached.
+    pub max_stream_seconds: u32,
+    /// Most audio a `/stream` session may send, in MB.
+    pub max_stream_mb: u32,
+    /// Directory recordings are kept in.
+    pub output_dir: String,
+}
+
+impl Default for RecordSettings {
+    fn default() -> Self {
+        Self {
+            input_format: String::new(),
+            device: String::new(),
+            segment_seconds: 30,
+            interim_seconds: 5,
+            max_stream_seconds: 4 * 3600,
+            max_stream_mb: 512,
+            output_dir: "~/.lytt/recordings".to_string(),
+        }
+    }
+}
+
+/// Access control for `lytt serve`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    /// Accepted API keys; none means the API is open.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<String>,
+    /// Environment variable with more comma-separated API keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_keys_env: Option<String>,
+    /// Requests per minute allowed per key (0 = unlimited).
+    pub rate_limit_per_minute: u32,
+}
+
+/// A limit on part of the library. Without `tag` or `source` it covers everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Only media with this tag (e.g. "podcasts" or "channel:Name").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Only media from this source ("youtube", "web" or "local").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Maximum number of transcript chunks to keep.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_documents: Option<usize>,
+    /// Remove media older than this many days.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+}
+
+/// An external command run at a pipeline stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookSettings {
+    /// Stage to run at (on_transcript, on_chunks, on_indexed).
+    pub event: crate::hooks::HookEvent,
+    /// Command to run.
+    pub command: String,
+    /// Arguments passed to the command.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Maximum run time in seconds.
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_seconds: u64,
+}
+
+fn default_hook_timeout() -> u64 {
+    60
+}
 
 impl Settings {
     /// Load settings from the default configuration file.
@@ -260,18 +1156,102 @@ impl Settings {
 
     /// Load settings from a specific path, or default location if None.
     pub fn load_from(path: Option<&PathBuf>) -> crate::error::Result<Self> {
+        Self::load_with_profile(path, None)
+    }
+
+    /// Load settings and apply a named profile on top of the base settings.
+    ///
+    /// Profiles are partial configs under `[profiles.<name>]`. A profile that
+    /// doesn't set `vector_store.sqlite_path` (or `columnar_path`) gets its
+    /// own database under `<data_dir>/profiles/<name>/`, so profiles never
+    /// share a store by accident.
+    pub fn load_with_profile(path: Option<&PathBuf>, profile: Option<&str>) -> crate::error::Result<Self> {
         let config_path = match path {
             Some(p) => p.clone(),
             None => Self::default_config_path(),
         };
 
-        if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let settings: Settings = toml::from_str(&content)?;
-            Ok(settings)
+        let content = if config_path.exists() {
+            std::fs::read_to_string(&config_path)?
         } else {
-            Ok(Settings::default())
+            String::new()
+        };
+
+        Self::from_toml_with_profile(&content, profile)
+    }
+
+    /// Parse settings from TOML and apply a named profile.
+    pub fn from_toml_with_profile(content: &str, profile: Option<&str>) -> crate::error::Result<Self> {
+        let mut table: toml::Table = toml::from_str(content)?;
+
+        if let Some(name) = profile {
+            Self::apply_profile(&mut table, name)?;
+        }
+
+        let mut settings: Settings = toml::Value::Table(table).try_into()?;
+        settings.active_profile = profile.map(|p| p.to_string());
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Reject combinations of values that parse but can't work together.
+    fn validate(&self) -> crate::error::Result<()> {
+        let rag = &self.rag;
+        if rag.confidence_floor >= rag.confidence_ceiling {
+            return Err(crate::error::LyttError::Config(format!(
+                "rag.confidence_floor ({}) must be below rag.confidence_ceiling ({})",
+                rag.confidence_floor, rag.confidence_ceiling
+            )));
+        }
+        Ok(())
+    }
+
+    /// Merge the named profile into the base settings table.
+    fn apply_profile(table: &mut toml::Table, name: &str) -> crate::error::Result<()> {
+        let profiles = table.get("profiles").and_then(|p| p.as_table());
+
+        let profile = profiles
+            .and_then(|p| p.get(name))
+            .and_then(|p| p.as_table())
+            .cloned()
+            .ok_or_else(|| {
+                let available: Vec<&str> = profiles
+                    .map(|p| p.keys().map(|k| k.as_str()).collect())
+                    .unwrap_or_default();
+                crate::error::LyttError::Config(format!(
+                    "Unknown profile '{}'. Available profiles: {}",
+                    name,
+                    if available.is_empty() { "none".to_string() } else { available.join(", ") }
+                ))
+            })?;
+
+        let sets = |key: &str| profile.get("vector_store").and_then(|v| v.get(key)).is_some();
+        let has_own_db = sets("sqlite_path");
+        let has_own_columnar = sets("columnar_path");
+
+        merge_tables(table, profile);
+
+        let data_dir = table
+            .get("general")
+            .and_then(|g| g.get("data_dir"))
+            .and_then(|d| d.as_str())
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| GeneralSettings::default().data_dir);
+        let profile_dir = format!("{}/profiles/{}", data_dir.trim_end_matches('/'), name);
+
+        let vector_store = table
+            .entry("vector_store")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if let Some(vs) = vector_store.as_table_mut() {
+            if !has_own_db {
+                vs.insert("sqlite_path".to_string(), toml::Value::String(format!("{}/vectors.db", profile_dir)));
+            }
+            if !has_own_columnar {
+                vs.insert("columnar_path".to_string(), toml::Value::String(format!("{}/columnar", profile_dir)));
+            }
         }
+
+        Ok(())
     }
 
     /// Save settings to the default configuration file.
@@ -313,8 +1293,132 @@ impl Settings {
         Self::expand_path(&self.general.temp_dir)
     }
 
+    /// Get the expanded cache directory path.
+    pub fn cache_dir(&self) -> PathBuf {
+        Self::expand_path(&self.general.cache_dir)
+    }
+
+    /// Get the expanded directory recordings are kept in.
+    pub fn recordings_dir(&self) -> PathBuf {
+        Self::expand_path(&self.record.output_dir)
+    }
+
+    /// Get the expanded directory scheduled digests are written to.
+    pub fn digests_dir(&self) -> PathBuf {
+        Self::expand_path(&self.digest.output_dir)
+    }
+
     /// Get the expanded SQLite database path.
     pub fn sqlite_path(&self) -> PathBuf {
         Self::expand_path(&self.vector_store.sqlite_path)
     }
+
+    /// Get the expanded columnar store directory.
+    pub fn columnar_path(&self) -> PathBuf {
+        Self::expand_path(&self.vector_store.columnar_path)
+    }
+
+    /// Get the archive directory for pruned documents (expanded).
+    pub fn archive_dir(&self) -> PathBuf {
+        Self::expand_path(&self.retention.archive_dir)
+    }
+
+    /// Get the model price table (built-in prices with config overrides).
+    pub fn pricing(&self) -> crate::usage::Pricing {
+        crate::usage::Pricing::with_overrides(&self.usage.prices)
+    }
+}
+
+/// Recursively merge `overlay` into `base`; overlay values win.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_tables(existing, incoming);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[rag]
+model = "gpt-4o-mini"
+
+[profiles.work]
+rag = { model = "gpt-4.1" }
+prompts = { custom_dir = "~/work/prompts" }
+
+[profiles.personal.vector_store]
+sqlite_path = "~/podcasts.db"
+"#;
+
+    #[test]
+    fn test_profile_overrides() {
+        let base = Settings::from_toml_with_profile(CONFIG, None).unwrap();
+        assert_eq!(base.rag.model, "gpt-4o-mini");
+        assert_eq!(base.vector_store.sqlite_path, "~/.lytt/vectors.db");
+
+        let work = Settings::from_toml_with_profile(CONFIG, Some("work")).unwrap();
+        assert_eq!(work.rag.model, "gpt-4.1");
+        assert_eq!(work.prompts.custom_dir.as_deref(), Some("~/work/prompts"));
+        // Unset values come from the base settings
+        assert_eq!(work.rag.max_context_chunks, 10);
+        // Profiles without their own database get a separate one
+        assert_eq!(work.vector_store.sqlite_path, "~/.lytt/profiles/work/vectors.db");
+        assert_eq!(work.active_profile.as_deref(), Some("work"));
+
+        let personal = Settings::from_toml_with_profile(CONFIG, Some("personal")).unwrap();
+        assert_eq!(personal.vector_store.sqlite_path, "~/podcasts.db");
+    }
+
+    #[test]
+    fn test_llm_endpoints() {
+        let settings = Settings::from_toml_with_profile(
+            "[rag.llm]\nprovider = \"ollama\"\n\n[chunking.llm]\nbase_url = \"http://localhost:8080/v1\"\n",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(settings.rag.llm.describe(), "ollama (http://localhost:11434/v1)");
+        assert!(!settings.rag.llm.uses_openai_api());
+        assert_eq!(settings.chunking.llm.effective_base_url(), Some("http://localhost:8080/v1"));
+        assert!(!settings.chunking.llm.uses_openai_api());
+        assert!(settings.transcription.processing.cleanup_llm.uses_openai_api());
+    }
+
+    #[test]
+    fn test_ytdlp_args() {
+        let settings = Settings::from_toml_with_profile(
+            "[youtube]\ncookies_file = \"/home/me/cookies.txt\"\nproxy = \"\"\nrate_limit = \"2M\"\nextra_args = [\"--force-ipv4\"]\n",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings.youtube.ytdlp_args(),
+            ["--cookies", "/home/me/cookies.txt", "--limit-rate", "2M", "--force-ipv4"]
+        );
+        assert!(YoutubeSettings::default().ytdlp_args().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let err = Settings::from_toml_with_profile(CONFIG, Some("missing")).unwrap_err();
+        assert!(err.to_string().contains("personal, work") || err.to_string().contains("work, personal"));
+    }
+
+    #[test]
+    fn test_confidence_scale_must_be_increasing() {
+        let err = Settings::from_toml_with_profile("[rag]\nconfidence_floor = 0.8\nconfidence_ceiling = 0.3\n", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("rag.confidence_floor (0.8) must be below rag.confidence_ceiling (0.3)"));
+        assert!(Settings::from_toml_with_profile("[rag]\nconfidence_floor = 0.1\nconfidence_ceiling = 0.5\n", None).is_ok());
+    }
 }
@@ -0,0 +1,281 @@
+//! Consensus analysis.
+
+use super::{ConsensusReport, Disagreement, Excerpt, Finding};
+use crate::config::Prompts;
+use crate::embedding::Embedder;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{DerivedFilter, SearchFilter, SearchResult, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Minimum similarity for an excerpt to be considered on-topic.
+const MIN_SCORE: f32 = 0.3;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct AnalysisResponse {
+    #[serde(default)]
+    agreements: Vec<RawFinding>,
+    #[serde(default)]
+    disagreements: Vec<RawDisagreement>,
+    #[serde(default)]
+    changes: Vec<RawFinding>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawFinding {
+    claim: String,
+    #[serde(default)]
+    sources: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawDisagreement {
+    question: String,
+    #[serde(default)]
+    positions: Vec<RawFinding>,
+}
+
+/// Reports where sources on a topic agree, disagree, or changed position.
+pub struct ConsensusAnalyzer {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<dyn VectorStore>,
+    embedder: Arc<dyn Embedder>,
+    prompts: Prompts,
+    filter: SearchFilter,
+    max_excerpts: usize,
+    per_media: usize,
+}
+
+impl ConsensusAnalyzer {
+    /// Create a new consensus analyzer.
+    pub fn new(vector_store: Arc<dyn VectorStore>, embedder: Arc<dyn Embedder>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            embedder,
+            prompts: Prompts::default(),
+            filter: SearchFilter::default().with_derived(DerivedFilter::Exclude),
+            max_excerpts: 24,
+            per_media: 3,
+        }
+    }
+
+    /// Set the maximum number of excerpts sent to the model.
+    pub fn with_max_excerpts(mut self, max_excerpts: usize) -> Self {
+        self.max_excerpts = max_excerpts;
+        self
+    }
+
+    /// Restrict retrieval (e.g. to recent media). Derived documents are
+    /// always excluded, since only primary sources hold positions.
+    pub fn with_filter(mut self, filter: SearchFilter) -> Self {
+        self.filter = filter.with_derived(DerivedFilter::Exclude);
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Analyse what the indexed sources say about `topic`.
+    #[instrument(skip(self))]
+    pub async fn analyze(&self, topic: &str) -> Result<ConsensusReport> {
+        let embedding = self.embedder.embed(topic).await?;
+        let results = self
+            .vector_store
+            .search_filtered(&embedding, self.max_excerpts * 3, MIN_SCORE, &self.filter)
+            .await?;
+
+        let excerpts = select_excerpts(results, self.max_excerpts, self.per_media);
+        let media_count = excerpts.iter().map(|e| &e.video_id).collect::<HashSet<_>>().len();
+        if media_count < 2 {
+            return Err(LyttError::InvalidInput(format!(
+                "Found {} source(s) on '{}'; at least two are needed to compare",
+                media_count, topic
+            )));
+        }
+
+        info!("Comparing {} excerpts from {} sources on '{}'", excerpts.len(), media_count, topic);
+
+        let mut vars = HashMap::new();
+        vars.insert("topic".to_string(), topic.to_string());
+        vars.insert("excerpts".to_string(), format_excerpts(&excerpts));
+
+        let system = self.prompts.render_with_custom(&self.prompts.consensus.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.consensus.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.2)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        let (agreements, disagreements, changes) = parse_analysis(content, &excerpts)?;
+
+        Ok(ConsensusReport {
+            topic: topic.to_string(),
+            agreements,
+            disagreements,
+            changes,
+            media_count,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Keep the best `per_media` results of each media item, up to `max` in
+/// total, ordered oldest first so excerpt numbers follow publication order.
+pub(crate) fn select_excerpts(results: Vec<SearchResult>, max: usize, per_media: usize) -> Vec<Excerpt> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut excerpts: Vec<Excerpt> = results
+        .into_iter()
+        .filter(|r| {
+            let count = counts.entry(r.document.video_id.clone()).or_default();
+            *count += 1;
+            *count <= per_media
+        })
+        .take(max)
+        .map(Excerpt::from)
+        .collect();
+
+    excerpts.sort_by(|a, b| a.date.cmp(&b.date).then(a.start_seconds.total_cmp(&b.start_seconds)));
+    excerpts
+}
+
+/// Number excerpts `[1]`, `[2]`, ... with their title, date and timestamp.
+pub(crate) fn format_excerpts(excerpts: &[Excerpt]) -> String {
+    excerpts
+        .iter()
+        .enumerate()
+        .map(|(i, e)| format!("[{}] {}\n{}", i + 1, e.citation(), e.content))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+type Analysis = (Vec<Finding>, Vec<Disagreement>, Vec<Finding>);
+
+/// Parse the model's JSON, resolving 1-based source numbers to excerpts.
+/// Claims without a valid source are dropped, as are disagreements left with
+/// fewer than two positions.
+fn parse_analysis(content: &str, excerpts: &[Excerpt]) -> Result<Analysis> {
+    let parsed: AnalysisResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid consensus JSON: {}", e)))?;
+
+    let agreements = resolve_findings(parsed.agreements, excerpts);
+    let changes = resolve_findings(parsed.changes, excerpts);
+    let disagreements = resolve_disagreements(parsed.disagreements, excerpts);
+
+    Ok((agreements, disagreements, changes))
+}
+
+/// Resolve the 1-based source numbers of findings to excerpts, dropping
+/// findings without a valid source.
+pub(super) fn resolve_findings(raw: Vec<RawFinding>, excerpts: &[Excerpt]) -> Vec<Finding> {
+    raw.into_iter()
+        .filter_map(|raw| {
+            let mut sources = raw.sources;
+            sources.sort_unstable();
+            sources.dedup();
+            let citations: Vec<Excerpt> = sources
+                .into_iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| excerpts.get(i)).cloned())
+                .collect();
+            let claim = raw.claim.trim().to_string();
+            (!claim.is_empty() && !citations.is_empty()).then_some(Finding { claim, citations })
+        })
+        .collect()
+}
+
+/// Resolve disagreements like [`resolve_findings`], dropping those left with
+/// fewer than two positions.
+pub(super) fn resolve_disagreements(raw: Vec<RawDisagreement>, excerpts: &[Excerpt]) -> Vec<Disagreement> {
+    raw.into_iter()
+        .filter_map(|d| {
+            let positions = resolve_findings(d.positions, excerpts);
+            (positions.len() >= 2).then(|| Disagreement {
+                question: d.question.trim().to_string(),
+                positions,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_analysis() {
+        let excerpt = |title: &str| Excerpt {
+            video_id: title.to_lowercase(),
+            video_title: title.to_string(),
+            timestamp: "01:00".to_string(),
+            start_seconds: 60.0,
+            date: Utc::now(),
+            content: "...".to_string(),
+        };
+        let excerpts = vec![excerpt("A"), excerpt("B")];
+
+        let (agreements, disagreements, changes) = parse_analysis(
+            r#"{
+                "agreements": [{"claim": "Both agree.", "sources": [2, 1, 2]}, {"claim": "Uncited.", "sources": [7]}],
+                "disagreements": [
+                    {"question": "Which is better?", "positions": [{"claim": "X", "sources": [1]}, {"claim": "Y", "sources": [2]}]},
+                    {"question": "One-sided", "positions": [{"claim": "Z", "sources": [0, 1]}]}
+                ]
+            }"#,
+            &excerpts,
+        )
+        .unwrap();
+
+        assert_eq!(agreements.len(), 1);
+        assert_eq!(agreements[0].citations.len(), 2);
+        assert_eq!(agreements[0].citations[0].video_title, "A");
+        assert_eq!(disagreements.len(), 1);
+        assert!(changes.is_empty());
+        assert!(parse_analysis("not json", &excerpts).is_err());
+    }
+}
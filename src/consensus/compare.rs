@@ -0,0 +1,211 @@
+//! Comparison of chosen media.
+
+use super::analyzer::{resolve_disagreements, resolve_findings, RawDisagreement, RawFinding};
+use super::{Comparison, Excerpt};
+use crate::config::Prompts;
+use crate::embedding::Embedder;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{DerivedFilter, SearchFilter, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, instrument, warn};
+
+/// Minimum similarity for an excerpt to be considered relevant.
+const MIN_SCORE: f32 = 0.3;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct ComparisonResponse {
+    #[serde(default)]
+    agreements: Vec<RawFinding>,
+    #[serde(default)]
+    contradictions: Vec<RawDisagreement>,
+    #[serde(default)]
+    unique: Vec<RawFinding>,
+}
+
+/// Compares what chosen media says in answer to a question: retrieves the
+/// most relevant excerpts of each separately, so none crowds out the others,
+/// and has the model report agreements, contradictions and unique points.
+pub struct VideoComparator {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<dyn VectorStore>,
+    embedder: Arc<dyn Embedder>,
+    prompts: Prompts,
+    per_video: usize,
+}
+
+impl VideoComparator {
+    /// Create a new comparator.
+    pub fn new(vector_store: Arc<dyn VectorStore>, embedder: Arc<dyn Embedder>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            embedder,
+            prompts: Prompts::default(),
+            per_video: 5,
+        }
+    }
+
+    /// Set the number of excerpts retrieved from each media item.
+    pub fn with_per_video(mut self, per_video: usize) -> Self {
+        self.per_video = per_video.max(1);
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Compare what the media in `video_ids` says about `question`.
+    #[instrument(skip(self))]
+    pub async fn compare(&self, question: &str, video_ids: &[String]) -> Result<Comparison> {
+        let embedding = self.embedder.embed(question).await?;
+
+        let mut media = Vec::new();
+        let mut unmatched = Vec::new();
+        let mut excerpts: Vec<Excerpt> = Vec::new();
+        for video_id in video_ids {
+            let Some(video) = self.vector_store.get_video(video_id).await? else {
+                return Err(LyttError::VideoNotFound(video_id.clone()));
+            };
+            let filter = SearchFilter::default().with_derived(DerivedFilter::Exclude).with_video(video_id);
+            let results = self
+                .vector_store
+                .search_filtered(&embedding, self.per_video, MIN_SCORE, &filter)
+                .await?;
+            if results.is_empty() {
+                warn!("Nothing in {} is relevant to the question", video_id);
+                unmatched.push(video.video_title);
+                continue;
+            }
+
+            // In the order they are said, so each video's excerpts read as a passage
+            let mut found: Vec<Excerpt> = results.into_iter().map(Excerpt::from).collect();
+            found.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+            excerpts.extend(found);
+            media.push((video.video_id, video.video_title));
+        }
+
+        if media.len() < 2 {
+            return Err(LyttError::InvalidInput(format!(
+                "Found relevant excerpts in {} of the media; at least two are needed to compare",
+                media.len()
+            )));
+        }
+
+        info!("Comparing {} excerpts from {} media items", excerpts.len(), media.len());
+
+        let mut vars = HashMap::new();
+        vars.insert("question".to_string(), question.to_string());
+        vars.insert("excerpts".to_string(), format_grouped(&media, &excerpts));
+
+        let system = self.prompts.render_with_custom(&self.prompts.compare.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.compare.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.2)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        let parsed: ComparisonResponse = serde_json::from_str(content)
+            .map_err(|e| LyttError::Rag(format!("Invalid comparison JSON: {}", e)))?;
+
+        Ok(Comparison {
+            question: question.to_string(),
+            media,
+            unmatched,
+            agreements: resolve_findings(parsed.agreements, &excerpts),
+            contradictions: resolve_disagreements(parsed.contradictions, &excerpts),
+            unique: resolve_findings(parsed.unique, &excerpts)
+                .into_iter()
+                .map(|mut finding| {
+                    // A point only one video makes is cited from that video
+                    let video_id = finding.citations[0].video_id.clone();
+                    finding.citations.retain(|e| e.video_id == video_id);
+                    finding
+                })
+                .collect(),
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Number excerpts `[1]`, `[2]`, ... across all media, under a heading for
+/// each media item.
+fn format_grouped(media: &[(String, String)], excerpts: &[Excerpt]) -> String {
+    let mut output = String::new();
+    for (video_id, title) in media {
+        output.push_str(&format!("## {}\n\n", title));
+        for (i, excerpt) in excerpts.iter().enumerate().filter(|(_, e)| e.video_id == *video_id) {
+            output.push_str(&format!("[{}] {}\n{}\n\n", i + 1, excerpt.citation(), excerpt.content));
+        }
+    }
+    output.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_grouped() {
+        let excerpt = |video_id: &str, content: &str| Excerpt {
+            video_id: video_id.to_string(),
+            video_title: video_id.to_uppercase(),
+            timestamp: "01:00".to_string(),
+            start_seconds: 60.0,
+            date: Utc::now(),
+            content: content.to_string(),
+        };
+        let media = vec![("a".to_string(), "A".to_string()), ("b".to_string(), "B".to_string())];
+        let excerpts = vec![excerpt("a", "first"), excerpt("a", "second"), excerpt("b", "third")];
+
+        let text = format_grouped(&media, &excerpts);
+        assert!(text.starts_with("## A\n\n[1] A ("));
+        assert!(text.contains("second\n\n## B\n\n[3] B ("));
+        assert!(text.ends_with("third"));
+    }
+}
@@ -0,0 +1,270 @@
+//! Consensus and contradiction analysis across sources.
+//!
+//! For a topic, the most relevant excerpts are retrieved from several media
+//! items and numbered in publication order. The model reports claims the
+//! sources agree on, questions they answer differently, and positions that
+//! changed over time, citing excerpt numbers; those are resolved back to
+//! titles, dates and timestamps here.
+//!
+//! A comparison does the same for media chosen by the user: excerpts
+//! answering a question are retrieved from each one separately, and the model
+//! reports agreements, contradictions and points only one of them makes.
+
+mod analyzer;
+mod compare;
+
+pub use analyzer::ConsensusAnalyzer;
+pub(crate) use analyzer::{format_excerpts, select_excerpts};
+pub use compare::VideoComparator;
+
+use crate::vector_store::SearchResult;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A retrieved excerpt the analysis can cite.
+#[derive(Debug, Clone, Serialize)]
+pub struct Excerpt {
+    /// Media ID.
+    pub video_id: String,
+    /// Media title.
+    pub video_title: String,
+    /// Transcript timestamp (MM:SS).
+    pub timestamp: String,
+    /// Start of the excerpt in seconds.
+    pub start_seconds: f64,
+    /// When the media was published (or indexed, without a publish date).
+    pub date: DateTime<Utc>,
+    /// Excerpt text.
+    pub content: String,
+}
+
+impl From<SearchResult> for Excerpt {
+    fn from(result: SearchResult) -> Self {
+        let document = result.document;
+        Self {
+            timestamp: document.format_timestamp(),
+            date: document.source_created_at.unwrap_or(document.indexed_at),
+            video_id: document.video_id,
+            video_title: document.video_title,
+            start_seconds: document.start_seconds,
+            content: document.content,
+        }
+    }
+}
+
+impl Excerpt {
+    /// Citation such as `Lecture 3 (2024-05-01) @ 04:12`.
+    pub fn citation(&self) -> String {
+        format!("{} ({}) @ {}", self.video_title, self.date.format("%Y-%m-%d"), self.timestamp)
+    }
+}
+
+/// A claim and the excerpts supporting it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    /// The claim, position or change, in one or two sentences.
+    pub claim: String,
+    /// Supporting excerpts, oldest first.
+    pub citations: Vec<Excerpt>,
+}
+
+/// A question the sources answer differently.
+#[derive(Debug, Clone, Serialize)]
+pub struct Disagreement {
+    /// What the sources disagree about.
+    pub question: String,
+    /// Each position with its sources.
+    pub positions: Vec<Finding>,
+}
+
+/// Where sources on a topic agree, disagree, or changed position.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsensusReport {
+    /// The topic analysed.
+    pub topic: String,
+    /// Claims several sources agree on.
+    pub agreements: Vec<Finding>,
+    /// Questions the sources answer differently.
+    pub disagreements: Vec<Disagreement>,
+    /// Positions that changed between older and newer sources.
+    pub changes: Vec<Finding>,
+    /// Number of distinct media items the excerpts came from.
+    pub media_count: usize,
+    /// When the report was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl ConsensusReport {
+    /// Whether the model found nothing to report.
+    pub fn is_empty(&self) -> bool {
+        self.agreements.is_empty() && self.disagreements.is_empty() && self.changes.is_empty()
+    }
+
+    /// Render the report as Markdown with a citation list under each claim.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Consensus: {}\n\n", self.topic);
+        output.push_str(&format!(
+            "*{} sources, generated {}*\n",
+            self.media_count,
+            self.created_at.format("%Y-%m-%d")
+        ));
+
+        if self.is_empty() {
+            output.push_str("\nNo agreements, disagreements or changes found.\n");
+            return output;
+        }
+
+        if !self.agreements.is_empty() {
+            output.push_str("\n## Agreement\n");
+            for finding in &self.agreements {
+                push_finding(&mut output, finding);
+            }
+        }
+
+        if !self.disagreements.is_empty() {
+            output.push_str("\n## Disagreement\n");
+            for disagreement in &self.disagreements {
+                output.push_str(&format!("\n### {}\n", disagreement.question));
+                for position in &disagreement.positions {
+                    push_finding(&mut output, position);
+                }
+            }
+        }
+
+        if !self.changes.is_empty() {
+            output.push_str("\n## Changed Over Time\n");
+            for finding in &self.changes {
+                push_finding(&mut output, finding);
+            }
+        }
+
+        output
+    }
+}
+
+/// A comparison of what chosen media says in answer to a question.
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    /// The question asked.
+    pub question: String,
+    /// The media compared, as (ID, title), in the order given.
+    pub media: Vec<(String, String)>,
+    /// Titles of media with nothing relevant to the question, left out.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unmatched: Vec<String>,
+    /// Points two or more of the media make.
+    pub agreements: Vec<Finding>,
+    /// Questions the media answer differently.
+    pub contradictions: Vec<Disagreement>,
+    /// Points only one of the media makes.
+    pub unique: Vec<Finding>,
+    /// When the comparison was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Comparison {
+    /// Whether the model found nothing to report.
+    pub fn is_empty(&self) -> bool {
+        self.agreements.is_empty() && self.contradictions.is_empty() && self.unique.is_empty()
+    }
+
+    /// Render the comparison as Markdown, with unique points grouped by the
+    /// media making them and a citation list under each point.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Comparison: {}\n\n", self.question);
+        let titles: Vec<&str> = self.media.iter().map(|(_, title)| title.as_str()).collect();
+        output.push_str(&format!("*Comparing {}*\n", titles.join(", ")));
+        if !self.unmatched.is_empty() {
+            output.push_str(&format!("*Nothing relevant in {}*\n", self.unmatched.join(", ")));
+        }
+
+        if self.is_empty() {
+            output.push_str("\nNo agreements, contradictions or unique points found.\n");
+            return output;
+        }
+
+        if !self.agreements.is_empty() {
+            output.push_str("\n## Agreements\n");
+            for finding in &self.agreements {
+                push_finding(&mut output, finding);
+            }
+        }
+
+        if !self.contradictions.is_empty() {
+            output.push_str("\n## Contradictions\n");
+            for contradiction in &self.contradictions {
+                output.push_str(&format!("\n### {}\n", contradiction.question));
+                for position in &contradiction.positions {
+                    push_finding(&mut output, position);
+                }
+            }
+        }
+
+        if !self.unique.is_empty() {
+            output.push_str("\n## Unique Points\n");
+            for (video_id, title) in &self.media {
+                let points: Vec<&Finding> =
+                    self.unique.iter().filter(|f| f.citations[0].video_id == *video_id).collect();
+                if points.is_empty() {
+                    continue;
+                }
+                output.push_str(&format!("\n### {}\n", title));
+                for finding in points {
+                    push_finding(&mut output, finding);
+                }
+            }
+        }
+
+        output
+    }
+}
+
+fn push_finding(output: &mut String, finding: &Finding) {
+    output.push_str(&format!("\n- {}\n", finding.claim));
+    for excerpt in &finding.citations {
+        output.push_str(&format!("  - {}\n", excerpt.citation()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn excerpt(title: &str, year: i32) -> Excerpt {
+        Excerpt {
+            video_id: title.to_lowercase(),
+            video_title: title.to_string(),
+            timestamp: "04:12".to_string(),
+            start_seconds: 252.0,
+            date: Utc.with_ymd_and_hms(year, 5, 1, 0, 0, 0).unwrap(),
+            content: "...".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let report = ConsensusReport {
+            topic: "intermittent fasting".to_string(),
+            agreements: vec![Finding {
+                claim: "Fasting lowers insulin.".to_string(),
+                citations: vec![excerpt("Ep 1", 2021), excerpt("Ep 9", 2023)],
+            }],
+            disagreements: vec![Disagreement {
+                question: "Does it preserve muscle?".to_string(),
+                positions: vec![
+                    Finding { claim: "Yes.".to_string(), citations: vec![excerpt("Ep 1", 2021)] },
+                    Finding { claim: "No.".to_string(), citations: vec![excerpt("Ep 9", 2023)] },
+                ],
+            }],
+            changes: Vec::new(),
+            media_count: 2,
+            created_at: Utc::now(),
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## Agreement\n\n- Fasting lowers insulin.\n  - Ep 1 (2021-05-01) @ 04:12\n  - Ep 9 (2023-05-01) @ 04:12\n"));
+        assert!(markdown.contains("### Does it preserve muscle?"));
+        assert!(!markdown.contains("## Changed Over Time"));
+    }
+}
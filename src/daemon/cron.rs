@@ -0,0 +1,207 @@
+//! Cron-style schedules.
+//!
+//! The usual five fields (minute, hour, day of month, month, day of week)
+//! with `*`, lists (`1,15`), ranges (`9-17`), steps (`*/15`, `0-30/10`) and
+//! English month and day names, plus the `@hourly`, `@daily`, `@weekly` and
+//! `@monthly` shorthands. As in cron, when both the day of month and the day
+//! of week are restricted, either one matching is enough.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Furthest ahead a next run is looked for; schedules that never match
+/// (e.g. February 30th) give up here.
+const SEARCH_YEARS: i64 = 5;
+
+/// A parsed cron schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of month and day of week fields were `*`.
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// The expression the schedule was parsed from.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The first time strictly after `after`, to the minute, that the
+    /// schedule matches, in the same time zone. Local times skipped by a
+    /// daylight saving change are skipped.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let local = after.naive_local();
+        let mut t = local.date().and_hms_opt(local.hour(), local.minute(), 0)? + Duration::minutes(1);
+        let limit = t + Duration::days(366 * SEARCH_YEARS);
+
+        while t < limit {
+            if !has(self.months, t.month()) {
+                t = first_of_next_month(t)?;
+            } else if !self.day_matches(t) {
+                t = (t.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else if let Some(next) = tz.from_local_datetime(&t).earliest() {
+                return Some(next);
+            } else {
+                t += Duration::minutes(1);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, t: NaiveDateTime) -> bool {
+        let day = has(self.days, t.day());
+        let weekday = has(self.weekdays, t.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (false, true) => day,
+            (true, false) => weekday,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+impl std::str::FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let expression = s.trim();
+        let expanded = match expression.to_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * sun".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            other => other.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Invalid schedule: {}. Use five cron fields (minute hour day month weekday), e.g. \"0 8 * * mon\", or @hourly, @daily, @weekly.",
+                expression
+            ));
+        };
+
+        // Sunday is both 0 and 7
+        let weekdays = parse_field(weekday, 0, 7, &WEEKDAYS)?;
+        let weekdays = (weekdays | (weekdays >> 7)) & 0x7f;
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])?,
+            days: parse_field(day, 1, 31, &[])?,
+            months: parse_field(month, 1, 12, &MONTHS)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+}
+
+impl std::fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn first_of_next_month(t: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+/// Parse one field into a bit set of the values it matches. `names` are the
+/// English names of the values, starting at `min`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> std::result::Result<u64, String> {
+    let invalid = || format!("Invalid schedule field: {} (values {}-{})", field, min, max);
+    let value = |s: &str| -> std::result::Result<u32, String> {
+        let v = match names.iter().position(|n| *n == s) {
+            Some(i) => i as u32 + min,
+            None => s.parse().map_err(|_| invalid())?,
+        };
+        if (min..=max).contains(&v) {
+            Ok(v)
+        } else {
+            Err(invalid())
+        }
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?)),
+            None => (part, None),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (value(a)?, value(b)?),
+                // "5/15" means from 5 to the end, every 15
+                None if step.is_some() => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            set |= 1 << v;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_cron_next_after() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap().and_utc();
+        let next = |expr: &str, after: &str| {
+            expr.parse::<CronSchedule>()
+                .unwrap()
+                .next_after(&at(after))
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        };
+
+        // 2025-01-01 is a Wednesday
+        assert_eq!(next("*/15 * * * *", "2025-01-01 10:07").as_deref(), Some("2025-01-01 10:15"));
+        assert_eq!(next("*/15 * * * *", "2025-01-01 10:15").as_deref(), Some("2025-01-01 10:30"));
+        assert_eq!(next("0 8 * * mon", "2025-01-01 09:00").as_deref(), Some("2025-01-06 08:00"));
+        assert_eq!(next("30 9-17/4 * * 1-5", "2025-01-03 18:00").as_deref(), Some("2025-01-06 09:30"));
+        assert_eq!(next("@weekly", "2025-01-01 00:00").as_deref(), Some("2025-01-05 00:00"));
+        assert_eq!(next("0 0 * * 7", "2025-01-01 00:00").as_deref(), Some("2025-01-05 00:00"));
+        assert_eq!(next("0 0 29 feb *", "2025-01-01 00:00").as_deref(), Some("2028-02-29 00:00"));
+        // Either the day of month or the day of week
+        assert_eq!(next("0 12 15 * fri", "2025-01-01 00:00").as_deref(), Some("2025-01-03 12:00"));
+        assert_eq!(next("0 0 30 feb *", "2025-01-01 00:00"), None);
+
+        assert!("* * * *".parse::<CronSchedule>().is_err());
+        assert!("60 * * * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+        assert!("0 17-9 * * *".parse::<CronSchedule>().is_err());
+        assert!("@daily".parse::<CronSchedule>().unwrap().next_after(&Utc::now()).is_some());
+    }
+}
@@ -0,0 +1,157 @@
+//! The daemon's jobs.
+
+use super::{Daemon, JobOutcome};
+use crate::audio_source::{content_hash, AudioSource, LocalSource, YoutubeSource};
+use crate::config::Settings;
+use crate::digest::write_digest;
+use crate::error::{LyttError, Result};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+impl Daemon {
+    /// Ingest media files in the watch directories that haven't been
+    /// ingested yet (by content hash, so moved and renamed files count).
+    /// Files modified within `settle_seconds` are left for the next scan.
+    pub(super) async fn scan_watch_dirs(&self) -> Result<JobOutcome> {
+        let settings = &self.orchestrator.settings().daemon;
+        let store = self.orchestrator.sqlite_store();
+        let settle = Duration::from_secs(settings.settle_seconds);
+        let source = LocalSource::new().with_recursive(true);
+
+        let mut outcome = JobOutcome::default();
+        let mut pending = 0;
+        for dir in &settings.watch_dirs {
+            let dir = Settings::expand_path(dir);
+            let files = match source.find_media_files(&dir.to_string_lossy()) {
+                Ok(files) => files,
+                Err(e) => {
+                    warn!("Skipping watch directory {}: {}", dir.display(), e);
+                    outcome.failed += 1;
+                    continue;
+                }
+            };
+
+            for file in files {
+                let Ok(modified) = file.metadata().and_then(|m| m.modified()) else {
+                    continue;
+                };
+                if modified.elapsed().unwrap_or_default() < settle {
+                    pending += 1;
+                    continue;
+                }
+                if self.seen.lock().is_ok_and(|seen| seen.get(&file) == Some(&modified)) {
+                    continue;
+                }
+
+                let hash = {
+                    let path = file.clone();
+                    tokio::task::spawn_blocking(move || content_hash(&path))
+                        .await
+                        .map_err(|e| LyttError::VideoSource(format!("Failed to hash {}: {}", file.display(), e)))
+                        .and_then(|hash| hash)
+                };
+                let hash = match hash {
+                    Ok(hash) => hash,
+                    Err(e) => {
+                        warn!("Failed to read {}: {}", file.display(), e);
+                        outcome.failed += 1;
+                        continue;
+                    }
+                };
+                let key = hash.clone();
+                let ingested = store.blocking(move |store| store.ingested_file(&key)).await?;
+                if ingested.is_some() || self.gave_up(&hash) {
+                    if let Ok(mut seen) = self.seen.lock() {
+                        seen.insert(file, modified);
+                    }
+                    continue;
+                }
+
+                let path = file.to_string_lossy().to_string();
+                info!("Ingesting {}", path);
+                match self.orchestrator.process_media(&path, false).await {
+                    Ok(result) => {
+                        // Duplicates are recorded too, so they aren't transcribed again next scan
+                        let media_id = result.duplicate_of.as_ref().unwrap_or(&result.media_id);
+                        let (hash, path, media_id) = (hash.clone(), path.clone(), media_id.clone());
+                        store
+                            .blocking(move |store| store.record_ingested_file(&hash, &path, &media_id))
+                            .await?;
+                        if let Ok(mut seen) = self.seen.lock() {
+                            seen.insert(file, modified);
+                        }
+                        if !result.skipped {
+                            outcome.indexed += 1;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to ingest {}: {}", path, e);
+                        self.record_failure(&hash);
+                        outcome.failed += 1;
+                    }
+                }
+            }
+        }
+
+        outcome.summary = format!("{} indexed, {} failed, {} still being written", outcome.indexed, outcome.failed, pending);
+        Ok(outcome)
+    }
+
+    /// Ingest media from the subscribed playlists and channels that isn't
+    /// indexed yet, checking the newest `subscription_limit` entries of each.
+    pub(super) async fn sync_subscriptions(&self) -> Result<JobOutcome> {
+        let settings = &self.orchestrator.settings().daemon;
+        let store = self.orchestrator.vector_store();
+        let source = YoutubeSource::new();
+
+        let mut outcome = JobOutcome::default();
+        for subscription in &settings.subscriptions {
+            let media = match source.list_media(subscription, Some(settings.subscription_limit)).await {
+                Ok(media) => media,
+                Err(e) => {
+                    warn!("Failed to list {}: {}", subscription, e);
+                    outcome.failed += 1;
+                    continue;
+                }
+            };
+
+            for item in media {
+                if store.is_video_indexed(&item.id).await? || self.gave_up(&item.id) {
+                    continue;
+                }
+                info!("Ingesting {} from {}", item.title, subscription);
+                match self.orchestrator.process_media(&item.id, false).await {
+                    Ok(result) if !result.skipped => outcome.indexed += 1,
+                    Ok(_) => debug!("Skipped {} (duplicate)", item.id),
+                    Err(e) => {
+                        warn!("Failed to ingest {}: {}", item.id, e);
+                        self.record_failure(&item.id);
+                        outcome.failed += 1;
+                    }
+                }
+            }
+        }
+
+        outcome.summary = format!(
+            "{} indexed, {} failed from {} subscriptions",
+            outcome.indexed,
+            outcome.failed,
+            settings.subscriptions.len()
+        );
+        Ok(outcome)
+    }
+
+    /// Write (and email) a digest of the media indexed since `since`.
+    pub(super) async fn digest(&self, since: DateTime<Utc>) -> Result<JobOutcome> {
+        let summary = match write_digest(&self.orchestrator, since).await? {
+            Some(path) => format!("wrote {}", path.display()),
+            None => format!("nothing indexed since {}", since.format("%Y-%m-%d %H:%M")),
+        };
+        Ok(JobOutcome {
+            summary,
+            ..JobOutcome::default()
+        })
+    }
+}
+
@@ -0,0 +1,296 @@
+//! Long-running daemon jobs.
+//!
+//! `lytt daemon` runs the API server next to scheduled jobs: scanning watch
+//! directories for new media files, syncing playlist and channel
+//! subscriptions, and generating digests. Each job runs on its own cron
+//! schedule from `[daemon.schedule]`, never overlapping itself, and reports
+//! its progress in a [`DaemonStatus`] served at `/status`.
+
+mod cron;
+mod jobs;
+
+pub use cron::CronSchedule;
+
+use crate::digest::DigestSchedule;
+use crate::error::{LyttError, Result};
+use crate::logging;
+use crate::orchestrator::Orchestrator;
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// A scheduled daemon job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    /// Ingest new media files from the watch directories.
+    Watch,
+    /// Ingest new media from subscribed playlists and channels.
+    Subscriptions,
+    /// Generate a digest of the media indexed since the last one.
+    Digest,
+}
+
+impl std::fmt::Display for JobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobKind::Watch => write!(f, "watch"),
+            JobKind::Subscriptions => write!(f, "subscriptions"),
+            JobKind::Digest => write!(f, "digest"),
+        }
+    }
+}
+
+/// What a job run did.
+#[derive(Debug, Clone, Default)]
+struct JobOutcome {
+    /// Media items indexed.
+    indexed: usize,
+    /// Items that failed (the run itself still succeeded).
+    failed: usize,
+    /// One-line description of the run.
+    summary: String,
+}
+
+/// Progress of one job.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobStatus {
+    /// The job.
+    pub job: JobKind,
+    /// Its cron schedule.
+    pub schedule: String,
+    /// Whether a run is in progress.
+    pub running: bool,
+    /// When the next run is due, if the schedule ever matches again.
+    #[schema(value_type = Option<String>)]
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// When the last run started.
+    #[schema(value_type = Option<String>)]
+    pub last_started_at: Option<DateTime<Utc>>,
+    /// When the last run finished.
+    #[schema(value_type = Option<String>)]
+    pub last_finished_at: Option<DateTime<Utc>>,
+    /// Run ID of the last run, which its log lines carry.
+    pub last_run_id: Option<String>,
+    /// What the last successful run did.
+    pub last_summary: Option<String>,
+    /// Why the last run failed, if it did.
+    pub last_error: Option<String>,
+    /// Completed runs.
+    pub runs: u64,
+    /// Runs that failed.
+    pub failed_runs: u64,
+    /// Media items indexed across all runs.
+    pub items_indexed: u64,
+    /// Items that failed across all runs.
+    pub items_failed: u64,
+}
+
+/// Structured status of the daemon and its jobs.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DaemonStatus {
+    /// Lytt version.
+    pub version: String,
+    /// When the daemon started.
+    #[schema(value_type = String)]
+    pub started_at: DateTime<Utc>,
+    /// Seconds since the daemon started.
+    pub uptime_seconds: i64,
+    /// The scheduled jobs.
+    pub jobs: Vec<JobStatus>,
+}
+
+/// Runs scheduled jobs and tracks their status.
+pub struct Daemon {
+    orchestrator: Arc<Orchestrator>,
+    started_at: DateTime<Utc>,
+    schedules: Vec<(JobKind, CronSchedule)>,
+    status: Mutex<Vec<JobStatus>>,
+    /// Failed attempts per file content hash or video ID.
+    attempts: Mutex<HashMap<String, u32>>,
+    /// Modification times of watched files already checked, so unchanged
+    /// files aren't hashed again on every scan.
+    seen: Mutex<HashMap<PathBuf, SystemTime>>,
+}
+
+impl Daemon {
+    /// Set up the jobs configured in `[daemon]`. A job is enabled when it has
+    /// a schedule and something to do (watch directories, subscriptions).
+    pub fn new(orchestrator: Arc<Orchestrator>) -> Result<Self> {
+        let settings = orchestrator.settings();
+        let daemon = &settings.daemon;
+
+        let digest = match daemon.schedule.digest.trim() {
+            "" => match settings.digest.schedule.trim() {
+                "" => "",
+                schedule => schedule.parse::<DigestSchedule>().map_err(LyttError::Config)?.cron(),
+            },
+            schedule => schedule,
+        };
+        let configured = [
+            (JobKind::Watch, daemon.schedule.watch.trim(), !daemon.watch_dirs.is_empty()),
+            (JobKind::Subscriptions, daemon.schedule.subscriptions.trim(), !daemon.subscriptions.is_empty()),
+            (JobKind::Digest, digest, true),
+        ];
+
+        let mut schedules = Vec::new();
+        for (job, schedule, has_work) in configured {
+            if schedule.is_empty() || !has_work {
+                continue;
+            }
+            let schedule: CronSchedule = schedule
+                .parse()
+                .map_err(|e| LyttError::Config(format!("[daemon.schedule] {}: {}", job, e)))?;
+            schedules.push((job, schedule));
+        }
+
+        let status = schedules
+            .iter()
+            .map(|(job, schedule)| JobStatus {
+                job: *job,
+                schedule: schedule.to_string(),
+                running: false,
+                next_run_at: None,
+                last_started_at: None,
+                last_finished_at: None,
+                last_run_id: None,
+                last_summary: None,
+                last_error: None,
+                runs: 0,
+                failed_runs: 0,
+                items_indexed: 0,
+                items_failed: 0,
+            })
+            .collect();
+
+        Ok(Self {
+            orchestrator,
+            started_at: Utc::now(),
+            schedules,
+            status: Mutex::new(status),
+            attempts: Mutex::new(HashMap::new()),
+            seen: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The enabled jobs and their schedules.
+    pub fn jobs(&self) -> impl Iterator<Item = (JobKind, &CronSchedule)> {
+        self.schedules.iter().map(|(job, schedule)| (*job, schedule))
+    }
+
+    /// Current status of the daemon and its jobs.
+    pub fn status(&self) -> DaemonStatus {
+        let now = Utc::now();
+        DaemonStatus {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: self.started_at,
+            uptime_seconds: (now - self.started_at).num_seconds(),
+            jobs: self.status.lock().map(|s| s.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Start every enabled job in the background.
+    pub fn spawn(self: &Arc<Self>) -> Vec<tokio::task::JoinHandle<()>> {
+        self.schedules
+            .iter()
+            .map(|(job, schedule)| {
+                let daemon = self.clone();
+                let (job, schedule) = (*job, schedule.clone());
+                tokio::spawn(async move { daemon.run_schedule(job, schedule).await })
+            })
+            .collect()
+    }
+
+    /// Run a job whenever its schedule is due. Runs that take longer than
+    /// the gap to the next one skip the missed times.
+    async fn run_schedule(&self, job: JobKind, schedule: CronSchedule) {
+        let mut last_run: Option<DateTime<Utc>> = None;
+        loop {
+            let Some(next) = schedule.next_after(&Local::now()) else {
+                warn!("The {} schedule '{}' never runs again", job, schedule);
+                self.update(job, |s| s.next_run_at = None);
+                return;
+            };
+            // Each digest covers the time since the last; the first one the
+            // gap to the run after it, which is the schedule's period
+            let period = schedule.next_after(&next).map(|after| after - next).unwrap_or_default();
+            let next = next.with_timezone(&Utc);
+            self.update(job, |s| s.next_run_at = Some(next));
+
+            let wait = (next - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            let since = last_run.unwrap_or(next - period);
+
+            // Each run logs under its own run ID
+            let run_id = logging::new_run_id();
+            let started = Utc::now();
+            self.update(job, |s| {
+                s.running = true;
+                s.last_started_at = Some(started);
+                s.last_run_id = Some(run_id.clone());
+            });
+
+            let span = tracing::info_span!(parent: None, "job", run_id = %run_id, job = %job);
+            let result = logging::scope(run_id, span, async {
+                info!("Running {} job", job);
+                let result = match job {
+                    JobKind::Watch => self.scan_watch_dirs().await,
+                    JobKind::Subscriptions => self.sync_subscriptions().await,
+                    JobKind::Digest => self.digest(since).await,
+                };
+                match &result {
+                    Ok(outcome) => info!("{} job finished: {}", job, outcome.summary),
+                    Err(e) => warn!("{} job failed: {}", job, e),
+                }
+                result
+            })
+            .await;
+
+            self.update(job, |s| {
+                s.running = false;
+                s.last_finished_at = Some(Utc::now());
+                s.runs += 1;
+                match result {
+                    Ok(outcome) => {
+                        s.items_indexed += outcome.indexed as u64;
+                        s.items_failed += outcome.failed as u64;
+                        s.last_summary = Some(outcome.summary);
+                        s.last_error = None;
+                    }
+                    Err(e) => {
+                        s.failed_runs += 1;
+                        s.last_error = Some(e.to_string());
+                    }
+                }
+            });
+            last_run = Some(started);
+        }
+    }
+
+    fn update(&self, job: JobKind, f: impl FnOnce(&mut JobStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            if let Some(s) = status.iter_mut().find(|s| s.job == job) {
+                f(s);
+            }
+        }
+    }
+
+    /// Whether an item has failed too often to try again.
+    fn gave_up(&self, key: &str) -> bool {
+        let max = self.orchestrator.settings().daemon.max_attempts.max(1);
+        self.attempts.lock().is_ok_and(|a| a.get(key).is_some_and(|n| *n >= max))
+    }
+
+    /// Count a failed attempt at an item.
+    fn record_failure(&self, key: &str) {
+        if let Ok(mut attempts) = self.attempts.lock() {
+            *attempts.entry(key.to_string()).or_default() += 1;
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Duplicate and near-duplicate media detection.
+//!
+//! Re-uploads and reposted podcast episodes produce near-identical
+//! transcripts. Media are compared by their mean chunk embedding first, and
+//! close pairs are confirmed chunk by chunk: a pair is a duplicate when most
+//! chunks of the shorter item have a near-identical chunk (by embedding or by
+//! text) in the other. Before transcription only metadata is available, so
+//! [`likely_duplicate`] compares titles and durations instead.
+
+use crate::audio_source::MediaMetadata;
+use crate::error::Result;
+use crate::vector_store::{DocType, Document, IndexedVideo, VectorStore};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{debug, instrument};
+
+/// Mean-embedding similarity below which a pair is not compared chunk by chunk.
+const CANDIDATE_SIMILARITY: f32 = 0.9;
+
+/// Duration difference (seconds) still considered the same recording.
+const DURATION_TOLERANCE_SECONDS: f64 = 5.0;
+
+/// Relative duration difference still considered the same recording.
+const DURATION_TOLERANCE_RATIO: f64 = 0.02;
+
+/// A media item in a duplicate pair.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateMedia {
+    /// Media ID.
+    pub video_id: String,
+    /// Media title.
+    pub video_title: String,
+    /// Indexed transcript chunks.
+    pub chunks: usize,
+    /// When the media was indexed.
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// Two media items with near-identical content.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePair {
+    /// The item indexed first (kept).
+    pub original: DuplicateMedia,
+    /// The item indexed later (the one to skip or delete).
+    pub duplicate: DuplicateMedia,
+    /// Cosine similarity of the items' mean chunk embeddings.
+    pub similarity: f32,
+    /// Fraction of the shorter item's chunks with a near-identical chunk in the other.
+    pub overlap: f32,
+}
+
+/// Source chunks of one media item.
+struct MediaChunks {
+    media: DuplicateMedia,
+    mean: Vec<f32>,
+    embeddings: Vec<Vec<f32>>,
+    texts: Vec<String>,
+}
+
+/// Finds duplicate media in the library.
+pub struct DuplicateFinder {
+    vector_store: Arc<dyn VectorStore>,
+    chunk_threshold: f32,
+    min_overlap: f32,
+}
+
+impl DuplicateFinder {
+    /// Create a new duplicate finder.
+    pub fn new(vector_store: Arc<dyn VectorStore>) -> Self {
+        Self {
+            vector_store,
+            chunk_threshold: 0.92,
+            min_overlap: 0.8,
+        }
+    }
+
+    /// Set the similarity at which two chunks count as the same content.
+    pub fn with_chunk_threshold(mut self, threshold: f32) -> Self {
+        self.chunk_threshold = threshold;
+        self
+    }
+
+    /// Set the fraction of matching chunks that makes two items duplicates.
+    pub fn with_min_overlap(mut self, min_overlap: f32) -> Self {
+        self.min_overlap = min_overlap;
+        self
+    }
+
+    /// Find all duplicate pairs, most similar first.
+    #[instrument(skip(self))]
+    pub async fn find(&self) -> Result<Vec<DuplicatePair>> {
+        let mut media = Vec::new();
+        for video in self.vector_store.list_videos().await? {
+            let docs = self.vector_store.get_by_video_id(&video.video_id).await?;
+            if let Some(chunks) = MediaChunks::new(&video, docs) {
+                media.push(chunks);
+            }
+        }
+        debug!("Comparing {} media items for duplicates", media.len());
+
+        Ok(find_duplicates(&media, self.chunk_threshold, self.min_overlap))
+    }
+}
+
+impl MediaChunks {
+    fn new(video: &IndexedVideo, docs: Vec<Document>) -> Option<Self> {
+        let (embeddings, texts): (Vec<Vec<f32>>, Vec<String>) = docs
+            .into_iter()
+            .filter(|d| d.doc_type == DocType::Source && !d.embedding.is_empty())
+            .map(|d| (normalize(&d.embedding), normalize_text(&d.content)))
+            .unzip();
+        if embeddings.is_empty() {
+            return None;
+        }
+
+        let mut mean = vec![0.0f32; embeddings[0].len()];
+        for embedding in &embeddings {
+            for (m, x) in mean.iter_mut().zip(embedding) {
+                *m += x;
+            }
+        }
+
+        Some(Self {
+            media: DuplicateMedia {
+                video_id: video.video_id.clone(),
+                video_title: video.video_title.clone(),
+                chunks: embeddings.len(),
+                indexed_at: video.indexed_at,
+            },
+            mean: normalize(&mean),
+            embeddings,
+            texts,
+        })
+    }
+
+    /// Fraction of this item's chunks that also occur in `other`.
+    fn overlap_with(&self, other: &MediaChunks, chunk_threshold: f32) -> f32 {
+        let matched = self
+            .embeddings
+            .iter()
+            .zip(&self.texts)
+            .filter(|(embedding, text)| {
+                other.texts.iter().any(|t| !text.is_empty() && t == *text)
+                    || other.embeddings.iter().any(|e| dot(embedding, e) >= chunk_threshold)
+            })
+            .count();
+        matched as f32 / self.embeddings.len() as f32
+    }
+}
+
+/// Compare every pair of media items.
+fn find_duplicates(media: &[MediaChunks], chunk_threshold: f32, min_overlap: f32) -> Vec<DuplicatePair> {
+    let mut pairs = Vec::new();
+
+    for (i, a) in media.iter().enumerate() {
+        for b in &media[i + 1..] {
+            let similarity = dot(&a.mean, &b.mean);
+            if similarity < CANDIDATE_SIMILARITY.min(chunk_threshold) {
+                continue;
+            }
+
+            let (shorter, longer) = if a.embeddings.len() <= b.embeddings.len() { (a, b) } else { (b, a) };
+            let overlap = shorter.overlap_with(longer, chunk_threshold);
+            if overlap < min_overlap {
+                continue;
+            }
+
+            let (original, duplicate) = if (a.media.indexed_at, &a.media.video_id) <= (b.media.indexed_at, &b.media.video_id) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            pairs.push(DuplicatePair {
+                original: original.media.clone(),
+                duplicate: duplicate.media.clone(),
+                similarity,
+                overlap,
+            });
+        }
+    }
+
+    pairs.sort_by(|a, b| b.overlap.total_cmp(&a.overlap).then(b.similarity.total_cmp(&a.similarity)));
+    pairs
+}
+
+/// An indexed media item that is probably the same recording as `metadata`:
+/// same title (ignoring case, punctuation and bracketed tags such as
+/// "[HD]") and, when both are known, the same duration.
+pub fn likely_duplicate<'a>(metadata: &MediaMetadata, indexed: &'a [IndexedVideo]) -> Option<&'a IndexedVideo> {
+    let title = normalize_title(&metadata.title);
+    if title.is_empty() {
+        return None;
+    }
+
+    indexed.iter().find(|video| {
+        if video.video_id == metadata.id || normalize_title(&video.video_title) != title {
+            return false;
+        }
+        match metadata.duration_seconds {
+            Some(duration) if video.total_duration_seconds > 0.0 => {
+                let difference = (duration as f64 - video.total_duration_seconds).abs();
+                difference <= DURATION_TOLERANCE_SECONDS.max(duration as f64 * DURATION_TOLERANCE_RATIO)
+            }
+            _ => true,
+        }
+    })
+}
+
+/// Lowercase words of a title, without bracketed tags.
+fn normalize_title(title: &str) -> String {
+    let mut depth = 0usize;
+    let stripped: String = title
+        .chars()
+        .filter(|c| match c {
+            '(' | '[' => {
+                depth += 1;
+                false
+            }
+            ')' | ']' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect();
+    normalize_text(&stripped)
+}
+
+/// Lowercase alphanumeric words separated by single spaces.
+fn normalize_text(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = dot(v, v).sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_source::SourceType;
+    use crate::vector_store::MemoryVectorStore;
+
+    fn doc(video_id: &str, content: &str, embedding: Vec<f32>, order: i32) -> Document {
+        Document::new(video_id.to_string(), format!("Title {}", video_id), None, content.to_string(), 0.0, 10.0, embedding, order, None)
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicates() {
+        let store = Arc::new(MemoryVectorStore::new());
+        store
+            .upsert_batch(&[
+                doc("original", "Welcome to the show.", vec![1.0, 0.0, 0.0], 0),
+                doc("original", "Today: gradient descent.", vec![0.0, 1.0, 0.0], 1),
+                // Re-upload: same text, slightly different embeddings
+                doc("reupload", "Welcome to the show!", vec![0.99, 0.05, 0.0], 0),
+                doc("reupload", "Today: gradient descent.", vec![0.0, 0.8, 0.2], 1),
+                doc("other", "Something else entirely.", vec![0.0, 0.0, 1.0], 0),
+            ])
+            .await
+            .unwrap();
+
+        let pairs = DuplicateFinder::new(store).find().await.unwrap();
+        assert_eq!(pairs.len(), 1);
+        let ids = [pairs[0].original.video_id.as_str(), pairs[0].duplicate.video_id.as_str()];
+        assert!(ids.contains(&"original") && ids.contains(&"reupload"));
+        assert_eq!(pairs[0].overlap, 1.0);
+    }
+
+    #[test]
+    fn test_likely_duplicate() {
+        let indexed = vec![IndexedVideo {
+            video_id: "abc".to_string(),
+            video_title: "Episode 12: Rust Async".to_string(),
+            chunk_count: 10,
+            total_duration_seconds: 3600.0,
+            indexed_at: Utc::now(),
+            language: None,
+        }];
+        let metadata = |title: &str, duration: Option<u32>| MediaMetadata {
+            id: "xyz".to_string(),
+            title: title.to_string(),
+            description: None,
+            duration_seconds: duration,
+            source_type: SourceType::YouTube,
+            source_url: String::new(),
+            published_at: None,
+            channel: None,
+            thumbnail_url: None,
+            album: None,
+            track: None,
+            chapters: Vec::new(),
+        };
+
+        assert!(likely_duplicate(&metadata("Episode 12 - Rust async [HD]", Some(3610)), &indexed).is_some());
+        assert!(likely_duplicate(&metadata("Episode 12: Rust Async", None), &indexed).is_some());
+        assert!(likely_duplicate(&metadata("Episode 12: Rust Async", Some(1800)), &indexed).is_none());
+        assert!(likely_duplicate(&metadata("Episode 13: Rust Async", Some(3600)), &indexed).is_none());
+    }
+}
@@ -0,0 +1,62 @@
+//! Emailing digests through the local mail transfer agent.
+//!
+//! The message is piped to a sendmail-compatible command (`sendmail`,
+//! `msmtp`, ...), which is expected to read the recipients from the headers
+//! (`sendmail -t`). Lytt doesn't talk SMTP itself.
+
+use super::Digest;
+use crate::config::DigestSettings;
+use crate::error::{LyttError, Result};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+/// Email a digest, as plain-text Markdown, to `[digest] email_to`.
+pub async fn send_email(digest: &Digest, settings: &DigestSettings) -> Result<()> {
+    if settings.email_to.is_empty() {
+        return Err(LyttError::Config("No [digest] email_to recipients configured".to_string()));
+    }
+
+    let message = format_message(digest, settings);
+    let command = &settings.sendmail_command;
+    debug!("Sending digest with {} {:?}", command, settings.sendmail_args);
+
+    let mut child = match Command::new(command)
+        .args(&settings.sendmail_args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(LyttError::ToolNotFound(command.to_string()));
+        }
+        Err(e) => return Err(LyttError::ToolFailed(format!("{}: {}", command, e))),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(message.as_bytes()).await?;
+    }
+
+    let result = child.wait_with_output().await?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(LyttError::ToolFailed(format!("{} exited with {}: {}", command, result.status, stderr.trim())));
+    }
+
+    info!("Emailed digest to {}", settings.email_to.join(", "));
+    Ok(())
+}
+
+/// Build the message: headers, a blank line, then the Markdown body. Local
+/// mail commands take plain newlines and convert them for SMTP.
+fn format_message(digest: &Digest, settings: &DigestSettings) -> String {
+    format!(
+        "From: {}\nTo: {}\nSubject: {}\nMIME-Version: 1.0\nContent-Type: text/plain; charset=utf-8\nContent-Transfer-Encoding: 8bit\n\n{}",
+        settings.email_from,
+        settings.email_to.join(", "),
+        digest.title(),
+        digest.to_markdown()
+    )
+}
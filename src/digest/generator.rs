@@ -0,0 +1,313 @@
+//! Digest generation.
+
+use super::{Digest, DigestEntry, DigestTheme};
+use crate::config::Prompts;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{DocType, Document, IndexedVideo, SqliteVectorStore, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Default budget for source material sent to the LLM, in characters.
+const DEFAULT_MAX_INPUT_CHARS: usize = 60_000;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct DigestResponse {
+    #[serde(default)]
+    media: Vec<RawEntry>,
+    #[serde(default)]
+    themes: Vec<RawTheme>,
+}
+
+#[derive(Deserialize)]
+struct RawEntry {
+    source: usize,
+    #[serde(default)]
+    key_points: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawTheme {
+    theme: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    sources: Vec<usize>,
+}
+
+/// Generates digests of the media indexed in a period.
+pub struct DigestGenerator {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<SqliteVectorStore>,
+    prompts: Prompts,
+    max_input_chars: usize,
+}
+
+impl DigestGenerator {
+    /// Create a new digest generator.
+    pub fn new(vector_store: Arc<SqliteVectorStore>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            prompts: Prompts::default(),
+            max_input_chars: DEFAULT_MAX_INPUT_CHARS,
+        }
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Set the character budget for source material.
+    pub fn with_max_input_chars(mut self, max_input_chars: usize) -> Self {
+        self.max_input_chars = max_input_chars;
+        self
+    }
+
+    /// Generate a digest of the media indexed since `since`.
+    ///
+    /// Media with a stored summary is described to the model by it, which is
+    /// shorter than the transcript; other media by its chunks. Rollups and
+    /// other derived documents are left out.
+    #[instrument(skip(self))]
+    pub async fn generate(&self, since: DateTime<Utc>) -> Result<Digest> {
+        let now = Utc::now();
+
+        let mut videos: Vec<IndexedVideo> = self
+            .vector_store
+            .list_videos()
+            .await?
+            .into_iter()
+            .filter(|v| v.indexed_at >= since)
+            .collect();
+        videos.sort_by_key(|v| v.indexed_at);
+
+        let mut media: Vec<(IndexedVideo, String)> = Vec::new();
+        for video in videos {
+            let docs = self.vector_store.get_by_video_id(&video.video_id).await?;
+            if docs.first().is_none_or(|d| d.doc_type == DocType::Derived) {
+                continue;
+            }
+            let material = match self.vector_store.get_summary(&video.video_id)? {
+                Some(summary) => summary.overview,
+                None => format_chunks(&docs),
+            };
+            media.push((video, material));
+        }
+
+        if media.is_empty() {
+            return Err(LyttError::InvalidInput(format!(
+                "No media indexed since {}",
+                since.format("%Y-%m-%d %H:%M")
+            )));
+        }
+
+        info!("Generating digest of {} media", media.len());
+
+        let mut vars = HashMap::new();
+        vars.insert("since".to_string(), since.format("%Y-%m-%d").to_string());
+        vars.insert("until".to_string(), now.format("%Y-%m-%d").to_string());
+        vars.insert("media_count".to_string(), media.len().to_string());
+        vars.insert("sources".to_string(), format_sources(&media, self.max_input_chars));
+
+        let content = self.complete(&vars).await?;
+        let (entries, themes) = parse_digest(&content, media.into_iter().map(|(video, _)| video).collect())?;
+
+        Ok(Digest {
+            since,
+            until: now,
+            media: entries,
+            themes,
+            created_at: now,
+        })
+    }
+
+    /// Call the LLM with the digest prompts.
+    async fn complete(&self, vars: &HashMap<String, String>) -> Result<String> {
+        let system = self.prompts.render_with_custom(&self.prompts.digest.system, vars);
+        let user = self.prompts.render_with_custom(&self.prompts.digest.user, vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Digest, &self.model, response.usage.as_ref());
+
+        response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))
+    }
+}
+
+/// Join a media item's chunks, with their section titles.
+fn format_chunks(docs: &[Document]) -> String {
+    docs.iter()
+        .map(|doc| match &doc.section_title {
+            Some(title) => format!("[{}] {}: {}", doc.format_timestamp(), title, doc.content),
+            None => format!("[{}] {}", doc.format_timestamp(), doc.content),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Number media `[1]`, `[2]`, ... for the prompt, sharing the character
+/// budget evenly across them.
+fn format_sources(media: &[(IndexedVideo, String)], max_chars: usize) -> String {
+    let per_media = max_chars / media.len().max(1);
+    let mut output = String::new();
+
+    for (i, (video, material)) in media.iter().enumerate() {
+        let material: String = material.chars().take(per_media).collect();
+        output.push_str(&format!(
+            "[{}] {} ({})\n{}\n\n",
+            i + 1,
+            video.video_title,
+            video.indexed_at.format("%Y-%m-%d"),
+            material
+        ));
+    }
+
+    output.trim_end().to_string()
+}
+
+/// Parse the model's JSON, resolving 1-based source numbers to media. Every
+/// media item gets an entry, with no key points if the model skipped it;
+/// themes without a valid source are dropped.
+fn parse_digest(content: &str, videos: Vec<IndexedVideo>) -> Result<(Vec<DigestEntry>, Vec<DigestTheme>)> {
+    let parsed: DigestResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid digest JSON: {}", e)))?;
+
+    let mut key_points: HashMap<usize, Vec<String>> = HashMap::new();
+    for raw in parsed.media {
+        let points = raw
+            .key_points
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty());
+        key_points.entry(raw.source).or_default().extend(points);
+    }
+
+    let themes = parsed
+        .themes
+        .into_iter()
+        .filter_map(|raw| {
+            let mut sources = raw.sources;
+            sources.sort_unstable();
+            sources.dedup();
+            let media: Vec<String> = sources
+                .into_iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| videos.get(i)))
+                .map(|v| v.video_title.clone())
+                .collect();
+            let title = raw.theme.trim().to_string();
+            (!title.is_empty() && !media.is_empty()).then(|| DigestTheme {
+                title,
+                summary: raw.summary.trim().to_string(),
+                media,
+            })
+        })
+        .collect();
+
+    let entries = videos
+        .into_iter()
+        .enumerate()
+        .map(|(i, video)| DigestEntry {
+            key_points: key_points.remove(&(i + 1)).unwrap_or_default(),
+            video_id: video.video_id,
+            video_title: video.video_title,
+            duration_seconds: video.total_duration_seconds,
+            indexed_at: video.indexed_at,
+        })
+        .collect();
+
+    Ok((entries, themes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_parse_digest() {
+        let now = Utc::now();
+        let video = |id: &str, days_ago: i64| IndexedVideo {
+            video_id: id.to_string(),
+            video_title: format!("Episode {}", id),
+            chunk_count: 4,
+            total_duration_seconds: 3725.0,
+            indexed_at: now - Duration::days(days_ago),
+            language: None,
+        };
+        let videos = vec![video("a", 5), video("b", 2)];
+
+        // The second media item is skipped; one theme cites nothing valid
+        let (entries, themes) = parse_digest(
+            r#"{"media": [{"source": 1, "key_points": ["Rates are rising.", " "]}],
+                "themes": [
+                    {"theme": "Interest rates", "summary": "Both discuss rates.", "sources": [2, 1, 2]},
+                    {"theme": "Uncited", "sources": [7]}
+                ]}"#,
+            videos,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key_points, vec!["Rates are rising."]);
+        assert!(entries[1].key_points.is_empty());
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].media, vec!["Episode a", "Episode b"]);
+
+        let digest = Digest {
+            since: now - Duration::days(7),
+            until: now,
+            media: entries,
+            themes,
+            created_at: now,
+        };
+        let markdown = digest.to_markdown();
+        assert!(markdown.contains("### Interest rates\n\nBoth discuss rates.\n\n*In: Episode a, Episode b*\n"));
+        assert!(markdown.contains("*a · 1h 02m · indexed"));
+        assert!(markdown.contains("- Rates are rising.\n"));
+        assert!(markdown.contains("No key points.\n"));
+        assert!(parse_digest("not json", Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Periodic digests of newly indexed media.
+//!
+//! A digest is a single briefing covering everything indexed in a period:
+//! key points for each media item plus the themes that cut across them. It
+//! can be printed, written as Markdown, or emailed through the local
+//! `sendmail`, and `lytt serve` can generate one on a daily or weekly schedule.
+
+mod email;
+mod generator;
+mod schedule;
+
+pub use email::send_email;
+pub use generator::DigestGenerator;
+pub use schedule::{spawn_schedule, write_digest, DigestSchedule};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// One media item in a digest.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestEntry {
+    /// Media ID.
+    pub video_id: String,
+    /// Media title.
+    pub video_title: String,
+    /// Length of the media in seconds.
+    pub duration_seconds: f64,
+    /// When the media was indexed.
+    pub indexed_at: DateTime<Utc>,
+    /// The main points it makes.
+    pub key_points: Vec<String>,
+}
+
+/// A theme shared by several media items in a digest.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestTheme {
+    /// The theme, in a few words.
+    pub title: String,
+    /// How the media treats it, in a few sentences.
+    pub summary: String,
+    /// Titles of the media items it comes up in.
+    pub media: Vec<String>,
+}
+
+/// A briefing on the media indexed in a period.
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    /// Start of the period.
+    pub since: DateTime<Utc>,
+    /// End of the period.
+    pub until: DateTime<Utc>,
+    /// Media indexed in the period, oldest first.
+    pub media: Vec<DigestEntry>,
+    /// Themes across the media.
+    pub themes: Vec<DigestTheme>,
+    /// When the digest was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Digest {
+    /// Title line, e.g. "Digest: 2025-01-01 to 2025-01-07".
+    pub fn title(&self) -> String {
+        format!("Digest: {} to {}", self.since.format("%Y-%m-%d"), self.until.format("%Y-%m-%d"))
+    }
+
+    /// File name for the digest's Markdown, dated by the end of the period.
+    pub fn file_name(&self) -> String {
+        format!("digest-{}.md", self.until.format("%Y-%m-%d"))
+    }
+
+    /// Render the digest as Markdown: themes first, then each media item's
+    /// key points.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# {}\n\n", self.title());
+        let noun = if self.media.len() == 1 { "item" } else { "items" };
+        output.push_str(&format!(
+            "*{} media {}, generated {}*\n",
+            self.media.len(),
+            noun,
+            self.created_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+
+        if !self.themes.is_empty() {
+            output.push_str("\n## Themes\n");
+            for theme in &self.themes {
+                output.push_str(&format!("\n### {}\n\n", theme.title));
+                if !theme.summary.is_empty() {
+                    output.push_str(&format!("{}\n", theme.summary));
+                }
+                if !theme.media.is_empty() {
+                    output.push_str(&format!("\n*In: {}*\n", theme.media.join(", ")));
+                }
+            }
+        }
+
+        output.push_str("\n## Media\n");
+        for entry in &self.media {
+            output.push_str(&format!(
+                "\n### {}\n\n*{} · {} · indexed {}*\n\n",
+                entry.video_title,
+                entry.video_id,
+                format_duration(entry.duration_seconds),
+                entry.indexed_at.format("%Y-%m-%d")
+            ));
+            if entry.key_points.is_empty() {
+                output.push_str("No key points.\n");
+            }
+            for point in &entry.key_points {
+                output.push_str(&format!("- {}\n", point));
+            }
+        }
+
+        output
+    }
+}
+
+/// Format seconds as "1h 02m" or "12m 34s".
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds >= 3600 {
+        format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,123 @@
+//! Scheduled digests for long-running processes.
+
+use super::{send_email, Digest, DigestGenerator};
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::logging;
+use crate::orchestrator::Orchestrator;
+use crate::usage::UsageTracker;
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How often a scheduled digest is generated. Each one covers the period
+/// since the last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestSchedule {
+    Daily,
+    Weekly,
+}
+
+impl DigestSchedule {
+    /// Time between digests, which is also the period each covers.
+    pub fn period(&self) -> Duration {
+        match self {
+            DigestSchedule::Daily => Duration::from_secs(24 * 3600),
+            DigestSchedule::Weekly => Duration::from_secs(7 * 24 * 3600),
+        }
+    }
+
+    /// The equivalent cron schedule, for `lytt daemon`.
+    pub fn cron(&self) -> &'static str {
+        match self {
+            DigestSchedule::Daily => "@daily",
+            DigestSchedule::Weekly => "@weekly",
+        }
+    }
+}
+
+impl std::str::FromStr for DigestSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" | "day" => Ok(DigestSchedule::Daily),
+            "weekly" | "week" => Ok(DigestSchedule::Weekly),
+            _ => Err(format!("Unknown digest schedule: {}. Use daily or weekly.", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for DigestSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigestSchedule::Daily => write!(f, "daily"),
+            DigestSchedule::Weekly => write!(f, "weekly"),
+        }
+    }
+}
+
+/// Generate a digest every `[digest] schedule` period in the background,
+/// writing it to the digests directory and emailing it if recipients are
+/// configured. The first one is due one period after starting. Returns None
+/// when no schedule is configured.
+pub fn spawn_schedule(orchestrator: Arc<Orchestrator>) -> Result<Option<tokio::task::JoinHandle<()>>> {
+    let configured = orchestrator.settings().digest.schedule.trim();
+    if configured.is_empty() {
+        return Ok(None);
+    }
+    let schedule: DigestSchedule = configured.parse().map_err(LyttError::Config)?;
+    let period = schedule.period();
+
+    Ok(Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            ticker.tick().await;
+            let since = Utc::now() - chrono::Duration::from_std(period).unwrap_or_default();
+            let run_id = logging::new_run_id();
+            let span = tracing::info_span!(parent: None, "job", run_id = %run_id, job = "digest");
+            logging::scope(run_id, span, async {
+                match write_digest(&orchestrator, since).await {
+                    Ok(Some(path)) => info!("Wrote {} digest to {}", schedule, path.display()),
+                    Ok(None) => info!("Nothing indexed for the {} digest", schedule),
+                    Err(e) => warn!("Scheduled {} digest failed: {}", schedule, e),
+                }
+            })
+            .await;
+        }
+    })))
+}
+
+/// Generate a digest of the media indexed since `since`, write it to the
+/// digests directory and email it if recipients are configured. Returns the
+/// file it was written to, or None when nothing was indexed in the period.
+pub async fn write_digest(orchestrator: &Orchestrator, since: DateTime<Utc>) -> Result<Option<PathBuf>> {
+    let settings = orchestrator.settings();
+
+    let generator = DigestGenerator::new(orchestrator.sqlite_store(), &settings.digest.model)
+        .with_prompts(orchestrator.prompts().clone())
+        .with_client(ChatClient::from_settings(&settings.digest.llm))
+        .with_max_input_chars(settings.digest.max_input_chars);
+
+    let tracker = UsageTracker::new();
+    let result = tracker.scope(generator.generate(since)).await;
+    orchestrator.record_usage("digest", None, &tracker).await;
+
+    let digest: Digest = match result {
+        Ok(digest) => digest,
+        Err(LyttError::InvalidInput(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let dir = settings.digests_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(digest.file_name());
+    std::fs::write(&path, digest.to_markdown())?;
+
+    if !settings.digest.email_to.is_empty() {
+        send_email(&digest, &settings.digest).await?;
+    }
+    Ok(Some(path))
+}
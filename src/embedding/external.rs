@@ -0,0 +1,80 @@
+//! Embeddings via an external plugin command.
+//!
+//! Request (one line on stdin): `{"type": "embed", "texts": ["...", "..."]}`
+//!
+//! Response (one line on stdout): `{"embeddings": [[0.1, ...], [0.3, ...]]}`
+
+use super::Embedder;
+use crate::error::{LyttError, Result};
+use crate::plugin::PluginProcess;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, instrument};
+
+/// Embedder backed by an external command.
+pub struct ExternalEmbedder {
+    plugin: PluginProcess,
+    dimensions: usize,
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl ExternalEmbedder {
+    /// Create an embedder that runs the given command and returns vectors of `dimensions`.
+    pub fn new(command: &str, args: Vec<String>, dimensions: usize) -> Self {
+        Self {
+            plugin: PluginProcess::new(command, args),
+            dimensions,
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for ExternalEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| LyttError::Embedding("Empty embedding response".to_string()))
+    }
+
+    #[instrument(skip(self, texts), fields(count = texts.len()))]
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        debug!("Requesting {} embeddings from {}", texts.len(), self.plugin.command());
+
+        let request = json!({"type": "embed", "texts": texts});
+        let response: EmbedResponse = serde_json::from_value(self.plugin.request(&request).await?)
+            .map_err(|e| LyttError::Embedding(format!("Invalid embed response: {}", e)))?;
+
+        if response.embeddings.len() != texts.len() {
+            return Err(LyttError::Embedding(format!(
+                "Plugin returned {} embeddings for {} texts",
+                response.embeddings.len(),
+                texts.len()
+            )));
+        }
+
+        if let Some(bad) = response.embeddings.iter().find(|e| e.len() != self.dimensions) {
+            return Err(LyttError::Embedding(format!(
+                "Plugin returned {}-dimensional embeddings, expected {} (embedding.dimensions)",
+                bad.len(),
+                self.dimensions
+            )));
+        }
+
+        Ok(response.embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
@@ -1,11 +1,15 @@
 //! Embedding generation for semantic search and retrieval.
 
+mod external;
 mod openai;
 
-pub use openai::OpenAIEmbedder;
+pub use external::ExternalEmbedder;
+pub use openai::{supports_dimensions, OpenAIEmbedder};
 
-use crate::error::Result;
+use crate::config::EmbeddingSettings;
+use crate::error::{LyttError, Result};
 use async_trait::async_trait;
+use std::sync::Arc;
 
 /// Trait for embedding generation.
 #[async_trait]
@@ -19,3 +23,56 @@ pub trait Embedder: Send + Sync {
     /// Get the embedding dimensions.
     fn dimensions(&self) -> usize;
 }
+
+/// Shorten an embedding to its first `dimensions` components and rescale it
+/// to unit length.
+///
+/// text-embedding-3 models are trained so that a prefix of the vector is
+/// itself a usable embedding; this gives the same result as requesting fewer
+/// dimensions from the API, without re-embedding.
+pub fn shorten(embedding: &[f32], dimensions: usize) -> Vec<f32> {
+    let mut shortened: Vec<f32> = embedding.iter().take(dimensions).copied().collect();
+    let norm = shortened.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        shortened.iter_mut().for_each(|v| *v /= norm);
+    }
+    shortened
+}
+
+/// Identify the configured embedding model, for checking that query and
+/// stored embeddings come from the same model.
+pub fn model_id(settings: &EmbeddingSettings) -> String {
+    match settings.provider.as_str() {
+        "external" => format!("external:{}", settings.command.as_deref().unwrap_or_default()),
+        _ => settings.model.clone(),
+    }
+}
+
+/// Create the embedder configured in settings (`openai` or `external`).
+pub fn create_embedder(settings: &EmbeddingSettings) -> Result<Arc<dyn Embedder>> {
+    let dimensions = settings.dimensions as usize;
+
+    match settings.provider.as_str() {
+        "external" => {
+            let command = settings.command.as_deref().ok_or_else(|| {
+                LyttError::Config("embedding.provider = \"external\" requires embedding.command".to_string())
+            })?;
+            Ok(Arc::new(ExternalEmbedder::new(command, settings.args.clone(), dimensions)))
+        }
+        _ => Ok(Arc::new(OpenAIEmbedder::with_config(&settings.model, dimensions))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shorten() {
+        let shortened = shorten(&[0.6, 0.8, 0.5, 0.5], 2);
+        assert_eq!(shortened, vec![0.6, 0.8]);
+
+        let shortened = shorten(&[3.0, 4.0, 1.0], 2);
+        assert!((shortened[0] - 0.6).abs() < 1e-6 && (shortened[1] - 0.8).abs() < 1e-6);
+    }
+}
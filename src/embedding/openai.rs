@@ -4,6 +4,7 @@ use super::Embedder;
 use crate::error::{Result, LyttError};
 use async_openai::types::{CreateEmbeddingRequestArgs, EmbeddingInput};
 use crate::openai::create_client;
+use crate::usage;
 use async_trait::async_trait;
 use tracing::{debug, instrument};
 
@@ -30,6 +31,12 @@ impl OpenAIEmbedder {
     }
 }
 
+/// Whether a model can return shortened embeddings (the `dimensions`
+/// parameter). Older models reject it and always return their full size.
+pub fn supports_dimensions(model: &str) -> bool {
+    !model.starts_with("text-embedding-ada")
+}
+
 impl Default for OpenAIEmbedder {
     fn default() -> Self {
         Self::new()
@@ -62,10 +69,12 @@ impl Embedder for OpenAIEmbedder {
         for chunk in texts.chunks(BATCH_SIZE) {
             let input: Vec<String> = chunk.to_vec();
 
-            let request = CreateEmbeddingRequestArgs::default()
-                .model(&self.model)
-                .input(EmbeddingInput::StringArray(input))
-                .dimensions(self.dimensions as u32)
+            let mut request = CreateEmbeddingRequestArgs::default();
+            request.model(&self.model).input(EmbeddingInput::StringArray(input));
+            if supports_dimensions(&self.model) {
+                request.dimensions(self.dimensions as u32);
+            }
+            let request = request
                 .build()
                 .map_err(|e| LyttError::Embedding(format!("Failed to build request: {}", e)))?;
 
@@ -73,6 +82,8 @@ impl Embedder for OpenAIEmbedder {
                 LyttError::OpenAI(format!("Embedding API error: {}", e))
             })?;
 
+            usage::record_embedding(&self.model, response.usage.prompt_tokens);
+
             // Sort by index to ensure correct order
             let mut embeddings: Vec<_> = response.data.into_iter().collect();
             embeddings.sort_by_key(|e| e.index);
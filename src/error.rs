@@ -44,6 +44,9 @@ pub enum LyttError {
     #[error("OpenAI API error: {0}")]
     OpenAI(String),
 
+    #[error("LLM API error: {0}")]
+    Llm(String),
+
     #[error("Media not found: {0}")]
     VideoNotFound(String),
 
@@ -58,6 +61,18 @@ pub enum LyttError {
 
     #[error("Agent error: {0}")]
     Agent(String),
+
+    #[error("Embedding mismatch: {0}")]
+    EmbeddingMismatch(String),
+
+    #[error("Export failed: {0}")]
+    Export(String),
+
+    #[error("Not enough disk space: {0}")]
+    DiskSpace(String),
+
+    #[error("Cancelled")]
+    Cancelled,
 }
 
 /// Result type alias for Lytt operations.
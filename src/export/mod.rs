@@ -0,0 +1,146 @@
+//! Transcript export to Word and PDF documents.
+//!
+//! A media item's chunks are laid out as a document with a title page, a
+//! heading wherever the section title changes and one timestamped paragraph
+//! per chunk, for readers who won't open JSON or subtitle files.
+
+mod docx;
+mod pdf;
+
+use crate::audio_source::MediaMetadata;
+use crate::error::Result;
+use crate::vector_store::Document;
+
+/// A timestamped paragraph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paragraph {
+    /// Where the paragraph starts in the media, in seconds.
+    pub start_seconds: f64,
+    /// Paragraph text.
+    pub text: String,
+}
+
+/// A run of paragraphs under one heading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// Heading, if the chunks have section titles.
+    pub heading: Option<String>,
+    /// Paragraphs in order.
+    pub paragraphs: Vec<Paragraph>,
+}
+
+/// A transcript ready to be written as a document.
+#[derive(Debug, Clone)]
+pub struct TranscriptDocument {
+    /// Title on the title page and in the file's properties.
+    pub title: String,
+    /// Label and value lines under the title (source, duration, ...).
+    pub details: Vec<(String, String)>,
+    /// Body sections.
+    pub sections: Vec<Section>,
+}
+
+impl TranscriptDocument {
+    /// Lay out chunks in time order, starting a section whenever the
+    /// section title changes.
+    pub fn from_chunks(title: &str, chunks: &[Document]) -> Self {
+        let mut chunks: Vec<&Document> = chunks.iter().collect();
+        chunks.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+
+        let mut sections: Vec<Section> = Vec::new();
+        for chunk in chunks {
+            let heading = chunk.section_title.clone().filter(|t| !t.trim().is_empty());
+            let paragraph = Paragraph {
+                start_seconds: chunk.start_seconds,
+                text: chunk.content.trim().to_string(),
+            };
+            match sections.last_mut() {
+                Some(section) if section.heading == heading => section.paragraphs.push(paragraph),
+                _ => sections.push(Section { heading, paragraphs: vec![paragraph] }),
+            }
+        }
+
+        Self {
+            title: title.to_string(),
+            details: Vec::new(),
+            sections,
+        }
+    }
+
+    /// Add a line to the title page.
+    pub fn with_detail(mut self, label: &str, value: impl Into<String>) -> Self {
+        self.details.push((label.to_string(), value.into()));
+        self
+    }
+
+    /// Write the document as a Word (.docx) file.
+    pub fn to_docx(&self) -> Result<Vec<u8>> {
+        docx::write(self)
+    }
+
+    /// Write the document as a PDF. The built-in Helvetica font covers
+    /// Western European text only; documents with other characters fail
+    /// with an error naming them.
+    pub fn to_pdf(&self) -> Result<Vec<u8>> {
+        pdf::write(self)
+    }
+}
+
+/// `[MM:SS]` or `[HH:MM:SS]` before a paragraph.
+fn timestamp_label(seconds: f64) -> String {
+    format!("[{}]", MediaMetadata::format_timestamp(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(section: Option<&str>, start: f64, text: &str) -> Document {
+        Document::new(
+            "vid".to_string(),
+            "Talk".to_string(),
+            section.map(String::from),
+            text.to_string(),
+            start,
+            start + 30.0,
+            vec![0.0; 3],
+            0,
+            None,
+        )
+    }
+
+    fn sample() -> TranscriptDocument {
+        let chunks = vec![
+            chunk(Some("Results"), 60.0, "Accuracy went up & cost went down."),
+            chunk(Some("Intro"), 0.0, "Welcome to the <talk>."),
+            chunk(Some("Intro"), 30.0, "Today: two things."),
+        ];
+        TranscriptDocument::from_chunks("A \"Talk\"", &chunks).with_detail("Duration", "1m 30s")
+    }
+
+    #[test]
+    fn test_sections() {
+        let document = sample();
+        let headings: Vec<_> = document.sections.iter().map(|s| s.heading.as_deref()).collect();
+        assert_eq!(headings, [Some("Intro"), Some("Results")]);
+        assert_eq!(document.sections[0].paragraphs[1].text, "Today: two things.");
+        assert_eq!(timestamp_label(3723.0), "[01:02:03]");
+    }
+
+    #[test]
+    fn test_docx_and_pdf() {
+        let document = sample();
+
+        let docx = document.to_docx().unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(docx)).unwrap();
+        let mut body = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("word/document.xml").unwrap(), &mut body).unwrap();
+        assert!(body.contains("A &quot;Talk&quot;"));
+        assert!(body.contains("Welcome to the &lt;talk&gt;."));
+        assert!(body.contains(r#"<w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t xml:space="preserve">Results</w:t>"#));
+        assert!(archive.by_name("word/styles.xml").is_ok());
+
+        let pdf = document.to_pdf().unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
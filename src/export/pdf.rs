@@ -0,0 +1,257 @@
+//! PDF writer.
+//!
+//! Pages are A4 with the built-in Helvetica fonts, so nothing has to be
+//! embedded. Those only cover the WinAnsi (Windows-1252) character set, so
+//! documents with other characters are refused rather than written garbled.
+//! Lines are wrapped on an average character width, which leaves some slack
+//! at the right margin.
+
+use super::{timestamp_label, TranscriptDocument};
+use crate::error::{LyttError, Result};
+use printpdf::{BuiltinFont, Color, Greyscale, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+
+/// Average Helvetica character width as a fraction of the font size.
+const CHAR_WIDTH: f32 = 0.52;
+
+/// Millimetres per point.
+const PT: f32 = 25.4 / 72.0;
+
+const TITLE_SIZE: f32 = 24.0;
+const HEADING_SIZE: f32 = 14.0;
+const BODY_SIZE: f32 = 11.0;
+const SMALL_SIZE: f32 = 9.0;
+
+/// Characters WinAnsi encodes at 0x80-0x9F, beyond Latin-1.
+const WIN_ANSI_EXTRA: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+/// Unencodable characters listed in the error before the rest are counted.
+const MAX_LISTED_CHARS: usize = 10;
+
+/// Write `document` as a PDF.
+pub(super) fn write(document: &TranscriptDocument) -> Result<Vec<u8>> {
+    check_encodable(document)?;
+    let mut pdf = Writer::new(&document.title)?;
+
+    // Title page
+    pdf.y = PAGE_HEIGHT - 90.0;
+    for line in wrap(&document.title, TITLE_SIZE) {
+        pdf.line(&line, TITLE_SIZE, true, 0.0);
+    }
+    pdf.space(8.0);
+    for (label, value) in &document.details {
+        for line in wrap(&format!("{}: {}", label, value), BODY_SIZE) {
+            pdf.line(&line, BODY_SIZE, false, 0.3);
+        }
+    }
+
+    pdf.new_page();
+    for section in &document.sections {
+        if let Some(heading) = &section.heading {
+            // Keep a heading with the start of its first paragraph
+            pdf.ensure_room(line_height(HEADING_SIZE) + 3.0 * line_height(BODY_SIZE));
+            pdf.space(4.0);
+            for line in wrap(heading, HEADING_SIZE) {
+                pdf.line(&line, HEADING_SIZE, true, 0.0);
+            }
+            pdf.space(2.0);
+        }
+        for paragraph in &section.paragraphs {
+            pdf.ensure_room(line_height(SMALL_SIZE) + line_height(BODY_SIZE));
+            pdf.line(&timestamp_label(paragraph.start_seconds), SMALL_SIZE, true, 0.4);
+            for line in wrap(&paragraph.text, BODY_SIZE) {
+                pdf.line(&line, BODY_SIZE, false, 0.0);
+            }
+            pdf.space(3.0);
+        }
+    }
+
+    pdf.finish()
+}
+
+/// Writes lines top to bottom, starting new pages as they fill up.
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    /// Baseline of the next line, in millimetres from the bottom.
+    y: f32,
+    pages: usize,
+}
+
+impl Writer {
+    fn new(title: &str) -> Result<Self> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(export_error)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(export_error)?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+            pages: 1,
+        })
+    }
+
+    /// Write one line; `grey` is 0 for black up to 1 for white.
+    fn line(&mut self, text: &str, size: f32, bold: bool, grey: f32) {
+        let height = line_height(size);
+        self.ensure_room(height);
+        self.y -= height;
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.set_fill_color(Color::Greyscale(Greyscale::new(grey, None)));
+        self.layer.use_text(text, size, Mm(MARGIN), Mm(self.y), font);
+    }
+
+    fn space(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+
+    /// Start a new page unless `height` still fits on this one.
+    fn ensure_room(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.pages += 1;
+        self.y = PAGE_HEIGHT - MARGIN;
+        // Number every page after the title page
+        self.layer.set_fill_color(Color::Greyscale(Greyscale::new(0.4, None)));
+        let number = format!("{}", self.pages - 1);
+        self.layer.use_text(number, SMALL_SIZE, Mm(PAGE_WIDTH / 2.0), Mm(MARGIN / 2.0), &self.regular);
+    }
+
+    fn finish(self) -> Result<Vec<u8>> {
+        self.doc.save_to_bytes().map_err(export_error)
+    }
+}
+
+/// Fail with the characters of `document` the built-in fonts can't show.
+fn check_encodable(document: &TranscriptDocument) -> Result<()> {
+    let texts = std::iter::once(document.title.as_str())
+        .chain(document.details.iter().flat_map(|(label, value)| [label.as_str(), value.as_str()]))
+        .chain(document.sections.iter().flat_map(|section| {
+            section
+                .heading
+                .as_deref()
+                .into_iter()
+                .chain(section.paragraphs.iter().map(|p| p.text.as_str()))
+        }));
+
+    let mut missing: Vec<char> = Vec::new();
+    for c in texts.flat_map(str::chars) {
+        if !is_win_ansi(c) && !missing.contains(&c) {
+            missing.push(c);
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut listed: Vec<String> = missing
+        .iter()
+        .take(MAX_LISTED_CHARS)
+        .map(|c| format!("'{}' (U+{:04X})", c, *c as u32))
+        .collect();
+    if missing.len() > MAX_LISTED_CHARS {
+        listed.push(format!("{} more", missing.len() - MAX_LISTED_CHARS));
+    }
+    Err(LyttError::Export(format!(
+        "the PDF font can't show {}; export as DOCX instead",
+        listed.join(", ")
+    )))
+}
+
+/// Whether the built-in fonts' WinAnsi encoding has a glyph for `c`.
+/// Line breaks and tabs are fine, as wrapping turns them into spaces.
+fn is_win_ansi(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\t' | ' '..='~' | '\u{a0}'..='\u{ff}') || WIN_ANSI_EXTRA.contains(c)
+}
+
+fn export_error(e: printpdf::Error) -> LyttError {
+    LyttError::Export(format!("couldn't write the PDF: {}", e))
+}
+
+fn line_height(size: f32) -> f32 {
+    size * 1.4 * PT
+}
+
+/// Break `text` into lines that fit between the margins at `size`. Words
+/// longer than a line are split.
+fn wrap(text: &str, size: f32) -> Vec<String> {
+    let max_chars = (((PAGE_WIDTH - 2.0 * MARGIN) / PT) / (size * CHAR_WIDTH)).floor().max(1.0) as usize;
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..max_chars).collect());
+            }
+            if word.is_empty() {
+                continue;
+            }
+            let word: String = word.into_iter().collect();
+            let needed = line.chars().count() + usize::from(!line.is_empty()) + word.chars().count();
+            if needed > max_chars && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        let text = "word ".repeat(100);
+        let lines = wrap(&text, BODY_SIZE);
+        assert!(lines.len() > 4);
+        assert!(lines.iter().all(|l| l.chars().count() <= 84 && !l.ends_with(' ')));
+        assert_eq!(lines.join(" "), text.trim());
+
+        let long = wrap(&"x".repeat(200), BODY_SIZE);
+        assert_eq!(long.iter().map(|l| l.len()).sum::<usize>(), 200);
+    }
+
+    #[test]
+    fn test_unencodable_characters() {
+        let western = TranscriptDocument {
+            title: "Café – “naïve” résumé €5".to_string(),
+            details: Vec::new(),
+            sections: Vec::new(),
+        };
+        assert!(write(&western).is_ok());
+
+        let cyrillic = TranscriptDocument {
+            title: "Привет".to_string(),
+            ..western
+        };
+        let err = write(&cyrillic).unwrap_err().to_string();
+        assert!(err.contains("'П' (U+041F)"), "{}", err);
+        assert!(err.contains("DOCX"));
+    }
+}
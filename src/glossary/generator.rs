@@ -0,0 +1,173 @@
+//! Glossary extraction.
+
+use super::GlossaryTerm;
+use crate::agent::{ToolCall, ToolContext};
+use crate::config::Prompts;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{info, instrument};
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct TermsResponse {
+    terms: Vec<RawTerm>,
+}
+
+#[derive(Deserialize)]
+struct RawTerm {
+    term: String,
+    definition: String,
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+/// Extracts glossary terms from a media item's transcript.
+pub struct GlossaryGenerator {
+    client: ChatClient,
+    model: String,
+    tools: ToolContext,
+    prompts: Prompts,
+}
+
+impl GlossaryGenerator {
+    /// Create a new glossary generator.
+    pub fn new(tools: ToolContext, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            tools,
+            prompts: Prompts::default(),
+        }
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Extract the terms defined in a media item.
+    #[instrument(skip(self))]
+    pub async fn generate(&self, video_id: &str) -> Result<Vec<GlossaryTerm>> {
+        let video = self
+            .tools
+            .vector_store
+            .get_video(video_id)
+            .await?
+            .ok_or_else(|| LyttError::VideoNotFound(video_id.to_string()))?;
+
+        // Same transcript view the agent gets from its get_transcript tool
+        let transcript = self
+            .tools
+            .execute(&ToolCall::GetTranscript {
+                video_id: video_id.to_string(),
+            })
+            .await?;
+
+        info!("Extracting glossary terms for '{}'", video.video_title);
+
+        let mut vars = HashMap::new();
+        vars.insert("title".to_string(), video.video_title.clone());
+        vars.insert("transcript".to_string(), transcript);
+
+        let system = self.prompts.render_with_custom(&self.prompts.glossary.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.glossary.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Agent(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Agent(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.2)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Agent(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Agent, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Agent("Empty response from LLM".to_string()))?;
+
+        let created_at = Utc::now();
+        Ok(parse_terms(content)?
+            .into_iter()
+            .map(|raw| GlossaryTerm {
+                term: raw.term,
+                definition: raw.definition,
+                video_id: video_id.to_string(),
+                video_title: video.video_title.clone(),
+                timestamp: raw.timestamp,
+                model: self.model.clone(),
+                created_at,
+            })
+            .collect())
+    }
+}
+
+/// Parse the `{"terms": [...]}` response, trimming and dropping empty terms.
+fn parse_terms(content: &str) -> Result<Vec<RawTerm>> {
+    let parsed: TermsResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Agent(format!("Invalid glossary JSON: {}", e)))?;
+
+    Ok(parsed
+        .terms
+        .into_iter()
+        .map(|t| RawTerm {
+            term: t.term.trim().to_string(),
+            definition: t.definition.trim().to_string(),
+            timestamp: t.timestamp.filter(|ts| !ts.trim().is_empty()),
+        })
+        .filter(|t| !t.term.is_empty() && !t.definition.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_terms() {
+        let terms = parse_terms(
+            r#"{"terms": [
+                {"term": " Entropy ", "definition": "Average surprise of a source.", "timestamp": "04:12"},
+                {"term": "", "definition": "Dropped"},
+                {"term": "Bit", "definition": "A binary digit.", "timestamp": ""}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(terms.len(), 2);
+        assert_eq!(terms[0].term, "Entropy");
+        assert_eq!(terms[0].timestamp.as_deref(), Some("04:12"));
+        assert!(terms[1].timestamp.is_none());
+        assert!(parse_terms("not json").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Glossary extraction across the library.
+//!
+//! Domain terms and the definitions speakers give for them are extracted from
+//! each media item's transcript and stored with a citation. Stored terms are
+//! exported as Markdown or CSV and fed back into transcription as vocabulary,
+//! so later recordings spell them consistently.
+
+mod generator;
+
+pub use generator::GlossaryGenerator;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A term defined in a media item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    /// The term as spoken.
+    pub term: String,
+    /// One-sentence definition, as the speaker uses the term.
+    pub definition: String,
+    /// Media the definition comes from.
+    pub video_id: String,
+    /// Title of that media.
+    pub video_title: String,
+    /// `MM:SS` timestamp where the term is defined, if known.
+    pub timestamp: Option<String>,
+    /// Model that extracted the term.
+    pub model: String,
+    /// When the term was extracted.
+    pub created_at: DateTime<Utc>,
+}
+
+impl GlossaryTerm {
+    /// Citation such as `Lecture 3 @ 04:12`.
+    pub fn citation(&self) -> String {
+        match &self.timestamp {
+            Some(timestamp) => format!("{} @ {}", self.video_title, timestamp),
+            None => self.video_title.clone(),
+        }
+    }
+}
+
+/// Output format for a glossary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlossaryFormat {
+    /// Markdown definition list.
+    Markdown,
+    /// CSV with term, definition, source and timestamp columns.
+    Csv,
+}
+
+impl std::str::FromStr for GlossaryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(GlossaryFormat::Markdown),
+            "csv" => Ok(GlossaryFormat::Csv),
+            _ => Err(format!("Unknown glossary format: {}. Use markdown or csv.", s)),
+        }
+    }
+}
+
+/// Combine terms from several media: one entry per term (case-insensitive),
+/// keeping the first definition, sorted by term.
+pub fn merge_terms(terms: impl IntoIterator<Item = GlossaryTerm>) -> Vec<GlossaryTerm> {
+    let mut merged: Vec<GlossaryTerm> = Vec::new();
+    for term in terms {
+        let key = term.term.to_lowercase();
+        if !merged.iter().any(|t| t.term.to_lowercase() == key) {
+            merged.push(term);
+        }
+    }
+    merged.sort_by_key(|t| t.term.to_lowercase());
+    merged
+}
+
+/// Render merged terms in the given format.
+pub fn render(terms: &[GlossaryTerm], format: GlossaryFormat) -> String {
+    match format {
+        GlossaryFormat::Markdown => {
+            let mut output = String::from("# Glossary\n");
+            for term in terms {
+                output.push_str(&format!(
+                    "\n**{}**: {} *({})*\n",
+                    term.term,
+                    term.definition,
+                    term.citation()
+                ));
+            }
+            output
+        }
+        GlossaryFormat::Csv => {
+            let mut output = String::from("term,definition,source,video_id,timestamp\n");
+            for term in terms {
+                output.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    csv_field(&term.term),
+                    csv_field(&term.definition),
+                    csv_field(&term.video_title),
+                    csv_field(&term.video_id),
+                    term.timestamp.as_deref().unwrap_or_default()
+                ));
+            }
+            output
+        }
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(term: &str, video_title: &str, timestamp: Option<&str>) -> GlossaryTerm {
+        GlossaryTerm {
+            term: term.to_string(),
+            definition: format!("What {} means, \"roughly\"", term),
+            video_id: "abc123".to_string(),
+            video_title: video_title.to_string(),
+            timestamp: timestamp.map(String::from),
+            model: "gpt-4o-mini".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_merge_and_render() {
+        let merged = merge_terms(vec![
+            term("Entropy", "Lecture 1", Some("04:12")),
+            term("bit", "Lecture 1", None),
+            term("entropy", "Lecture 2", None),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].term, "bit");
+        assert_eq!(merged[1].citation(), "Lecture 1 @ 04:12");
+
+        let markdown = render(&merged, GlossaryFormat::Markdown);
+        assert!(markdown.contains("**Entropy**: What Entropy means, \"roughly\" *(Lecture 1 @ 04:12)*"));
+
+        let csv = render(&merged, GlossaryFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "term,definition,source,video_id,timestamp");
+        assert_eq!(lines[2], "Entropy,\"What Entropy means, \"\"roughly\"\"\",Lecture 1,abc123,04:12");
+    }
+}
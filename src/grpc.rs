@@ -0,0 +1,382 @@
+//! gRPC API (enabled with the `grpc` feature).
+//!
+//! Mirrors the REST endpoints of `lytt serve` for platforms that integrate
+//! over gRPC. Transcription progress and answers are server-streamed.
+//! The service definition lives in `proto/lytt.proto`.
+
+use crate::audio_source::parse_input;
+use crate::auth::{AccessControl, AccessDenied};
+use crate::llm::ChatClient;
+use crate::logging;
+use crate::orchestrator::Orchestrator;
+use crate::rag::{self, ConfidenceScale, ContextChunk, QueryExpander, RagEngine, RecencyRanking};
+use crate::usage::UsageTracker;
+use crate::vector_store::{DerivedFilter, SearchFilter};
+use futures::stream::{BoxStream, StreamExt};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::codegen::{http, BoxFuture, Service};
+use tonic::{Request, Response, Status};
+
+/// Generated protobuf types and service traits.
+pub mod proto {
+    #![allow(missing_docs, clippy::all)]
+    tonic::include_proto!("lytt.v1");
+}
+
+use proto::lytt_server::{Lytt, LyttServer};
+use proto::transcribe_progress::Stage;
+use proto::{
+    ask_event, AskEvent, AskRequest, Citation, Citations, ListMediaRequest, ListMediaResponse, Media,
+    QuoteSpan, SearchRequest, SearchResponse, SearchResult, Source, Sources, TranscribeProgress,
+    TranscribeRequest,
+};
+
+/// Serve the gRPC API until the server fails.
+///
+/// Requests carry the API key as `authorization: Bearer <key>` (or `x-api-key`)
+/// metadata, checked like the REST API.
+pub async fn serve(
+    addr: SocketAddr,
+    orchestrator: Arc<Orchestrator>,
+    access: Arc<AccessControl>,
+) -> crate::error::Result<()> {
+    let check_access = move |request: Request<()>| {
+        let metadata = request.metadata();
+        let value = |name| metadata.get(name).and_then(|v| v.to_str().ok());
+        match access.check(value("authorization"), value("x-api-key")) {
+            Ok(()) => Ok(request),
+            Err(denied @ AccessDenied::Unauthorized) => Err(Status::unauthenticated(denied.to_string())),
+            Err(denied) => Err(Status::resource_exhausted(denied.to_string())),
+        }
+    };
+
+    tonic::transport::Server::builder()
+        .layer(tower::layer::layer_fn(|inner| TraceRequest { inner }))
+        .add_service(LyttServer::with_interceptor(GrpcService::new(orchestrator), check_access))
+        .serve(addr)
+        .await
+        .map_err(|e| std::io::Error::other(format!("gRPC server failed: {}", e)).into())
+}
+
+/// Metadata key carrying a call's run ID, as with the REST API.
+const X_REQUEST_ID: &str = "x-request-id";
+
+/// Runs every call as its own run, like `trace_request` in `lytt serve`: a
+/// `request` span with a run ID (the caller's `x-request-id`, or a new one),
+/// which is echoed back in the response metadata.
+#[derive(Clone)]
+struct TraceRequest<S> {
+    inner: S,
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for TraceRequest<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let run_id = request
+            .headers()
+            .get(X_REQUEST_ID)
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| !id.is_empty() && id.len() <= 64)
+            .map(str::to_string)
+            .unwrap_or_else(logging::new_run_id);
+        let span = tracing::info_span!(parent: None, "request", run_id = %run_id, path = %request.uri().path());
+
+        let started = Instant::now();
+        let call = self.inner.call(request);
+        Box::pin(async move {
+            let mut result = logging::scope(run_id.clone(), span.clone(), call).await;
+            span.in_scope(|| {
+                tracing::info!(elapsed_ms = started.elapsed().as_millis() as u64, "Handled request")
+            });
+            if let (Ok(response), Ok(value)) = (&mut result, http::HeaderValue::from_str(&run_id)) {
+                response.headers_mut().insert(X_REQUEST_ID, value);
+            }
+            result
+        })
+    }
+}
+
+/// gRPC service backed by the shared orchestrator.
+pub struct GrpcService {
+    orchestrator: Arc<Orchestrator>,
+}
+
+impl GrpcService {
+    /// Create a service for an orchestrator.
+    pub fn new(orchestrator: Arc<Orchestrator>) -> Self {
+        Self { orchestrator }
+    }
+}
+
+/// Parse the `derived` field; empty means include.
+fn derived_filter(derived: &str) -> Result<DerivedFilter, Status> {
+    if derived.is_empty() {
+        return Ok(DerivedFilter::Include);
+    }
+    derived.parse().map_err(Status::invalid_argument)
+}
+
+fn internal(e: crate::error::LyttError) -> Status {
+    Status::internal(e.to_string())
+}
+
+impl From<ContextChunk> for Source {
+    fn from(chunk: ContextChunk) -> Self {
+        Self {
+            video_id: chunk.video_id,
+            video_title: chunk.video_title,
+            timestamp: chunk.timestamp,
+            score: chunk.score,
+            content: chunk.content,
+        }
+    }
+}
+
+impl From<rag::Citation> for Citation {
+    fn from(citation: rag::Citation) -> Self {
+        Self {
+            marker: citation.marker as u32,
+            document_id: citation.document_id.to_string(),
+            video_id: citation.video_id,
+            video_title: citation.video_title,
+            timestamp: citation.timestamp,
+            start_seconds: citation.start_seconds,
+            end_seconds: citation.end_seconds,
+            chapter: citation.chapter,
+            quote: citation.quote.map(|q| QuoteSpan {
+                text: q.text,
+                start: q.start as u32,
+                end: q.end as u32,
+            }),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Lytt for GrpcService {
+    type TranscribeStream = BoxStream<'static, Result<TranscribeProgress, Status>>;
+    type AskStream = BoxStream<'static, Result<AskEvent, Status>>;
+
+    async fn transcribe(
+        &self,
+        request: Request<TranscribeRequest>,
+    ) -> Result<Response<Self::TranscribeStream>, Status> {
+        if self.orchestrator.settings().vector_store.read_only {
+            return Err(Status::failed_precondition("Server is a read-only replica"));
+        }
+
+        let req = request.into_inner();
+        let media_id = parse_input(&req.input)
+            .map(|(_, id)| id)
+            .ok_or_else(|| Status::invalid_argument(format!("Could not parse input: {}", req.input)))?;
+
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let orchestrator = self.orchestrator.clone();
+
+        tokio::spawn(logging::in_current_run(async move {
+            let started = Instant::now();
+            let _ = tx.unbounded_send(Ok(TranscribeProgress {
+                stage: Stage::Started as i32,
+                media_id: media_id.clone(),
+                ..Default::default()
+            }));
+
+            let progress = match orchestrator.process_media(&req.input, req.force).await {
+                Ok(result) => TranscribeProgress {
+                    stage: if result.skipped { Stage::Skipped } else { Stage::Completed } as i32,
+                    media_id: result.media_id,
+                    title: result.title,
+                    chunks_indexed: result.chunks_indexed as u32,
+                    error: String::new(),
+                    elapsed_seconds: started.elapsed().as_secs_f64(),
+                },
+                Err(e) => TranscribeProgress {
+                    stage: Stage::Failed as i32,
+                    media_id,
+                    error: e.to_string(),
+                    elapsed_seconds: started.elapsed().as_secs_f64(),
+                    ..Default::default()
+                },
+            };
+            let _ = tx.unbounded_send(Ok(progress));
+        }));
+
+        Ok(Response::new(rx.boxed()))
+    }
+
+    async fn search(&self, request: Request<SearchRequest>) -> Result<Response<SearchResponse>, Status> {
+        let req = request.into_inner();
+        let filter = SearchFilter::default().with_derived(derived_filter(&req.derived)?);
+        let limit = if req.limit == 0 { 5 } else { req.limit as usize };
+
+        let query_embedding = self.orchestrator.embedder().embed(&req.query).await.map_err(internal)?;
+        let results = self
+            .orchestrator
+            .vector_store()
+            .search_filtered(&query_embedding, limit, req.min_score.unwrap_or(0.3), &filter)
+            .await
+            .map_err(internal)?;
+
+        Ok(Response::new(SearchResponse {
+            results: results
+                .into_iter()
+                .map(|r| SearchResult {
+                    timestamp: r.document.format_timestamp(),
+                    video_id: r.document.video_id,
+                    video_title: r.document.video_title,
+                    chunk_title: r.document.section_title.unwrap_or_default(),
+                    content: r.document.content,
+                    start_seconds: r.document.start_seconds,
+                    end_seconds: r.document.end_seconds,
+                    score: r.score,
+                    doc_type: r.document.doc_type.as_str().to_string(),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn ask(&self, request: Request<AskRequest>) -> Result<Response<Self::AskStream>, Status> {
+        let req = request.into_inner();
+        let settings = self.orchestrator.settings();
+        let filter = SearchFilter::default().with_derived(derived_filter(&req.derived)?);
+        let model = if req.model.is_empty() { settings.rag.model.clone() } else { req.model };
+        let max_chunks = if req.max_chunks == 0 { 10 } else { req.max_chunks as usize };
+
+        let engine = RagEngine::new(
+            self.orchestrator.vector_store(),
+            self.orchestrator.embedder(),
+            &model,
+            max_chunks,
+        )
+        .with_client(ChatClient::from_settings(&settings.rag.llm))
+        .with_filter(filter)
+        .with_token_budget(settings.rag.context_budget())
+        .with_neighbor_window(settings.rag.neighbor_window)
+        .with_min_confidence(settings.rag.min_confidence)
+        .with_confidence_scale(ConfidenceScale::from_settings(&settings.rag))
+        .with_expansion(QueryExpander::from_settings(&settings.rag, &model, self.orchestrator.prompts()))
+        .with_recency(RecencyRanking::from_settings(&settings.rag))
+        .with_diversity(settings.rag.diversity)
+        .with_routing(settings.rag.routing);
+
+        // The answer's usage is recorded as the spawned task consumes it
+        let tracker = UsageTracker::new();
+        let streamed = tracker.scope(engine.ask_stream(&req.question)).await;
+        let streamed = match streamed {
+            Ok(streamed) => streamed,
+            Err(e) => {
+                self.orchestrator.record_usage("ask", None, &tracker).await;
+                return Err(internal(e));
+            }
+        };
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let orchestrator = self.orchestrator.clone();
+
+        tokio::spawn(logging::in_current_run(async move {
+            tracker
+                .scope(async move {
+                    let mut answer = String::new();
+                    let mut tokens = streamed.answer;
+                    while let Some(token) = tokens.next().await {
+                        match token {
+                            Ok(text) => {
+                                answer.push_str(&text);
+                                let event = AskEvent { event: Some(ask_event::Event::Token(text)) };
+                                // Stop generating once the client has gone away
+                                if tx.unbounded_send(Ok(event)).is_err() {
+                                    return;
+                                }
+                            }
+                            Err(e) => {
+                                let _ = tx.unbounded_send(Err(internal(e)));
+                                return;
+                            }
+                        }
+                    }
+
+                    let citations = engine.record_citations(&answer, &streamed.sources).await;
+
+                    let sources = Sources {
+                        sources: streamed.sources.into_iter().map(Source::from).collect(),
+                    };
+                    let _ = tx.unbounded_send(Ok(AskEvent { event: Some(ask_event::Event::Sources(sources)) }));
+
+                    let citations = Citations {
+                        citations: citations.into_iter().map(Citation::from).collect(),
+                    };
+                    let _ = tx.unbounded_send(Ok(AskEvent { event: Some(ask_event::Event::Citations(citations)) }));
+                })
+                .await;
+            orchestrator.record_usage("ask", None, &tracker).await;
+        }));
+
+        Ok(Response::new(rx.boxed()))
+    }
+
+    async fn list_media(
+        &self,
+        _request: Request<ListMediaRequest>,
+    ) -> Result<Response<ListMediaResponse>, Status> {
+        let media = self.orchestrator.vector_store().list_videos().await.map_err(internal)?;
+
+        Ok(Response::new(ListMediaResponse {
+            media: media
+                .into_iter()
+                .map(|m| Media {
+                    video_id: m.video_id,
+                    video_title: m.video_title,
+                    chunk_count: m.chunk_count,
+                    total_duration_seconds: m.total_duration_seconds,
+                })
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derived_filter() {
+        assert_eq!(derived_filter("").unwrap(), DerivedFilter::Include);
+        assert_eq!(derived_filter("only").unwrap(), DerivedFilter::Only);
+        assert_eq!(
+            derived_filter("sometimes").unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
+    #[tokio::test]
+    async fn test_calls_run_with_request_ids() {
+        // Answers with the run ID the call ran under
+        let inner = tower::service_fn(|_: http::Request<()>| async {
+            Ok::<_, std::convert::Infallible>(http::Response::new(logging::current_run_id()))
+        });
+        let mut service = TraceRequest { inner };
+
+        let request = http::Request::builder().header(X_REQUEST_ID, "run-42").body(()).unwrap();
+        let response = service.call(request).await.unwrap();
+        assert_eq!(response.body().as_deref(), Some("run-42"));
+        assert_eq!(response.headers()[X_REQUEST_ID], "run-42");
+
+        let response = service.call(http::Request::new(())).await.unwrap();
+        let run_id = response.body().clone().expect("no run ID");
+        assert!(!run_id.is_empty());
+        assert_eq!(response.headers()[X_REQUEST_ID], run_id.as_str());
+    }
+}
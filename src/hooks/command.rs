@@ -0,0 +1,213 @@
+//! External command hooks.
+//!
+//! The command receives a JSON payload on stdin:
+//!
+//! ```json
+//! {"event": "on_chunks", "media_id": "abc123", "title": "...", "chunks": [...]}
+//! ```
+//!
+//! `on_transcript` payloads carry `transcript`, `on_chunks` payloads carry
+//! `chunks`, and `on_indexed` payloads carry `documents` (without embeddings).
+//! To modify the data, print the same object (or just the `transcript` /
+//! `chunks` field) to stdout. Empty output leaves the data unchanged. A non-zero
+//! exit status fails the hook.
+
+use super::{Hook, HookContext, HookEvent};
+use crate::chunking::ContentChunk;
+use crate::config::HookSettings;
+use crate::error::{LyttError, Result};
+use crate::transcription::Transcript;
+use crate::vector_store::Document;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Hook that runs an external command for one pipeline event.
+pub struct CommandHook {
+    name: String,
+    event: HookEvent,
+    command: String,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+impl CommandHook {
+    /// Create a command hook.
+    pub fn new(event: HookEvent, command: &str, args: Vec<String>) -> Self {
+        Self {
+            name: command.to_string(),
+            event,
+            command: command.to_string(),
+            args,
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Create a command hook from config.
+    pub fn from_settings(settings: &HookSettings) -> Self {
+        Self::new(settings.event, &settings.command, settings.args.clone())
+            .with_timeout(Duration::from_secs(settings.timeout_seconds))
+    }
+
+    /// Set the maximum run time for the command.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run the command with the payload and return its parsed stdout, if any.
+    async fn run(&self, ctx: &HookContext, key: &str, data: Value) -> Result<Option<Value>> {
+        let payload = json!({
+            "event": self.event,
+            "media_id": ctx.media_id,
+            "title": ctx.title,
+            key: data,
+        });
+
+        let mut child = Command::new(shellexpand::tilde(&self.command).as_ref())
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    LyttError::ToolNotFound(self.command.clone())
+                } else {
+                    LyttError::ToolFailed(format!("hook '{}': {}", self.name, e))
+                }
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(payload.to_string().as_bytes()).await?;
+            // Dropping stdin closes it so the command sees EOF
+        }
+
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                LyttError::ToolFailed(format!(
+                    "hook '{}' timed out after {}s",
+                    self.name,
+                    self.timeout.as_secs()
+                ))
+            })??;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(LyttError::ToolFailed(format!(
+                "hook '{}' exited with {}: {}",
+                self.name,
+                output.status,
+                stderr.trim()
+            )));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let value: Value = serde_json::from_str(stdout.trim()).map_err(|e| {
+            LyttError::ToolFailed(format!("hook '{}' printed invalid JSON: {}", self.name, e))
+        })?;
+
+        // Accept either the full payload or just the data field
+        Ok(Some(match value {
+            Value::Object(mut map) if map.contains_key(key) => map.remove(key).unwrap_or_default(),
+            other => other,
+        }))
+    }
+}
+
+#[async_trait]
+impl Hook for CommandHook {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn on_transcript(&self, ctx: &HookContext, transcript: &mut Transcript) -> Result<()> {
+        if self.event != HookEvent::OnTranscript {
+            return Ok(());
+        }
+
+        if let Some(value) = self.run(ctx, "transcript", serde_json::to_value(&*transcript)?).await? {
+            *transcript = serde_json::from_value(value).map_err(|e| {
+                LyttError::ToolFailed(format!("hook '{}' returned an invalid transcript: {}", self.name, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn on_chunks(&self, ctx: &HookContext, chunks: &mut Vec<ContentChunk>) -> Result<()> {
+        if self.event != HookEvent::OnChunks {
+            return Ok(());
+        }
+
+        if let Some(value) = self.run(ctx, "chunks", serde_json::to_value(&*chunks)?).await? {
+            *chunks = serde_json::from_value(value).map_err(|e| {
+                LyttError::ToolFailed(format!("hook '{}' returned invalid chunks: {}", self.name, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn on_indexed(&self, ctx: &HookContext, documents: &[Document]) -> Result<()> {
+        if self.event != HookEvent::OnIndexed {
+            return Ok(());
+        }
+
+        // Embeddings are large and rarely useful to scripts
+        let documents: Vec<Value> = documents
+            .iter()
+            .map(|doc| {
+                let mut value = serde_json::to_value(doc)?;
+                if let Some(map) = value.as_object_mut() {
+                    map.remove("embedding");
+                }
+                Ok(value)
+            })
+            .collect::<Result<_>>()?;
+
+        self.run(ctx, "documents", Value::Array(documents)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hook_modifies_chunks() {
+        // sed rewrites the payload; the hook accepts the full object back
+        let hook = CommandHook::new(
+            HookEvent::OnChunks,
+            "sed",
+            vec!["s/password/[redacted]/g".to_string()],
+        );
+
+        let ctx = HookContext::new("vid", "Title");
+        let mut chunks = vec![ContentChunk::new(None, "my password is hunter2".to_string(), 0.0, 5.0, 0)];
+        hook.on_chunks(&ctx, &mut chunks).await.unwrap();
+        assert_eq!(chunks[0].content, "my [redacted] is hunter2");
+
+        // Other events are ignored
+        let mut transcript = Transcript::new("vid".to_string(), Vec::new());
+        hook.on_transcript(&ctx, &mut transcript).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hook_failure() {
+        let hook = CommandHook::new(HookEvent::OnChunks, "false", Vec::new());
+        let ctx = HookContext::new("vid", "Title");
+        let mut chunks = Vec::new();
+        assert!(hook.on_chunks(&ctx, &mut chunks).await.is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Pipeline hooks.
+//!
+//! Hooks observe or modify pipeline data between stages:
+//! - `on_transcript` runs after transcription, before the transcript is stored
+//! - `on_chunks` runs after chunking, before embedding
+//! - `on_indexed` runs after the documents are written to the vector store
+//!
+//! Library users implement [`Hook`] and register it with
+//! [`Orchestrator::with_hook`](crate::orchestrator::Orchestrator::with_hook).
+//! External commands can be configured with `[[hooks]]` in the config file
+//! (see [`CommandHook`]).
+
+mod command;
+
+pub use command::CommandHook;
+
+use crate::chunking::ContentChunk;
+use crate::config::Settings;
+use crate::error::Result;
+use crate::transcription::Transcript;
+use crate::vector_store::Document;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Pipeline stage a hook runs at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// After transcription, before the transcript is stored and chunked.
+    OnTranscript,
+    /// After chunking, before embedding.
+    OnChunks,
+    /// After documents are indexed.
+    OnIndexed,
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookEvent::OnTranscript => write!(f, "on_transcript"),
+            HookEvent::OnChunks => write!(f, "on_chunks"),
+            HookEvent::OnIndexed => write!(f, "on_indexed"),
+        }
+    }
+}
+
+/// Media the pipeline is currently processing.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookContext {
+    /// Media ID.
+    pub media_id: String,
+    /// Media title.
+    pub title: String,
+}
+
+impl HookContext {
+    /// Create a new hook context.
+    pub fn new(media_id: &str, title: &str) -> Self {
+        Self {
+            media_id: media_id.to_string(),
+            title: title.to_string(),
+        }
+    }
+}
+
+/// A pipeline hook. All methods default to no-ops.
+///
+/// Errors from `on_transcript` and `on_chunks` abort processing, so a failing
+/// redaction hook never lets unredacted content through. Errors from
+/// `on_indexed` are logged, since the content is already stored.
+#[async_trait]
+pub trait Hook: Send + Sync {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Inspect or modify the transcript.
+    async fn on_transcript(&self, _ctx: &HookContext, _transcript: &mut Transcript) -> Result<()> {
+        Ok(())
+    }
+
+    /// Inspect or modify the chunks.
+    async fn on_chunks(&self, _ctx: &HookContext, _chunks: &mut Vec<ContentChunk>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Observe the indexed documents.
+    async fn on_indexed(&self, _ctx: &HookContext, _documents: &[Document]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Ordered collection of hooks. Hooks run in registration order.
+#[derive(Clone, Default)]
+pub struct HookRegistry {
+    hooks: Vec<Arc<dyn Hook>>,
+}
+
+impl HookRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the external command hooks defined in config.
+    pub fn from_settings(settings: &Settings) -> Self {
+        let mut registry = Self::new();
+        for hook in &settings.hooks {
+            registry.register(Arc::new(CommandHook::from_settings(hook)));
+        }
+        registry
+    }
+
+    /// Register a hook.
+    pub fn register(&mut self, hook: Arc<dyn Hook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Number of registered hooks.
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Whether no hooks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run all `on_transcript` hooks.
+    ///
+    /// The full text and duration are recomputed from the segments afterwards,
+    /// so hooks only need to edit segments.
+    pub async fn run_transcript(&self, ctx: &HookContext, transcript: &mut Transcript) -> Result<()> {
+        if self.hooks.is_empty() {
+            return Ok(());
+        }
+
+        for hook in &self.hooks {
+            debug!("Running on_transcript hook '{}'", hook.name());
+            hook.on_transcript(ctx, transcript).await?;
+        }
+
+        let segments = std::mem::take(&mut transcript.segments);
+        *transcript = Transcript::new(transcript.video_id.clone(), segments);
+        Ok(())
+    }
+
+    /// Run all `on_chunks` hooks.
+    pub async fn run_chunks(&self, ctx: &HookContext, chunks: &mut Vec<ContentChunk>) -> Result<()> {
+        for hook in &self.hooks {
+            debug!("Running on_chunks hook '{}'", hook.name());
+            hook.on_chunks(ctx, chunks).await?;
+        }
+        Ok(())
+    }
+
+    /// Run all `on_indexed` hooks, logging failures.
+    pub async fn run_indexed(&self, ctx: &HookContext, documents: &[Document]) {
+        for hook in &self.hooks {
+            debug!("Running on_indexed hook '{}'", hook.name());
+            if let Err(e) = hook.on_indexed(ctx, documents).await {
+                warn!("Hook '{}' failed on_indexed for {}: {}", hook.name(), ctx.media_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptSegment;
+
+    struct Redact;
+
+    #[async_trait]
+    impl Hook for Redact {
+        fn name(&self) -> &str {
+            "redact"
+        }
+
+        async fn on_transcript(&self, _ctx: &HookContext, transcript: &mut Transcript) -> Result<()> {
+            for segment in &mut transcript.segments {
+                segment.text = segment.text.replace("secret", "[redacted]");
+            }
+            Ok(())
+        }
+
+        async fn on_chunks(&self, _ctx: &HookContext, chunks: &mut Vec<ContentChunk>) -> Result<()> {
+            chunks.retain(|c| !c.content.is_empty());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_runs_hooks() {
+        let mut registry = HookRegistry::new();
+        registry.register(Arc::new(Redact));
+        assert_eq!(registry.len(), 1);
+
+        let ctx = HookContext::new("vid", "Title");
+        let mut transcript = Transcript::new(
+            "vid".to_string(),
+            vec![TranscriptSegment::new(0.0, 5.0, "the secret plan".to_string())],
+        );
+        registry.run_transcript(&ctx, &mut transcript).await.unwrap();
+        assert_eq!(transcript.segments[0].text, "the [redacted] plan");
+        assert_eq!(transcript.full_text, "the [redacted] plan");
+
+        let mut chunks = vec![
+            ContentChunk::new(None, "kept".to_string(), 0.0, 5.0, 0),
+            ContentChunk::new(None, String::new(), 5.0, 10.0, 1),
+        ];
+        registry.run_chunks(&ctx, &mut chunks).await.unwrap();
+        assert_eq!(chunks.len(), 1);
+    }
+}
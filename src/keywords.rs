@@ -0,0 +1,148 @@
+//! Keyword extraction for indexed chunks.
+//!
+//! Uses RAKE (Rapid Automatic Keyword Extraction): candidate phrases are the
+//! runs of words between stopwords and punctuation, and each phrase is scored
+//! by how often its words occur and how many other words they co-occur with.
+//! It runs locally, so keywords cost nothing to extract at index time.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Longest phrase, in words, kept as a keyword.
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// Words that split candidate phrases. Includes common spoken fillers, which
+/// transcripts are full of.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "above", "actually", "after", "again", "against", "all", "also", "am", "an", "and", "any",
+    "are", "around", "as", "at", "basically", "be", "because", "been", "before", "being", "below", "between",
+    "both", "but", "by", "can", "could", "did", "do", "does", "doing", "don't", "down", "during", "each",
+    "even", "every", "few", "for", "from", "further", "get", "gets", "getting", "go", "going", "gonna",
+    "got", "had", "has", "have", "having", "he", "her", "here", "hers", "him", "his", "how", "i", "i'm",
+    "if", "in", "into", "is", "it", "it's", "its", "just", "kind", "know", "like", "lot", "maybe", "me",
+    "mean", "more", "most", "much", "my", "no", "nor", "not", "now", "of", "off", "oh", "okay", "on",
+    "once", "one", "only", "or", "other", "our", "out", "over", "own", "pretty", "really", "right", "said",
+    "same", "say", "see", "she", "should", "so", "some", "something", "sort", "such", "than", "that",
+    "that's", "the", "their", "them", "then", "there", "there's", "these", "they", "thing", "things",
+    "think", "this", "those", "through", "to", "too", "uh", "um", "under", "until", "up", "us", "very",
+    "want", "was", "way", "we", "we're", "well", "were", "what", "when", "where", "which", "while", "who",
+    "why", "will", "with", "would", "yeah", "yes", "you", "you're", "your",
+];
+
+/// How often a keyword occurs across a media item's chunks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeywordCount {
+    /// The keyword or key phrase (lowercase).
+    pub keyword: String,
+    /// Number of chunks it was extracted from.
+    pub count: usize,
+}
+
+/// Extract up to `max` keywords from `text`, best first.
+pub fn extract_keywords(text: &str, max: usize) -> Vec<String> {
+    if max == 0 {
+        return Vec::new();
+    }
+
+    let phrases = candidate_phrases(text);
+
+    // Word scores: degree (co-occurring words, including itself) over frequency
+    let mut frequency: HashMap<&str, f64> = HashMap::new();
+    let mut degree: HashMap<&str, f64> = HashMap::new();
+    for phrase in &phrases {
+        for word in phrase {
+            *frequency.entry(word).or_default() += 1.0;
+            *degree.entry(word).or_default() += phrase.len() as f64;
+        }
+    }
+
+    let mut scored: HashMap<String, f64> = HashMap::new();
+    for phrase in &phrases {
+        let score = phrase.iter().map(|w| degree[w.as_str()] / frequency[w.as_str()]).sum();
+        scored.insert(phrase.join(" "), score);
+    }
+
+    let mut keywords: Vec<(String, f64)> = scored.into_iter().collect();
+    keywords.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    keywords.into_iter().take(max).map(|(k, _)| k).collect()
+}
+
+/// Split text into lowercase candidate phrases at stopwords and punctuation.
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+
+    let mut flush = |current: &mut Vec<String>| {
+        if !current.is_empty() && current.len() <= MAX_PHRASE_WORDS {
+            phrases.push(std::mem::take(current));
+        }
+        current.clear();
+    };
+
+    for token in text.split_whitespace() {
+        let ends_clause = token.ends_with(['.', ',', ';', ':', '!', '?', ')', '"']);
+        let word = token
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+            .trim_matches('\'')
+            .to_lowercase();
+
+        if word.chars().count() < 3 || STOPWORDS.contains(&word.as_str()) || word.chars().all(|c| c.is_numeric()) {
+            flush(&mut current);
+        } else {
+            current.push(word);
+        }
+
+        if ends_clause {
+            flush(&mut current);
+        }
+    }
+    flush(&mut current);
+
+    phrases
+}
+
+/// Count keywords across documents, most frequent first.
+pub fn count_keywords<'a>(keyword_lists: impl IntoIterator<Item = &'a [String]>, limit: usize) -> Vec<KeywordCount> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for keywords in keyword_lists {
+        for keyword in keywords {
+            *counts.entry(keyword.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut counted: Vec<KeywordCount> = counts
+        .into_iter()
+        .map(|(keyword, count)| KeywordCount { keyword: keyword.to_string(), count })
+        .collect();
+    counted.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.keyword.cmp(&b.keyword)));
+    counted.truncate(limit);
+    counted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_keywords() {
+        let text = "So today we're talking about gradient descent. Gradient descent is, um, \
+            basically how neural networks learn. The learning rate controls how big each step is.";
+        let keywords = extract_keywords(text, 4);
+
+        assert_eq!(keywords.len(), 4);
+        assert!(keywords.contains(&"gradient descent".to_string()));
+        assert!(keywords.contains(&"neural networks learn".to_string()));
+        assert!(keywords.iter().all(|k| !k.split(' ').any(|w| STOPWORDS.contains(&w))));
+
+        assert!(extract_keywords("um, yeah, so", 5).is_empty());
+        assert!(extract_keywords(text, 0).is_empty());
+    }
+
+    #[test]
+    fn test_count_keywords() {
+        let a = vec!["rust".to_string(), "async".to_string()];
+        let b = vec!["rust".to_string()];
+        let counts = count_keywords([a.as_slice(), b.as_slice()], 1);
+        assert_eq!(counts, vec![KeywordCount { keyword: "rust".to_string(), count: 2 }]);
+    }
+}
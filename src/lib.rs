@@ -19,12 +19,35 @@
 //! - `config` - Configuration management
 //! - `audio_source` - Audio source abstraction (YouTube, local files)
 //! - `audio` - Audio download and processing
+//! - `auth` - API key authentication and rate limiting for the servers
 //! - `transcription` - Speech-to-text transcription
 //! - `chunking` - Content chunking strategies
 //! - `embedding` - Embedding generation
+//! - `grpc` - gRPC API (`grpc` feature)
 //! - `vector_store` - Vector database abstraction
+//! - `daemon` - Scheduled jobs for `lytt daemon` (watch folders, subscriptions, digests)
+//! - `dedupe` - Duplicate and near-duplicate media detection
+//! - `digest` - Periodic digests of newly indexed media
+//! - `export` - Word and PDF transcript documents
+//! - `hooks` - Pipeline hooks (library and external commands)
+//! - `keywords` - Per-chunk keyword extraction (RAKE)
+//! - `llm` - Chat-completion backends (OpenAI-compatible, Anthropic)
+//! - `logging` - Run IDs and JSON log output
+//! - `quiz` - Quiz and flashcard generation
 //! - `rag` - RAG engine for question answering
+//! - `retention` - Retention policies and library pruning
+//! - `rollup` - Summary rollups across tagged media
+//! - `summary` - Stored per-chunk and whole-media summaries
+//! - `timeline` - Dated timelines of how a topic evolved across the library
+//! - `topics` - Topic clustering and the library topic map
+//! - `meeting` - Meeting recording sidecars (speakers, attendees)
+//! - `notes` - Markdown note export (Obsidian vaults)
+//! - `notifications` - Webhook notifications for pipeline events
 //! - `orchestrator` - Pipeline coordination
+//! - `plugin` - External NDJSON plugin processes (chunkers, embedders)
+//! - `tts` - Text-to-speech
+//! - `tui` - Terminal UI for browsing the library
+//! - `usage` - Usage tracking and cost estimation
 //!
 //! # Example
 //!
@@ -48,16 +71,48 @@
 pub mod agent;
 pub mod audio;
 pub mod audio_source;
+pub mod audiobook;
+pub mod auth;
 pub mod chunking;
 pub mod cli;
 pub mod config;
+pub mod consensus;
+pub mod daemon;
+pub mod dedupe;
+pub mod digest;
 pub mod embedding;
 pub mod error;
+pub mod export;
+pub mod glossary;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod hooks;
+pub mod keywords;
+pub mod llm;
+pub mod logging;
 pub mod mcp;
+pub mod meeting;
+pub mod notes;
+pub mod notifications;
 pub mod openai;
 pub mod orchestrator;
+pub mod pack;
+pub mod plugin;
+pub mod progress;
+pub mod quiz;
 pub mod rag;
+pub mod retention;
+pub mod rollup;
+pub mod summary;
+#[cfg(any(test, feature = "e2e"))]
+pub mod testing;
+pub mod timeline;
+pub mod titles;
+pub mod topics;
 pub mod transcription;
+pub mod tts;
+pub mod tui;
+pub mod usage;
 pub mod vector_store;
 
 pub use error::{Result, LyttError};
@@ -0,0 +1,368 @@
+//! Anthropic Messages API backend.
+//!
+//! Translates OpenAI-style chat requests (system/user/assistant/tool messages,
+//! function tools, JSON mode) to the Messages API and maps the reply back.
+
+use crate::error::{LyttError, Result};
+use crate::openai::{http_client, DEFAULT_TIMEOUT_SECS};
+use async_openai::types::{CreateChatCompletionRequest, CreateChatCompletionResponse};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+/// Default Anthropic API base URL.
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+/// API version header sent with every request.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is required by the Messages API; used when the request doesn't set one.
+const DEFAULT_MAX_TOKENS: u32 = 8192;
+
+/// Appended to the system prompt when the request asks for JSON output.
+const JSON_MODE_INSTRUCTION: &str =
+    "Respond with a single valid JSON object only, without Markdown code fences or commentary.";
+
+/// Client for the Anthropic Messages API.
+#[derive(Clone)]
+pub struct AnthropicClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl AnthropicClient {
+    /// Create a client. `None` base URL uses the public API.
+    pub fn new(base_url: Option<&str>, api_key: Option<String>) -> Self {
+        Self {
+            http: http_client(Duration::from_secs(DEFAULT_TIMEOUT_SECS)),
+            base_url: base_url.unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    /// Send an OpenAI-style chat request to the Messages API.
+    pub async fn create(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            LyttError::Config(
+                "Anthropic API key not set. Set it with: export ANTHROPIC_API_KEY='sk-ant-...'"
+                    .to_string(),
+            )
+        })?;
+
+        let request = serde_json::to_value(&request)?;
+        let json_mode = is_json_mode(&request);
+        let body = to_messages_request(&request)?;
+
+        let response = self
+            .http
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let payload: Value = response.json().await?;
+
+        if !status.is_success() {
+            let message = payload
+                .pointer("/error/message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("unknown error");
+            return Err(LyttError::Llm(format!("Anthropic API returned {}: {}", status, message)));
+        }
+
+        from_messages_response(&payload, json_mode)
+    }
+}
+
+/// Whether the request asks for a JSON response.
+fn is_json_mode(request: &Value) -> bool {
+    matches!(
+        request.pointer("/response_format/type").and_then(|t| t.as_str()),
+        Some("json_object") | Some("json_schema")
+    )
+}
+
+/// Build a Messages API request body from a serialized OpenAI chat request.
+fn to_messages_request(request: &Value) -> Result<Value> {
+    let mut system_parts: Vec<String> = Vec::new();
+    let mut messages: Vec<Value> = Vec::new();
+
+    let empty = Vec::new();
+    for message in request["messages"].as_array().unwrap_or(&empty) {
+        let role = message["role"].as_str().unwrap_or("user");
+
+        let (role, blocks) = match role {
+            "system" | "developer" => {
+                system_parts.push(content_text(&message["content"]));
+                continue;
+            }
+            "assistant" => {
+                let mut blocks = Vec::new();
+                let text = content_text(&message["content"]);
+                if !text.is_empty() {
+                    blocks.push(json!({"type": "text", "text": text}));
+                }
+                for call in message["tool_calls"].as_array().unwrap_or(&empty) {
+                    let arguments = call["function"]["arguments"].as_str().unwrap_or("{}");
+                    let input: Value =
+                        serde_json::from_str(arguments).unwrap_or_else(|_| json!({}));
+                    blocks.push(json!({
+                        "type": "tool_use",
+                        "id": call["id"],
+                        "name": call["function"]["name"],
+                        "input": input,
+                    }));
+                }
+                ("assistant", blocks)
+            }
+            "tool" => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": message["tool_call_id"],
+                    "content": content_text(&message["content"]),
+                })],
+            ),
+            _ => (
+                "user",
+                vec![json!({"type": "text", "text": content_text(&message["content"])})],
+            ),
+        };
+
+        if blocks.is_empty() {
+            continue;
+        }
+
+        // The Messages API requires alternating roles; merge consecutive turns
+        match messages.last_mut() {
+            Some(last) if last["role"] == role => {
+                if let Some(content) = last["content"].as_array_mut() {
+                    content.extend(blocks);
+                }
+            }
+            _ => messages.push(json!({"role": role, "content": blocks})),
+        }
+    }
+
+    if is_json_mode(request) {
+        system_parts.push(JSON_MODE_INSTRUCTION.to_string());
+    }
+
+    let max_tokens = request["max_completion_tokens"]
+        .as_u64()
+        .or_else(|| request["max_tokens"].as_u64())
+        .unwrap_or(DEFAULT_MAX_TOKENS as u64);
+
+    let mut body = Map::new();
+    body.insert("model".to_string(), request["model"].clone());
+    body.insert("max_tokens".to_string(), json!(max_tokens));
+    body.insert("messages".to_string(), Value::Array(messages));
+
+    let system = system_parts
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if !system.is_empty() {
+        body.insert("system".to_string(), Value::String(system));
+    }
+
+    if let Some(temperature) = request["temperature"].as_f64() {
+        // Anthropic accepts 0.0-1.0
+        body.insert("temperature".to_string(), json!(temperature.clamp(0.0, 1.0)));
+    }
+
+    if let Some(tools) = request["tools"].as_array() {
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|tool| {
+                let function = &tool["function"];
+                let schema = if function["parameters"].is_object() {
+                    function["parameters"].clone()
+                } else {
+                    json!({"type": "object", "properties": {}})
+                };
+                json!({
+                    "name": function["name"],
+                    "description": function["description"].as_str().unwrap_or_default(),
+                    "input_schema": schema,
+                })
+            })
+            .collect();
+        body.insert("tools".to_string(), Value::Array(tools));
+    }
+
+    Ok(Value::Object(body))
+}
+
+/// Convert a Messages API response to an OpenAI chat completion response.
+fn from_messages_response(response: &Value, json_mode: bool) -> Result<CreateChatCompletionResponse> {
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+
+    if let Some(blocks) = response["content"].as_array() {
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("text") => text.push_str(block["text"].as_str().unwrap_or_default()),
+                Some("tool_use") => tool_calls.push(json!({
+                    "id": block["id"],
+                    "type": "function",
+                    "function": {
+                        "name": block["name"],
+                        "arguments": block["input"].to_string(),
+                    },
+                })),
+                _ => {}
+            }
+        }
+    }
+
+    if json_mode {
+        text = strip_code_fence(&text).to_string();
+    }
+
+    let finish_reason = match response["stop_reason"].as_str() {
+        Some("tool_use") => "tool_calls",
+        Some("max_tokens") => "length",
+        _ => "stop",
+    };
+
+    let mut message = json!({
+        "role": "assistant",
+        "content": if text.is_empty() { Value::Null } else { Value::String(text) },
+    });
+    if !tool_calls.is_empty() {
+        message["tool_calls"] = Value::Array(tool_calls);
+    }
+
+    let input_tokens = response.pointer("/usage/input_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
+    let output_tokens = response.pointer("/usage/output_tokens").and_then(|t| t.as_u64()).unwrap_or(0);
+
+    let converted = json!({
+        "id": response["id"].as_str().unwrap_or_default(),
+        "object": "chat.completion",
+        "created": chrono::Utc::now().timestamp() as u32,
+        "model": response["model"].as_str().unwrap_or_default(),
+        "choices": [{
+            "index": 0,
+            "message": message,
+            "finish_reason": finish_reason,
+        }],
+        "usage": {
+            "prompt_tokens": input_tokens,
+            "completion_tokens": output_tokens,
+            "total_tokens": input_tokens + output_tokens,
+        },
+    });
+
+    serde_json::from_value(converted)
+        .map_err(|e| LyttError::Llm(format!("Unexpected Anthropic response: {}", e)))
+}
+
+/// Text of an OpenAI message content (plain string or array of parts).
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Remove a surrounding Markdown code fence, if present.
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    match trimmed.strip_prefix("```") {
+        Some(rest) => {
+            // Skip the language tag on the opening line
+            let body = rest.split_once('\n').map(|(_, b)| b).unwrap_or(rest);
+            body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+        }
+        None => trimmed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_messages_request() {
+        let request = json!({
+            "model": "claude-sonnet-4-5",
+            "temperature": 1.5,
+            "response_format": {"type": "json_object"},
+            "messages": [
+                {"role": "system", "content": "Be helpful."},
+                {"role": "user", "content": "Find videos about Rust"},
+                {"role": "assistant", "tool_calls": [
+                    {"id": "call_1", "type": "function", "function": {"name": "search", "arguments": "{\"query\":\"rust\"}"}},
+                    {"id": "call_2", "type": "function", "function": {"name": "list_videos", "arguments": "{}"}}
+                ]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "3 results"},
+                {"role": "tool", "tool_call_id": "call_2", "content": "10 videos"}
+            ],
+            "tools": [
+                {"type": "function", "function": {"name": "search", "description": "Search", "parameters": {"type": "object"}}}
+            ]
+        });
+
+        let body = to_messages_request(&request).unwrap();
+        assert_eq!(body["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert_eq!(body["temperature"], 1.0);
+        assert!(body["system"].as_str().unwrap().starts_with("Be helpful."));
+        assert!(body["system"].as_str().unwrap().contains("JSON"));
+
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["content"][0]["type"], "tool_use");
+        assert_eq!(messages[1]["content"][0]["input"]["query"], "rust");
+        // Both tool results are merged into a single user turn
+        assert_eq!(messages[2]["role"], "user");
+        assert_eq!(messages[2]["content"].as_array().unwrap().len(), 2);
+        assert_eq!(messages[2]["content"][1]["tool_use_id"], "call_2");
+
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+    }
+
+    #[test]
+    fn test_from_messages_response() {
+        let response = json!({
+            "id": "msg_1",
+            "model": "claude-sonnet-4-5",
+            "stop_reason": "tool_use",
+            "content": [
+                {"type": "text", "text": "Let me search."},
+                {"type": "tool_use", "id": "toolu_1", "name": "search", "input": {"query": "rust"}}
+            ],
+            "usage": {"input_tokens": 100, "output_tokens": 20}
+        });
+
+        let converted = from_messages_response(&response, false).unwrap();
+        let message = &converted.choices[0].message;
+        assert_eq!(message.content.as_deref(), Some("Let me search."));
+
+        let calls = message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].function.name, "search");
+        assert_eq!(calls[0].function.arguments, r#"{"query":"rust"}"#);
+
+        let usage = converted.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 100);
+        assert_eq!(usage.total_tokens, 120);
+    }
+
+    #[test]
+    fn test_strip_code_fence() {
+        assert_eq!(strip_code_fence("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+        assert_eq!(strip_code_fence("  {\"a\": 1} "), "{\"a\": 1}");
+    }
+}
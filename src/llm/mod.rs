@@ -0,0 +1,145 @@
+//! Chat-completion backends.
+//!
+//! Requests and responses use the `async_openai` types throughout the crate.
+//! [`ChatClient`] sends them either to an OpenAI-compatible endpoint as-is
+//! (OpenAI, Ollama, llama.cpp server, ...), or translates them for the
+//! Anthropic Messages API.
+
+mod anthropic;
+
+pub use anthropic::AnthropicClient;
+
+use crate::config::{LlmProvider, LlmSettings};
+use crate::error::{LyttError, Result};
+use crate::openai::{create_client, create_client_for};
+use crate::usage::{self, UsageStage};
+use async_openai::config::OpenAIConfig;
+use async_openai::types::{
+    ChatCompletionStreamOptions, CreateChatCompletionRequest, CreateChatCompletionResponse,
+};
+use futures::stream::{self, BoxStream, StreamExt};
+use tracing::debug;
+
+/// Stream of answer text fragments, in order.
+pub type TextStream = BoxStream<'static, Result<String>>;
+
+/// Client for chat completions against the configured provider.
+#[derive(Clone)]
+pub enum ChatClient {
+    /// OpenAI or an OpenAI-compatible endpoint.
+    OpenAI(async_openai::Client<OpenAIConfig>),
+    /// Anthropic Messages API.
+    Anthropic(AnthropicClient),
+}
+
+impl ChatClient {
+    /// OpenAI client with default configuration (`OPENAI_API_KEY`).
+    pub fn openai() -> Self {
+        ChatClient::OpenAI(create_client())
+    }
+
+    /// Create a client from role settings.
+    pub fn from_settings(settings: &LlmSettings) -> Self {
+        let api_key = settings
+            .api_key_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|key| !key.is_empty());
+
+        match settings.provider {
+            LlmProvider::OpenAI => {
+                ChatClient::OpenAI(create_client_for(settings.base_url.as_deref(), api_key))
+            }
+            LlmProvider::Ollama => {
+                // Ollama ignores the key, but the client would otherwise send OPENAI_API_KEY
+                let api_key = api_key.unwrap_or_else(|| "ollama".to_string());
+                ChatClient::OpenAI(create_client_for(settings.effective_base_url(), Some(api_key)))
+            }
+            LlmProvider::Anthropic => {
+                let api_key = api_key.or_else(|| {
+                    std::env::var("ANTHROPIC_API_KEY").ok().filter(|k| !k.is_empty())
+                });
+                ChatClient::Anthropic(AnthropicClient::new(settings.base_url.as_deref(), api_key))
+            }
+        }
+    }
+
+    /// Send a chat completion request.
+    pub async fn create(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse> {
+        debug!("Chat completion with {}", request.model);
+
+        match self {
+            ChatClient::OpenAI(client) => client
+                .chat()
+                .create(request)
+                .await
+                .map_err(|e| LyttError::OpenAI(e.to_string())),
+            ChatClient::Anthropic(client) => client.create(request).await,
+        }
+    }
+
+    /// Send a chat completion request and stream the reply text as it is
+    /// generated. The Anthropic backend returns the whole reply as one fragment.
+    /// Token usage is recorded under `stage` as the stream is consumed.
+    pub async fn create_stream(
+        &self,
+        mut request: CreateChatCompletionRequest,
+        stage: UsageStage,
+    ) -> Result<TextStream> {
+        debug!("Streaming chat completion with {}", request.model);
+        let model = request.model.clone();
+
+        match self {
+            ChatClient::OpenAI(client) => {
+                // Usage arrives in a final chunk with no choices
+                request.stream_options = Some(ChatCompletionStreamOptions { include_usage: true });
+                let chunks = client
+                    .chat()
+                    .create_stream(request)
+                    .await
+                    .map_err(|e| LyttError::OpenAI(e.to_string()))?;
+
+                Ok(chunks
+                    .filter_map(move |chunk| {
+                        let model = model.clone();
+                        async move {
+                            match chunk {
+                                Ok(chunk) => {
+                                    usage::record_chat(stage, &model, chunk.usage.as_ref());
+                                    chunk
+                                        .choices
+                                        .into_iter()
+                                        .next()
+                                        .and_then(|choice| choice.delta.content)
+                                        .filter(|text| !text.is_empty())
+                                        .map(Ok)
+                                }
+                                Err(e) => Some(Err(LyttError::OpenAI(e.to_string()))),
+                            }
+                        }
+                    })
+                    .boxed())
+            }
+            ChatClient::Anthropic(client) => {
+                let response = client.create(request).await?;
+                usage::record_chat(stage, &model, response.usage.as_ref());
+                let text = response
+                    .choices
+                    .into_iter()
+                    .next()
+                    .and_then(|choice| choice.message.content)
+                    .unwrap_or_default();
+                Ok(stream::once(async move { Ok(text) }).boxed())
+            }
+        }
+    }
+}
+
+impl Default for ChatClient {
+    fn default() -> Self {
+        Self::openai()
+    }
+}
@@ -0,0 +1,282 @@
+//! Structured logging and run IDs.
+//!
+//! Every CLI invocation, HTTP request and daemon job run gets a short run ID.
+//! Like usage tracking, it lives in a task-local set by [`scope`], which also
+//! enters a span carrying it (`run`, `request` or `job`), so every event the
+//! work logs can be traced back to it. With `--log-format json` events are
+//! written by [`JsonLayer`] as one JSON object per line, with the fields of
+//! the spans they happened in, and the pipeline's progress messages
+//! ([`status`]) become events too instead of plain text on stderr.
+
+use chrono::{SecondsFormat, Utc};
+use indicatif::ProgressBar;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::future::Future;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Instrument, Span, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+tokio::task_local! {
+    static RUN_ID: String;
+}
+
+/// Set when logging as JSON.
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// How log events are written to stderr (`--log-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines, with progress messages printed as they happen.
+    #[default]
+    Text,
+    /// One JSON object per event, see [`JsonLayer`].
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format: {}. Use text or json.", s)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Switch progress and CLI messages to log events (`--log-format json`).
+pub fn set_format(format: LogFormat) {
+    JSON.store(format == LogFormat::Json, Ordering::Relaxed);
+}
+
+/// Whether logs are written as JSON.
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// A new run ID: 12 hex digits, short enough to read and grep for.
+pub fn new_run_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..12].to_string()
+}
+
+/// The run ID of the current task, if it runs inside [`scope`].
+pub fn current_run_id() -> Option<String> {
+    RUN_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Run a future as the run `run_id`, inside `span` (which should record the
+/// run ID as its `run_id` field).
+pub async fn scope<F: Future>(run_id: String, span: Span, fut: F) -> F::Output {
+    RUN_ID.scope(run_id, fut.instrument(span)).await
+}
+
+/// Carry the current run ID and span over to a future that will be spawned
+/// onto another task.
+pub fn in_current_run<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+    let run_id = current_run_id();
+    let fut = fut.instrument(Span::current());
+    async move {
+        match run_id {
+            Some(run_id) => RUN_ID.scope(run_id, fut).await,
+            None => fut.await,
+        }
+    }
+}
+
+/// Report pipeline progress ("  Transcribing..."): printed to stderr, or
+/// logged as an info event when logging as JSON.
+pub fn status(message: impl fmt::Display) {
+    if is_json() {
+        tracing::info!("{}", message.to_string().trim());
+    } else {
+        eprintln!("{}", message);
+    }
+}
+
+/// A progress bar drawn to stderr, hidden when logging as JSON.
+pub fn progress_bar(len: u64) -> ProgressBar {
+    if is_json() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(len)
+    }
+}
+
+/// Layer writing each event as a line of JSON: `timestamp`, `level`,
+/// `target`, `message` and the event's other fields, plus the fields of the
+/// spans it happened in (inner spans win) and their names, outermost first,
+/// under `spans`.
+pub struct JsonLayer<W> {
+    writer: W,
+}
+
+impl JsonLayer<fn() -> std::io::Stderr> {
+    /// A layer writing to stderr.
+    pub fn stderr() -> Self {
+        Self::new(std::io::stderr)
+    }
+}
+
+impl<W> JsonLayer<W> {
+    /// A layer writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+/// Fields recorded on a span, kept in its extensions.
+struct SpanFields(Map<String, Value>);
+
+impl<S, W> Layer<S> for JsonLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+                values.record(&mut JsonVisitor(fields));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true).into(),
+        );
+        line.insert("level".to_string(), metadata.level().to_string().into());
+        line.insert("target".to_string(), metadata.target().into());
+
+        if let Some(scope) = ctx.event_scope(event) {
+            let mut spans = Vec::new();
+            for span in scope.from_root() {
+                spans.push(Value::from(span.name()));
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    line.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+            }
+            line.insert("spans".to_string(), spans.into());
+        }
+        event.record(&mut JsonVisitor(&mut line));
+
+        let Ok(mut buf) = serde_json::to_vec(&line) else {
+            return;
+        };
+        buf.push(b'\n');
+        // One write per line, so concurrent events don't interleave
+        let _ = self.writer.make_writer_for(metadata).write_all(&buf);
+    }
+}
+
+/// Records fields as JSON values.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.0.insert(field.name().to_string(), value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_layer_run_id() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(JsonLayer::new(move || writer.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let run_id = new_run_id();
+        assert_eq!(run_id.len(), 12);
+        let span = tracing::info_span!("run", run_id = %run_id, command = "transcribe");
+        let seen = scope(run_id.clone(), span, async {
+            let _item = tracing::info_span!("process_media", input = "abc123").entered();
+            tracing::warn!(segments = 3, "Segment at {}s failed", 600);
+            current_run_id()
+        })
+        .await;
+        assert_eq!(seen.as_deref(), Some(run_id.as_str()));
+        assert_eq!(current_run_id(), None);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["message"], "Segment at 600s failed");
+        assert_eq!(line["segments"], 3);
+        assert_eq!(line["run_id"], run_id.as_str());
+        assert_eq!(line["command"], "transcribe");
+        assert_eq!(line["input"], "abc123");
+        assert_eq!(line["spans"], serde_json::json!(["run", "process_media"]));
+    }
+}
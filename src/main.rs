@@ -1,40 +1,91 @@
 //! Lytt CLI entry point.
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
+use std::process::ExitCode;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
-use lytt::cli::{commands, Cli, Commands};
+use lytt::cli::{commands, exit, picker, Cli, Commands, Output};
+use lytt::audio::clean_orphaned_workspaces;
 use lytt::config::Settings;
+use lytt::logging::{self, JsonLayer, LogFormat};
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    Output::set_quiet(cli.quiet);
 
-    // Initialize logging
+    if let Err(e) = init_logging(&cli) {
+        eprintln!("Error: {:#}", e);
+        return exit::exit_code(&Err(e));
+    }
+
+    // Everything the command logs carries its run ID
+    let run_id = logging::new_run_id();
+    let span = tracing::info_span!("run", run_id = %run_id, command = matches.subcommand_name().unwrap_or_default());
+    let result = logging::scope(run_id, span.clone(), run(cli)).await;
+    if let Err(e) = &result {
+        if logging::is_json() {
+            span.in_scope(|| tracing::error!("Error: {:#}", e));
+        } else {
+            eprintln!("Error: {:#}", e);
+        }
+    }
+    exit::exit_code(&result)
+}
+
+/// Log to stderr as text or JSON (`--log-format`), at the level set by
+/// `--verbose` and `--quiet` or `RUST_LOG`.
+fn init_logging(cli: &Cli) -> Result<()> {
+    let format: LogFormat = cli
+        .log_format
+        .parse()
+        .map_err(lytt::LyttError::InvalidInput)?;
+
+    // JSON logs replace the status messages, so they start at info
     let log_level = match cli.verbose {
+        _ if cli.quiet => "error",
+        0 if format == LogFormat::Json => "info",
         0 => "warn",
         1 => "info",
         2 => "debug",
         _ => "trace",
     };
 
+    let (text, json) = match format {
+        LogFormat::Text => (
+            Some(tracing_subscriber::fmt::layer().with_target(false).with_writer(std::io::stderr)),
+            None,
+        ),
+        LogFormat::Json => (None, Some(JsonLayer::stderr())),
+    };
     tracing_subscriber::registry()
         .with(EnvFilter::new(
             std::env::var("RUST_LOG").unwrap_or_else(|_| format!("lytt={}", log_level)),
         ))
-        .with(tracing_subscriber::fmt::layer().with_target(false))
+        .with(text)
+        .with(json)
         .init();
+    logging::set_format(format);
+    Ok(())
+}
 
+async fn run(cli: Cli) -> Result<()> {
     // Load configuration
-    let settings = match &cli.config {
-        Some(path) => Settings::load_from(Some(&std::path::PathBuf::from(path)))?,
-        None => Settings::load()?,
-    };
+    let config_path = cli.config.as_ref().map(std::path::PathBuf::from);
+    let settings = Settings::load_with_profile(config_path.as_ref(), cli.profile.as_deref())?;
+    lytt::audio::ytdlp::configure(&settings.youtube);
 
     // Ensure data directories exist
     std::fs::create_dir_all(settings.data_dir())?;
     std::fs::create_dir_all(settings.temp_dir())?;
 
+    // Remove what runs that crashed left in the temp directory
+    let freed = clean_orphaned_workspaces(&settings.temp_dir());
+    if freed > 0 {
+        tracing::info!("Removed {} MB left in the temp directory by interrupted runs", freed / (1024 * 1024));
+    }
+
     // Execute command
     match &cli.command {
         Commands::Init => {
@@ -45,48 +96,278 @@ async fn main() -> Result<()> {
             commands::run_doctor(&settings)?;
         }
 
-        Commands::Transcribe { input, force, output, format, chunk, embed, playlist, limit } => {
-            commands::run_transcribe(input, *force, output.clone(), format, *chunk, *embed, *playlist, *limit, settings).await?;
+        Commands::Transcribe {
+            input,
+            force,
+            output,
+            format,
+            chunk,
+            embed,
+            playlist,
+            limit,
+            recursive,
+            excludes,
+            meeting,
+            audiobook,
+            estimate,
+            dry_run,
+            tags,
+            series,
+            title,
+        } => {
+            if *estimate {
+                commands::run_estimate(input, *playlist, *limit, settings).await?;
+            } else if *dry_run {
+                commands::run_dry_run(input, *force, *playlist, *limit, settings).await?;
+            } else {
+                commands::run_transcribe(
+                    input,
+                    *force,
+                    output.clone(),
+                    format,
+                    *chunk,
+                    *embed,
+                    *playlist,
+                    *limit,
+                    *recursive,
+                    excludes,
+                    *meeting,
+                    *audiobook,
+                    tags,
+                    series.as_deref(),
+                    title.as_deref(),
+                    settings,
+                )
+                .await?;
+            }
+        }
+
+        Commands::Record { title, device, tags } => {
+            commands::run_record(title.as_deref(), device.as_deref(), tags, settings).await?;
         }
 
         Commands::Ask {
             question,
             model,
             max_chunks,
+            derived,
+            since,
+            video,
+            series,
+            compare,
+            videos,
+            attach,
+            save,
+            no_cache,
+            multi_query,
+            recent,
+            verify,
+            speak,
+            speak_to,
         } => {
-            commands::run_ask(question, model.clone(), *max_chunks, settings).await?;
+            commands::run_ask(
+                question,
+                model.clone(),
+                *max_chunks,
+                derived,
+                since.as_deref(),
+                video.as_deref(),
+                series.as_deref(),
+                *compare,
+                videos,
+                attach.as_deref(),
+                *save,
+                *no_cache,
+                *multi_query,
+                *recent,
+                *verify,
+                *speak,
+                speak_to.as_deref(),
+                settings,
+            )
+            .await?;
         }
 
         Commands::Search {
             query,
             limit,
             min_score,
+            derived,
+            keyword,
+            topic,
+            series,
+            language,
+            recent,
+        } => {
+            commands::run_search(
+                query,
+                *limit,
+                *min_score,
+                derived,
+                keyword.as_deref(),
+                *topic,
+                series.as_deref(),
+                language.as_deref(),
+                *recent,
+                settings,
+            )
+            .await?;
+        }
+
+        Commands::Grep {
+            pattern,
+            regex,
+            ignore_case,
+            context,
+            video,
+            limit,
         } => {
-            commands::run_search(query, *limit, *min_score, settings).await?;
+            commands::run_grep(pattern, *regex, *ignore_case, *context, video.as_deref(), *limit, settings).await?;
         }
 
-        Commands::Chat { model } => {
-            commands::run_chat(model.clone(), settings).await?;
+        Commands::Chat { model, speak } => {
+            commands::run_chat(model.clone(), *speak, settings).await?;
         }
 
         Commands::Agent { task, video, model } => {
             commands::run_agent(task, video.clone(), model.clone(), settings).await?;
         }
 
-        Commands::List => {
-            commands::run_list(settings).await?;
+        Commands::List {
+            detail,
+            language,
+            sort,
+            source,
+            channel,
+            since,
+            json,
+            table,
+            page,
+            page_size,
+        } => {
+            commands::run_list(
+                *detail,
+                language.as_deref(),
+                sort,
+                source.as_deref(),
+                channel.as_deref(),
+                since.as_deref(),
+                *json,
+                *table,
+                *page,
+                *page_size,
+                settings,
+            )
+            .await?;
+        }
+
+        Commands::Tui => {
+            commands::run_tui(settings).await?;
+        }
+
+        Commands::Rechunk { video_id, stale } => {
+            if *stale {
+                commands::run_rechunk_stale(settings).await?;
+            } else {
+                let video_id = picker::media_id_or_pick(video_id.as_deref(), "Media to rechunk", &settings).await?;
+                commands::run_rechunk(&video_id, settings).await?;
+            }
+        }
+
+        Commands::Reembed { local, yes } => {
+            commands::run_reembed(*local, *yes, settings).await?;
+        }
+
+        Commands::Tag { video_id, tags, remove } => {
+            commands::run_tag(video_id, tags, *remove, settings)?;
+        }
+
+        Commands::Rollup { tag, channel, period, model, list } => {
+            commands::run_rollup(tag.clone(), channel.clone(), period, model.clone(), *list, settings).await?;
+        }
+
+        Commands::Digest { since, format, output, email, model } => {
+            commands::run_digest(since, format, output.clone(), *email, model.clone(), settings).await?;
+        }
+
+        Commands::Summarize { video_id, model, show } => {
+            let video_id = picker::media_id_or_pick(video_id.as_deref(), "Media to summarize", &settings).await?;
+            commands::run_summarize(&video_id, model.clone(), *show, settings).await?;
         }
 
-        Commands::Rechunk { video_id } => {
-            commands::run_rechunk(video_id, settings).await?;
+        Commands::Prune { dry_run } => {
+            commands::run_prune(*dry_run, settings).await?;
         }
 
-        Commands::Export { video_id, output, format } => {
-            commands::run_export(video_id, output.clone(), format, settings).await?;
+        Commands::Dedupe { threshold, min_overlap, delete, yes, format } => {
+            commands::run_dedupe(*threshold, *min_overlap, *delete, *yes, format, settings).await?;
         }
 
-        Commands::Serve { host, port } => {
-            commands::run_serve(host, *port, settings).await?;
+        Commands::Quiz { video_id, format, count, output, model } => {
+            commands::run_quiz(video_id, format, *count, output.clone(), model.clone(), settings).await?;
+        }
+
+        Commands::Pack { tag, output, cards, quotes, model } => {
+            commands::run_pack(tag, output, *cards, *quotes, model.clone(), settings).await?;
+        }
+
+        Commands::Consensus { topic, since, limit, format, model } => {
+            commands::run_consensus(topic, since.as_deref(), *limit, format, model.clone(), settings).await?;
+        }
+
+        Commands::Timeline { topic, since, limit, format, model } => {
+            commands::run_timeline(topic, since.as_deref(), *limit, format, model.clone(), settings).await?;
+        }
+
+        Commands::Topics { clusters, format, list, model } => {
+            commands::run_topics(*clusters, format, *list, model.clone(), settings).await?;
+        }
+
+        Commands::Glossary { video, tag, format, output, refresh, model } => {
+            commands::run_glossary(video.clone(), tag.clone(), format, output.clone(), *refresh, model.clone(), settings).await?;
+        }
+
+        Commands::Export { video_id, all, output, format, embeddings } => {
+            if *all {
+                commands::run_export_all(output.clone(), format, *embeddings, settings).await?;
+            } else {
+                let video_id = picker::media_id_or_pick(video_id.as_deref(), "Media to export", &settings).await?;
+                commands::run_export(&video_id, output.clone(), format, *embeddings, settings).await?;
+            }
+        }
+
+        Commands::Highlights { query, output, count, max_clip, min_score, announce } => {
+            commands::run_highlights(query, output, *count, *max_clip, *min_score, *announce, settings).await?;
+        }
+
+        Commands::Open { target, timestamp, print } => {
+            let target = picker::media_id_or_pick(target.as_deref(), "Media to open", &settings).await?;
+            commands::run_open(&target, timestamp.as_deref(), *print, settings).await?;
+        }
+
+        Commands::ExportNotes { vault, folder, related } => {
+            commands::run_export_notes(vault, folder, *related, settings).await?;
+        }
+
+        Commands::Usage { by, days } => {
+            commands::run_usage(by, *days, settings)?;
+        }
+
+        Commands::History { video_id, limit, json } => {
+            let video_id = picker::media_id_or_pick(video_id.as_deref(), "Media to show history of", &settings).await?;
+            commands::run_history(&video_id, *limit, *json, settings)?;
+        }
+
+        Commands::Stats { top_sources, never_retrieved, limit } => {
+            commands::run_stats(*top_sources, *never_retrieved, *limit, settings)?;
+        }
+
+        Commands::Serve { host, port, replica_of, grpc_port } => {
+            commands::run_serve(host, *port, replica_of.as_deref(), *grpc_port, settings).await?;
+        }
+
+        Commands::Daemon { host, port, grpc_port } => {
+            commands::run_daemon(host, *port, *grpc_port, settings).await?;
         }
 
         Commands::Mcp => {
@@ -96,6 +377,24 @@ async fn main() -> Result<()> {
         Commands::Config { action } => {
             commands::run_config(action, settings)?;
         }
+
+        Commands::Index { action } => {
+            commands::run_index(action, settings)?;
+        }
+        Commands::Db { action } => {
+            commands::run_db(action, settings).await?;
+        }
+        Commands::Cache { action } => {
+            commands::run_cache(action, &settings)?;
+        }
+
+        Commands::Series { action } => {
+            commands::run_series(action, &settings)?;
+        }
+
+        Commands::Transcript { action } => {
+            commands::run_transcript(action, settings).await?;
+        }
     }
 
     Ok(())
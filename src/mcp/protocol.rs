@@ -94,6 +94,7 @@ pub struct InitializeResult {
 #[derive(Debug, Serialize)]
 pub struct ServerCapabilities {
     pub tools: ToolsCapability,
+    pub resources: ResourcesCapability,
 }
 
 #[derive(Debug, Serialize)]
@@ -102,6 +103,13 @@ pub struct ToolsCapability {
     pub list_changed: bool,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesCapability {
+    pub subscribe: bool,
+    pub list_changed: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ServerInfo {
     pub name: String,
@@ -129,6 +137,41 @@ pub struct ToolCallParams {
     pub name: String,
     #[serde(default)]
     pub arguments: Option<Value>,
+    #[serde(rename = "_meta", default)]
+    pub meta: Option<RequestMeta>,
+}
+
+/// Request metadata (`_meta`).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestMeta {
+    /// Token to attach to `notifications/progress` for this request.
+    #[serde(default)]
+    pub progress_token: Option<Value>,
+}
+
+/// JSON-RPC notification (no `id`, no response expected).
+#[derive(Debug, Serialize)]
+pub struct JsonRpcNotification {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: Value,
+}
+
+impl JsonRpcNotification {
+    /// A `notifications/progress` message for a request's progress token.
+    pub fn progress(token: &Value, progress: u32, total: u32, message: &str) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            method: "notifications/progress".to_string(),
+            params: serde_json::json!({
+                "progressToken": token,
+                "progress": progress,
+                "total": total,
+                "message": message,
+            }),
+        }
+    }
 }
 
 /// Tool call response.
@@ -161,3 +204,41 @@ impl ToolCallResult {
         }
     }
 }
+
+/// Resource definition.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub mime_type: String,
+}
+
+/// Resources list response.
+#[derive(Debug, Serialize)]
+pub struct ResourcesListResult {
+    pub resources: Vec<Resource>,
+}
+
+/// Resource read request params.
+#[derive(Debug, Deserialize)]
+pub struct ResourceReadParams {
+    pub uri: String,
+}
+
+/// Resource read response.
+#[derive(Debug, Serialize)]
+pub struct ResourceReadResult {
+    pub contents: Vec<ResourceContents>,
+}
+
+/// Text contents of a resource.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    pub uri: String,
+    pub mime_type: String,
+    pub text: String,
+}
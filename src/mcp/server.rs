@@ -2,22 +2,33 @@
 
 use super::protocol::*;
 use super::tools::get_tools;
+use crate::audio_source::{AudioSource, YoutubeSource};
 use crate::config::Settings;
-use crate::embedding::{Embedder, OpenAIEmbedder};
+use crate::llm::ChatClient;
+use crate::logging;
 use crate::orchestrator::Orchestrator;
-use crate::rag::RagEngine;
+use crate::progress::{ProgressReporter, ProgressStage};
+use crate::rag::{AnswerVerifier, ConfidenceScale, QueryExpander, RagEngine, RecencyRanking};
+use crate::transcription::{format_transcript as format_transcript_as, language_code, OutputFormat};
+use crate::usage::UsageTracker;
+use crate::vector_store::{DerivedFilter, Document, SearchFilter};
 use serde_json::{json, Value};
 use std::io::{self, BufRead, Write};
-use std::sync::Arc;
+use tracing::{error, warn};
 
 const PROTOCOL_VERSION: &str = "2024-11-05";
 const SERVER_NAME: &str = "lytt";
 const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// URI prefix of transcript resources (`lytt://video/{id}`).
+const VIDEO_URI_PREFIX: &str = "lytt://video/";
+
 /// MCP Server for Lytt.
 pub struct McpServer {
     settings: Settings,
     orchestrator: Option<Orchestrator>,
+    /// Periodic WAL checkpoints while the server runs.
+    checkpoints: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl McpServer {
@@ -26,6 +37,7 @@ impl McpServer {
         Self {
             settings,
             orchestrator: None,
+            checkpoints: None,
         }
     }
 
@@ -35,7 +47,7 @@ impl McpServer {
         let mut stdout = io::stdout();
 
         // Log to stderr so it doesn't interfere with JSON-RPC
-        eprintln!("Lytt MCP server starting...");
+        logging::status("Lytt MCP server starting...");
 
         for line in stdin.lock().lines() {
             let line = line?;
@@ -46,7 +58,7 @@ impl McpServer {
             let request: JsonRpcRequest = match serde_json::from_str(&line) {
                 Ok(req) => req,
                 Err(e) => {
-                    eprintln!("Failed to parse request: {}", e);
+                    warn!("Failed to parse request: {}", e);
                     let response = JsonRpcResponse::error(None, -32700, "Parse error");
                     writeln!(stdout, "{}", serde_json::to_string(&response)?)?;
                     stdout.flush()?;
@@ -72,6 +84,8 @@ impl McpServer {
             }
             "tools/list" => self.handle_tools_list(request.id),
             "tools/call" => self.handle_tools_call(request.id, request.params).await,
+            "resources/list" => self.handle_resources_list(request.id).await,
+            "resources/read" => self.handle_resources_read(request.id, request.params).await,
             _ => JsonRpcResponse::error(
                 request.id,
                 -32601,
@@ -85,11 +99,15 @@ impl McpServer {
         // Initialize the orchestrator lazily
         match Orchestrator::new(self.settings.clone()) {
             Ok(orch) => {
+                // Re-initializing replaces the previous checkpoint task
+                if let Some(previous) = std::mem::replace(&mut self.checkpoints, orch.spawn_checkpoints()) {
+                    previous.abort();
+                }
                 self.orchestrator = Some(orch);
-                eprintln!("Orchestrator initialized");
+                logging::status("Orchestrator initialized");
             }
             Err(e) => {
-                eprintln!("Failed to initialize orchestrator: {}", e);
+                error!("Failed to initialize orchestrator: {}", e);
                 return JsonRpcResponse::error(id, -32000, &format!("Init failed: {}", e));
             }
         }
@@ -98,6 +116,10 @@ impl McpServer {
             protocol_version: PROTOCOL_VERSION.to_string(),
             capabilities: ServerCapabilities {
                 tools: ToolsCapability { list_changed: false },
+                resources: ResourcesCapability {
+                    subscribe: false,
+                    list_changed: false,
+                },
             },
             server_info: ServerInfo {
                 name: SERVER_NAME.to_string(),
@@ -130,18 +152,116 @@ impl McpServer {
             None => return JsonRpcResponse::error(id, -32602, "Missing params"),
         };
 
-        let result = match params.name.as_str() {
-            "transcribe" => self.tool_transcribe(params.arguments).await,
-            "search" => self.tool_search(params.arguments).await,
-            "ask" => self.tool_ask(params.arguments).await,
+        // Long tool calls report pipeline stages to clients that asked for progress
+        let reporter = params
+            .meta
+            .as_ref()
+            .and_then(|m| m.progress_token.clone())
+            .map(progress_reporter);
+
+        let call = self.call_tool(&params.name, params.arguments);
+        let result = match reporter {
+            Some(reporter) => reporter.scope(call).await,
+            None => call.await,
+        };
+
+        JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
+    }
+
+    /// Run a tool by name.
+    async fn call_tool(&self, name: &str, arguments: Option<Value>) -> ToolCallResult {
+        match name {
+            "transcribe" => self.tool_transcribe(arguments).await,
+            "search" => self.tool_search(arguments).await,
+            "ask" => self.tool_ask(arguments).await,
             "list_media" => self.tool_list_media().await,
-            "get_transcript" => self.tool_get_transcript(params.arguments).await,
-            _ => ToolCallResult::error(format!("Unknown tool: {}", params.name)),
+            "get_transcript" => self.tool_get_transcript(arguments).await,
+            "delete_media" => self.tool_delete_media(arguments).await,
+            "rechunk" => self.tool_rechunk(arguments).await,
+            "export_transcript" => self.tool_export_transcript(arguments).await,
+            "list_playlist" => self.tool_list_playlist(arguments).await,
+            _ => ToolCallResult::error(format!("Unknown tool: {}", name)),
+        }
+    }
+
+    /// Handle resources/list request: one transcript resource per indexed media item.
+    async fn handle_resources_list(&self, id: Option<Value>) -> JsonRpcResponse {
+        let orchestrator = match &self.orchestrator {
+            Some(o) => o,
+            None => return JsonRpcResponse::error(id, -32000, "Server not initialized"),
         };
 
+        let media = match orchestrator.vector_store().list_videos().await {
+            Ok(media) => media,
+            Err(e) => {
+                return JsonRpcResponse::error(id, -32603, &format!("Failed to list media: {}", e))
+            }
+        };
+
+        let resources = media
+            .into_iter()
+            .map(|item| Resource {
+                uri: format!("{}{}", VIDEO_URI_PREFIX, item.video_id),
+                description: Some(format!(
+                    "Transcript ({} chunks, {})",
+                    item.chunk_count,
+                    format_duration(item.total_duration_seconds)
+                )),
+                name: item.video_title,
+                mime_type: "text/markdown".to_string(),
+            })
+            .collect();
+
+        let result = ResourcesListResult { resources };
         JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
     }
 
+    /// Handle resources/read request for a `lytt://video/{id}` transcript.
+    async fn handle_resources_read(
+        &self,
+        id: Option<Value>,
+        params: Option<Value>,
+    ) -> JsonRpcResponse {
+        let params: ResourceReadParams = match params {
+            Some(p) => match serde_json::from_value(p) {
+                Ok(params) => params,
+                Err(e) => {
+                    return JsonRpcResponse::error(id, -32602, &format!("Invalid params: {}", e))
+                }
+            },
+            None => return JsonRpcResponse::error(id, -32602, "Missing params"),
+        };
+
+        let video_id = match video_id_from_uri(&params.uri) {
+            Some(video_id) => video_id,
+            None => {
+                return JsonRpcResponse::error(id, -32002, &format!("Resource not found: {}", params.uri))
+            }
+        };
+
+        let orchestrator = match &self.orchestrator {
+            Some(o) => o,
+            None => return JsonRpcResponse::error(id, -32000, "Server not initialized"),
+        };
+
+        match orchestrator.vector_store().get_by_video_id(video_id).await {
+            Ok(chunks) if chunks.is_empty() => {
+                JsonRpcResponse::error(id, -32002, &format!("Resource not found: {}", params.uri))
+            }
+            Ok(chunks) => {
+                let result = ResourceReadResult {
+                    contents: vec![ResourceContents {
+                        uri: params.uri,
+                        mime_type: "text/markdown".to_string(),
+                        text: format_transcript(chunks),
+                    }],
+                };
+                JsonRpcResponse::success(id, serde_json::to_value(result).unwrap())
+            }
+            Err(e) => JsonRpcResponse::error(id, -32603, &format!("Failed to read transcript: {}", e)),
+        }
+    }
+
     /// Transcribe tool.
     async fn tool_transcribe(&self, args: Option<Value>) -> ToolCallResult {
         let args = match args {
@@ -163,7 +283,12 @@ impl McpServer {
 
         match orchestrator.process_media(input, force).await {
             Ok(result) => {
-                if result.skipped {
+                if let Some(original) = &result.duplicate_of {
+                    ToolCallResult::text(format!(
+                        "'{}' looks like a duplicate of {} and was skipped. Use force=true to transcribe it anyway.",
+                        result.title, original
+                    ))
+                } else if result.skipped {
                     ToolCallResult::text(format!(
                         "'{}' is already indexed ({} chunks). Use force=true to reprocess.",
                         result.title, result.chunks_indexed
@@ -193,17 +318,21 @@ impl McpServer {
 
         let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
         let min_score = args.get("min_score").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
+        let filter = match search_filter(&args) {
+            Ok(f) => f,
+            Err(e) => return ToolCallResult::error(e),
+        };
 
         let orchestrator = match &self.orchestrator {
             Some(o) => o,
             None => return ToolCallResult::error("Server not initialized".to_string()),
         };
+        if let Err(e) = orchestrator.check_embedding().await {
+            return ToolCallResult::error(e.to_string());
+        }
 
         // Generate query embedding
-        let embedder = OpenAIEmbedder::with_config(
-            &self.settings.embedding.model,
-            self.settings.embedding.dimensions as usize,
-        );
+        let embedder = orchestrator.embedder();
 
         let query_embedding = match embedder.embed(query).await {
             Ok(emb) => emb,
@@ -213,7 +342,7 @@ impl McpServer {
         // Search
         match orchestrator
             .vector_store()
-            .search_with_threshold(&query_embedding, limit, min_score)
+            .search_filtered(&query_embedding, limit, min_score, &filter)
             .await
         {
             Ok(results) => {
@@ -223,12 +352,19 @@ impl McpServer {
 
                 let mut output = format!("Found {} results:\n\n", results.len());
                 for (i, result) in results.iter().enumerate() {
+                    let summary = result
+                        .document
+                        .summary
+                        .as_deref()
+                        .map(|s| format!("   Summary: {}\n", s))
+                        .unwrap_or_default();
                     output.push_str(&format!(
-                        "{}. **{}** @ {}\n   Score: {:.2}\n   {}\n\n",
+                        "{}. **{}** @ {}\n   Score: {:.2}\n{}   {}\n\n",
                         i + 1,
                         result.document.video_title,
                         result.document.format_timestamp(),
                         result.score,
+                        summary,
                         truncate(&result.document.content, 200)
                     ));
                 }
@@ -255,38 +391,74 @@ impl McpServer {
             .get("max_chunks")
             .and_then(|v| v.as_u64())
             .unwrap_or(10) as usize;
+        let filter = match search_filter(&args) {
+            Ok(f) => f,
+            Err(e) => return ToolCallResult::error(e),
+        };
 
         let orchestrator = match &self.orchestrator {
             Some(o) => o,
             None => return ToolCallResult::error("Server not initialized".to_string()),
         };
+        if let Err(e) = orchestrator.check_embedding().await {
+            return ToolCallResult::error(e.to_string());
+        }
 
-        let embedder = Arc::new(OpenAIEmbedder::with_config(
-            &self.settings.embedding.model,
-            self.settings.embedding.dimensions as usize,
-        ));
+        let embedder = orchestrator.embedder();
 
         let engine = RagEngine::new(
             orchestrator.vector_store(),
             embedder,
             &self.settings.rag.model,
             max_chunks,
-        );
-
-        match engine.ask(question).await {
+        )
+        .with_client(ChatClient::from_settings(&self.settings.rag.llm))
+        .with_filter(filter)
+        .with_token_budget(self.settings.rag.context_budget())
+        .with_neighbor_window(self.settings.rag.neighbor_window)
+        .with_min_confidence(self.settings.rag.min_confidence)
+        .with_confidence_scale(ConfidenceScale::from_settings(&self.settings.rag))
+        .with_expansion(QueryExpander::from_settings(&self.settings.rag, &self.settings.rag.model, orchestrator.prompts()))
+        .with_recency(RecencyRanking::from_settings(&self.settings.rag))
+        .with_diversity(self.settings.rag.diversity)
+        .with_routing(self.settings.rag.routing)
+        .with_verifier(AnswerVerifier::from_settings(&self.settings.rag, &self.settings.rag.model, orchestrator.prompts()))
+        .with_cache(self.settings.rag.cache.ttl());
+
+        let tracker = UsageTracker::new();
+        let result = tracker.scope(engine.ask(question)).await;
+        orchestrator.record_usage("ask", None, &tracker).await;
+
+        match result {
             Ok(response) => {
                 let mut output = format!("{}\n\n", response.answer);
+                if let Some(confidence) = &response.confidence {
+                    output.push_str(&format!("**Confidence:** {:.2}\n\n", confidence.score));
+                }
 
                 if !response.sources.is_empty() {
                     output.push_str("**Sources:**\n");
-                    for source in &response.sources {
+                    for (i, source) in response.sources.iter().enumerate() {
                         output.push_str(&format!(
-                            "- {} @ {} (score: {:.2})\n",
-                            source.video_title, source.timestamp, source.score
+                            "- [{}] {} @ {} (score: {:.2})\n",
+                            i + 1,
+                            source.video_title,
+                            source.timestamp,
+                            source.score
                         ));
                     }
                 }
 
+                if let Some(verification) = &response.verification {
+                    let flagged: Vec<_> = verification.flagged().collect();
+                    if !flagged.is_empty() {
+                        output.push_str("\n**Not supported by the sources:**\n");
+                        for check in flagged {
+                            output.push_str(&format!("- ({}) {}\n", check.verdict, check.claim));
+                        }
+                    }
+                }
+
                 ToolCallResult::text(output)
             }
             Err(e) => ToolCallResult::error(format!("RAG query failed: {}", e)),
@@ -345,27 +517,206 @@ impl McpServer {
             Ok(chunks) if chunks.is_empty() => {
                 ToolCallResult::error(format!("No transcript found for video ID: {}", video_id))
             }
-            Ok(mut chunks) => {
-                // Sort by start time
-                chunks.sort_by(|a, b| a.start_seconds.partial_cmp(&b.start_seconds).unwrap());
+            Ok(chunks) => ToolCallResult::text(format_transcript(chunks)),
+            Err(e) => ToolCallResult::error(format!("Failed to get transcript: {}", e)),
+        }
+    }
 
-                let title = chunks.first().map(|c| c.video_title.clone()).unwrap_or_default();
-                let mut output = format!("**{}**\n\n", title);
+    /// Delete media tool.
+    async fn tool_delete_media(&self, args: Option<Value>) -> ToolCallResult {
+        let video_id = match video_id_arg(&args) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
 
-                for chunk in &chunks {
-                    let timestamp = chunk.format_timestamp();
-                    let section = chunk.section_title.as_deref().unwrap_or("Segment");
-                    output.push_str(&format!(
-                        "[{}] **{}**\n{}\n\n",
-                        timestamp, section, chunk.content
-                    ));
-                }
+        let orchestrator = match &self.orchestrator {
+            Some(o) => o,
+            None => return ToolCallResult::error("Server not initialized".to_string()),
+        };
 
-                ToolCallResult::text(output)
-            }
-            Err(e) => ToolCallResult::error(format!("Failed to get transcript: {}", e)),
+        match orchestrator.delete_media(video_id).await {
+            Ok(0) => ToolCallResult::error(format!("No indexed media with ID: {}", video_id)),
+            Ok(deleted) => ToolCallResult::text(format!(
+                "Deleted {} ({} chunks) from the knowledge base.",
+                video_id, deleted
+            )),
+            Err(e) => ToolCallResult::error(format!("Delete failed: {}", e)),
         }
     }
+
+    /// Rechunk tool.
+    async fn tool_rechunk(&self, args: Option<Value>) -> ToolCallResult {
+        let video_id = match video_id_arg(&args) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
+
+        let orchestrator = match &self.orchestrator {
+            Some(o) => o,
+            None => return ToolCallResult::error("Server not initialized".to_string()),
+        };
+
+        match orchestrator.rechunk_media(video_id).await {
+            Ok(result) => ToolCallResult::text(format!(
+                "Rechunked '{}' ({} chunks indexed).",
+                result.title, result.chunks_indexed
+            )),
+            Err(e) => ToolCallResult::error(format!("Rechunk failed: {}", e)),
+        }
+    }
+
+    /// Export transcript tool.
+    async fn tool_export_transcript(&self, args: Option<Value>) -> ToolCallResult {
+        let video_id = match video_id_arg(&args) {
+            Ok(id) => id,
+            Err(e) => return e,
+        };
+
+        let format: OutputFormat = match args
+            .as_ref()
+            .and_then(|a| a.get("format"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("json")
+            .parse()
+        {
+            Ok(f) => f,
+            Err(e) => return ToolCallResult::error(e),
+        };
+
+        let orchestrator = match &self.orchestrator {
+            Some(o) => o,
+            None => return ToolCallResult::error("Server not initialized".to_string()),
+        };
+
+        let id = video_id.to_string();
+        match orchestrator.sqlite_store().blocking(move |store| store.get_transcript(&id)).await {
+            Ok(Some((_, transcript))) => ToolCallResult::text(format_transcript_as(&transcript, format)),
+            Ok(None) => ToolCallResult::error(format!("No stored transcript for video ID: {}", video_id)),
+            Err(e) => ToolCallResult::error(format!("Failed to export transcript: {}", e)),
+        }
+    }
+
+    /// List playlist tool.
+    async fn tool_list_playlist(&self, args: Option<Value>) -> ToolCallResult {
+        let args = match args {
+            Some(a) => a,
+            None => return ToolCallResult::error("Missing arguments".to_string()),
+        };
+
+        let url = match args.get("url").and_then(|v| v.as_str()) {
+            Some(u) => u,
+            None => return ToolCallResult::error("Missing 'url' argument".to_string()),
+        };
+
+        let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
+
+        let orchestrator = match &self.orchestrator {
+            Some(o) => o,
+            None => return ToolCallResult::error("Server not initialized".to_string()),
+        };
+
+        let source = YoutubeSource::new();
+        if !source.can_handle(url) {
+            return ToolCallResult::error(format!("Not a YouTube playlist or channel URL: {}", url));
+        }
+
+        let videos = match source.list_media(url, Some(limit)).await {
+            Ok(videos) => videos,
+            Err(e) => return ToolCallResult::error(format!("Failed to list playlist: {}", e)),
+        };
+
+        if videos.is_empty() {
+            return ToolCallResult::text("No videos found in playlist.".to_string());
+        }
+
+        let mut output = format!("Playlist contains {} videos:\n\n", videos.len());
+        for video in &videos {
+            let indexed = orchestrator
+                .vector_store()
+                .is_video_indexed(&video.id)
+                .await
+                .unwrap_or(false);
+            let duration = video
+                .duration_seconds
+                .map(|d| format!(", {}", format_duration(d as f64)))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "- **{}** (ID: {}{}){}\n",
+                video.title,
+                video.id,
+                duration,
+                if indexed { " [indexed]" } else { "" }
+            ));
+        }
+
+        ToolCallResult::text(output)
+    }
+}
+
+/// A reporter that writes each stage as a `notifications/progress` message to stdout.
+fn progress_reporter(token: Value) -> ProgressReporter {
+    ProgressReporter::new(move |update| {
+        let notification =
+            JsonRpcNotification::progress(&token, update.stage.step(), ProgressStage::TOTAL, &update.message);
+        if let Ok(line) = serde_json::to_string(&notification) {
+            let mut stdout = io::stdout().lock();
+            let _ = writeln!(stdout, "{}", line);
+            let _ = stdout.flush();
+        }
+    })
+}
+
+/// Get the required "video_id" argument.
+fn video_id_arg(args: &Option<Value>) -> Result<&str, ToolCallResult> {
+    let args = args
+        .as_ref()
+        .ok_or_else(|| ToolCallResult::error("Missing arguments".to_string()))?;
+    args.get("video_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolCallResult::error("Missing 'video_id' argument".to_string()))
+}
+
+/// Format a media item's chunks as a Markdown transcript, in time order.
+fn format_transcript(mut chunks: Vec<Document>) -> String {
+    chunks.sort_by(|a, b| a.start_seconds.partial_cmp(&b.start_seconds).unwrap());
+
+    let title = chunks.first().map(|c| c.video_title.clone()).unwrap_or_default();
+    let mut output = format!("**{}**\n\n", title);
+
+    for chunk in &chunks {
+        let timestamp = chunk.format_timestamp();
+        let section = chunk.section_title.as_deref().unwrap_or("Segment");
+        output.push_str(&format!(
+            "[{}] **{}**\n{}\n\n",
+            timestamp, section, chunk.content
+        ));
+    }
+
+    output
+}
+
+/// Extract the media ID from a `lytt://video/{id}` resource URI.
+fn video_id_from_uri(uri: &str) -> Option<&str> {
+    uri.strip_prefix(VIDEO_URI_PREFIX).filter(|id| !id.is_empty())
+}
+
+/// Parse the optional "derived", "video_id", "keyword" and "language" arguments into a search filter.
+fn search_filter(args: &Value) -> Result<SearchFilter, String> {
+    let derived: DerivedFilter = match args.get("derived").and_then(|v| v.as_str()) {
+        Some(s) => s.parse()?,
+        None => DerivedFilter::default(),
+    };
+    let mut filter = SearchFilter::default().with_derived(derived);
+    if let Some(video_id) = args.get("video_id").and_then(|v| v.as_str()) {
+        filter = filter.with_video(video_id);
+    }
+    if let Some(keyword) = args.get("keyword").and_then(|v| v.as_str()) {
+        filter = filter.with_keyword(keyword);
+    }
+    if let Some(language) = args.get("language").and_then(|v| v.as_str()) {
+        filter = filter.with_language(language_code(language).unwrap_or_else(|| language.to_string()));
+    }
+    Ok(filter)
 }
 
 /// Truncate text with ellipsis.
@@ -392,3 +743,37 @@ fn format_duration(seconds: f64) -> String {
         format!("{}s", secs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_video_id_from_uri() {
+        assert_eq!(video_id_from_uri("lytt://video/dQw4w9WgXcQ"), Some("dQw4w9WgXcQ"));
+        assert_eq!(
+            video_id_from_uri("lytt://video/local__home_me_REC_0043.m4a"),
+            Some("local__home_me_REC_0043.m4a")
+        );
+        assert_eq!(video_id_from_uri("lytt://video/"), None);
+        assert_eq!(video_id_from_uri("file:///tmp/x"), None);
+    }
+
+    #[test]
+    fn test_progress_token() {
+        let params: ToolCallParams = serde_json::from_value(json!({
+            "name": "transcribe",
+            "arguments": {"input": "abc"},
+            "_meta": {"progressToken": 7}
+        }))
+        .unwrap();
+        let token = params.meta.and_then(|m| m.progress_token).unwrap();
+
+        let notification = JsonRpcNotification::progress(&token, 3, ProgressStage::TOTAL, "Transcribing");
+        let value = serde_json::to_value(&notification).unwrap();
+        assert_eq!(value["method"], "notifications/progress");
+        assert_eq!(value["params"]["progressToken"], 7);
+        assert_eq!(value["params"]["total"], 5);
+        assert!(value.get("id").is_none());
+    }
+}
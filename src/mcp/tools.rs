@@ -48,6 +48,24 @@ pub fn get_tools() -> Vec<Tool> {
                         "type": "number",
                         "description": "Minimum similarity score (0.0-1.0)",
                         "default": 0.3
+                    },
+                    "derived": {
+                        "type": "string",
+                        "enum": ["include", "exclude", "only"],
+                        "description": "Whether to search derived documents (rollups, summaries)",
+                        "default": "include"
+                    },
+                    "video_id": {
+                        "type": "string",
+                        "description": "Only search this video (see list_media for IDs)"
+                    },
+                    "keyword": {
+                        "type": "string",
+                        "description": "Only chunks tagged with this extracted keyword"
+                    },
+                    "language": {
+                        "type": "string",
+                        "description": "Only media in this spoken language (ISO 639-1 code, e.g. \"en\")"
                     }
                 },
                 "required": ["query"]
@@ -67,8 +85,26 @@ pub fn get_tools() -> Vec<Tool> {
                     },
                     "max_chunks": {
                         "type": "integer",
-                        "description": "Maximum context chunks to include",
+                        "description": "Maximum context chunks to include (when no context token budget is configured)",
                         "default": 10
+                    },
+                    "derived": {
+                        "type": "string",
+                        "enum": ["include", "exclude", "only"],
+                        "description": "Whether to search derived documents (rollups, summaries)",
+                        "default": "include"
+                    },
+                    "video_id": {
+                        "type": "string",
+                        "description": "Only answer from this video (see list_media for IDs)"
+                    },
+                    "keyword": {
+                        "type": "string",
+                        "description": "Only use chunks tagged with this extracted keyword"
+                    },
+                    "language": {
+                        "type": "string",
+                        "description": "Only use media in this spoken language (ISO 639-1 code, e.g. \"en\")"
                     }
                 },
                 "required": ["question"]
@@ -99,5 +135,79 @@ pub fn get_tools() -> Vec<Tool> {
                 "required": ["video_id"]
             }),
         },
+        Tool {
+            name: "delete_media".to_string(),
+            description: "Remove an indexed video from the knowledge base, including its transcript, \
+                summary and tags. This cannot be undone."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "video_id": {
+                        "type": "string",
+                        "description": "The video ID to delete"
+                    }
+                },
+                "required": ["video_id"]
+            }),
+        },
+        Tool {
+            name: "rechunk".to_string(),
+            description: "Re-chunk and re-embed an indexed video from its stored transcript, \
+                using the current chunking settings. Does not re-transcribe."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "video_id": {
+                        "type": "string",
+                        "description": "The video ID to rechunk"
+                    }
+                },
+                "required": ["video_id"]
+            }),
+        },
+        Tool {
+            name: "export_transcript".to_string(),
+            description: "Export the stored transcript of an indexed video as JSON, SRT subtitles or WebVTT."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "video_id": {
+                        "type": "string",
+                        "description": "The video ID to export"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["json", "srt", "vtt"],
+                        "description": "Output format",
+                        "default": "json"
+                    }
+                },
+                "required": ["video_id"]
+            }),
+        },
+        Tool {
+            name: "list_playlist".to_string(),
+            description: "List the videos in a YouTube playlist or channel without transcribing them, \
+                marking those already indexed. Use this to decide what to transcribe."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "YouTube playlist or channel URL"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of videos to list",
+                        "default": 50
+                    }
+                },
+                "required": ["url"]
+            }),
+        },
     ]
 }
@@ -0,0 +1,317 @@
+//! Meeting recordings.
+//!
+//! Zoom, Teams and Meet exports leave files next to the recording: caption
+//! transcripts (WebVTT) naming who spoke, and for Zoom cloud recordings JSON
+//! with the meeting topic, start time, participants and an active-speaker
+//! timeline. Zoom's local recordings sit in a folder named after the date and
+//! topic. These give the speakers in the transcript and the attendees.
+
+use crate::audio_source::{LocalSource, MediaMetadata};
+use crate::error::Result;
+use crate::transcription::{parse_cue_time, parse_vtt, Transcript, TranscriptSegment};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{debug, warn};
+
+/// Prefix of the tags naming meeting attendees.
+pub const ATTENDEE_TAG_PREFIX: &str = "attendee:";
+
+/// Tag for media an attendee was in.
+pub fn attendee_tag(name: &str) -> String {
+    format!("{}{}", ATTENDEE_TAG_PREFIX, name)
+}
+
+/// What a recording's sidecar files say about the meeting.
+#[derive(Debug, Clone, Default)]
+pub struct Meeting {
+    /// Meeting topic.
+    pub title: Option<String>,
+    /// When the meeting started.
+    pub started_at: Option<DateTime<Utc>>,
+    /// Participants and speakers, in order of appearance.
+    pub attendees: Vec<String>,
+    /// Exported caption transcript, speakers named at each change of speaker.
+    pub captions: Vec<TranscriptSegment>,
+    /// Active speaker from each start time on (seconds), in order: from the
+    /// timeline, or else the captions.
+    pub turns: Vec<(f64, String)>,
+}
+
+impl Meeting {
+    /// Read the sidecar files of a recording. Returns `None` if it has none.
+    ///
+    /// Sidecars are files in the same directory whose name starts like the
+    /// recording's, or any there if the recording is the only media file.
+    pub fn from_sidecars(recording: &Path) -> Result<Option<Self>> {
+        let dir = recording.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let mut meeting = Meeting::default();
+        if let Some((title, started_at)) = dir.file_name().and_then(|n| parse_folder_name(&n.to_string_lossy())) {
+            meeting.title = Some(title);
+            meeting.started_at = started_at;
+        }
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect();
+        files.sort();
+        let sole_recording = files.iter().filter(|p| LocalSource::is_media_file(p)).count() == 1;
+        let stem = name_stem(recording);
+        let sidecars: Vec<&PathBuf> = files
+            .iter()
+            .filter(|p| *p != recording)
+            .filter(|p| sole_recording || stem.starts_with(&name_stem(p)))
+            .collect();
+
+        // Zoom exports closed captions too; the transcript names the speakers
+        let mut vtts: Vec<&&PathBuf> = sidecars.iter().filter(|p| has_extension(p, "vtt")).collect();
+        vtts.sort_by_key(|p| !p.to_string_lossy().to_lowercase().contains("transcript"));
+        let mut caption_turns = Vec::new();
+        if let Some(vtt) = vtts.first() {
+            debug!("Reading meeting captions from {}", vtt.display());
+            (meeting.captions, caption_turns) = speaker_captions(&std::fs::read_to_string(vtt)?);
+        }
+
+        for json in sidecars.iter().filter(|p| has_extension(p, "json")) {
+            match serde_json::from_str::<Value>(&std::fs::read_to_string(json)?) {
+                Ok(value) => meeting.read_json(&value),
+                Err(e) => warn!("Skipping {}: {}", json.display(), e),
+            }
+        }
+
+        if meeting.turns.is_empty() {
+            meeting.turns = caption_turns;
+        }
+        let speakers: Vec<String> = meeting.turns.iter().map(|(_, name)| name.clone()).collect();
+        for name in speakers {
+            meeting.add_attendee(&name);
+        }
+
+        let found = meeting.title.is_some() || !meeting.attendees.is_empty() || !meeting.captions.is_empty();
+        Ok(found.then_some(meeting))
+    }
+
+    /// Use the meeting's title and start time for the media.
+    pub fn apply(&self, metadata: &mut MediaMetadata) {
+        if let Some(title) = &self.title {
+            metadata.title = title.clone();
+        }
+        if self.started_at.is_some() {
+            metadata.published_at = self.started_at;
+        }
+    }
+
+    /// The exported caption transcript, if there is one.
+    pub fn transcript(&self, media_id: &str) -> Option<Transcript> {
+        (!self.captions.is_empty()).then(|| Transcript::new(media_id.to_string(), self.captions.clone()))
+    }
+
+    /// Name the speaker at the start of each segment where the speaker
+    /// changes, for a transcript made from the audio.
+    pub fn label_speakers(&self, transcript: &mut Transcript) {
+        if self.turns.is_empty() {
+            return;
+        }
+        let segments = label_turns(&transcript.segments, |segment| {
+            let middle = (segment.start_seconds + segment.end_seconds) / 2.0;
+            self.turns.iter().take_while(|(start, _)| *start <= middle).last().map(|(_, name)| name.clone())
+        });
+        *transcript = Transcript::new(transcript.video_id.clone(), segments)
+            .with_language(transcript.language.clone())
+            .with_chapters(std::mem::take(&mut transcript.chapters));
+    }
+
+    /// Take what a Zoom JSON file holds: the recording's topic and start
+    /// time, participants, or the active-speaker timeline.
+    fn read_json(&mut self, value: &Value) {
+        if let Some(topic) = value["topic"].as_str().filter(|t| !t.is_empty()) {
+            self.title = Some(topic.to_string());
+        }
+        if let Some(start) = value["start_time"].as_str().and_then(|s| DateTime::parse_from_rfc3339(s).ok()) {
+            self.started_at = Some(start.with_timezone(&Utc));
+        }
+
+        let participants = value["participants"].as_array().or_else(|| value.as_array());
+        for participant in participants.into_iter().flatten() {
+            let name = participant
+                .as_str()
+                .or_else(|| ["name", "user_name", "display_name"].iter().find_map(|k| participant[k].as_str()));
+            if let Some(name) = name {
+                self.add_attendee(name);
+            }
+        }
+
+        for entry in value["timeline"].as_array().into_iter().flatten() {
+            let start = entry["ts"].as_str().and_then(parse_cue_time);
+            let name = entry["users"][0]["username"].as_str().map(str::trim).filter(|n| !n.is_empty());
+            if let (Some(start), Some(name)) = (start, name) {
+                if self.turns.last().is_none_or(|(_, last)| last != name) {
+                    self.turns.push((start, name.to_string()));
+                }
+            }
+        }
+    }
+
+    fn add_attendee(&mut self, name: &str) {
+        let name = name.trim();
+        if !name.is_empty() && !self.attendees.iter().any(|a| a.eq_ignore_ascii_case(name)) {
+            self.attendees.push(name.to_string());
+        }
+    }
+}
+
+/// Caption segments with the speaker named where it changes, and the
+/// speaker turns.
+///
+/// Teams marks speakers with voice tags (`<v Alice Smith>`), Zoom and Meet
+/// start each cue with `Alice Smith: `. Captions where fewer than half the
+/// cues name a speaker are taken to name none.
+fn speaker_captions(vtt: &str) -> (Vec<TranscriptSegment>, Vec<(f64, String)>) {
+    static VOICE: OnceLock<Regex> = OnceLock::new();
+    let voice = VOICE.get_or_init(|| Regex::new(r"<v(?:\.[\w.-]+)*\s+([^>]+)>").expect("valid voice tag regex"));
+    let segments = parse_vtt(&voice.replace_all(vtt, "$1: "));
+
+    let named = segments.iter().filter(|s| split_speaker(&s.text).is_some()).count();
+    if named * 2 < segments.len() {
+        return (segments, Vec::new());
+    }
+    let mut turns: Vec<(f64, String)> = Vec::new();
+    let segments = label_turns(&segments, |segment| {
+        match split_speaker(&segment.text) {
+            Some((name, _)) if turns.last().is_none_or(|(_, last)| last != name) => {
+                turns.push((segment.start_seconds, name.to_string()));
+            }
+            _ => {}
+        }
+        turns.last().map(|(_, name)| name.clone())
+    });
+    (segments, turns)
+}
+
+/// Segments with `Name: ` before each one where the speaker changes. A
+/// segment already starting with its speaker's name loses it first.
+fn label_turns(
+    segments: &[TranscriptSegment],
+    mut speaker_of: impl FnMut(&TranscriptSegment) -> Option<String>,
+) -> Vec<TranscriptSegment> {
+    let mut previous: Option<String> = None;
+    segments
+        .iter()
+        .map(|segment| {
+            let speaker = speaker_of(segment);
+            let text = match split_speaker(&segment.text) {
+                Some((name, text)) if speaker.as_deref() == Some(name) => text,
+                _ => segment.text.as_str(),
+            };
+            let text = match &speaker {
+                Some(name) if previous.as_ref() != Some(name) => format!("{}: {}", name, text),
+                _ => text.to_string(),
+            };
+            previous = speaker;
+            TranscriptSegment::new(segment.start_seconds, segment.end_seconds, text)
+        })
+        .collect()
+}
+
+/// Split `Name: text` into the speaker's name and the text, for names of up
+/// to four words.
+pub(crate) fn split_speaker(text: &str) -> Option<(&str, &str)> {
+    let (name, rest) = text.split_once(": ")?;
+    let words = name.split_whitespace().count();
+    let plausible = (1..=4).contains(&words)
+        && name.chars().next().is_some_and(|c| c.is_alphabetic())
+        && !name.contains(['.', '!', '?', ',', '"']);
+    plausible.then(|| (name.trim(), rest.trim_start()))
+}
+
+/// Title and start of a Zoom local recording folder, named like
+/// `2024-03-01 10.00.00 Weekly Sync 85012345678`.
+fn parse_folder_name(name: &str) -> Option<(String, Option<DateTime<Utc>>)> {
+    static FOLDER: OnceLock<Regex> = OnceLock::new();
+    let folder = FOLDER.get_or_init(|| {
+        Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}\.\d{2}\.\d{2}) (.+?)(?: \d{9,11})?$").expect("valid folder regex")
+    });
+    let caps = folder.captures(name)?;
+    let started_at = NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H.%M.%S")
+        .ok()
+        .and_then(|t| Local.from_local_datetime(&t).single())
+        .map(|t| t.with_timezone(&Utc));
+    Some((caps[2].to_string(), started_at))
+}
+
+/// File name up to its first dot: `GMT20240301-100000_Recording` for
+/// `GMT20240301-100000_Recording.transcript.vtt`.
+fn name_stem(path: &Path) -> String {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    name.split('.').next().unwrap_or_default().to_string()
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speaker_captions() {
+        let teams = "WEBVTT\n\n00:00:01.000 --> 00:00:04.000\n<v Alice Smith>Let's start.</v>\n\n\
+                     00:00:04.000 --> 00:00:06.000\n<v Alice Smith>I'll send the report Friday.</v>\n\n\
+                     00:00:06.000 --> 00:00:09.000\n<v Bob>Sounds good.</v>\n";
+        let (segments, turns) = speaker_captions(teams);
+        let texts: Vec<String> = segments.into_iter().map(|s| s.text).collect();
+        assert_eq!(texts, ["Alice Smith: Let's start.", "I'll send the report Friday.", "Bob: Sounds good."]);
+        assert_eq!(turns, [(1.0, "Alice Smith".to_string()), (6.0, "Bob".to_string())]);
+
+        let plain = "WEBVTT\n\n00:00:01.000 --> 00:00:04.000\nNote: this is not a speaker.\n\n\
+                     00:00:04.000 --> 00:00:06.000\nJust captions.\n\n00:00:06.000 --> 00:00:08.000\nMore.\n";
+        let (segments, turns) = speaker_captions(plain);
+        assert_eq!(segments[0].text, "Note: this is not a speaker.");
+        assert!(turns.is_empty());
+    }
+
+    #[test]
+    fn test_from_sidecars() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("2024-03-01 10.00.00 Weekly Sync 85012345678");
+        std::fs::create_dir(&dir).unwrap();
+        let recording = dir.join("GMT20240301-100000_Recording.m4a");
+        std::fs::write(&recording, b"audio").unwrap();
+        std::fs::write(
+            dir.join("GMT20240301-100000_Recording.timeline.json"),
+            r#"{"timeline": [
+                {"ts": "00:00:00.000", "users": [{"username": "Alice"}]},
+                {"ts": "00:00:05.000", "users": [{"username": "Alice"}]},
+                {"ts": "00:00:10.000", "users": [{"username": "Bob"}]}
+            ]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("participants.json"), r#"{"participants": [{"name": "Carol"}, {"name": "Alice"}]}"#)
+            .unwrap();
+
+        let meeting = Meeting::from_sidecars(&recording).unwrap().unwrap();
+        assert_eq!(meeting.title.as_deref(), Some("Weekly Sync"));
+        assert!(meeting.started_at.is_some());
+        assert_eq!(meeting.attendees, ["Carol", "Alice", "Bob"]);
+        assert_eq!(meeting.turns, [(0.0, "Alice".to_string()), (10.0, "Bob".to_string())]);
+
+        let mut transcript = Transcript::new(
+            "m".to_string(),
+            vec![
+                TranscriptSegment::new(0.0, 4.0, "Hello.".to_string()),
+                TranscriptSegment::new(4.0, 8.0, "I'll do it.".to_string()),
+                TranscriptSegment::new(9.0, 14.0, "Thanks.".to_string()),
+            ],
+        );
+        meeting.label_speakers(&mut transcript);
+        assert_eq!(transcript.full_text, "Alice: Hello. I'll do it. Bob: Thanks.");
+
+        let lonely = root.path().join("call.mp3");
+        std::fs::write(&lonely, b"audio").unwrap();
+        assert!(Meeting::from_sidecars(&lonely).unwrap().is_none());
+    }
+}
@@ -0,0 +1,288 @@
+//! Markdown note export for PKM tools such as Obsidian.
+//!
+//! Each media item becomes one note with YAML frontmatter, its chunks as
+//! sections with timestamp links, and `[[wikilinks]]` to the most similar
+//! other media, so the vault's backlinks connect related videos.
+
+use crate::error::Result;
+use crate::rollup::CHANNEL_TAG_PREFIX;
+use crate::summary::MediaSummary;
+use crate::vector_store::{cosine_similarity, DocType, Document, SqliteVectorStore, VectorStore};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Minimum similarity for two media to link to each other.
+const RELATED_MIN_SCORE: f32 = 0.5;
+
+/// A media item prepared for export.
+struct NoteSource {
+    video_id: String,
+    title: String,
+    file_stem: String,
+    docs: Vec<Document>,
+    tags: Vec<String>,
+    summary: Option<MediaSummary>,
+    /// Mean of the chunk embeddings.
+    centroid: Vec<f32>,
+}
+
+/// Writes one Markdown note per media item into a vault directory.
+pub struct NoteExporter {
+    store: std::sync::Arc<SqliteVectorStore>,
+    related: usize,
+}
+
+impl NoteExporter {
+    /// Create a new exporter.
+    pub fn new(store: std::sync::Arc<SqliteVectorStore>) -> Self {
+        Self { store, related: 5 }
+    }
+
+    /// Set the maximum number of related media linked from each note.
+    pub fn with_related(mut self, related: usize) -> Self {
+        self.related = related;
+        self
+    }
+
+    /// Export all media into `dir`, returning the written note paths.
+    pub async fn export(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(dir)?;
+
+        let mut sources = Vec::new();
+        let mut stems = HashSet::new();
+
+        for video in self.store.list_videos().await? {
+            let mut docs = self.store.get_by_video_id(&video.video_id).await?;
+            // Rollups and summaries aren't media
+            docs.retain(|d| d.doc_type == DocType::Source);
+            if docs.is_empty() {
+                continue;
+            }
+            docs.sort_by_key(|d| d.chunk_order);
+
+            let mut file_stem = sanitize_file_name(&video.video_title);
+            if !stems.insert(file_stem.clone()) {
+                file_stem = format!("{} ({})", file_stem, video.video_id);
+                stems.insert(file_stem.clone());
+            }
+
+            sources.push(NoteSource {
+                centroid: centroid(&docs),
+                tags: self.store.get_tags(&video.video_id)?,
+                summary: self.store.get_summary(&video.video_id)?,
+                video_id: video.video_id,
+                title: video.video_title,
+                file_stem,
+                docs,
+            });
+        }
+
+        let mut paths = Vec::with_capacity(sources.len());
+        for (i, source) in sources.iter().enumerate() {
+            let related = related_notes(&sources, i, self.related);
+            let path = dir.join(format!("{}.md", source.file_stem));
+            std::fs::write(&path, render_note(source, &related))?;
+            paths.push(path);
+        }
+
+        info!("Exported {} notes to {:?}", paths.len(), dir);
+        Ok(paths)
+    }
+}
+
+/// Mean embedding of a media item's chunks.
+fn centroid(docs: &[Document]) -> Vec<f32> {
+    let dims = docs.first().map(|d| d.embedding.len()).unwrap_or(0);
+    let mut sum = vec![0.0f32; dims];
+    for doc in docs.iter().filter(|d| d.embedding.len() == dims) {
+        for (s, v) in sum.iter_mut().zip(&doc.embedding) {
+            *s += v;
+        }
+    }
+    sum
+}
+
+/// The most similar other notes to `sources[index]`, as (file stem, score).
+fn related_notes(sources: &[NoteSource], index: usize, limit: usize) -> Vec<(String, f32)> {
+    let this = &sources[index];
+    let mut scored: Vec<(String, f32)> = sources
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, other)| (other.file_stem.clone(), cosine_similarity(&this.centroid, &other.centroid)))
+        .filter(|(_, score)| *score >= RELATED_MIN_SCORE)
+        .collect();
+
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(limit);
+    scored
+}
+
+/// Render a note.
+fn render_note(source: &NoteSource, related: &[(String, f32)]) -> String {
+    let channel = source
+        .tags
+        .iter()
+        .find_map(|t| t.strip_prefix(CHANNEL_TAG_PREFIX));
+    let tags: Vec<String> = source
+        .tags
+        .iter()
+        .filter(|t| !t.starts_with(CHANNEL_TAG_PREFIX))
+        .map(|t| t.replace(char::is_whitespace, "-"))
+        .collect();
+    let first = &source.docs[0];
+    let date = first.source_created_at.unwrap_or(first.indexed_at);
+    let duration = source.docs.iter().map(|d| d.end_seconds).fold(0.0f64, f64::max);
+    let url = media_url(&source.video_id);
+
+    let mut note = String::from("---\n");
+    note.push_str(&format!("title: {}\n", yaml_string(&source.title)));
+    note.push_str(&format!("video_id: {}\n", yaml_string(&source.video_id)));
+    if let Some(channel) = channel {
+        note.push_str(&format!("channel: {}\n", yaml_string(channel)));
+    }
+    note.push_str(&format!("date: {}\n", date.format("%Y-%m-%d")));
+    note.push_str(&format!("duration: {}\n", yaml_string(&format_seconds(duration))));
+    if let Some(url) = &url {
+        note.push_str(&format!("source: {}\n", yaml_string(url)));
+    }
+    if tags.is_empty() {
+        note.push_str("tags: []\n");
+    } else {
+        note.push_str("tags:\n");
+        for tag in &tags {
+            note.push_str(&format!("  - {}\n", yaml_string(tag)));
+        }
+    }
+    note.push_str("---\n\n");
+
+    note.push_str(&format!("# {}\n\n", source.title));
+    if let Some(summary) = &source.summary {
+        note.push_str(&format!("{}\n\n", summary.overview.trim()));
+    }
+
+    note.push_str("## Transcript\n");
+    for doc in &source.docs {
+        let timestamp = doc.format_timestamp();
+        let anchor = match &url {
+            Some(url) => format!("[{}]({}&t={}s)", timestamp, url, doc.start_seconds as u32),
+            None => timestamp,
+        };
+        match &doc.section_title {
+            Some(title) => note.push_str(&format!("\n### {} {}\n\n", anchor, title)),
+            None => note.push_str(&format!("\n### {}\n\n", anchor)),
+        }
+        note.push_str(doc.content.trim());
+        note.push('\n');
+    }
+
+    if !related.is_empty() {
+        note.push_str("\n## Related\n\n");
+        for (stem, score) in related {
+            note.push_str(&format!("- [[{}]] ({:.2})\n", stem, score));
+        }
+    }
+
+    note
+}
+
+/// Deep-linkable URL for a media item, if it has one.
+fn media_url(video_id: &str) -> Option<String> {
+    if video_id.starts_with("local_") {
+        None
+    } else {
+        Some(format!("https://youtube.com/watch?v={}", video_id))
+    }
+}
+
+/// Format seconds as H:MM:SS or MM:SS.
+fn format_seconds(seconds: f64) -> String {
+    let total = seconds as u32;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+/// Quote a value for YAML frontmatter.
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Make a title safe to use as a note file name (and wikilink target).
+fn sanitize_file_name(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cleaned = cleaned.trim_matches('.').to_string();
+
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else {
+        cleaned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(video_id: &str, title: &str, embedding: Vec<f32>) -> NoteSource {
+        let doc = Document::new(
+            video_id.to_string(),
+            title.to_string(),
+            Some("Intro".to_string()),
+            "Hello there.".to_string(),
+            65.0,
+            130.0,
+            embedding,
+            0,
+            None,
+        );
+        NoteSource {
+            video_id: video_id.to_string(),
+            title: title.to_string(),
+            file_stem: sanitize_file_name(title),
+            centroid: centroid(std::slice::from_ref(&doc)),
+            docs: vec![doc],
+            tags: vec!["channel:Two Minute Papers".to_string(), "deep learning".to_string()],
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn test_render_note() {
+        let s = source("abc123", "Lecture 1: \"Bits\"", vec![1.0, 0.0]);
+        let note = render_note(&s, &[("Lecture 2".to_string(), 0.91)]);
+
+        assert!(note.starts_with("---\ntitle: \"Lecture 1: \\\"Bits\\\"\"\n"));
+        assert!(note.contains("channel: \"Two Minute Papers\"\n"));
+        assert!(note.contains("tags:\n  - \"deep-learning\"\n---"));
+        assert!(note.contains("duration: \"02:10\"\n"));
+        assert!(note.contains("### [01:05](https://youtube.com/watch?v=abc123&t=65s) Intro\n"));
+        assert!(note.contains("- [[Lecture 2]] (0.91)"));
+    }
+
+    #[test]
+    fn test_related_notes() {
+        let sources = vec![
+            source("a", "A", vec![1.0, 0.0]),
+            source("b", "B", vec![0.9, 0.1]),
+            source("c", "C", vec![0.0, 1.0]),
+        ];
+
+        let related = related_notes(&sources, 0, 5);
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0, "B");
+        assert_eq!(sanitize_file_name("What/is: AI?"), "What is AI");
+    }
+}
@@ -0,0 +1,181 @@
+//! Webhook notifications for pipeline events.
+//!
+//! When `[notifications] webhook_url` is set, every transcription posts a JSON
+//! event when it starts, completes or fails, so Lytt can trigger n8n/Zapier-style
+//! automations. Delivery is best effort: failures are logged, never propagated.
+
+use crate::audio_source::MediaMetadata;
+use crate::config::NotificationSettings;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Kind of pipeline event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EventKind {
+    /// Metadata fetched, transcription about to start.
+    #[serde(rename = "transcription.started")]
+    Started,
+    /// Media transcribed and indexed.
+    #[serde(rename = "transcription.completed")]
+    Completed,
+    /// Processing failed.
+    #[serde(rename = "transcription.failed")]
+    Failed,
+}
+
+/// JSON body posted to the webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineEvent {
+    /// What happened.
+    pub event: EventKind,
+    /// Media ID.
+    pub media_id: String,
+    /// Media title (unknown if metadata couldn't be fetched).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Media duration in seconds (if known).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+    /// Chunks indexed (completed events only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_count: Option<usize>,
+    /// Processing time in seconds (completed and failed events).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_seconds: Option<f64>,
+    /// Error message (failed events only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When the event happened.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl PipelineEvent {
+    fn new(event: EventKind, media_id: &str) -> Self {
+        Self {
+            event,
+            media_id: media_id.to_string(),
+            title: None,
+            duration_seconds: None,
+            chunk_count: None,
+            elapsed_seconds: None,
+            error: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn with_metadata(mut self, metadata: &MediaMetadata) -> Self {
+        self.title = Some(metadata.title.clone());
+        self.duration_seconds = metadata.duration_seconds;
+        self
+    }
+
+    /// Transcription of the media is starting.
+    pub fn started(metadata: &MediaMetadata) -> Self {
+        Self::new(EventKind::Started, &metadata.id).with_metadata(metadata)
+    }
+
+    /// The media was transcribed and indexed.
+    pub fn completed(metadata: &MediaMetadata, chunk_count: usize, elapsed: Duration) -> Self {
+        let mut event = Self::new(EventKind::Completed, &metadata.id).with_metadata(metadata);
+        event.chunk_count = Some(chunk_count);
+        event.elapsed_seconds = Some(elapsed.as_secs_f64());
+        event
+    }
+
+    /// Processing failed, possibly before metadata was available.
+    pub fn failed(
+        media_id: &str,
+        metadata: Option<&MediaMetadata>,
+        error: &str,
+        elapsed: Duration,
+    ) -> Self {
+        let mut event = Self::new(EventKind::Failed, media_id);
+        if let Some(metadata) = metadata {
+            event = event.with_metadata(metadata);
+        }
+        event.error = Some(error.to_string());
+        event.elapsed_seconds = Some(elapsed.as_secs_f64());
+        event
+    }
+}
+
+/// Posts pipeline events to the configured webhook.
+#[derive(Clone)]
+pub struct Notifier {
+    webhook_url: Option<String>,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    /// Create a notifier from settings. Without a webhook URL it does nothing.
+    pub fn from_settings(settings: &NotificationSettings) -> Self {
+        Self {
+            webhook_url: settings.webhook_url.clone().filter(|url| !url.is_empty()),
+            client: crate::openai::http_client(Duration::from_secs(settings.timeout_seconds)),
+        }
+    }
+
+    /// Whether a webhook is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some()
+    }
+
+    /// Post an event. Errors are logged and otherwise ignored.
+    pub async fn notify(&self, event: &PipelineEvent) {
+        let Some(url) = &self.webhook_url else {
+            return;
+        };
+
+        match self.client.post(url).json(event).send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("Sent {:?} webhook for {}", event.event, event.media_id);
+            }
+            Ok(response) => warn!("Webhook returned {} for {:?} event", response.status(), event.event),
+            Err(e) => warn!("Failed to send webhook: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_source::SourceType;
+
+    #[test]
+    fn test_event_json() {
+        let metadata = MediaMetadata {
+            id: "abc123".to_string(),
+            title: "Lecture".to_string(),
+            description: None,
+            duration_seconds: Some(600),
+            source_type: SourceType::YouTube,
+            source_url: "https://youtube.com/watch?v=abc123".to_string(),
+            published_at: None,
+            channel: None,
+            thumbnail_url: None,
+            album: None,
+            track: None,
+            chapters: Vec::new(),
+        };
+
+        let json = serde_json::to_value(PipelineEvent::completed(&metadata, 12, Duration::from_secs(90))).unwrap();
+        assert_eq!(json["event"], "transcription.completed");
+        assert_eq!(json["media_id"], "abc123");
+        assert_eq!(json["title"], "Lecture");
+        assert_eq!(json["chunk_count"], 12);
+        assert_eq!(json["duration_seconds"], 600);
+        assert!(json.get("error").is_none());
+
+        let json = serde_json::to_value(PipelineEvent::failed("abc123", None, "boom", Duration::ZERO)).unwrap();
+        assert_eq!(json["event"], "transcription.failed");
+        assert_eq!(json["error"], "boom");
+        assert!(json.get("title").is_none());
+    }
+
+    #[test]
+    fn test_disabled_without_url() {
+        assert!(!Notifier::from_settings(&NotificationSettings::default()).is_enabled());
+    }
+}
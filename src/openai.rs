@@ -4,7 +4,7 @@ use async_openai::{config::OpenAIConfig, Client};
 use std::time::Duration;
 
 /// Default timeout for OpenAI API requests (5 minutes).
-const DEFAULT_TIMEOUT_SECS: u64 = 300;
+pub(crate) const DEFAULT_TIMEOUT_SECS: u64 = 300;
 
 /// Create an OpenAI client with configured timeout.
 ///
@@ -15,10 +15,28 @@ pub fn create_client() -> Client<OpenAIConfig> {
 
 /// Create an OpenAI client with a custom timeout.
 pub fn create_client_with_timeout(timeout: Duration) -> Client<OpenAIConfig> {
-    let http_client = reqwest::Client::builder()
+    Client::with_config(OpenAIConfig::default()).with_http_client(http_client(timeout))
+}
+
+/// Create a client for an OpenAI-compatible endpoint.
+///
+/// `None` values fall back to the OpenAI defaults (public API, `OPENAI_API_KEY`).
+pub fn create_client_for(base_url: Option<&str>, api_key: Option<String>) -> Client<OpenAIConfig> {
+    let mut config = OpenAIConfig::default();
+    if let Some(url) = base_url {
+        config = config.with_api_base(url.trim_end_matches('/'));
+    }
+    if let Some(key) = api_key {
+        config = config.with_api_key(key);
+    }
+
+    Client::with_config(config).with_http_client(http_client(Duration::from_secs(DEFAULT_TIMEOUT_SECS)))
+}
+
+/// HTTP client with the given request timeout.
+pub(crate) fn http_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
         .timeout(timeout)
         .build()
-        .expect("Failed to create HTTP client");
-
-    Client::with_config(OpenAIConfig::default()).with_http_client(http_client)
+        .expect("Failed to create HTTP client")
 }
@@ -2,18 +2,47 @@
 //!
 //! Coordinates the entire process from audio download to indexing.
 
-use crate::audio::download_audio;
-use crate::audio_source::{MediaMetadata, parse_input};
-use crate::chunking::{ChunkingConfig, ChunkingStrategy, ContentChunk, create_chunker_with_prompts};
-use crate::config::{Prompts, Settings, TranscriptionProcessingSettings, TranscriptionProvider};
-use crate::embedding::{Embedder, OpenAIEmbedder};
+use crate::audio::{
+    concat_audio, download_audio, download_audio_cached, preprocess_audio, remove_partial_files, remove_ranges, ytdlp, AudioCache,
+    Workspace, AUDIO_BYTES_PER_SECOND,
+};
+use crate::audio_source::sponsorblock::{self, SkipRange};
+use crate::audio_source::{MediaMetadata, SourceType, parse_input};
+use crate::audiobook::Audiobook;
+use crate::chunking::{chunking_fingerprint, composite_text, ChunkingConfig, ContentChunk, create_chunker_from_settings};
+use crate::config::{ChunkingSettings, Prompts, Settings};
+use crate::dedupe::likely_duplicate;
+use crate::embedding::{create_embedder, model_id, shorten, Embedder};
 use crate::error::{Result, LyttError};
-use crate::transcription::{TranscriptionProcessor, Transcript, Transcriber};
-use crate::vector_store::{Document, SqliteVectorStore, VectorStore};
-use std::path::PathBuf;
+use crate::hooks::{Hook, HookContext, HookRegistry};
+use crate::keywords::extract_keywords;
+use crate::llm::ChatClient;
+use crate::logging;
+use crate::meeting::{attendee_tag, Meeting};
+use crate::notifications::{Notifier, PipelineEvent};
+use crate::progress::{self, ProgressStage};
+use crate::rollup::channel_tag;
+use crate::summary::MediaSummary;
+use crate::titles::TitleGenerator;
+use crate::topics::TopicIndex;
+use crate::transcription::{captions_transcript, TranscriptionProcessor, Transcript, Transcriber};
+use crate::usage::{ProcessingRun, RunStatus, UsageTracker};
+#[cfg(feature = "columnar")]
+use crate::vector_store::ColumnarVectorStore;
+#[cfg(feature = "postgres")]
+use crate::vector_store::PgVectorStore;
+use crate::vector_store::{DocType, Document, EmbeddingInfo, IndexKind, Quantization, SqliteVectorStore, VectorFormat, VectorStore};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, instrument, warn};
 
+/// Most glossary terms passed to transcription (the prompt is capped anyway).
+const GLOSSARY_VOCABULARY_LIMIT: usize = 100;
+
 /// The main orchestrator for the Lytt pipeline.
 pub struct Orchestrator {
     settings: Settings,
@@ -21,7 +50,17 @@ pub struct Orchestrator {
     transcriber: Arc<dyn Transcriber>,
     embedder: Arc<dyn Embedder>,
     vector_store: Arc<SqliteVectorStore>,
-    temp_dir: PathBuf,
+    /// Where documents are indexed and searched: the SQLite store, Postgres
+    /// or the columnar store.
+    documents: Arc<dyn VectorStore>,
+    hooks: HookRegistry,
+    notifier: Notifier,
+    /// Where audio is downloaded and split; removed when the run ends.
+    workspace: Workspace,
+    /// Read meeting sidecar files next to local recordings.
+    meetings: bool,
+    /// Cancels the run in progress (Ctrl+C).
+    cancel: CancellationToken,
 }
 
 impl Orchestrator {
@@ -33,46 +72,73 @@ impl Orchestrator {
             Some(&settings.prompts.variables),
         )?;
 
-        // Create transcription processor
-        // Whisper mode: no secondary text model, just cleanup
-        // Fusion mode: Whisper + GPT-4o + LLM fusion
-        let processing_settings = match settings.transcription.provider {
-            TranscriptionProvider::Whisper => {
-                info!("Using Whisper with LLM cleanup ({})", settings.transcription.processing.cleanup_model);
-                TranscriptionProcessingSettings {
-                    timestamp_model: settings.transcription.model.clone(),
-                    text_model: None, // No secondary model
-                    cleanup_model: settings.transcription.processing.cleanup_model.clone(),
-                    max_concurrent: settings.transcription.max_concurrent_chunks,
-                }
-            }
-            TranscriptionProvider::Fusion => {
-                let text_model = settings.transcription.processing.text_model.clone()
-                    .unwrap_or_else(|| "gpt-4o-transcribe".to_string());
-                info!(
-                    "Using fusion transcription ({} + {} -> {})",
-                    settings.transcription.processing.timestamp_model,
-                    text_model,
-                    settings.transcription.processing.cleanup_model
-                );
-                settings.transcription.processing.clone()
+        let vector_store = if settings.vector_store.read_only {
+            SqliteVectorStore::open_read_only(&settings.sqlite_path())?
+                .with_pool_size(settings.vector_store.pool_size)?
+        } else {
+            let vector_format: VectorFormat = settings
+                .vector_store
+                .vector_format
+                .parse()
+                .map_err(|e| LyttError::Config(format!("vector_store.vector_format: {}", e)))?;
+            let store = SqliteVectorStore::new(&settings.sqlite_path())?
+                .with_pool_size(settings.vector_store.pool_size)?
+                .with_replication(&settings.vector_store.replication)?
+                .with_vector_format(vector_format);
+            // Keep stored topics covering newly indexed documents
+            let store = if settings.topics.persist {
+                store.with_index(Arc::new(TopicIndex::new()))
+            } else {
+                store
+            };
+            let quantization = settings.vector_store.quantization.trim();
+            if quantization.is_empty() || quantization.eq_ignore_ascii_case("none") {
+                store
+            } else {
+                let quantization: Quantization = quantization
+                    .parse()
+                    .map_err(|e| LyttError::Config(format!("vector_store.quantization: {}", e)))?;
+                store.with_quantization(quantization, settings.vector_store.rescore_factor)
             }
         };
 
-        let transcriber: Arc<dyn Transcriber> = Arc::new(TranscriptionProcessor::with_config(
-            &processing_settings,
-            &prompts.cleanup.system,
-        )?);
+        // Load the ANN graph from the stored embeddings before the first search
+        let vector_store = Arc::new(if settings.vector_store.index.eq_ignore_ascii_case("hnsw") {
+            let store = vector_store.with_ann();
+            store.rebuild_index(IndexKind::Ann, &mut |_, _| {})?;
+            store
+        } else {
+            vector_store
+        });
 
-        let embedder = Arc::new(OpenAIEmbedder::with_config(
-            &settings.embedding.model,
-            settings.embedding.dimensions as usize,
-        ));
+        // Documents live in SQLite unless Postgres or the columnar store is configured
+        let documents: Arc<dyn VectorStore> = match settings.vector_store.provider.as_str() {
+            "postgres" => postgres_store(&settings)?,
+            "columnar" => columnar_store(&settings)?,
+            _ => vector_store.clone(),
+        };
+
+        // Bias transcription towards configured terms, then terms from `lytt glossary`
+        let mut vocabulary = settings.transcription.vocabulary.clone();
+        if settings.transcription.glossary_vocabulary {
+            match vector_store.glossary_vocabulary(GLOSSARY_VOCABULARY_LIMIT) {
+                Ok(terms) => vocabulary.extend(terms),
+                Err(e) => warn!("Failed to load glossary vocabulary: {}", e),
+            }
+        }
+
+        let transcriber: Arc<dyn Transcriber> = Arc::new(
+            TranscriptionProcessor::from_settings(&settings.transcription, &prompts.cleanup.system)?
+                .with_vocabulary(&vocabulary),
+        );
 
-        let vector_store = Arc::new(SqliteVectorStore::new(&settings.sqlite_path())?);
+        let embedder = create_embedder(&settings.embedding)?;
 
-        let temp_dir = settings.temp_dir();
-        std::fs::create_dir_all(&temp_dir)?;
+        let workspace = Workspace::from_settings(&settings)?;
+        ytdlp::configure(&settings.youtube);
+
+        let hooks = HookRegistry::from_settings(&settings);
+        let notifier = Notifier::from_settings(&settings.notifications);
 
         Ok(Self {
             settings,
@@ -80,7 +146,12 @@ impl Orchestrator {
             transcriber,
             embedder,
             vector_store,
-            temp_dir,
+            documents,
+            hooks,
+            notifier,
+            workspace,
+            meetings: false,
+            cancel: CancellationToken::new(),
         })
     }
 
@@ -92,22 +163,53 @@ impl Orchestrator {
         embedder: Arc<dyn Embedder>,
         vector_store: Arc<SqliteVectorStore>,
     ) -> Result<Self> {
-        let temp_dir = settings.temp_dir();
-        std::fs::create_dir_all(&temp_dir)?;
+        let workspace = Workspace::from_settings(&settings)?;
+        ytdlp::configure(&settings.youtube);
+
+        let hooks = HookRegistry::from_settings(&settings);
+        let notifier = Notifier::from_settings(&settings.notifications);
 
         Ok(Self {
             settings,
             prompts,
             transcriber,
             embedder,
+            documents: vector_store.clone(),
             vector_store,
-            temp_dir,
+            hooks,
+            notifier,
+            workspace,
+            meetings: false,
+            cancel: CancellationToken::new(),
         })
     }
 
-    /// Get a reference to the vector store (as trait object).
+    /// Treat local files as meeting recordings: take their title, speakers
+    /// and attendees from Zoom, Teams or Meet files exported alongside.
+    pub fn with_meetings(mut self, enabled: bool) -> Self {
+        self.meetings = enabled;
+        self
+    }
+
+    /// Stop runs when `token` is cancelled. The stage in progress is dropped,
+    /// which aborts its API requests and kills its yt-dlp or ffmpeg process,
+    /// and the run fails with [`LyttError::Cancelled`]. Writes to the library
+    /// aren't interrupted, so it is left as it was before the stage.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Register a pipeline hook. Hooks run after those defined in config.
+    pub fn with_hook(mut self, hook: Arc<dyn Hook>) -> Self {
+        self.hooks.register(hook);
+        self
+    }
+
+    /// Get a reference to the vector store holding the documents (SQLite,
+    /// Postgres or columnar, as configured).
     pub fn vector_store(&self) -> Arc<dyn VectorStore> {
-        self.vector_store.clone() as Arc<dyn VectorStore>
+        self.documents.clone()
     }
 
     /// Get a reference to the SQLite vector store (for transcript storage).
@@ -115,46 +217,443 @@ impl Orchestrator {
         self.vector_store.clone()
     }
 
+    /// Start periodic WAL checkpoints for a long-running process, unless
+    /// they're disabled, the database is read-only, or replication settings
+    /// schedule them instead.
+    pub fn spawn_checkpoints(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let store_settings = &self.settings.vector_store;
+        let interval = store_settings.checkpoint_interval_secs;
+        if interval == 0 || store_settings.read_only || store_settings.replication.enabled {
+            return None;
+        }
+        Some(self.sqlite_store().spawn_checkpoints(std::time::Duration::from_secs(interval)))
+    }
+
     /// Get a reference to the embedder.
     pub fn embedder(&self) -> Arc<dyn Embedder> {
         self.embedder.clone()
     }
 
+    /// Get a reference to the transcriber.
+    pub fn transcriber(&self) -> Arc<dyn Transcriber> {
+        self.transcriber.clone()
+    }
+
     /// Get the settings.
     pub fn settings(&self) -> &Settings {
         &self.settings
     }
 
+    /// Get the loaded prompts.
+    pub fn prompts(&self) -> &Prompts {
+        &self.prompts
+    }
+
+    /// The embedding model and dimensions new embeddings are made with.
+    pub fn configured_embedding(&self) -> EmbeddingInfo {
+        EmbeddingInfo::new(model_id(&self.settings.embedding), self.settings.embedding.dimensions as usize)
+    }
+
+    /// Fail if the library was indexed with a different embedding model or
+    /// dimensions than configured; their similarity scores would be meaningless.
+    pub async fn check_embedding(&self) -> Result<()> {
+        match self.with_store(|store| store.embedding_info()).await? {
+            Some(indexed) => indexed.ensure_compatible(&self.configured_embedding()),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the configured embedding model as the one the library uses.
+    async fn record_embedding(&self) -> Result<()> {
+        let configured = self.configured_embedding();
+        self.with_store(move |store| {
+            store.set_embedding_info(configured.model.as_deref().unwrap_or_default(), configured.dimensions)
+        })
+        .await
+    }
+
+    /// Run a stage of the pipeline unless, or until, the run is cancelled.
+    async fn until_cancelled<T>(&self, stage: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => Err(LyttError::Cancelled),
+            result = stage => result,
+        }
+    }
+
+    /// Fingerprint of the configured chunking settings and prompts.
+    pub fn chunking_fingerprint(&self) -> String {
+        chunking_fingerprint(&self.settings.chunking, &self.prompts)
+    }
+
+    /// Run synchronous work against the SQLite store on the blocking pool.
+    async fn with_store<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SqliteVectorStore) -> Result<T> + Send + 'static,
+    {
+        self.vector_store.blocking(f).await
+    }
+
+    /// Record where a media item came from, so it can be fetched again.
+    async fn record_media_source(&self, metadata: &MediaMetadata) -> Result<()> {
+        let (media_id, source_type, source_url) =
+            (metadata.id.clone(), metadata.source_type, metadata.source_url.clone());
+        self.with_store(move |store| store.set_media_source(&media_id, source_type, &source_url))
+            .await
+    }
+
+    async fn add_tags(&self, media_id: &str, tags: Vec<String>) -> Result<()> {
+        let media_id = media_id.to_string();
+        self.with_store(move |store| store.add_tags(&media_id, &tags)).await
+    }
+
+    async fn get_audiobook(&self, video_id: &str) -> Result<Option<(String, Option<String>)>> {
+        let video_id = video_id.to_string();
+        self.with_store(move |store| store.get_audiobook(&video_id)).await
+    }
+
+    /// Record that media was chunked with the configured chunking settings.
+    async fn record_chunking(&self, video_id: &str) {
+        let video_id = video_id.to_string();
+        let fingerprint = self.chunking_fingerprint();
+        let recorded = self
+            .with_store(move |store| store.set_chunking_fingerprint(&video_id, &fingerprint))
+            .await;
+        if let Err(e) = recorded {
+            warn!("Failed to record chunking configuration: {}", e);
+        }
+    }
+
+    /// Persist the usage collected by a tracker, if usage tracking is enabled.
+    pub async fn record_usage(&self, operation: &str, video_id: Option<&str>, tracker: &UsageTracker) {
+        if !self.settings.usage.enabled {
+            return;
+        }
+
+        let records = tracker.records();
+        let operation = operation.to_string();
+        let video_id = video_id.map(str::to_string);
+        let pricing = self.settings.pricing();
+        let recorded = self
+            .with_store(move |store| store.record_usage(&operation, video_id.as_deref(), &records, &pricing))
+            .await;
+        if let Err(e) = recorded {
+            warn!("Failed to record usage: {}", e);
+        }
+    }
+
+    /// Log a pipeline run over a media item in the processing history, with
+    /// the configuration it ran under and the usage its tracker collected.
+    async fn record_run(
+        &self,
+        operation: &str,
+        video_id: &str,
+        started_at: DateTime<Utc>,
+        transcribed: bool,
+        tracker: &UsageTracker,
+        outcome: std::result::Result<&ProcessResult, &LyttError>,
+    ) {
+        let chunking = &self.settings.chunking;
+        let (status, error, chunks) = match outcome {
+            Ok(result) if result.skipped => (RunStatus::Skipped, None, 0),
+            Ok(result) => (RunStatus::Completed, None, result.chunks_indexed),
+            Err(LyttError::Cancelled) => (RunStatus::Cancelled, None, 0),
+            Err(e) => (RunStatus::Failed, Some(e.to_string()), 0),
+        };
+        let run = ProcessingRun {
+            video_id: video_id.to_string(),
+            operation: operation.to_string(),
+            started_at,
+            finished_at: Utc::now(),
+            status,
+            error,
+            transcription_provider: transcribed.then(|| self.settings.transcription.provider.to_string()),
+            transcription_model: transcribed.then(|| self.settings.transcription.model.clone()),
+            chunking_strategy: chunking.strategy.clone(),
+            chunking_model: (!matches!(chunking.strategy.as_str(), "temporal" | "sliding" | "external"))
+                .then(|| chunking.model.clone()),
+            chunking_fingerprint: self.chunking_fingerprint(),
+            embedding_model: model_id(&self.settings.embedding),
+            chunks,
+            input_tokens: 0,
+            output_tokens: 0,
+            audio_seconds: 0.0,
+            cost_usd: 0.0,
+            run_id: logging::current_run_id(),
+        }
+        .with_usage(&tracker.records(), &self.settings.pricing());
+
+        if let Err(e) = self.with_store(move |store| store.record_processing_run(&run)).await {
+            warn!("Failed to record processing history: {}", e);
+        }
+    }
+
     /// Process media: download audio, transcribe, chunk, embed, and index.
     #[instrument(skip(self), fields(input = %input))]
     pub async fn process_media(&self, input: &str, force: bool) -> Result<ProcessResult> {
+        let tracker = UsageTracker::new();
+        let started_at = Utc::now();
+        let result = tracker.scope(self.process_media_tracked(input, force)).await;
+
+        // Record usage even on failure - the API calls made so far are still billed
+        let video_id = parse_input(input).map(|(_, id)| id);
+        self.record_usage("transcribe", video_id.as_deref(), &tracker).await;
+
+        // Local files are identified by content, so the result has the real ID
+        let media_id = result.as_ref().ok().map(|r| r.media_id.clone()).or(video_id);
+        if let Some(media_id) = media_id {
+            self.record_run("transcribe", &media_id, started_at, true, &tracker, result.as_ref()).await;
+        }
+
+        result
+    }
+
+    /// Index a transcript made outside the pipeline, such as a live
+    /// recording, as a new media item.
+    #[instrument(skip(self, metadata, transcript), fields(media_id = %metadata.id))]
+    pub async fn index_transcript(&self, mut metadata: MediaMetadata, transcript: Transcript) -> Result<ProcessResult> {
+        let tracker = UsageTracker::new();
+        let started = Instant::now();
+        let started_at = Utc::now();
+        self.notifier.notify(&PipelineEvent::started(&metadata)).await;
+        let result = tracker.scope(self.index_transcript_of(&metadata, transcript, None)).await;
+        self.record_usage("transcribe", Some(&metadata.id), &tracker).await;
+
+        let result = match result {
+            Ok((indexed, title)) => {
+                metadata.title = title;
+                let event = PipelineEvent::completed(&metadata, indexed, started.elapsed());
+                self.notifier.notify(&event).await;
+
+                Ok(ProcessResult {
+                    media_id: metadata.id.clone(),
+                    title: metadata.title.clone(),
+                    chunks_indexed: indexed,
+                    skipped: false,
+                    duplicate_of: None,
+                })
+            }
+            Err(e) => {
+                let event = PipelineEvent::failed(&metadata.id, Some(&metadata), &e.to_string(), started.elapsed());
+                self.notifier.notify(&event).await;
+                Err(e)
+            }
+        };
+        self.record_run("index", &metadata.id, started_at, false, &tracker, result.as_ref()).await;
+        result
+    }
+
+    /// Process an audiobook as one media item: join its parts, transcribe,
+    /// chunk by chapter, embed and index.
+    #[instrument(skip(self, book), fields(book = %book.title))]
+    pub async fn process_audiobook(&self, book: &Audiobook, force: bool) -> Result<ProcessResult> {
+        let tracker = UsageTracker::new();
+        let started_at = Utc::now();
+        let media_id = book.media_id();
+        let result = tracker.scope(self.process_audiobook_tracked(book, force)).await;
+        self.record_usage("transcribe", Some(&media_id), &tracker).await;
+        self.record_run("transcribe", &media_id, started_at, true, &tracker, result.as_ref()).await;
+        result
+    }
+
+    async fn process_audiobook_tracked(&self, book: &Audiobook, force: bool) -> Result<ProcessResult> {
+        let mut metadata = book.metadata();
+        if !force && self.documents.is_video_indexed(&metadata.id).await? {
+            info!("Audiobook {} is already indexed, skipping", metadata.id);
+            return Ok(ProcessResult {
+                media_id: metadata.id,
+                title: metadata.title,
+                chunks_indexed: 0,
+                skipped: true,
+                duplicate_of: None,
+            });
+        }
+
+        if let Some(duration) = metadata.duration_seconds {
+            logging::status(format_args!("  Duration: {}:{:02}:{:02}", duration / 3600, duration % 3600 / 60, duration % 60));
+            if duration > self.settings.transcription.max_audiobook_seconds {
+                return Err(LyttError::InvalidInput(format!(
+                    "Audiobook duration ({} seconds) exceeds maximum ({} seconds)",
+                    duration, self.settings.transcription.max_audiobook_seconds
+                )));
+            }
+        }
+
+        // Recorded first, so chunking and indexing know it's a book
+        let parts: Vec<String> = book.parts.iter().map(|p| p.source_url.clone()).collect();
+        self.vector_store
+            .store_audiobook(&metadata.id, &book.title, book.author.as_deref(), &parts)?;
+
+        let started = Instant::now();
+        self.notifier.notify(&PipelineEvent::started(&metadata)).await;
+        let result = async {
+            let transcribed = self.until_cancelled(self.transcribe_book(book, &metadata)).await;
+            if let Err(LyttError::Cancelled) = transcribed {
+                remove_partial_files(self.workspace.path(), &metadata.id);
+            }
+            self.index_transcript_of(&metadata, transcribed?, None).await
+        }
+        .await;
+
+        match result {
+            Ok((indexed, title)) => {
+                metadata.title = title;
+                let event = PipelineEvent::completed(&metadata, indexed, started.elapsed());
+                self.notifier.notify(&event).await;
+
+                Ok(ProcessResult {
+                    media_id: metadata.id,
+                    title: metadata.title,
+                    chunks_indexed: indexed,
+                    skipped: false,
+                    duplicate_of: None,
+                })
+            }
+            Err(e) => {
+                let event = PipelineEvent::failed(&metadata.id, Some(&metadata), &e.to_string(), started.elapsed());
+                self.notifier.notify(&event).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Transcribe an audiobook, joining a book in parts into one file first.
+    async fn transcribe_book(&self, book: &Audiobook, metadata: &MediaMetadata) -> Result<Transcript> {
+        if book.parts.len() == 1 {
+            return self.transcribe_audio(metadata, &[]).await;
+        }
+
+        logging::status(format_args!("  Joining {} parts...", book.parts.len()));
+        progress::report(ProgressStage::Download, format!("Joining {} parts of '{}'", book.parts.len(), book.title));
+        if let Some(duration) = metadata.duration_seconds {
+            self.workspace
+                .ensure_space(u64::from(duration) * AUDIO_BYTES_PER_SECOND, "Joining the audiobook")?;
+        }
+        let audio_path = self.workspace.path().join(format!("{}.mp3", metadata.id));
+        concat_audio(&book.part_paths(), &audio_path).await?;
+        self.transcribe_file(&audio_path, &[]).await
+    }
+
+    async fn process_media_tracked(&self, input: &str, force: bool) -> Result<ProcessResult> {
         // Parse input
         let (source, media_id) = parse_input(input).ok_or_else(|| {
             LyttError::InvalidInput(format!("Could not parse input: {}", input))
         })?;
 
         // Check if already indexed
-        if !force && self.vector_store.is_video_indexed(&media_id).await? {
+        if !force && self.documents.is_video_indexed(&media_id).await? {
             info!("Media {} is already indexed, skipping", media_id);
             return Ok(ProcessResult {
                 media_id,
                 title: "Already indexed".to_string(),
                 chunks_indexed: 0,
                 skipped: true,
+                duplicate_of: None,
             });
         }
 
         // Fetch metadata
         info!("Fetching metadata for {}", media_id);
-        eprintln!("  Fetching metadata...");
-        let metadata = source.fetch_media(&media_id).await?;
-        eprintln!("  Title: {}", metadata.title);
+        logging::status("  Fetching metadata...");
+        progress::report(ProgressStage::Metadata, "Fetching metadata");
+        let started = Instant::now();
+        let mut metadata = match source.fetch_media(&media_id).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let event = PipelineEvent::failed(&media_id, None, &e.to_string(), started.elapsed());
+                self.notifier.notify(&event).await;
+                return Err(e);
+            }
+        };
+        logging::status(format_args!("  Title: {}", metadata.title));
 
+        // Local files are identified by content, known only once fetched
+        if !force && metadata.id != media_id && self.documents.is_video_indexed(&metadata.id).await? {
+            info!("Media {} is already indexed, skipping", metadata.id);
+            // The file may have moved since it was indexed
+            if let Err(e) = self.record_media_source(&metadata).await {
+                warn!("Failed to record media source: {}", e);
+            }
+            return Ok(ProcessResult {
+                media_id: metadata.id,
+                title: metadata.title,
+                chunks_indexed: 0,
+                skipped: true,
+                duplicate_of: None,
+            });
+        }
+
+        // Warn before spending transcription money on a re-upload
+        if !force && self.settings.dedupe.check_on_transcribe {
+            let indexed = self.documents.list_videos().await?;
+            if let Some(original) = likely_duplicate(&metadata, &indexed) {
+                warn!("{} looks like a duplicate of {}", media_id, original.video_id);
+                logging::status(format_args!(
+                    "  Warning: looks like a duplicate of '{}' ({})",
+                    original.video_title, original.video_id
+                ));
+                if self.settings.dedupe.skip_on_transcribe {
+                    return Ok(ProcessResult {
+                        media_id,
+                        title: metadata.title,
+                        chunks_indexed: 0,
+                        skipped: true,
+                        duplicate_of: Some(original.video_id.clone()),
+                    });
+                }
+            }
+        }
+
+        let meeting = if self.meetings && metadata.source_type == SourceType::Local {
+            Meeting::from_sidecars(Path::new(&metadata.source_url)).unwrap_or_else(|e| {
+                warn!("Failed to read meeting files: {}", e);
+                None
+            })
+        } else {
+            None
+        };
+        if let Some(meeting) = &meeting {
+            meeting.apply(&mut metadata);
+            logging::status(format_args!("  Meeting: {} ({} attendees)", metadata.title, meeting.attendees.len()));
+        }
+
+        self.notifier.notify(&PipelineEvent::started(&metadata)).await;
+
+        match self.transcribe_and_index(&metadata, meeting.as_ref()).await {
+            Ok((indexed, title)) => {
+                metadata.title = title;
+                let event = PipelineEvent::completed(&metadata, indexed, started.elapsed());
+                self.notifier.notify(&event).await;
+
+                Ok(ProcessResult {
+                    media_id: metadata.id,
+                    title: metadata.title,
+                    chunks_indexed: indexed,
+                    skipped: false,
+                    duplicate_of: None,
+                })
+            }
+            Err(e) => {
+                let event = PipelineEvent::failed(&media_id, Some(&metadata), &e.to_string(), started.elapsed());
+                self.notifier.notify(&event).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Download, transcribe, chunk and index media whose metadata is known.
+    /// Returns the number of chunks indexed and the title the media was indexed under.
+    async fn transcribe_and_index(
+        &self,
+        metadata: &MediaMetadata,
+        meeting: Option<&Meeting>,
+    ) -> Result<(usize, String)> {
         // Check duration limit
         if let Some(duration) = metadata.duration_seconds {
             let mins = duration / 60;
             let secs = duration % 60;
-            eprintln!("  Duration: {}:{:02}", mins, secs);
+            logging::status(format_args!("  Duration: {}:{:02}", mins, secs));
             if duration > self.settings.transcription.max_duration_seconds {
                 return Err(LyttError::InvalidInput(format!(
                     "Media duration ({} seconds) exceeds maximum ({} seconds)",
@@ -163,62 +662,233 @@ impl Orchestrator {
             }
         }
 
-        // Download/extract audio
-        info!("Extracting audio for: {}", metadata.title);
-        eprintln!("  Downloading audio...");
-        let audio_path = download_audio(&metadata.source_url, &media_id, &self.temp_dir).await?;
-        eprintln!("  Audio downloaded.");
+        let exported = meeting.and_then(|m| m.transcript(&metadata.id));
+        if exported.is_some() {
+            logging::status("  Using the meeting's exported transcript (no transcription needed)");
+        }
+        let transcript = match exported {
+            Some(transcript) => transcript,
+            None => {
+                let transcribed = self
+                    .until_cancelled(async {
+                        let skipped = self.sponsorblock_ranges(metadata).await;
+                        match captions_transcript(&self.settings.transcription, metadata, self.workspace.path()).await {
+                            Some(mut transcript) => {
+                                let dropped = sponsorblock::drop_skipped(&mut transcript, &skipped);
+                                if dropped > 0 {
+                                    logging::status(format_args!("  Dropped {} caption segments in SponsorBlock segments", dropped));
+                                }
+                                Ok(transcript)
+                            }
+                            None => self.transcribe_audio(metadata, &skipped).await,
+                        }
+                    })
+                    .await;
+                // Audio and captions cut off mid-download would be taken for a cached download next time
+                if let Err(LyttError::Cancelled) = transcribed {
+                    remove_partial_files(self.workspace.path(), &metadata.id);
+                }
+                let mut transcript = transcribed?;
+                if let Some(meeting) = meeting {
+                    meeting.label_speakers(&mut transcript);
+                }
+                transcript
+            }
+        };
+        self.index_transcript_of(metadata, transcript, meeting).await
+    }
 
-        // Transcribe
-        info!("Transcribing audio...");
-        eprintln!("  Transcribing...");
-        let transcript = self.transcriber.transcribe(&audio_path).await?;
-        eprintln!("  Transcription complete ({} segments)", transcript.segments.len());
+    /// Store, chunk and index the transcript of media.
+    /// Returns the number of chunks indexed and the title the media was indexed under.
+    async fn index_transcript_of(
+        &self,
+        metadata: &MediaMetadata,
+        mut transcript: Transcript,
+        meeting: Option<&Meeting>,
+    ) -> Result<(usize, String)> {
+        if transcript.chapters.is_empty() {
+            transcript.chapters = metadata.chapters.clone();
+        }
+
+        let metadata = &self.title_untitled(metadata, &transcript).await;
+        let media_id = metadata.id.as_str();
+
+        let hook_ctx = HookContext::new(media_id, &metadata.title);
+        self.hooks.run_transcript(&hook_ctx, &mut transcript).await?;
 
         // Store raw transcript for potential rechunking
-        if let Err(e) = self.vector_store.store_transcript(&media_id, &metadata.title, &transcript) {
+        let stored = {
+            let (media_id, title, transcript) = (media_id.to_string(), metadata.title.clone(), transcript.clone());
+            self.with_store(move |store| store.store_transcript(&media_id, &title, &transcript)).await
+        };
+        if let Err(e) = stored {
             warn!("Failed to store transcript (rechunking won't be available): {}", e);
         }
 
+        if let Err(e) = self.record_media_source(metadata).await {
+            warn!("Failed to record media source: {}", e);
+        }
+
+        // Tag with the channel so it can be rolled up per channel
+        if let Some(channel) = &metadata.channel {
+            if let Err(e) = self.add_tags(media_id, vec![channel_tag(channel)]).await {
+                warn!("Failed to tag media with channel: {}", e);
+            }
+        }
+        if let Some(album) = &metadata.album {
+            if let Err(e) = self.add_tags(media_id, vec![format!("album:{}", album)]).await {
+                warn!("Failed to tag media with album: {}", e);
+            }
+        }
+        if let Some(meeting) = meeting.filter(|m| !m.attendees.is_empty()) {
+            let tags: Vec<String> = meeting.attendees.iter().map(|a| attendee_tag(a)).collect();
+            if let Err(e) = self.add_tags(media_id, tags).await {
+                warn!("Failed to tag media with attendees: {}", e);
+            }
+        }
+
         // Chunk
         info!("Chunking transcript...");
-        eprintln!("  Chunking transcript...");
-        let chunks = self.chunk_transcript(&transcript, &metadata).await?;
-        eprintln!("  Created {} chunks", chunks.len());
+        logging::status("  Chunking transcript...");
+        progress::report(
+            ProgressStage::Chunk,
+            format!("Chunking transcript ({} segments)", transcript.segments.len()),
+        );
+        let mut chunks = self.until_cancelled(self.chunk_transcript(&transcript, metadata)).await?;
+        self.hooks.run_chunks(&hook_ctx, &mut chunks).await?;
+        logging::status(format_args!("  Created {} chunks", chunks.len()));
 
         // Index
         info!("Indexing {} chunks...", chunks.len());
-        eprintln!("  Generating embeddings and indexing...");
-        let indexed = self.index_chunks(&metadata, chunks).await?;
-        eprintln!("  Indexed {} chunks", indexed);
+        logging::status("  Generating embeddings and indexing...");
+        progress::report(ProgressStage::Index, format!("Embedding and indexing {} chunks", chunks.len()));
+        let indexed = self.index_chunks(metadata, chunks, &transcript, &hook_ctx).await?;
+        logging::status(format_args!("  Indexed {} chunks", indexed));
+        self.record_chunking(media_id).await;
+
+        Ok((indexed, metadata.title.clone()))
+    }
+
+    /// Download, preprocess and transcribe the audio of media.
+    /// Ranges in `skipped` are cut from the audio before transcription.
+    async fn transcribe_audio(&self, metadata: &MediaMetadata, skipped: &[SkipRange]) -> Result<Transcript> {
+        // Download/extract audio
+        info!("Extracting audio for: {}", metadata.title);
+        logging::status("  Downloading audio...");
+        progress::report(ProgressStage::Download, format!("Downloading audio for '{}'", metadata.title));
+        let audio_path = self.download(metadata).await?;
+        logging::status("  Audio downloaded.");
+        self.transcribe_file(&audio_path, skipped).await
+    }
+
+    /// Preprocess and transcribe downloaded audio, then remove it. Ranges in
+    /// `skipped` are cut from the audio before transcription.
+    async fn transcribe_file(&self, audio_path: &Path, skipped: &[SkipRange]) -> Result<Transcript> {
+        preprocess_audio(audio_path, &self.settings.audio).await?;
+        if !skipped.is_empty() {
+            remove_ranges(audio_path, skipped).await?;
+            logging::status(format_args!(
+                "  Skipping {:.0}s of SponsorBlock segments",
+                sponsorblock::skipped_seconds(skipped)
+            ));
+        }
+
+        // Transcribe
+        info!("Transcribing audio...");
+        logging::status("  Transcribing...");
+        progress::report(ProgressStage::Transcribe, "Transcribing");
+        self.ensure_split_space(audio_path)?;
+        let mut transcript = self.transcriber.transcribe(audio_path).await?;
+        sponsorblock::restore_timeline(&mut transcript, skipped);
+        logging::status(format_args!("  Transcription complete ({} segments)", transcript.segments.len()));
 
         // Cleanup audio file
-        if let Err(e) = std::fs::remove_file(&audio_path) {
+        if let Err(e) = std::fs::remove_file(audio_path) {
             warn!("Failed to cleanup audio file: {}", e);
         }
 
-        Ok(ProcessResult {
-            media_id: metadata.id,
-            title: metadata.title,
-            chunks_indexed: indexed,
-            skipped: false,
-        })
+        Ok(transcript)
+    }
+
+    /// SponsorBlock ranges to skip in a YouTube video, when enabled.
+    async fn sponsorblock_ranges(&self, metadata: &MediaMetadata) -> Vec<SkipRange> {
+        sponsorblock::ranges_for(&self.settings.youtube, metadata).await
+    }
+
+    /// Download the audio of `metadata` into the workspace, once there is
+    /// room for it. Audio of online media comes from the audio cache when
+    /// it was downloaded before.
+    async fn download(&self, metadata: &MediaMetadata) -> Result<PathBuf> {
+        if let Some(duration) = metadata.duration_seconds {
+            let needed = u64::from(duration) * AUDIO_BYTES_PER_SECOND;
+            self.workspace.ensure_space(needed, "Downloading the audio")?;
+        }
+        if metadata.source_type == SourceType::Local {
+            return download_audio(&metadata.source_url, &metadata.id, self.workspace.path()).await;
+        }
+        let cache = AudioCache::from_settings(&self.settings);
+        download_audio_cached(&metadata.source_url, &metadata.id, self.workspace.path(), &cache).await
+    }
+
+    /// Check there is room to split the downloaded audio into segments,
+    /// which take about as much space as the audio itself.
+    fn ensure_split_space(&self, audio_path: &Path) -> Result<()> {
+        let size = std::fs::metadata(audio_path)?.len();
+        self.workspace.ensure_space(size, "Splitting the audio")
+    }
+
+    /// Give media that only has its file name as a title a generated title and tags.
+    /// A title generated on an earlier run is reused. Failures keep the file name.
+    async fn title_untitled(&self, metadata: &MediaMetadata, transcript: &Transcript) -> MediaMetadata {
+        let mut metadata = metadata.clone();
+        if !self.settings.titles.auto || !metadata.has_fallback_title() {
+            return metadata;
+        }
+
+        let media_id = metadata.id.clone();
+        let generated = match self.with_store(move |store| store.get_generated_title(&media_id)).await {
+            Ok(Some(existing)) => existing,
+            _ => {
+                logging::status("  Generating title...");
+                let generator = TitleGenerator::new(&self.settings.titles.model)
+                    .with_excerpt_seconds(self.settings.titles.excerpt_seconds)
+                    .with_prompts(self.prompts.clone())
+                    .with_client(ChatClient::from_settings(&self.settings.titles.llm));
+
+                match generator.generate(&metadata.id, &metadata.title, transcript).await {
+                    Ok(generated) => {
+                        let stored = {
+                            let generated = generated.clone();
+                            self.with_store(move |store| store.store_generated_title(&generated)).await
+                        };
+                        if let Err(e) = stored {
+                            warn!("Failed to store generated title: {}", e);
+                        }
+                        if let Err(e) = self.add_tags(&metadata.id, generated.tags.clone()).await {
+                            warn!("Failed to tag media: {}", e);
+                        }
+                        generated
+                    }
+                    Err(e) => {
+                        warn!("Failed to generate a title, keeping '{}': {}", metadata.title, e);
+                        return metadata;
+                    }
+                }
+            }
+        };
+
+        logging::status(format_args!("  Title: {} (generated)", generated.title));
+        metadata.title = generated.title;
+        metadata
     }
 
     /// Chunk a transcript.
     async fn chunk_transcript(
         &self,
         transcript: &Transcript,
-        _metadata: &MediaMetadata,
+        metadata: &MediaMetadata,
     ) -> Result<Vec<ContentChunk>> {
-        let strategy: ChunkingStrategy = self
-            .settings
-            .chunking
-            .strategy
-            .parse()
-            .unwrap_or(ChunkingStrategy::Semantic);
-
-        let chunker = create_chunker_with_prompts(strategy, self.prompts.clone());
+        let chunker = create_chunker_from_settings(&self.chunking_settings(&metadata.id).await, self.prompts.clone())?;
 
         let config = ChunkingConfig {
             target_duration: self.settings.chunking.target_chunk_seconds,
@@ -229,28 +899,62 @@ impl Orchestrator {
         chunker.chunk(transcript, &config).await
     }
 
+    /// Chunking settings for a media item: audiobooks are split by chapter
+    /// whatever the configured strategy.
+    async fn chunking_settings(&self, video_id: &str) -> ChunkingSettings {
+        let mut settings = self.settings.chunking.clone();
+        if matches!(self.get_audiobook(video_id).await, Ok(Some(_))) {
+            settings.strategy = "chapters".to_string();
+        }
+        settings
+    }
+
     /// Generate embeddings and index chunks.
     async fn index_chunks(
         &self,
         metadata: &MediaMetadata,
         chunks: Vec<ContentChunk>,
+        transcript: &Transcript,
+        hook_ctx: &HookContext,
     ) -> Result<usize> {
         if chunks.is_empty() {
             return Ok(0);
         }
 
-        // Delete existing documents for this media
-        self.vector_store.delete_by_video_id(&metadata.id).await?;
+        self.check_embedding().await?;
+
+        // Embed before deleting anything, so a failed or cancelled run keeps the existing chunks
+        let documents = self.until_cancelled(self.embed_chunks(metadata, chunks, transcript)).await?;
 
-        // Generate embeddings in batch
-        let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+        // Replace existing documents for this media
+        self.documents.delete_by_video_id(&metadata.id).await?;
+        self.clear_summary(&metadata.id).await?;
+
+        // Batch upsert
+        let count = self.documents.upsert_batch(&documents).await?;
+        self.record_embedding().await?;
+        self.hooks.run_indexed(hook_ctx, &documents).await;
+
+        Ok(count)
+    }
+
+    /// Generate embeddings for chunks and turn them into documents.
+    async fn embed_chunks(
+        &self,
+        metadata: &MediaMetadata,
+        chunks: Vec<ContentChunk>,
+        transcript: &Transcript,
+    ) -> Result<Vec<Document>> {
+        let composite = self.settings.embedding.composite;
+        let texts: Vec<String> = chunks.iter().map(|c| c.embedding_text(composite)).collect();
         let embeddings = self.embedder.embed_batch(&texts).await?;
+        let (book, author) = self.audiobook_of(&metadata.id).await;
 
-        // Create documents
-        let documents: Vec<Document> = chunks
+        Ok(chunks
             .into_iter()
             .zip(embeddings)
             .map(|(chunk, embedding)| {
+                let keywords = extract_keywords(&chunk.content, self.settings.chunking.keywords_per_chunk);
                 Document::new(
                     metadata.id.clone(),
                     metadata.title.clone(),
@@ -262,23 +966,74 @@ impl Orchestrator {
                     chunk.order,
                     metadata.published_at,
                 )
+                .with_keywords(keywords)
+                .with_language(transcript.language.clone())
+                .with_summary(chunk.summary)
+                .with_book(book.clone(), author.clone())
+                .with_chapter(transcript.chapter_at(chunk.start_seconds).map(|c| c.title.clone()))
             })
-            .collect();
+            .collect())
+    }
 
-        // Batch upsert
-        let count = self.vector_store.upsert_batch(&documents).await?;
+    /// Transcribe, chunk and embed media without storing anything, for one-off
+    /// questions about audio that shouldn't join the library. Transcript and
+    /// chunk hooks still run; the caller is responsible for usage tracking.
+    #[instrument(skip(self))]
+    pub async fn transcribe_ephemeral(&self, input: &str) -> Result<Vec<Document>> {
+        let (source, media_id) = parse_input(input).ok_or_else(|| {
+            LyttError::InvalidInput(format!("Could not parse input: {}", input))
+        })?;
 
-        Ok(count)
+        let metadata = source.fetch_media(&media_id).await?;
+        let skipped = self.sponsorblock_ranges(&metadata).await;
+        let mut transcript = match captions_transcript(&self.settings.transcription, &metadata, self.workspace.path()).await {
+            Some(mut transcript) => {
+                sponsorblock::drop_skipped(&mut transcript, &skipped);
+                transcript
+            }
+            None => {
+                let audio_path = self.download(&metadata).await?;
+                let transcribed = self.transcribe_file(&audio_path, &skipped).await;
+                // Removed by transcribe_file once transcribed
+                if transcribed.is_err() {
+                    if let Err(e) = std::fs::remove_file(&audio_path) {
+                        warn!("Failed to cleanup audio file: {}", e);
+                    }
+                }
+                transcribed?
+            }
+        };
+        if transcript.chapters.is_empty() {
+            transcript.chapters = metadata.chapters.clone();
+        }
+
+        let hook_ctx = HookContext::new(&media_id, &metadata.title);
+        self.hooks.run_transcript(&hook_ctx, &mut transcript).await?;
+
+        let mut chunks = self.chunk_transcript(&transcript, &metadata).await?;
+        self.hooks.run_chunks(&hook_ctx, &mut chunks).await?;
+
+        self.embed_chunks(&metadata, chunks, &transcript).await
     }
 
     /// Rechunk existing media (re-chunk and re-embed without re-transcribing).
     /// Requires the transcript to be stored in the database.
     #[instrument(skip(self))]
     pub async fn rechunk_media(&self, video_id: &str) -> Result<ProcessResult> {
+        let tracker = UsageTracker::new();
+        let started_at = Utc::now();
+        let result = tracker.scope(self.rechunk_media_tracked(video_id)).await;
+        self.record_usage("rechunk", Some(video_id), &tracker).await;
+        self.record_run("rechunk", video_id, started_at, false, &tracker, result.as_ref()).await;
+        result
+    }
+
+    async fn rechunk_media_tracked(&self, video_id: &str) -> Result<ProcessResult> {
         // Get stored transcript
+        let id = video_id.to_string();
         let (title, transcript) = self
-            .vector_store
-            .get_transcript(video_id)?
+            .with_store(move |store| store.get_transcript(&id))
+            .await?
             .ok_or_else(|| {
                 LyttError::InvalidInput(format!(
                     "No stored transcript for '{}'. Transcripts are only stored for videos \
@@ -290,14 +1045,8 @@ impl Orchestrator {
         info!("Rechunking '{}' from stored transcript", title);
 
         // Chunk with current settings and prompts
-        let strategy: ChunkingStrategy = self
-            .settings
-            .chunking
-            .strategy
-            .parse()
-            .unwrap_or(ChunkingStrategy::Semantic);
-
-        let chunker = create_chunker_with_prompts(strategy, self.prompts.clone());
+        progress::report(ProgressStage::Chunk, format!("Rechunking '{}'", title));
+        let chunker = create_chunker_from_settings(&self.chunking_settings(video_id).await, self.prompts.clone())?;
 
         let config = ChunkingConfig {
             target_duration: self.settings.chunking.target_chunk_seconds,
@@ -305,20 +1054,29 @@ impl Orchestrator {
             max_duration: self.settings.chunking.max_chunk_seconds,
         };
 
-        let chunks = chunker.chunk(&transcript, &config).await?;
+        self.check_embedding().await?;
+        let mut chunks = self.until_cancelled(chunker.chunk(&transcript, &config)).await?;
 
-        // Delete old chunks
-        self.vector_store.delete_by_video_id(video_id).await?;
+        let hook_ctx = HookContext::new(video_id, &title);
+        self.hooks.run_chunks(&hook_ctx, &mut chunks).await?;
 
-        // Generate new embeddings
-        let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
-        let embeddings = self.embedder.embed_batch(&texts).await?;
+        // Generate new embeddings, before deleting the old chunks
+        progress::report(ProgressStage::Index, format!("Embedding and indexing {} chunks", chunks.len()));
+        let composite = self.settings.embedding.composite;
+        let texts: Vec<String> = chunks.iter().map(|c| c.embedding_text(composite)).collect();
+        let embeddings = self.until_cancelled(self.embedder.embed_batch(&texts)).await?;
+
+        // Delete old chunks
+        self.documents.delete_by_video_id(video_id).await?;
+        self.clear_summary(video_id).await?;
 
         // Create documents
+        let (book, author) = self.audiobook_of(video_id).await;
         let documents: Vec<Document> = chunks
             .into_iter()
             .zip(embeddings)
             .map(|(chunk, embedding)| {
+                let keywords = extract_keywords(&chunk.content, self.settings.chunking.keywords_per_chunk);
                 Document::new(
                     video_id.to_string(),
                     title.clone(),
@@ -330,23 +1088,183 @@ impl Orchestrator {
                     chunk.order,
                     None,
                 )
+                .with_keywords(keywords)
+                .with_language(transcript.language.clone())
+                .with_summary(chunk.summary)
+                .with_book(book.clone(), author.clone())
+                .with_chapter(transcript.chapter_at(chunk.start_seconds).map(|c| c.title.clone()))
             })
             .collect();
 
         // Index
-        let count = self.vector_store.upsert_batch(&documents).await?;
+        let count = self.documents.upsert_batch(&documents).await?;
+        self.record_embedding().await?;
+        self.record_chunking(video_id).await;
+        self.hooks.run_indexed(&hook_ctx, &documents).await;
 
         Ok(ProcessResult {
             media_id: video_id.to_string(),
             title,
             chunks_indexed: count,
             skipped: false,
+            duplicate_of: None,
         })
     }
 
+    /// Book title and author of media indexed as an audiobook.
+    async fn audiobook_of(&self, video_id: &str) -> (Option<String>, Option<String>) {
+        match self.get_audiobook(video_id).await {
+            Ok(Some((book, author))) => (Some(book), author),
+            Ok(None) => (None, None),
+            Err(e) => {
+                warn!("Failed to look up audiobook {}: {}", video_id, e);
+                (None, None)
+            }
+        }
+    }
+
+    /// Drop a stored summary (and its indexed overview) once the chunks it describes change.
+    async fn clear_summary(&self, video_id: &str) -> Result<()> {
+        let id = video_id.to_string();
+        if self.with_store(move |store| store.delete_summary(&id)).await? {
+            self.vector_store
+                .delete_by_video_id(&MediaSummary::document_id(video_id))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Remove a media item from the library: its chunks, summary, transcript and tags.
+    /// Returns the number of chunks deleted.
+    #[instrument(skip(self))]
+    pub async fn delete_media(&self, video_id: &str) -> Result<usize> {
+        let deleted = self.documents.delete_by_video_id(video_id).await?;
+        self.clear_summary(video_id).await?;
+        let id = video_id.to_string();
+        self.with_store(move |store| store.delete_media_records(&id)).await?;
+        Ok(deleted)
+    }
+
+    /// Re-embed every stored document with the configured embedding model,
+    /// after switching models or dimensions. Chunks and their text are kept.
+    ///
+    /// With `local`, stored vectors are shortened to the configured
+    /// dimensions (text-embedding-3 models only) and rewritten in the
+    /// configured vector format instead, without calling the embedding API.
+    /// Reports progress as (documents done, total).
+    pub async fn reembed_library(&self, local: bool, progress: &mut dyn FnMut(usize, usize)) -> Result<usize> {
+        const PAGE_SIZE: usize = 200;
+
+        if matches!(self.settings.vector_store.provider.as_str(), "postgres" | "columnar") {
+            return Err(LyttError::VectorStore(format!(
+                "Re-embedding works on the SQLite store only; re-index the media into the {} store instead",
+                self.settings.vector_store.provider
+            )));
+        }
+
+        let configured = self.configured_embedding();
+        if local {
+            self.check_local_reembed(&configured).await?;
+        }
+
+        let tracker = UsageTracker::new();
+        let total = self.vector_store.document_count().await?;
+        let composite = self.settings.embedding.composite;
+
+        let result = tracker
+            .scope(async {
+                let mut done = 0;
+                let mut after: Option<String> = None;
+                progress(done, total);
+                loop {
+                    let from = after.clone();
+                    let mut docs = self
+                        .with_store(move |store| store.documents_after(from.as_deref(), PAGE_SIZE))
+                        .await?;
+                    let Some(last) = docs.last() else {
+                        break;
+                    };
+                    after = Some(last.id.to_string());
+
+                    if local {
+                        for doc in docs.iter_mut() {
+                            if doc.embedding.len() > configured.dimensions {
+                                doc.embedding = shorten(&doc.embedding, configured.dimensions);
+                            }
+                        }
+                        self.vector_store.upsert_batch(&docs).await?;
+                        done += docs.len();
+                        progress(done, total);
+                        continue;
+                    }
+
+                    let texts: Vec<String> = docs
+                        .iter()
+                        .map(|d| match d.doc_type {
+                            DocType::Source if composite => {
+                                composite_text(d.section_title.as_deref(), d.summary.as_deref(), &d.content)
+                            }
+                            _ => d.content.clone(),
+                        })
+                        .collect();
+                    let embeddings = self.embedder.embed_batch(&texts).await?;
+                    for (doc, embedding) in docs.iter_mut().zip(embeddings) {
+                        doc.embedding = embedding;
+                    }
+                    self.vector_store.upsert_batch(&docs).await?;
+
+                    done += docs.len();
+                    progress(done, total);
+                }
+                self.record_embedding().await?;
+                Ok(done)
+            })
+            .await;
+
+        self.record_usage("reembed", None, &tracker).await;
+        result
+    }
+
+    /// Check that stored vectors can be brought to the configured embedding
+    /// settings without re-embedding.
+    async fn check_local_reembed(&self, configured: &EmbeddingInfo) -> Result<()> {
+        let Some(indexed) = self.with_store(|store| store.embedding_info()).await? else {
+            return Ok(());
+        };
+        if let (Some(indexed_model), Some(model)) = (&indexed.model, &configured.model) {
+            if indexed_model != model {
+                return Err(LyttError::InvalidInput(format!(
+                    "The library was embedded with {}, not {}; run 'lytt reembed' without --local",
+                    indexed_model, model
+                )));
+            }
+        }
+        if configured.dimensions > indexed.dimensions {
+            return Err(LyttError::InvalidInput(format!(
+                "Stored vectors have {} dimensions and can't be lengthened to {}; run 'lytt reembed' without --local",
+                indexed.dimensions, configured.dimensions
+            )));
+        }
+        let model = configured.model.as_deref().unwrap_or_default();
+        if configured.dimensions < indexed.dimensions && !model.starts_with("text-embedding-3") {
+            return Err(LyttError::InvalidInput(format!(
+                "{} embeddings can't be shortened; run 'lytt reembed' without --local",
+                model
+            )));
+        }
+        Ok(())
+    }
+
     /// List all videos that have stored transcripts (available for rechunking).
-    pub fn list_rechunkable(&self) -> Result<Vec<(String, String, f64)>> {
-        self.vector_store.list_transcripts()
+    pub async fn list_rechunkable(&self) -> Result<Vec<(String, String, f64)>> {
+        self.with_store(|store| store.list_transcripts()).await
+    }
+
+    /// Media with a stored transcript that was chunked with other chunking
+    /// settings or prompts, as (video_id, title, duration).
+    pub async fn list_stale(&self) -> Result<Vec<(String, String, f64)>> {
+        let fingerprint = self.chunking_fingerprint();
+        self.with_store(move |store| store.stale_transcripts(&fingerprint)).await
     }
 }
 
@@ -359,6 +1277,72 @@ pub struct ProcessResult {
     pub title: String,
     /// Number of chunks indexed.
     pub chunks_indexed: usize,
-    /// Whether processing was skipped (already indexed).
+    /// Whether processing was skipped (already indexed, or a likely duplicate).
     pub skipped: bool,
+    /// Indexed media this was skipped as a likely duplicate of.
+    pub duplicate_of: Option<String>,
+}
+
+/// The Postgres document store configured in `[vector_store]`.
+#[cfg(feature = "postgres")]
+fn postgres_store(settings: &Settings) -> Result<Arc<dyn VectorStore>> {
+    if settings.vector_store.postgres_url.is_empty() {
+        return Err(LyttError::Config(
+            "The postgres provider needs [vector_store] postgres_url".to_string(),
+        ));
+    }
+    Ok(Arc::new(PgVectorStore::new(
+        &settings.vector_store.postgres_url,
+        settings.embedding.dimensions as usize,
+    )?))
+}
+
+#[cfg(not(feature = "postgres"))]
+fn postgres_store(_settings: &Settings) -> Result<Arc<dyn VectorStore>> {
+    Err(LyttError::Config(
+        "Postgres support is not compiled in. Rebuild with: cargo install lytt --features postgres".to_string(),
+    ))
+}
+
+/// The columnar document store configured in `[vector_store]`.
+#[cfg(feature = "columnar")]
+fn columnar_store(settings: &Settings) -> Result<Arc<dyn VectorStore>> {
+    Ok(Arc::new(ColumnarVectorStore::open(&settings.columnar_path())?))
+}
+
+#[cfg(not(feature = "columnar"))]
+fn columnar_store(_settings: &Settings) -> Result<Arc<dyn VectorStore>> {
+    Err(LyttError::Config(
+        "Columnar store support is not compiled in. Rebuild with: cargo install lytt --features columnar".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_settings;
+
+    /// Error from creating an orchestrator with `configure` applied to the test settings.
+    fn config_error(dir: &Path, configure: impl FnOnce(&mut Settings)) -> String {
+        let mut settings = test_settings(dir);
+        configure(&mut settings);
+        match Orchestrator::new(settings) {
+            Ok(_) => panic!("settings were accepted"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unknown_vector_format_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = config_error(dir.path(), |s| s.vector_store.vector_format = "f8".to_string());
+        assert!(err.contains("vector_store.vector_format"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_quantization_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = config_error(dir.path(), |s| s.vector_store.quantization = "int3".to_string());
+        assert!(err.contains("vector_store.quantization"), "{}", err);
+    }
 }
@@ -0,0 +1,230 @@
+//! Study pack generation.
+
+use super::{merge_glossary, GlossaryEntry, KeyQuote, PackSection, StudyPack};
+use crate::agent::{ToolCall, ToolContext};
+use crate::config::Prompts;
+use crate::embedding::Embedder;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::quiz::QuizGenerator;
+use crate::summary::SummaryGenerator;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{SqliteVectorStore, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct NotesResponse {
+    #[serde(default)]
+    quotes: Vec<KeyQuote>,
+    #[serde(default)]
+    glossary: Vec<GlossaryEntry>,
+}
+
+/// Builds a study pack from the media with a tag.
+pub struct PackGenerator {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<SqliteVectorStore>,
+    embedder: Arc<dyn Embedder>,
+    prompts: Prompts,
+    cards: usize,
+    quotes: usize,
+}
+
+impl PackGenerator {
+    /// Create a new pack generator.
+    pub fn new(vector_store: Arc<SqliteVectorStore>, embedder: Arc<dyn Embedder>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            embedder,
+            prompts: Prompts::default(),
+            cards: 5,
+            quotes: 5,
+        }
+    }
+
+    /// Set the number of flashcards per media item.
+    pub fn with_cards(mut self, cards: usize) -> Self {
+        self.cards = cards;
+        self
+    }
+
+    /// Set the number of key quotes per media item.
+    pub fn with_quotes(mut self, quotes: usize) -> Self {
+        self.quotes = quotes;
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Generate a pack for every media item tagged `tag`. Stored summaries are
+    /// reused; media without one is summarized (and the summary stored) first.
+    #[instrument(skip(self))]
+    pub async fn generate(&self, tag: &str) -> Result<StudyPack> {
+        let video_ids = self.vector_store.videos_with_tag(tag)?;
+
+        let tools = ToolContext::new(self.vector_store.clone(), self.embedder.clone());
+        let quiz = QuizGenerator::new(tools.clone(), &self.model)
+            .with_prompts(self.prompts.clone())
+            .with_client(self.client.clone());
+        let summaries = SummaryGenerator::new(self.vector_store.clone(), self.embedder.clone(), &self.model)
+            .with_prompts(self.prompts.clone())
+            .with_client(self.client.clone());
+
+        let mut sections = Vec::new();
+        let mut glossary = Vec::new();
+
+        for video_id in video_ids {
+            // Tagged but no longer indexed
+            if !self.vector_store.is_video_indexed(&video_id).await? {
+                continue;
+            }
+
+            let summary = match self.vector_store.get_summary(&video_id)? {
+                Some(summary) => summary,
+                None => summaries.generate(&video_id).await?,
+            };
+            info!("Adding '{}' to the pack", summary.video_title);
+
+            let transcript = tools
+                .execute(&ToolCall::GetTranscript {
+                    video_id: video_id.clone(),
+                })
+                .await?;
+            let (quotes, terms) = self.extract_notes(&summary.video_title, &transcript).await?;
+            glossary.extend(terms);
+
+            sections.push(PackSection {
+                quiz: quiz.generate(&video_id, self.cards).await?,
+                video_id,
+                video_title: summary.video_title,
+                overview: summary.overview,
+                quotes,
+            });
+        }
+
+        if sections.is_empty() {
+            return Err(LyttError::InvalidInput(format!("No indexed media tagged '{}'", tag)));
+        }
+
+        Ok(StudyPack {
+            tag: tag.to_string(),
+            sections,
+            glossary: merge_glossary(glossary),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Pick key quotes and glossary terms from one transcript.
+    async fn extract_notes(&self, title: &str, transcript: &str) -> Result<(Vec<KeyQuote>, Vec<GlossaryEntry>)> {
+        let mut vars = HashMap::new();
+        vars.insert("title".to_string(), title.to_string());
+        vars.insert("quotes".to_string(), self.quotes.to_string());
+        vars.insert("transcript".to_string(), transcript.to_string());
+
+        let system = self.prompts.render_with_custom(&self.prompts.pack.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.pack.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Agent(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Agent(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Agent(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Agent, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Agent("Empty response from LLM".to_string()))?;
+
+        let (mut quotes, glossary) = parse_notes(content, title)?;
+        quotes.truncate(self.quotes);
+        Ok((quotes, glossary))
+    }
+}
+
+/// Parse the `{"quotes": [...], "glossary": [...]}` response, dropping empty
+/// entries and attributing glossary terms to `title`.
+fn parse_notes(content: &str, title: &str) -> Result<(Vec<KeyQuote>, Vec<GlossaryEntry>)> {
+    let parsed: NotesResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Agent(format!("Invalid study notes JSON: {}", e)))?;
+
+    let quotes = parsed
+        .quotes
+        .into_iter()
+        .filter(|q| !q.quote.trim().is_empty())
+        .collect();
+
+    let glossary = parsed
+        .glossary
+        .into_iter()
+        .filter(|g| !g.term.trim().is_empty() && !g.definition.trim().is_empty())
+        .map(|g| GlossaryEntry {
+            source: title.to_string(),
+            ..g
+        })
+        .collect();
+
+    Ok((quotes, glossary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notes() {
+        let (quotes, glossary) = parse_notes(
+            r#"{
+                "quotes": [{"quote": "Entropy measures surprise.", "timestamp": "04:12"}, {"quote": " "}],
+                "glossary": [{"term": "Entropy", "definition": "Expected information."}, {"term": "Bit", "definition": ""}]
+            }"#,
+            "Lecture 1",
+        )
+        .unwrap();
+
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].timestamp.as_deref(), Some("04:12"));
+        assert_eq!(glossary.len(), 1);
+        assert_eq!(glossary[0].source, "Lecture 1");
+        assert!(parse_notes("not json", "Lecture 1").is_err());
+    }
+}
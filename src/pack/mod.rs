@@ -0,0 +1,228 @@
+//! Study packs: one Markdown packet covering every media item with a tag.
+//!
+//! A pack combines each item's summary, key quotes with timestamps and
+//! flashcards, plus a glossary merged across the whole tag. Summaries and
+//! flashcards come from the summary and quiz generators; quotes and glossary
+//! terms are extracted from the transcript with a structured-output prompt.
+
+mod generator;
+
+pub use generator::PackGenerator;
+
+use crate::quiz::Quiz;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A verbatim quote from a transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyQuote {
+    /// The quoted words.
+    pub quote: String,
+    /// Transcript timestamp (MM:SS), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+/// A term and its definition as used in the source material.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    /// The term.
+    pub term: String,
+    /// Short definition.
+    pub definition: String,
+    /// Title of the media the definition comes from.
+    #[serde(default)]
+    pub source: String,
+    /// Transcript timestamp (MM:SS), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+/// Everything the pack contains for one media item.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackSection {
+    /// Media ID.
+    pub video_id: String,
+    /// Media title.
+    pub video_title: String,
+    /// Overview from the media's summary.
+    pub overview: String,
+    /// Key quotes, in transcript order.
+    pub quotes: Vec<KeyQuote>,
+    /// Flashcards for the media.
+    pub quiz: Quiz,
+}
+
+/// A study pack for one tag.
+#[derive(Debug, Clone, Serialize)]
+pub struct StudyPack {
+    /// Tag the pack covers.
+    pub tag: String,
+    /// One section per media item.
+    pub sections: Vec<PackSection>,
+    /// Glossary across all sections, sorted by term.
+    pub glossary: Vec<GlossaryEntry>,
+    /// When the pack was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl StudyPack {
+    /// Render the pack as a single Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Study Pack: {}\n\n", self.tag);
+        output.push_str(&format!(
+            "*{} items, generated {}*\n\n## Contents\n\n",
+            self.sections.len(),
+            self.created_at.format("%Y-%m-%d")
+        ));
+
+        for (i, section) in self.sections.iter().enumerate() {
+            output.push_str(&format!("{}. {}\n", i + 1, section.video_title));
+        }
+        if !self.glossary.is_empty() {
+            output.push_str(&format!("{}. Glossary\n", self.sections.len() + 1));
+        }
+        output.push_str(&format!("{}. Quiz\n", self.sections.len() + 2));
+
+        for section in &self.sections {
+            output.push_str(&format!("\n## {}\n\n### Summary\n\n{}\n", section.video_title, section.overview));
+
+            if !section.quotes.is_empty() {
+                output.push_str("\n### Key Quotes\n");
+                for quote in &section.quotes {
+                    output.push_str(&format!("\n> {}\n", quote.quote));
+                    if let Some(timestamp) = &quote.timestamp {
+                        output.push_str(&format!(">\n> — @ {}\n", timestamp));
+                    }
+                }
+            }
+        }
+
+        if !self.glossary.is_empty() {
+            output.push_str("\n## Glossary\n\n");
+            for entry in &self.glossary {
+                output.push_str(&format!("**{}**: {}", entry.term, entry.definition));
+                match &entry.timestamp {
+                    Some(timestamp) => output.push_str(&format!(" *({} @ {})*\n\n", entry.source, timestamp)),
+                    None => output.push_str(&format!(" *({})*\n\n", entry.source)),
+                }
+            }
+        }
+
+        output.push_str("\n## Quiz\n");
+        for section in &self.sections {
+            output.push_str(&format!("\n### {}\n", section.video_title));
+            for (i, card) in section.quiz.cards.iter().enumerate() {
+                output.push_str(&format!("\n{}. **{}**\n\n   {}", i + 1, card.question, card.answer));
+                if let Some(timestamp) = &card.timestamp {
+                    output.push_str(&format!(" *@ {}*", timestamp));
+                }
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    /// Render all flashcards as one Anki text import.
+    pub fn to_anki(&self) -> String {
+        let mut output = String::new();
+        for (i, section) in self.sections.iter().enumerate() {
+            let deck = section.quiz.to_anki();
+            if i == 0 {
+                output.push_str(&deck);
+            } else {
+                // Only the first file header is kept
+                for line in deck.lines().filter(|l| !l.starts_with('#')) {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Merge glossaries from several media: the first definition of a term wins
+/// (case-insensitive) and the result is sorted by term.
+pub fn merge_glossary(entries: impl IntoIterator<Item = GlossaryEntry>) -> Vec<GlossaryEntry> {
+    let mut merged: Vec<GlossaryEntry> = Vec::new();
+    for entry in entries {
+        let key = entry.term.trim().to_lowercase();
+        if key.is_empty() || merged.iter().any(|e| e.term.to_lowercase() == key) {
+            continue;
+        }
+        merged.push(GlossaryEntry {
+            term: entry.term.trim().to_string(),
+            ..entry
+        });
+    }
+    merged.sort_by_key(|e| e.term.to_lowercase());
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quiz::Flashcard;
+
+    fn entry(term: &str, source: &str) -> GlossaryEntry {
+        GlossaryEntry {
+            term: term.to_string(),
+            definition: format!("Definition from {}", source),
+            source: source.to_string(),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_glossary() {
+        let merged = merge_glossary(vec![
+            entry("Entropy", "Lecture 1"),
+            entry(" bit ", "Lecture 1"),
+            entry("entropy", "Lecture 2"),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].term, "bit");
+        assert_eq!(merged[1].source, "Lecture 1");
+    }
+
+    #[test]
+    fn test_to_markdown_and_anki() {
+        let section = |id: &str, title: &str| PackSection {
+            video_id: id.to_string(),
+            video_title: title.to_string(),
+            overview: "Overview.".to_string(),
+            quotes: vec![KeyQuote {
+                quote: "Information is surprise.".to_string(),
+                timestamp: Some("02:10".to_string()),
+            }],
+            quiz: Quiz {
+                video_id: id.to_string(),
+                video_title: title.to_string(),
+                cards: vec![Flashcard {
+                    question: "What is a bit?".to_string(),
+                    answer: "A binary digit.".to_string(),
+                    timestamp: None,
+                }],
+            },
+        };
+        let pack = StudyPack {
+            tag: "exam-prep".to_string(),
+            sections: vec![section("a", "Lecture 1"), section("b", "Lecture 2")],
+            glossary: vec![entry("Bit", "Lecture 1")],
+            created_at: Utc::now(),
+        };
+
+        let markdown = pack.to_markdown();
+        assert!(markdown.starts_with("# Study Pack: exam-prep"));
+        assert!(markdown.contains("## Lecture 2\n\n### Summary"));
+        assert!(markdown.contains("> — @ 02:10"));
+        assert!(markdown.contains("**Bit**: Definition from Lecture 1 *(Lecture 1)*"));
+
+        let anki = pack.to_anki();
+        assert_eq!(anki.matches("#separator:tab").count(), 1);
+        assert_eq!(anki.lines().filter(|l| !l.starts_with('#')).count(), 2);
+    }
+}
@@ -0,0 +1,161 @@
+//! External command plugins speaking newline-delimited JSON.
+//!
+//! The plugin is started once and kept running. Each request is written to
+//! its stdin as a single JSON line, and the plugin answers with a single JSON
+//! line on stdout. A response of the form `{"error": "message"}` is reported
+//! as a failure. Anything the plugin writes to stderr is passed through.
+
+use crate::error::{LyttError, Result};
+use serde_json::Value;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// A running plugin process.
+struct Running {
+    // Held so the process is killed when the plugin is dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+/// An external plugin process, started on first use.
+pub struct PluginProcess {
+    command: String,
+    args: Vec<String>,
+    process: Mutex<Option<Running>>,
+}
+
+impl PluginProcess {
+    /// Create a plugin for the given command. The process isn't started yet.
+    pub fn new(command: &str, args: Vec<String>) -> Self {
+        Self {
+            command: shellexpand::tilde(command).to_string(),
+            args,
+            process: Mutex::new(None),
+        }
+    }
+
+    /// Command this plugin runs.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    fn spawn(&self) -> Result<Running> {
+        debug!("Starting plugin: {} {:?}", self.command, self.args);
+
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    LyttError::ToolNotFound(self.command.clone())
+                } else {
+                    LyttError::ToolFailed(format!("{}: {}", self.command, e))
+                }
+            })?;
+
+        let stdin = child.stdin.take().ok_or_else(|| {
+            LyttError::ToolFailed(format!("{}: failed to open stdin", self.command))
+        })?;
+        let stdout = child.stdout.take().ok_or_else(|| {
+            LyttError::ToolFailed(format!("{}: failed to open stdout", self.command))
+        })?;
+
+        Ok(Running {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    /// Send one request and wait for the response.
+    ///
+    /// Requests are serialized; if the exchange fails the process is restarted
+    /// on the next request.
+    pub async fn request(&self, request: &Value) -> Result<Value> {
+        let mut guard = self.process.lock().await;
+
+        if guard.is_none() {
+            *guard = Some(self.spawn()?);
+        }
+
+        let result = match guard.as_mut() {
+            Some(running) => Self::exchange(running, request).await,
+            None => unreachable!("plugin process was just started"),
+        };
+
+        let line = match result {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Plugin {} failed, it will be restarted: {}", self.command, e);
+                *guard = None;
+                return Err(LyttError::ToolFailed(format!("{}: {}", self.command, e)));
+            }
+        };
+
+        let response: Value = serde_json::from_str(&line).map_err(|e| {
+            LyttError::ToolFailed(format!("{}: invalid JSON response: {}", self.command, e))
+        })?;
+
+        if let Some(error) = response.get("error").and_then(|e| e.as_str()) {
+            return Err(LyttError::ToolFailed(format!("{}: {}", self.command, error)));
+        }
+
+        Ok(response)
+    }
+
+    async fn exchange(running: &mut Running, request: &Value) -> std::io::Result<String> {
+        let mut line = request.to_string();
+        line.push('\n');
+        running.stdin.write_all(line.as_bytes()).await?;
+        running.stdin.flush().await?;
+
+        let mut response = String::new();
+        let read = running.stdout.read_line(&mut response).await?;
+        if read == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "plugin exited without responding",
+            ));
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_plugin_round_trip() {
+        // `cat` echoes each request line back as its response
+        let plugin = PluginProcess::new("cat", Vec::new());
+
+        let response = plugin.request(&json!({"type": "ping", "n": 1})).await.unwrap();
+        assert_eq!(response["n"], 1);
+
+        // The same process handles later requests
+        let response = plugin.request(&json!({"type": "ping", "n": 2})).await.unwrap();
+        assert_eq!(response["n"], 2);
+
+        let error = plugin.request(&json!({"error": "boom"})).await.unwrap_err();
+        assert!(error.to_string().contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_not_found() {
+        let plugin = PluginProcess::new("lytt-no-such-plugin", Vec::new());
+        let error = plugin.request(&json!({})).await.unwrap_err();
+        assert!(matches!(error, LyttError::ToolNotFound(_)));
+    }
+}
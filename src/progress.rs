@@ -0,0 +1,106 @@
+//! Stage-level progress reporting for long pipeline runs.
+//!
+//! Like usage tracking, progress goes to the reporter of the current task:
+//! callers that want updates wrap the work in [`ProgressReporter::scope`], and
+//! [`report`] is a no-op everywhere else.
+
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    static CURRENT: ProgressReporter;
+}
+
+/// Pipeline stage, in the order a transcription goes through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStage {
+    /// Fetching media metadata.
+    Metadata,
+    /// Downloading or extracting audio.
+    Download,
+    /// Speech-to-text and cleanup.
+    Transcribe,
+    /// Splitting the transcript into chunks.
+    Chunk,
+    /// Embedding and indexing chunks.
+    Index,
+}
+
+impl ProgressStage {
+    /// Number of stages in a full transcription.
+    pub const TOTAL: u32 = 5;
+
+    /// 1-based position of the stage in a full transcription.
+    pub fn step(&self) -> u32 {
+        match self {
+            ProgressStage::Metadata => 1,
+            ProgressStage::Download => 2,
+            ProgressStage::Transcribe => 3,
+            ProgressStage::Chunk => 4,
+            ProgressStage::Index => 5,
+        }
+    }
+}
+
+/// A progress update: the stage that just started and a short description.
+#[derive(Debug, Clone)]
+pub struct ProgressUpdate {
+    pub stage: ProgressStage,
+    pub message: String,
+}
+
+/// Receives progress updates for the current task.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    callback: Arc<dyn Fn(ProgressUpdate) + Send + Sync>,
+}
+
+impl ProgressReporter {
+    /// Create a reporter that passes every update to `callback`.
+    pub fn new(callback: impl Fn(ProgressUpdate) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Run a future with this reporter receiving its progress updates.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT.scope(self.clone(), fut).await
+    }
+}
+
+/// Report that a stage has started to the current task's reporter, if any.
+pub fn report(stage: ProgressStage, message: impl Into<String>) {
+    let _ = CURRENT.try_with(|reporter| {
+        (reporter.callback)(ProgressUpdate {
+            stage,
+            message: message.into(),
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_report_in_scope() {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        let reporter = ProgressReporter::new(move |u| sink.lock().unwrap().push((u.stage.step(), u.message)));
+
+        report(ProgressStage::Metadata, "ignored outside a scope");
+        reporter
+            .scope(async {
+                report(ProgressStage::Download, "Downloading audio");
+                report(ProgressStage::Index, "Indexing");
+            })
+            .await;
+
+        assert_eq!(
+            *updates.lock().unwrap(),
+            vec![(2, "Downloading audio".to_string()), (5, "Indexing".to_string())]
+        );
+    }
+}
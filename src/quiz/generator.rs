@@ -0,0 +1,154 @@
+//! Quiz generation.
+
+use super::{Flashcard, Quiz};
+use crate::agent::{ToolCall, ToolContext};
+use crate::config::Prompts;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{info, instrument};
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct CardsResponse {
+    cards: Vec<Flashcard>,
+}
+
+/// Generates flashcards from a media item's transcript.
+pub struct QuizGenerator {
+    client: ChatClient,
+    model: String,
+    tools: ToolContext,
+    prompts: Prompts,
+}
+
+impl QuizGenerator {
+    /// Create a new quiz generator.
+    pub fn new(tools: ToolContext, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            tools,
+            prompts: Prompts::default(),
+        }
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Generate up to `count` cards for a media item.
+    #[instrument(skip(self))]
+    pub async fn generate(&self, video_id: &str, count: usize) -> Result<Quiz> {
+        let video = self
+            .tools
+            .vector_store
+            .get_video(video_id)
+            .await?
+            .ok_or_else(|| LyttError::VideoNotFound(video_id.to_string()))?;
+
+        // Same transcript view the agent gets from its get_transcript tool
+        let transcript = self
+            .tools
+            .execute(&ToolCall::GetTranscript {
+                video_id: video_id.to_string(),
+            })
+            .await?;
+
+        info!("Generating {} cards for '{}'", count, video.video_title);
+
+        let mut vars = HashMap::new();
+        vars.insert("title".to_string(), video.video_title.clone());
+        vars.insert("count".to_string(), count.to_string());
+        vars.insert("transcript".to_string(), transcript);
+
+        let system = self.prompts.render_with_custom(&self.prompts.quiz.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.quiz.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Agent(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Agent(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Agent(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Agent, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Agent("Empty response from LLM".to_string()))?;
+
+        let mut cards = parse_cards(content)?;
+        cards.truncate(count);
+
+        Ok(Quiz {
+            video_id: video_id.to_string(),
+            video_title: video.video_title,
+            cards,
+        })
+    }
+}
+
+/// Parse the `{"cards": [...]}` response, dropping empty cards.
+fn parse_cards(content: &str) -> Result<Vec<Flashcard>> {
+    let parsed: CardsResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Agent(format!("Invalid quiz JSON: {}", e)))?;
+
+    Ok(parsed
+        .cards
+        .into_iter()
+        .filter(|c| !c.question.trim().is_empty() && !c.answer.trim().is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cards() {
+        let cards = parse_cards(
+            r#"{"cards": [
+                {"question": "What is a bit?", "answer": "A binary digit.", "timestamp": "01:10"},
+                {"question": "", "answer": "Dropped"},
+                {"question": "Why base 2?", "answer": "Two voltage levels."}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(cards.len(), 2);
+        assert_eq!(cards[0].timestamp.as_deref(), Some("01:10"));
+        assert!(cards[1].timestamp.is_none());
+        assert!(parse_cards("not json").is_err());
+    }
+}
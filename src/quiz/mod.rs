@@ -0,0 +1,157 @@
+//! Quiz and flashcard generation from indexed media.
+//!
+//! Cards are generated from a media item's transcript with a structured-output
+//! prompt and can be exported as an Anki-importable deck, JSON, or Markdown.
+
+mod generator;
+
+pub use generator::QuizGenerator;
+
+use serde::{Deserialize, Serialize};
+
+/// A question/answer pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Flashcard {
+    /// Question shown on the front of the card.
+    pub question: String,
+    /// Answer shown on the back of the card.
+    pub answer: String,
+    /// Transcript timestamp (MM:SS) where the answer is covered, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+/// Generated cards for one media item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quiz {
+    /// Media the quiz covers.
+    pub video_id: String,
+    /// Title of the media.
+    pub video_title: String,
+    /// Generated cards.
+    pub cards: Vec<Flashcard>,
+}
+
+/// Output format for a quiz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuizFormat {
+    /// Tab-separated text file for Anki's "Import File".
+    Anki,
+    /// JSON document.
+    Json,
+    /// Markdown list of questions and answers.
+    Markdown,
+}
+
+impl std::str::FromStr for QuizFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "anki" => Ok(QuizFormat::Anki),
+            "json" => Ok(QuizFormat::Json),
+            "markdown" | "md" => Ok(QuizFormat::Markdown),
+            _ => Err(format!("Unknown quiz format: {}. Use anki, json, or markdown.", s)),
+        }
+    }
+}
+
+impl Quiz {
+    /// Render the quiz in the given format.
+    pub fn render(&self, format: QuizFormat) -> crate::error::Result<String> {
+        Ok(match format {
+            QuizFormat::Anki => self.to_anki(),
+            QuizFormat::Json => serde_json::to_string_pretty(self)?,
+            QuizFormat::Markdown => self.to_markdown(),
+        })
+    }
+
+    /// Render as an Anki text import: one note per line with front, back, and tags.
+    pub fn to_anki(&self) -> String {
+        let tag = format!("lytt {}", anki_tag(&self.video_id));
+        let mut output = String::from("#separator:tab\n#html:true\n#tags column:3\n");
+
+        for card in &self.cards {
+            let mut back = anki_field(&card.answer);
+            back.push_str(&format!("<br><br><i>{}", anki_field(&self.video_title)));
+            if let Some(timestamp) = &card.timestamp {
+                back.push_str(&format!(" @ {}", anki_field(timestamp)));
+            }
+            back.push_str("</i>");
+
+            output.push_str(&format!("{}\t{}\t{}\n", anki_field(&card.question), back, tag));
+        }
+
+        output
+    }
+
+    /// Render as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Quiz: {}\n", self.video_title);
+
+        for (i, card) in self.cards.iter().enumerate() {
+            output.push_str(&format!("\n## {}. {}\n\n{}\n", i + 1, card.question, card.answer));
+            if let Some(timestamp) = &card.timestamp {
+                output.push_str(&format!("\n*@ {}*\n", timestamp));
+            }
+        }
+
+        output
+    }
+}
+
+/// Escape text for an Anki field (tabs separate fields, HTML is enabled).
+fn anki_field(text: &str) -> String {
+    text.trim()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\t', " ")
+        .replace('\n', "<br>")
+}
+
+/// Anki tags can't contain spaces.
+fn anki_tag(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiz() -> Quiz {
+        Quiz {
+            video_id: "abc123".to_string(),
+            video_title: "Lecture <1>".to_string(),
+            cards: vec![Flashcard {
+                question: "What is 2\tin binary?".to_string(),
+                answer: "10\nbecause 2 = 1*2 + 0".to_string(),
+                timestamp: Some("03:45".to_string()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_to_anki() {
+        let anki = quiz().to_anki();
+        let lines: Vec<&str> = anki.lines().collect();
+        assert_eq!(lines[0], "#separator:tab");
+        assert_eq!(lines.len(), 4);
+
+        let fields: Vec<&str> = lines[3].split('\t').collect();
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[0], "What is 2 in binary?");
+        assert_eq!(fields[1], "10<br>because 2 = 1*2 + 0<br><br><i>Lecture &lt;1&gt; @ 03:45</i>");
+        assert_eq!(fields[2], "lytt abc123");
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!("anki".parse::<QuizFormat>().unwrap(), QuizFormat::Anki);
+        assert_eq!("md".parse::<QuizFormat>().unwrap(), QuizFormat::Markdown);
+        assert!("csv".parse::<QuizFormat>().is_err());
+        assert!(quiz().to_markdown().contains("## 1. What is 2\tin binary?"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Resolving citation markers in answers to structured citations.
+//!
+//! Sources are numbered in the prompt, and the model cites them as `[2]`,
+//! `[1, 3]`, or `[2: "short verbatim quote"]`. Markers are resolved to the
+//! numbered sources, quotes are located in the source text so clients can
+//! highlight them, and the answer keeps only the plain `[n]` markers.
+
+use super::{cited_sources, ContextChunk};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// A quote from a source, located in its text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteSpan {
+    /// The quoted text as it appears in the source.
+    pub text: String,
+    /// Byte offset of the quote in the source content.
+    pub start: usize,
+    /// Byte offset just past the quote.
+    pub end: usize,
+}
+
+/// A source cited in an answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    /// Marker number used in the answer (`[n]`), the source's position in
+    /// the response's sources, starting at 1.
+    pub marker: usize,
+    /// ID of the cited document.
+    pub document_id: uuid::Uuid,
+    /// Video ID.
+    pub video_id: String,
+    /// Video title.
+    pub video_title: String,
+    /// Chapter the source starts in (if the media has chapters).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<String>,
+    /// Formatted start timestamp (e.g., "02:34").
+    pub timestamp: String,
+    /// Start time in seconds.
+    pub start_seconds: f64,
+    /// End time in seconds.
+    pub end_seconds: f64,
+    /// URL with timestamp (if available).
+    pub url: Option<String>,
+    /// Supporting quote, when the model gave one found in the source.
+    pub quote: Option<QuoteSpan>,
+}
+
+impl Citation {
+    fn new(marker: usize, source: &ContextChunk, quote: Option<QuoteSpan>) -> Self {
+        Self {
+            marker,
+            document_id: source.document_id,
+            video_id: source.video_id.clone(),
+            video_title: source.video_title.clone(),
+            chapter: source.chapter.clone(),
+            timestamp: source.timestamp.clone(),
+            start_seconds: source.start_seconds,
+            end_seconds: source.end_seconds,
+            url: source.url.clone(),
+            quote,
+        }
+    }
+}
+
+fn marker_regex() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| {
+        Regex::new(r#"\[(\d+(?:\s*,\s*\d+)*)(?:\s*:\s*["“]([^"”\]]*)["”])?\]"#).expect("valid citation regex")
+    })
+}
+
+/// Resolve the citation markers in `answer` against the numbered `sources`.
+///
+/// Returns the answer with quotes stripped from its markers, and one
+/// citation per distinct source and quote, in order of first mention.
+/// Answers written with the older `[Title @ MM:SS]` format (e.g. from
+/// custom prompts) are matched by title and timestamp instead.
+pub fn resolve_citations(answer: &str, sources: &[ContextChunk]) -> (String, Vec<Citation>) {
+    let mut citations: Vec<Citation> = Vec::new();
+
+    let cleaned = marker_regex().replace_all(answer, |caps: &regex::Captures| {
+        let markers: Vec<usize> = caps[1]
+            .split(',')
+            .filter_map(|n| n.trim().parse().ok())
+            .filter(|n| (1..=sources.len()).contains(n))
+            .collect();
+        if markers.is_empty() {
+            return caps[0].to_string();
+        }
+
+        // A quote belongs to the single source it was given with
+        let quote = caps.get(2).map(|q| q.as_str().trim()).filter(|q| !q.is_empty());
+        for &marker in &markers {
+            let source = &sources[marker - 1];
+            let span = quote.filter(|_| markers.len() == 1).and_then(|q| locate_quote(&source.content, q));
+            push(&mut citations, Citation::new(marker, source, span));
+        }
+
+        format!(
+            "[{}]",
+            markers.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")
+        )
+    });
+
+    if citations.is_empty() {
+        for source in cited_sources(answer, sources) {
+            if let Some(index) = sources.iter().position(|s| std::ptr::eq(s, source)) {
+                push(&mut citations, Citation::new(index + 1, source, None));
+            }
+        }
+    }
+
+    (cleaned.into_owned(), citations)
+}
+
+/// Add a citation unless the same source was already cited with the same
+/// (or, for a citation without a quote, any) quote.
+fn push(citations: &mut Vec<Citation>, citation: Citation) {
+    let seen = citations
+        .iter()
+        .any(|c| c.marker == citation.marker && (c.quote == citation.quote || citation.quote.is_none()));
+    if !seen {
+        citations.push(citation);
+    }
+}
+
+/// Find `quote` in `content`, ignoring case and differences in whitespace.
+fn locate_quote(content: &str, quote: &str) -> Option<QuoteSpan> {
+    let words: Vec<String> = quote.split_whitespace().map(regex::escape).collect();
+    if words.is_empty() {
+        return None;
+    }
+    let pattern = Regex::new(&format!("(?i){}", words.join(r"\s+"))).ok()?;
+    pattern.find(content).map(|m| QuoteSpan {
+        text: m.as_str().to_string(),
+        start: m.start(),
+        end: m.end(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::DocType;
+
+    fn source(title: &str, timestamp: &str, content: &str) -> ContextChunk {
+        ContextChunk {
+            document_id: uuid::Uuid::new_v4(),
+            video_id: title.to_lowercase(),
+            video_title: title.to_string(),
+            chapter: None,
+            timestamp: timestamp.to_string(),
+            start_seconds: 125.0,
+            end_seconds: 150.0,
+            content: content.to_string(),
+            summary: None,
+            score: 0.9,
+            url: None,
+            doc_type: DocType::Source,
+        }
+    }
+
+    #[test]
+    fn test_resolve_citations() {
+        let sources = vec![
+            source("Rust Talk", "02:05", "The borrow checker enforces\nownership at compile time."),
+            source("Go Talk", "10:00", "Goroutines are cheap."),
+        ];
+        let answer = r#"Rust checks ownership statically [1: "enforces ownership at compile time"]. Both are fast [1, 2]. See also [7]."#;
+
+        let (cleaned, citations) = resolve_citations(answer, &sources);
+        assert_eq!(cleaned, "Rust checks ownership statically [1]. Both are fast [1, 2]. See also [7].");
+        assert_eq!(citations.len(), 2);
+        assert_eq!(citations[0].marker, 1);
+        assert_eq!(citations[0].end_seconds, 150.0);
+        let quote = citations[0].quote.as_ref().unwrap();
+        assert_eq!(quote.text, "enforces\nownership at compile time");
+        assert_eq!(&sources[0].content[quote.start..quote.end], quote.text);
+        assert_eq!(citations[1].video_title, "Go Talk");
+        assert!(citations[1].quote.is_none());
+
+        // Quotes not found in the source are dropped
+        let (_, citations) = resolve_citations(r#"Claim [2: "made up words"]"#, &sources);
+        assert!(citations[0].quote.is_none());
+
+        // Older title/timestamp citations still resolve
+        let (_, citations) = resolve_citations("Goroutines [Go Talk @ 10:00]", &sources);
+        assert_eq!(citations[0].marker, 2);
+    }
+}
@@ -0,0 +1,179 @@
+//! Answer confidence from retrieval scores and the model's self-assessment.
+//!
+//! Similarity scores say how close the best excerpts are to the question,
+//! not whether they answer it, so the model also rates how well the excerpts
+//! cover the question on a final `Confidence:` line. The two are averaged;
+//! answers from custom prompts without that line use retrieval alone.
+
+use super::ContextChunk;
+use crate::config::RagSettings;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Similarities that map to retrieval confidence 0 and 1. Which similarity
+/// means a good match depends on the embedding model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceScale {
+    /// Similarity at or below which retrieval confidence is zero.
+    pub floor: f32,
+    /// Similarity at or above which retrieval confidence is one.
+    pub ceiling: f32,
+}
+
+impl ConfidenceScale {
+    /// Scale from `rag.confidence_floor` and `rag.confidence_ceiling`.
+    pub fn from_settings(settings: &RagSettings) -> Self {
+        Self {
+            floor: settings.confidence_floor,
+            ceiling: settings.confidence_ceiling,
+        }
+    }
+}
+
+impl Default for ConfidenceScale {
+    fn default() -> Self {
+        Self { floor: 0.3, ceiling: 0.8 }
+    }
+}
+
+/// Instruction added to non-streamed answer requests.
+pub(crate) const SELF_ASSESSMENT_INSTRUCTION: &str = "After the answer, add a final line \
+`Confidence: X` where X is a number from 0 to 1 rating how completely the excerpts answer \
+the question (0 = not at all, 1 = fully and explicitly).";
+
+/// How much an answer can be trusted, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Confidence {
+    /// Combined confidence.
+    pub score: f32,
+    /// Confidence from the similarity of the retrieved excerpts.
+    pub retrieval: f32,
+    /// The model's own rating, when it gave one.
+    pub model: Option<f32>,
+}
+
+impl Confidence {
+    /// Combine retrieval confidence with the model's rating, if any.
+    pub fn new(retrieval: f32, model: Option<f32>) -> Self {
+        let score = match model {
+            Some(model) => (retrieval + model) / 2.0,
+            None => retrieval,
+        };
+        Self { score, retrieval, model }
+    }
+
+    /// Confidence from retrieval scores alone (before the answer is written).
+    pub fn from_sources(sources: &[ContextChunk], scale: ConfidenceScale) -> Self {
+        Self::new(retrieval_confidence(sources, scale), None)
+    }
+
+    /// Whether the answer is too uncertain to give.
+    pub fn is_below(&self, threshold: f32) -> bool {
+        self.score < threshold
+    }
+}
+
+/// Retrieval confidence: the best score blended with the mean of the top
+/// three, rescaled from `scale.floor`..`scale.ceiling` to 0..1.
+pub fn retrieval_confidence(sources: &[ContextChunk], scale: ConfidenceScale) -> f32 {
+    let mut scores: Vec<f32> = sources.iter().map(|s| s.score).collect();
+    if scores.is_empty() {
+        return 0.0;
+    }
+    scores.sort_by(|a, b| b.total_cmp(a));
+
+    let top = &scores[..scores.len().min(3)];
+    let mean = top.iter().sum::<f32>() / top.len() as f32;
+    let blended = 0.6 * scores[0] + 0.4 * mean;
+    let range = (scale.ceiling - scale.floor).max(f32::EPSILON);
+    ((blended - scale.floor) / range).clamp(0.0, 1.0)
+}
+
+fn assessment_regex() -> &'static Regex {
+    static ASSESSMENT: OnceLock<Regex> = OnceLock::new();
+    ASSESSMENT.get_or_init(|| {
+        Regex::new(r"(?im)^[\s*_]*confidence[\s*_]*:[\s*_]*(\d+(?:\.\d+)?)\s*(%|/\s*10\b)?[\s*_.]*\z")
+            .expect("valid confidence regex")
+    })
+}
+
+/// Split the model's `Confidence: X` line off the end of `answer`.
+///
+/// Accepts `0.8`, `80%` and `8/10`. Returns the answer unchanged and `None`
+/// when there is no such line.
+pub fn take_self_assessment(answer: &str) -> (String, Option<f32>) {
+    let trimmed = answer.trim_end();
+    let Some(caps) = assessment_regex().captures(trimmed) else {
+        return (answer.to_string(), None);
+    };
+
+    let value: f32 = match caps[1].parse() {
+        Ok(value) => value,
+        Err(_) => return (answer.to_string(), None),
+    };
+    let value = match caps.get(2).map(|m| m.as_str()) {
+        Some("%") => value / 100.0,
+        Some(_) => value / 10.0,
+        None if value > 1.0 && value <= 10.0 => value / 10.0,
+        None if value > 10.0 => value / 100.0,
+        None => value,
+    };
+
+    let start = caps.get(0).map_or(trimmed.len(), |m| m.start());
+    (trimmed[..start].trim_end().to_string(), Some(value.clamp(0.0, 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::DocType;
+
+    fn source(score: f32) -> ContextChunk {
+        ContextChunk {
+            document_id: uuid::Uuid::new_v4(),
+            video_id: "v".to_string(),
+            video_title: "Talk".to_string(),
+            chapter: None,
+            timestamp: "00:00".to_string(),
+            start_seconds: 0.0,
+            end_seconds: 10.0,
+            content: String::new(),
+            summary: None,
+            score,
+            url: None,
+            doc_type: DocType::Source,
+        }
+    }
+
+    #[test]
+    fn test_retrieval_confidence() {
+        let scale = ConfidenceScale::default();
+        assert_eq!(retrieval_confidence(&[], scale), 0.0);
+        assert_eq!(retrieval_confidence(&[source(0.95), source(0.9)], scale), 1.0);
+        assert_eq!(retrieval_confidence(&[source(0.3)], scale), 0.0);
+
+        let mid = retrieval_confidence(&[source(0.6), source(0.4), source(0.35)], scale);
+        assert!(mid > 0.3 && mid < 0.6, "{}", mid);
+
+        // Models with lower similarities need a lower scale
+        let low = ConfidenceScale { floor: 0.1, ceiling: 0.5 };
+        assert!((retrieval_confidence(&[source(0.3)], low) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_take_self_assessment() {
+        let (answer, rating) = take_self_assessment("Ownership is checked at compile time [1].\n\nConfidence: 0.85\n");
+        assert_eq!(answer, "Ownership is checked at compile time [1].");
+        assert_eq!(rating, Some(0.85));
+
+        assert_eq!(take_self_assessment("Yes [2].\n**Confidence:** 70%").1, Some(0.7));
+        assert_eq!(take_self_assessment("Yes [2].\nconfidence: 8/10").1, Some(0.8));
+
+        let (answer, rating) = take_self_assessment("My confidence: 0.9 is high. The end.");
+        assert_eq!(answer, "My confidence: 0.9 is high. The end.");
+        assert_eq!(rating, None);
+
+        assert!(Confidence::new(0.2, Some(0.4)).is_below(0.35));
+    }
+}
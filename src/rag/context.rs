@@ -1,10 +1,21 @@
 //! Context building for RAG responses.
 
-use super::ContextChunk;
+use super::{ContextChunk, QueryExpander, RecencyRanking};
 use crate::embedding::Embedder;
 use crate::error::Result;
-use crate::vector_store::{SearchResult, VectorStore};
+use crate::vector_store::{cosine_similarity, DocType, Document, SearchFilter, SearchResult, VectorStore};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Candidates retrieved for packing when a token budget is set.
+const PACK_CANDIDATES: usize = 50;
+
+/// Chunks searched for media about a subject (question routing).
+const SUBJECT_CANDIDATES: usize = 200;
+
+/// Largest gap, in seconds, between consecutive chunks that are stitched.
+const STITCH_GAP_SECONDS: f64 = 5.0;
 
 /// Builds context from search results for RAG.
 pub struct ContextBuilder {
@@ -12,6 +23,13 @@ pub struct ContextBuilder {
     embedder: Arc<dyn Embedder>,
     max_chunks: usize,
     min_score: f32,
+    filter: SearchFilter,
+    expander: Option<QueryExpander>,
+    recency: Option<RecencyRanking>,
+    diversity: f32,
+    token_budget: Option<usize>,
+    stitch: bool,
+    neighbor_window: usize,
 }
 
 impl ContextBuilder {
@@ -25,6 +43,13 @@ impl ContextBuilder {
             embedder,
             max_chunks: 10,
             min_score: 0.3,
+            filter: SearchFilter::default(),
+            expander: None,
+            recency: None,
+            diversity: 0.0,
+            token_budget: None,
+            stitch: false,
+            neighbor_window: 0,
         }
     }
 
@@ -40,35 +65,182 @@ impl ContextBuilder {
         self
     }
 
-    /// Build context for a query.
-    pub async fn build(&self, query: &str) -> Result<Vec<ContextChunk>> {
-        // Generate query embedding
-        let query_embedding = self.embedder.embed(query).await?;
+    /// Set the search filter (e.g. to include or exclude derived documents).
+    pub fn with_filter(mut self, filter: SearchFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 
-        // Search for relevant documents
+    /// Pack context up to a token budget instead of a fixed number of chunks
+    /// (`None` uses `max_chunks`).
+    pub fn with_token_budget(mut self, budget: Option<usize>) -> Self {
+        self.token_budget = budget;
+        self
+    }
+
+    /// Merge retrieved chunks that follow each other in the same media item,
+    /// first adding up to `neighbor_window` chunks either side of each hit.
+    pub fn with_stitching(mut self, neighbor_window: usize) -> Self {
+        self.stitch = true;
+        self.neighbor_window = neighbor_window;
+        self
+    }
+
+    /// Search with LLM reformulations of the query as well as the query
+    /// itself (multi-query retrieval). `None` searches the query alone.
+    pub fn with_expansion(mut self, expander: Option<QueryExpander>) -> Self {
+        self.expander = expander;
+        self
+    }
+
+    /// Rank results by a blend of similarity and recency (`None` ranks by
+    /// similarity alone).
+    pub fn with_recency(mut self, recency: Option<RecencyRanking>) -> Self {
+        self.recency = recency;
+        self
+    }
+
+    /// Pick chunks by maximal marginal relevance, trading relevance for
+    /// difference from the chunks already picked by `diversity` (0-1; 0
+    /// picks by relevance alone).
+    pub fn with_diversity(mut self, diversity: f32) -> Self {
+        self.diversity = diversity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Whether the search filter narrows which media is searched.
+    pub fn is_narrow(&self) -> bool {
+        self.filter.is_narrow()
+    }
+
+    /// IDs of the media with transcript chunks relevant to `subject`.
+    pub async fn media_about(&self, subject: &str) -> Result<HashSet<String>> {
+        let embedding = self.embedder.embed(subject).await?;
         let results = self
             .vector_store
-            .search_with_threshold(&query_embedding, self.max_chunks, self.min_score)
+            .search_filtered(&embedding, SUBJECT_CANDIDATES, self.min_score, &self.filter)
             .await?;
-
-        // Convert to context chunks
-        let chunks: Vec<ContextChunk> = results
+        Ok(results
             .into_iter()
-            .map(|r| {
-                let mut chunk = ContextChunk::from(r.clone());
-                // Build YouTube URL with timestamp if it's a YouTube video
-                if !r.document.video_id.starts_with("local_") {
-                    chunk.url = Some(format!(
-                        "https://youtube.com/watch?v={}&t={}s",
-                        r.document.video_id,
-                        r.document.start_seconds as u32
-                    ));
+            .filter(|r| r.document.doc_type == DocType::Source)
+            .map(|r| r.document.video_id)
+            .collect())
+    }
+
+    /// The retrieval settings as a string, so answers built from different
+    /// context are cached separately.
+    pub fn describe(&self) -> String {
+        let expansion = self.expander.as_ref().map(|e| (e.model(), e.variants()));
+        format!(
+            "max_chunks={} min_score={} filter={} expansion={:?} recency={:?} diversity={} token_budget={:?} stitch={} neighbors={}",
+            self.max_chunks,
+            self.min_score,
+            self.filter.cache_key(),
+            expansion,
+            self.recency,
+            self.diversity,
+            self.token_budget,
+            self.stitch,
+            self.neighbor_window
+        )
+    }
+
+    /// Build context for a query.
+    pub async fn build(&self, query: &str) -> Result<Vec<ContextChunk>> {
+        let mut queries = vec![query.to_string()];
+        if let Some(expander) = &self.expander {
+            // Expansion only improves recall, so fall back to the plain query
+            match expander.expand(query).await {
+                Ok(variants) => queries.extend(variants),
+                Err(e) => warn!("Query expansion failed, searching the question only: {}", e),
+            }
+        }
+
+        let limit = match self.token_budget {
+            Some(_) => PACK_CANDIDATES.max(self.max_chunks),
+            None => self.max_chunks,
+        };
+        // Newer or more varied matches further down the similarity ranking
+        // can move up
+        let candidates = if self.recency.is_some() || self.diversity > 0.0 {
+            PACK_CANDIDATES.max(limit)
+        } else {
+            limit
+        };
+
+        let mut results = if queries.len() == 1 {
+            let query_embedding = self.embedder.embed(query).await?;
+            self.vector_store
+                .search_filtered(&query_embedding, candidates, self.min_score, &self.filter)
+                .await?
+        } else {
+            let embeddings = self.embedder.embed_batch(&queries).await?;
+            let mut result_sets = Vec::with_capacity(embeddings.len());
+            for embedding in &embeddings {
+                result_sets.push(
+                    self.vector_store
+                        .search_filtered(embedding, candidates, self.min_score, &self.filter)
+                        .await?,
+                );
+            }
+            merge_results(result_sets, candidates)
+        };
+        if let Some(recency) = &self.recency {
+            recency.rerank(&mut results, chrono::Utc::now());
+        }
+        if self.diversity == 0.0 {
+            results.truncate(limit);
+        }
+
+        let results = if self.stitch {
+            let results = match self.neighbor_window {
+                0 => results,
+                window => self.add_neighbors(results, window).await?,
+            };
+            stitch_results(results)
+        } else {
+            results
+        };
+
+        let results = if self.diversity > 0.0 {
+            let mut results = diversify(results, self.diversity);
+            if self.token_budget.is_none() {
+                results.truncate(self.max_chunks);
+            }
+            results
+        } else {
+            results
+        };
+
+        let results = match self.token_budget {
+            Some(budget) => pack_results(results, budget),
+            None => results,
+        };
+
+        Ok(Self::from_results(results))
+    }
+
+    /// Add the chunks around each of the best `max_chunks` transcript hits,
+    /// scored like the hit they surround.
+    async fn add_neighbors(&self, mut results: Vec<SearchResult>, window: usize) -> Result<Vec<SearchResult>> {
+        let mut seen: HashSet<uuid::Uuid> = results.iter().map(|r| r.document.id).collect();
+        let mut neighbors = Vec::new();
+
+        let hits = results.iter().filter(|r| r.document.doc_type == DocType::Source).take(self.max_chunks);
+        for hit in hits {
+            let docs = self
+                .vector_store
+                .get_neighbors(&hit.document.video_id, hit.document.chunk_order, window)
+                .await?;
+            for document in docs {
+                if seen.insert(document.id) && self.filter.matches(&document) {
+                    neighbors.push(SearchResult { document, score: hit.score });
                 }
-                chunk
-            })
-            .collect();
+            }
+        }
 
-        Ok(chunks)
+        results.extend(neighbors);
+        Ok(results)
     }
 
     /// Build context from raw search results.
@@ -77,7 +249,8 @@ impl ContextBuilder {
             .into_iter()
             .map(|r| {
                 let mut chunk = ContextChunk::from(r.clone());
-                if !r.document.video_id.starts_with("local_") {
+                // Build YouTube URL with timestamp if it's a YouTube video
+                if r.document.doc_type == DocType::Source && !r.document.video_id.starts_with("local_") {
                     chunk.url = Some(format!(
                         "https://youtube.com/watch?v={}&t={}s",
                         r.document.video_id,
@@ -90,6 +263,141 @@ impl ContextBuilder {
     }
 }
 
+/// Merge the results of several searches, keeping each document once with
+/// its best score, and return the top `limit` by score.
+fn merge_results(result_sets: Vec<Vec<SearchResult>>, limit: usize) -> Vec<SearchResult> {
+    let mut best: HashMap<uuid::Uuid, SearchResult> = HashMap::new();
+    for result in result_sets.into_iter().flatten() {
+        match best.get(&result.document.id) {
+            Some(existing) if existing.score >= result.score => {}
+            _ => {
+                best.insert(result.document.id, result);
+            }
+        }
+    }
+
+    let mut merged: Vec<SearchResult> = best.into_values().collect();
+    merged.sort_by(|a, b| b.score.total_cmp(&a.score));
+    merged.truncate(limit);
+    merged
+}
+
+/// Merge transcript chunks that follow each other (consecutive chunk order,
+/// at most a few seconds apart) in the same media item into one result with
+/// the best member's score and ID. Results come back ordered by score.
+fn stitch_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let (mut sources, derived): (Vec<_>, Vec<_>) =
+        results.into_iter().partition(|r| r.document.doc_type == DocType::Source);
+    sources.sort_by(|a, b| {
+        a.document
+            .video_id
+            .cmp(&b.document.video_id)
+            .then(a.document.chunk_order.cmp(&b.document.chunk_order))
+    });
+
+    let mut stitched: Vec<SearchResult> = Vec::new();
+    let mut last_order = i32::MIN;
+    for result in sources {
+        let order = result.document.chunk_order;
+        match stitched.last_mut() {
+            Some(prev)
+                if prev.document.video_id == result.document.video_id
+                    && order == last_order.saturating_add(1)
+                    && result.document.start_seconds <= prev.document.end_seconds + STITCH_GAP_SECONDS =>
+            {
+                prev.document.content.push('\n');
+                prev.document.content.push_str(&result.document.content);
+                prev.document.end_seconds = prev.document.end_seconds.max(result.document.end_seconds);
+                if result.score > prev.score {
+                    prev.score = result.score;
+                    prev.document.id = result.document.id;
+                }
+            }
+            _ => stitched.push(result),
+        }
+        last_order = order;
+    }
+
+    stitched.extend(derived);
+    stitched.sort_by(|a, b| b.score.total_cmp(&a.score));
+    stitched
+}
+
+/// Order results by maximal marginal relevance: each next result is the one
+/// that best balances its score against its similarity to the results before
+/// it, the latter weighted by `diversity`.
+fn diversify(mut remaining: Vec<SearchResult>, diversity: f32) -> Vec<SearchResult> {
+    let mut chosen: Vec<SearchResult> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let marginal = |result: &SearchResult| {
+            let redundancy = chosen
+                .iter()
+                .map(|c| cosine_similarity(&c.document.embedding, &result.document.embedding))
+                .fold(0.0, f32::max);
+            (1.0 - diversity) * result.score - diversity * redundancy
+        };
+        // Ties go to the earlier (better scored) result
+        let next = (0..remaining.len())
+            .map(|i| (i, marginal(&remaining[i])))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map_or(0, |(i, _)| i);
+        chosen.push(remaining.remove(next));
+    }
+    chosen
+}
+
+/// Count tokens the way OpenAI's current chat models do (`o200k_base`).
+pub fn count_tokens(text: &str) -> usize {
+    tiktoken_rs::o200k_base_singleton().lock().encode_ordinary(text).len()
+}
+
+/// Tokens a result takes up once formatted into the prompt.
+fn prompt_tokens(document: &Document) -> usize {
+    count_tokens(&format!(
+        "---\n[00] {}, {} @ {}\n{}\n---\n\n",
+        document.video_title,
+        document.chapter.as_deref().unwrap_or_default(),
+        document.format_timestamp(),
+        document.content
+    ))
+}
+
+/// Fill `budget` tokens with results in the order given (best first),
+/// skipping results that duplicate one already chosen and results too large
+/// for what is left.
+fn pack_results(results: Vec<SearchResult>, budget: usize) -> Vec<SearchResult> {
+    let mut packed: Vec<SearchResult> = Vec::new();
+    let mut used = 0;
+    for result in results {
+        if packed.iter().any(|p| is_duplicate(&p.document, &result.document)) {
+            continue;
+        }
+        let tokens = prompt_tokens(&result.document);
+        if used + tokens > budget {
+            continue;
+        }
+        used += tokens;
+        packed.push(result);
+    }
+
+    debug!("Packed {} chunks into {}/{} context tokens", packed.len(), used, budget);
+    packed
+}
+
+/// Whether two documents repeat each other: identical text, or chunks of the
+/// same kind from one media item that overlap for most of the shorter one.
+fn is_duplicate(a: &Document, b: &Document) -> bool {
+    if a.content.trim() == b.content.trim() {
+        return true;
+    }
+    if a.video_id != b.video_id || a.doc_type != b.doc_type {
+        return false;
+    }
+    let overlap = a.end_seconds.min(b.end_seconds) - a.start_seconds.max(b.start_seconds);
+    let shorter = (a.end_seconds - a.start_seconds).min(b.end_seconds - b.start_seconds);
+    shorter > 0.0 && overlap > shorter / 2.0
+}
+
 /// Format context chunks for display in a prompt.
 pub fn format_context_for_prompt(chunks: &[ContextChunk]) -> String {
     chunks
@@ -99,7 +407,7 @@ pub fn format_context_for_prompt(chunks: &[ContextChunk]) -> String {
             format!(
                 "---\n[{}] {} @ {}\n{}\n---",
                 i + 1,
-                chunk.video_title,
+                chunk.source_label(),
                 chunk.timestamp,
                 chunk.content
             )
@@ -121,7 +429,7 @@ pub fn format_context_for_display(chunks: &[ContextChunk]) -> String {
 
             format!(
                 "{} @ {} (score: {:.2}){}",
-                chunk.video_title,
+                chunk.source_label(),
                 chunk.timestamp,
                 chunk.score,
                 url_part
@@ -130,3 +438,158 @@ pub fn format_context_for_display(chunks: &[ContextChunk]) -> String {
         .collect::<Vec<_>>()
         .join("\n\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_video_filter_restricts_retrieval() {
+        use crate::testing::MockEmbedder;
+        use crate::vector_store::MemoryVectorStore;
+
+        let embedder = Arc::new(MockEmbedder::default());
+        let store = Arc::new(MemoryVectorStore::new());
+        let question = "How does the borrow checker work?";
+        for (video, content) in [("best", question), ("other", "The borrow checker in a different talk")] {
+            let doc = Document::new(
+                video.to_string(),
+                video.to_string(),
+                None,
+                content.to_string(),
+                0.0,
+                30.0,
+                embedder.embed(content).await.unwrap(),
+                0,
+                None,
+            );
+            store.upsert(&doc).await.unwrap();
+        }
+
+        let builder = ContextBuilder::new(store, embedder).with_min_score(0.0);
+        let everything = builder.build(question).await.unwrap();
+        assert_eq!(everything[0].video_id, "best");
+
+        // Only the chosen media is searched, even when others match better
+        let scoped = builder.with_filter(SearchFilter::default().with_video("other"));
+        let chunks = scoped.build(question).await.unwrap();
+        assert!(!chunks.is_empty());
+        assert!(chunks.iter().all(|c| c.video_id == "other"));
+    }
+
+    #[test]
+    fn test_describe_for_cache_keys() {
+        use crate::testing::MockEmbedder;
+        use crate::vector_store::MemoryVectorStore;
+
+        let builder = |model: &str, filter: SearchFilter| {
+            ContextBuilder::new(Arc::new(MemoryVectorStore::new()), Arc::new(MockEmbedder::default()))
+                .with_filter(filter)
+                .with_expansion(Some(QueryExpander::new(model)))
+                .describe()
+        };
+        let now = chrono::Utc::now();
+        let window = |now| SearchFilter::default().with_since_window("7d", now).unwrap();
+
+        // The same relative window describes the same restriction a minute later
+        assert_eq!(
+            builder("gpt-4o-mini", window(now)),
+            builder("gpt-4o-mini", window(now + chrono::Duration::minutes(1)))
+        );
+        // Another expansion model retrieves other chunks
+        assert_ne!(builder("gpt-4o-mini", window(now)), builder("gpt-4.1", window(now)));
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let doc = |content: &str| {
+            Document::new("v".to_string(), "Video".to_string(), None, content.to_string(), 0.0, 10.0, vec![], 0, None)
+        };
+        let (a, b, c) = (doc("a"), doc("b"), doc("c"));
+        let hit = |d: &Document, score: f32| SearchResult { document: d.clone(), score };
+
+        let merged = merge_results(
+            vec![
+                vec![hit(&a, 0.5), hit(&b, 0.4)],
+                vec![hit(&b, 0.9), hit(&c, 0.3)],
+            ],
+            2,
+        );
+
+        let ids: Vec<_> = merged.iter().map(|r| (r.document.content.as_str(), r.score)).collect();
+        assert_eq!(ids, vec![("b", 0.9), ("a", 0.5)]);
+    }
+
+    #[test]
+    fn test_pack_results() {
+        let doc = |content: &str, start: f64, end: f64| {
+            Document::new("v".to_string(), "Video".to_string(), None, content.to_string(), start, end, vec![], 0, None)
+        };
+        let hit = |d: Document, score: f32| SearchResult { document: d, score };
+        let long = "word ".repeat(200);
+
+        let results = vec![
+            hit(doc("best chunk", 0.0, 30.0), 0.9),
+            hit(doc("overlapping chunk", 10.0, 40.0), 0.8),
+            hit(doc(&long, 60.0, 90.0), 0.7),
+            hit(doc("small chunk", 120.0, 150.0), 0.6),
+        ];
+        let budget = prompt_tokens(&results[0].document) + prompt_tokens(&results[3].document);
+
+        let packed = pack_results(results, budget);
+        let contents: Vec<_> = packed.iter().map(|r| r.document.content.as_str()).collect();
+        assert_eq!(contents, vec!["best chunk", "small chunk"]);
+    }
+
+    #[test]
+    fn test_diversify() {
+        let hit = |content: &str, embedding: Vec<f32>, score: f32| SearchResult {
+            document: Document::new("v".to_string(), "Video".to_string(), None, content.to_string(), 0.0, 10.0, embedding, 0, None),
+            score,
+        };
+        let results = vec![
+            hit("a", vec![1.0, 0.0], 0.9),
+            hit("a again", vec![1.0, 0.01], 0.89),
+            hit("b", vec![0.0, 1.0], 0.8),
+        ];
+        let order = |results: Vec<SearchResult>| results.into_iter().map(|r| r.document.content).collect::<Vec<_>>();
+
+        assert_eq!(order(diversify(results.clone(), 0.0)), vec!["a", "a again", "b"]);
+        assert_eq!(order(diversify(results, 0.5)), vec!["a", "b", "a again"]);
+    }
+
+    #[test]
+    fn test_stitch_results() {
+        let doc = |order: i32, start: f64| {
+            let mut d = Document::new(
+                "v".to_string(), "Video".to_string(), None, format!("chunk {}", order), start, start + 30.0, vec![], order, None,
+            );
+            if order < 0 {
+                d.video_id = "other".to_string();
+            }
+            d
+        };
+        let hit = |d: Document, score: f32| SearchResult { document: d, score };
+
+        let stitched = stitch_results(vec![
+            hit(doc(3, 90.0), 0.6),
+            hit(doc(1, 30.0), 0.4),
+            hit(doc(2, 60.0), 0.8),
+            hit(doc(5, 150.0), 0.5),
+            hit(doc(-1, 0.0), 0.7),
+        ]);
+
+        let summary: Vec<_> = stitched
+            .iter()
+            .map(|r| (r.document.content.as_str(), r.document.start_seconds, r.document.end_seconds, r.score))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("chunk 1\nchunk 2\nchunk 3", 30.0, 120.0, 0.8),
+                ("chunk -1", 0.0, 30.0, 0.7),
+                ("chunk 5", 150.0, 180.0, 0.5),
+            ]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Query expansion for multi-query retrieval.
+
+use crate::config::{Prompts, RagSettings};
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct ExpansionResponse {
+    #[serde(default)]
+    queries: Vec<String>,
+}
+
+/// Rewrites a question into alternative search queries.
+pub struct QueryExpander {
+    client: ChatClient,
+    model: String,
+    prompts: Prompts,
+    variants: usize,
+}
+
+impl QueryExpander {
+    /// Create a new query expander.
+    pub fn new(model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            prompts: Prompts::default(),
+            variants: 4,
+        }
+    }
+
+    /// Build an expander from `[rag.multi_query]`, or `None` when it is disabled.
+    pub fn from_settings(settings: &RagSettings, model: &str, prompts: &Prompts) -> Option<Self> {
+        settings.multi_query.enabled.then(|| {
+            Self::new(model)
+                .with_client(ChatClient::from_settings(&settings.llm))
+                .with_prompts(prompts.clone())
+                .with_variants(settings.multi_query.variants)
+        })
+    }
+
+    /// Set the number of reformulations to ask for.
+    pub fn with_variants(mut self, variants: usize) -> Self {
+        self.variants = variants.max(1);
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Number of reformulations asked for.
+    pub fn variants(&self) -> usize {
+        self.variants
+    }
+
+    /// Model that writes the reformulations.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Return reformulations of `question`, not including the question itself.
+    pub async fn expand(&self, question: &str) -> Result<Vec<String>> {
+        let mut vars = HashMap::new();
+        vars.insert("question".to_string(), question.to_string());
+        vars.insert("count".to_string(), self.variants.to_string());
+
+        let system = self.prompts.render_with_custom(&self.prompts.expansion.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.expansion.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.5)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        let queries = parse_queries(content, question, self.variants)?;
+        debug!("Expanded question into {:?}", queries);
+        Ok(queries)
+    }
+}
+
+/// Parse the model's JSON, dropping blanks, repeats and restatements of the
+/// original question, and keeping at most `max` queries.
+fn parse_queries(content: &str, question: &str, max: usize) -> Result<Vec<String>> {
+    let parsed: ExpansionResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid query expansion JSON: {}", e)))?;
+
+    let mut seen = HashSet::from([question.trim().to_lowercase()]);
+    Ok(parsed
+        .queries
+        .into_iter()
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty() && seen.insert(q.to_lowercase()))
+        .take(max)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_queries() {
+        let content = r#"{"queries": ["how do I cache answers", " answer caching ", "", "Answer Caching", "memoizing responses", "extra"]}"#;
+        let queries = parse_queries(content, "How do I cache answers", 3).unwrap();
+        assert_eq!(queries, vec!["answer caching", "memoizing responses", "extra"]);
+
+        assert!(parse_queries("not json", "q", 3).is_err());
+    }
+}
@@ -2,43 +2,149 @@
 //!
 //! Provides the ability to ask questions and get answers from the video knowledge base.
 
+mod citations;
+mod confidence;
 pub mod context;
+mod expansion;
+mod recency;
 mod response;
+pub mod router;
+mod verify;
 
-pub use context::ContextBuilder;
-pub use response::{RagEngine, RagResponse};
+pub use citations::{resolve_citations, Citation, QuoteSpan};
+pub use confidence::{retrieval_confidence, take_self_assessment, Confidence, ConfidenceScale};
+pub use context::{count_tokens, ContextBuilder};
+pub use expansion::QueryExpander;
+pub use recency::RecencyRanking;
+pub use response::{RagEngine, RagResponse, RagStream};
+pub use verify::{AnswerVerifier, ClaimCheck, Verdict, Verification};
 
-use crate::vector_store::SearchResult;
+use crate::vector_store::{DocType, SearchResult};
+use serde::{Deserialize, Serialize};
 
 /// A search result with formatted context for display.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextChunk {
+    /// ID of the retrieved document.
+    pub document_id: uuid::Uuid,
     /// Video ID.
     pub video_id: String,
     /// Video title.
     pub video_title: String,
+    /// Chapter the chunk starts in (if the media has chapters).
+    #[serde(default)]
+    pub chapter: Option<String>,
     /// Formatted timestamp (e.g., "02:34").
     pub timestamp: String,
     /// Start time in seconds.
     pub start_seconds: f64,
+    /// End time in seconds.
+    #[serde(default)]
+    pub end_seconds: f64,
     /// Text content.
     pub content: String,
+    /// Section summary (if the chunker wrote one).
+    #[serde(default)]
+    pub summary: Option<String>,
     /// Similarity score.
     pub score: f32,
     /// URL with timestamp (if available).
     pub url: Option<String>,
+    /// Whether this chunk is transcript content or a derived document.
+    pub doc_type: DocType,
 }
 
 impl From<SearchResult> for ContextChunk {
     fn from(result: SearchResult) -> Self {
         Self {
+            document_id: result.document.id,
             video_id: result.document.video_id.clone(),
             video_title: result.document.video_title.clone(),
+            chapter: result.document.chapter.clone(),
             timestamp: result.document.format_timestamp(),
             start_seconds: result.document.start_seconds,
+            end_seconds: result.document.end_seconds,
             content: result.document.content.clone(),
+            summary: result.document.summary.clone(),
             score: result.score,
             url: None, // Will be populated by the engine
+            doc_type: result.document.doc_type,
         }
     }
 }
+
+impl ContextChunk {
+    /// The media title, with the chapter when known, as in
+    /// "Dune, Chapter 7". Cited together with the timestamp.
+    pub fn source_label(&self) -> String {
+        match &self.chapter {
+            Some(chapter) => format!("{}, {}", self.video_title, chapter),
+            None => self.video_title.clone(),
+        }
+    }
+}
+
+/// Sources an answer cites, using the older `[Video Title @ MM:SS]` citation
+/// format (see [`resolve_citations`] for numbered markers).
+///
+/// A source counts as cited when the answer contains its title and timestamp,
+/// or just its timestamp if no other source shares it (models often shorten
+/// titles).
+pub fn cited_sources<'a>(answer: &str, sources: &'a [ContextChunk]) -> Vec<&'a ContextChunk> {
+    sources
+        .iter()
+        .filter(|source| {
+            if answer.contains(&format!("{} @ {}", source.video_title, source.timestamp))
+                || answer.contains(&format!("{} @ {}", source.source_label(), source.timestamp))
+            {
+                return true;
+            }
+            let marker = format!("@ {}", source.timestamp);
+            answer.contains(&marker)
+                && sources
+                    .iter()
+                    .filter(|other| other.timestamp == source.timestamp)
+                    .count()
+                    == 1
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(title: &str, timestamp: &str) -> ContextChunk {
+        ContextChunk {
+            document_id: uuid::Uuid::new_v4(),
+            video_id: title.to_lowercase(),
+            video_title: title.to_string(),
+            chapter: None,
+            timestamp: timestamp.to_string(),
+            start_seconds: 0.0,
+            end_seconds: 0.0,
+            content: String::new(),
+            summary: None,
+            score: 0.9,
+            url: None,
+            doc_type: DocType::Source,
+        }
+    }
+
+    #[test]
+    fn test_cited_sources() {
+        let sources = vec![
+            chunk("Rust Talk", "02:05"),
+            chunk("Go Talk", "02:05"),
+            chunk("Go Talk", "10:00"),
+            chunk("Python Talk", "05:00"),
+        ];
+        let answer = "Ownership is key [Rust Talk @ 02:05]. Goroutines are cheap [Go @ 10:00].";
+
+        let cited: Vec<_> = cited_sources(answer, &sources)
+            .iter()
+            .map(|s| (s.video_title.as_str(), s.timestamp.as_str()))
+            .collect();
+        assert_eq!(cited, vec![("Rust Talk", "02:05"), ("Go Talk", "10:00")]);
+    }
+}
@@ -0,0 +1,86 @@
+//! Recency-aware ranking.
+//!
+//! Blends each result's similarity with how recently its media was published,
+//! so "latest guidance on X" finds this year's talk rather than a stale one
+//! that happens to phrase things more closely. Recency decays exponentially
+//! with age and halves every `half_life_days`.
+
+use crate::config::RagSettings;
+use crate::vector_store::SearchResult;
+use chrono::{DateTime, Utc};
+
+/// Re-ranks search results by similarity and recency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecencyRanking {
+    /// Share of the score given to recency (0-1).
+    weight: f32,
+    /// Age, in days, at which recency is worth half.
+    half_life_days: f64,
+}
+
+impl RecencyRanking {
+    /// Create a ranking giving `weight` (clamped to 0-1) of the score to
+    /// recency with the given half-life.
+    pub fn new(weight: f32, half_life_days: f64) -> Self {
+        Self {
+            weight: weight.clamp(0.0, 1.0),
+            half_life_days: half_life_days.max(1.0 / 24.0),
+        }
+    }
+
+    /// Build a ranking from `[rag.recency]`, or `None` when it is disabled.
+    pub fn from_settings(settings: &RagSettings) -> Option<Self> {
+        settings
+            .recency
+            .enabled
+            .then(|| Self::new(settings.recency.weight, settings.recency.half_life_days))
+    }
+
+    /// Recency of something published at `published`, from 1 (now or in the
+    /// future) down towards 0.
+    fn recency(&self, published: DateTime<Utc>, now: DateTime<Utc>) -> f32 {
+        let age_days = (now - published).num_seconds().max(0) as f64 / 86_400.0;
+        0.5f64.powf(age_days / self.half_life_days) as f32
+    }
+
+    /// Replace the scores of `results` with the blend of similarity and
+    /// recency and sort them by it. Media without a publication date is
+    /// dated by when it was indexed.
+    pub fn rerank(&self, results: &mut [SearchResult], now: DateTime<Utc>) {
+        for result in results.iter_mut() {
+            let published = result.document.source_created_at.unwrap_or(result.document.indexed_at);
+            result.score = (1.0 - self.weight) * result.score + self.weight * self.recency(published, now);
+        }
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::Document;
+    use chrono::Duration;
+
+    #[test]
+    fn test_recency_rerank() {
+        let now = Utc::now();
+        let result = |video_id: &str, score: f32, age_days: i64| {
+            let published = Some(now - Duration::days(age_days));
+            let document =
+                Document::new(video_id.to_string(), video_id.to_string(), None, String::new(), 0.0, 1.0, Vec::new(), 0, published);
+            SearchResult { document, score }
+        };
+        // The stale video is a slightly closer match
+        let mut results = vec![result("stale", 0.80, 730), result("fresh", 0.75, 10)];
+
+        RecencyRanking::new(0.0, 180.0).rerank(&mut results, now);
+        assert_eq!(results[0].document.video_id, "stale");
+
+        RecencyRanking::new(0.3, 180.0).rerank(&mut results, now);
+        assert_eq!(results[0].document.video_id, "fresh");
+
+        let ranking = RecencyRanking::new(1.0, 30.0);
+        assert!((ranking.recency(now - Duration::days(30), now) - 0.5).abs() < 1e-4);
+        assert_eq!(ranking.recency(now + Duration::days(1), now), 1.0);
+    }
+}
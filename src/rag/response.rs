@@ -1,27 +1,50 @@
 //! RAG response generation.
 
-use super::{context::format_context_for_prompt, ContextBuilder, ContextChunk};
+use super::{
+    confidence::SELF_ASSESSMENT_INSTRUCTION,
+    router::{self, MetadataQuery}, context::format_context_for_prompt, resolve_citations, take_self_assessment,
+    AnswerVerifier, Citation, Confidence, ConfidenceScale, ContextBuilder, ContextChunk, QueryExpander, RecencyRanking, Verification,
+};
 use crate::config::Prompts;
 use crate::embedding::Embedder;
 use crate::error::{Result, LyttError};
-use crate::vector_store::VectorStore;
+use crate::vector_store::{SearchFilter, VectorStore};
 use async_openai::types::{
     ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
     ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-    CreateChatCompletionRequestArgs,
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs,
 };
-use crate::openai::create_client;
+use crate::llm::{ChatClient, TextStream};
+use crate::usage::{self, UsageStage};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
-use tracing::{debug, info, instrument};
+use std::time::Duration;
+use tracing::{debug, info, instrument, warn};
+
+/// Answer given when nothing relevant is indexed.
+const NO_CONTEXT_ANSWER: &str =
+    "I couldn't find any relevant information in your video library for this question.";
+
+/// Answer given when the answer's confidence is below the threshold.
+const LOW_CONFIDENCE_ANSWER: &str =
+    "There is not enough information in your library to answer this question confidently.";
 
 /// RAG engine for question answering.
 pub struct RagEngine {
-    client: async_openai::Client<async_openai::config::OpenAIConfig>,
+    client: ChatClient,
     model: String,
+    vector_store: Arc<dyn VectorStore>,
     context_builder: ContextBuilder,
     prompts: Prompts,
     conversation_history: Vec<ChatCompletionRequestMessage>,
+    cache_ttl: Option<Duration>,
+    verifier: Option<AnswerVerifier>,
+    min_confidence: f32,
+    confidence_scale: ConfidenceScale,
+    routing: bool,
 }
 
 impl RagEngine {
@@ -32,42 +55,352 @@ impl RagEngine {
         model: &str,
         max_context_chunks: usize,
     ) -> Self {
-        let context_builder = ContextBuilder::new(vector_store, embedder)
+        let context_builder = ContextBuilder::new(vector_store.clone(), embedder)
             .with_max_chunks(max_context_chunks)
-            .with_min_score(0.3);
+            .with_min_score(0.3)
+            .with_stitching(0);
 
         Self {
-            client: create_client(),
+            client: ChatClient::openai(),
             model: model.to_string(),
+            vector_store,
             context_builder,
             prompts: Prompts::default(),
             conversation_history: Vec::new(),
+            cache_ttl: None,
+            verifier: None,
+            min_confidence: 0.0,
+            confidence_scale: ConfidenceScale::default(),
+            routing: false,
         }
     }
 
+    /// Reuse answers to repeated questions for up to `ttl` (`None` disables
+    /// the cache). Cached answers are dropped whenever the library changes.
+    pub fn with_cache(mut self, ttl: Option<Duration>) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Set the search filter used when retrieving context.
+    pub fn with_filter(mut self, filter: SearchFilter) -> Self {
+        self.context_builder = self.context_builder.with_filter(filter);
+        self
+    }
+
+    /// Pack retrieved context up to a token budget (`None` uses a fixed
+    /// number of chunks).
+    pub fn with_token_budget(mut self, budget: Option<usize>) -> Self {
+        self.context_builder = self.context_builder.with_token_budget(budget);
+        self
+    }
+
+    /// Also include up to `window` chunks either side of each retrieved
+    /// chunk, stitched into continuous passages.
+    pub fn with_neighbor_window(mut self, window: usize) -> Self {
+        self.context_builder = self.context_builder.with_stitching(window);
+        self
+    }
+
+    /// Retrieve context with multi-query expansion (`None` disables it).
+    pub fn with_expansion(mut self, expander: Option<QueryExpander>) -> Self {
+        self.context_builder = self.context_builder.with_expansion(expander);
+        self
+    }
+
+    /// Rank retrieved context by similarity and recency (`None` ranks by
+    /// similarity alone).
+    pub fn with_recency(mut self, recency: Option<RecencyRanking>) -> Self {
+        self.context_builder = self.context_builder.with_recency(recency);
+        self
+    }
+
+    /// Trade relevance of retrieved context for variety (0-1; 0 picks
+    /// chunks by relevance alone).
+    pub fn with_diversity(mut self, diversity: f32) -> Self {
+        self.context_builder = self.context_builder.with_diversity(diversity);
+        self
+    }
+
+    /// Answer questions about the library itself ("how many videos do I
+    /// have about Rust?") from the list of indexed media instead of
+    /// retrieved chunks. Only [`Self::ask`] and [`Self::ask_stream`] route.
+    pub fn with_routing(mut self, enabled: bool) -> Self {
+        self.routing = enabled;
+        self
+    }
+
+    /// Check answers against their sources (`None` disables the check).
+    /// Only [`Self::ask`] answers are checked.
+    pub fn with_verifier(mut self, verifier: Option<AnswerVerifier>) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
+    /// Decline to answer when confidence is below `min_confidence` (0-1,
+    /// default 0: always answer).
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// Map retrieval similarities to confidence on this scale (default
+    /// 0.3 to 0.8).
+    pub fn with_confidence_scale(mut self, scale: ConfidenceScale) -> Self {
+        self.confidence_scale = scale;
+        self
+    }
+
+    /// Set the minimum similarity for retrieved context (default 0.3).
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.context_builder = self.context_builder.with_min_score(min_score);
+        self
+    }
+
     /// Set custom prompts (with user-defined variables).
     pub fn with_prompts(mut self, prompts: Prompts) -> Self {
         self.prompts = prompts;
         self
     }
 
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Ask a single question and get a response.
     #[instrument(skip(self), fields(question = %question))]
     pub async fn ask(&self, question: &str) -> Result<RagResponse> {
         info!("Processing question: {}", question);
 
+        if let Some(answer) = self.route(question).await? {
+            return Ok(RagResponse {
+                answer,
+                sources: Vec::new(),
+                citations: Vec::new(),
+                verification: None,
+                confidence: None,
+                abstained: false,
+                cached: false,
+            });
+        }
+
+        let cache = match self.cache_ttl {
+            Some(ttl) => self
+                .vector_store
+                .corpus_version()
+                .await?
+                .map(|version| (self.cache_key(question), version, ttl)),
+            None => None,
+        };
+
+        if let Some((key, version, ttl)) = &cache {
+            if let Some(cached) = self.vector_store.cached_answer(key, *version, *ttl).await? {
+                match serde_json::from_str::<RagResponse>(&cached) {
+                    Ok(response) => {
+                        debug!("Answer cache hit");
+                        return Ok(RagResponse { cached: true, ..response });
+                    }
+                    Err(e) => warn!("Ignoring unreadable cached answer: {}", e),
+                }
+            }
+        }
+
         // Build context from the knowledge base
         let context_chunks = self.context_builder.build(question).await?;
 
         if context_chunks.is_empty() {
             return Ok(RagResponse {
-                answer: "I couldn't find any relevant information in your video library for this question.".to_string(),
+                answer: NO_CONTEXT_ANSWER.to_string(),
                 sources: Vec::new(),
+                citations: Vec::new(),
+                verification: None,
+                confidence: None,
+                abstained: true,
+                cached: false,
             });
         }
 
-        // Build prompt
-        let context_text = format_context_for_prompt(&context_chunks);
+        // Don't spend a generation on excerpts that can't support an answer
+        let retrieval = Confidence::from_sources(&context_chunks, self.confidence_scale);
+        if retrieval.is_below(self.min_confidence) {
+            debug!("Retrieval confidence {:.2} below {:.2}", retrieval.score, self.min_confidence);
+            return Ok(Self::abstain(context_chunks, retrieval));
+        }
+
+        let mut request = self.answer_request(question, &context_chunks)?;
+        request.messages.push(
+            ChatCompletionRequestSystemMessageArgs::default()
+                .content(SELF_ASSESSMENT_INSTRUCTION)
+                .build()
+                .map_err(|e| LyttError::Rag(e.to_string()))?
+                .into(),
+        );
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let answer = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?
+            .clone();
+
+        debug!("Generated response with {} sources", context_chunks.len());
+        let (answer, rating) = take_self_assessment(&answer);
+        let confidence = Confidence::new(retrieval.retrieval, rating);
+        if confidence.is_below(self.min_confidence) {
+            debug!("Answer confidence {:.2} below {:.2}", confidence.score, self.min_confidence);
+            return Ok(Self::abstain(context_chunks, confidence));
+        }
+
+        let (answer, citations) = resolve_citations(&answer, &context_chunks);
+        self.record_cited(&citations).await;
+
+        // A failed check doesn't lose the answer; it is just left unverified
+        let verification = match &self.verifier {
+            Some(verifier) => match verifier.verify(&answer, &context_chunks).await {
+                Ok(verification) => Some(verification),
+                Err(e) => {
+                    warn!("Failed to verify answer: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let response = RagResponse {
+            answer,
+            sources: context_chunks,
+            citations,
+            verification,
+            confidence: Some(confidence),
+            abstained: false,
+            cached: false,
+        };
+
+        if let Some((key, version, _)) = &cache {
+            let stored = match serde_json::to_string(&response) {
+                Ok(json) => self.vector_store.cache_answer(key, *version, &json).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = stored {
+                warn!("Failed to cache answer: {}", e);
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Answer a metadata question from the list of indexed media, or `None`
+    /// for questions about content. Searches narrowed to some of the media
+    /// (`--video`, `--series`, ...) always go through retrieval.
+    async fn route(&self, question: &str) -> Result<Option<String>> {
+        if !self.routing || self.context_builder.is_narrow() {
+            return Ok(None);
+        }
+        let Some(query) = router::route(question) else {
+            return Ok(None);
+        };
+        debug!("Answering {:?} from library metadata", query);
+        Ok(Some(self.answer_metadata(&query).await?))
+    }
+
+    async fn answer_metadata(&self, query: &MetadataQuery) -> Result<String> {
+        let mut videos = self.vector_store.list_videos().await?;
+        if let Some(subject) = &query.about {
+            // Media that talks about the subject, or is named after it
+            let relevant = self.context_builder.media_about(subject).await?;
+            videos.retain(|v| relevant.contains(&v.video_id) || v.video_title.to_lowercase().contains(subject));
+        }
+        Ok(query.answer(&videos))
+    }
+
+    /// Cache key for a question: the normalized question plus everything else
+    /// that shapes the answer (model, prompts, retrieval settings).
+    fn cache_key(&self, question: &str) -> String {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.model.hash(&mut hasher);
+        self.prompts.rag.system.hash(&mut hasher);
+        self.prompts.rag.user.hash(&mut hasher);
+        self.context_builder.describe().hash(&mut hasher);
+        self.verifier.as_ref().map(|v| v.model()).hash(&mut hasher);
+        self.min_confidence.to_bits().hash(&mut hasher);
+        self.confidence_scale.floor.to_bits().hash(&mut hasher);
+        self.confidence_scale.ceiling.to_bits().hash(&mut hasher);
+        format!("{:016x}:{}", hasher.finish(), normalize_question(question))
+    }
+
+    /// Response declining to answer, keeping the sources that were found.
+    fn abstain(sources: Vec<ContextChunk>, confidence: Confidence) -> RagResponse {
+        RagResponse {
+            answer: LOW_CONFIDENCE_ANSWER.to_string(),
+            sources,
+            citations: Vec::new(),
+            verification: None,
+            confidence: Some(confidence),
+            abstained: true,
+            cached: false,
+        }
+    }
+
+    /// Ask a single question and stream the answer as it is generated.
+    ///
+    /// The sources are known before the answer starts. Callers that want
+    /// citation statistics pass the complete answer to [`Self::record_citations`].
+    /// Usage of the answer call is recorded as the stream is consumed, so
+    /// consume it in the same usage scope as this call.
+    #[instrument(skip(self), fields(question = %question))]
+    pub async fn ask_stream(&self, question: &str) -> Result<RagStream> {
+        info!("Streaming answer to: {}", question);
+
+        if let Some(answer) = self.route(question).await? {
+            return Ok(RagStream {
+                answer: futures::stream::once(async { Ok(answer) }).boxed(),
+                sources: Vec::new(),
+                confidence: None,
+            });
+        }
+
+        let context_chunks = self.context_builder.build(question).await?;
+
+        if context_chunks.is_empty() {
+            return Ok(RagStream {
+                answer: futures::stream::once(async { Ok(NO_CONTEXT_ANSWER.to_string()) }).boxed(),
+                sources: Vec::new(),
+                confidence: None,
+            });
+        }
+
+        // Streamed answers can only be judged on retrieval
+        let confidence = Confidence::from_sources(&context_chunks, self.confidence_scale);
+        if confidence.is_below(self.min_confidence) {
+            return Ok(RagStream {
+                answer: futures::stream::once(async { Ok(LOW_CONFIDENCE_ANSWER.to_string()) }).boxed(),
+                sources: context_chunks,
+                confidence: Some(confidence),
+            });
+        }
+
+        let request = self.answer_request(question, &context_chunks)?;
+        let answer = self.client.create_stream(request, UsageStage::Rag).await?;
+
+        Ok(RagStream {
+            answer,
+            sources: context_chunks,
+            confidence: Some(confidence),
+        })
+    }
+
+    /// Build the single-question completion request for the retrieved context.
+    fn answer_request(
+        &self,
+        question: &str,
+        context_chunks: &[ContextChunk],
+    ) -> Result<CreateChatCompletionRequest> {
+        let context_text = format_context_for_prompt(context_chunks);
 
         let mut vars = HashMap::new();
         vars.insert("question".to_string(), question.to_string());
@@ -89,30 +422,12 @@ impl RagEngine {
                 .into(),
         ];
 
-        let request = CreateChatCompletionRequestArgs::default()
+        CreateChatCompletionRequestArgs::default()
             .model(&self.model)
             .messages(messages)
             .temperature(0.7)
             .build()
-            .map_err(|e| LyttError::Rag(e.to_string()))?;
-
-        let response = self.client.chat().create(request).await.map_err(|e| {
-            LyttError::OpenAI(format!("Failed to generate response: {}", e))
-        })?;
-
-        let answer = response
-            .choices
-            .first()
-            .and_then(|c| c.message.content.as_ref())
-            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?
-            .clone();
-
-        debug!("Generated response with {} sources", context_chunks.len());
-
-        Ok(RagResponse {
-            answer,
-            sources: context_chunks,
-        })
+            .map_err(|e| LyttError::Rag(e.to_string()))
     }
 
     /// Start or continue a chat session.
@@ -165,9 +480,9 @@ impl RagEngine {
             .build()
             .map_err(|e| LyttError::Rag(e.to_string()))?;
 
-        let response = self.client.chat().create(request).await.map_err(|e| {
-            LyttError::OpenAI(format!("Failed to generate response: {}", e))
-        })?;
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
 
         let answer = response
             .choices
@@ -188,25 +503,93 @@ impl RagEngine {
             self.conversation_history = self.conversation_history[self.conversation_history.len() - 20..].to_vec();
         }
 
+        let (answer, citations) = resolve_citations(&answer, &context_chunks);
+        self.record_cited(&citations).await;
+
         Ok(RagResponse {
             answer,
             sources: context_chunks,
+            citations,
+            verification: None,
+            confidence: None,
+            abstained: false,
+            cached: false,
         })
     }
 
+    /// Resolve the citations in a streamed answer and count the cited
+    /// sources towards retrieval statistics.
+    pub async fn record_citations(&self, answer: &str, sources: &[ContextChunk]) -> Vec<Citation> {
+        let (_, citations) = resolve_citations(answer, sources);
+        self.record_cited(&citations).await;
+        citations
+    }
+
+    /// Count cited sources towards retrieval statistics.
+    async fn record_cited(&self, citations: &[Citation]) {
+        let mut ids: Vec<_> = citations.iter().map(|c| c.document_id).collect();
+        ids.sort();
+        ids.dedup();
+        if ids.is_empty() {
+            return;
+        }
+        if let Err(e) = self.vector_store.record_citations(&ids).await {
+            warn!("Failed to record citations: {}", e);
+        }
+    }
+
     /// Clear conversation history.
     pub fn clear_history(&mut self) {
         self.conversation_history.clear();
     }
 }
 
+/// A RAG answer that is still being generated.
+pub struct RagStream {
+    /// Answer text fragments, in order.
+    pub answer: TextStream,
+    /// Source chunks used for the answer.
+    pub sources: Vec<ContextChunk>,
+    /// Confidence from retrieval scores (`None` when nothing was found).
+    pub confidence: Option<Confidence>,
+}
+
 /// A RAG response with answer and sources.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RagResponse {
     /// The generated answer.
     pub answer: String,
-    /// Source chunks used for the answer.
+    /// Source chunks used for the answer, numbered from 1 by the answer's
+    /// citation markers.
     pub sources: Vec<ContextChunk>,
+    /// Sources the answer cites, in order of first mention.
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+    /// Claim-by-claim check against the sources, when `rag.verify` is on.
+    #[serde(default)]
+    pub verification: Option<Verification>,
+    /// How far the answer can be trusted (`None` for chat replies and when
+    /// nothing relevant was found).
+    #[serde(default)]
+    pub confidence: Option<Confidence>,
+    /// Whether the engine declined to answer for lack of information.
+    #[serde(default)]
+    pub abstained: bool,
+    /// Whether the answer came from the answer cache.
+    #[serde(skip)]
+    pub cached: bool,
+}
+
+/// Lowercase, collapse whitespace and drop trailing punctuation, so trivially
+/// different phrasings of a question share a cache entry.
+fn normalize_question(question: &str) -> String {
+    question
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(['?', '!', '.', ' '])
+        .to_string()
 }
 
 impl RagResponse {
@@ -214,12 +597,29 @@ impl RagResponse {
     pub fn format_for_display(&self) -> String {
         let mut output = self.answer.clone();
 
+        if let Some(confidence) = &self.confidence {
+            output.push_str(&format!("\n\nConfidence: {:.0}%", confidence.score * 100.0));
+        }
+
+        if let Some(verification) = &self.verification {
+            let flagged: Vec<_> = verification.flagged().collect();
+            if !flagged.is_empty() {
+                output.push_str("\n\n--- Not supported by the sources ---\n");
+                for check in flagged {
+                    output.push_str(&format!("\n({}) {}", check.verdict, check.claim));
+                }
+            }
+        }
+
         if !self.sources.is_empty() {
             output.push_str("\n\n--- Sources ---\n");
-            for source in &self.sources {
+            for (i, source) in self.sources.iter().enumerate() {
                 output.push_str(&format!(
-                    "\n{} @ {} (score: {:.2})",
-                    source.video_title, source.timestamp, source.score
+                    "\n[{}] {} @ {} (score: {:.2})",
+                    i + 1,
+                    source.source_label(),
+                    source.timestamp,
+                    source.score
                 ));
                 if let Some(url) = &source.url {
                     output.push_str(&format!("\n  {}", url));
@@ -230,3 +630,15 @@ impl RagResponse {
         output
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_question() {
+        assert_eq!(normalize_question("  What is  Entropy? "), "what is entropy");
+        assert_eq!(normalize_question("what is entropy"), "what is entropy");
+        assert_ne!(normalize_question("What is entropy?"), normalize_question("What is a bit?"));
+    }
+}
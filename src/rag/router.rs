@@ -0,0 +1,238 @@
+//! Question routing.
+//!
+//! Questions about the library itself ("how many videos do I have about
+//! Rust?", "which episode is the longest?") can't be answered from a handful
+//! of retrieved chunks. They are recognized by their phrasing and answered
+//! from the store's list of indexed media instead, without an LLM call. All
+//! other questions go through retrieval as usual.
+
+use crate::vector_store::IndexedVideo;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Words for an item of the library.
+const MEDIA: &str = r"(?:videos?|episodes?|recordings?|podcasts?|talks?|lectures?|meetings?|audiobooks?|books?|files?|items?|media)";
+
+/// Phrasings of metadata questions, matched against the lowercased question.
+struct Patterns {
+    count: Regex,
+    total: Regex,
+    list: Regex,
+    superlative: Regex,
+    media: Regex,
+    library: Regex,
+    about: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let regex = |pattern: String| Regex::new(&pattern).expect("valid routing regex");
+        Patterns {
+            count: regex(format!(r"^how many {}\b", MEDIA)),
+            total: regex(format!(
+                r"^how (?:many hours|many minutes|much (?:audio|video|content)|long (?:are|is) (?:all )?(?:of )?(?:my|the) {})\b",
+                MEDIA
+            )),
+            list: regex(format!(
+                r"^(?:(?:list|show)(?: me)?(?: all)?(?: of)?(?: my| the)? {m}\b|(?:what|which) {m} (?:do i have|are there|are indexed|have i)\b)",
+                m = MEDIA
+            )),
+            superlative: regex(format!(
+                r"^(?:which|what)(?:'s| is| was)?(?: the)? (?:{m} (?:is|was) the )?(longest|shortest|newest|latest|most recent|oldest|earliest|first)\b(?: \w+)?(?: {m})?",
+                m = MEDIA
+            )),
+            media: regex(format!(r"\b{}\b", MEDIA)),
+            library: regex(
+                r"\b(?:do i have|have i (?:got|saved|indexed|transcribed|recorded|added)|did i (?:index|transcribe|record|add|save)|are (?:indexed|transcribed|stored|saved)|(?:are )?(?:in|from) (?:my|the) library)\b"
+                    .to_string(),
+            ),
+            about: regex(r"\b(?:about|on|covering|mentioning|related to)\s+(.+)$".to_string()),
+        }
+    })
+}
+
+/// What a metadata question asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// How many items there are.
+    Count,
+    /// How much audio there is in total.
+    TotalDuration,
+    /// Which items there are.
+    List,
+    /// The longest item.
+    Longest,
+    /// The shortest item.
+    Shortest,
+    /// The most recently indexed item.
+    Newest,
+    /// The item indexed first.
+    Oldest,
+}
+
+/// A question about the library rather than its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataQuery {
+    /// What is asked for.
+    pub aggregate: Aggregate,
+    /// Only items about this subject, if given.
+    pub about: Option<String>,
+}
+
+/// Classify a question: `Some` for a metadata question answered from the
+/// list of indexed media, `None` for a question about content.
+pub fn route(question: &str) -> Option<MetadataQuery> {
+    let question = question.trim().trim_end_matches(['?', '.', '!']).to_lowercase();
+    let question = question.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let patterns = patterns();
+
+    // "How many books does the author recommend?" is about content
+    let aggregate = if patterns.count.is_match(&question) && patterns.library.is_match(&question) {
+        Aggregate::Count
+    } else if patterns.total.is_match(&question) {
+        Aggregate::TotalDuration
+    } else if patterns.list.is_match(&question) {
+        Aggregate::List
+    } else if let Some(captures) = patterns.superlative.captures(&question) {
+        // "Which talk is the longest", but not "what is the longest pause in the keynote"
+        if !patterns.media.is_match(&captures[0]) {
+            return None;
+        }
+        match &captures[1] {
+            "longest" => Aggregate::Longest,
+            "shortest" => Aggregate::Shortest,
+            "newest" | "latest" | "most recent" => Aggregate::Newest,
+            _ => Aggregate::Oldest,
+        }
+    } else {
+        return None;
+    };
+
+    let subject = patterns.library.replace_all(&question, "");
+    let about = patterns
+        .about
+        .captures(&subject)
+        .map(|c| c[1].trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(MetadataQuery { aggregate, about })
+}
+
+impl MetadataQuery {
+    /// Answer the question from `videos`, the indexed media in scope (already
+    /// narrowed to the subject, if any).
+    pub fn answer(&self, videos: &[IndexedVideo]) -> String {
+        let scope = match &self.about {
+            Some(about) => format!(" about {}", about),
+            None => String::new(),
+        };
+        if videos.is_empty() {
+            return format!("Your library has no media{}.", scope);
+        }
+
+        let line = |v: &IndexedVideo| {
+            format!(
+                "- {} [{}] ({}, indexed {})",
+                v.video_title,
+                v.video_id,
+                format_duration(v.total_duration_seconds),
+                v.indexed_at.format("%Y-%m-%d")
+            )
+        };
+        let pick = |best: Option<&IndexedVideo>, what: &str| match best {
+            Some(v) => format!("The {} item{} is:\n{}", what, scope, line(v)),
+            None => format!("Your library has no media{}.", scope),
+        };
+
+        match self.aggregate {
+            Aggregate::Count => {
+                let noun = if videos.len() == 1 { "item" } else { "items" };
+                format!("Your library has {} {}{}.", videos.len(), noun, scope)
+            }
+            Aggregate::TotalDuration => {
+                let total: f64 = videos.iter().map(|v| v.total_duration_seconds).sum();
+                format!(
+                    "Your library has {} of media{} across {} items.",
+                    format_duration(total),
+                    scope,
+                    videos.len()
+                )
+            }
+            Aggregate::List => {
+                let mut sorted: Vec<&IndexedVideo> = videos.iter().collect();
+                sorted.sort_by_key(|v| std::cmp::Reverse(v.indexed_at));
+                let lines: Vec<String> = sorted.into_iter().map(line).collect();
+                format!("Your library has {} items{}:\n{}", lines.len(), scope, lines.join("\n"))
+            }
+            Aggregate::Longest => pick(
+                videos.iter().max_by(|a, b| a.total_duration_seconds.total_cmp(&b.total_duration_seconds)),
+                "longest",
+            ),
+            Aggregate::Shortest => pick(
+                videos.iter().min_by(|a, b| a.total_duration_seconds.total_cmp(&b.total_duration_seconds)),
+                "shortest",
+            ),
+            Aggregate::Newest => pick(videos.iter().max_by_key(|v| v.indexed_at), "most recently indexed"),
+            Aggregate::Oldest => pick(videos.iter().min_by_key(|v| v.indexed_at), "first indexed"),
+        }
+    }
+}
+
+/// Format seconds as "1h 02m" or "12m 34s".
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds >= 3600 {
+        format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_route() {
+        let query = |aggregate, about: Option<&str>| {
+            Some(MetadataQuery {
+                aggregate,
+                about: about.map(String::from),
+            })
+        };
+        assert_eq!(route("How many videos do I have about Rust?"), query(Aggregate::Count, Some("rust")));
+        assert_eq!(route("Which episode is the longest?"), query(Aggregate::Longest, None));
+        assert_eq!(route("What's the most recent talk?"), query(Aggregate::Newest, None));
+        assert_eq!(route("how many hours of audio do I have"), query(Aggregate::TotalDuration, None));
+        assert_eq!(route("List my recordings about budgets"), query(Aggregate::List, Some("budgets")));
+        assert_eq!(route("What is RLHF?"), None);
+        assert_eq!(route("What is the longest pause in the keynote?"), None);
+        assert_eq!(route("How many parameters does GPT-3 have?"), None);
+        assert_eq!(route("How many talks about Rust are in my library?"), query(Aggregate::Count, Some("rust")));
+        assert_eq!(route("How many episodes have I transcribed?"), query(Aggregate::Count, None));
+        assert_eq!(route("How many books does the author recommend?"), None);
+        assert_eq!(route("How many items are on the agenda?"), None);
+        assert_eq!(route("How many meetings did the team skip last quarter?"), None);
+
+        let now = Utc::now();
+        let video = |id: &str, duration: f64, days_ago: i64| IndexedVideo {
+            video_id: id.to_string(),
+            video_title: format!("Video {}", id),
+            chunk_count: 1,
+            total_duration_seconds: duration,
+            indexed_at: now - Duration::days(days_ago),
+            language: None,
+        };
+        let videos = vec![video("a", 600.0, 3), video("b", 3725.0, 10)];
+        assert_eq!(
+            MetadataQuery { aggregate: Aggregate::Count, about: Some("rust".to_string()) }.answer(&videos),
+            "Your library has 2 items about rust."
+        );
+        assert!(MetadataQuery { aggregate: Aggregate::Longest, about: None }
+            .answer(&videos)
+            .contains("Video b [b] (1h 02m"));
+        assert!(MetadataQuery { aggregate: Aggregate::Newest, about: None }.answer(&videos).contains("[a]"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Grounding check for generated answers.
+//!
+//! After an answer is written, a second LLM call (LLM-as-judge) splits it
+//! into factual claims and checks each against the retrieved excerpts, so
+//! statements the sources don't back up can be flagged before the answer is
+//! passed on.
+
+use super::{context::format_context_for_prompt, ContextChunk};
+use crate::config::{Prompts, RagSettings};
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// How well the excerpts back up a claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// The excerpts state or directly imply the claim.
+    Supported,
+    /// The excerpts don't mention it, or only partly back it up.
+    Unsupported,
+    /// The excerpts say otherwise.
+    Contradicted,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Supported => write!(f, "supported"),
+            Verdict::Unsupported => write!(f, "unsupported"),
+            Verdict::Contradicted => write!(f, "contradicted"),
+        }
+    }
+}
+
+/// One claim of an answer and how it was judged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimCheck {
+    /// The claim, as stated in the answer.
+    pub claim: String,
+    /// Whether the excerpts back it up.
+    pub verdict: Verdict,
+    /// Numbers of the excerpts that support or contradict it.
+    #[serde(default)]
+    pub sources: Vec<usize>,
+    /// Short reason for the verdict.
+    #[serde(default)]
+    pub explanation: String,
+}
+
+/// Result of checking an answer against its sources.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Verification {
+    /// Every factual claim in the answer.
+    pub claims: Vec<ClaimCheck>,
+}
+
+impl Verification {
+    /// Whether every claim is supported by the sources.
+    pub fn is_grounded(&self) -> bool {
+        self.claims.iter().all(|c| c.verdict == Verdict::Supported)
+    }
+
+    /// Claims that are unsupported or contradicted.
+    pub fn flagged(&self) -> impl Iterator<Item = &ClaimCheck> {
+        self.claims.iter().filter(|c| c.verdict != Verdict::Supported)
+    }
+}
+
+/// Checks answers against the excerpts they were written from.
+pub struct AnswerVerifier {
+    client: ChatClient,
+    model: String,
+    prompts: Prompts,
+}
+
+impl AnswerVerifier {
+    /// Create a new answer verifier.
+    pub fn new(model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            prompts: Prompts::default(),
+        }
+    }
+
+    /// Build a verifier from `rag.verify`, or `None` when it is disabled.
+    pub fn from_settings(settings: &RagSettings, model: &str, prompts: &Prompts) -> Option<Self> {
+        settings.verify.then(|| {
+            Self::new(model)
+                .with_client(ChatClient::from_settings(&settings.llm))
+                .with_prompts(prompts.clone())
+        })
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Model used as the judge.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Check each claim of `answer` against the numbered `sources`.
+    pub async fn verify(&self, answer: &str, sources: &[ContextChunk]) -> Result<Verification> {
+        let mut vars = HashMap::new();
+        vars.insert("answer".to_string(), answer.to_string());
+        vars.insert("context".to_string(), format_context_for_prompt(sources));
+
+        let system = self.prompts.render_with_custom(&self.prompts.verify.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.verify.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.0)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        let verification = parse_verification(content, sources.len())?;
+        debug!(
+            "Checked {} claims, {} flagged",
+            verification.claims.len(),
+            verification.flagged().count()
+        );
+        Ok(verification)
+    }
+}
+
+/// Parse the judge's JSON, dropping blank claims and excerpt numbers that
+/// don't exist.
+fn parse_verification(content: &str, source_count: usize) -> Result<Verification> {
+    let mut parsed: Verification = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid verification JSON: {}", e)))?;
+
+    parsed.claims.retain(|c| !c.claim.trim().is_empty());
+    for claim in &mut parsed.claims {
+        claim.sources.retain(|n| (1..=source_count).contains(n));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verification() {
+        let content = r#"{"claims": [
+            {"claim": "Rust has no garbage collector.", "verdict": "supported", "sources": [1, 9]},
+            {"claim": "Rust was released in 2010.", "verdict": "unsupported", "explanation": "No excerpt gives a date."},
+            {"claim": " ", "verdict": "supported"}
+        ]}"#;
+        let verification = parse_verification(content, 2).unwrap();
+
+        assert_eq!(verification.claims.len(), 2);
+        assert_eq!(verification.claims[0].sources, vec![1]);
+        assert!(!verification.is_grounded());
+        let flagged: Vec<_> = verification.flagged().map(|c| c.claim.as_str()).collect();
+        assert_eq!(flagged, vec!["Rust was released in 2010."]);
+
+        assert!(parse_verification(r#"{"claims": [{"claim": "x", "verdict": "maybe"}]}"#, 1).is_err());
+    }
+}
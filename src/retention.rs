@@ -0,0 +1,303 @@
+//! Retention policies and library pruning.
+//!
+//! Policies bound the number of transcript chunks (`max_documents`) and the
+//! age of media (`max_age_days`), optionally per tag or source. Over-quota
+//! libraries lose their lowest-value chunks first: chunks that have never been
+//! returned by a search, then the least recently used. Derived documents
+//! (rollups, summaries) are never pruned.
+
+use crate::audio_source::SourceType;
+use crate::config::{PruneAction, RetentionPolicy, RetentionSettings};
+use crate::error::Result;
+use crate::summary::MediaSummary;
+use crate::vector_store::{SqliteVectorStore, VectorStore};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use uuid::Uuid;
+
+/// A transcript chunk considered for pruning.
+#[derive(Debug, Clone)]
+pub struct RetentionCandidate {
+    /// Document ID.
+    pub id: Uuid,
+    /// Media the chunk belongs to.
+    pub video_id: String,
+    /// Title of the media.
+    pub video_title: String,
+    /// Publication date of the media, or when it was indexed.
+    pub date: DateTime<Utc>,
+    /// Number of times the chunk was returned by a search.
+    pub retrieval_count: u32,
+    /// When the chunk was last returned by a search.
+    pub last_retrieved_at: Option<DateTime<Utc>>,
+}
+
+/// Why a chunk is pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PruneReason {
+    /// Older than `max_age_days`.
+    Age,
+    /// Over `max_documents`.
+    Quota,
+}
+
+impl std::fmt::Display for PruneReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PruneReason::Age => write!(f, "age"),
+            PruneReason::Quota => write!(f, "quota"),
+        }
+    }
+}
+
+/// A chunk selected for pruning.
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneItem {
+    pub id: Uuid,
+    pub video_id: String,
+    pub video_title: String,
+    pub reason: PruneReason,
+    pub retrieval_count: u32,
+}
+
+/// Result of applying a prune plan.
+#[derive(Debug, Clone)]
+pub struct PruneOutcome {
+    /// Documents removed from the store.
+    pub removed: usize,
+    /// Archive file written, if the action was `archive`.
+    pub archive_path: Option<PathBuf>,
+}
+
+/// Whether a policy covers a media item.
+fn policy_matches(policy: &RetentionPolicy, video_id: &str, tags: &[String]) -> bool {
+    if let Some(tag) = &policy.tag {
+        if !tags.iter().any(|t| t == tag) {
+            return false;
+        }
+    }
+    if let Some(source) = &policy.source {
+        if !source.eq_ignore_ascii_case(&SourceType::of_media_id(video_id).to_string()) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Select the chunks the policies would prune.
+///
+/// `tags` maps media IDs to their tags. Each chunk is listed at most once,
+/// with the reason of the first policy that selected it.
+pub fn plan(
+    candidates: &[RetentionCandidate],
+    tags: &HashMap<String, Vec<String>>,
+    policies: &[RetentionPolicy],
+    now: DateTime<Utc>,
+) -> Vec<PruneItem> {
+    let no_tags = Vec::new();
+    let mut selected: HashSet<Uuid> = HashSet::new();
+    let mut items = Vec::new();
+
+    let mut select = |candidate: &RetentionCandidate, reason: PruneReason| {
+        if selected.insert(candidate.id) {
+            items.push(PruneItem {
+                id: candidate.id,
+                video_id: candidate.video_id.clone(),
+                video_title: candidate.video_title.clone(),
+                reason,
+                retrieval_count: candidate.retrieval_count,
+            });
+        }
+    };
+
+    for policy in policies {
+        let mut members: Vec<&RetentionCandidate> = candidates
+            .iter()
+            .filter(|c| policy_matches(policy, &c.video_id, tags.get(&c.video_id).unwrap_or(&no_tags)))
+            .collect();
+
+        if let Some(days) = policy.max_age_days {
+            let cutoff = now - Duration::days(days as i64);
+            members.retain(|c| {
+                if c.date < cutoff {
+                    select(c, PruneReason::Age);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        if let Some(max) = policy.max_documents {
+            if members.len() > max {
+                // Lowest value first: never retrieved, then least recently used
+                members.sort_by_key(|c| (c.retrieval_count > 0, c.last_retrieved_at.unwrap_or(c.date)));
+                let excess = members.len() - max;
+                for candidate in &members[..excess] {
+                    select(candidate, PruneReason::Quota);
+                }
+            }
+        }
+    }
+
+    items
+}
+
+/// Plan pruning for a store with the configured policies.
+pub fn plan_for_store(store: &SqliteVectorStore, settings: &RetentionSettings) -> Result<Vec<PruneItem>> {
+    if settings.policies.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let candidates = store.retention_candidates()?;
+
+    let mut tags = HashMap::new();
+    for video_id in candidates.iter().map(|c| &c.video_id).collect::<HashSet<_>>() {
+        tags.insert(video_id.clone(), store.get_tags(video_id)?);
+    }
+
+    Ok(plan(&candidates, &tags, &settings.policies, Utc::now()))
+}
+
+/// Remove the planned chunks, archiving them first if configured.
+///
+/// Media left without any chunks also lose their stored summary. Transcripts
+/// are kept, so pruned media can be restored with `lytt rechunk`.
+pub async fn apply(
+    store: &SqliteVectorStore,
+    items: &[PruneItem],
+    action: PruneAction,
+    archive_dir: &Path,
+) -> Result<PruneOutcome> {
+    if items.is_empty() {
+        return Ok(PruneOutcome {
+            removed: 0,
+            archive_path: None,
+        });
+    }
+
+    let ids: Vec<Uuid> = items.iter().map(|i| i.id).collect();
+
+    let archive_path = match action {
+        PruneAction::Archive => Some(archive(store, &ids, archive_dir)?),
+        PruneAction::Delete => None,
+    };
+
+    let removed = store.delete_documents(&ids)?;
+
+    let affected: HashSet<&str> = items.iter().map(|i| i.video_id.as_str()).collect();
+    for video_id in affected {
+        if !store.is_video_indexed(video_id).await? && store.delete_summary(video_id)? {
+            store.delete_by_video_id(&MediaSummary::document_id(video_id)).await?;
+        }
+    }
+
+    info!("Pruned {} documents", removed);
+    Ok(PruneOutcome { removed, archive_path })
+}
+
+/// Write documents to a timestamped JSONL file.
+fn archive(store: &SqliteVectorStore, ids: &[Uuid], archive_dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(archive_dir)?;
+    let path = archive_dir.join(format!("prune-{}.jsonl", Utc::now().format("%Y%m%d-%H%M%S")));
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+    for doc in store.get_documents(ids)? {
+        serde_json::to_writer(&mut file, &doc)?;
+        file.write_all(b"\n")?;
+    }
+    file.flush()?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(video_id: &str, days_old: i64, retrievals: u32) -> RetentionCandidate {
+        RetentionCandidate {
+            id: Uuid::new_v4(),
+            video_id: video_id.to_string(),
+            video_title: video_id.to_string(),
+            date: Utc::now() - Duration::days(days_old),
+            retrieval_count: retrievals,
+            last_retrieved_at: (retrievals > 0).then(Utc::now),
+        }
+    }
+
+    #[test]
+    fn test_plan_quota_prefers_never_retrieved() {
+        let candidates = vec![
+            candidate("a", 10, 3),
+            candidate("a", 5, 0),
+            candidate("b", 20, 0),
+            candidate("b", 1, 1),
+        ];
+        let policy = RetentionPolicy {
+            max_documents: Some(2),
+            ..Default::default()
+        };
+
+        let items = plan(&candidates, &HashMap::new(), &[policy], Utc::now());
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| i.retrieval_count == 0 && i.reason == PruneReason::Quota));
+    }
+
+    #[test]
+    fn test_plan_age_by_tag_and_source() {
+        let candidates = vec![
+            candidate("local_old", 100, 5),
+            candidate("yt_old", 100, 0),
+            candidate("yt_new", 1, 0),
+        ];
+        let mut tags = HashMap::new();
+        tags.insert("yt_old".to_string(), vec!["podcasts".to_string()]);
+        tags.insert("yt_new".to_string(), vec!["podcasts".to_string()]);
+
+        let policies = vec![
+            RetentionPolicy {
+                tag: Some("podcasts".to_string()),
+                max_age_days: Some(30),
+                ..Default::default()
+            },
+            RetentionPolicy {
+                source: Some("youtube".to_string()),
+                max_age_days: Some(30),
+                ..Default::default()
+            },
+        ];
+
+        let items = plan(&candidates, &tags, &policies, Utc::now());
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].video_id, "yt_old");
+        assert_eq!(items[0].reason, PruneReason::Age);
+    }
+
+    #[test]
+    fn test_plan_source_tells_web_from_youtube() {
+        let candidates = vec![
+            candidate("dQw4w9WgXcQ", 100, 0),
+            candidate("web_vimeo_76979871", 100, 0),
+            candidate("local_abc", 100, 0),
+        ];
+        let policy = |source: &str| RetentionPolicy {
+            source: Some(source.to_string()),
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+
+        let youtube = plan(&candidates, &HashMap::new(), &[policy("youtube")], Utc::now());
+        assert_eq!(youtube.len(), 1);
+        assert_eq!(youtube[0].video_id, "dQw4w9WgXcQ");
+
+        let web = plan(&candidates, &HashMap::new(), &[policy("web")], Utc::now());
+        assert_eq!(web.len(), 1);
+        assert_eq!(web[0].video_id, "web_vimeo_76979871");
+    }
+}
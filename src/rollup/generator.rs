@@ -0,0 +1,254 @@
+//! Rollup generation.
+
+use super::{Rollup, RollupPeriod};
+use crate::config::Prompts;
+use crate::embedding::Embedder;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{Document, SqliteVectorStore, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs,
+};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Default budget for source material sent to the LLM, in characters.
+const DEFAULT_MAX_INPUT_CHARS: usize = 60_000;
+
+/// Generates, stores, and indexes rollups.
+pub struct RollupGenerator {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<SqliteVectorStore>,
+    embedder: Arc<dyn Embedder>,
+    prompts: Prompts,
+    max_input_chars: usize,
+}
+
+impl RollupGenerator {
+    /// Create a new rollup generator.
+    pub fn new(vector_store: Arc<SqliteVectorStore>, embedder: Arc<dyn Embedder>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            embedder,
+            prompts: Prompts::default(),
+            max_input_chars: DEFAULT_MAX_INPUT_CHARS,
+        }
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Set the character budget for source material.
+    pub fn with_max_input_chars(mut self, max_input_chars: usize) -> Self {
+        self.max_input_chars = max_input_chars;
+        self
+    }
+
+    /// Generate a rollup for a tag over a period, then store and index it.
+    #[instrument(skip(self))]
+    pub async fn generate(&self, tag: &str, period: RollupPeriod) -> Result<Rollup> {
+        let now = Utc::now();
+        let start = period.start(now);
+
+        // Collect tagged media within the window
+        let mut media: Vec<(DateTime<Utc>, Vec<Document>)> = Vec::new();
+        for video_id in self.vector_store.videos_with_tag(tag)? {
+            let docs = self.vector_store.get_by_video_id(&video_id).await?;
+            let Some(first) = docs.first() else {
+                continue;
+            };
+            let date = first.source_created_at.unwrap_or(first.indexed_at);
+            if start.is_some_and(|s| date < s) {
+                continue;
+            }
+            media.push((date, docs));
+        }
+
+        if media.is_empty() {
+            return Err(LyttError::InvalidInput(format!(
+                "No indexed media tagged '{}' in the last {}",
+                tag, period
+            )));
+        }
+
+        media.sort_by_key(|(date, _)| *date);
+        info!("Generating {} rollup for '{}' from {} media", period, tag, media.len());
+
+        let sources = format_sources(&media, self.max_input_chars);
+
+        let mut vars = HashMap::new();
+        vars.insert("tag".to_string(), tag.to_string());
+        vars.insert("period".to_string(), period.to_string());
+        vars.insert("media_count".to_string(), media.len().to_string());
+        vars.insert("sources".to_string(), sources);
+
+        let content = self.complete(&vars).await?;
+
+        let rollup = Rollup {
+            id: Rollup::make_id(tag, period),
+            tag: tag.to_string(),
+            period,
+            period_start: start,
+            period_end: now,
+            content,
+            source_video_ids: media
+                .iter()
+                .map(|(_, docs)| docs[0].video_id.clone())
+                .collect(),
+            created_at: now,
+        };
+
+        self.vector_store.store_rollup(&rollup)?;
+        self.index(&rollup).await?;
+
+        Ok(rollup)
+    }
+
+    /// Index a rollup's sections so they are retrievable by search and ask.
+    pub async fn index(&self, rollup: &Rollup) -> Result<usize> {
+        self.vector_store.delete_by_video_id(&rollup.id).await?;
+
+        let sections = rollup.sections();
+        let texts: Vec<String> = sections.iter().map(|(_, text)| text.clone()).collect();
+        let embeddings = self.embedder.embed_batch(&texts).await?;
+
+        // Provenance points at every chunk of the media the rollup was built from
+        let mut provenance = Vec::new();
+        for video_id in &rollup.source_video_ids {
+            for doc in self.vector_store.get_by_video_id(video_id).await? {
+                provenance.push(doc.id.to_string());
+            }
+        }
+
+        let title = rollup.title();
+        let documents: Vec<Document> = sections
+            .into_iter()
+            .zip(embeddings)
+            .enumerate()
+            .map(|(i, ((section_title, content), embedding))| {
+                Document::new(
+                    rollup.id.clone(),
+                    title.clone(),
+                    section_title,
+                    content,
+                    0.0,
+                    0.0,
+                    embedding,
+                    i as i32,
+                    Some(rollup.created_at),
+                )
+                .derived_from(provenance.clone())
+            })
+            .collect();
+
+        self.vector_store.upsert_batch(&documents).await
+    }
+
+    /// Call the LLM with the rollup prompts.
+    async fn complete(&self, vars: &HashMap<String, String>) -> Result<String> {
+        let system = self.prompts.render_with_custom(&self.prompts.rollup.system, vars);
+        let user = self.prompts.render_with_custom(&self.prompts.rollup.user, vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rollup, &self.model, response.usage.as_ref());
+
+        response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))
+    }
+}
+
+/// Format media chunks for the prompt, sharing the character budget evenly across media.
+fn format_sources(media: &[(DateTime<Utc>, Vec<Document>)], max_chars: usize) -> String {
+    let per_media = max_chars / media.len().max(1);
+    let mut output = String::new();
+
+    for (date, docs) in media {
+        let mut section = format!("### {} ({})\n", docs[0].video_title, date.format("%Y-%m-%d"));
+        let per_chunk = per_media.saturating_sub(section.len()) / docs.len().max(1);
+
+        for doc in docs {
+            let content: String = doc.content.chars().take(per_chunk).collect();
+            match &doc.section_title {
+                Some(title) => section.push_str(&format!(
+                    "- [{}] {}: {}\n",
+                    doc.format_timestamp(),
+                    title,
+                    content
+                )),
+                None => section.push_str(&format!("- [{}] {}\n", doc.format_timestamp(), content)),
+            }
+        }
+
+        output.push_str(&section);
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sources_respects_budget() {
+        let docs: Vec<Document> = (0..10)
+            .map(|i| {
+                Document::new(
+                    "v1".to_string(),
+                    "Lecture 1".to_string(),
+                    Some(format!("Part {}", i)),
+                    "x".repeat(1000),
+                    i as f64 * 60.0,
+                    (i + 1) as f64 * 60.0,
+                    vec![],
+                    i,
+                    None,
+                )
+            })
+            .collect();
+
+        let output = format_sources(&[(Utc::now(), docs)], 2000);
+        assert!(output.starts_with("### Lecture 1"));
+        assert!(output.contains("Part 9"));
+        assert!(output.len() < 3000);
+    }
+}
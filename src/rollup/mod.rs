@@ -0,0 +1,191 @@
+//! Summary rollups across tagged media.
+//!
+//! A rollup is a synthesis document covering all media with a given tag
+//! (or channel) over a time period. Rollups are stored and indexed back into
+//! the vector store, so broad questions can be answered from one document
+//! instead of hundreds of chunks.
+
+mod generator;
+
+pub use generator::RollupGenerator;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Prefix for tags that are derived from a media's channel.
+pub const CHANNEL_TAG_PREFIX: &str = "channel:";
+
+/// Build the tag used for a channel.
+pub fn channel_tag(channel: &str) -> String {
+    format!("{}{}", CHANNEL_TAG_PREFIX, channel)
+}
+
+/// Time window covered by a rollup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RollupPeriod {
+    Week,
+    Month,
+    Quarter,
+    Year,
+    /// All media, regardless of date.
+    All,
+}
+
+impl RollupPeriod {
+    /// Start of the window ending at `now`, or None for all time.
+    pub fn start(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let days = match self {
+            RollupPeriod::Week => 7,
+            RollupPeriod::Month => 30,
+            RollupPeriod::Quarter => 91,
+            RollupPeriod::Year => 365,
+            RollupPeriod::All => return None,
+        };
+        Some(now - Duration::days(days))
+    }
+}
+
+impl std::str::FromStr for RollupPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "week" | "weekly" => Ok(RollupPeriod::Week),
+            "month" | "monthly" => Ok(RollupPeriod::Month),
+            "quarter" | "quarterly" => Ok(RollupPeriod::Quarter),
+            "year" | "yearly" => Ok(RollupPeriod::Year),
+            "all" => Ok(RollupPeriod::All),
+            _ => Err(format!(
+                "Unknown period: {}. Use week, month, quarter, year, or all.",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for RollupPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RollupPeriod::Week => write!(f, "week"),
+            RollupPeriod::Month => write!(f, "month"),
+            RollupPeriod::Quarter => write!(f, "quarter"),
+            RollupPeriod::Year => write!(f, "year"),
+            RollupPeriod::All => write!(f, "all"),
+        }
+    }
+}
+
+/// A stored rollup document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rollup {
+    /// Rollup ID (also used as the video ID of its indexed chunks).
+    pub id: String,
+    /// Tag the rollup covers.
+    pub tag: String,
+    /// Period the rollup covers.
+    pub period: RollupPeriod,
+    /// Start of the covered window (None for all time).
+    pub period_start: Option<DateTime<Utc>>,
+    /// End of the covered window.
+    pub period_end: DateTime<Utc>,
+    /// Markdown synthesis.
+    pub content: String,
+    /// Media included in the rollup.
+    pub source_video_ids: Vec<String>,
+    /// When the rollup was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Rollup {
+    /// Build the ID for a tag/period pair.
+    pub fn make_id(tag: &str, period: RollupPeriod) -> String {
+        let slug: String = tag
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let slug = slug
+            .split('-')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        format!("rollup-{}-{}", slug, period)
+    }
+
+    /// Display title used when indexing.
+    pub fn title(&self) -> String {
+        format!("Rollup: {} ({})", self.tag, self.period)
+    }
+
+    /// Split the markdown content into (section title, text) pairs on `## ` headings.
+    pub fn sections(&self) -> Vec<(Option<String>, String)> {
+        let mut sections = Vec::new();
+        let mut title: Option<String> = None;
+        let mut body = String::new();
+
+        for line in self.content.lines() {
+            if let Some(heading) = line.strip_prefix("## ") {
+                if !body.trim().is_empty() {
+                    sections.push((title.take(), body.trim().to_string()));
+                }
+                title = Some(heading.trim().to_string());
+                body.clear();
+            } else {
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+
+        if !body.trim().is_empty() {
+            sections.push((title, body.trim().to_string()));
+        }
+
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rollup(content: &str) -> Rollup {
+        Rollup {
+            id: Rollup::make_id("lectures", RollupPeriod::Month),
+            tag: "lectures".to_string(),
+            period: RollupPeriod::Month,
+            period_start: None,
+            period_end: Utc::now(),
+            content: content.to_string(),
+            source_video_ids: vec![],
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_make_id() {
+        assert_eq!(Rollup::make_id("lectures", RollupPeriod::Month), "rollup-lectures-month");
+        assert_eq!(
+            Rollup::make_id("channel:Two Minute Papers", RollupPeriod::Week),
+            "rollup-channel-two-minute-papers-week"
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let r = rollup("# Overview\nIntro text\n\n## Themes\nA and B\n\n## Open questions\nC?");
+        let sections = r.sections();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].0, None);
+        assert!(sections[0].1.contains("Intro text"));
+        assert_eq!(sections[1].0.as_deref(), Some("Themes"));
+        assert_eq!(sections[2].1, "C?");
+    }
+
+    #[test]
+    fn test_period_parse() {
+        assert_eq!("month".parse::<RollupPeriod>().unwrap(), RollupPeriod::Month);
+        assert!("fortnight".parse::<RollupPeriod>().is_err());
+        assert!(RollupPeriod::All.start(Utc::now()).is_none());
+    }
+}
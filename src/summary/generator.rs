@@ -0,0 +1,188 @@
+//! Summary generation.
+
+use super::{ChunkSummary, MediaSummary};
+use crate::config::Prompts;
+use crate::embedding::Embedder;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{Document, SqliteVectorStore, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Generates, stores, and indexes media summaries.
+pub struct SummaryGenerator {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<SqliteVectorStore>,
+    embedder: Arc<dyn Embedder>,
+    prompts: Prompts,
+}
+
+impl SummaryGenerator {
+    /// Create a new summary generator.
+    pub fn new(vector_store: Arc<SqliteVectorStore>, embedder: Arc<dyn Embedder>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            embedder,
+            prompts: Prompts::default(),
+        }
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Summarize each chunk of a media item and then the whole item, then store and index it.
+    #[instrument(skip(self))]
+    pub async fn generate(&self, video_id: &str) -> Result<MediaSummary> {
+        let mut docs = self.vector_store.get_by_video_id(video_id).await?;
+        if docs.is_empty() {
+            return Err(LyttError::InvalidInput(format!("Media not found: {}", video_id)));
+        }
+        docs.sort_by_key(|d| d.chunk_order);
+
+        let video_title = docs[0].video_title.clone();
+        info!("Summarizing '{}' ({} chunks)", video_title, docs.len());
+
+        let mut chunks = Vec::with_capacity(docs.len());
+        for doc in &docs {
+            chunks.push(self.summarize_chunk(doc).await?);
+        }
+
+        let duration = docs.iter().map(|d| d.end_seconds).fold(0.0f64, f64::max);
+
+        let mut vars = HashMap::new();
+        vars.insert("title".to_string(), video_title.clone());
+        vars.insert("duration_minutes".to_string(), format!("{:.0}", duration / 60.0));
+        vars.insert("sections".to_string(), format_sections(&chunks));
+
+        let overview = self
+            .complete(&self.prompts.summary.system, &self.prompts.summary.user, &vars)
+            .await?;
+
+        let summary = MediaSummary {
+            video_id: video_id.to_string(),
+            video_title,
+            overview: overview.trim().to_string(),
+            chunks,
+            model: self.model.clone(),
+            created_at: Utc::now(),
+        };
+
+        self.vector_store.store_summary(&summary)?;
+        self.index(&summary, &docs).await?;
+
+        Ok(summary)
+    }
+
+    /// Index the overview as a derived document pointing back at the media's chunks.
+    async fn index(&self, summary: &MediaSummary, docs: &[Document]) -> Result<usize> {
+        let id = MediaSummary::document_id(&summary.video_id);
+        self.vector_store.delete_by_video_id(&id).await?;
+
+        let embedding = self.embedder.embed(&summary.overview).await?;
+        let provenance = docs.iter().map(|d| d.id.to_string()).collect();
+
+        let document = Document::new(
+            id,
+            summary.title(),
+            Some("Overview".to_string()),
+            summary.overview.clone(),
+            0.0,
+            0.0,
+            embedding,
+            0,
+            docs[0].source_created_at,
+        )
+        .derived_from(provenance);
+
+        self.vector_store.upsert_batch(&[document]).await
+    }
+
+    /// Summarize a single chunk.
+    async fn summarize_chunk(&self, doc: &Document) -> Result<ChunkSummary> {
+        let mut vars = HashMap::new();
+        vars.insert("title".to_string(), doc.video_title.clone());
+        vars.insert(
+            "section".to_string(),
+            doc.section_title.clone().unwrap_or_else(|| "Untitled".to_string()),
+        );
+        vars.insert("timestamp".to_string(), doc.format_timestamp());
+        vars.insert("content".to_string(), doc.content.clone());
+
+        let summary = self
+            .complete(&self.prompts.summary.chunk_system, &self.prompts.summary.chunk_user, &vars)
+            .await?;
+
+        Ok(ChunkSummary {
+            chunk_order: doc.chunk_order,
+            section_title: doc.section_title.clone(),
+            start_seconds: doc.start_seconds,
+            end_seconds: doc.end_seconds,
+            summary: summary.trim().to_string(),
+        })
+    }
+
+    /// Call the LLM with a system/user prompt pair.
+    async fn complete(&self, system: &str, user: &str, vars: &HashMap<String, String>) -> Result<String> {
+        let system = self.prompts.render_with_custom(system, vars);
+        let user = self.prompts.render_with_custom(user, vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Summary, &self.model, response.usage.as_ref());
+
+        response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))
+    }
+}
+
+/// Format chunk summaries for the overview prompt.
+fn format_sections(chunks: &[ChunkSummary]) -> String {
+    chunks
+        .iter()
+        .map(|c| match &c.section_title {
+            Some(title) => format!("- [{}] {}: {}", c.format_timestamp(), title, c.summary),
+            None => format!("- [{}] {}", c.format_timestamp(), c.summary),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,140 @@
+//! Stored per-chunk and whole-video summaries.
+//!
+//! A summary is generated once per media item and kept in the `summaries`
+//! table, so it can be shown by `list --detail` and the HTTP API without
+//! calling the LLM again. The overview is also indexed as a derived document,
+//! which gives broad questions about a video a single place to land.
+
+mod generator;
+
+pub use generator::SummaryGenerator;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Prefix for the video ID of indexed summary documents.
+pub const SUMMARY_ID_PREFIX: &str = "summary-";
+
+/// Summary of a single chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkSummary {
+    /// Order of the summarized chunk within the media.
+    pub chunk_order: i32,
+    /// Section title of the chunk, if any.
+    pub section_title: Option<String>,
+    /// Start time of the chunk in seconds.
+    pub start_seconds: f64,
+    /// End time of the chunk in seconds.
+    pub end_seconds: f64,
+    /// One or two sentence summary.
+    pub summary: String,
+}
+
+impl ChunkSummary {
+    /// Format the start time as MM:SS or HH:MM:SS.
+    pub fn format_timestamp(&self) -> String {
+        let total_seconds = self.start_seconds as u32;
+        let hours = total_seconds / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+        let secs = total_seconds % 60;
+
+        if hours > 0 {
+            format!("{:02}:{:02}:{:02}", hours, minutes, secs)
+        } else {
+            format!("{:02}:{:02}", minutes, secs)
+        }
+    }
+}
+
+/// A stored summary of one media item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaSummary {
+    /// Media the summary covers.
+    pub video_id: String,
+    /// Title of the media.
+    pub video_title: String,
+    /// Whole-video overview.
+    pub overview: String,
+    /// Per-chunk summaries, in chunk order.
+    pub chunks: Vec<ChunkSummary>,
+    /// Model that generated the summary.
+    pub model: String,
+    /// When the summary was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl MediaSummary {
+    /// Video ID used for the indexed summary document of a media item.
+    pub fn document_id(video_id: &str) -> String {
+        format!("{}{}", SUMMARY_ID_PREFIX, video_id)
+    }
+
+    /// Display title used when indexing.
+    pub fn title(&self) -> String {
+        format!("Summary: {}", self.video_title)
+    }
+
+    /// Render the summary as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# {}\n\n{}\n", self.video_title, self.overview.trim());
+
+        if !self.chunks.is_empty() {
+            output.push_str("\n## Sections\n\n");
+            for chunk in &self.chunks {
+                match &chunk.section_title {
+                    Some(title) => output.push_str(&format!(
+                        "- [{}] **{}**: {}\n",
+                        chunk.format_timestamp(),
+                        title,
+                        chunk.summary
+                    )),
+                    None => output.push_str(&format!(
+                        "- [{}] {}\n",
+                        chunk.format_timestamp(),
+                        chunk.summary
+                    )),
+                }
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        let summary = MediaSummary {
+            video_id: "abc123".to_string(),
+            video_title: "Lecture 1".to_string(),
+            overview: "An introduction to binary numbers.".to_string(),
+            chunks: vec![
+                ChunkSummary {
+                    chunk_order: 0,
+                    section_title: Some("Binary".to_string()),
+                    start_seconds: 45.0,
+                    end_seconds: 220.0,
+                    summary: "How binary works.".to_string(),
+                },
+                ChunkSummary {
+                    chunk_order: 1,
+                    section_title: None,
+                    start_seconds: 3725.0,
+                    end_seconds: 3800.0,
+                    summary: "Wrap-up.".to_string(),
+                },
+            ],
+            model: "gpt-4o-mini".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let markdown = summary.to_markdown();
+        assert!(markdown.starts_with("# Lecture 1\n\nAn introduction"));
+        assert!(markdown.contains("- [00:45] **Binary**: How binary works."));
+        assert!(markdown.contains("- [01:02:05] Wrap-up."));
+        assert_eq!(MediaSummary::document_id("abc123"), "summary-abc123");
+    }
+}
@@ -0,0 +1,115 @@
+//! Mock providers for tests.
+
+use crate::embedding::Embedder;
+use crate::error::Result;
+use crate::transcription::{Transcriber, Transcript};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Transcriber that returns a fixed transcript for any audio.
+pub struct MockTranscriber {
+    transcript: Transcript,
+    calls: AtomicUsize,
+}
+
+impl MockTranscriber {
+    /// Return `transcript` for every file.
+    pub fn new(transcript: Transcript) -> Self {
+        Self {
+            transcript,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of files transcribed so far.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Transcriber for MockTranscriber {
+    async fn transcribe(&self, _audio_path: &Path) -> Result<Transcript> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(self.transcript.clone())
+    }
+
+    async fn transcribe_with_language(&self, audio_path: &Path, _language: &str) -> Result<Transcript> {
+        self.transcribe(audio_path).await
+    }
+}
+
+/// Deterministic bag-of-words embedder: each lowercased word is hashed into
+/// one of `dimensions` buckets and the vector is L2-normalised. Texts sharing
+/// words score higher, which is enough for retrieval tests.
+pub struct MockEmbedder {
+    dimensions: usize,
+}
+
+impl MockEmbedder {
+    /// Create an embedder with the given number of dimensions.
+    pub fn new(dimensions: usize) -> Self {
+        Self { dimensions }
+    }
+
+    fn vector(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 2)
+            .map(|w| w.to_lowercase())
+        {
+            // FNV-1a, so buckets are stable across runs and platforms
+            let hash = word
+                .bytes()
+                .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+            vector[(hash % self.dimensions as u64) as usize] += 1.0;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+}
+
+impl Default for MockEmbedder {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+#[async_trait]
+impl Embedder for MockEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.vector(text))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| self.vector(t)).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::cosine_similarity;
+
+    #[tokio::test]
+    async fn test_mock_embedder() {
+        let embedder = MockEmbedder::default();
+        let query = embedder.embed("entropy of a coin").await.unwrap();
+        let related = embedder.embed("A fair coin has an entropy of one bit").await.unwrap();
+        let unrelated = embedder.embed("Huffman coding gives frequent symbols short codes").await.unwrap();
+
+        assert_eq!(query.len(), 64);
+        assert_eq!(query, embedder.embed("entropy of a coin").await.unwrap());
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+}
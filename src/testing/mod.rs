@@ -0,0 +1,94 @@
+//! Test support: bundled fixtures, mock providers and golden snapshots.
+//!
+//! Compiled for unit tests and with the `e2e` feature, which also enables the
+//! pipeline tests in `tests/`:
+//!
+//! ```text
+//! cargo test --features e2e
+//! LYTT_UPDATE_SNAPSHOTS=1 cargo test --features e2e   # accept changed output
+//! ```
+//!
+//! The mocks stand in for the paid providers (speech-to-text and embeddings),
+//! so the orchestrator, chunkers, vector store and exporters run for real
+//! without network access. Snapshots live in `tests/snapshots/`.
+
+mod mock;
+mod snapshot;
+
+pub use mock::{MockEmbedder, MockTranscriber};
+pub use snapshot::assert_snapshot;
+
+use crate::config::{Prompts, Settings};
+use crate::error::Result;
+use crate::orchestrator::Orchestrator;
+use crate::transcription::Transcript;
+use crate::vector_store::{Document, SqliteVectorStore};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Path of a file in `tests/fixtures/`.
+pub fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+}
+
+/// Load a transcript fixture (`tests/fixtures/<name>.json`).
+pub fn transcript_fixture(name: &str) -> Transcript {
+    let path = fixture_path(&format!("{}.json", name));
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("Invalid transcript fixture {}: {}", path.display(), e))
+}
+
+/// Settings that keep all state under `dir` and need no LLM calls:
+/// temporal chunking, no automatic titles, no glossary vocabulary.
+pub fn test_settings(dir: &Path) -> Settings {
+    let mut settings = Settings::default();
+    settings.general.data_dir = dir.display().to_string();
+    settings.general.temp_dir = dir.join("tmp").display().to_string();
+    settings.general.cache_dir = dir.join("cache").display().to_string();
+    settings.vector_store.sqlite_path = dir.join("lytt.db").display().to_string();
+    settings.chunking.strategy = "temporal".to_string();
+    settings.chunking.target_chunk_seconds = 90;
+    settings.chunking.min_chunk_seconds = 30;
+    settings.chunking.max_chunk_seconds = 180;
+    settings.titles.auto = false;
+    settings.transcription.glossary_vocabulary = false;
+    settings
+}
+
+/// An orchestrator over an on-disk store in `dir`, transcribing with
+/// `transcriber` and embedding with a [`MockEmbedder`].
+pub fn test_orchestrator(dir: &Path, transcriber: Arc<MockTranscriber>) -> Result<Orchestrator> {
+    let settings = test_settings(dir);
+    let vector_store = Arc::new(SqliteVectorStore::new(&settings.sqlite_path())?);
+    Orchestrator::with_components(
+        settings,
+        Prompts::default(),
+        transcriber,
+        Arc::new(MockEmbedder::default()),
+        vector_store,
+    )
+}
+
+/// Render indexed chunks for a snapshot, in chunk order, without IDs or
+/// embeddings (which differ between runs).
+pub fn render_chunks(documents: &[Document]) -> String {
+    let mut sorted: Vec<&Document> = documents.iter().collect();
+    sorted.sort_by_key(|d| d.chunk_order);
+
+    sorted
+        .iter()
+        .map(|d| {
+            format!(
+                "#{} [{:.1}-{:.1}] {}\n{}\n",
+                d.chunk_order,
+                d.start_seconds,
+                d.end_seconds,
+                d.section_title.as_deref().unwrap_or("-"),
+                d.content
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
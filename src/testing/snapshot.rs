@@ -0,0 +1,98 @@
+//! Golden snapshot comparisons.
+
+use std::path::{Path, PathBuf};
+
+/// Set to `1` to write the actual output over stored snapshots.
+const UPDATE_ENV: &str = "LYTT_UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", name))
+}
+
+/// Compare `actual` with `tests/snapshots/<name>.snap`.
+///
+/// A missing snapshot is written and the test fails, so new snapshots are
+/// reviewed before they are committed. With `LYTT_UPDATE_SNAPSHOTS=1`, the
+/// snapshot is overwritten and the test passes.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    let update = std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1");
+
+    let expected = match std::fs::read_to_string(&path) {
+        Ok(expected) if !update => expected,
+        result => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("Failed to create snapshot directory");
+            }
+            std::fs::write(&path, actual).expect("Failed to write snapshot");
+            if update {
+                return;
+            }
+            panic!(
+                "Snapshot '{}' did not exist ({}); wrote it to {}. Review it and re-run.",
+                name,
+                result.err().map(|e| e.to_string()).unwrap_or_default(),
+                path.display()
+            );
+        }
+    };
+
+    if let Some(diff) = diff_lines(&expected, actual) {
+        panic!(
+            "Snapshot '{}' does not match {}:\n{}\nRe-run with {}=1 to accept the new output.",
+            name,
+            path.display(),
+            diff,
+            UPDATE_ENV
+        );
+    }
+}
+
+/// Line diff of the first differences, or `None` if the texts are equal.
+fn diff_lines(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut diff = Vec::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        let (old, new) = (expected.get(i), actual.get(i));
+        if old != new {
+            diff.push(format!("line {}:", i + 1));
+            if let Some(old) = old {
+                diff.push(format!("- {}", old));
+            }
+            if let Some(new) = new {
+                diff.push(format!("+ {}", new));
+            }
+        }
+        if diff.len() >= 30 {
+            diff.push("...".to_string());
+            break;
+        }
+    }
+
+    if diff.is_empty() {
+        // Only trailing newlines differ
+        diff.push("(trailing newline differs)".to_string());
+    }
+    Some(diff.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        assert!(diff_lines("a\nb\n", "a\nb\n").is_none());
+        assert_eq!(diff_lines("a\nb", "a\nc\nd").unwrap(), "line 2:\n- b\n+ c\nline 3:\n+ d");
+        assert_eq!(diff_lines("a\n", "a").unwrap(), "(trailing newline differs)");
+    }
+}
@@ -0,0 +1,295 @@
+//! Timelines of how a topic evolved across the library.
+//!
+//! The excerpts most relevant to a topic are retrieved from across the
+//! library and numbered in publication order (then by timestamp). The model
+//! groups them into dated events, citing excerpt numbers; each event is dated
+//! by its earliest cited excerpt rather than by the model, and events are put
+//! in date order here.
+
+use crate::config::Prompts;
+use crate::consensus::{format_excerpts, select_excerpts, Excerpt};
+use crate::embedding::Embedder;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{DerivedFilter, SearchFilter, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Minimum similarity for an excerpt to be considered on-topic.
+const MIN_SCORE: f32 = 0.3;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct TimelineResponse {
+    #[serde(default)]
+    events: Vec<RawEvent>,
+}
+
+#[derive(Deserialize)]
+struct RawEvent {
+    headline: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    sources: Vec<usize>,
+}
+
+/// One dated development in a timeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// Publication date of the earliest excerpt cited.
+    pub date: DateTime<Utc>,
+    /// What happened, in a few words.
+    pub headline: String,
+    /// One or two sentences on the development.
+    pub summary: String,
+    /// Supporting excerpts, oldest first.
+    pub citations: Vec<Excerpt>,
+}
+
+/// How a topic evolved across the library.
+#[derive(Debug, Clone, Serialize)]
+pub struct Timeline {
+    /// The topic traced.
+    pub topic: String,
+    /// Events, oldest first.
+    pub events: Vec<TimelineEvent>,
+    /// Number of distinct media items the excerpts came from.
+    pub media_count: usize,
+    /// When the timeline was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Timeline {
+    /// Render the timeline as Markdown with a citation list under each event.
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Timeline: {}\n\n", self.topic);
+        output.push_str(&format!(
+            "*{} sources, generated {}*\n",
+            self.media_count,
+            self.created_at.format("%Y-%m-%d")
+        ));
+
+        if self.events.is_empty() {
+            output.push_str("\nNo developments found.\n");
+            return output;
+        }
+
+        for event in &self.events {
+            output.push_str(&format!("\n## {} - {}\n\n", event.date.format("%Y-%m-%d"), event.headline));
+            if !event.summary.is_empty() {
+                output.push_str(&format!("{}\n\n", event.summary));
+            }
+            for excerpt in &event.citations {
+                output.push_str(&format!("- {}\n", excerpt.citation()));
+            }
+        }
+
+        output
+    }
+}
+
+/// Builds timelines of how a topic evolved across the library.
+pub struct TimelineBuilder {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<dyn VectorStore>,
+    embedder: Arc<dyn Embedder>,
+    prompts: Prompts,
+    filter: SearchFilter,
+    max_excerpts: usize,
+    per_media: usize,
+}
+
+impl TimelineBuilder {
+    /// Create a new timeline builder.
+    pub fn new(vector_store: Arc<dyn VectorStore>, embedder: Arc<dyn Embedder>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            embedder,
+            prompts: Prompts::default(),
+            filter: SearchFilter::default().with_derived(DerivedFilter::Exclude),
+            max_excerpts: 40,
+            per_media: 4,
+        }
+    }
+
+    /// Set the maximum number of excerpts sent to the model.
+    pub fn with_max_excerpts(mut self, max_excerpts: usize) -> Self {
+        self.max_excerpts = max_excerpts;
+        self
+    }
+
+    /// Restrict retrieval (e.g. to recent media). Derived documents are
+    /// always excluded, since they aren't dated by when things were said.
+    pub fn with_filter(mut self, filter: SearchFilter) -> Self {
+        self.filter = filter.with_derived(DerivedFilter::Exclude);
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Trace how `topic` evolved across the indexed media.
+    #[instrument(skip(self))]
+    pub async fn build(&self, topic: &str) -> Result<Timeline> {
+        let embedding = self.embedder.embed(topic).await?;
+        let results = self
+            .vector_store
+            .search_filtered(&embedding, self.max_excerpts * 3, MIN_SCORE, &self.filter)
+            .await?;
+
+        let excerpts = select_excerpts(results, self.max_excerpts, self.per_media);
+        if excerpts.is_empty() {
+            return Err(LyttError::InvalidInput(format!("Nothing in the library matches '{}'", topic)));
+        }
+        let media_count = excerpts.iter().map(|e| &e.video_id).collect::<HashSet<_>>().len();
+
+        info!("Ordering {} excerpts from {} sources on '{}'", excerpts.len(), media_count, topic);
+
+        let mut vars = HashMap::new();
+        vars.insert("topic".to_string(), topic.to_string());
+        vars.insert("excerpts".to_string(), format_excerpts(&excerpts));
+
+        let system = self.prompts.render_with_custom(&self.prompts.timeline.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.timeline.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.2)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        Ok(Timeline {
+            topic: topic.to_string(),
+            events: parse_events(content, &excerpts)?,
+            media_count,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Parse the model's JSON, resolving 1-based source numbers to excerpts and
+/// dating each event by its earliest source. Events without a valid source
+/// are dropped.
+fn parse_events(content: &str, excerpts: &[Excerpt]) -> Result<Vec<TimelineEvent>> {
+    let parsed: TimelineResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid timeline JSON: {}", e)))?;
+
+    let mut events: Vec<TimelineEvent> = parsed
+        .events
+        .into_iter()
+        .filter_map(|raw| {
+            let mut sources = raw.sources;
+            sources.sort_unstable();
+            sources.dedup();
+            // Excerpts are numbered oldest first, so the first is the earliest
+            let citations: Vec<Excerpt> = sources
+                .into_iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| excerpts.get(i)).cloned())
+                .collect();
+            let headline = raw.headline.trim().to_string();
+            let date = citations.first()?.date;
+            (!headline.is_empty()).then(|| TimelineEvent {
+                date,
+                headline,
+                summary: raw.summary.trim().to_string(),
+                citations,
+            })
+        })
+        .collect();
+
+    events.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then(a.citations[0].start_seconds.total_cmp(&b.citations[0].start_seconds))
+    });
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_events() {
+        let excerpt = |title: &str, year: i32| Excerpt {
+            video_id: title.to_lowercase(),
+            video_title: title.to_string(),
+            timestamp: "01:00".to_string(),
+            start_seconds: 60.0,
+            date: Utc.with_ymd_and_hms(year, 3, 1, 0, 0, 0).unwrap(),
+            content: "...".to_string(),
+        };
+        let excerpts = vec![excerpt("Ep 1", 2021), excerpt("Ep 5", 2022), excerpt("Ep 9", 2024)];
+
+        // Out of order, one event without a valid source
+        let events = parse_events(
+            r#"{"events": [
+                {"headline": "Reversal", "summary": "They now advise against it.", "sources": [3]},
+                {"headline": "First mention", "summary": "Introduced as an experiment.", "sources": [2, 1]},
+                {"headline": "Uncited", "sources": [9]}
+            ]}"#,
+            &excerpts,
+        )
+        .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].headline, "First mention");
+        assert_eq!(events[0].date, excerpts[0].date);
+        assert_eq!(events[0].citations.len(), 2);
+
+        let timeline = Timeline {
+            topic: "remote work".to_string(),
+            events,
+            media_count: 3,
+            created_at: Utc::now(),
+        };
+        let markdown = timeline.to_markdown();
+        assert!(markdown.contains("## 2021-03-01 - First mention\n\nIntroduced as an experiment.\n\n- Ep 1 (2021-03-01) @ 01:00\n"));
+        assert!(parse_events("not json", &excerpts).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Title generation.
+
+use super::GeneratedTitle;
+use crate::config::Prompts;
+use crate::error::{LyttError, Result};
+use crate::llm::ChatClient;
+use crate::transcription::Transcript;
+use crate::usage::{self, UsageStage};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{info, instrument};
+
+/// Most tags kept from a response.
+const MAX_TAGS: usize = 5;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct TitleResponse {
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Proposes titles and tags from the start of a transcript.
+pub struct TitleGenerator {
+    client: ChatClient,
+    model: String,
+    excerpt_seconds: f64,
+    prompts: Prompts,
+}
+
+impl TitleGenerator {
+    /// Create a new title generator.
+    pub fn new(model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            excerpt_seconds: 180.0,
+            prompts: Prompts::default(),
+        }
+    }
+
+    /// Set how much of the transcript (from the start) the model sees.
+    pub fn with_excerpt_seconds(mut self, seconds: f64) -> Self {
+        self.excerpt_seconds = seconds;
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Generate a title and tags for a media item from its transcript.
+    #[instrument(skip(self, transcript))]
+    pub async fn generate(
+        &self,
+        video_id: &str,
+        original_title: &str,
+        transcript: &Transcript,
+    ) -> Result<GeneratedTitle> {
+        let excerpt = transcript_excerpt(transcript, self.excerpt_seconds);
+        if excerpt.trim().is_empty() {
+            return Err(LyttError::InvalidInput(format!(
+                "No transcript to title {}",
+                video_id
+            )));
+        }
+
+        info!("Generating title for '{}'", original_title);
+
+        let mut vars = HashMap::new();
+        vars.insert("original_title".to_string(), original_title.to_string());
+        vars.insert("transcript".to_string(), excerpt);
+
+        let system = self.prompts.render_with_custom(&self.prompts.titles.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.titles.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Title, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        let (title, tags) = parse_title(content)?;
+
+        Ok(GeneratedTitle {
+            video_id: video_id.to_string(),
+            title,
+            original_title: original_title.to_string(),
+            tags,
+            model: self.model.clone(),
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Text of the segments that start within the first `seconds` of the transcript.
+fn transcript_excerpt(transcript: &Transcript, seconds: f64) -> String {
+    transcript
+        .segments
+        .iter()
+        .take_while(|s| s.start_seconds < seconds)
+        .map(|s| s.text.trim())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse the `{"title": ..., "tags": [...]}` response, normalizing tags.
+fn parse_title(content: &str) -> Result<(String, Vec<String>)> {
+    let parsed: TitleResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid title JSON: {}", e)))?;
+
+    let title = parsed.title.trim().trim_matches('"').trim().to_string();
+    if title.is_empty() {
+        return Err(LyttError::Rag("Model returned an empty title".to_string()));
+    }
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in parsed.tags {
+        let tag = tag.trim().to_lowercase().replace(' ', "-");
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_TAGS);
+
+    Ok((title, tags))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptSegment;
+
+    #[test]
+    fn test_transcript_excerpt() {
+        let transcript = Transcript::new(
+            "rec".to_string(),
+            vec![
+                TranscriptSegment::new(0.0, 60.0, "Welcome to the standup.".to_string()),
+                TranscriptSegment::new(60.0, 200.0, "First, the release.".to_string()),
+                TranscriptSegment::new(200.0, 260.0, "Not included.".to_string()),
+            ],
+        );
+
+        assert_eq!(
+            transcript_excerpt(&transcript, 180.0),
+            "Welcome to the standup. First, the release."
+        );
+    }
+
+    #[test]
+    fn test_parse_title() {
+        let (title, tags) = parse_title(
+            r#"{"title": " \"Weekly Standup: Release Planning\" ", "tags": ["Meetings", "release planning", "meetings", ""]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(title, "Weekly Standup: Release Planning");
+        assert_eq!(tags, vec!["meetings", "release-planning"]);
+        assert!(parse_title(r#"{"title": "  "}"#).is_err());
+        assert!(parse_title("not json").is_err());
+    }
+}
@@ -0,0 +1,42 @@
+//! Automatic titles for untitled media.
+//!
+//! Local files without a title tag are named after the file (e.g. "REC_0043").
+//! For those, a cheap LLM pass over the opening minutes of the transcript
+//! proposes a descriptive title and a few tags. The original name and the
+//! model are kept so the title's provenance can be shown.
+
+mod generator;
+
+pub use generator::TitleGenerator;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A title generated for a media item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedTitle {
+    /// Media the title belongs to.
+    pub video_id: String,
+    /// Generated title.
+    pub title: String,
+    /// Title the media had before (usually the file name).
+    pub original_title: String,
+    /// Suggested tags.
+    pub tags: Vec<String>,
+    /// Model that generated the title.
+    pub model: String,
+    /// When the title was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+impl GeneratedTitle {
+    /// One-line description of where the title came from.
+    pub fn provenance(&self) -> String {
+        format!(
+            "Title generated by {} from \"{}\" on {}",
+            self.model,
+            self.original_title,
+            self.created_at.format("%Y-%m-%d")
+        )
+    }
+}
@@ -0,0 +1,125 @@
+//! Spherical k-means over embeddings.
+
+/// Result of clustering a set of vectors.
+#[derive(Debug, Clone)]
+pub struct Clustering {
+    /// Unit-length centroid of each cluster.
+    pub centroids: Vec<Vec<f32>>,
+    /// Cluster index of each input vector.
+    pub assignments: Vec<usize>,
+}
+
+/// A reasonable number of clusters for `n` documents (√(n/2), 2–30).
+pub fn default_k(n: usize) -> usize {
+    ((n as f64 / 2.0).sqrt().round() as usize).clamp(2, 30)
+}
+
+/// Cluster vectors by cosine similarity into at most `k` clusters.
+///
+/// Initial centroids are chosen deterministically (the first vector, then
+/// repeatedly the vector least similar to any chosen centroid), so the same
+/// library always gives the same topics.
+pub fn kmeans(vectors: &[Vec<f32>], k: usize, max_iterations: usize) -> Clustering {
+    let vectors: Vec<Vec<f32>> = vectors.iter().map(|v| normalize(v)).collect();
+    let k = k.min(vectors.len()).max(1);
+    if vectors.is_empty() {
+        return Clustering { centroids: Vec::new(), assignments: Vec::new() };
+    }
+
+    let mut centroids = vec![vectors[0].clone()];
+    let mut best: Vec<f32> = vectors.iter().map(|v| dot(v, &centroids[0])).collect();
+    while centroids.len() < k {
+        let (next, _) = best
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap_or((0, &0.0));
+        let centroid = vectors[next].clone();
+        for (score, v) in best.iter_mut().zip(&vectors) {
+            *score = score.max(dot(v, &centroid));
+        }
+        centroids.push(centroid);
+    }
+
+    let mut assignments = vec![usize::MAX; vectors.len()];
+    for _ in 0..max_iterations.max(1) {
+        let mut changed = false;
+        for (assignment, v) in assignments.iter_mut().zip(&vectors) {
+            let nearest = nearest_centroid(v, &centroids);
+            if *assignment != nearest {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let dims = vectors[0].len();
+        let mut sums = vec![vec![0.0f32; dims]; centroids.len()];
+        for (v, &cluster) in vectors.iter().zip(&assignments) {
+            for (s, x) in sums[cluster].iter_mut().zip(v) {
+                *s += x;
+            }
+        }
+        for (centroid, sum) in centroids.iter_mut().zip(sums) {
+            // Empty clusters keep their previous centroid
+            if sum.iter().any(|x| *x != 0.0) {
+                *centroid = normalize(&sum);
+            }
+        }
+    }
+
+    Clustering { centroids, assignments }
+}
+
+/// Index of the centroid most similar to `vector`.
+pub fn nearest_centroid(vector: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(vector, c)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = dot(v, v).sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmeans() {
+        let vectors = vec![
+            vec![1.0, 0.1, 0.0],
+            vec![0.0, 1.0, 0.1],
+            vec![0.9, 0.0, 0.1],
+            vec![0.1, 0.9, 0.0],
+            vec![1.0, 0.0, 0.0],
+        ];
+        let clustering = kmeans(&vectors, 2, 20);
+
+        assert_eq!(clustering.centroids.len(), 2);
+        let a = clustering.assignments[0];
+        let b = clustering.assignments[1];
+        assert_ne!(a, b);
+        assert_eq!(clustering.assignments, vec![a, b, a, b, a]);
+        assert_eq!(nearest_centroid(&normalize(&[0.0, 2.0, 0.0]), &clustering.centroids), b);
+
+        assert_eq!(kmeans(&vectors, 10, 20).centroids.len(), 5);
+        assert_eq!(default_k(8), 2);
+        assert_eq!(default_k(5000), 30);
+    }
+}
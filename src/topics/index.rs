@@ -0,0 +1,84 @@
+//! Keeping persisted topic assignments up to date.
+
+use super::nearest_centroid;
+use crate::error::Result;
+use crate::vector_store::{DerivedIndex, Document, IndexKind, SqliteVectorStore};
+use rusqlite::{params, Connection};
+
+/// Assigns newly indexed documents to the nearest stored topic.
+///
+/// Topics themselves are only recomputed by `lytt topics`; this index keeps
+/// `document_topics` covering every document in between. Without stored
+/// topics it does nothing.
+#[derive(Debug, Default)]
+pub struct TopicIndex;
+
+impl TopicIndex {
+    /// Create a new topic index.
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn centroids(conn: &Connection) -> Result<(Vec<i64>, Vec<Vec<f32>>)> {
+        let mut stmt = conn.prepare("SELECT id, centroid FROM topics ORDER BY id")?;
+        let rows = stmt.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let bytes: Vec<u8> = row.get(1)?;
+            Ok((id, SqliteVectorStore::bytes_to_embedding(&bytes)))
+        })?;
+
+        let mut ids = Vec::new();
+        let mut centroids = Vec::new();
+        for row in rows {
+            let (id, centroid) = row?;
+            ids.push(id);
+            centroids.push(centroid);
+        }
+        Ok((ids, centroids))
+    }
+}
+
+impl DerivedIndex for TopicIndex {
+    fn kind(&self) -> IndexKind {
+        IndexKind::Topics
+    }
+
+    fn clear(&self, conn: &Connection) -> Result<()> {
+        conn.execute("DELETE FROM document_topics", [])?;
+        Ok(())
+    }
+
+    fn on_upsert(&self, conn: &Connection, docs: &[Document]) -> Result<()> {
+        let (ids, centroids) = Self::centroids(conn)?;
+        if centroids.is_empty() {
+            return Ok(());
+        }
+
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO document_topics (document_id, video_id, topic_id) VALUES (?1, ?2, ?3)",
+        )?;
+        for doc in docs.iter().filter(|d| !d.embedding.is_empty()) {
+            let topic_id = ids[nearest_centroid(&doc.embedding, &centroids)];
+            stmt.execute(params![doc.id.to_string(), doc.video_id, topic_id])?;
+        }
+        Ok(())
+    }
+
+    fn on_delete(&self, conn: &Connection, video_id: &str) -> Result<()> {
+        conn.execute("DELETE FROM document_topics WHERE video_id = ?1", params![video_id])?;
+        Ok(())
+    }
+
+    fn on_delete_documents(&self, conn: &Connection, ids: &[String]) -> Result<()> {
+        let mut stmt = conn.prepare("DELETE FROM document_topics WHERE document_id = ?1")?;
+        for id in ids {
+            stmt.execute(params![id])?;
+        }
+        Ok(())
+    }
+
+    fn entry_count(&self, conn: &Connection) -> Result<usize> {
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM document_topics", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+}
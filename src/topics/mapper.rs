@@ -0,0 +1,325 @@
+//! Building and labelling the topic map.
+
+use super::{default_k, kmeans, nearest_centroid, Topic, TopicAssignment, TopicMap, TopicMedia, TopicModel};
+use crate::config::Prompts;
+use crate::error::{LyttError, Result};
+use crate::keywords::count_keywords;
+use crate::llm::ChatClient;
+use crate::usage::{self, UsageStage};
+use crate::vector_store::{DocType, Document, VectorStore};
+use async_openai::types::{
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// K-means iterations before giving up on convergence.
+const MAX_ITERATIONS: usize = 50;
+
+/// Central chunks shown to the model per cluster.
+const SAMPLES_PER_TOPIC: usize = 5;
+
+/// Characters kept of each sample chunk.
+const SAMPLE_CHARS: usize = 400;
+
+/// Shape of the model's structured output.
+#[derive(Deserialize)]
+struct LabelResponse {
+    #[serde(default)]
+    topics: Vec<RawLabel>,
+}
+
+#[derive(Deserialize)]
+struct RawLabel {
+    cluster: usize,
+    label: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Clusters the library into topics and labels them.
+pub struct TopicMapper {
+    client: ChatClient,
+    model: String,
+    vector_store: Arc<dyn VectorStore>,
+    prompts: Prompts,
+    clusters: Option<usize>,
+    media_per_topic: usize,
+}
+
+impl TopicMapper {
+    /// Create a new topic mapper.
+    pub fn new(vector_store: Arc<dyn VectorStore>, model: &str) -> Self {
+        Self {
+            client: ChatClient::openai(),
+            model: model.to_string(),
+            vector_store,
+            prompts: Prompts::default(),
+            clusters: None,
+            media_per_topic: 5,
+        }
+    }
+
+    /// Set the number of topics (default: based on library size).
+    pub fn with_clusters(mut self, clusters: Option<usize>) -> Self {
+        self.clusters = clusters;
+        self
+    }
+
+    /// Set how many media items are listed per topic.
+    pub fn with_media_per_topic(mut self, media_per_topic: usize) -> Self {
+        self.media_per_topic = media_per_topic;
+        self
+    }
+
+    /// Set custom prompts (with user-defined variables).
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Set the chat-completion client (e.g. an Anthropic backend).
+    pub fn with_client(mut self, client: ChatClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Cluster every transcript chunk in the library and label the topics.
+    #[instrument(skip(self))]
+    pub async fn map(&self) -> Result<TopicModel> {
+        let mut documents = Vec::new();
+        for video in self.vector_store.list_videos().await? {
+            documents.extend(self.vector_store.get_by_video_id(&video.video_id).await?);
+        }
+        let (sources, derived): (Vec<Document>, Vec<Document>) = documents
+            .into_iter()
+            .filter(|d| !d.embedding.is_empty())
+            .partition(|d| d.doc_type == DocType::Source);
+
+        if sources.len() < 2 {
+            return Err(LyttError::InvalidInput(format!(
+                "Found {} indexed chunk(s); at least two are needed to find topics",
+                sources.len()
+            )));
+        }
+
+        let k = self.clusters.unwrap_or_else(|| default_k(sources.len()));
+        info!("Clustering {} chunks into {} topics", sources.len(), k);
+
+        let embeddings: Vec<Vec<f32>> = sources.iter().map(|d| d.embedding.clone()).collect();
+        let clustering = kmeans(&embeddings, k, MAX_ITERATIONS);
+
+        // Number topics by size, largest first, dropping empty clusters
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); clustering.centroids.len()];
+        for (i, &cluster) in clustering.assignments.iter().enumerate() {
+            members[cluster].push(i);
+        }
+        let mut order: Vec<usize> = (0..members.len()).filter(|&c| !members[c].is_empty()).collect();
+        order.sort_by(|a, b| members[*b].len().cmp(&members[*a].len()).then(a.cmp(b)));
+
+        let samples: Vec<String> = order
+            .iter()
+            .enumerate()
+            .map(|(n, &c)| {
+                describe_cluster(n + 1, &members[c], &sources, &embeddings, &clustering.centroids[c])
+            })
+            .collect();
+        let labels = self.label(&samples).await?;
+
+        let mut topics = Vec::with_capacity(order.len());
+        let mut centroids = Vec::with_capacity(order.len());
+        let mut assignments = Vec::with_capacity(sources.len() + derived.len());
+        let mut topic_of_cluster = HashMap::new();
+
+        for (n, &c) in order.iter().enumerate() {
+            let id = (n + 1) as i64;
+            topic_of_cluster.insert(c, id);
+            let docs: Vec<&Document> = members[c].iter().map(|&i| &sources[i]).collect();
+            let (label, description) = labels
+                .get(&(n + 1))
+                .cloned()
+                .unwrap_or_else(|| (fallback_label(&docs, id), String::new()));
+
+            topics.push(Topic {
+                id,
+                label,
+                description,
+                chunks: docs.len(),
+                media: top_media(&docs, self.media_per_topic),
+            });
+            centroids.push((id, clustering.centroids[c].clone()));
+        }
+
+        for (doc, &cluster) in sources.iter().zip(&clustering.assignments) {
+            assignments.push(TopicAssignment {
+                document_id: doc.id.to_string(),
+                video_id: doc.video_id.clone(),
+                topic_id: topic_of_cluster[&cluster],
+            });
+        }
+        // Derived documents (summaries, rollups) join their nearest topic
+        for doc in &derived {
+            let cluster = nearest_centroid(&doc.embedding, &clustering.centroids);
+            if let Some(&topic_id) = topic_of_cluster.get(&cluster) {
+                assignments.push(TopicAssignment {
+                    document_id: doc.id.to_string(),
+                    video_id: doc.video_id.clone(),
+                    topic_id,
+                });
+            }
+        }
+
+        let media_count = sources
+            .iter()
+            .map(|d| d.video_id.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+
+        Ok(TopicModel {
+            map: TopicMap {
+                topics,
+                document_count: sources.len(),
+                media_count,
+                model: self.model.clone(),
+                created_at: Utc::now(),
+            },
+            centroids,
+            assignments,
+        })
+    }
+
+    /// Ask the model for a label and description of each numbered cluster.
+    async fn label(&self, clusters: &[String]) -> Result<HashMap<usize, (String, String)>> {
+        let mut vars = HashMap::new();
+        vars.insert("clusters".to_string(), clusters.join("\n\n"));
+
+        let system = self.prompts.render_with_custom(&self.prompts.topics.system, &vars);
+        let user = self.prompts.render_with_custom(&self.prompts.topics.user, &vars);
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user)
+                    .build()
+                    .map_err(|e| LyttError::Rag(e.to_string()))?
+                    .into(),
+            ])
+            .temperature(0.3)
+            .response_format(ResponseFormat::JsonObject)
+            .build()
+            .map_err(|e| LyttError::Rag(e.to_string()))?;
+
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Rag, &self.model, response.usage.as_ref());
+
+        let content = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .ok_or_else(|| LyttError::Rag("Empty response from LLM".to_string()))?;
+
+        parse_labels(content)
+    }
+}
+
+/// Describe a cluster for the labelling prompt: its size, media titles and
+/// the chunks closest to its centroid.
+fn describe_cluster(
+    number: usize,
+    members: &[usize],
+    sources: &[Document],
+    embeddings: &[Vec<f32>],
+    centroid: &[f32],
+) -> String {
+    let docs: Vec<&Document> = members.iter().map(|&i| &sources[i]).collect();
+    let titles: Vec<String> = top_media(&docs, 5).into_iter().map(|m| m.video_title).collect();
+
+    let mut central: Vec<(usize, f32)> = members
+        .iter()
+        .map(|&i| (i, embeddings[i].iter().zip(centroid).map(|(a, b)| a * b).sum()))
+        .collect();
+    central.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut out = format!(
+        "Cluster {} ({} chunks from {} media)\nMedia: {}\nSamples:",
+        number,
+        members.len(),
+        titles.len(),
+        titles.join("; ")
+    );
+    for (i, _) in central.into_iter().take(SAMPLES_PER_TOPIC) {
+        let content: String = sources[i].content.chars().take(SAMPLE_CHARS).collect();
+        out.push_str(&format!("\n- {}", content.replace('\n', " ")));
+    }
+    out
+}
+
+/// Media with the most chunks among `docs`, most first.
+fn top_media(docs: &[&Document], limit: usize) -> Vec<TopicMedia> {
+    let mut counts: HashMap<&str, (&str, usize)> = HashMap::new();
+    for doc in docs {
+        counts.entry(&doc.video_id).or_insert((&doc.video_title, 0)).1 += 1;
+    }
+    let mut media: Vec<TopicMedia> = counts
+        .into_iter()
+        .map(|(video_id, (video_title, chunks))| TopicMedia {
+            video_id: video_id.to_string(),
+            video_title: video_title.to_string(),
+            chunks,
+        })
+        .collect();
+    media.sort_by(|a, b| b.chunks.cmp(&a.chunks).then_with(|| a.video_title.cmp(&b.video_title)));
+    media.truncate(limit);
+    media
+}
+
+/// Label from the cluster's most common keywords, for clusters the model skipped.
+fn fallback_label(docs: &[&Document], id: i64) -> String {
+    let keywords = count_keywords(docs.iter().map(|d| d.keywords.as_slice()), 3);
+    if keywords.is_empty() {
+        return format!("Topic {}", id);
+    }
+    keywords.into_iter().map(|k| k.keyword).collect::<Vec<_>>().join(", ")
+}
+
+/// Parse the model's JSON into labels keyed by cluster number.
+fn parse_labels(content: &str) -> Result<HashMap<usize, (String, String)>> {
+    let parsed: LabelResponse = serde_json::from_str(content)
+        .map_err(|e| LyttError::Rag(format!("Invalid topic label JSON: {}", e)))?;
+
+    Ok(parsed
+        .topics
+        .into_iter()
+        .filter(|t| !t.label.trim().is_empty())
+        .map(|t| (t.cluster, (t.label.trim().to_string(), t.description.trim().to_string())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        let content = r#"{"topics": [
+            {"cluster": 1, "label": " Ownership ", "description": "Borrowing and lifetimes."},
+            {"cluster": 2, "label": ""}
+        ]}"#;
+        let labels = parse_labels(content).unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[&1], ("Ownership".to_string(), "Borrowing and lifetimes.".to_string()));
+
+        assert!(parse_labels("nope").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Topic clustering and a map of the library.
+//!
+//! Transcript chunks are clustered by embedding (spherical k-means), each
+//! cluster is labelled by an LLM from its most central chunks, and the result
+//! lists every topic with the media that talk about it most. Topics can be
+//! persisted so new documents are assigned to the nearest topic as they are
+//! indexed and searches can be restricted to one topic.
+
+mod cluster;
+mod index;
+mod mapper;
+
+pub use cluster::{default_k, kmeans, nearest_centroid, Clustering};
+pub use index::TopicIndex;
+pub use mapper::TopicMapper;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A media item's share of a topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicMedia {
+    /// Media ID.
+    pub video_id: String,
+    /// Media title.
+    pub video_title: String,
+    /// Chunks of this media item in the topic.
+    pub chunks: usize,
+}
+
+/// A cluster of related chunks.
+#[derive(Debug, Clone, Serialize)]
+pub struct Topic {
+    /// Topic number (1 is the largest topic).
+    pub id: i64,
+    /// Short label.
+    pub label: String,
+    /// One-sentence description.
+    pub description: String,
+    /// Chunks in the topic.
+    pub chunks: usize,
+    /// Media with the most chunks in the topic, most first.
+    pub media: Vec<TopicMedia>,
+}
+
+/// Topics of the whole library.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicMap {
+    /// Topics, largest first.
+    pub topics: Vec<Topic>,
+    /// Chunks clustered.
+    pub document_count: usize,
+    /// Media items clustered.
+    pub media_count: usize,
+    /// Model used for labels.
+    pub model: String,
+    /// When the topics were computed.
+    pub created_at: DateTime<Utc>,
+}
+
+impl TopicMap {
+    /// Render the map as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Library topics\n\n{} topics across {} chunks from {} media items.\n",
+            self.topics.len(),
+            self.document_count,
+            self.media_count
+        );
+
+        for topic in &self.topics {
+            out.push_str(&format!("\n## {}. {} ({} chunks)\n\n", topic.id, topic.label, topic.chunks));
+            if !topic.description.is_empty() {
+                out.push_str(&format!("{}\n\n", topic.description));
+            }
+            for media in &topic.media {
+                out.push_str(&format!("- {} (`{}`): {} chunks\n", media.video_title, media.video_id, media.chunks));
+            }
+        }
+
+        out
+    }
+}
+
+/// Topic a document belongs to.
+#[derive(Debug, Clone)]
+pub struct TopicAssignment {
+    /// Document ID.
+    pub document_id: String,
+    /// Media ID of the document.
+    pub video_id: String,
+    /// Topic number.
+    pub topic_id: i64,
+}
+
+/// A topic map with the centroids and assignments needed to persist it.
+#[derive(Debug, Clone)]
+pub struct TopicModel {
+    /// The labelled topics.
+    pub map: TopicMap,
+    /// Unit-length centroid of each topic, by topic number.
+    pub centroids: Vec<(i64, Vec<f32>)>,
+    /// Topic of every document, including derived documents.
+    pub assignments: Vec<TopicAssignment>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_map_markdown() {
+        let map = TopicMap {
+            topics: vec![Topic {
+                id: 1,
+                label: "Ownership".to_string(),
+                description: "Borrowing and lifetimes.".to_string(),
+                chunks: 12,
+                media: vec![TopicMedia {
+                    video_id: "abc".to_string(),
+                    video_title: "Rust 101".to_string(),
+                    chunks: 8,
+                }],
+            }],
+            document_count: 40,
+            media_count: 3,
+            model: "gpt-4o-mini".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let md = map.to_markdown();
+        assert!(md.contains("1 topics across 40 chunks from 3 media items"));
+        assert!(md.contains("## 1. Ownership (12 chunks)"));
+        assert!(md.contains("- Rust 101 (`abc`): 8 chunks"));
+    }
+}
@@ -0,0 +1,292 @@
+//! Existing YouTube captions as a transcript source.
+//!
+//! Many videos already carry uploaded subtitles or YouTube's own speech
+//! recognition. Downloading those with yt-dlp costs nothing, so the
+//! `captions` provider tries them first and only transcribes the audio when a
+//! video has none.
+
+use super::{language_code, Transcript, TranscriptSegment};
+use crate::audio::ytdlp_command;
+use crate::audio_source::{MediaMetadata, SourceType};
+use crate::config::{TranscriptionProvider, TranscriptionSettings};
+use crate::error::{LyttError, Result};
+use crate::logging;
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::{debug, info, warn};
+
+/// A caption track chosen for download.
+#[derive(Debug, Clone, PartialEq)]
+struct CaptionTrack {
+    /// yt-dlp language key, e.g. "en" or "de-orig".
+    key: String,
+    /// Uploaded by the channel rather than generated by YouTube.
+    manual: bool,
+}
+
+/// Use YouTube captions as the transcript when the `captions` provider is
+/// configured. Returns `None` (after logging why) so the caller can fall back
+/// to transcribing the audio.
+pub async fn captions_transcript(
+    settings: &TranscriptionSettings,
+    metadata: &MediaMetadata,
+    output_dir: &Path,
+) -> Option<Transcript> {
+    if settings.provider != TranscriptionProvider::Captions || metadata.source_type != SourceType::YouTube {
+        return None;
+    }
+
+    let language = settings.language.as_deref().filter(|l| !l.eq_ignore_ascii_case("auto"));
+    match fetch_captions(&metadata.source_url, &metadata.id, output_dir, language).await {
+        Ok(Some(transcript)) => {
+            info!("Using YouTube captions ({} segments)", transcript.segments.len());
+            logging::status("  Using existing captions (no transcription needed)");
+            Some(transcript)
+        }
+        Ok(None) => {
+            info!("No captions for {}, transcribing audio", metadata.id);
+            logging::status("  No captions available, transcribing audio");
+            None
+        }
+        Err(e) => {
+            warn!("Failed to fetch captions for {}, transcribing audio: {}", metadata.id, e);
+            None
+        }
+    }
+}
+
+/// Download the best caption track for a video and convert it to a transcript.
+///
+/// Prefers uploaded subtitles over automatic captions, in `language` if given
+/// and otherwise in the video's own language. Returns `None` when the video
+/// has no usable captions.
+pub async fn fetch_captions(
+    url: &str,
+    media_id: &str,
+    output_dir: &Path,
+    language: Option<&str>,
+) -> Result<Option<Transcript>> {
+    let info = run_ytdlp(&["--dump-json", "--skip-download", "--no-playlist", "--no-warnings", url]).await?;
+    let info: Value = serde_json::from_slice(&info)
+        .map_err(|e| LyttError::VideoSource(format!("Failed to parse yt-dlp output: {}", e)))?;
+
+    let Some(track) = pick_track(&info, language) else {
+        return Ok(None);
+    };
+    debug!("Downloading {} captions '{}'", if track.manual { "uploaded" } else { "automatic" }, track.key);
+
+    std::fs::create_dir_all(output_dir)?;
+    let template = output_dir.join(format!("{}.captions.%(ext)s", media_id));
+    let write_flag = if track.manual { "--write-subs" } else { "--write-auto-subs" };
+    run_ytdlp(&[
+        "--skip-download",
+        write_flag,
+        "--sub-format",
+        "vtt",
+        "--sub-langs",
+        &track.key,
+        "--output",
+        template.to_str().unwrap_or_default(),
+        "--no-playlist",
+        "--quiet",
+        "--no-warnings",
+        url,
+    ])
+    .await?;
+
+    let path = output_dir.join(format!("{}.captions.{}.vtt", media_id, track.key));
+    let vtt = std::fs::read_to_string(&path)
+        .map_err(|e| LyttError::AudioDownload(format!("Caption file not found after download: {}", e)))?;
+    let _ = std::fs::remove_file(&path);
+
+    let segments = parse_vtt(&vtt);
+    if segments.is_empty() {
+        return Ok(None);
+    }
+
+    let base = track.key.trim_end_matches("-orig");
+    Ok(Some(Transcript::new(media_id.to_string(), segments).with_language(language_code(base))))
+}
+
+async fn run_ytdlp(args: &[&str]) -> Result<Vec<u8>> {
+    let output = ytdlp_command().args(args).output().await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            LyttError::ToolNotFound("yt-dlp".to_string())
+        } else {
+            LyttError::VideoSource(format!("Failed to run yt-dlp: {}", e))
+        }
+    })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(LyttError::VideoSource(format!("yt-dlp failed: {}", stderr.trim())));
+    }
+    Ok(output.stdout)
+}
+
+/// Choose a caption track from yt-dlp's `subtitles` (uploaded) and
+/// `automatic_captions` maps.
+///
+/// Automatic captions are offered machine-translated into every language;
+/// only the original-language track (`<lang>-orig`, or the video's own
+/// language) is a real transcript.
+fn pick_track(info: &Value, language: Option<&str>) -> Option<CaptionTrack> {
+    let wanted = language
+        .and_then(language_code)
+        .or_else(|| info["language"].as_str().and_then(language_code));
+
+    let keys = |field: &str| -> Vec<String> {
+        info[field]
+            .as_object()
+            .map(|tracks| tracks.keys().filter(|k| *k != "live_chat").cloned().collect())
+            .unwrap_or_default()
+    };
+    let base = |key: &str| key.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    let manual = keys("subtitles");
+    let automatic = keys("automatic_captions");
+
+    let manual_track = match &wanted {
+        Some(wanted) => manual.iter().find(|k| base(k) == *wanted),
+        // Without a known language, a single uploaded track is unambiguous
+        None => (manual.len() == 1).then(|| &manual[0]),
+    };
+    if let Some(key) = manual_track {
+        return Some(CaptionTrack { key: key.clone(), manual: true });
+    }
+
+    let automatic_track = match (automatic.iter().find(|k| k.ends_with("-orig")), &wanted) {
+        (Some(original), Some(wanted)) => (base(original) == *wanted).then_some(original),
+        (Some(original), None) => Some(original),
+        // Older listings don't mark the original; trust the video's own language
+        (None, Some(wanted)) => automatic
+            .iter()
+            .find(|k| *k == wanted)
+            .filter(|_| info["language"].as_str().and_then(language_code).is_none_or(|l| l == *wanted)),
+        (None, None) => None,
+    };
+    automatic_track.map(|key| CaptionTrack { key: key.clone(), manual: false })
+}
+
+fn cue_timing_regex() -> &'static Regex {
+    static TIMING: OnceLock<Regex> = OnceLock::new();
+    TIMING.get_or_init(|| {
+        Regex::new(r"^((?:\d+:)?\d{1,2}:\d{2}[.,]\d{3})\s+-->\s+((?:\d+:)?\d{1,2}:\d{2}[.,]\d{3})")
+            .expect("valid cue timing regex")
+    })
+}
+
+fn tag_regex() -> &'static Regex {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    TAG.get_or_init(|| Regex::new(r"<[^>]*>").expect("valid tag regex"))
+}
+
+/// Parse a `HH:MM:SS.mmm` or `MM:SS.mmm` cue timestamp.
+pub(crate) fn parse_cue_time(s: &str) -> Option<f64> {
+    let s = s.replace(',', ".");
+    let mut seconds = 0.0;
+    for part in s.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Convert WebVTT captions to transcript segments.
+///
+/// YouTube's automatic captions scroll: each cue repeats the previous line
+/// before adding a new one, with word-timing tags inline. Tags are stripped
+/// and lines already emitted by the previous cue are dropped, so each spoken
+/// line appears once.
+pub fn parse_vtt(vtt: &str) -> Vec<TranscriptSegment> {
+    let mut segments: Vec<TranscriptSegment> = Vec::new();
+    let mut previous_lines: Vec<String> = Vec::new();
+    let mut lines = vtt.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(caps) = cue_timing_regex().captures(line.trim()) else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (parse_cue_time(&caps[1]), parse_cue_time(&caps[2])) else {
+            continue;
+        };
+
+        let mut cue_lines = Vec::new();
+        // Cues end at an empty line; YouTube pads cues with lines of one space
+        while let Some(text) = lines.next_if(|l| !l.trim_end_matches('\r').is_empty()) {
+            let text = tag_regex().replace_all(text, "");
+            let text = decode_entities(text.trim());
+            if !text.is_empty() {
+                cue_lines.push(text);
+            }
+        }
+
+        let new_lines: Vec<&String> = cue_lines.iter().filter(|l| !previous_lines.contains(l)).collect();
+        if !new_lines.is_empty() {
+            let text = new_lines.iter().map(|l| l.as_str()).collect::<Vec<_>>().join(" ");
+            segments.push(TranscriptSegment::new(start, end, text));
+        } else if let Some(last) = segments.last_mut() {
+            // A repeat of the last line keeps it on screen a little longer
+            last.end_seconds = last.end_seconds.max(end);
+        }
+        if !cue_lines.is_empty() {
+            previous_lines = cue_lines;
+        }
+    }
+
+    segments
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_vtt_rolling_captions() {
+        let vtt = "WEBVTT\nKind: captions\nLanguage: en\n\n\
+            00:00:00.160 --> 00:00:02.470 align:start position:0%\n \n\
+            hello<00:00:00.560><c> everyone</c>\n\n\
+            00:00:02.470 --> 00:00:02.480 align:start position:0%\n\
+            hello everyone\n \n\n\
+            00:00:02.480 --> 00:00:05.120 align:start position:0%\n\
+            hello everyone\n\
+            today<00:00:03.000><c> we</c><00:00:03.200><c> talk</c> about R&amp;D\n\n\
+            1:00:01.000 --> 1:00:02.000\n\
+            last line\n";
+
+        let segments = parse_vtt(vtt);
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].text, "hello everyone");
+        assert_eq!(segments[0].end_seconds, 2.48);
+        assert_eq!(segments[1].text, "today we talk about R&D");
+        assert_eq!(segments[1].start_seconds, 2.48);
+        assert_eq!(segments[2].start_seconds, 3601.0);
+    }
+
+    #[test]
+    fn test_pick_track() {
+        let info = json!({
+            "language": "de",
+            "subtitles": {"en": [], "live_chat": []},
+            "automatic_captions": {"de-orig": [], "de": [], "en": [], "fr": []}
+        });
+        assert_eq!(pick_track(&info, None), Some(CaptionTrack { key: "de-orig".to_string(), manual: false }));
+        assert_eq!(pick_track(&info, Some("english")), Some(CaptionTrack { key: "en".to_string(), manual: true }));
+        assert_eq!(pick_track(&info, Some("fr")), None);
+
+        let unmarked = json!({"automatic_captions": {"en": [], "fr": []}});
+        assert_eq!(pick_track(&unmarked, Some("en")), Some(CaptionTrack { key: "en".to_string(), manual: false }));
+
+        assert_eq!(pick_track(&json!({"title": "No captions"}), None), None);
+    }
+}
@@ -5,41 +5,113 @@
 //! 2. For each segment, run Whisper AND GPT-4o in parallel
 //! 3. Fuse each segment pairwise (Whisper words + GPT-4o text → fused segment)
 //! 4. Merge all fused segments into complete transcript with timestamps
+//!
+//! The GPT-4o-only provider skips Whisper: each part is transcribed in short
+//! windows and sentences are timed by their position within the window.
 
 use super::{
-    gpt4o::Gpt4oTranscriber, whisper::WhisperTranscriber, FusedSegment,
+    gpt4o::Gpt4oTranscriber, language_code, vocabulary_prompt, whisper::WhisperTranscriber, FusedSegment,
     Transcriber, Transcript, TranscriptSegment, WhisperWord,
 };
-use crate::audio::split_audio;
-use crate::config::TranscriptionProcessingSettings;
+use crate::audio::{segments_dir, split_audio};
+use crate::config::{TranscriptionProcessingSettings, TranscriptionProvider, TranscriptionSettings};
 use crate::error::{LyttError, Result};
 use async_openai::types::{
     ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
     ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs, ResponseFormat,
 };
-use crate::openai::create_client;
+use crate::llm::ChatClient;
+use crate::logging;
+use crate::usage::{self, UsageStage};
 use async_trait::async_trait;
 use futures::stream::{self, StreamExt};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use std::path::Path;
 use tracing::{debug, info, instrument, warn};
 
+/// Audio window transcribed at a time without Whisper; bounds how far off a
+/// GPT-4o-only timestamp can be.
+const GPT4O_WINDOW_SECONDS: u32 = 30;
+
 /// Transcription processor combining Whisper timestamps with optional secondary text model.
 ///
-/// Two modes:
+/// Three modes:
 /// - Full fusion: Whisper (timestamps) + GPT-4o (text) → LLM fuses both
 /// - Whisper + cleanup: Whisper only → LLM cleans up and creates natural segments
+/// - GPT-4o only: GPT-4o over short windows → sentences timed within each window
 pub struct TranscriptionProcessor {
-    whisper: WhisperTranscriber,
+    whisper: Option<WhisperTranscriber>,
     gpt4o: Option<Gpt4oTranscriber>,
-    client: async_openai::Client<async_openai::config::OpenAIConfig>,
+    client: ChatClient,
     cleanup_model: String,
     system_prompt: String,
     segment_duration_seconds: u32,
     max_concurrent_segments: usize,
+    language: Option<String>,
 }
 
 impl TranscriptionProcessor {
+    /// Create the processor for the configured transcription provider.
+    pub fn from_settings(settings: &TranscriptionSettings, system_prompt: &str) -> Result<Self> {
+        let processor = match settings.provider {
+            // Captions fall back to Whisper for videos without them
+            TranscriptionProvider::Whisper | TranscriptionProvider::Captions => {
+                info!("Using Whisper with LLM cleanup ({})", settings.processing.cleanup_model);
+                Self::with_config(
+                    &TranscriptionProcessingSettings {
+                        timestamp_model: settings.model.clone(),
+                        text_model: None, // No secondary model
+                        cleanup_model: settings.processing.cleanup_model.clone(),
+                        max_concurrent: settings.max_concurrent_chunks,
+                        cleanup_llm: settings.processing.cleanup_llm.clone(),
+                    },
+                    system_prompt,
+                )
+            }
+            TranscriptionProvider::Fusion => {
+                info!(
+                    "Using fusion transcription ({} + {} -> {})",
+                    settings.processing.timestamp_model,
+                    settings.processing.text_model.as_deref().unwrap_or("gpt-4o-transcribe"),
+                    settings.processing.cleanup_model
+                );
+                Self::with_config(&settings.processing, system_prompt)
+            }
+            TranscriptionProvider::Gpt4o => {
+                let model = settings
+                    .processing
+                    .text_model
+                    .clone()
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or_else(|| "gpt-4o-transcribe".to_string());
+                if model.starts_with("whisper") {
+                    return Err(LyttError::Config(format!(
+                        "transcription.provider = \"gpt4o\" needs a GPT-4o transcription model in \
+                         transcription.processing.text_model, not {}",
+                        model
+                    )));
+                }
+                info!("Using {} only (no Whisper)", model);
+
+                Ok(Self {
+                    whisper: None,
+                    gpt4o: Some(Gpt4oTranscriber::with_config(
+                        &model,
+                        GPT4O_WINDOW_SECONDS,
+                        settings.max_concurrent_chunks,
+                    )?),
+                    client: ChatClient::from_settings(&settings.processing.cleanup_llm),
+                    cleanup_model: settings.processing.cleanup_model.clone(),
+                    system_prompt: system_prompt.to_string(),
+                    segment_duration_seconds: 300,
+                    max_concurrent_segments: settings.processing.max_concurrent,
+                    language: None,
+                })
+            }
+        }?;
+        Ok(processor.with_language(settings.language.as_deref()))
+    }
+
     /// Create with custom settings and system prompt.
     pub fn with_config(settings: &TranscriptionProcessingSettings, system_prompt: &str) -> Result<Self> {
         let gpt4o = if settings.has_text_model() {
@@ -54,25 +126,62 @@ impl TranscriptionProcessor {
         };
 
         Ok(Self {
-            whisper: WhisperTranscriber::with_config(
+            whisper: Some(WhisperTranscriber::with_config(
                 &settings.timestamp_model,
                 // Don't chunk internally - we handle segmentation
                 u32::MAX,
                 1,
-            )?,
+            )?),
             gpt4o,
-            client: create_client(),
+            client: ChatClient::from_settings(&settings.cleanup_llm),
             cleanup_model: settings.cleanup_model.clone(),
             system_prompt: system_prompt.to_string(),
             // 5 minutes per segment for parallel processing
             segment_duration_seconds: 300,
             max_concurrent_segments: settings.max_concurrent,
+            language: None,
         })
     }
 
+    /// Transcribe in this language instead of detecting it. `None` or
+    /// `"auto"` detects the language of each file.
+    pub fn with_language(mut self, language: Option<&str>) -> Self {
+        self.language = language
+            .filter(|l| !l.trim().is_empty() && !l.trim().eq_ignore_ascii_case("auto"))
+            .and_then(language_code);
+        self
+    }
+
+    /// Bias speech-to-text towards these terms (names, jargon, acronyms).
+    pub fn with_vocabulary(mut self, terms: &[String]) -> Self {
+        let prompt = vocabulary_prompt(terms);
+        self.whisper = self.whisper.map(|t| t.with_prompt(prompt.clone()));
+        self.gpt4o = self.gpt4o.map(|t| t.with_prompt(prompt));
+        self
+    }
+
+    /// Detect the spoken language with Whisper, so every part gets the same
+    /// hint. Detection failures fall back to per-request auto-detection, and
+    /// so does gpt-4o-only mode, which has no Whisper to detect with.
+    async fn detect_language(&self, audio_path: &Path) -> Option<String> {
+        let whisper = self.whisper.as_ref()?;
+
+        match whisper.detect_language(audio_path).await {
+            Ok(Some(language)) => {
+                info!("Detected language: {}", language);
+                Some(language)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Language detection failed, letting each part auto-detect: {}", e);
+                None
+            }
+        }
+    }
+
     /// Check if running in full fusion mode (with secondary text model).
     pub fn is_full_fusion(&self) -> bool {
-        self.gpt4o.is_some()
+        self.whisper.is_some() && self.gpt4o.is_some()
     }
 
     /// Process a single segment: transcribe and clean up/fuse.
@@ -84,18 +193,37 @@ impl TranscriptionProcessor {
     ) -> Result<Vec<TranscriptSegment>> {
         debug!("Starting segment at {:.0}s", time_offset);
 
+        let whisper = match (&self.whisper, &self.gpt4o) {
+            (Some(whisper), _) => whisper,
+            (None, Some(gpt4o)) => {
+                let windows = gpt4o
+                    .transcribe_windows(segment_path, language, GPT4O_WINDOW_SECONDS)
+                    .await?;
+                info!("Segment at {:.0}s: {} windows ({} only)", time_offset, windows.len(), gpt4o.model());
+                return Ok(windows
+                    .into_iter()
+                    .flat_map(|w| split_sentences(&w.text, time_offset + w.start_seconds, time_offset + w.end_seconds))
+                    .map(Into::into)
+                    .collect());
+            }
+            (None, None) => return Err(LyttError::Transcription("No transcription model configured".to_string())),
+        };
+
         let (whisper_words, text) = if let Some(ref gpt4o) = self.gpt4o {
             // Full fusion: run Whisper and GPT-4o in parallel
             debug!("Running Whisper + GPT-4o for segment at {:.0}s", time_offset);
             let (words, gpt4o_text) = tokio::try_join!(
-                self.whisper.transcribe_single_with_words(segment_path, language),
+                whisper.transcribe_single_with_words(segment_path, language),
                 gpt4o.transcribe_single(segment_path, language),
             )?;
+            // The text model doesn't report duration; bill it for the same audio as Whisper
+            let seconds = words.last().map(|w| w.end).unwrap_or(0.0);
+            usage::record_audio(gpt4o.model(), seconds);
             (words, gpt4o_text)
         } else {
             // Whisper-only: get words and construct text from them
             debug!("Running Whisper-only for segment at {:.0}s", time_offset);
-            let words = self.whisper.transcribe_single_with_words(segment_path, language).await?;
+            let words = whisper.transcribe_single_with_words(segment_path, language).await?;
             let text = words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>().join(" ");
             (words, text)
         };
@@ -194,12 +322,9 @@ impl TranscriptionProcessor {
             .build()
             .map_err(|e| LyttError::Transcription(e.to_string()))?;
 
-        let response = self
-            .client
-            .chat()
-            .create(request)
-            .await
-            .map_err(|e| LyttError::OpenAI(format!("Fusion error: {}", e)))?;
+        let response = self.client.create(request).await?;
+
+        usage::record_chat(UsageStage::Cleanup, &self.cleanup_model, response.usage.as_ref());
 
         let content = response
             .choices
@@ -284,6 +409,42 @@ impl TranscriptionProcessor {
     }
 }
 
+/// Split a window's text into sentences, timing each by its share of the
+/// window's characters.
+fn split_sentences(text: &str, start: f64, end: f64) -> Vec<FusedSegment> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sentences = Vec::new();
+    let mut sentence_start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_boundary = matches!(c, '.' | '!' | '?') && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+        if at_boundary {
+            sentences.push((sentence_start, i + c.len_utf8()));
+            sentence_start = i + c.len_utf8();
+        }
+    }
+    if sentence_start < text.len() {
+        sentences.push((sentence_start, text.len()));
+    }
+
+    let seconds_per_byte = (end - start).max(0.0) / text.len() as f64;
+    sentences
+        .into_iter()
+        .filter_map(|(from, to)| {
+            let sentence = text[from..to].trim();
+            (!sentence.is_empty()).then(|| FusedSegment {
+                text: sentence.to_string(),
+                start_seconds: start + from as f64 * seconds_per_byte,
+                end_seconds: start + to as f64 * seconds_per_byte,
+            })
+        })
+        .collect()
+}
+
 impl Default for TranscriptionProcessor {
     fn default() -> Self {
         use crate::config::CleanupPrompts;
@@ -312,24 +473,23 @@ impl Transcriber for TranscriptionProcessor {
             .unwrap_or("unknown")
             .to_string();
 
-        let lang = if language.is_empty() {
-            None
-        } else {
-            Some(language)
-        };
-
         info!("Fusion transcription: {}", media_id);
 
+        let lang = match language_code(language).or_else(|| self.language.clone()) {
+            Some(lang) => Some(lang),
+            None => self.detect_language(audio_path).await,
+        };
+
         // 1. Split audio into segments
-        let temp_dir = tempfile::tempdir()?;
+        let temp_dir = segments_dir(audio_path)?;
         let segments = split_audio(audio_path, temp_dir.path(), self.segment_duration_seconds).await?;
         let segment_count = segments.len();
 
         info!("Split into {} parts for processing", segment_count);
-        eprintln!("  Processing {} audio parts", segment_count);
+        logging::status(format_args!("  Processing {} audio parts", segment_count));
 
         // Create progress bar
-        let pb = ProgressBar::new(segment_count as u64);
+        let pb = logging::progress_bar(segment_count as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("  {spinner:.green} Transcribing [{bar:30.cyan/blue}] {pos}/{len}")
@@ -341,12 +501,11 @@ impl Transcriber for TranscriptionProcessor {
         // 2. Process segments with concurrency control
         // Each segment: Whisper + GPT-4o in parallel, then fuse
         // Fail fast on first error
-        let lang_owned = lang.map(|s| s.to_string());
         let mut all_segments: Vec<TranscriptSegment> = Vec::new();
 
         let mut stream = stream::iter(segments)
             .map(|(segment_path, time_offset)| {
-                let lang_ref = lang_owned.as_deref();
+                let lang_ref = lang.as_deref();
                 async move {
                     (time_offset, self.process_segment(&segment_path, time_offset, lang_ref).await)
                 }
@@ -361,7 +520,7 @@ impl Transcriber for TranscriptionProcessor {
                     pb.finish_and_clear();
                     drop(temp_dir);
                     let err_msg = format!("Segment at {:.0}s failed: {}", time_offset, e);
-                    eprintln!("  Error: {}", err_msg);
+                    logging::status(format_args!("  Error: {}", err_msg));
                     return Err(LyttError::Transcription(err_msg));
                 }
             }
@@ -376,9 +535,9 @@ impl Transcriber for TranscriptionProcessor {
         all_segments.sort_by(|a, b| a.start_seconds.partial_cmp(&b.start_seconds).unwrap());
 
         info!("Fusion complete: {} timestamped sections", all_segments.len());
-        eprintln!("  Transcription complete");
+        logging::status("  Transcription complete");
 
-        Ok(Transcript::new(media_id, all_segments))
+        Ok(Transcript::new(media_id, all_segments).with_language(lang.clone()))
     }
 }
 
@@ -389,13 +548,14 @@ mod tests {
     fn create_test_processor() -> TranscriptionProcessor {
         // Create with test settings (won't actually call APIs in tests)
         TranscriptionProcessor {
-            whisper: WhisperTranscriber::with_config("whisper-1", 120, 1).unwrap(),
+            whisper: Some(WhisperTranscriber::with_config("whisper-1", 120, 1).unwrap()),
             gpt4o: Some(Gpt4oTranscriber::with_config("gpt-4o-transcribe", 120, 1).unwrap()),
-            client: create_client(),
+            client: ChatClient::openai(),
             cleanup_model: "gpt-4.1".to_string(),
             system_prompt: "Test".to_string(),
             segment_duration_seconds: 120,
             max_concurrent_segments: 2,
+            language: None,
         }
     }
 
@@ -436,6 +596,19 @@ mod tests {
         assert!((segments[0].end_seconds - 121.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_split_sentences() {
+        let segments = split_sentences(" Hi there. Version 2.0 is out! Really", 30.0, 60.0);
+
+        let texts: Vec<_> = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hi there.", "Version 2.0 is out!", "Really"]);
+        assert_eq!(segments[0].start_seconds, 30.0);
+        assert!((segments[2].end_seconds - 60.0).abs() < 0.01);
+        assert!(segments.windows(2).all(|w| w[0].end_seconds <= w[1].start_seconds + 0.01));
+
+        assert!(split_sentences("  ", 0.0, 30.0).is_empty());
+    }
+
     #[test]
     fn test_align_empty_input() {
         let transcriber = create_test_processor();
@@ -1,13 +1,16 @@
 //! GPT-4o transcribe implementation.
 //!
 //! Provides high-accuracy text transcription without word-level timestamps.
-//! Used in fusion mode alongside Whisper (which provides timestamps).
+//! Used in fusion mode alongside Whisper (which provides timestamps), or on
+//! its own with timestamps taken from short audio windows.
 
-use super::PlainTranscript;
-use crate::audio::split_audio;
+use super::{FusedSegment, PlainTranscript};
+use crate::audio::{probe_duration, segments_dir, split_audio};
 use crate::error::{Result, LyttError};
+use crate::logging;
 use async_openai::types::{AudioResponseFormat, CreateTranscriptionRequestArgs};
 use crate::openai::create_client;
+use crate::usage;
 use futures::StreamExt;
 use futures::stream;
 use std::path::Path;
@@ -21,6 +24,7 @@ pub struct Gpt4oTranscriber {
     model: String,
     chunk_duration_seconds: u32,
     max_concurrent_chunks: usize,
+    prompt: Option<String>,
 }
 
 impl Gpt4oTranscriber {
@@ -42,9 +46,21 @@ impl Gpt4oTranscriber {
             model: model.to_string(),
             chunk_duration_seconds,
             max_concurrent_chunks,
+            prompt: None,
         })
     }
 
+    /// Set a prompt that biases recognition, e.g. towards domain vocabulary.
+    pub fn with_prompt(mut self, prompt: Option<String>) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Get the model name.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
     /// Transcribe a single audio file to plain text.
     #[instrument(skip(self), fields(audio_path = %audio_path.display()))]
     pub async fn transcribe_single(
@@ -72,6 +88,9 @@ impl Gpt4oTranscriber {
         if let Some(lang) = language {
             request_builder.language(lang);
         }
+        if let Some(prompt) = &self.prompt {
+            request_builder.prompt(prompt);
+        }
 
         let request = request_builder.build().map_err(|e| {
             LyttError::Transcription(format!("Failed to build request: {}", e))
@@ -106,7 +125,7 @@ impl Gpt4oTranscriber {
         media_id: &str,
         progress: Option<Arc<AtomicU64>>,
     ) -> Result<PlainTranscript> {
-        let temp_dir = tempfile::tempdir()?;
+        let temp_dir = segments_dir(audio_path)?;
         let chunks = split_audio(audio_path, temp_dir.path(), self.chunk_duration_seconds).await?;
 
         if chunks.len() == 1 {
@@ -149,7 +168,7 @@ impl Gpt4oTranscriber {
                 Err(e) => {
                     drop(temp_dir);
                     let err_msg = format!("{} chunk {} at {:.0}s failed: {}", self.model, idx, time_offset, e);
-                    eprintln!("  Error: {}", err_msg);
+                    logging::status(format_args!("  Error: {}", err_msg));
                     return Err(LyttError::Transcription(err_msg));
                 }
             }
@@ -174,6 +193,46 @@ impl Gpt4oTranscriber {
             language: language.map(|s| s.to_string()),
         })
     }
+
+    /// Transcribe audio in windows of `window_seconds`, returning each
+    /// window's text with the window's start and end time.
+    ///
+    /// The model returns no timestamps, so the window length bounds how far
+    /// off a timestamp can be. Windows end at pauses where possible.
+    pub async fn transcribe_windows(
+        &self,
+        audio_path: &Path,
+        language: Option<&str>,
+        window_seconds: u32,
+    ) -> Result<Vec<FusedSegment>> {
+        let duration = probe_duration(audio_path).await?;
+        let temp_dir = segments_dir(audio_path)?;
+        let windows = split_audio(audio_path, temp_dir.path(), window_seconds).await?;
+        let window_count = windows.len();
+
+        // Windows are cut at pauses, so each ends where the next starts
+        let ends: Vec<f64> = windows.iter().skip(1).map(|(_, start)| *start).chain([duration]).collect();
+
+        let mut results: Vec<FusedSegment> = stream::iter(windows.into_iter().zip(ends))
+            .map(|((window_path, start), end)| async move {
+                let text = self.transcribe_single(&window_path, language).await?;
+                usage::record_audio(&self.model, end - start);
+                Ok::<_, LyttError>(FusedSegment {
+                    text,
+                    start_seconds: start,
+                    end_seconds: end,
+                })
+            })
+            .buffer_unordered(self.max_concurrent_chunks.max(1))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+
+        results.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+        debug!("Transcribed {} windows with {}", window_count, self.model);
+        Ok(results)
+    }
 }
 
 impl Default for Gpt4oTranscriber {
@@ -0,0 +1,98 @@
+//! Transcripts made by other tools: SubRip, WebVTT and JSON files.
+//!
+//! JSON is read from lytt's own exports (`lytt transcribe -o`, `lytt
+//! export`) and from Whisper's `verbose_json`: a `segments` array whose items
+//! have a start, an end and text.
+
+use super::{language_code, parse_vtt, Transcript, TranscriptSegment};
+use crate::error::{LyttError, Result};
+use serde_json::Value;
+use std::path::Path;
+
+/// Extensions read as transcripts rather than media.
+const TRANSCRIPT_EXTENSIONS: [&str; 3] = ["srt", "vtt", "json"];
+
+/// Whether the path names a transcript file lytt can import.
+pub fn is_transcript_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| TRANSCRIPT_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Read a transcript file into a transcript for `media_id`.
+pub fn read_transcript_file(path: &Path, media_id: &str) -> Result<Transcript> {
+    let content = std::fs::read_to_string(path)?;
+    let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+
+    let (segments, language) = if is_json {
+        let value: Value = serde_json::from_str(&content)?;
+        let segments = json_segments(&value).ok_or_else(|| {
+            LyttError::InvalidInput(format!("{} has no segments with start, end and text", path.display()))
+        })?;
+        (segments, value["language"].as_str().and_then(language_code))
+    } else {
+        // SubRip cues are WebVTT cues with a number line and commas in the times
+        (parse_vtt(&content), None)
+    };
+
+    let segments: Vec<TranscriptSegment> = segments.into_iter().filter(|s| !s.text.trim().is_empty()).collect();
+    if segments.is_empty() {
+        return Err(LyttError::InvalidInput(format!("No transcript text found in {}", path.display())));
+    }
+    Ok(Transcript::new(media_id.to_string(), segments).with_language(language))
+}
+
+/// Segments from a `segments` array, or a top-level array, of objects with
+/// `start_seconds`/`start`, `end_seconds`/`end` and `text`.
+fn json_segments(value: &Value) -> Option<Vec<TranscriptSegment>> {
+    let items = value["segments"].as_array().or_else(|| value.as_array())?;
+    let segments: Vec<TranscriptSegment> = items
+        .iter()
+        .filter_map(|item| {
+            let time = |keys: [&str; 2]| keys.iter().find_map(|k| item[*k].as_f64());
+            let start = time(["start_seconds", "start"])?;
+            let end = time(["end_seconds", "end"])?;
+            let text = item["text"].as_str()?.trim();
+            Some(TranscriptSegment::new(start, end.max(start), text.to_string()))
+        })
+        .collect();
+    (!segments.is_empty()).then_some(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_transcript_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let srt = dir.path().join("talk.SRT");
+        std::fs::write(
+            &srt,
+            "1\r\n00:00:01,000 --> 00:00:04,500\r\n<i>Hello</i> and welcome.\r\n\r\n2\r\n00:00:05,000 --> 00:00:08,000\r\nToday: two topics,\r\nboth short.\r\n",
+        )
+        .unwrap();
+        assert!(is_transcript_file(&srt));
+        let transcript = read_transcript_file(&srt, "local_x").unwrap();
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[0].text, "Hello and welcome.");
+        assert_eq!(transcript.segments[1].text, "Today: two topics, both short.");
+        assert_eq!((transcript.segments[1].start_seconds, transcript.duration_seconds), (5.0, 8.0));
+
+        let whisper = dir.path().join("whisper.json");
+        std::fs::write(&whisper, r#"{"language": "english", "segments": [{"start": 0.0, "end": 2.5, "text": " Hi."}]}"#).unwrap();
+        let transcript = read_transcript_file(&whisper, "local_y").unwrap();
+        assert_eq!(transcript.segments[0].text, "Hi.");
+        assert_eq!(transcript.language.as_deref(), Some("en"));
+
+        let exported = dir.path().join("export.json");
+        std::fs::write(&exported, r#"{"segments": [{"title": "", "text": "Chunk", "start_seconds": 3.0, "end_seconds": 9.0}]}"#).unwrap();
+        assert_eq!(read_transcript_file(&exported, "local_z").unwrap().segments[0].end_seconds, 9.0);
+
+        let empty = dir.path().join("empty.vtt");
+        std::fs::write(&empty, "WEBVTT\n\n").unwrap();
+        assert!(read_transcript_file(&empty, "local_e").is_err());
+        assert!(!is_transcript_file(Path::new("talk.mp3")));
+    }
+}
@@ -0,0 +1,102 @@
+//! Spoken-language codes.
+//!
+//! Whisper reports the detected language by name ("english"), while its
+//! `language` parameter and lytt's storage use ISO 639-1 codes ("en").
+
+/// Languages supported by the Whisper API, by ISO 639-1 code.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("af", "afrikaans"),
+    ("ar", "arabic"),
+    ("hy", "armenian"),
+    ("az", "azerbaijani"),
+    ("be", "belarusian"),
+    ("bs", "bosnian"),
+    ("bg", "bulgarian"),
+    ("ca", "catalan"),
+    ("zh", "chinese"),
+    ("hr", "croatian"),
+    ("cs", "czech"),
+    ("da", "danish"),
+    ("nl", "dutch"),
+    ("en", "english"),
+    ("et", "estonian"),
+    ("fi", "finnish"),
+    ("fr", "french"),
+    ("gl", "galician"),
+    ("de", "german"),
+    ("el", "greek"),
+    ("he", "hebrew"),
+    ("hi", "hindi"),
+    ("hu", "hungarian"),
+    ("is", "icelandic"),
+    ("id", "indonesian"),
+    ("it", "italian"),
+    ("ja", "japanese"),
+    ("kn", "kannada"),
+    ("kk", "kazakh"),
+    ("ko", "korean"),
+    ("lv", "latvian"),
+    ("lt", "lithuanian"),
+    ("mk", "macedonian"),
+    ("ms", "malay"),
+    ("mr", "marathi"),
+    ("mi", "maori"),
+    ("ne", "nepali"),
+    ("no", "norwegian"),
+    ("fa", "persian"),
+    ("pl", "polish"),
+    ("pt", "portuguese"),
+    ("ro", "romanian"),
+    ("ru", "russian"),
+    ("sr", "serbian"),
+    ("sk", "slovak"),
+    ("sl", "slovenian"),
+    ("es", "spanish"),
+    ("sw", "swahili"),
+    ("sv", "swedish"),
+    ("tl", "tagalog"),
+    ("ta", "tamil"),
+    ("th", "thai"),
+    ("tr", "turkish"),
+    ("uk", "ukrainian"),
+    ("ur", "urdu"),
+    ("vi", "vietnamese"),
+    ("cy", "welsh"),
+];
+
+/// Normalize a language name or code to a lowercase ISO 639-1 code.
+///
+/// Accepts codes ("EN"), names ("English") and regional tags ("pt-BR").
+/// Unknown two-letter codes are passed through; anything else is `None`.
+pub fn language_code(language: &str) -> Option<String> {
+    let language = language.trim().to_lowercase();
+    let primary = language.split(['-', '_']).next().unwrap_or_default();
+
+    if let Some((code, _)) = LANGUAGES.iter().find(|(code, name)| *code == primary || *name == language) {
+        return Some(code.to_string());
+    }
+    (primary.len() == 2 && primary.chars().all(|c| c.is_ascii_alphabetic())).then(|| primary.to_string())
+}
+
+/// English name of a language code, for display.
+pub fn language_name(code: &str) -> Option<&'static str> {
+    let code = code.trim().to_lowercase();
+    LANGUAGES.iter().find(|(c, _)| *c == code).map(|(_, name)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_code() {
+        assert_eq!(language_code("english").as_deref(), Some("en"));
+        assert_eq!(language_code("Norwegian").as_deref(), Some("no"));
+        assert_eq!(language_code("DE").as_deref(), Some("de"));
+        assert_eq!(language_code("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(language_code("yo").as_deref(), Some("yo"));
+        assert_eq!(language_code("klingon"), None);
+        assert_eq!(language_code(""), None);
+        assert_eq!(language_name("sv"), Some("swedish"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Incremental transcription of a live audio stream.
+//!
+//! Audio arrives as 16-bit little-endian mono PCM. Whatever hasn't been
+//! finalized yet is transcribed again every `interim_seconds` for an interim
+//! result, and once it reaches `segment_seconds` its segments become final:
+//! all but the last, whose audio is carried over so no sentence is cut off.
+
+use super::{Transcriber, TranscriptSegment};
+use crate::error::{LyttError, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A transcription result pushed to the client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LiveEvent {
+    /// Text of the audio not yet final; replaced by the next result.
+    Interim { start: f64, end: f64, text: String },
+    /// A segment that won't change again.
+    Final { start: f64, end: f64, text: String },
+}
+
+/// Transcribes a stream of PCM audio as it arrives.
+pub struct LiveTranscriber {
+    transcriber: Arc<dyn Transcriber>,
+    sample_rate: u32,
+    /// Every sample received, as PCM bytes.
+    audio: Vec<u8>,
+    /// Byte offset where the audio not yet final starts.
+    committed: usize,
+    /// Bytes received when the last interim result was made.
+    interim_at: usize,
+    interim_seconds: u32,
+    segment_seconds: u32,
+    segments: Vec<TranscriptSegment>,
+}
+
+impl LiveTranscriber {
+    /// Create a live transcriber for PCM at `sample_rate`. An
+    /// `interim_seconds` of 0 turns interim results off.
+    pub fn new(transcriber: Arc<dyn Transcriber>, sample_rate: u32, interim_seconds: u32, segment_seconds: u32) -> Self {
+        Self {
+            transcriber,
+            sample_rate: sample_rate.max(1),
+            audio: Vec::new(),
+            committed: 0,
+            interim_at: 0,
+            interim_seconds,
+            segment_seconds: segment_seconds.max(1),
+            segments: Vec::new(),
+        }
+    }
+
+    /// Add received audio.
+    pub fn push(&mut self, pcm: &[u8]) {
+        self.audio.extend_from_slice(pcm);
+    }
+
+    /// Transcribe the pending audio if enough has arrived for a final or an
+    /// interim result.
+    pub async fn poll(&mut self, scratch: &Path) -> Result<Vec<LiveEvent>> {
+        let pending = self.seconds(self.audio.len() - self.committed);
+        if pending >= self.segment_seconds as f64 {
+            return self.transcribe_pending(scratch, false).await;
+        }
+        let since_interim = self.seconds(self.audio.len() - self.interim_at);
+        if self.interim_seconds > 0 && since_interim >= self.interim_seconds as f64 {
+            self.interim_at = self.audio.len();
+            return self.transcribe_pending(scratch, true).await;
+        }
+        Ok(Vec::new())
+    }
+
+    /// Make everything received final.
+    pub async fn finish(&mut self, scratch: &Path) -> Result<Vec<LiveEvent>> {
+        let mut events = Vec::new();
+        // One pass may carry its last segment over; the second takes it
+        while self.audio.len() > self.committed {
+            let before = self.committed;
+            events.extend(self.transcribe_pending(scratch, false).await?);
+            if self.committed == before {
+                self.committed = self.audio.len();
+            }
+        }
+        Ok(events)
+    }
+
+    /// Final segments so far, timed from the start of the stream.
+    pub fn segments(&self) -> &[TranscriptSegment] {
+        &self.segments
+    }
+
+    /// Seconds of audio received.
+    pub fn duration(&self) -> f64 {
+        self.seconds(self.audio.len())
+    }
+
+    /// Write all audio received to a WAV file.
+    pub fn write_wav(&self, path: &Path) -> Result<()> {
+        write_wav(path, &self.audio, self.sample_rate)
+    }
+
+    async fn transcribe_pending(&mut self, scratch: &Path, interim: bool) -> Result<Vec<LiveEvent>> {
+        // Keep whole samples
+        let end = self.audio.len() - (self.audio.len() - self.committed) % 2;
+        if end <= self.committed {
+            return Ok(Vec::new());
+        }
+        write_wav(scratch, &self.audio[self.committed..end], self.sample_rate)?;
+        let transcript = self.transcriber.transcribe(scratch).await?;
+        let offset = self.seconds(self.committed);
+        let segments: Vec<TranscriptSegment> =
+            transcript.segments.into_iter().filter(|s| !s.text.trim().is_empty()).collect();
+
+        if interim {
+            let text = segments.iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" ");
+            return Ok(vec![LiveEvent::Interim { start: offset, end: self.seconds(end), text }]);
+        }
+
+        // The last segment may be cut off mid-sentence unless the stream ended
+        let finishing = end == self.audio.len() && self.seconds(end - self.committed) < self.segment_seconds as f64;
+        let keep = if finishing || segments.len() < 2 { segments.len() } else { segments.len() - 1 };
+        let next = match segments.get(keep) {
+            Some(carried) => self.committed + self.bytes(carried.start_seconds),
+            None => end,
+        };
+
+        let mut events = Vec::new();
+        for segment in segments.into_iter().take(keep) {
+            let segment = TranscriptSegment::new(segment.start_seconds + offset, segment.end_seconds + offset, segment.text.trim().to_string());
+            events.push(LiveEvent::Final {
+                start: segment.start_seconds,
+                end: segment.end_seconds,
+                text: segment.text.clone(),
+            });
+            self.segments.push(segment);
+        }
+        self.committed = next.min(end);
+        self.interim_at = self.interim_at.max(self.committed);
+        Ok(events)
+    }
+
+    fn seconds(&self, bytes: usize) -> f64 {
+        bytes as f64 / 2.0 / self.sample_rate as f64
+    }
+
+    /// Whole-sample byte offset of a time.
+    fn bytes(&self, seconds: f64) -> usize {
+        (seconds.max(0.0) * self.sample_rate as f64) as usize * 2
+    }
+}
+
+/// Write 16-bit mono PCM to a WAV file.
+fn write_wav(path: &Path, pcm: &[u8], sample_rate: u32) -> Result<()> {
+    let too_long = || LyttError::InvalidInput(format!("{} bytes of audio is too long for a WAV file", pcm.len()));
+    let data_len = u32::try_from(pcm.len()).map_err(|_| too_long())?;
+    let riff_len = data_len.checked_add(36).ok_or_else(too_long)?;
+    let byte_rate = sample_rate
+        .checked_mul(2)
+        .ok_or_else(|| LyttError::InvalidInput(format!("Invalid sample rate: {}", sample_rate)))?;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    file.write_all(b"RIFF")?;
+    file.write_all(&riff_len.to_le_bytes())?;
+    file.write_all(b"WAVEfmt ")?;
+    file.write_all(&16u32.to_le_bytes())?;
+    file.write_all(&1u16.to_le_bytes())?; // PCM
+    file.write_all(&1u16.to_le_bytes())?; // mono
+    file.write_all(&sample_rate.to_le_bytes())?;
+    file.write_all(&byte_rate.to_le_bytes())?;
+    file.write_all(&2u16.to_le_bytes())?;
+    file.write_all(&16u16.to_le_bytes())?;
+    file.write_all(b"data")?;
+    file.write_all(&data_len.to_le_bytes())?;
+    file.write_all(pcm)?;
+    file.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTranscriber;
+    use crate::transcription::Transcript;
+
+    #[tokio::test]
+    async fn test_live_transcriber() {
+        let transcript = Transcript::new(
+            "live".to_string(),
+            vec![
+                TranscriptSegment::new(0.0, 4.0, "First sentence.".to_string()),
+                TranscriptSegment::new(4.0, 9.0, "Second, cut".to_string()),
+            ],
+        );
+        let mock = Arc::new(MockTranscriber::new(transcript));
+        let dir = tempfile::tempdir().unwrap();
+        let scratch = dir.path().join("scratch.wav");
+        // 100 samples a second keeps the buffers small
+        let mut live = LiveTranscriber::new(mock.clone(), 100, 2, 10);
+        let second = vec![0u8; 200];
+
+        live.push(&second);
+        assert!(live.poll(&scratch).await.unwrap().is_empty());
+        live.push(&second);
+        let interim = live.poll(&scratch).await.unwrap();
+        assert!(matches!(&interim[..], [LiveEvent::Interim { text, .. }] if text == "First sentence. Second, cut"));
+
+        for _ in 0..8 {
+            live.push(&second);
+        }
+        let events = live.poll(&scratch).await.unwrap();
+        assert_eq!(events, [LiveEvent::Final { start: 0.0, end: 4.0, text: "First sentence.".to_string() }]);
+        // The cut-off segment's audio is carried over
+        assert_eq!(live.committed, 800);
+
+        live.finish(&scratch).await.unwrap();
+        let starts: Vec<f64> = live.segments().iter().map(|s| s.start_seconds).collect();
+        assert_eq!(starts, [0.0, 4.0, 8.0]);
+        assert_eq!(live.duration(), 10.0);
+
+        live.write_wav(&scratch).unwrap();
+        assert_eq!(std::fs::metadata(&scratch).unwrap().len(), 44 + 2000);
+    }
+}
@@ -7,18 +7,31 @@
 //! - **Whisper** (default): Uses OpenAI Whisper with LLM cleanup for proper segmentation.
 //! - **Fusion**: Combines Whisper's word-level timestamps with GPT-4o's accurate text,
 //!   using an LLM to intelligently fuse both transcriptions.
+//! - **GPT-4o**: GPT-4o transcription only, timed by short audio windows.
+//! - **Captions**: YouTube's existing captions, falling back to Whisper when there are none.
+//!
+//! Existing SubRip, WebVTT and JSON transcripts can be imported without any audio.
 
+mod captions;
 mod format;
 mod fusion;
 mod gpt4o;
+mod import;
+mod language;
+mod live;
 mod models;
 mod whisper;
 
+pub use captions::{captions_transcript, fetch_captions, parse_vtt};
+pub(crate) use captions::parse_cue_time;
 pub use format::{format_transcript, OutputFormat, SegmentExport, TranscriptExport};
 pub use fusion::TranscriptionProcessor;
 pub use gpt4o::Gpt4oTranscriber;
+pub use import::{is_transcript_file, read_transcript_file};
+pub use language::{language_code, language_name};
+pub use live::{LiveEvent, LiveTranscriber};
 pub use models::{
-    FusedSegment, PlainTranscript, Transcript, TranscriptSegment, WhisperWord, WordLevelTranscript,
+    Chapter, FusedSegment, PlainTranscript, Transcript, TranscriptSegment, WhisperWord, WordLevelTranscript,
 };
 pub use whisper::{is_api_key_configured, WhisperTranscriber};
 
@@ -39,3 +52,49 @@ pub trait Transcriber: Send + Sync {
         language: &str,
     ) -> Result<Transcript>;
 }
+
+/// Whisper only reads the last 224 tokens of a prompt; keep well under that.
+const MAX_VOCABULARY_CHARS: usize = 800;
+
+/// Build a transcription prompt listing domain vocabulary, so the model spells
+/// those terms the same way. Repeated terms are listed once and terms past
+/// the length limit are dropped.
+pub fn vocabulary_prompt(terms: &[String]) -> Option<String> {
+    let mut prompt = String::from("Vocabulary:");
+    let mut seen = std::collections::HashSet::new();
+    let mut added = 0;
+
+    for term in terms.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !seen.insert(term.to_lowercase()) {
+            continue;
+        }
+        if prompt.len() + term.len() + 2 > MAX_VOCABULARY_CHARS {
+            break;
+        }
+        if added > 0 {
+            prompt.push(',');
+        }
+        prompt.push(' ');
+        prompt.push_str(term);
+        added += 1;
+    }
+
+    (added > 0).then(|| format!("{}.", prompt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vocabulary_prompt() {
+        assert_eq!(vocabulary_prompt(&[]), None);
+        assert_eq!(
+            vocabulary_prompt(&["Kubernetes".to_string(), " ".to_string(), "gRPC".to_string(), "kubernetes".to_string()]),
+            Some("Vocabulary: Kubernetes, gRPC.".to_string())
+        );
+
+        let many: Vec<String> = (0..500).map(|i| format!("term{}", i)).collect();
+        assert!(vocabulary_prompt(&many).unwrap().len() <= MAX_VOCABULARY_CHARS + 1);
+    }
+}
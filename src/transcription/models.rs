@@ -98,6 +98,23 @@ pub struct Transcript {
     pub full_text: String,
     /// Total duration in seconds.
     pub duration_seconds: f64,
+    /// Spoken language as an ISO 639-1 code, when known.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Creator-defined chapters, when the source has them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+}
+
+/// A creator-defined chapter (e.g. from a YouTube video description).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    /// Chapter title.
+    pub title: String,
+    /// Start time in seconds.
+    pub start_seconds: f64,
+    /// End time in seconds.
+    pub end_seconds: f64,
 }
 
 impl Transcript {
@@ -119,9 +136,28 @@ impl Transcript {
             segments,
             full_text,
             duration_seconds,
+            language: None,
+            chapters: Vec::new(),
         }
     }
 
+    /// Set the spoken language.
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Set the chapters.
+    pub fn with_chapters(mut self, chapters: Vec<Chapter>) -> Self {
+        self.chapters = chapters;
+        self
+    }
+
+    /// The chapter playing at `seconds`: the last one starting at or before it.
+    pub fn chapter_at(&self, seconds: f64) -> Option<&Chapter> {
+        self.chapters.iter().rev().find(|c| c.start_seconds <= seconds + 0.5)
+    }
+
     /// Get the text content between two timestamps.
     pub fn text_between(&self, start: f64, end: f64) -> String {
         self.segments
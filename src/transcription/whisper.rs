@@ -1,24 +1,33 @@
 //! OpenAI Whisper transcription implementation.
 
-use super::{Transcriber, Transcript, TranscriptSegment, WhisperWord, WordLevelTranscript};
-use crate::audio::split_audio;
+use super::{language_code, Transcriber, Transcript, TranscriptSegment, WhisperWord, WordLevelTranscript};
+use crate::audio::{extract_segment, probe_duration, segments_dir, split_audio};
 use crate::error::{Result, LyttError};
+use crate::logging;
 use async_openai::types::{AudioResponseFormat, CreateTranscriptionRequestArgs, TimestampGranularity};
 use crate::openai::create_client;
+use crate::usage;
 use async_trait::async_trait;
 use futures::stream::{self, StreamExt};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tracing::{debug, info, instrument, warn};
 
+/// Length of the clip sent to Whisper to detect the spoken language.
+const LANGUAGE_PROBE_SECONDS: f64 = 30.0;
+
+/// Latest point the language probe starts at.
+const LANGUAGE_PROBE_OFFSET_SECONDS: f64 = 60.0;
+
 /// OpenAI Whisper-based transcriber.
 pub struct WhisperTranscriber {
     client: async_openai::Client<async_openai::config::OpenAIConfig>,
     model: String,
     chunk_duration_seconds: u32,
     max_concurrent_chunks: usize,
+    prompt: Option<String>,
 }
 
 impl WhisperTranscriber {
@@ -40,9 +49,16 @@ impl WhisperTranscriber {
             model: model.to_string(),
             chunk_duration_seconds,
             max_concurrent_chunks,
+            prompt: None,
         })
     }
 
+    /// Set a prompt that biases recognition, e.g. towards domain vocabulary.
+    pub fn with_prompt(mut self, prompt: Option<String>) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
     /// Transcribe a single audio file (no splitting).
     #[instrument(skip(self), fields(audio_path = %audio_path.display()))]
     async fn transcribe_single(&self, audio_path: &Path, language: Option<&str>) -> Result<Vec<TranscriptSegment>> {
@@ -65,6 +81,9 @@ impl WhisperTranscriber {
         if let Some(lang) = language {
             request_builder.language(lang);
         }
+        if let Some(prompt) = &self.prompt {
+            request_builder.prompt(prompt);
+        }
 
         let request = request_builder.build()
             .map_err(|e| LyttError::Transcription(format!("Failed to build request: {}", e)))?;
@@ -72,6 +91,8 @@ impl WhisperTranscriber {
         let response = self.client.audio().transcribe_verbose_json(request).await
             .map_err(|e| LyttError::OpenAI(format!("Whisper API error: {}", e)))?;
 
+        usage::record_audio(&self.model, response.duration as f64);
+
         // Parse segments from verbose JSON response
         let segments: Vec<TranscriptSegment> = response.segments
             .map(|segs| {
@@ -96,6 +117,38 @@ impl WhisperTranscriber {
         Ok(segments)
     }
 
+    /// Detect the spoken language from a short clip of the audio.
+    ///
+    /// The clip starts a little way in to skip intro music. Returns the
+    /// ISO 639-1 code, or `None` if Whisper reports a language it can't name.
+    #[instrument(skip(self), fields(audio_path = %audio_path.display()))]
+    pub async fn detect_language(&self, audio_path: &Path) -> Result<Option<String>> {
+        let duration = probe_duration(audio_path).await?;
+        let start = (duration * 0.1).min(LANGUAGE_PROBE_OFFSET_SECONDS);
+        let length = LANGUAGE_PROBE_SECONDS.min(duration - start).max(1.0);
+
+        let temp_dir = segments_dir(audio_path)?;
+        let clip = temp_dir.path().join("language_probe.mp3");
+        extract_segment(audio_path, &clip, start, length).await?;
+        let file_bytes = tokio::fs::read(&clip).await?;
+
+        let request = CreateTranscriptionRequestArgs::default()
+            .file(async_openai::types::AudioInput::from_vec_u8("language_probe.mp3".to_string(), file_bytes))
+            .model(&self.model)
+            .response_format(AudioResponseFormat::VerboseJson)
+            .build()
+            .map_err(|e| LyttError::Transcription(format!("Failed to build request: {}", e)))?;
+
+        let response = self.client.audio().transcribe_verbose_json(request).await
+            .map_err(|e| LyttError::OpenAI(format!("Whisper API error: {}", e)))?;
+
+        usage::record_audio(&self.model, response.duration as f64);
+
+        let code = language_code(&response.language);
+        debug!("Detected language '{}' ({:?})", response.language, code);
+        Ok(code)
+    }
+
     /// Transcribe an audio file, splitting if necessary.
     #[instrument(skip(self), fields(audio_path = %audio_path.display()))]
     async fn transcribe_with_splitting(
@@ -104,7 +157,7 @@ impl WhisperTranscriber {
         language: Option<&str>,
         video_id: &str,
     ) -> Result<Transcript> {
-        let temp_dir = tempfile::tempdir()?;
+        let temp_dir = segments_dir(audio_path)?;
         let chunks = split_audio(audio_path, temp_dir.path(), self.chunk_duration_seconds).await?;
 
         if chunks.len() == 1 {
@@ -117,7 +170,7 @@ impl WhisperTranscriber {
         info!("Processing {} audio chunks with {}", chunk_count, self.model);
 
         // Create progress bar
-        let pb = Arc::new(ProgressBar::new(chunk_count as u64));
+        let pb = Arc::new(logging::progress_bar(chunk_count as u64));
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("  {spinner:.green} Whisper   [{bar:30.cyan/blue}] {pos}/{len}")
@@ -146,7 +199,7 @@ impl WhisperTranscriber {
                     pb.finish_and_clear();
                     drop(temp_dir);
                     let err_msg = format!("Chunk {} at {:.0}s failed: {}", idx, time_offset, e);
-                    eprintln!("  Error: {}", err_msg);
+                    logging::status(format_args!("  Error: {}", err_msg));
                     return Err(LyttError::Transcription(err_msg));
                 }
             }
@@ -205,6 +258,9 @@ impl WhisperTranscriber {
         if let Some(lang) = language {
             request_builder.language(lang);
         }
+        if let Some(prompt) = &self.prompt {
+            request_builder.prompt(prompt);
+        }
 
         let request = request_builder.build().map_err(|e| {
             LyttError::Transcription(format!("Failed to build request: {}", e))
@@ -217,6 +273,8 @@ impl WhisperTranscriber {
             .await
             .map_err(|e| LyttError::OpenAI(format!("Whisper API error: {}", e)))?;
 
+        usage::record_audio(&self.model, response.duration as f64);
+
         // Parse words from verbose JSON response
         let words: Vec<WhisperWord> = response
             .words
@@ -282,7 +340,7 @@ impl WhisperTranscriber {
         media_id: &str,
         progress: Option<Arc<AtomicU64>>,
     ) -> Result<WordLevelTranscript> {
-        let temp_dir = tempfile::tempdir()?;
+        let temp_dir = segments_dir(audio_path)?;
         let chunks = split_audio(audio_path, temp_dir.path(), self.chunk_duration_seconds).await?;
 
         if chunks.len() == 1 {
@@ -383,7 +441,8 @@ impl Transcriber for WhisperTranscriber {
             .unwrap_or("unknown")
             .to_string();
 
-        self.transcribe_with_splitting(audio_path, Some(language), &video_id).await
+        let transcript = self.transcribe_with_splitting(audio_path, Some(language), &video_id).await?;
+        Ok(transcript.with_language(language_code(language)))
     }
 }
 
@@ -0,0 +1,286 @@
+//! Text-to-speech.
+//!
+//! Speaks answers and announcements with the OpenAI speech API or a local
+//! engine (any command that reads text on stdin and writes a WAV file, such
+//! as piper or espeak-ng), and plays the result.
+
+use crate::audio::concat_audio;
+use crate::config::TtsSettings;
+use crate::error::{LyttError, Result};
+use crate::openai::create_client;
+use async_openai::config::OpenAIConfig;
+use async_openai::types::{CreateSpeechRequest, SpeechModel, SpeechResponseFormat, Voice};
+use async_openai::Client;
+use regex::Regex;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::OnceLock;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Longest text the speech API accepts in one request.
+const MAX_INPUT_CHARS: usize = 4096;
+
+/// Players tried for playback, with the arguments that make them play audio
+/// once without a window.
+const PLAYERS: [(&str, &[&str]); 4] = [
+    ("mpv", &["--no-video", "--really-quiet"]),
+    ("ffplay", &["-nodisp", "-autoexit", "-loglevel", "quiet"]),
+    ("afplay", &[]),
+    ("aplay", &["-q"]),
+];
+
+enum Engine {
+    OpenAI {
+        client: Client<OpenAIConfig>,
+        model: SpeechModel,
+        voice: Voice,
+        speed: f32,
+    },
+    Local {
+        command: String,
+        args: Vec<String>,
+    },
+}
+
+/// Speaks text with the configured engine.
+pub struct Speaker {
+    engine: Engine,
+}
+
+impl Speaker {
+    /// Create a speaker from settings, rejecting unknown engines and voices.
+    pub fn from_settings(settings: &TtsSettings) -> Result<Self> {
+        let engine = match settings.engine.to_lowercase().as_str() {
+            "openai" => Engine::OpenAI {
+                client: create_client(),
+                model: speech_model(&settings.model),
+                voice: parse_voice(&settings.voice)?,
+                speed: settings.speed.clamp(0.25, 4.0),
+            },
+            "local" => Engine::Local {
+                command: settings.command.clone().ok_or_else(|| {
+                    LyttError::Config("tts.command must be set for the local engine".to_string())
+                })?,
+                args: settings.args.clone(),
+            },
+            other => {
+                return Err(LyttError::Config(format!("Unknown TTS engine: {}. Use openai or local.", other)));
+            }
+        };
+        Ok(Self { engine })
+    }
+
+    /// File extension of the audio this speaker writes.
+    pub fn extension(&self) -> &'static str {
+        match self.engine {
+            Engine::OpenAI { .. } => "mp3",
+            Engine::Local { .. } => "wav",
+        }
+    }
+
+    /// Speak `text` into an audio file at `dest` (see [`Speaker::extension`]).
+    ///
+    /// Texts too long for one API request are spoken in parts and joined.
+    pub async fn speak_to_file(&self, text: &str, dest: &Path) -> Result<()> {
+        match &self.engine {
+            Engine::OpenAI { .. } => {
+                let pieces = split_for_speech(text, MAX_INPUT_CHARS);
+                if let [piece] = pieces.as_slice() {
+                    return self.request_speech(piece, dest).await;
+                }
+
+                let dir = tempfile::tempdir()?;
+                let mut parts = Vec::with_capacity(pieces.len());
+                for (i, piece) in pieces.iter().enumerate() {
+                    let part = dir.path().join(format!("part_{:03}.mp3", i));
+                    self.request_speech(piece, &part).await?;
+                    parts.push(part);
+                }
+                concat_audio(&parts, dest).await
+            }
+            Engine::Local { command, args } => run_local(command, args, text, dest).await,
+        }
+    }
+
+    /// Speak `text` and play it, or save it to `save_to` instead.
+    ///
+    /// Citation markers and Markdown are left out of the spoken text.
+    pub async fn speak(&self, text: &str, save_to: Option<&Path>) -> Result<()> {
+        let text = speakable(text);
+        if text.is_empty() {
+            return Ok(());
+        }
+        if let Some(path) = save_to {
+            return self.speak_to_file(&text, path).await;
+        }
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(format!("speech.{}", self.extension()));
+        self.speak_to_file(&text, &path).await?;
+        play(&path).await
+    }
+
+    async fn request_speech(&self, text: &str, dest: &Path) -> Result<()> {
+        let Engine::OpenAI { client, model, voice, speed } = &self.engine else {
+            unreachable!("only the OpenAI engine makes API requests");
+        };
+        debug!("Speaking {} characters", text.chars().count());
+
+        let request = CreateSpeechRequest {
+            input: text.to_string(),
+            model: model.clone(),
+            voice: voice.clone(),
+            response_format: Some(SpeechResponseFormat::Mp3),
+            speed: Some(*speed),
+        };
+        let response = client
+            .audio()
+            .speech(request)
+            .await
+            .map_err(|e| LyttError::OpenAI(format!("Speech API error: {}", e)))?;
+
+        tokio::fs::write(dest, &response.bytes).await?;
+        Ok(())
+    }
+}
+
+/// Run a local TTS command with the text on stdin.
+async fn run_local(command: &str, args: &[String], text: &str, dest: &Path) -> Result<()> {
+    let output = dest.to_string_lossy();
+    let args: Vec<String> = args.iter().map(|a| a.replace("{output}", &output)).collect();
+    debug!("Speaking with {} {:?}", command, args);
+
+    let mut child = match Command::new(command)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(LyttError::ToolNotFound(command.to_string()));
+        }
+        Err(e) => return Err(LyttError::ToolFailed(format!("{}: {}", command, e))),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+
+    let result = child.wait_with_output().await?;
+    if !result.status.success() || !dest.exists() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(LyttError::ToolFailed(format!("{} didn't write {}: {}", command, output, stderr.trim())));
+    }
+    Ok(())
+}
+
+/// Play an audio file with the first installed player, waiting until it ends.
+pub async fn play(path: &Path) -> Result<()> {
+    for (player, args) in PLAYERS {
+        let status = Command::new(player)
+            .args(args)
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+        match status {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => return Err(LyttError::ToolFailed(format!("{} exited with {}", player, status))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(LyttError::ToolFailed(format!("{}: {}", player, e))),
+        }
+    }
+    Err(LyttError::ToolNotFound("an audio player (mpv, ffplay, afplay or aplay)".to_string()))
+}
+
+/// The text of an answer as it should be read aloud: without citation
+/// markers, Markdown emphasis, headings or list bullets.
+pub fn speakable(text: &str) -> String {
+    static MARKUP: OnceLock<Regex> = OnceLock::new();
+    let markup = MARKUP.get_or_init(|| {
+        Regex::new(r#"(?m)\s*\[\d+(?:\s*,\s*\d+)*(?:\s*:\s*["“][^"”\]]*["”])?\]|[*_`]+|^\s*(?:#+|[-*]|\d+\.)\s+"#)
+            .expect("valid markup regex")
+    });
+    markup.replace_all(text, "").trim().to_string()
+}
+
+/// Split text into pieces of at most `max_chars`, at sentence ends where
+/// possible.
+fn split_for_speech(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for sentence in text.split_inclusive(['.', '!', '?', '\n']) {
+        if !current.is_empty() && current.chars().count() + sentence.chars().count() > max_chars {
+            pieces.push(std::mem::take(&mut current));
+        }
+        current.push_str(sentence);
+        // A single sentence longer than the limit is cut where it must be
+        while current.chars().count() > max_chars {
+            let cut = current.char_indices().nth(max_chars).map_or(current.len(), |(i, _)| i);
+            pieces.push(current[..cut].to_string());
+            current = current[cut..].to_string();
+        }
+    }
+    if !current.trim().is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn speech_model(model: &str) -> SpeechModel {
+    match model {
+        "tts-1" => SpeechModel::Tts1,
+        "tts-1-hd" => SpeechModel::Tts1Hd,
+        other => SpeechModel::Other(other.to_string()),
+    }
+}
+
+fn parse_voice(voice: &str) -> Result<Voice> {
+    match voice.to_lowercase().as_str() {
+        "alloy" => Ok(Voice::Alloy),
+        "echo" => Ok(Voice::Echo),
+        "fable" => Ok(Voice::Fable),
+        "onyx" => Ok(Voice::Onyx),
+        "nova" => Ok(Voice::Nova),
+        "shimmer" => Ok(Voice::Shimmer),
+        _ => Err(LyttError::Config(format!(
+            "Unknown voice: {}. Use alloy, echo, fable, onyx, nova, or shimmer.",
+            voice
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_voice() {
+        assert_eq!(parse_voice("Nova").unwrap(), Voice::Nova);
+        assert!(parse_voice("robot").is_err());
+        assert_eq!(speech_model("tts-1-hd"), SpeechModel::Tts1Hd);
+        assert_eq!(speech_model("gpt-4o-mini-tts"), SpeechModel::Other("gpt-4o-mini-tts".to_string()));
+
+        let local = TtsSettings { engine: "local".to_string(), ..Default::default() };
+        assert!(Speaker::from_settings(&local).is_err());
+    }
+
+    #[test]
+    fn test_speakable() {
+        let answer = "## Summary\n\nThe **key point** is pricing [1, 2].\n- Costs fell [3: \"by half\"].";
+        assert_eq!(speakable(answer), "Summary\n\nThe key point is pricing.\nCosts fell.");
+    }
+
+    #[test]
+    fn test_split_for_speech() {
+        let pieces = split_for_speech("One two. Three four. Five.", 12);
+        assert_eq!(pieces, ["One two.", " Three four.", " Five."]);
+        assert_eq!(split_for_speech("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(split_for_speech("Short.", 4096), ["Short."]);
+    }
+}
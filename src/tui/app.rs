@@ -0,0 +1,355 @@
+//! TUI application state and key handling.
+
+use crate::rag::ContextChunk;
+use crate::transcription::TranscriptSegment;
+use crate::vector_store::{Document, IndexedVideo};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Which pane has keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    /// Media list on the left.
+    Media,
+    /// Detail view on the right.
+    Detail,
+}
+
+/// What the detail pane shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    /// Chunk timeline of the selected media.
+    Chunks,
+    /// Transcript of a media, positioned at a timestamp.
+    Transcript,
+    /// Search results.
+    Results,
+}
+
+/// Text input mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMode {
+    Normal,
+    /// Typing a media list filter.
+    Filter,
+    /// Typing a search query.
+    Search,
+}
+
+/// Work the event loop must do in response to a key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    None,
+    /// Load the chunk timeline of a media.
+    LoadChunks(String),
+    /// Open a media's transcript at a timestamp.
+    OpenTranscript { video_id: String, seconds: f64 },
+    /// Run a semantic search.
+    Search(String),
+}
+
+/// TUI application state.
+pub struct App {
+    pub media: Vec<IndexedVideo>,
+    pub filter: String,
+    pub media_selected: usize,
+    pub chunks: Vec<Document>,
+    pub chunk_selected: usize,
+    pub transcript_title: String,
+    pub transcript: Vec<TranscriptSegment>,
+    pub transcript_selected: usize,
+    pub query: String,
+    pub results: Vec<ContextChunk>,
+    pub result_selected: usize,
+    pub input: String,
+    pub input_mode: InputMode,
+    pub focus: Focus,
+    pub view: View,
+    /// View to return to when leaving the transcript.
+    pub previous_view: View,
+    pub status: String,
+    pub should_quit: bool,
+}
+
+impl App {
+    /// Create the app with the indexed media.
+    pub fn new(media: Vec<IndexedVideo>) -> Self {
+        Self {
+            media,
+            filter: String::new(),
+            media_selected: 0,
+            chunks: Vec::new(),
+            chunk_selected: 0,
+            transcript_title: String::new(),
+            transcript: Vec::new(),
+            transcript_selected: 0,
+            query: String::new(),
+            results: Vec::new(),
+            result_selected: 0,
+            input: String::new(),
+            input_mode: InputMode::Normal,
+            focus: Focus::Media,
+            view: View::Chunks,
+            previous_view: View::Chunks,
+            status: "Enter: open  /: filter  s: search  Tab: switch pane  Esc: back  q: quit".to_string(),
+            should_quit: false,
+        }
+    }
+
+    /// Media matching the current filter (case-insensitive title or ID match).
+    pub fn filtered_media(&self) -> Vec<&IndexedVideo> {
+        let filter = self.filter.to_lowercase();
+        self.media
+            .iter()
+            .filter(|m| {
+                filter.is_empty()
+                    || m.video_title.to_lowercase().contains(&filter)
+                    || m.video_id.to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+
+    /// The currently selected media, if any.
+    pub fn selected_media(&self) -> Option<&IndexedVideo> {
+        self.filtered_media().get(self.media_selected).copied()
+    }
+
+    /// Show a media's chunk timeline.
+    pub fn set_chunks(&mut self, mut chunks: Vec<Document>) {
+        chunks.sort_by_key(|c| c.chunk_order);
+        self.chunks = chunks;
+        self.chunk_selected = 0;
+        self.view = View::Chunks;
+        self.focus = Focus::Detail;
+    }
+
+    /// Show a transcript, selecting the segment playing at `seconds`.
+    pub fn set_transcript(&mut self, title: String, segments: Vec<TranscriptSegment>, seconds: f64) {
+        self.transcript_selected = segments
+            .iter()
+            .position(|s| s.end_seconds > seconds)
+            .unwrap_or(0);
+        self.transcript_title = title;
+        self.transcript = segments;
+        if self.view != View::Transcript {
+            self.previous_view = self.view;
+        }
+        self.view = View::Transcript;
+        self.focus = Focus::Detail;
+    }
+
+    /// Show search results.
+    pub fn set_results(&mut self, query: String, results: Vec<ContextChunk>) {
+        self.status = format!("{} result(s) for \"{}\"", results.len(), query);
+        self.query = query;
+        self.results = results;
+        self.result_selected = 0;
+        self.view = View::Results;
+        self.focus = Focus::Detail;
+    }
+
+    /// Handle a key press and return any work for the event loop.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return Action::None;
+        }
+
+        match self.input_mode {
+            InputMode::Normal => self.handle_normal_key(key),
+            InputMode::Filter | InputMode::Search => self.handle_input_key(key),
+        }
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Esc => {
+                if self.input_mode == InputMode::Filter {
+                    self.filter.clear();
+                    self.media_selected = 0;
+                }
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Enter => {
+                let mode = self.input_mode;
+                self.input_mode = InputMode::Normal;
+                if mode == InputMode::Search {
+                    let query = std::mem::take(&mut self.input);
+                    if !query.trim().is_empty() {
+                        return Action::Search(query);
+                    }
+                }
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.sync_filter();
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.sync_filter();
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn sync_filter(&mut self) {
+        if self.input_mode == InputMode::Filter {
+            self.filter = self.input.clone();
+            self.media_selected = 0;
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('/') => {
+                self.input = self.filter.clone();
+                self.input_mode = InputMode::Filter;
+                self.focus = Focus::Media;
+            }
+            KeyCode::Char('s') => {
+                self.input.clear();
+                self.input_mode = InputMode::Search;
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Media => Focus::Detail,
+                    Focus::Detail => Focus::Media,
+                };
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.focus = Focus::Media,
+            KeyCode::Right | KeyCode::Char('l') => self.focus = Focus::Detail,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Char('g') | KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::Char('G') | KeyCode::End => self.move_selection(isize::MAX),
+            KeyCode::Esc => {
+                if self.focus == Focus::Detail && self.view == View::Transcript {
+                    self.view = self.previous_view;
+                } else {
+                    self.focus = Focus::Media;
+                }
+            }
+            KeyCode::Enter => return self.activate(),
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Open the selected item in the focused pane.
+    fn activate(&mut self) -> Action {
+        match (self.focus, self.view) {
+            (Focus::Media, _) => match self.selected_media() {
+                Some(media) => Action::LoadChunks(media.video_id.clone()),
+                None => Action::None,
+            },
+            (Focus::Detail, View::Chunks) => match self.chunks.get(self.chunk_selected) {
+                Some(chunk) => Action::OpenTranscript {
+                    video_id: chunk.video_id.clone(),
+                    seconds: chunk.start_seconds,
+                },
+                None => Action::None,
+            },
+            (Focus::Detail, View::Results) => match self.results.get(self.result_selected) {
+                Some(result) => Action::OpenTranscript {
+                    video_id: result.video_id.clone(),
+                    seconds: result.start_seconds,
+                },
+                None => Action::None,
+            },
+            (Focus::Detail, View::Transcript) => Action::None,
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let media_len = self.filtered_media().len();
+        let (selected, len) = match (self.focus, self.view) {
+            (Focus::Media, _) => (&mut self.media_selected, media_len),
+            (Focus::Detail, View::Chunks) => (&mut self.chunk_selected, self.chunks.len()),
+            (Focus::Detail, View::Transcript) => (&mut self.transcript_selected, self.transcript.len()),
+            (Focus::Detail, View::Results) => (&mut self.result_selected, self.results.len()),
+        };
+
+        if len == 0 {
+            *selected = 0;
+            return;
+        }
+
+        let next = (*selected as isize).saturating_add(delta);
+        *selected = next.clamp(0, len as isize - 1) as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn media(id: &str, title: &str) -> IndexedVideo {
+        IndexedVideo {
+            video_id: id.to_string(),
+            video_title: title.to_string(),
+            chunk_count: 3,
+            total_duration_seconds: 600.0,
+            indexed_at: Utc::now(),
+            language: None,
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_filter_media() {
+        let mut app = App::new(vec![media("a1", "Rust Ownership"), media("b2", "Go Channels")]);
+
+        app.handle_key(key(KeyCode::Char('/')));
+        for c in "rust".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.filtered_media().len(), 1);
+        assert_eq!(app.selected_media().unwrap().video_id, "a1");
+
+        // Esc clears the filter
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.filtered_media().len(), 2);
+    }
+
+    #[test]
+    fn test_navigation_and_actions() {
+        let mut app = App::new(vec![media("a1", "First"), media("b2", "Second")]);
+
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.media_selected, 1);
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::LoadChunks("b2".to_string()));
+
+        app.handle_key(key(KeyCode::Char('s')));
+        for c in "traits".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::Search("traits".to_string()));
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn test_transcript_positioning() {
+        let mut app = App::new(vec![]);
+        let segments = vec![
+            TranscriptSegment::new(0.0, 10.0, "one".to_string()),
+            TranscriptSegment::new(10.0, 20.0, "two".to_string()),
+            TranscriptSegment::new(20.0, 30.0, "three".to_string()),
+        ];
+        app.set_transcript("Test".to_string(), segments, 15.0);
+        assert_eq!(app.transcript_selected, 1);
+        assert_eq!(app.view, View::Transcript);
+
+        // Esc returns to the chunk timeline
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.view, View::Chunks);
+    }
+}
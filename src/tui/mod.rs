@@ -0,0 +1,103 @@
+//! Terminal UI for browsing the transcript library.
+//!
+//! Lists indexed media, shows chunk timelines, runs searches, and opens
+//! transcripts at a timestamp.
+
+mod app;
+mod ui;
+
+pub use app::App;
+
+use app::Action;
+use crate::error::Result;
+use crate::orchestrator::Orchestrator;
+use crate::rag::ContextBuilder;
+use crate::transcription::TranscriptSegment;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+use std::time::Duration;
+
+/// Run the TUI until the user quits.
+pub async fn run(orchestrator: &Orchestrator, search_limit: usize) -> Result<()> {
+    let media = orchestrator.vector_store().list_videos().await?;
+    let mut app = App::new(media);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, orchestrator, search_limit).await;
+    ratatui::restore();
+
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    orchestrator: &Orchestrator,
+    search_limit: usize,
+) -> Result<()> {
+    let context_builder = ContextBuilder::new(orchestrator.vector_store(), orchestrator.embedder())
+        .with_max_chunks(search_limit)
+        .with_min_score(0.3);
+
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.handle_key(key) {
+            Action::None => {}
+            Action::LoadChunks(video_id) => {
+                match orchestrator.vector_store().get_by_video_id(&video_id).await {
+                    Ok(chunks) => app.set_chunks(chunks),
+                    Err(e) => app.status = format!("Failed to load chunks: {}", e),
+                }
+            }
+            Action::OpenTranscript { video_id, seconds } => {
+                match load_transcript(orchestrator, &video_id).await {
+                    Ok((title, segments)) => app.set_transcript(title, segments, seconds),
+                    Err(e) => app.status = format!("Failed to load transcript: {}", e),
+                }
+            }
+            Action::Search(query) => {
+                app.status = format!("Searching for \"{}\"...", query);
+                terminal.draw(|frame| ui::draw(frame, app))?;
+
+                match context_builder.build(&query).await {
+                    Ok(results) => app.set_results(query, results),
+                    Err(e) => app.status = format!("Search failed: {}", e),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the stored transcript, falling back to indexed chunks for media
+/// transcribed before transcripts were stored.
+async fn load_transcript(
+    orchestrator: &Orchestrator,
+    video_id: &str,
+) -> Result<(String, Vec<TranscriptSegment>)> {
+    if let Some((title, transcript)) = orchestrator.sqlite_store().get_transcript(video_id)? {
+        return Ok((title, transcript.segments));
+    }
+
+    let mut chunks = orchestrator.vector_store().get_by_video_id(video_id).await?;
+    chunks.sort_by_key(|c| c.chunk_order);
+    let title = chunks.first().map(|c| c.video_title.clone()).unwrap_or_default();
+    let segments = chunks
+        .into_iter()
+        .map(|c| TranscriptSegment::new(c.start_seconds, c.end_seconds, c.content))
+        .collect();
+
+    Ok((title, segments))
+}
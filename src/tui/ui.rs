@@ -0,0 +1,205 @@
+//! TUI rendering.
+
+use super::app::{App, Focus, InputMode, View};
+use crate::audio_source::MediaMetadata;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+/// Draw the whole interface.
+pub fn draw(frame: &mut Frame, app: &App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(frame.area());
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(rows[0]);
+
+    draw_media(frame, app, columns[0]);
+    match app.view {
+        View::Chunks => draw_chunks(frame, app, columns[1]),
+        View::Transcript => draw_transcript(frame, app, columns[1]),
+        View::Results => draw_results(frame, app, columns[1]),
+    }
+    draw_status(frame, app, rows[1]);
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    Block::default().borders(Borders::ALL).border_style(style).title(title)
+}
+
+fn highlight() -> Style {
+    Style::default().add_modifier(Modifier::REVERSED)
+}
+
+fn draw_list(frame: &mut Frame, area: Rect, block: Block, items: Vec<ListItem>, selected: usize) {
+    let mut state = ListState::default();
+    if !items.is_empty() {
+        state.select(Some(selected));
+    }
+    let list = List::new(items).block(block).highlight_style(highlight());
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_media(frame: &mut Frame, app: &App, area: Rect) {
+    let media = app.filtered_media();
+    let title = if app.filter.is_empty() {
+        format!(" Media ({}) ", media.len())
+    } else {
+        format!(" Media ({}/{}) filter: {} ", media.len(), app.media.len(), app.filter)
+    };
+
+    let items: Vec<ListItem> = media
+        .iter()
+        .map(|m| {
+            ListItem::new(Line::from(vec![
+                Span::raw(m.video_title.clone()),
+                Span::styled(
+                    format!("  {} · {}", m.chunk_count, MediaMetadata::format_timestamp(m.total_duration_seconds)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    draw_list(frame, area, pane(title, app.focus == Focus::Media), items, app.media_selected);
+}
+
+fn draw_chunks(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match app.chunks.first() {
+        Some(chunk) => format!(" Timeline: {} ", chunk.video_title),
+        None => " Timeline (select media and press Enter) ".to_string(),
+    };
+
+    let width = area.width.saturating_sub(4) as usize;
+    let items: Vec<ListItem> = app
+        .chunks
+        .iter()
+        .map(|c| {
+            let range = format!(
+                "[{} - {}] ",
+                MediaMetadata::format_timestamp(c.start_seconds),
+                MediaMetadata::format_timestamp(c.end_seconds)
+            );
+            let heading = Line::from(vec![
+                Span::styled(range, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    c.section_title.clone().unwrap_or_default(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ]);
+            let preview: String = c.content.chars().take(width).collect();
+            ListItem::new(Text::from(vec![
+                heading,
+                Line::styled(preview, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    draw_list(frame, area, pane(title, app.focus == Focus::Detail), items, app.chunk_selected);
+}
+
+fn draw_transcript(frame: &mut Frame, app: &App, area: Rect) {
+    let title = format!(" Transcript: {} (Esc to go back) ", app.transcript_title);
+    let width = area.width.saturating_sub(12).max(20) as usize;
+
+    let items: Vec<ListItem> = app
+        .transcript
+        .iter()
+        .map(|s| {
+            let mut lines = Vec::new();
+            for (i, line) in wrap(&s.text, width).into_iter().enumerate() {
+                let prefix = if i == 0 {
+                    format!("{:>8}  ", MediaMetadata::format_timestamp(s.start_seconds))
+                } else {
+                    " ".repeat(10)
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, Style::default().fg(Color::Yellow)),
+                    Span::raw(line),
+                ]));
+            }
+            ListItem::new(Text::from(lines))
+        })
+        .collect();
+
+    draw_list(frame, area, pane(title, app.focus == Focus::Detail), items, app.transcript_selected);
+}
+
+fn draw_results(frame: &mut Frame, app: &App, area: Rect) {
+    let title = format!(" Search: {} ", app.query);
+    let width = area.width.saturating_sub(4) as usize;
+
+    let items: Vec<ListItem> = app
+        .results
+        .iter()
+        .map(|r| {
+            let heading = Line::from(vec![
+                Span::styled(format!("{:.2} ", r.score), Style::default().fg(Color::Green)),
+                Span::styled(r.video_title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" @ {}", r.timestamp), Style::default().fg(Color::Yellow)),
+            ]);
+            let preview: String = r.content.chars().take(width).collect();
+            ListItem::new(Text::from(vec![
+                heading,
+                Line::styled(preview, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    draw_list(frame, area, pane(title, app.focus == Focus::Detail), items, app.result_selected);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let (title, text) = match app.input_mode {
+        InputMode::Normal => (" lytt ", app.status.clone()),
+        InputMode::Filter => (" Filter (Enter to keep, Esc to clear) ", format!("/{}", app.input)),
+        InputMode::Search => (" Search (Enter to run, Esc to cancel) ", format!("?{}", app.input)),
+    };
+
+    let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(paragraph, area);
+}
+
+/// Greedy word wrap.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three four", 9), vec!["one two", "three", "four"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+}
@@ -0,0 +1,154 @@
+//! Up-front cost estimation from media duration.
+
+use super::{Pricing, UsageStage};
+use crate::config::{Settings, TranscriptionProvider};
+use serde::Serialize;
+
+/// Rough speech density used for estimates (tokens of transcript per audio minute).
+const TRANSCRIPT_TOKENS_PER_MINUTE: f64 = 200.0;
+
+/// Cleanup input per minute: word-level timestamp JSON is ~20x the plain text.
+const CLEANUP_INPUT_TOKENS_PER_MINUTE: f64 = 4000.0;
+
+/// Fixed prompt overhead per LLM call (system prompt + instructions).
+const PROMPT_OVERHEAD_TOKENS: f64 = 600.0;
+
+/// Length of audio parts sent to the cleanup model (see `TranscriptionProcessor`).
+const CLEANUP_PART_SECONDS: f64 = 300.0;
+
+/// One line of a cost estimate.
+#[derive(Debug, Clone, Serialize)]
+pub struct EstimateLine {
+    pub stage: UsageStage,
+    pub model: String,
+    pub cost_usd: f64,
+}
+
+/// Predicted cost of processing media.
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimate {
+    /// Duration the estimate is based on.
+    pub duration_seconds: f64,
+    /// Per-stage breakdown.
+    pub lines: Vec<EstimateLine>,
+}
+
+impl CostEstimate {
+    /// Total predicted cost in USD.
+    pub fn total(&self) -> f64 {
+        self.lines.iter().map(|l| l.cost_usd).sum()
+    }
+}
+
+/// Estimate the cost of transcribing, chunking, and embedding media of the given duration.
+pub fn estimate_transcription(settings: &Settings, pricing: &Pricing, duration_seconds: f64) -> CostEstimate {
+    let minutes = duration_seconds / 60.0;
+    let transcript_tokens = (minutes * TRANSCRIPT_TOKENS_PER_MINUTE).ceil() as u64;
+    let mut lines = Vec::new();
+
+    // Speech-to-text
+    let text_model = settings
+        .transcription
+        .processing
+        .text_model
+        .clone()
+        .filter(|m| !m.is_empty());
+    let models = match settings.transcription.provider {
+        // Captions are free but the estimate assumes the Whisper fallback
+        TranscriptionProvider::Whisper | TranscriptionProvider::Captions => vec![settings.transcription.model.clone()],
+        TranscriptionProvider::Fusion => std::iter::once(settings.transcription.processing.timestamp_model.clone())
+            .chain(text_model)
+            .collect(),
+        TranscriptionProvider::Gpt4o => vec![text_model.unwrap_or_else(|| "gpt-4o-transcribe".to_string())],
+    };
+    for model in models {
+        lines.push(EstimateLine {
+            stage: UsageStage::Transcription,
+            cost_usd: pricing.cost(&model, 0, 0, duration_seconds),
+            model,
+        });
+    }
+
+    // Cleanup/fusion, one call per audio part (GPT-4o-only output isn't cleaned up)
+    if settings.transcription.provider != TranscriptionProvider::Gpt4o {
+        let parts = (duration_seconds / CLEANUP_PART_SECONDS).ceil().max(1.0);
+        let cleanup_model = &settings.transcription.processing.cleanup_model;
+        let cleanup_input = minutes * CLEANUP_INPUT_TOKENS_PER_MINUTE + parts * PROMPT_OVERHEAD_TOKENS;
+        let cleanup_output = transcript_tokens as f64 * 1.5;
+        lines.push(EstimateLine {
+            stage: UsageStage::Cleanup,
+            model: cleanup_model.clone(),
+            cost_usd: pricing.cost(cleanup_model, cleanup_input as u64, cleanup_output as u64, 0.0),
+        });
+    }
+
+    // Semantic chunking, one call over the timestamped transcript
+    if !matches!(settings.chunking.strategy.as_str(), "temporal" | "sliding") {
+        let chunks = estimate_chunks(settings, duration_seconds) as f64;
+        let input = transcript_tokens as f64 * 1.3 + PROMPT_OVERHEAD_TOKENS;
+        let output = chunks * 60.0;
+        lines.push(EstimateLine {
+            stage: UsageStage::Chunking,
+            model: settings.chunking.model.clone(),
+            cost_usd: pricing.cost(&settings.chunking.model, input as u64, output as u64, 0.0),
+        });
+    }
+
+    // Embeddings over chunk content
+    lines.push(EstimateLine {
+        stage: UsageStage::Embedding,
+        model: settings.embedding.model.clone(),
+        cost_usd: pricing.cost(&settings.embedding.model, transcript_tokens, 0, 0.0),
+    });
+
+    CostEstimate {
+        duration_seconds,
+        lines,
+    }
+}
+
+/// Rough number of chunks media of the given duration is split into.
+pub fn estimate_chunks(settings: &Settings, duration_seconds: f64) -> usize {
+    let chunking = &settings.chunking;
+    let chunks = if chunking.strategy == "sliding" {
+        // Windows of chunk_tokens, each starting chunk_tokens - overlap_tokens after the last
+        let tokens = duration_seconds / 60.0 * TRANSCRIPT_TOKENS_PER_MINUTE;
+        let step = chunking.chunk_tokens.saturating_sub(chunking.overlap_tokens).max(1) as f64;
+        ((tokens - chunking.overlap_tokens as f64) / step).ceil()
+    } else {
+        (duration_seconds / chunking.target_chunk_seconds.max(1) as f64).ceil()
+    };
+    chunks.max(1.0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_scales_with_duration() {
+        let settings = Settings::default();
+        let pricing = Pricing::default();
+
+        let short = estimate_transcription(&settings, &pricing, 600.0);
+        let long = estimate_transcription(&settings, &pricing, 3600.0);
+
+        assert!(short.total() > 0.0);
+        assert!(long.total() > short.total());
+        // Whisper at $0.006/min dominates the audio line
+        let audio = &long.lines[0];
+        assert_eq!(audio.stage, UsageStage::Transcription);
+        assert!((audio.cost_usd - 0.36).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_chunks() {
+        let mut settings = Settings::default();
+        assert_eq!(estimate_chunks(&settings, 3600.0), 20);
+        assert_eq!(estimate_chunks(&settings, 10.0), 1);
+
+        // 60 minutes is ~12000 tokens: 400-token windows every 320 tokens
+        settings.chunking.strategy = "sliding".to_string();
+        assert_eq!(estimate_chunks(&settings, 3600.0), 38);
+    }
+}
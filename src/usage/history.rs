@@ -0,0 +1,110 @@
+//! Per-media processing history.
+//!
+//! Every pipeline run over a media item (transcription, import, rechunk) is
+//! logged with the configuration it ran under, how long it took and what it
+//! consumed, so a failure or a worse index can be traced back to the
+//! settings that produced it.
+
+use super::{Pricing, UsageRecord};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// How a pipeline run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    /// The media was indexed.
+    Completed,
+    /// Nothing was done: already indexed or a likely duplicate.
+    Skipped,
+    /// The run stopped with an error.
+    Failed,
+    /// The run was stopped with Ctrl+C.
+    Cancelled,
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunStatus::Completed => write!(f, "completed"),
+            RunStatus::Skipped => write!(f, "skipped"),
+            RunStatus::Failed => write!(f, "failed"),
+            RunStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for RunStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "completed" => Ok(RunStatus::Completed),
+            "skipped" => Ok(RunStatus::Skipped),
+            "failed" => Ok(RunStatus::Failed),
+            "cancelled" => Ok(RunStatus::Cancelled),
+            _ => Err(format!("Unknown run status: {}", s)),
+        }
+    }
+}
+
+/// One logged pipeline run over a media item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessingRun {
+    /// Media the run processed.
+    pub video_id: String,
+    /// Pipeline operation ("transcribe", "import", "rechunk").
+    pub operation: String,
+    /// When the run started.
+    pub started_at: DateTime<Utc>,
+    /// When the run ended.
+    pub finished_at: DateTime<Utc>,
+    /// How the run ended.
+    pub status: RunStatus,
+    /// Error message of a failed run.
+    pub error: Option<String>,
+    /// Transcription provider, for runs that transcribed audio.
+    pub transcription_provider: Option<String>,
+    /// Speech-to-text model, for runs that transcribed audio.
+    pub transcription_model: Option<String>,
+    /// Chunking strategy.
+    pub chunking_strategy: String,
+    /// Chunking model, for strategies that use an LLM.
+    pub chunking_model: Option<String>,
+    /// Fingerprint of the chunking settings and prompts (see
+    /// [`crate::chunking::chunking_fingerprint`]).
+    pub chunking_fingerprint: String,
+    /// Embedding model.
+    pub embedding_model: String,
+    /// Chunks indexed.
+    pub chunks: usize,
+    /// Prompt/input tokens across all API calls.
+    pub input_tokens: u64,
+    /// Completion/output tokens across all API calls.
+    pub output_tokens: u64,
+    /// Seconds of audio sent to speech-to-text.
+    pub audio_seconds: f64,
+    /// Estimated cost in USD.
+    pub cost_usd: f64,
+    /// Run ID of the command, request or job the run was part of, which its
+    /// log lines carry.
+    pub run_id: Option<String>,
+}
+
+impl ProcessingRun {
+    /// Wall-clock duration of the run in seconds.
+    pub fn duration_seconds(&self) -> f64 {
+        (self.finished_at - self.started_at).num_milliseconds() as f64 / 1000.0
+    }
+
+    /// Add up the API usage of the run.
+    pub fn with_usage(mut self, records: &[UsageRecord], pricing: &Pricing) -> Self {
+        for record in records {
+            self.input_tokens += record.input_tokens;
+            self.output_tokens += record.output_tokens;
+            self.audio_seconds += record.audio_seconds;
+            self.cost_usd += record.cost(pricing);
+        }
+        self
+    }
+}
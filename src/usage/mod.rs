@@ -0,0 +1,225 @@
+//! Usage tracking and cost estimation.
+//!
+//! API calls report token and audio usage to the tracker of the current
+//! pipeline run. Runs are scoped with [`UsageTracker::scope`], so components
+//! don't need a handle threaded through them; calls made outside a scope are
+//! simply not recorded.
+
+mod estimate;
+mod history;
+mod pricing;
+
+pub use estimate::{estimate_chunks, estimate_transcription, CostEstimate, EstimateLine};
+pub use history::{ProcessingRun, RunStatus};
+pub use pricing::{ModelPrice, Pricing};
+
+use async_openai::types::CompletionUsage;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+tokio::task_local! {
+    static CURRENT: UsageTracker;
+}
+
+/// Pipeline stage that consumed an API call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageStage {
+    /// Speech-to-text (billed per audio minute).
+    Transcription,
+    /// LLM cleanup/fusion of raw transcripts.
+    Cleanup,
+    /// Semantic chunking.
+    Chunking,
+    /// Embedding generation.
+    Embedding,
+    /// RAG answer generation.
+    Rag,
+    /// Agent and chat tool loops.
+    Agent,
+    /// Tag/channel rollups.
+    Rollup,
+    /// Periodic digests.
+    Digest,
+    /// Per-media summaries.
+    Summary,
+    /// Generated titles for untitled media.
+    Title,
+}
+
+impl std::fmt::Display for UsageStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsageStage::Transcription => write!(f, "transcription"),
+            UsageStage::Cleanup => write!(f, "cleanup"),
+            UsageStage::Chunking => write!(f, "chunking"),
+            UsageStage::Embedding => write!(f, "embedding"),
+            UsageStage::Rag => write!(f, "rag"),
+            UsageStage::Agent => write!(f, "agent"),
+            UsageStage::Rollup => write!(f, "rollup"),
+            UsageStage::Digest => write!(f, "digest"),
+            UsageStage::Summary => write!(f, "summary"),
+            UsageStage::Title => write!(f, "title"),
+        }
+    }
+}
+
+/// A single metered API call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// Stage that made the call.
+    pub stage: UsageStage,
+    /// Model used.
+    pub model: String,
+    /// Prompt/input tokens.
+    pub input_tokens: u64,
+    /// Completion/output tokens.
+    pub output_tokens: u64,
+    /// Seconds of audio processed.
+    pub audio_seconds: f64,
+}
+
+impl UsageRecord {
+    /// Cost of this call in USD using the given price table.
+    pub fn cost(&self, pricing: &Pricing) -> f64 {
+        pricing.cost(&self.model, self.input_tokens, self.output_tokens, self.audio_seconds)
+    }
+}
+
+/// Collects usage records for one pipeline run.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    records: Arc<Mutex<Vec<UsageRecord>>>,
+}
+
+impl UsageTracker {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run a future with this tracker as the current one.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT.scope(self.clone(), fut).await
+    }
+
+    /// Add a record.
+    pub fn push(&self, record: UsageRecord) {
+        if let Ok(mut records) = self.records.lock() {
+            records.push(record);
+        }
+    }
+
+    /// Snapshot of all records collected so far.
+    pub fn records(&self) -> Vec<UsageRecord> {
+        self.records.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    /// Total cost of all records in USD.
+    pub fn total_cost(&self, pricing: &Pricing) -> f64 {
+        self.records().iter().map(|r| r.cost(pricing)).sum()
+    }
+}
+
+/// Record usage on the current run's tracker (no-op outside a scope).
+pub fn record(record: UsageRecord) {
+    let _ = CURRENT.try_with(|tracker| tracker.push(record));
+}
+
+/// Record a chat completion call.
+pub fn record_chat(stage: UsageStage, model: &str, usage: Option<&CompletionUsage>) {
+    if let Some(usage) = usage {
+        record(UsageRecord {
+            stage,
+            model: model.to_string(),
+            input_tokens: usage.prompt_tokens as u64,
+            output_tokens: usage.completion_tokens as u64,
+            audio_seconds: 0.0,
+        });
+    }
+}
+
+/// Record an embedding call.
+pub fn record_embedding(model: &str, tokens: u32) {
+    record(UsageRecord {
+        stage: UsageStage::Embedding,
+        model: model.to_string(),
+        input_tokens: tokens as u64,
+        output_tokens: 0,
+        audio_seconds: 0.0,
+    });
+}
+
+/// Record a speech-to-text call.
+pub fn record_audio(model: &str, seconds: f64) {
+    record(UsageRecord {
+        stage: UsageStage::Transcription,
+        model: model.to_string(),
+        input_tokens: 0,
+        output_tokens: 0,
+        audio_seconds: seconds,
+    });
+}
+
+/// How to group usage in reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGroupBy {
+    Video,
+    Day,
+    Model,
+}
+
+impl std::str::FromStr for UsageGroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "video" | "media" => Ok(UsageGroupBy::Video),
+            "day" | "date" => Ok(UsageGroupBy::Day),
+            "model" => Ok(UsageGroupBy::Model),
+            _ => Err(format!("Unknown grouping: {}. Use video, day, or model.", s)),
+        }
+    }
+}
+
+/// Aggregated usage for one group in a report.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSummary {
+    /// Group key (video ID, date, or model).
+    pub key: String,
+    /// Number of distinct runs.
+    pub runs: u32,
+    /// Total input tokens.
+    pub input_tokens: u64,
+    /// Total output tokens.
+    pub output_tokens: u64,
+    /// Total audio seconds.
+    pub audio_seconds: f64,
+    /// Total cost in USD.
+    pub cost_usd: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tracker_scope() {
+        let tracker = UsageTracker::new();
+        tracker
+            .scope(async {
+                record_audio("whisper-1", 120.0);
+                record_embedding("text-embedding-3-small", 1000);
+            })
+            .await;
+
+        // Outside the scope nothing is recorded
+        record_audio("whisper-1", 60.0);
+
+        let records = tracker.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].stage, UsageStage::Transcription);
+        assert_eq!(records[1].input_tokens, 1000);
+    }
+}
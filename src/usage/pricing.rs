@@ -0,0 +1,114 @@
+//! Model price table.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Price for a single model (USD).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelPrice {
+    /// Price per million input tokens.
+    pub input_per_million: f64,
+    /// Price per million output tokens.
+    pub output_per_million: f64,
+    /// Price per minute of audio.
+    pub audio_per_minute: f64,
+}
+
+impl ModelPrice {
+    const fn tokens(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+            audio_per_minute: 0.0,
+        }
+    }
+
+    const fn audio(audio_per_minute: f64) -> Self {
+        Self {
+            input_per_million: 0.0,
+            output_per_million: 0.0,
+            audio_per_minute,
+        }
+    }
+}
+
+/// Built-in list prices. Overridable via `[usage.prices]` in config.
+const DEFAULT_PRICES: &[(&str, ModelPrice)] = &[
+    ("whisper-1", ModelPrice::audio(0.006)),
+    ("gpt-4o-transcribe", ModelPrice::audio(0.006)),
+    ("gpt-4o-mini-transcribe", ModelPrice::audio(0.003)),
+    ("gpt-4.1", ModelPrice::tokens(2.00, 8.00)),
+    ("gpt-4.1-mini", ModelPrice::tokens(0.40, 1.60)),
+    ("gpt-4.1-nano", ModelPrice::tokens(0.10, 0.40)),
+    ("gpt-4o", ModelPrice::tokens(2.50, 10.00)),
+    ("gpt-4o-mini", ModelPrice::tokens(0.15, 0.60)),
+    ("claude-opus-4-1", ModelPrice::tokens(15.00, 75.00)),
+    ("claude-sonnet-4-5", ModelPrice::tokens(3.00, 15.00)),
+    ("claude-sonnet-4-0", ModelPrice::tokens(3.00, 15.00)),
+    ("claude-haiku-4-5", ModelPrice::tokens(1.00, 5.00)),
+    ("claude-3-5-haiku-latest", ModelPrice::tokens(0.80, 4.00)),
+    ("text-embedding-3-small", ModelPrice::tokens(0.02, 0.0)),
+    ("text-embedding-3-large", ModelPrice::tokens(0.13, 0.0)),
+    ("text-embedding-ada-002", ModelPrice::tokens(0.10, 0.0)),
+];
+
+/// Lookup table from model name to price.
+#[derive(Debug, Clone)]
+pub struct Pricing {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl Pricing {
+    /// Built-in prices with the given overrides applied.
+    pub fn with_overrides(overrides: &HashMap<String, ModelPrice>) -> Self {
+        let mut prices: HashMap<String, ModelPrice> = DEFAULT_PRICES
+            .iter()
+            .map(|(model, price)| (model.to_string(), *price))
+            .collect();
+        for (model, price) in overrides {
+            prices.insert(model.clone(), *price);
+        }
+        Self { prices }
+    }
+
+    /// Price for a model, if known.
+    pub fn get(&self, model: &str) -> Option<ModelPrice> {
+        self.prices.get(model).copied()
+    }
+
+    /// Compute cost in USD. Unknown models are counted as free.
+    pub fn cost(&self, model: &str, input_tokens: u64, output_tokens: u64, audio_seconds: f64) -> f64 {
+        let price = self.get(model).unwrap_or_default();
+        input_tokens as f64 / 1_000_000.0 * price.input_per_million
+            + output_tokens as f64 / 1_000_000.0 * price.output_per_million
+            + audio_seconds / 60.0 * price.audio_per_minute
+    }
+}
+
+impl Default for Pricing {
+    fn default() -> Self {
+        Self::with_overrides(&HashMap::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost() {
+        let pricing = Pricing::default();
+        assert!((pricing.cost("whisper-1", 0, 0, 600.0) - 0.06).abs() < 1e-9);
+        assert!((pricing.cost("gpt-4o-mini", 1_000_000, 1_000_000, 0.0) - 0.75).abs() < 1e-9);
+        assert_eq!(pricing.cost("unknown-model", 1000, 1000, 60.0), 0.0);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut overrides = HashMap::new();
+        overrides.insert("whisper-1".to_string(), ModelPrice::audio(0.01));
+        let pricing = Pricing::with_overrides(&overrides);
+        assert!((pricing.cost("whisper-1", 0, 0, 60.0) - 0.01).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,371 @@
+//! In-memory HNSW graph for approximate nearest-neighbour search.
+//!
+//! The graph is built from the stored embeddings when the store opens and is
+//! kept in sync on every upsert and delete. It lives only in memory, so
+//! nothing is written to the database and it costs roughly the size of the
+//! embeddings in RAM. Deleted documents are tombstoned and the graph is
+//! rebuilt once tombstones outnumber live entries.
+
+use super::{cosine_similarity, DerivedFilter, DerivedIndex, DocType, Document, IndexKind};
+use crate::error::{LyttError, Result};
+use rusqlite::Connection;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::RwLock;
+
+/// Neighbours kept per node on the upper layers.
+const M: usize = 16;
+/// Neighbours kept per node on the bottom layer.
+const M0: usize = 2 * M;
+/// Candidate list size while inserting.
+const EF_CONSTRUCTION: usize = 100;
+/// Smallest candidate list size while searching.
+const MIN_EF_SEARCH: usize = 64;
+
+/// A similarity score paired with a node, ordered by score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored(f32, u32);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+#[derive(Debug)]
+struct Node {
+    id: String,
+    video_id: String,
+    derived: bool,
+    embedding: Vec<f32>,
+    /// Neighbours on each layer the node belongs to.
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+#[derive(Debug, Default)]
+struct Graph {
+    nodes: Vec<Node>,
+    by_id: HashMap<String, u32>,
+    entry: Option<u32>,
+    deleted: usize,
+    rng: u64,
+}
+
+impl Graph {
+    fn similarity(&self, query: &[f32], node: u32) -> f32 {
+        cosine_similarity(query, &self.nodes[node as usize].embedding)
+    }
+
+    /// Draw a layer with probability decaying by a factor of `M` per level.
+    fn random_level(&mut self) -> usize {
+        self.rng = self.rng.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let uniform = ((self.rng >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() / (M as f64).ln()) as usize
+    }
+
+    /// Best-first search of one layer, returning up to `ef` nodes, best first.
+    fn search_layer(&self, query: &[f32], entries: &[u32], ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        let mut found: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+
+        for &node in entries {
+            let scored = Scored(self.similarity(query, node), node);
+            candidates.push(scored);
+            found.push(Reverse(scored));
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = found.peek().map_or(f32::MIN, |r| r.0 .0);
+            if found.len() >= ef && current.0 < worst {
+                break;
+            }
+            let Some(neighbors) = self.nodes[current.1 as usize].neighbors.get(layer) else {
+                continue;
+            };
+            for &neighbor in neighbors {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let scored = Scored(self.similarity(query, neighbor), neighbor);
+                let worst = found.peek().map_or(f32::MIN, |r| r.0 .0);
+                if found.len() < ef || scored.0 > worst {
+                    candidates.push(scored);
+                    found.push(Reverse(scored));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut found: Vec<Scored> = found.into_iter().map(|r| r.0).collect();
+        found.sort_by(|a, b| b.cmp(a));
+        found
+    }
+
+    /// Descend from the entry point to `layer`, one greedy step per layer.
+    fn descend(&self, query: &[f32], layer: usize) -> Option<u32> {
+        let entry = self.entry?;
+        let mut current = entry;
+        let top = self.nodes[entry as usize].neighbors.len() - 1;
+        for l in (layer + 1..=top).rev() {
+            current = self.search_layer(query, &[current], 1, l)[0].1;
+        }
+        Some(current)
+    }
+
+    fn insert(&mut self, id: String, video_id: String, derived: bool, embedding: Vec<f32>) {
+        if let Some(old) = self.by_id.remove(&id) {
+            self.nodes[old as usize].deleted = true;
+            self.deleted += 1;
+        }
+
+        let level = self.random_level();
+        let index = self.nodes.len() as u32;
+        self.by_id.insert(id.clone(), index);
+        self.nodes.push(Node {
+            id,
+            video_id,
+            derived,
+            embedding,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(index);
+            return;
+        };
+        let top = self.nodes[entry as usize].neighbors.len() - 1;
+        let query = self.nodes[index as usize].embedding.clone();
+
+        let mut entries = vec![self.descend(&query, level).unwrap_or(entry)];
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, &entries, EF_CONSTRUCTION, layer);
+            let max = if layer == 0 { M0 } else { M };
+            let neighbors: Vec<u32> = found
+                .iter()
+                .filter(|s| s.1 != index && !self.nodes[s.1 as usize].deleted)
+                .take(M)
+                .map(|s| s.1)
+                .collect();
+
+            for &neighbor in &neighbors {
+                self.nodes[neighbor as usize].neighbors[layer].push(index);
+                if self.nodes[neighbor as usize].neighbors[layer].len() > max {
+                    self.prune(neighbor, layer, max);
+                }
+            }
+            self.nodes[index as usize].neighbors[layer] = neighbors;
+            entries = found.iter().map(|s| s.1).collect();
+        }
+
+        if level > top {
+            self.entry = Some(index);
+        }
+    }
+
+    /// Keep a node's `max` closest live neighbours on a layer.
+    fn prune(&mut self, node: u32, layer: usize, max: usize) {
+        let embedding = &self.nodes[node as usize].embedding;
+        let mut scored: Vec<Scored> = self.nodes[node as usize].neighbors[layer]
+            .iter()
+            .filter(|&&n| !self.nodes[n as usize].deleted)
+            .map(|&n| Scored(cosine_similarity(embedding, &self.nodes[n as usize].embedding), n))
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        scored.truncate(max);
+        self.nodes[node as usize].neighbors[layer] = scored.into_iter().map(|s| s.1).collect();
+    }
+
+    fn delete(&mut self, id: &str) {
+        if let Some(index) = self.by_id.remove(id) {
+            self.nodes[index as usize].deleted = true;
+            self.deleted += 1;
+        }
+    }
+
+    /// Rebuild without tombstones once they outnumber live entries.
+    fn maybe_compact(&mut self) {
+        if self.deleted <= self.by_id.len() {
+            return;
+        }
+        let nodes = std::mem::take(&mut self.nodes);
+        *self = Graph { rng: self.rng, ..Graph::default() };
+        for node in nodes.into_iter().filter(|n| !n.deleted) {
+            self.insert(node.id, node.video_id, node.derived, node.embedding);
+        }
+    }
+}
+
+/// HNSW index over the stored embeddings.
+#[derive(Debug, Default)]
+pub struct AnnIndex {
+    graph: RwLock<Graph>,
+}
+
+impl AnnIndex {
+    /// Create an empty index; fill it with `SqliteVectorStore::rebuild_index`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// IDs and similarities of about the `count` documents closest to the
+    /// query, best first.
+    pub fn search(&self, query_embedding: &[f32], count: usize, derived: DerivedFilter) -> Result<Vec<(String, f32)>> {
+        let graph = self.read()?;
+        let Some(entry) = graph.descend(query_embedding, 0) else {
+            return Ok(Vec::new());
+        };
+
+        let ef = count.max(MIN_EF_SEARCH);
+        Ok(graph
+            .search_layer(query_embedding, &[entry], ef, 0)
+            .into_iter()
+            .map(|s| (&graph.nodes[s.1 as usize], s.0))
+            .filter(|(node, _)| !node.deleted)
+            .filter(|(node, _)| match derived {
+                DerivedFilter::Include => true,
+                DerivedFilter::Exclude => !node.derived,
+                DerivedFilter::Only => node.derived,
+            })
+            .take(count)
+            .map(|(node, score)| (node.id.clone(), score))
+            .collect())
+    }
+
+    /// Add documents, replacing any already in the index.
+    pub fn insert(&self, docs: &[Document]) -> Result<()> {
+        let mut graph = self.write()?;
+        for doc in docs.iter().filter(|d| !d.embedding.is_empty()) {
+            graph.insert(doc.id.to_string(), doc.video_id.clone(), doc.doc_type == DocType::Derived, doc.embedding.clone());
+        }
+        graph.maybe_compact();
+        Ok(())
+    }
+
+    /// Remove every document of a media item.
+    pub fn remove_video(&self, video_id: &str) -> Result<()> {
+        let mut graph = self.write()?;
+        let ids: Vec<String> = graph
+            .nodes
+            .iter()
+            .filter(|n| !n.deleted && n.video_id == video_id)
+            .map(|n| n.id.clone())
+            .collect();
+        for id in ids {
+            graph.delete(&id);
+        }
+        graph.maybe_compact();
+        Ok(())
+    }
+
+    /// Remove documents by ID.
+    pub fn remove(&self, ids: &[String]) -> Result<()> {
+        let mut graph = self.write()?;
+        for id in ids {
+            graph.delete(id);
+        }
+        graph.maybe_compact();
+        Ok(())
+    }
+
+    /// Number of documents in the index.
+    pub fn document_count(&self) -> Result<usize> {
+        Ok(self.read()?.by_id.len())
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, Graph>> {
+        self.graph
+            .read()
+            .map_err(|e| LyttError::VectorStore(format!("Failed to acquire ANN index lock: {}", e)))
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, Graph>> {
+        self.graph
+            .write()
+            .map_err(|e| LyttError::VectorStore(format!("Failed to acquire ANN index lock: {}", e)))
+    }
+}
+
+impl DerivedIndex for AnnIndex {
+    fn kind(&self) -> IndexKind {
+        IndexKind::Ann
+    }
+
+    fn clear(&self, _conn: &Connection) -> Result<()> {
+        let mut graph = self.write()?;
+        *graph = Graph { rng: graph.rng, ..Graph::default() };
+        Ok(())
+    }
+
+    fn on_upsert(&self, _conn: &Connection, docs: &[Document]) -> Result<()> {
+        self.insert(docs)
+    }
+
+    fn on_delete(&self, _conn: &Connection, video_id: &str) -> Result<()> {
+        self.remove_video(video_id)
+    }
+
+    fn on_delete_documents(&self, _conn: &Connection, ids: &[String]) -> Result<()> {
+        self.remove(ids)
+    }
+
+    fn entry_count(&self, _conn: &Connection) -> Result<usize> {
+        self.document_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_store::test_vector;
+
+    #[test]
+    fn test_ann_recall() {
+        let vector = |seed: u64| test_vector(seed, 32);
+        let docs: Vec<Document> = (0..1000)
+            .map(|i| Document::new(format!("video{}", i % 100), "Title".to_string(), None, String::new(), 0.0, 10.0, vector(i), 0, None))
+            .collect();
+
+        let conn = Connection::open_in_memory().unwrap();
+        let index = AnnIndex::new();
+        index.on_upsert(&conn, &docs).unwrap();
+        assert_eq!(index.entry_count(&conn).unwrap(), 1000);
+
+        // Compare the top 10 against an exact scan
+        let mut hits = 0;
+        for seed in 5000..5020 {
+            let query = vector(seed);
+            let mut exact: Vec<(f32, String)> =
+                docs.iter().map(|d| (cosine_similarity(&query, &d.embedding), d.id.to_string())).collect();
+            exact.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let found = index.search(&query, 10, DerivedFilter::Include).unwrap();
+            assert_eq!(found.len(), 10);
+            hits += exact[..10].iter().filter(|(_, id)| found.iter().any(|(f, _)| f == id)).count();
+        }
+        assert!(hits >= 180, "recall@10 too low: {}/200", hits);
+
+        // Deleting most documents compacts the graph and keeps searches working
+        for v in 0..60 {
+            index.on_delete(&conn, &format!("video{}", v)).unwrap();
+        }
+        assert_eq!(index.entry_count(&conn).unwrap(), 400);
+        assert!(index.read().unwrap().nodes.len() < 1000);
+        let found = index.search(&docs[999].embedding, 1, DerivedFilter::Include).unwrap();
+        assert_eq!(found[0].0, docs[999].id.to_string());
+    }
+}
@@ -0,0 +1,743 @@
+//! Columnar vector store in Arrow IPC files.
+//!
+//! This stands in for a LanceDB backend: the `lancedb` crate pulls in a
+//! large async dependency tree (DataFusion, object_store, its own Arrow
+//! version) that lytt can't take on, so the store keeps Lance's layout ideas
+//! with the plain Arrow crates instead.
+//!
+//! Every media item is one fragment file under the store directory, holding
+//! its documents column by column. Opening the store reads only the columns
+//! the HNSW index and the media list need; document text is read from a
+//! fragment when a search or lookup returns it, so large libraries neither
+//! scan every embedding nor keep every transcript in memory. Adding or
+//! deleting a media item rewrites or removes only its own fragment. File
+//! I/O runs on tokio's blocking pool. Transcripts and other metadata stay in
+//! the local SQLite database.
+
+use super::{
+    cosine_similarity, AnnIndex, DerivedFilter, DocType, Document, IndexedVideo, SearchFilter, SearchResult,
+    VectorStore,
+};
+use crate::error::{LyttError, Result};
+use arrow_array::builder::{
+    Float32Builder, Float64Builder, Int32Builder, ListBuilder, StringBuilder, TimestampMicrosecondBuilder,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type, Int32Type, TimestampMicrosecondType};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_ipc::reader::FileReader;
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::{debug, info, instrument};
+use uuid::Uuid;
+
+/// File extension of fragment files.
+const FRAGMENT_EXTENSION: &str = "arrow";
+
+/// Columns read when the store opens: enough for the search index and the
+/// media list, without the document text.
+const INDEX_COLUMNS: &[&str] = &[
+    "id",
+    "video_id",
+    "video_title",
+    "end_seconds",
+    "embedding",
+    "chunk_order",
+    "indexed_at",
+    "doc_type",
+    "language",
+];
+
+/// Vector store keeping one Arrow IPC file per media item.
+pub struct ColumnarVectorStore {
+    dir: PathBuf,
+    schema: SchemaRef,
+    catalog: RwLock<Catalog>,
+    /// Held while a fragment is rewritten, so concurrent writes to the same
+    /// media item don't lose each other's documents.
+    writing: tokio::sync::Mutex<()>,
+    ann: AnnIndex,
+}
+
+/// What's known about the fragments without reading them.
+#[derive(Default)]
+struct Catalog {
+    /// Media items with a fragment.
+    videos: HashMap<String, Entry>,
+    /// Media item of every document, by document ID.
+    video_of: HashMap<String, String>,
+}
+
+/// A media item's fragment.
+struct Entry {
+    /// Listing of the media item; `None` when it holds only derived documents.
+    video: Option<IndexedVideo>,
+    /// IDs of its documents.
+    ids: Vec<String>,
+}
+
+impl Entry {
+    /// Describe a fragment from its documents, in chunk order.
+    fn of(video_id: &str, docs: &[Document]) -> Self {
+        let video = docs.first().filter(|d| d.doc_type == DocType::Source).map(|first| IndexedVideo {
+            video_id: video_id.to_string(),
+            video_title: first.video_title.clone(),
+            chunk_count: docs.len() as u32,
+            total_duration_seconds: docs.iter().map(|d| d.end_seconds).fold(0.0, f64::max),
+            indexed_at: docs.iter().map(|d| d.indexed_at).max().unwrap_or(first.indexed_at),
+            language: docs.iter().find_map(|d| d.language.clone()),
+        });
+        Self {
+            video,
+            ids: docs.iter().map(|d| d.id.to_string()).collect(),
+        }
+    }
+}
+
+impl Catalog {
+    fn insert(&mut self, video_id: &str, entry: Entry) {
+        self.remove(video_id);
+        for id in &entry.ids {
+            self.video_of.insert(id.clone(), video_id.to_string());
+        }
+        self.videos.insert(video_id.to_string(), entry);
+    }
+
+    fn remove(&mut self, video_id: &str) -> Option<Entry> {
+        let entry = self.videos.remove(video_id)?;
+        for id in &entry.ids {
+            self.video_of.remove(id);
+        }
+        Some(entry)
+    }
+}
+
+impl ColumnarVectorStore {
+    /// Open the store in `dir`, creating the directory if needed, and index
+    /// the embeddings of every fragment.
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let schema = Arc::new(document_schema());
+        let projection = INDEX_COLUMNS
+            .iter()
+            .map(|name| schema.index_of(name).map_err(arrow))
+            .collect::<Result<Vec<usize>>>()?;
+
+        let ann = AnnIndex::new();
+        let mut catalog = Catalog::default();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(FRAGMENT_EXTENSION) {
+                continue;
+            }
+            let docs = read_index_columns(&path, &projection)?;
+            if let Some(first) = docs.first() {
+                ann.insert(&docs)?;
+                let video_id = first.video_id.clone();
+                // Fragments were once named after the hex-encoded ID
+                let named = fragment_path(dir, &video_id);
+                if path != named {
+                    std::fs::rename(&path, &named)?;
+                }
+                catalog.insert(&video_id, Entry::of(&video_id, &docs));
+            }
+        }
+        info!(
+            "Opened columnar store at {} ({} media items)",
+            dir.display(),
+            catalog.videos.len()
+        );
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            schema,
+            catalog: RwLock::new(catalog),
+            writing: tokio::sync::Mutex::new(()),
+            ann,
+        })
+    }
+
+    /// Read the documents of media items from their fragments.
+    async fn load(&self, video_ids: Vec<String>) -> Result<Vec<Document>> {
+        let paths: Vec<PathBuf> = video_ids.iter().map(|id| fragment_path(&self.dir, id)).collect();
+        blocking(move || {
+            let mut docs = Vec::new();
+            for path in paths {
+                // A fragment deleted since the lookup has no documents left
+                if path.exists() {
+                    docs.extend(read_fragment(&path)?);
+                }
+            }
+            Ok(docs)
+        })
+        .await
+    }
+
+    /// Score every document passing the filter, reading one fragment at a time.
+    async fn scan(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        let video_ids: Vec<String> = {
+            let catalog = self.read()?;
+            match &filter.video_id {
+                Some(id) => catalog.videos.contains_key(id).then(|| id.clone()).into_iter().collect(),
+                None => catalog.videos.keys().cloned().collect(),
+            }
+        };
+        let paths: Vec<PathBuf> = video_ids.iter().map(|id| fragment_path(&self.dir, id)).collect();
+        let query_embedding = query_embedding.to_vec();
+        let filter = filter.clone();
+
+        blocking(move || {
+            let mut results: Vec<SearchResult> = Vec::new();
+            for path in paths.iter().filter(|p| p.exists()) {
+                results.extend(
+                    read_fragment(path)?
+                        .into_iter()
+                        .filter(|doc| filter.matches(doc))
+                        .map(|doc| SearchResult {
+                            score: cosine_similarity(&query_embedding, &doc.embedding),
+                            document: doc,
+                        })
+                        .filter(|r| r.score >= min_score),
+                );
+                results.sort_by(|a, b| b.score.total_cmp(&a.score));
+                results.truncate(limit);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, Catalog>> {
+        self.catalog
+            .read()
+            .map_err(|e| LyttError::VectorStore(format!("Failed to acquire columnar store lock: {}", e)))
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, Catalog>> {
+        self.catalog
+            .write()
+            .map_err(|e| LyttError::VectorStore(format!("Failed to acquire columnar store lock: {}", e)))
+    }
+}
+
+/// Run file I/O on tokio's blocking pool.
+async fn blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || span.in_scope(f))
+        .await
+        .map_err(|e| LyttError::VectorStore(format!("Columnar store task failed: {}", e)))?
+}
+
+/// Path of the fragment holding a media item's documents, named after a
+/// hash of its ID: IDs may be long or contain characters not allowed in
+/// file names. The ID itself is stored in the fragment's rows.
+fn fragment_path(dir: &Path, video_id: &str) -> PathBuf {
+    let name: String = Sha256::digest(video_id.as_bytes())
+        .iter()
+        .take(16)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    dir.join(format!("{}.{}", name, FRAGMENT_EXTENSION))
+}
+
+/// Write a media item's documents, replacing its fragment atomically.
+fn write_fragment(path: &Path, schema: &SchemaRef, docs: &[Document]) -> Result<()> {
+    let batch = to_batch(schema, docs)?;
+    let temp = path.with_extension("tmp");
+
+    let mut writer = FileWriter::try_new(File::create(&temp)?, schema).map_err(arrow)?;
+    writer.write(&batch).map_err(arrow)?;
+    writer.finish().map_err(arrow)?;
+    writer.into_inner().map_err(arrow)?.sync_all()?;
+    std::fs::rename(&temp, path)?;
+    debug!("Wrote {} documents to {}", docs.len(), path.display());
+    Ok(())
+}
+
+/// Read all documents from a fragment file.
+fn read_fragment(path: &Path) -> Result<Vec<Document>> {
+    let reader = FileReader::try_new(File::open(path)?, None).map_err(arrow)?;
+    let mut docs = Vec::new();
+    for batch in reader {
+        docs.extend(from_batch(&batch.map_err(arrow)?)?);
+    }
+    Ok(docs)
+}
+
+/// Read a fragment's index columns, as documents without text.
+fn read_index_columns(path: &Path, projection: &[usize]) -> Result<Vec<Document>> {
+    let reader = FileReader::try_new(File::open(path)?, Some(projection.to_vec())).map_err(arrow)?;
+    let mut docs = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(arrow)?;
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| LyttError::VectorStore(format!("Fragment is missing the {} column", name)))
+        };
+        let id = column("id")?.as_string::<i32>();
+        let video_id = column("video_id")?.as_string::<i32>();
+        let video_title = column("video_title")?.as_string::<i32>();
+        let end_seconds = column("end_seconds")?.as_primitive::<Float64Type>();
+        let embedding = column("embedding")?.as_list::<i32>();
+        let chunk_order = column("chunk_order")?.as_primitive::<Int32Type>();
+        let indexed_at = column("indexed_at")?.as_primitive::<TimestampMicrosecondType>();
+        let doc_type = column("doc_type")?.as_string::<i32>();
+        let language = column("language")?.as_string::<i32>();
+
+        for row in 0..batch.num_rows() {
+            let mut doc = Document::new(
+                video_id.value(row).to_string(),
+                video_title.value(row).to_string(),
+                None,
+                String::new(),
+                0.0,
+                end_seconds.value(row),
+                embedding.value(row).as_primitive::<Float32Type>().values().to_vec(),
+                chunk_order.value(row),
+                None,
+            );
+            doc.id = Uuid::parse_str(id.value(row))
+                .map_err(|e| LyttError::VectorStore(format!("Invalid document ID in fragment: {}", e)))?;
+            doc.indexed_at = DateTime::<Utc>::from_timestamp_micros(indexed_at.value(row)).unwrap_or_default();
+            doc.doc_type = doc_type.value(row).parse().unwrap_or_default();
+            doc.language = (!language.is_null(row)).then(|| language.value(row).to_string());
+            docs.push(doc);
+        }
+    }
+    Ok(docs)
+}
+
+/// Convert documents to a record batch.
+fn to_batch(schema: &SchemaRef, docs: &[Document]) -> Result<RecordBatch> {
+    let mut id = StringBuilder::new();
+    let mut video_id = StringBuilder::new();
+    let mut video_title = StringBuilder::new();
+    let mut section_title = StringBuilder::new();
+    let mut content = StringBuilder::new();
+    let mut start_seconds = Float64Builder::new();
+    let mut end_seconds = Float64Builder::new();
+    let mut embedding = ListBuilder::new(Float32Builder::new()).with_field(item_field(DataType::Float32));
+    let mut chunk_order = Int32Builder::new();
+    let mut source_created_at = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+    let mut indexed_at = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+    let mut doc_type = StringBuilder::new();
+    let mut provenance = ListBuilder::new(StringBuilder::new()).with_field(item_field(DataType::Utf8));
+    let mut keywords = ListBuilder::new(StringBuilder::new()).with_field(item_field(DataType::Utf8));
+    let mut language = StringBuilder::new();
+    let mut summary = StringBuilder::new();
+    let mut book = StringBuilder::new();
+    let mut author = StringBuilder::new();
+    let mut chapter = StringBuilder::new();
+
+    for doc in docs {
+        id.append_value(doc.id.to_string());
+        video_id.append_value(&doc.video_id);
+        video_title.append_value(&doc.video_title);
+        section_title.append_option(doc.section_title.as_deref());
+        content.append_value(&doc.content);
+        start_seconds.append_value(doc.start_seconds);
+        end_seconds.append_value(doc.end_seconds);
+        embedding.values().append_slice(&doc.embedding);
+        embedding.append(true);
+        chunk_order.append_value(doc.chunk_order);
+        source_created_at.append_option(doc.source_created_at.map(|t| t.timestamp_micros()));
+        indexed_at.append_value(doc.indexed_at.timestamp_micros());
+        doc_type.append_value(doc.doc_type.as_str());
+        for source in &doc.provenance {
+            provenance.values().append_value(source);
+        }
+        provenance.append(true);
+        for keyword in &doc.keywords {
+            keywords.values().append_value(keyword);
+        }
+        keywords.append(true);
+        language.append_option(doc.language.as_deref());
+        summary.append_option(doc.summary.as_deref());
+        book.append_option(doc.book.as_deref());
+        author.append_option(doc.author.as_deref());
+        chapter.append_option(doc.chapter.as_deref());
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(id.finish()),
+        Arc::new(video_id.finish()),
+        Arc::new(video_title.finish()),
+        Arc::new(section_title.finish()),
+        Arc::new(content.finish()),
+        Arc::new(start_seconds.finish()),
+        Arc::new(end_seconds.finish()),
+        Arc::new(embedding.finish()),
+        Arc::new(chunk_order.finish()),
+        Arc::new(source_created_at.finish()),
+        Arc::new(indexed_at.finish()),
+        Arc::new(doc_type.finish()),
+        Arc::new(provenance.finish()),
+        Arc::new(keywords.finish()),
+        Arc::new(language.finish()),
+        Arc::new(summary.finish()),
+        Arc::new(book.finish()),
+        Arc::new(author.finish()),
+        Arc::new(chapter.finish()),
+    ];
+    RecordBatch::try_new(schema.clone(), columns).map_err(arrow)
+}
+
+/// Schema of a fragment file.
+fn document_schema() -> Schema {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("video_id", DataType::Utf8, false),
+        Field::new("video_title", DataType::Utf8, false),
+        Field::new("section_title", DataType::Utf8, true),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("start_seconds", DataType::Float64, false),
+        Field::new("end_seconds", DataType::Float64, false),
+        Field::new("embedding", DataType::List(Arc::new(item_field(DataType::Float32))), false),
+        Field::new("chunk_order", DataType::Int32, false),
+        Field::new("source_created_at", timestamp.clone(), true),
+        Field::new("indexed_at", timestamp, false),
+        Field::new("doc_type", DataType::Utf8, false),
+        Field::new("provenance", DataType::List(Arc::new(item_field(DataType::Utf8))), false),
+        Field::new("keywords", DataType::List(Arc::new(item_field(DataType::Utf8))), false),
+        Field::new("language", DataType::Utf8, true),
+        Field::new("summary", DataType::Utf8, true),
+        Field::new("book", DataType::Utf8, true),
+        Field::new("author", DataType::Utf8, true),
+        Field::new("chapter", DataType::Utf8, true),
+    ])
+}
+
+/// Element field of a list column.
+fn item_field(data_type: DataType) -> Field {
+    Field::new("item", data_type, false)
+}
+
+/// Convert a record batch back to documents.
+fn from_batch(batch: &RecordBatch) -> Result<Vec<Document>> {
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| LyttError::VectorStore(format!("Fragment is missing the {} column", name)))
+    };
+    let id = column("id")?.as_string::<i32>();
+    let video_id = column("video_id")?.as_string::<i32>();
+    let video_title = column("video_title")?.as_string::<i32>();
+    let section_title = column("section_title")?.as_string::<i32>();
+    let content = column("content")?.as_string::<i32>();
+    let start_seconds = column("start_seconds")?.as_primitive::<Float64Type>();
+    let end_seconds = column("end_seconds")?.as_primitive::<Float64Type>();
+    let embedding = column("embedding")?.as_list::<i32>();
+    let chunk_order = column("chunk_order")?.as_primitive::<Int32Type>();
+    let source_created_at = column("source_created_at")?.as_primitive::<TimestampMicrosecondType>();
+    let indexed_at = column("indexed_at")?.as_primitive::<TimestampMicrosecondType>();
+    let doc_type = column("doc_type")?.as_string::<i32>();
+    let provenance = column("provenance")?.as_list::<i32>();
+    let keywords = column("keywords")?.as_list::<i32>();
+    let language = column("language")?.as_string::<i32>();
+    let summary = column("summary")?.as_string::<i32>();
+    // Fragments written before audiobooks were supported lack these
+    let book = batch.column_by_name("book").map(|c| c.as_string::<i32>());
+    let author = batch.column_by_name("author").map(|c| c.as_string::<i32>());
+    let chapter = batch.column_by_name("chapter").map(|c| c.as_string::<i32>());
+
+    let optional = |array: &arrow_array::StringArray, row: usize| {
+        (!array.is_null(row)).then(|| array.value(row).to_string())
+    };
+    let strings = |array: &arrow_array::ListArray, row: usize| -> Vec<String> {
+        array.value(row).as_string::<i32>().iter().flatten().map(String::from).collect()
+    };
+    let timestamp = |micros: i64| DateTime::<Utc>::from_timestamp_micros(micros).unwrap_or_default();
+
+    (0..batch.num_rows())
+        .map(|row| {
+            Ok(Document {
+                id: Uuid::parse_str(id.value(row))
+                    .map_err(|e| LyttError::VectorStore(format!("Invalid document ID in fragment: {}", e)))?,
+                video_id: video_id.value(row).to_string(),
+                video_title: video_title.value(row).to_string(),
+                section_title: optional(section_title, row),
+                content: content.value(row).to_string(),
+                start_seconds: start_seconds.value(row),
+                end_seconds: end_seconds.value(row),
+                embedding: embedding.value(row).as_primitive::<Float32Type>().values().to_vec(),
+                chunk_order: chunk_order.value(row),
+                source_created_at: (!source_created_at.is_null(row))
+                    .then(|| timestamp(source_created_at.value(row))),
+                indexed_at: timestamp(indexed_at.value(row)),
+                doc_type: doc_type.value(row).parse().unwrap_or_default(),
+                provenance: strings(provenance, row),
+                keywords: strings(keywords, row),
+                language: optional(language, row),
+                summary: optional(summary, row),
+                book: book.and_then(|a| optional(a, row)),
+                author: author.and_then(|a| optional(a, row)),
+                chapter: chapter.and_then(|a| optional(a, row)),
+            })
+        })
+        .collect()
+}
+
+/// Wrap an Arrow error.
+fn arrow(e: arrow_schema::ArrowError) -> LyttError {
+    LyttError::VectorStore(format!("Arrow error: {}", e))
+}
+
+#[async_trait]
+impl VectorStore for ColumnarVectorStore {
+    async fn upsert(&self, doc: &Document) -> Result<()> {
+        self.upsert_batch(std::slice::from_ref(doc)).await?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, docs), fields(count = docs.len()))]
+    async fn upsert_batch(&self, docs: &[Document]) -> Result<usize> {
+        let mut by_video: HashMap<String, Vec<Document>> = HashMap::new();
+        for doc in docs {
+            by_video.entry(doc.video_id.clone()).or_default().push(doc.clone());
+        }
+
+        let _writing = self.writing.lock().await;
+        for (video_id, new_docs) in by_video {
+            let path = fragment_path(&self.dir, &video_id);
+            let schema = self.schema.clone();
+            let merged = blocking(move || {
+                let mut merged = if path.exists() { read_fragment(&path)? } else { Vec::new() };
+                for doc in new_docs {
+                    match merged.iter_mut().find(|d| d.id == doc.id) {
+                        Some(existing) => *existing = doc,
+                        None => merged.push(doc),
+                    }
+                }
+                merged.sort_by_key(|d| d.chunk_order);
+                write_fragment(&path, &schema, &merged)?;
+                Ok(merged)
+            })
+            .await?;
+            self.write()?.insert(&video_id, Entry::of(&video_id, &merged));
+        }
+        self.ann.insert(docs)?;
+        Ok(docs.len())
+    }
+
+    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_with_threshold(query_embedding, limit, 0.0).await
+    }
+
+    async fn search_with_threshold(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_filtered(query_embedding, limit, min_score, &SearchFilter::default())
+            .await
+    }
+
+    #[instrument(skip(self, query_embedding, filter))]
+    async fn search_filtered(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        filter.reject_series("the columnar store")?;
+
+        // Narrow filters could exclude every ANN candidate, so scan instead
+        if filter.is_narrow() || filter.derived == DerivedFilter::Only {
+            return self.scan(query_embedding, limit, min_score, filter).await;
+        }
+
+        let hits: HashMap<String, f32> = self
+            .ann
+            .search(query_embedding, limit, filter.derived)?
+            .into_iter()
+            .filter(|(_, score)| *score >= min_score)
+            .collect();
+        let video_ids: Vec<String> = {
+            let catalog = self.read()?;
+            let mut ids: Vec<String> = hits.keys().filter_map(|id| catalog.video_of.get(id).cloned()).collect();
+            ids.sort();
+            ids.dedup();
+            ids
+        };
+
+        let mut results: Vec<SearchResult> = self
+            .load(video_ids)
+            .await?
+            .into_iter()
+            .filter(|doc| hits.contains_key(&doc.id.to_string()))
+            .map(|doc| SearchResult {
+                score: cosine_similarity(query_embedding, &doc.embedding),
+                document: doc,
+            })
+            .filter(|r| r.score >= min_score)
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    async fn delete_by_video_id(&self, video_id: &str) -> Result<usize> {
+        let _writing = self.writing.lock().await;
+        let Some(entry) = self.write()?.remove(video_id) else {
+            return Ok(0);
+        };
+        let path = fragment_path(&self.dir, video_id);
+        blocking(move || Ok(std::fs::remove_file(path)?)).await?;
+        self.ann.remove_video(video_id)?;
+        Ok(entry.ids.len())
+    }
+
+    async fn list_videos(&self) -> Result<Vec<IndexedVideo>> {
+        let mut videos: Vec<IndexedVideo> = self.read()?.videos.values().filter_map(|e| e.video.clone()).collect();
+        videos.sort_by_key(|v| std::cmp::Reverse(v.indexed_at));
+        Ok(videos)
+    }
+
+    async fn get_video(&self, video_id: &str) -> Result<Option<IndexedVideo>> {
+        Ok(self.read()?.videos.get(video_id).and_then(|e| e.video.clone()))
+    }
+
+    async fn is_video_indexed(&self, video_id: &str) -> Result<bool> {
+        Ok(self.read()?.videos.contains_key(video_id))
+    }
+
+    async fn get_by_video_id(&self, video_id: &str) -> Result<Vec<Document>> {
+        if !self.is_video_indexed(video_id).await? {
+            return Ok(Vec::new());
+        }
+        self.load(vec![video_id.to_string()]).await
+    }
+
+    async fn document_count(&self) -> Result<usize> {
+        Ok(self.read()?.video_of.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_columnar_store_persists_fragments() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ColumnarVectorStore::open(dir.path()).unwrap();
+
+        let doc = |video: &str, order: i32, embedding: Vec<f32>| {
+            Document::new(
+                video.to_string(),
+                format!("Title {}", video),
+                Some("Intro".to_string()),
+                format!("Chunk {}", order),
+                order as f64 * 30.0,
+                (order + 1) as f64 * 30.0,
+                embedding,
+                order,
+                Some(Utc::now()),
+            )
+            .with_keywords(vec!["rust".to_string()])
+            .with_language(Some("en".to_string()))
+        };
+        store
+            .upsert_batch(&[doc("a/1", 0, vec![1.0, 0.0, 0.0]), doc("a/1", 1, vec![0.0, 1.0, 0.0])])
+            .await
+            .unwrap();
+        store.upsert(&doc("b", 0, vec![0.0, 0.0, 1.0])).await.unwrap();
+
+        // Everything survives reopening
+        drop(store);
+        let store = ColumnarVectorStore::open(dir.path()).unwrap();
+        assert_eq!(store.document_count().await.unwrap(), 3);
+        let docs = store.get_by_video_id("a/1").await.unwrap();
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[1].content, "Chunk 1");
+        assert_eq!(docs[0].keywords, vec!["rust".to_string()]);
+        assert_eq!(docs[0].section_title.as_deref(), Some("Intro"));
+        assert!(docs[0].source_created_at.is_some());
+
+        let results = store.search(&[0.0, 0.9, 0.1], 1).await.unwrap();
+        assert_eq!(results[0].document.content, "Chunk 1");
+        let filtered = store
+            .search_filtered(&[0.0, 0.9, 0.1], 5, 0.0, &SearchFilter::default().with_video("b"))
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+
+        assert_eq!(store.delete_by_video_id("a/1").await.unwrap(), 2);
+        assert!(!store.is_video_indexed("a/1").await.unwrap());
+        assert_eq!(store.list_videos().await.unwrap().len(), 1);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // IDs too long for a file name still get a fragment
+        let long_id = format!("web_{}", "x".repeat(400));
+        store.upsert(&doc(&long_id, 0, vec![1.0, 0.0, 0.0])).await.unwrap();
+        drop(store);
+        let store = ColumnarVectorStore::open(dir.path()).unwrap();
+        assert!(store.is_video_indexed(&long_id).await.unwrap());
+        assert_eq!(store.delete_by_video_id(&long_id).await.unwrap(), 1);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_columnar_store_reads_text_on_demand() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(ColumnarVectorStore::open(dir.path()).unwrap());
+
+        // Writes to one media item from several tasks all land in its fragment
+        let writes: Vec<_> = (0..8)
+            .map(|order| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    let doc = Document::new(
+                        "talk".to_string(),
+                        "Talk".to_string(),
+                        None,
+                        format!("Chunk {}", order),
+                        0.0,
+                        10.0,
+                        vec![1.0, order as f32, 0.0],
+                        order,
+                        None,
+                    );
+                    store.upsert(&doc).await
+                })
+            })
+            .collect();
+        for write in writes {
+            write.await.unwrap().unwrap();
+        }
+        drop(store);
+
+        // Opening reads the index columns only; search results come with their text
+        let path = fragment_path(dir.path(), "talk");
+        let schema = document_schema();
+        let projection: Vec<usize> = INDEX_COLUMNS.iter().map(|c| schema.index_of(c).unwrap()).collect();
+        let skeletons = read_index_columns(&path, &projection).unwrap();
+        assert_eq!(skeletons.len(), 8);
+        assert!(skeletons.iter().all(|d| d.content.is_empty()));
+
+        let store = ColumnarVectorStore::open(dir.path()).unwrap();
+        assert_eq!(store.list_videos().await.unwrap()[0].chunk_count, 8);
+        let results = store.search(&[1.0, 0.0, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].document.content, "Chunk 0");
+    }
+}
@@ -0,0 +1,100 @@
+//! Secondary indexes derived from the documents table.
+//!
+//! Structures such as ANN graphs, full-text tables, topic clusters and
+//! quantized embeddings are
+//! built from stored documents. They register with the SQLite store, which
+//! keeps them up to date on every upsert and delete and can rebuild them from
+//! scratch (`lytt index rebuild`).
+
+use super::Document;
+use crate::error::Result;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Kind of derived index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexKind {
+    /// Approximate nearest-neighbour index over embeddings.
+    Ann,
+    /// Full-text search index over chunk content.
+    Fts,
+    /// Topic clusters over embeddings.
+    Topics,
+    /// Quantized embeddings for two-stage search.
+    Quantized,
+}
+
+impl IndexKind {
+    /// All index kinds.
+    pub const ALL: [IndexKind; 4] = [IndexKind::Ann, IndexKind::Fts, IndexKind::Topics, IndexKind::Quantized];
+}
+
+impl std::str::FromStr for IndexKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ann" => Ok(IndexKind::Ann),
+            "fts" => Ok(IndexKind::Fts),
+            "topics" => Ok(IndexKind::Topics),
+            "quantized" => Ok(IndexKind::Quantized),
+            _ => Err(format!("Unknown index: {}. Use ann, fts, topics, or quantized.", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for IndexKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexKind::Ann => write!(f, "ann"),
+            IndexKind::Fts => write!(f, "fts"),
+            IndexKind::Topics => write!(f, "topics"),
+            IndexKind::Quantized => write!(f, "quantized"),
+        }
+    }
+}
+
+/// A secondary structure maintained alongside the documents table.
+///
+/// Methods receive the connection the document change is made on, so
+/// `on_upsert` and `on_delete` are applied in the same transaction as the
+/// change when the store uses one. Pooled connections may call them
+/// concurrently.
+pub trait DerivedIndex: Send + Sync {
+    /// Which index this is.
+    fn kind(&self) -> IndexKind;
+
+    /// Remove every entry (first step of a rebuild).
+    fn clear(&self, conn: &Connection) -> Result<()>;
+
+    /// Add or replace entries for the given documents.
+    fn on_upsert(&self, conn: &Connection, docs: &[Document]) -> Result<()>;
+
+    /// Remove entries for all documents of a media item.
+    fn on_delete(&self, conn: &Connection, video_id: &str) -> Result<()>;
+
+    /// Remove entries for individual documents (e.g. when pruning).
+    fn on_delete_documents(&self, conn: &Connection, ids: &[String]) -> Result<()>;
+
+    /// Number of documents currently covered by the index.
+    fn entry_count(&self, conn: &Connection) -> Result<usize>;
+}
+
+/// Result of a consistency check on one index.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    /// Index checked.
+    pub kind: IndexKind,
+    /// Documents covered by the index.
+    pub entries: usize,
+    /// Documents in the store.
+    pub documents: usize,
+}
+
+impl IndexStatus {
+    /// Whether the index covers exactly the stored documents.
+    pub fn is_consistent(&self) -> bool {
+        self.entries == self.documents
+    }
+}
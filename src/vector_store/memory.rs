@@ -2,7 +2,7 @@
 //!
 //! Useful for testing and small datasets.
 
-use super::{cosine_similarity, Document, IndexedVideo, SearchResult, VectorStore};
+use super::{cosine_similarity, DocType, Document, IndexedVideo, SearchFilter, SearchResult, VectorStore};
 use crate::error::Result;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -54,10 +54,23 @@ impl VectorStore for MemoryVectorStore {
         limit: usize,
         min_score: f32,
     ) -> Result<Vec<SearchResult>> {
+        self.search_filtered(query_embedding, limit, min_score, &SearchFilter::default())
+            .await
+    }
+
+    async fn search_filtered(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        filter.reject_series("the in-memory store")?;
         let docs = self.documents.read().unwrap();
 
         let mut results: Vec<SearchResult> = docs
             .values()
+            .filter(|doc| filter.matches(doc))
             .map(|doc| {
                 let score = cosine_similarity(query_embedding, &doc.embedding);
                 SearchResult {
@@ -86,7 +99,7 @@ impl VectorStore for MemoryVectorStore {
 
         let mut video_map: HashMap<String, IndexedVideo> = HashMap::new();
 
-        for doc in docs.values() {
+        for doc in docs.values().filter(|d| d.doc_type == DocType::Source) {
             let entry = video_map.entry(doc.video_id.clone()).or_insert_with(|| {
                 IndexedVideo {
                     video_id: doc.video_id.clone(),
@@ -94,10 +107,14 @@ impl VectorStore for MemoryVectorStore {
                     chunk_count: 0,
                     total_duration_seconds: 0.0,
                     indexed_at: doc.indexed_at,
+                    language: None,
                 }
             });
 
             entry.chunk_count += 1;
+            if entry.language.is_none() {
+                entry.language = doc.language.clone();
+            }
             if doc.end_seconds > entry.total_duration_seconds {
                 entry.total_duration_seconds = doc.end_seconds;
             }
@@ -107,7 +124,7 @@ impl VectorStore for MemoryVectorStore {
         }
 
         let mut videos: Vec<IndexedVideo> = video_map.into_values().collect();
-        videos.sort_by(|a, b| b.indexed_at.cmp(&a.indexed_at));
+        videos.sort_by_key(|v| std::cmp::Reverse(v.indexed_at));
 
         Ok(videos)
     }
@@ -182,5 +199,8 @@ mod tests {
         let videos = store.list_videos().await.unwrap();
         assert_eq!(videos.len(), 1);
         assert_eq!(videos[0].chunk_count, 2);
+
+        let series = SearchFilter::default().with_series("lectures");
+        assert!(store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &series).await.is_err());
     }
 }
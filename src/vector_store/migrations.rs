@@ -0,0 +1,459 @@
+//! Versioned schema migrations for the SQLite store.
+//!
+//! The schema version is kept in SQLite's `user_version` header field.
+//! Opening a writable store applies every migration above it, in order and
+//! each in its own transaction, so existing databases upgrade automatically.
+//! To change the schema, append a migration to `MIGRATIONS`; never edit one
+//! that has been released.
+
+use crate::error::{LyttError, Result};
+use rusqlite::{Connection, TransactionBehavior};
+use tracing::info;
+
+/// One schema change.
+struct Migration {
+    description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Every migration, oldest first. A database at version N has had the first
+/// N applied.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "baseline schema",
+        apply: baseline,
+    },
+    Migration {
+        description: "record where media came from",
+        apply: media_sources,
+    },
+    Migration {
+        description: "manifest of ingested local files",
+        apply: ingested_files,
+    },
+    Migration {
+        description: "chunking configuration per media item",
+        apply: chunking_fingerprints,
+    },
+    Migration {
+        description: "processing log",
+        apply: processing_log,
+    },
+    Migration {
+        description: "audiobook book, author and chapter of documents",
+        apply: audiobooks,
+    },
+    Migration {
+        description: "series of media",
+        apply: series,
+    },
+    Migration {
+        description: "run IDs in the processing log",
+        apply: processing_run_ids,
+    },
+];
+
+/// Schema version this build creates and understands.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.len() as u32
+}
+
+/// Schema version recorded in the database.
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Bring the database up to the latest schema; returns the number of
+/// migrations applied.
+pub fn run(conn: &mut Connection) -> Result<usize> {
+    let mut applied = 0;
+    loop {
+        // An immediate transaction takes the write lock up front, so two
+        // processes opening the same database can't apply a migration twice
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let version = schema_version(&tx)?;
+        if version > latest_version() {
+            return Err(LyttError::VectorStore(format!(
+                "Database schema version {} is newer than this version of lytt supports ({}); upgrade lytt",
+                version,
+                latest_version()
+            )));
+        }
+        let Some(migration) = MIGRATIONS.get(version as usize) else {
+            return Ok(applied);
+        };
+
+        (migration.apply)(&tx)?;
+        tx.pragma_update(None, "user_version", version + 1)?;
+        tx.commit()?;
+        info!("Applied database migration {}: {}", version + 1, migration.description);
+        applied += 1;
+    }
+}
+
+/// Version 1: every table as of the introduction of versioned migrations.
+///
+/// Databases created before then have no version but may already have some
+/// or all of these tables, so the tables are created only if missing and
+/// columns added since the first release are added only if missing.
+fn baseline(conn: &Connection) -> Result<()> {
+    conn.execute_batch(BASELINE_SCHEMA)?;
+
+    let columns = table_columns(conn, "documents")?;
+    let document_columns = [
+        ("doc_type", "TEXT NOT NULL DEFAULT 'source'"),
+        ("provenance", "TEXT"),
+        ("keywords", "TEXT"),
+        ("language", "TEXT"),
+        ("summary", "TEXT"),
+        ("embedding_format", "TEXT NOT NULL DEFAULT 'f32'"),
+    ];
+    for (name, definition) in document_columns {
+        if !columns.iter().any(|c| c == name) {
+            conn.execute_batch(&format!("ALTER TABLE documents ADD COLUMN {} {};", name, definition))?;
+        }
+    }
+
+    if !table_columns(conn, "retrieval_stats")?.iter().any(|c| c == "citation_count") {
+        conn.execute_batch("ALTER TABLE retrieval_stats ADD COLUMN citation_count INTEGER NOT NULL DEFAULT 0;")?;
+    }
+
+    Ok(())
+}
+
+/// Version 2: the source type and URL or file path of each media item, so
+/// `lytt open` can find local files again.
+fn media_sources(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE media_sources (
+            video_id TEXT PRIMARY KEY,
+            source_type TEXT NOT NULL,
+            source_url TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Version 3: content hashes of local files already ingested from a
+/// directory, so re-running an archive import skips them.
+fn ingested_files(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE ingested_files (
+            content_hash TEXT PRIMARY KEY,
+            path TEXT NOT NULL,
+            video_id TEXT NOT NULL,
+            ingested_at TEXT NOT NULL
+        );
+        CREATE INDEX idx_ingested_files_video ON ingested_files(video_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Version 4: a fingerprint of the chunking settings and prompts each media
+/// item was last chunked with, so `lytt rechunk --stale` can skip the rest.
+fn chunking_fingerprints(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE chunking_fingerprints (
+            video_id TEXT PRIMARY KEY,
+            fingerprint TEXT NOT NULL,
+            chunked_at TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Version 5: one row per pipeline run over a media item, with the
+/// configuration it ran under, for `lytt history`.
+fn processing_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE processing_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            video_id TEXT NOT NULL,
+            operation TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            finished_at TEXT NOT NULL,
+            status TEXT NOT NULL,
+            error TEXT,
+            transcription_provider TEXT,
+            transcription_model TEXT,
+            chunking_strategy TEXT NOT NULL,
+            chunking_model TEXT,
+            chunking_fingerprint TEXT NOT NULL,
+            embedding_model TEXT NOT NULL,
+            chunks INTEGER NOT NULL DEFAULT 0,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            audio_seconds REAL NOT NULL DEFAULT 0,
+            cost_usd REAL NOT NULL DEFAULT 0
+        );
+        CREATE INDEX idx_processing_log_video ON processing_log(video_id, started_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Version 6: the book, author and chapter of each document, so answers
+/// from audiobooks can cite chapters, and the parts each audiobook was
+/// joined from.
+fn audiobooks(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE documents ADD COLUMN book TEXT;
+        ALTER TABLE documents ADD COLUMN author TEXT;
+        ALTER TABLE documents ADD COLUMN chapter TEXT;
+
+        CREATE TABLE audiobooks (
+            video_id TEXT PRIMARY KEY,
+            book TEXT NOT NULL,
+            author TEXT,
+            parts TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Version 7: series grouping media (a course, a podcast season, a meeting
+/// series), each media item in at most one, numbered by episode.
+fn series(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE series (
+            name TEXT PRIMARY KEY,
+            description TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE series_media (
+            video_id TEXT PRIMARY KEY,
+            series TEXT NOT NULL,
+            episode INTEGER NOT NULL
+        );
+        CREATE INDEX idx_series_media_series ON series_media(series, episode);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Version 8: the run ID of each logged pipeline run, matching the `run_id`
+/// of its log lines.
+fn processing_run_ids(conn: &Connection) -> Result<()> {
+    conn.execute_batch("ALTER TABLE processing_log ADD COLUMN run_id TEXT;")?;
+    Ok(())
+}
+
+/// Column names of a table.
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt.query_map([], |row| row.get(1))?.collect::<rusqlite::Result<_>>()?;
+    Ok(columns)
+}
+
+const BASELINE_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS documents (
+        id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        video_title TEXT NOT NULL,
+        section_title TEXT,
+        content TEXT NOT NULL,
+        start_seconds REAL NOT NULL,
+        end_seconds REAL NOT NULL,
+        embedding BLOB NOT NULL,
+        chunk_order INTEGER NOT NULL,
+        source_created_at TEXT,
+        indexed_at TEXT NOT NULL,
+        doc_type TEXT NOT NULL DEFAULT 'source',
+        provenance TEXT,
+        keywords TEXT,
+        language TEXT,
+        summary TEXT,
+        embedding_format TEXT NOT NULL DEFAULT 'f32'
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_documents_video_id ON documents(video_id);
+    CREATE INDEX IF NOT EXISTS idx_documents_indexed_at ON documents(indexed_at);
+
+    CREATE TABLE IF NOT EXISTS transcripts (
+        video_id TEXT PRIMARY KEY,
+        video_title TEXT NOT NULL,
+        transcript_json TEXT NOT NULL,
+        duration_seconds REAL NOT NULL,
+        transcribed_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS usage_records (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        run_id TEXT NOT NULL,
+        operation TEXT NOT NULL,
+        video_id TEXT,
+        stage TEXT NOT NULL,
+        model TEXT NOT NULL,
+        input_tokens INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        audio_seconds REAL NOT NULL,
+        cost_usd REAL NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS media_tags (
+        video_id TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (video_id, tag)
+    );
+
+    CREATE TABLE IF NOT EXISTS rollups (
+        id TEXT PRIMARY KEY,
+        tag TEXT NOT NULL,
+        period TEXT NOT NULL,
+        period_start TEXT,
+        period_end TEXT NOT NULL,
+        content TEXT NOT NULL,
+        source_video_ids TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS retrieval_stats (
+        document_id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        retrieval_count INTEGER NOT NULL DEFAULT 0,
+        citation_count INTEGER NOT NULL DEFAULT 0,
+        last_retrieved_at TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS summaries (
+        video_id TEXT PRIMARY KEY,
+        video_title TEXT NOT NULL,
+        overview TEXT NOT NULL,
+        chunk_summaries TEXT NOT NULL,
+        model TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS generated_titles (
+        video_id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        original_title TEXT NOT NULL,
+        tags TEXT NOT NULL,
+        model TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS embedding_info (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        model TEXT NOT NULL,
+        dimensions INTEGER NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS corpus_version (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        version INTEGER NOT NULL
+    );
+    INSERT OR IGNORE INTO corpus_version (id, version) VALUES (1, 0);
+
+    CREATE TRIGGER IF NOT EXISTS documents_version_insert AFTER INSERT ON documents
+    BEGIN UPDATE corpus_version SET version = version + 1; END;
+    CREATE TRIGGER IF NOT EXISTS documents_version_update AFTER UPDATE ON documents
+    BEGIN UPDATE corpus_version SET version = version + 1; END;
+    CREATE TRIGGER IF NOT EXISTS documents_version_delete AFTER DELETE ON documents
+    BEGIN UPDATE corpus_version SET version = version + 1; END;
+
+    CREATE TABLE IF NOT EXISTS answer_cache (
+        key TEXT PRIMARY KEY,
+        version INTEGER NOT NULL,
+        answer TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS glossary_terms (
+        video_id TEXT NOT NULL,
+        term TEXT NOT NULL,
+        definition TEXT NOT NULL,
+        video_title TEXT NOT NULL,
+        timestamp TEXT,
+        model TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        PRIMARY KEY (video_id, term)
+    );
+
+    CREATE TABLE IF NOT EXISTS topics (
+        id INTEGER PRIMARY KEY,
+        label TEXT NOT NULL,
+        description TEXT NOT NULL,
+        centroid BLOB NOT NULL,
+        model TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS document_topics (
+        document_id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        topic_id INTEGER NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_document_topics_topic ON document_topics(topic_id);
+
+    CREATE TABLE IF NOT EXISTS document_quantized (
+        document_id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        doc_type TEXT NOT NULL,
+        code BLOB NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_document_quantized_video ON document_quantized(video_id);
+
+    CREATE INDEX IF NOT EXISTS idx_usage_records_created_at ON usage_records(created_at);
+    CREATE INDEX IF NOT EXISTS idx_media_tags_tag ON media_tags(tag);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrades_unversioned_database() {
+        // A database from before versioning, missing later columns
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE documents (
+                id TEXT PRIMARY KEY,
+                video_id TEXT NOT NULL,
+                video_title TEXT NOT NULL,
+                section_title TEXT,
+                content TEXT NOT NULL,
+                start_seconds REAL NOT NULL,
+                end_seconds REAL NOT NULL,
+                embedding BLOB NOT NULL,
+                chunk_order INTEGER NOT NULL,
+                source_created_at TEXT,
+                indexed_at TEXT NOT NULL
+            );
+            INSERT INTO documents VALUES ('a', 'v', 'Title', NULL, 'Text', 0, 1, x'', 0, NULL, '2024-01-01T00:00:00Z');
+            "#,
+        )
+        .unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 0);
+
+        assert_eq!(run(&mut conn).unwrap(), MIGRATIONS.len());
+        assert_eq!(schema_version(&conn).unwrap(), latest_version());
+        let columns = table_columns(&conn, "documents").unwrap();
+        assert!(columns.iter().any(|c| c == "embedding_format"));
+        let doc_type: String = conn.query_row("SELECT doc_type FROM documents", [], |row| row.get(0)).unwrap();
+        assert_eq!(doc_type, "source");
+
+        // Up to date: nothing to do
+        assert_eq!(run(&mut conn).unwrap(), 0);
+
+        // Written by a newer lytt
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+        assert!(run(&mut conn).is_err());
+    }
+}
@@ -2,16 +2,32 @@
 //!
 //! Provides a trait-based interface for different vector database backends.
 
+mod ann;
+#[cfg(feature = "columnar")]
+mod columnar;
+mod index;
 mod memory;
+mod migrations;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod quantized;
 mod sqlite;
 
+pub use ann::AnnIndex;
+#[cfg(feature = "columnar")]
+pub use columnar::ColumnarVectorStore;
+pub use index::{DerivedIndex, IndexKind, IndexStatus};
 pub use memory::MemoryVectorStore;
+#[cfg(feature = "postgres")]
+pub use postgres::PgVectorStore;
+pub use quantized::{Quantization, QuantizedIndex};
 pub use sqlite::SqliteVectorStore;
 
-use crate::error::Result;
+use crate::error::{LyttError, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// A document stored in the vector database.
@@ -39,10 +55,35 @@ pub struct Document {
     pub source_created_at: Option<DateTime<Utc>>,
     /// When this document was indexed.
     pub indexed_at: DateTime<Utc>,
+    /// Whether this is transcript content or a derived artifact.
+    #[serde(default)]
+    pub doc_type: DocType,
+    /// IDs of the source documents a derived document was built from.
+    #[serde(default)]
+    pub provenance: Vec<String>,
+    /// Keywords and key phrases extracted from the content.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Spoken language of the media (ISO 639-1 code, e.g. "en").
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Summary of the section (if the chunker wrote one).
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Title of the book, for audiobooks.
+    #[serde(default)]
+    pub book: Option<String>,
+    /// Author of the book, for audiobooks.
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Chapter of the media the chunk starts in.
+    #[serde(default)]
+    pub chapter: Option<String>,
 }
 
 impl Document {
     /// Create a new document.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         video_id: String,
         video_title: String,
@@ -66,9 +107,55 @@ impl Document {
             chunk_order,
             source_created_at,
             indexed_at: Utc::now(),
+            doc_type: DocType::Source,
+            provenance: Vec::new(),
+            keywords: Vec::new(),
+            language: None,
+            summary: None,
+            book: None,
+            author: None,
+            chapter: None,
         }
     }
 
+    /// Set the extracted keywords.
+    pub fn with_keywords(mut self, keywords: Vec<String>) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Set the spoken language (ISO 639-1 code).
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Set the section summary.
+    pub fn with_summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Set the book and author of an audiobook.
+    pub fn with_book(mut self, book: Option<String>, author: Option<String>) -> Self {
+        self.book = book;
+        self.author = author;
+        self
+    }
+
+    /// Set the chapter the chunk starts in.
+    pub fn with_chapter(mut self, chapter: Option<String>) -> Self {
+        self.chapter = chapter;
+        self
+    }
+
+    /// Mark this document as derived from the given source documents.
+    pub fn derived_from(mut self, provenance: Vec<String>) -> Self {
+        self.doc_type = DocType::Derived;
+        self.provenance = provenance;
+        self
+    }
+
     /// Format timestamp for display.
     pub fn format_timestamp(&self) -> String {
         let total_seconds = self.start_seconds as u32;
@@ -84,6 +171,291 @@ impl Document {
     }
 }
 
+/// Kind of indexed document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocType {
+    /// Transcript content.
+    #[default]
+    Source,
+    /// Artifact generated from other documents (rollups, summaries, reports).
+    Derived,
+}
+
+impl DocType {
+    /// Database representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocType::Source => "source",
+            DocType::Derived => "derived",
+        }
+    }
+}
+
+impl std::str::FromStr for DocType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "source" => Ok(DocType::Source),
+            "derived" => Ok(DocType::Derived),
+            _ => Err(format!("Unknown document type: {}", s)),
+        }
+    }
+}
+
+/// How searches treat derived documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DerivedFilter {
+    /// Search both transcripts and derived documents.
+    #[default]
+    Include,
+    /// Search transcripts only.
+    Exclude,
+    /// Search derived documents only.
+    Only,
+}
+
+impl std::str::FromStr for DerivedFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "include" => Ok(DerivedFilter::Include),
+            "exclude" => Ok(DerivedFilter::Exclude),
+            "only" => Ok(DerivedFilter::Only),
+            _ => Err(format!("Unknown derived filter: {}. Use include, exclude, or only.", s)),
+        }
+    }
+}
+
+/// How stored embeddings are encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorFormat {
+    /// 32-bit floats, exactly as returned by the embedder.
+    #[default]
+    F32,
+    /// 16-bit floats: half the size, with differences in similarity
+    /// scores far below what changes a ranking.
+    F16,
+}
+
+impl VectorFormat {
+    /// Name stored alongside each vector.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VectorFormat::F32 => "f32",
+            VectorFormat::F16 => "f16",
+        }
+    }
+
+    /// Bytes per vector component.
+    pub fn bytes_per_value(&self) -> usize {
+        match self {
+            VectorFormat::F32 => 4,
+            VectorFormat::F16 => 2,
+        }
+    }
+
+    /// Encode a vector as little-endian bytes.
+    pub fn encode(&self, vector: &[f32]) -> Vec<u8> {
+        match self {
+            VectorFormat::F32 => vector.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            VectorFormat::F16 => vector.iter().flat_map(|v| half::f16::from_f32(*v).to_le_bytes()).collect(),
+        }
+    }
+
+    /// Decode a vector written by [`VectorFormat::encode`].
+    pub fn decode(&self, bytes: &[u8]) -> Vec<f32> {
+        match self {
+            VectorFormat::F32 => bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            VectorFormat::F16 => bytes
+                .chunks_exact(2)
+                .map(|b| half::f16::from_le_bytes([b[0], b[1]]).to_f32())
+                .collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for VectorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "f32" | "float32" => Ok(VectorFormat::F32),
+            "f16" | "float16" | "half" => Ok(VectorFormat::F16),
+            _ => Err(format!("Unknown vector format: {}. Use f32 or f16.", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for VectorFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Restrictions applied to a search.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// How to treat derived documents.
+    pub derived: DerivedFilter,
+    /// Only documents published (or, without a publish date, indexed) at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// The window `since` was given as (`30d`), if any. `since` itself moves
+    /// with the clock, so cache keys use this instead.
+    pub since_window: Option<String>,
+    /// Only documents from this media item.
+    pub video_id: Option<String>,
+    /// Only documents tagged with this keyword (case-insensitive).
+    pub keyword: Option<String>,
+    /// Only documents assigned to this stored topic (SQLite store only).
+    pub topic: Option<i64>,
+    /// Only documents from media in this series (SQLite store only).
+    pub series: Option<String>,
+    /// Only documents in this language (ISO 639-1 code, case-insensitive).
+    pub language: Option<String>,
+}
+
+impl SearchFilter {
+    /// Set how derived documents are treated.
+    pub fn with_derived(mut self, derived: DerivedFilter) -> Self {
+        self.derived = derived;
+        self
+    }
+
+    /// Only match documents from media published or indexed at or after `since`.
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only match documents from media published or indexed within a time
+    /// window (see [`parse_since`]).
+    pub fn with_since_window(mut self, window: &str, now: DateTime<Utc>) -> std::result::Result<Self, String> {
+        self.since = Some(parse_since(window, now)?);
+        self.since_window = Some(window.trim().to_string());
+        Ok(self)
+    }
+
+    /// Describe the filter for a cache key: the same for the same
+    /// restrictions, whenever it was built.
+    pub fn cache_key(&self) -> String {
+        let mut stable = self.clone();
+        if stable.since_window.is_some() {
+            stable.since = None;
+        }
+        format!("{:?}", stable)
+    }
+
+    /// Only match documents from one media item.
+    pub fn with_video(mut self, video_id: impl Into<String>) -> Self {
+        self.video_id = Some(video_id.into());
+        self
+    }
+
+    /// Only match documents with this extracted keyword.
+    pub fn with_keyword(mut self, keyword: impl Into<String>) -> Self {
+        self.keyword = Some(keyword.into());
+        self
+    }
+
+    /// Only match documents assigned to a stored topic (see `lytt topics`).
+    pub fn with_topic(mut self, topic: i64) -> Self {
+        self.topic = Some(topic);
+        self
+    }
+
+    /// Only match documents from media in a series (see `lytt series`).
+    pub fn with_series(mut self, series: impl Into<String>) -> Self {
+        self.series = Some(series.into());
+        self
+    }
+
+    /// Only match documents in one language (ISO 639-1 code, e.g. "de").
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Whether the filter narrows which media is searched, so it may exclude
+    /// most documents.
+    pub fn is_narrow(&self) -> bool {
+        self.since.is_some()
+            || self.video_id.is_some()
+            || self.keyword.is_some()
+            || self.topic.is_some()
+            || self.series.is_some()
+            || self.language.is_some()
+    }
+
+    /// Fail if the filter restricts by series, which only the SQLite store
+    /// keeps; other stores would otherwise search everything.
+    pub fn reject_series(&self, store: &str) -> Result<()> {
+        match &self.series {
+            Some(series) => Err(LyttError::InvalidInput(format!(
+                "Cannot filter by series '{}': series are only supported by the SQLite vector store, not {}",
+                series, store
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Check whether a document passes the filter.
+    ///
+    /// Topic and series assignments live in the store, so `topic` and
+    /// `series` are applied by the store's scan rather than here.
+    pub fn matches(&self, doc: &Document) -> bool {
+        let derived = match self.derived {
+            DerivedFilter::Include => true,
+            DerivedFilter::Exclude => doc.doc_type != DocType::Derived,
+            DerivedFilter::Only => doc.doc_type == DocType::Derived,
+        };
+        let recent = self
+            .since
+            .is_none_or(|since| doc.source_created_at.unwrap_or(doc.indexed_at) >= since);
+        let video = self.video_id.as_ref().is_none_or(|id| &doc.video_id == id);
+        let keyword = self
+            .keyword
+            .as_ref()
+            .is_none_or(|k| doc.keywords.iter().any(|dk| dk.eq_ignore_ascii_case(k.trim())));
+        let language = self.language.as_ref().is_none_or(|l| {
+            doc.language.as_ref().is_some_and(|dl| dl.eq_ignore_ascii_case(l.trim()))
+        });
+        derived && recent && video && keyword && language
+    }
+}
+
+/// Parse a time window such as `30d`, `2w`, `12h` or a date (`2025-01-31`)
+/// into the start of the window.
+pub fn parse_since(window: &str, now: DateTime<Utc>) -> std::result::Result<DateTime<Utc>, String> {
+    let window = window.trim();
+
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(window, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let invalid = || format!("Invalid time window: {}. Use e.g. 12h, 30d, 2w or 2025-01-31.", window);
+    let (split, _) = window.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = window.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+    let duration = match unit {
+        "h" => chrono::TimeDelta::try_hours(amount),
+        "d" => chrono::TimeDelta::try_days(amount),
+        "w" => chrono::TimeDelta::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+    duration
+        .and_then(|d| now.checked_sub_signed(d))
+        .ok_or_else(|| format!("Time window out of range: {}", window))
+}
+
 /// A search result with score.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -93,6 +465,57 @@ pub struct SearchResult {
     pub score: f32,
 }
 
+/// Search results that may be incomplete because a deadline passed.
+#[derive(Debug, Clone, Default)]
+pub struct PartialResults {
+    /// Best results found, sorted by score.
+    pub results: Vec<SearchResult>,
+    /// Whether the search stopped before scoring every document.
+    pub partial: bool,
+}
+
+/// The embedding model and dimensions a library was indexed with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingInfo {
+    /// Model identifier; unknown for libraries indexed before it was recorded.
+    pub model: Option<String>,
+    /// Vector dimensions.
+    pub dimensions: usize,
+}
+
+impl EmbeddingInfo {
+    /// Create embedding info for a known model.
+    pub fn new(model: impl Into<String>, dimensions: usize) -> Self {
+        Self {
+            model: Some(model.into()),
+            dimensions,
+        }
+    }
+
+    /// Check that query embeddings from `configured` can be compared with
+    /// vectors indexed with `self`.
+    pub fn ensure_compatible(&self, configured: &EmbeddingInfo) -> Result<()> {
+        let model_differs = matches!((&self.model, &configured.model), (Some(a), Some(b)) if a != b);
+        if !model_differs && self.dimensions == configured.dimensions {
+            return Ok(());
+        }
+        Err(LyttError::EmbeddingMismatch(format!(
+            "the library was indexed with {} but the embedding settings use {}. \
+             Run 'lytt reembed' to re-embed the library, or restore the previous [embedding] settings",
+            self, configured
+        )))
+    }
+}
+
+impl std::fmt::Display for EmbeddingInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.model {
+            Some(model) => write!(f, "{} ({} dimensions)", model, self.dimensions),
+            None => write!(f, "{}-dimension embeddings", self.dimensions),
+        }
+    }
+}
+
 /// Summary information about an indexed video.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexedVideo {
@@ -106,6 +529,122 @@ pub struct IndexedVideo {
     pub total_duration_seconds: f64,
     /// When the video was indexed.
     pub indexed_at: DateTime<Utc>,
+    /// Spoken language (ISO 639-1 code), if detected.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Retrieval statistics for one media item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoRetrievalStats {
+    /// Video ID.
+    pub video_id: String,
+    /// Video title.
+    pub video_title: String,
+    /// Number of indexed chunks.
+    pub chunk_count: u32,
+    /// Chunks returned by at least one search.
+    pub retrieved_chunks: u32,
+    /// Times any chunk was returned by a search.
+    pub retrievals: u64,
+    /// Times any chunk was cited in a generated answer.
+    pub citations: u64,
+    /// When a chunk was last returned by a search.
+    pub last_retrieved_at: Option<DateTime<Utc>>,
+}
+
+/// A media item in a series.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesEpisode {
+    /// Episode number within the series.
+    pub episode: u32,
+    /// Media ID.
+    pub video_id: String,
+    /// Media title.
+    pub video_title: String,
+    /// Transcript duration in seconds (0 without a stored transcript).
+    pub duration_seconds: f64,
+    /// Number of indexed chunks.
+    pub chunk_count: u32,
+    /// Times any chunk was returned by a search.
+    pub retrievals: u64,
+    /// Times any chunk was cited in a generated answer.
+    pub citations: u64,
+}
+
+/// A series and totals over its episodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesStats {
+    /// Series name.
+    pub name: String,
+    /// What the series is.
+    pub description: Option<String>,
+    /// When the series was created.
+    pub created_at: DateTime<Utc>,
+    /// Number of episodes.
+    pub episodes: usize,
+    /// Total transcript duration in seconds.
+    pub duration_seconds: f64,
+    /// Total indexed chunks.
+    pub chunk_count: u32,
+    /// Total retrievals of the series' chunks.
+    pub retrievals: u64,
+    /// Total citations of the series' chunks.
+    pub citations: u64,
+}
+
+impl SeriesStats {
+    /// Totals over the episodes of a series.
+    pub fn from_episodes(
+        name: String,
+        description: Option<String>,
+        created_at: DateTime<Utc>,
+        episodes: &[SeriesEpisode],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            created_at,
+            episodes: episodes.len(),
+            duration_seconds: episodes.iter().map(|e| e.duration_seconds).sum(),
+            chunk_count: episodes.iter().map(|e| e.chunk_count).sum(),
+            retrievals: episodes.iter().map(|e| e.retrievals).sum(),
+            citations: episodes.iter().map(|e| e.citations).sum(),
+        }
+    }
+}
+
+/// Outcome of a WAL checkpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Whether readers or writers prevented a complete checkpoint.
+    pub busy: bool,
+    /// Frames in the WAL.
+    pub wal_frames: u64,
+    /// Frames copied into the database.
+    pub checkpointed: u64,
+}
+
+/// Size of the database file and how much of it is unused.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DatabaseSize {
+    /// Size of the main database file.
+    pub file_bytes: u64,
+    /// Free pages left by deletes, reclaimed by `VACUUM`.
+    pub free_bytes: u64,
+    /// Size of the write-ahead log.
+    pub wal_bytes: u64,
+}
+
+/// Space used by one table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableSize {
+    /// Table name.
+    pub name: String,
+    /// Number of rows.
+    pub rows: u64,
+    /// Bytes of pages used by the table and its indexes.
+    pub bytes: u64,
 }
 
 /// Trait for vector store implementations.
@@ -128,13 +667,39 @@ pub trait VectorStore: Send + Sync {
         min_score: f32,
     ) -> Result<Vec<SearchResult>>;
 
+    /// Search with a minimum similarity threshold, restricted by a filter.
+    async fn search_filtered(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>>;
+
+    /// Filtered search that stops at `deadline`, returning the best results
+    /// scored so far. Stores that can't stop early return complete results.
+    async fn search_with_deadline(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+        _deadline: Instant,
+    ) -> Result<PartialResults> {
+        let results = self
+            .search_filtered(query_embedding, limit, min_score, filter)
+            .await?;
+        Ok(PartialResults { results, partial: false })
+    }
+
     /// Delete documents by video ID.
     async fn delete_by_video_id(&self, video_id: &str) -> Result<usize>;
 
-    /// List all indexed videos.
+    /// List all indexed videos. Derived documents (summaries, rollups) are
+    /// stored under their own IDs and aren't listed.
     async fn list_videos(&self) -> Result<Vec<IndexedVideo>>;
 
-    /// Get a specific video's information.
+    /// Get a specific video's information (None for derived documents).
     async fn get_video(&self, video_id: &str) -> Result<Option<IndexedVideo>>;
 
     /// Check if a video is indexed.
@@ -145,6 +710,46 @@ pub trait VectorStore: Send + Sync {
 
     /// Get total document count.
     async fn document_count(&self) -> Result<usize>;
+
+    /// Transcript chunks of `video_id` within `window` chunks of
+    /// `chunk_order` (excluding that chunk), in order.
+    async fn get_neighbors(&self, video_id: &str, chunk_order: i32, window: usize) -> Result<Vec<Document>> {
+        let window = window as i32;
+        Ok(self
+            .get_by_video_id(video_id)
+            .await?
+            .into_iter()
+            .filter(|d| {
+                d.doc_type == DocType::Source
+                    && d.chunk_order != chunk_order
+                    && (d.chunk_order - chunk_order).abs() <= window
+            })
+            .collect())
+    }
+
+    /// Record that documents were cited in a generated answer.
+    /// Stores without usage statistics ignore this.
+    async fn record_citations(&self, _ids: &[Uuid]) -> Result<()> {
+        Ok(())
+    }
+
+    /// A number that changes whenever documents are added, changed or
+    /// removed. Stores that don't track changes return `None`, which
+    /// disables answer caching.
+    async fn corpus_version(&self) -> Result<Option<i64>> {
+        Ok(None)
+    }
+
+    /// A cached answer stored under `key` for this corpus version, if it is
+    /// younger than `max_age`.
+    async fn cached_answer(&self, _key: &str, _version: i64, _max_age: Duration) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Cache an answer (serialized by the caller) for a corpus version.
+    async fn cache_answer(&self, _key: &str, _version: i64, _answer: &str) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Compute cosine similarity between two vectors.
@@ -164,6 +769,19 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     dot_product / (norm_a * norm_b)
 }
 
+/// Deterministic pseudo-random vector with components in [-1, 1], for
+/// index tests.
+#[cfg(test)]
+pub(crate) fn test_vector(seed: u64, dimensions: usize) -> Vec<f32> {
+    let mut x = seed;
+    (0..dimensions)
+        .map(|_| {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((x >> 33) % 2001) as f32 / 1000.0 - 1.0
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +815,62 @@ mod tests {
 
         assert_eq!(doc.format_timestamp(), "02:05");
     }
+
+    #[test]
+    fn test_search_filter() {
+        let source = Document::new(
+            "v1".to_string(),
+            "Video".to_string(),
+            None,
+            "content".to_string(),
+            0.0,
+            10.0,
+            vec![],
+            0,
+            None,
+        );
+        let derived = source.clone().derived_from(vec![source.id.to_string()]);
+
+        let exclude = SearchFilter::default().with_derived(DerivedFilter::Exclude);
+        assert!(exclude.matches(&source));
+        assert!(!exclude.matches(&derived));
+
+        let only = SearchFilter::default().with_derived(DerivedFilter::Only);
+        assert!(!only.matches(&source));
+        assert!(only.matches(&derived));
+        assert_eq!(derived.provenance.len(), 1);
+
+        let recent = SearchFilter::default().with_since(Utc::now() - chrono::Duration::days(1));
+        assert!(recent.matches(&source));
+        let mut old = source.clone();
+        old.source_created_at = Some(Utc::now() - chrono::Duration::days(30));
+        assert!(!recent.matches(&old));
+    }
+
+    #[test]
+    fn test_filter_cache_key_ignores_the_clock() {
+        let now = Utc::now();
+        let earlier = SearchFilter::default().with_since_window("30d", now - chrono::Duration::hours(1)).unwrap();
+        let later = SearchFilter::default().with_since_window("30d", now).unwrap();
+        assert_ne!(earlier.since, later.since);
+        assert_eq!(earlier.cache_key(), later.cache_key());
+        let longer = SearchFilter::default().with_since_window("60d", now).unwrap();
+        assert_ne!(longer.cache_key(), later.cache_key());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("30d", now).unwrap(), now - chrono::Duration::days(30));
+        assert_eq!(parse_since("2w", now).unwrap(), now - chrono::Duration::weeks(2));
+        assert_eq!(
+            parse_since("2025-01-31", now).unwrap().to_rfc3339(),
+            "2025-01-31T00:00:00+00:00"
+        );
+        assert!(parse_since("soon", now).is_err());
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("5é", now).is_err());
+        assert!(parse_since("9223372036854775807w", now).is_err());
+        assert!(parse_since("999999999999h", now).is_err());
+    }
 }
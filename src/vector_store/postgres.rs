@@ -0,0 +1,430 @@
+//! Postgres vector store using the pgvector extension.
+//!
+//! Lets several lytt instances share one library on a database server.
+//! Similarity search runs in Postgres over an HNSW index on the embeddings,
+//! with filters applied in SQL. Transcripts, tags, usage and other metadata
+//! stay in the local SQLite database.
+
+use super::{DerivedFilter, DocType, Document, IndexedVideo, SearchFilter, SearchResult, VectorStore};
+use crate::error::{LyttError, Result};
+use async_trait::async_trait;
+use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use pgvector::Vector;
+use tokio::sync::OnceCell;
+use tokio_postgres::{NoTls, Row};
+use tracing::{debug, info, instrument};
+
+/// Columns selected when loading full documents.
+const DOCUMENT_COLUMNS: &str = "id, video_id, video_title, section_title, content, start_seconds, end_seconds, \
+    embedding, chunk_order, source_created_at, indexed_at, doc_type, provenance, keywords, language, summary, \
+    book, author, chapter";
+
+/// Maximum open connections to the server.
+const POOL_SIZE: usize = 16;
+
+/// Vector store backed by Postgres with pgvector.
+pub struct PgVectorStore {
+    pool: Pool,
+    /// Length of the stored embeddings, fixed by the schema.
+    dimensions: usize,
+    /// Set once the schema has been created in this process.
+    schema: OnceCell<()>,
+}
+
+impl PgVectorStore {
+    /// Connect to the database at `url`, a `postgres://` URL or key=value
+    /// connection string. Connections are opened on first use, when the
+    /// schema is also created for embeddings of `dimensions` values.
+    pub fn new(url: &str, dimensions: usize) -> Result<Self> {
+        let config: tokio_postgres::Config = url
+            .parse()
+            .map_err(|e| LyttError::Config(format!("Invalid Postgres connection string: {}", e)))?;
+        let manager = Manager::from_config(
+            config,
+            NoTls,
+            ManagerConfig { recycling_method: RecyclingMethod::Fast },
+        );
+        let pool = Pool::builder(manager)
+            .max_size(POOL_SIZE)
+            .build()
+            .map_err(|e| LyttError::VectorStore(format!("Failed to create Postgres pool: {}", e)))?;
+
+        Ok(Self {
+            pool,
+            dimensions,
+            schema: OnceCell::new(),
+        })
+    }
+
+    /// Take a connection from the pool, creating the schema the first time.
+    async fn client(&self) -> Result<deadpool_postgres::Client> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| LyttError::VectorStore(format!("Failed to connect to Postgres: {}", e)))?;
+        self.schema
+            .get_or_try_init(|| async {
+                client.batch_execute(&schema(self.dimensions)).await.map_err(pg)?;
+                info!("Initialized Postgres vector store ({} dimensions)", self.dimensions);
+                Ok::<(), LyttError>(())
+            })
+            .await?;
+        Ok(client)
+    }
+
+    /// Map a row selected with `DOCUMENT_COLUMNS` to a document.
+    fn row_to_document(row: &Row) -> Result<Document> {
+        let embedding: Vector = row.try_get(7).map_err(pg)?;
+        let doc_type: String = row.try_get(11).map_err(pg)?;
+        Ok(Document {
+            id: row.try_get(0).map_err(pg)?,
+            video_id: row.try_get(1).map_err(pg)?,
+            video_title: row.try_get(2).map_err(pg)?,
+            section_title: row.try_get(3).map_err(pg)?,
+            content: row.try_get(4).map_err(pg)?,
+            start_seconds: row.try_get(5).map_err(pg)?,
+            end_seconds: row.try_get(6).map_err(pg)?,
+            embedding: embedding.to_vec(),
+            chunk_order: row.try_get(8).map_err(pg)?,
+            source_created_at: row.try_get(9).map_err(pg)?,
+            indexed_at: row.try_get(10).map_err(pg)?,
+            doc_type: doc_type.parse().unwrap_or_default(),
+            provenance: row.try_get(12).map_err(pg)?,
+            keywords: row.try_get(13).map_err(pg)?,
+            language: row.try_get(14).map_err(pg)?,
+            summary: row.try_get(15).map_err(pg)?,
+            book: row.try_get(16).map_err(pg)?,
+            author: row.try_get(17).map_err(pg)?,
+            chapter: row.try_get(18).map_err(pg)?,
+        })
+    }
+}
+
+/// Tables and indexes, created if missing.
+fn schema(dimensions: usize) -> String {
+    format!(
+        r#"
+        CREATE EXTENSION IF NOT EXISTS vector;
+
+        CREATE TABLE IF NOT EXISTS lytt_documents (
+            id UUID PRIMARY KEY,
+            video_id TEXT NOT NULL,
+            video_title TEXT NOT NULL,
+            section_title TEXT,
+            content TEXT NOT NULL,
+            start_seconds DOUBLE PRECISION NOT NULL,
+            end_seconds DOUBLE PRECISION NOT NULL,
+            embedding vector({}) NOT NULL,
+            chunk_order INTEGER NOT NULL,
+            source_created_at TIMESTAMPTZ,
+            indexed_at TIMESTAMPTZ NOT NULL,
+            doc_type TEXT NOT NULL DEFAULT 'source',
+            provenance TEXT[] NOT NULL DEFAULT '{{}}',
+            keywords TEXT[] NOT NULL DEFAULT '{{}}',
+            language TEXT,
+            summary TEXT,
+            book TEXT,
+            author TEXT,
+            chapter TEXT
+        );
+
+        ALTER TABLE lytt_documents ADD COLUMN IF NOT EXISTS book TEXT;
+        ALTER TABLE lytt_documents ADD COLUMN IF NOT EXISTS author TEXT;
+        ALTER TABLE lytt_documents ADD COLUMN IF NOT EXISTS chapter TEXT;
+
+        CREATE INDEX IF NOT EXISTS lytt_documents_video_id_idx ON lytt_documents (video_id);
+        CREATE INDEX IF NOT EXISTS lytt_documents_embedding_idx
+            ON lytt_documents USING hnsw (embedding vector_cosine_ops);
+        "#,
+        dimensions
+    )
+}
+
+/// Wrap a Postgres error.
+fn pg(e: tokio_postgres::Error) -> LyttError {
+    LyttError::VectorStore(format!("Postgres error: {}", e))
+}
+
+/// SQL condition for how derived documents are treated.
+fn derived_clause(derived: DerivedFilter) -> &'static str {
+    match derived {
+        DerivedFilter::Include => "",
+        DerivedFilter::Exclude => "AND doc_type <> 'derived'",
+        DerivedFilter::Only => "AND doc_type = 'derived'",
+    }
+}
+
+#[async_trait]
+impl VectorStore for PgVectorStore {
+    async fn upsert(&self, doc: &Document) -> Result<()> {
+        self.upsert_batch(std::slice::from_ref(doc)).await?;
+        Ok(())
+    }
+
+    #[instrument(skip(self, docs), fields(count = docs.len()))]
+    async fn upsert_batch(&self, docs: &[Document]) -> Result<usize> {
+        let mut client = self.client().await?;
+        let tx = client.transaction().await.map_err(pg)?;
+        let stmt = tx
+            .prepare(&format!(
+                r#"
+                INSERT INTO lytt_documents ({})
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+                ON CONFLICT (id) DO UPDATE SET
+                    video_id = EXCLUDED.video_id,
+                    video_title = EXCLUDED.video_title,
+                    section_title = EXCLUDED.section_title,
+                    content = EXCLUDED.content,
+                    start_seconds = EXCLUDED.start_seconds,
+                    end_seconds = EXCLUDED.end_seconds,
+                    embedding = EXCLUDED.embedding,
+                    chunk_order = EXCLUDED.chunk_order,
+                    source_created_at = EXCLUDED.source_created_at,
+                    indexed_at = EXCLUDED.indexed_at,
+                    doc_type = EXCLUDED.doc_type,
+                    provenance = EXCLUDED.provenance,
+                    keywords = EXCLUDED.keywords,
+                    language = EXCLUDED.language,
+                    summary = EXCLUDED.summary,
+                    book = EXCLUDED.book,
+                    author = EXCLUDED.author,
+                    chapter = EXCLUDED.chapter
+                "#,
+                DOCUMENT_COLUMNS
+            ))
+            .await
+            .map_err(pg)?;
+
+        for doc in docs {
+            if doc.embedding.len() != self.dimensions {
+                return Err(LyttError::VectorStore(format!(
+                    "Embedding has {} dimensions but the Postgres store holds {}",
+                    doc.embedding.len(),
+                    self.dimensions
+                )));
+            }
+            tx.execute(
+                &stmt,
+                &[
+                    &doc.id,
+                    &doc.video_id,
+                    &doc.video_title,
+                    &doc.section_title,
+                    &doc.content,
+                    &doc.start_seconds,
+                    &doc.end_seconds,
+                    &Vector::from(doc.embedding.clone()),
+                    &doc.chunk_order,
+                    &doc.source_created_at,
+                    &doc.indexed_at,
+                    &doc.doc_type.as_str(),
+                    &doc.provenance,
+                    &doc.keywords,
+                    &doc.language,
+                    &doc.summary,
+                    &doc.book,
+                    &doc.author,
+                    &doc.chapter,
+                ],
+            )
+            .await
+            .map_err(pg)?;
+        }
+
+        tx.commit().await.map_err(pg)?;
+        debug!("Upserted {} documents", docs.len());
+        Ok(docs.len())
+    }
+
+    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_with_threshold(query_embedding, limit, 0.0).await
+    }
+
+    async fn search_with_threshold(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_filtered(query_embedding, limit, min_score, &SearchFilter::default())
+            .await
+    }
+
+    /// Topic filters are ignored: topics are stored in SQLite. Series
+    /// filters are rejected.
+    #[instrument(skip(self, query_embedding, filter))]
+    async fn search_filtered(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        filter.reject_series("PostgreSQL")?;
+        let client = self.client().await?;
+        let query = Vector::from(query_embedding.to_vec());
+        let keyword = filter.keyword.as_deref().map(str::trim);
+        let language = filter.language.as_deref().map(str::trim);
+
+        let rows = client
+            .query(
+                &format!(
+                    r#"
+                    SELECT {}, (1 - (embedding <=> $1))::real AS score
+                    FROM lytt_documents
+                    WHERE ($2::text IS NULL OR video_id = $2)
+                      AND ($3::timestamptz IS NULL OR COALESCE(source_created_at, indexed_at) >= $3)
+                      AND ($4::text IS NULL OR lower(language) = lower($4))
+                      AND ($5::text IS NULL OR EXISTS (SELECT 1 FROM unnest(keywords) k WHERE lower(k) = lower($5)))
+                      {}
+                    ORDER BY embedding <=> $1
+                    LIMIT $6
+                    "#,
+                    DOCUMENT_COLUMNS,
+                    derived_clause(filter.derived)
+                ),
+                &[&query, &filter.video_id, &filter.since, &language, &keyword, &(limit as i64)],
+            )
+            .await
+            .map_err(pg)?;
+
+        let mut results = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let score: f32 = row.try_get(19).map_err(pg)?;
+            if score >= min_score {
+                results.push(SearchResult { document: Self::row_to_document(row)?, score });
+            }
+        }
+        debug!("Found {} matching documents", results.len());
+        Ok(results)
+    }
+
+    async fn delete_by_video_id(&self, video_id: &str) -> Result<usize> {
+        let client = self.client().await?;
+        let deleted = client
+            .execute("DELETE FROM lytt_documents WHERE video_id = $1", &[&video_id])
+            .await
+            .map_err(pg)?;
+        Ok(deleted as usize)
+    }
+
+    async fn list_videos(&self) -> Result<Vec<IndexedVideo>> {
+        let client = self.client().await?;
+        let rows = client
+            .query(
+                r#"
+                SELECT video_id, MAX(video_title), COUNT(*), MAX(end_seconds), MAX(indexed_at), MAX(language)
+                FROM lytt_documents
+                WHERE doc_type = 'source'
+                GROUP BY video_id
+                ORDER BY MAX(indexed_at) DESC
+                "#,
+                &[],
+            )
+            .await
+            .map_err(pg)?;
+
+        rows.iter()
+            .map(|row| {
+                let chunk_count: i64 = row.try_get(2).map_err(pg)?;
+                Ok(IndexedVideo {
+                    video_id: row.try_get(0).map_err(pg)?,
+                    video_title: row.try_get(1).map_err(pg)?,
+                    chunk_count: chunk_count as u32,
+                    total_duration_seconds: row.try_get(3).map_err(pg)?,
+                    indexed_at: row.try_get(4).map_err(pg)?,
+                    language: row.try_get(5).map_err(pg)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_video(&self, video_id: &str) -> Result<Option<IndexedVideo>> {
+        let videos = self.list_videos().await?;
+        Ok(videos.into_iter().find(|v| v.video_id == video_id))
+    }
+
+    async fn is_video_indexed(&self, video_id: &str) -> Result<bool> {
+        let client = self.client().await?;
+        let row = client
+            .query_one("SELECT EXISTS (SELECT 1 FROM lytt_documents WHERE video_id = $1)", &[&video_id])
+            .await
+            .map_err(pg)?;
+        row.try_get(0).map_err(pg)
+    }
+
+    async fn get_by_video_id(&self, video_id: &str) -> Result<Vec<Document>> {
+        let client = self.client().await?;
+        let rows = client
+            .query(
+                &format!("SELECT {} FROM lytt_documents WHERE video_id = $1 ORDER BY chunk_order", DOCUMENT_COLUMNS),
+                &[&video_id],
+            )
+            .await
+            .map_err(pg)?;
+        rows.iter().map(Self::row_to_document).collect()
+    }
+
+    async fn document_count(&self) -> Result<usize> {
+        let client = self.client().await?;
+        let row = client.query_one("SELECT COUNT(*) FROM lytt_documents", &[]).await.map_err(pg)?;
+        let count: i64 = row.try_get(0).map_err(pg)?;
+        Ok(count as usize)
+    }
+
+    async fn get_neighbors(&self, video_id: &str, chunk_order: i32, window: usize) -> Result<Vec<Document>> {
+        let client = self.client().await?;
+        let rows = client
+            .query(
+                &format!(
+                    r#"
+                    SELECT {} FROM lytt_documents
+                    WHERE video_id = $1 AND doc_type = $2 AND chunk_order <> $3 AND abs(chunk_order - $3) <= $4
+                    ORDER BY chunk_order
+                    "#,
+                    DOCUMENT_COLUMNS
+                ),
+                &[&video_id, &DocType::Source.as_str(), &chunk_order, &(window as i32)],
+            )
+            .await
+            .map_err(pg)?;
+        rows.iter().map(Self::row_to_document).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_postgres_store() {
+        assert!(PgVectorStore::new("not a connection string", 3).is_err());
+        assert!(schema(1536).contains("embedding vector(1536) NOT NULL"));
+
+        // Round trip against a real server with pgvector, when one is provided
+        let Ok(url) = std::env::var("LYTT_TEST_POSTGRES_URL") else {
+            return;
+        };
+        let store = PgVectorStore::new(&url, 3).unwrap();
+        store.delete_by_video_id("lytt-test").await.unwrap();
+
+        let first = Document::new("lytt-test".to_string(), "Test".to_string(), None, "One".to_string(), 0.0, 10.0, vec![1.0, 0.0, 0.0], 0, None)
+            .with_keywords(vec!["Rust".to_string()])
+            .with_language(Some("en".to_string()));
+        let second = Document::new("lytt-test".to_string(), "Test".to_string(), None, "Two".to_string(), 10.0, 20.0, vec![0.0, 1.0, 0.0], 1, None);
+        store.upsert_batch(&[first.clone(), second]).await.unwrap();
+        assert!(store.upsert(&Document::new("lytt-test".to_string(), "Test".to_string(), None, String::new(), 0.0, 1.0, vec![1.0], 2, None)).await.is_err());
+
+        let results = store.search(&[1.0, 0.1, 0.0], 2).await.unwrap();
+        assert_eq!(results[0].document.id, first.id);
+        assert_eq!(results[0].document.keywords, vec!["Rust".to_string()]);
+        let filter = SearchFilter::default().with_video("lytt-test").with_keyword("rust").with_language("EN");
+        assert_eq!(store.search_filtered(&[0.0, 1.0, 0.0], 5, 0.0, &filter).await.unwrap().len(), 1);
+        assert_eq!(store.get_neighbors("lytt-test", 0, 1).await.unwrap().len(), 1);
+        assert_eq!(store.get_video("lytt-test").await.unwrap().unwrap().chunk_count, 2);
+
+        assert_eq!(store.delete_by_video_id("lytt-test").await.unwrap(), 2);
+        assert!(!store.is_video_indexed("lytt-test").await.unwrap());
+    }
+}
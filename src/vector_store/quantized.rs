@@ -0,0 +1,211 @@
+//! Quantized embeddings for two-stage search.
+//!
+//! A compact copy of every embedding (one bit or one byte per dimension) is
+//! kept in `document_quantized`. Searches score those codes first, which
+//! reads 4-32x less data than the full vectors, and then rescore only the best
+//! candidates with the exact embeddings.
+
+use super::{DerivedFilter, DerivedIndex, Document, IndexKind};
+use crate::error::Result;
+use rusqlite::{params, Connection};
+
+/// How embeddings are quantized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantization {
+    /// One sign bit per dimension, compared by Hamming distance.
+    Binary,
+    /// One signed byte per dimension, scaled per vector.
+    Int8,
+}
+
+impl std::str::FromStr for Quantization {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "binary" => Ok(Quantization::Binary),
+            "int8" => Ok(Quantization::Int8),
+            _ => Err(format!("Unknown quantization: {}. Use none, binary, or int8.", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for Quantization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quantization::Binary => write!(f, "binary"),
+            Quantization::Int8 => write!(f, "int8"),
+        }
+    }
+}
+
+/// Quantized copies of the stored embeddings.
+#[derive(Debug)]
+pub struct QuantizedIndex {
+    quantization: Quantization,
+    rescore_factor: usize,
+}
+
+/// Fewest candidates rescored per search, however small the limit.
+const MIN_CANDIDATES: usize = 100;
+
+impl QuantizedIndex {
+    /// Create an index that rescores `rescore_factor` candidates per result.
+    pub fn new(quantization: Quantization, rescore_factor: usize) -> Self {
+        Self {
+            quantization,
+            rescore_factor: rescore_factor.max(1),
+        }
+    }
+
+    /// How the embeddings are quantized.
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
+    /// Number of candidates to rescore for a search returning `limit` results.
+    pub fn candidate_count(&self, limit: usize) -> usize {
+        (limit * self.rescore_factor).max(MIN_CANDIDATES)
+    }
+
+    /// Quantize an embedding.
+    pub fn encode(&self, embedding: &[f32]) -> Vec<u8> {
+        match self.quantization {
+            Quantization::Binary => {
+                let mut code = vec![0u8; embedding.len().div_ceil(8)];
+                for (i, v) in embedding.iter().enumerate() {
+                    if *v > 0.0 {
+                        code[i / 8] |= 1 << (i % 8);
+                    }
+                }
+                code
+            }
+            Quantization::Int8 => {
+                // Per-vector scale first, then one byte per dimension
+                let max = embedding.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+                let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+                let mut code = scale.to_le_bytes().to_vec();
+                code.extend(embedding.iter().map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8 as u8));
+                code
+            }
+        }
+    }
+
+    /// Approximate similarity between two codes; higher is more similar.
+    pub fn similarity(&self, a: &[u8], b: &[u8]) -> f32 {
+        match self.quantization {
+            Quantization::Binary => {
+                let distance: u32 = a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum();
+                -(distance as f32)
+            }
+            Quantization::Int8 => {
+                if a.len() < 4 || b.len() < 4 {
+                    return f32::MIN;
+                }
+                let scale_a = f32::from_le_bytes([a[0], a[1], a[2], a[3]]);
+                let scale_b = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+                let dot: i32 = a[4..].iter().zip(&b[4..]).map(|(x, y)| (*x as i8 as i32) * (*y as i8 as i32)).sum();
+                dot as f32 * scale_a * scale_b
+            }
+        }
+    }
+
+    /// IDs of the `count` documents whose codes are closest to the query.
+    pub fn candidates(
+        &self,
+        conn: &Connection,
+        query_embedding: &[f32],
+        count: usize,
+        derived: DerivedFilter,
+    ) -> Result<Vec<String>> {
+        let filter_clause = match derived {
+            DerivedFilter::Include => "",
+            DerivedFilter::Exclude => "WHERE doc_type != 'derived'",
+            DerivedFilter::Only => "WHERE doc_type = 'derived'",
+        };
+        let query = self.encode(query_embedding);
+
+        let mut stmt = conn.prepare(&format!("SELECT document_id, code FROM document_quantized {}", filter_clause))?;
+        let mut scored: Vec<(f32, String)> = stmt
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let code: Vec<u8> = row.get(1)?;
+                Ok((self.similarity(&query, &code), id))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        if scored.len() > count {
+            scored.select_nth_unstable_by(count, |a, b| b.0.total_cmp(&a.0));
+            scored.truncate(count);
+        }
+        Ok(scored.into_iter().map(|(_, id)| id).collect())
+    }
+}
+
+impl DerivedIndex for QuantizedIndex {
+    fn kind(&self) -> IndexKind {
+        IndexKind::Quantized
+    }
+
+    fn clear(&self, conn: &Connection) -> Result<()> {
+        conn.execute("DELETE FROM document_quantized", [])?;
+        Ok(())
+    }
+
+    fn on_upsert(&self, conn: &Connection, docs: &[Document]) -> Result<()> {
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO document_quantized (document_id, video_id, doc_type, code) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for doc in docs.iter().filter(|d| !d.embedding.is_empty()) {
+            stmt.execute(params![doc.id.to_string(), doc.video_id, doc.doc_type.as_str(), self.encode(&doc.embedding)])?;
+        }
+        Ok(())
+    }
+
+    fn on_delete(&self, conn: &Connection, video_id: &str) -> Result<()> {
+        conn.execute("DELETE FROM document_quantized WHERE video_id = ?1", params![video_id])?;
+        Ok(())
+    }
+
+    fn on_delete_documents(&self, conn: &Connection, ids: &[String]) -> Result<()> {
+        let mut stmt = conn.prepare("DELETE FROM document_quantized WHERE document_id = ?1")?;
+        for id in ids {
+            stmt.execute(params![id])?;
+        }
+        Ok(())
+    }
+
+    fn entry_count(&self, conn: &Connection) -> Result<usize> {
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM document_quantized", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantized_similarity_ranks_like_cosine() {
+        let query = [0.9, 0.1, -0.3, 0.2, 0.0, -0.1, 0.4, 0.1, 0.05];
+        let close = [0.8, 0.2, -0.2, 0.3, 0.1, -0.2, 0.3, 0.0, 0.1];
+        let far = [-0.7, 0.3, 0.5, -0.2, 0.1, 0.3, -0.4, -0.1, -0.1];
+
+        for quantization in [Quantization::Binary, Quantization::Int8] {
+            let index = QuantizedIndex::new(quantization, 10);
+            let q = index.encode(&query);
+            assert!(
+                index.similarity(&q, &index.encode(&close)) > index.similarity(&q, &index.encode(&far)),
+                "{}",
+                quantization
+            );
+        }
+
+        let binary = QuantizedIndex::new(Quantization::Binary, 10);
+        assert_eq!(binary.encode(&query).len(), 2);
+        let int8 = QuantizedIndex::new(Quantization::Int8, 10);
+        assert_eq!(int8.encode(&query).len(), 4 + 9);
+        assert_eq!(int8.candidate_count(5), 100);
+        assert_eq!(int8.candidate_count(20), 200);
+    }
+}
@@ -1,21 +1,128 @@
 //! SQLite-based vector store implementation.
 //!
 //! Uses SQLite with cosine similarity computed in Rust for simplicity.
+//! Connections come from a pool, so concurrent searches and writes only wait
+//! on SQLite's own locks rather than on each other.
 //! For production use cases with large datasets, consider using sqlite-vec extension
 //! or a dedicated vector database.
 
-use super::{cosine_similarity, Document, IndexedVideo, SearchResult, VectorStore};
+use super::migrations;
+use super::{
+    cosine_similarity, AnnIndex, Checkpoint, DatabaseSize, DerivedFilter, DerivedIndex, Document, EmbeddingInfo, IndexKind, IndexStatus, IndexedVideo,
+    PartialResults, Quantization, QuantizedIndex, SearchFilter, SearchResult, SeriesEpisode, SeriesStats, TableSize,
+    VectorFormat, VectorStore, VideoRetrievalStats,
+};
+use crate::audio_source::SourceType;
+use crate::config::ReplicationSettings;
 use crate::error::{Result, LyttError};
+use crate::glossary::GlossaryTerm;
+use crate::keywords::{count_keywords, KeywordCount};
+use crate::retention::RetentionCandidate;
+use crate::rollup::{Rollup, RollupPeriod};
+use crate::summary::MediaSummary;
+use crate::titles::GeneratedTitle;
+use crate::topics::{Topic, TopicMap, TopicMedia, TopicModel};
+use crate::usage::{Pricing, ProcessingRun, RunStatus, UsageGroupBy, UsageRecord, UsageSummary};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
-use std::path::Path;
-use std::sync::Mutex;
-use tracing::{debug, info, instrument};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, instrument, warn};
 
-/// SQLite-based vector store.
+/// Columns selected when loading full documents.
+const DOCUMENT_COLUMNS: &str = "id, video_id, video_title, section_title, content, \
+    start_seconds, end_seconds, embedding, chunk_order, source_created_at, indexed_at, \
+    doc_type, provenance, keywords, language, summary, embedding_format, book, author, chapter";
+
+/// How many rows are scored between deadline checks.
+const DEADLINE_CHECK_INTERVAL: usize = 256;
+
+/// How many documents are fed to a derived index at a time during a rebuild.
+const REBUILD_BATCH_SIZE: usize = 500;
+
+/// How many documents a paged scan reads per statement.
+const SCAN_PAGE_SIZE: i64 = 1000;
+
+/// SQLite-based vector store. Clones share the pool and indexes.
+#[derive(Clone)]
 pub struct SqliteVectorStore {
-    conn: Mutex<Connection>,
+    pool: Pool,
+    /// How pooled connections are opened (`None` for in-memory stores).
+    config: Option<ConnectionConfig>,
+    /// Derived indexes kept in sync with the documents table.
+    indexes: Vec<Arc<dyn DerivedIndex>>,
+    /// Whether the database was opened read-only.
+    read_only: bool,
+    /// Checkpoint schedule when the database is replicated.
+    replication: Option<Arc<Replication>>,
+    /// Encoding of newly written embeddings.
+    vector_format: VectorFormat,
+    /// Quantized embeddings used to preselect search candidates.
+    quantized: Option<Arc<QuantizedIndex>>,
+    /// In-memory graph used for approximate nearest-neighbour search.
+    ann: Option<Arc<AnnIndex>>,
+}
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+type PooledConnection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+/// Connections kept open to a database file unless configured otherwise.
+const DEFAULT_POOL_SIZE: u32 = 8;
+
+/// Pragmas for writable databases.
+const WRITE_PRAGMAS: &str = "PRAGMA journal_mode=WAL;";
+
+/// Pragmas for writable databases replicated with Litestream or LiteFS.
+const REPLICATION_PRAGMAS: &str = "PRAGMA journal_mode=WAL; PRAGMA wal_autocheckpoint=0; PRAGMA synchronous=NORMAL;";
+
+/// How pooled connections to a database file are opened.
+#[derive(Debug, Clone)]
+struct ConnectionConfig {
+    path: PathBuf,
+    flags: OpenFlags,
+    /// Statements run on every new connection.
+    pragmas: &'static str,
+    /// How long a connection waits for another's lock before failing.
+    busy_timeout: Duration,
+    pool_size: u32,
+}
+
+impl ConnectionConfig {
+    fn new(path: &Path, flags: OpenFlags, pragmas: &'static str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            flags,
+            pragmas,
+            busy_timeout: Duration::from_millis(ReplicationSettings::default().busy_timeout_ms),
+            pool_size: DEFAULT_POOL_SIZE,
+        }
+    }
+
+    /// Open a pool, connecting lazily beyond the first connection.
+    fn open(&self) -> Result<Pool> {
+        let pragmas = self.pragmas;
+        let busy_timeout = self.busy_timeout;
+        let manager = SqliteConnectionManager::file(&self.path)
+            .with_flags(self.flags)
+            .with_init(move |conn| {
+                conn.busy_timeout(busy_timeout)?;
+                conn.execute_batch(pragmas)
+            });
+        r2d2::Pool::builder()
+            .max_size(self.pool_size.max(1))
+            .min_idle(Some(1))
+            .build(manager)
+            .map_err(|e| LyttError::VectorStore(format!("Failed to open {}: {}", self.path.display(), e)))
+    }
+}
+
+/// Replication state: when the WAL was last checkpointed.
+struct Replication {
+    checkpoint_interval: Option<Duration>,
+    last_checkpoint: Mutex<Instant>,
 }
 
 impl SqliteVectorStore {
@@ -27,87 +134,277 @@ impl SqliteVectorStore {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(path)?;
-
-        // Enable WAL mode for better concurrent performance
-        conn.execute_batch("PRAGMA journal_mode=WAL;")?;
-
-        // Create tables
-        conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS documents (
-                id TEXT PRIMARY KEY,
-                video_id TEXT NOT NULL,
-                video_title TEXT NOT NULL,
-                section_title TEXT,
-                content TEXT NOT NULL,
-                start_seconds REAL NOT NULL,
-                end_seconds REAL NOT NULL,
-                embedding BLOB NOT NULL,
-                chunk_order INTEGER NOT NULL,
-                source_created_at TEXT,
-                indexed_at TEXT NOT NULL
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_documents_video_id ON documents(video_id);
-            CREATE INDEX IF NOT EXISTS idx_documents_indexed_at ON documents(indexed_at);
-
-            CREATE TABLE IF NOT EXISTS transcripts (
-                video_id TEXT PRIMARY KEY,
-                video_title TEXT NOT NULL,
-                transcript_json TEXT NOT NULL,
-                duration_seconds REAL NOT NULL,
-                transcribed_at TEXT NOT NULL
-            );
-            "#,
-        )?;
+        // WAL mode lets pooled connections read while another writes
+        let config = ConnectionConfig::new(path, OpenFlags::default(), WRITE_PRAGMAS);
+        let pool = config.open()?;
+        let mut conn = Self::pooled(&pool)?;
+        migrations::run(&mut conn)?;
+        drop(conn);
 
         info!("Initialized SQLite vector store at {:?}", path);
 
         Ok(Self {
-            conn: Mutex::new(conn),
+            pool,
+            config: Some(config),
+            indexes: Vec::new(),
+            read_only: false,
+            replication: None,
+            vector_format: VectorFormat::default(),
+            quantized: None,
+            ann: None,
         })
     }
 
     /// Create an in-memory SQLite vector store (useful for testing).
     pub fn in_memory() -> Result<Self> {
-        let conn = Connection::open_in_memory()?;
+        // Every connection to ":memory:" is a separate database, so the pool
+        // holds exactly one and never closes it
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .build(SqliteConnectionManager::memory())
+            .map_err(|e| LyttError::VectorStore(format!("Failed to open in-memory database: {}", e)))?;
+        let mut conn = Self::pooled(&pool)?;
+        migrations::run(&mut conn)?;
+        drop(conn);
 
-        conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS documents (
-                id TEXT PRIMARY KEY,
-                video_id TEXT NOT NULL,
-                video_title TEXT NOT NULL,
-                section_title TEXT,
-                content TEXT NOT NULL,
-                start_seconds REAL NOT NULL,
-                end_seconds REAL NOT NULL,
-                embedding BLOB NOT NULL,
-                chunk_order INTEGER NOT NULL,
-                source_created_at TEXT,
-                indexed_at TEXT NOT NULL
-            );
+        Ok(Self {
+            pool,
+            config: None,
+            indexes: Vec::new(),
+            read_only: false,
+            replication: None,
+            vector_format: VectorFormat::default(),
+            quantized: None,
+            ann: None,
+        })
+    }
 
-            CREATE INDEX IF NOT EXISTS idx_documents_video_id ON documents(video_id);
+    /// Open an existing database read-only, e.g. a replica kept up to date by
+    /// Litestream or LiteFS. Nothing is written, not even retrieval statistics,
+    /// and searches read in short pages so replica updates are never blocked.
+    #[instrument(skip_all)]
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(LyttError::VectorStore(format!(
+                "Replica database not found: {}",
+                path.display()
+            )));
+        }
 
-            CREATE TABLE IF NOT EXISTS transcripts (
-                video_id TEXT PRIMARY KEY,
-                video_title TEXT NOT NULL,
-                transcript_json TEXT NOT NULL,
-                duration_seconds REAL NOT NULL,
-                transcribed_at TEXT NOT NULL
+        let config = ConnectionConfig::new(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX, "");
+        let pool = config.open()?;
+
+        // Replicas are never migrated; the primary upgrades them
+        let version = migrations::schema_version(&*Self::pooled(&pool)?)?;
+        if version != migrations::latest_version() {
+            warn!(
+                "Read-only database has schema version {}, this version of lytt expects {}",
+                version,
+                migrations::latest_version()
             );
-            "#,
-        )?;
+        }
+
+        info!("Opened read-only SQLite vector store at {:?}", path);
 
         Ok(Self {
-            conn: Mutex::new(conn),
+            pool,
+            config: Some(config),
+            indexes: Vec::new(),
+            read_only: true,
+            replication: None,
+            vector_format: VectorFormat::default(),
+            quantized: None,
+            ann: None,
+        })
+    }
+
+    /// Configure the connection for Litestream/LiteFS replication: WAL mode with
+    /// automatic checkpoints disabled, a busy timeout so writes wait for the
+    /// replication tool's locks, and paged scans instead of long-held reads.
+    pub fn with_replication(mut self, settings: &ReplicationSettings) -> Result<Self> {
+        if !settings.enabled {
+            return Ok(self);
+        }
+
+        // Reopen so every pooled connection gets the replication pragmas
+        if let Some(config) = self.config.as_mut() {
+            config.pragmas = REPLICATION_PRAGMAS;
+            config.busy_timeout = Duration::from_millis(settings.busy_timeout_ms);
+            self.pool = config.open()?;
+        }
+
+        self.replication = Some(Arc::new(Replication {
+            checkpoint_interval: (settings.checkpoint_interval_secs > 0)
+                .then(|| Duration::from_secs(settings.checkpoint_interval_secs)),
+            last_checkpoint: Mutex::new(Instant::now()),
+        }));
+        Ok(self)
+    }
+
+    /// Keep up to `size` connections open, so that many searches, writes
+    /// and other queries can run at once. In-memory stores always use one.
+    pub fn with_pool_size(mut self, size: u32) -> Result<Self> {
+        if let Some(config) = self.config.as_mut() {
+            if config.pool_size != size {
+                config.pool_size = size;
+                self.pool = config.open()?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Whether the database was opened read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Take a connection from the pool, waiting if all are in use.
+    fn conn(&self) -> Result<PooledConnection> {
+        Self::pooled(&self.pool)
+    }
+
+    /// Run blocking database work on tokio's blocking pool, so waiting for a
+    /// connection or a query never stalls the async workers. Async code calls
+    /// the store's other (synchronous) methods through this.
+    pub async fn blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SqliteVectorStore) -> Result<T> + Send + 'static,
+    {
+        let store = self.clone();
+        let span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || span.in_scope(|| f(&store)))
+            .await
+            .map_err(|e| LyttError::VectorStore(format!("Database task failed: {}", e)))?
+    }
+
+    fn pooled(pool: &Pool) -> Result<PooledConnection> {
+        pool.get()
+            .map_err(|e| LyttError::VectorStore(format!("Failed to get a database connection: {}", e)))
+    }
+
+    /// Run a passive WAL checkpoint if the configured interval has elapsed.
+    /// Passive checkpoints never block readers or the replication tool.
+    fn maybe_checkpoint(&self, conn: &Connection) {
+        let Some(replication) = &self.replication else {
+            return;
+        };
+        let Some(interval) = replication.checkpoint_interval else {
+            return;
+        };
+        let Ok(mut last) = replication.last_checkpoint.lock() else {
+            return;
+        };
+        if last.elapsed() < interval {
+            return;
+        }
+
+        match conn.query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |row| {
+            Ok((row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+        }) {
+            Ok((log, checkpointed)) => debug!("Checkpointed {} of {} WAL frames", checkpointed, log),
+            Err(e) => debug!("WAL checkpoint skipped: {}", e),
+        }
+        *last = Instant::now();
+    }
+
+    /// Store new embeddings in this format. Existing vectors keep theirs
+    /// until they are rewritten (see `lytt reembed --local`).
+    pub fn with_vector_format(mut self, format: VectorFormat) -> Self {
+        self.vector_format = format;
+        self
+    }
+
+    /// Keep quantized copies of the embeddings and search in two stages:
+    /// score the codes, then rescore `rescore_factor` candidates per result
+    /// with the full embeddings. Existing libraries need
+    /// `lytt index rebuild --quantized` once; until then searches scan
+    /// every embedding.
+    pub fn with_quantization(mut self, quantization: Quantization, rescore_factor: usize) -> Self {
+        let index = Arc::new(QuantizedIndex::new(quantization, rescore_factor));
+        self.indexes.push(index.clone());
+        self.quantized = Some(index);
+        self
+    }
+
+    /// Search through an in-memory HNSW graph instead of scanning every
+    /// embedding. The graph starts empty; fill it with
+    /// `rebuild_index(IndexKind::Ann, ..)` after opening the store.
+    pub fn with_ann(mut self) -> Self {
+        let index = Arc::new(AnnIndex::new());
+        self.indexes.push(index.clone());
+        self.ann = Some(index);
+        self
+    }
+
+    /// Register a derived index, maintained on every upsert and delete.
+    pub fn with_index(mut self, index: Arc<dyn DerivedIndex>) -> Self {
+        self.indexes.push(index);
+        self
+    }
+
+    /// Map a row selected with `DOCUMENT_COLUMNS` to a document.
+    fn row_to_document(row: &rusqlite::Row) -> rusqlite::Result<Document> {
+        let id_str: String = row.get(0)?;
+        let embedding_bytes: Vec<u8> = row.get(7)?;
+        let source_created_str: Option<String> = row.get(9)?;
+        let indexed_at_str: String = row.get(10)?;
+        let doc_type: String = row.get(11)?;
+        let provenance: Option<String> = row.get(12)?;
+        let keywords: Option<String> = row.get(13)?;
+        let language: Option<String> = row.get(14)?;
+        let summary: Option<String> = row.get(15)?;
+        let format: VectorFormat = row.get::<_, String>(16)?.parse().unwrap_or_default();
+
+        Ok(Document {
+            id: uuid::Uuid::parse_str(&id_str).unwrap_or_default(),
+            video_id: row.get(1)?,
+            video_title: row.get(2)?,
+            section_title: row.get(3)?,
+            content: row.get(4)?,
+            start_seconds: row.get(5)?,
+            end_seconds: row.get(6)?,
+            embedding: format.decode(&embedding_bytes),
+            chunk_order: row.get(8)?,
+            source_created_at: source_created_str.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
+            indexed_at: DateTime::parse_from_rfc3339(&indexed_at_str)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            doc_type: doc_type.parse().unwrap_or_default(),
+            provenance: provenance
+                .and_then(|p| serde_json::from_str(&p).ok())
+                .unwrap_or_default(),
+            keywords: keywords
+                .and_then(|k| serde_json::from_str(&k).ok())
+                .unwrap_or_default(),
+            language,
+            summary,
+            book: row.get(17)?,
+            author: row.get(18)?,
+            chapter: row.get(19)?,
         })
     }
 
+    /// Serialize provenance, storing NULL for documents without any.
+    fn provenance_json(doc: &Document) -> Result<Option<String>> {
+        if doc.provenance.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(&doc.provenance)?))
+    }
+
+    /// Serialize keywords, storing NULL for documents without any.
+    fn keywords_json(doc: &Document) -> Result<Option<String>> {
+        if doc.keywords.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(&doc.keywords)?))
+    }
+
     /// Serialize embedding to bytes.
-    fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
+    pub(crate) fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
         embedding
             .iter()
             .flat_map(|f| f.to_le_bytes())
@@ -115,7 +412,7 @@ impl SqliteVectorStore {
     }
 
     /// Deserialize embedding from bytes.
-    fn bytes_to_embedding(bytes: &[u8]) -> Vec<f32> {
+    pub(crate) fn bytes_to_embedding(bytes: &[u8]) -> Vec<f32> {
         bytes
             .chunks_exact(4)
             .map(|chunk| {
@@ -130,18 +427,138 @@ impl SqliteVectorStore {
 impl VectorStore for SqliteVectorStore {
     #[instrument(skip(self, doc))]
     async fn upsert(&self, doc: &Document) -> Result<()> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+        let doc = doc.clone();
+        self.blocking(move |store| store.upsert_blocking(&doc)).await
+    }
+
+    #[instrument(skip(self, docs))]
+    async fn upsert_batch(&self, docs: &[Document]) -> Result<usize> {
+        let docs = docs.to_vec();
+        self.blocking(move |store| store.upsert_batch_blocking(&docs)).await
+    }
+
+    #[instrument(skip(self, query_embedding))]
+    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_with_threshold(query_embedding, limit, 0.0).await
+    }
+
+    #[instrument(skip(self, query_embedding))]
+    async fn search_with_threshold(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_filtered(query_embedding, limit, min_score, &SearchFilter::default())
+            .await
+    }
+
+    #[instrument(skip(self, query_embedding, filter))]
+    async fn search_filtered(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        let query_embedding = query_embedding.to_vec();
+        let filter = filter.clone();
+        self.blocking(move |store| store.scan(&query_embedding, limit, min_score, &filter, None))
+            .await
+            .map(|r| r.results)
+    }
+
+    #[instrument(skip(self, query_embedding))]
+    async fn search_with_deadline(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+        deadline: Instant,
+    ) -> Result<PartialResults> {
+        let query_embedding = query_embedding.to_vec();
+        let filter = filter.clone();
+        self.blocking(move |store| {
+            store.scan(&query_embedding, limit, min_score, &filter, Some(deadline))
+        })
+        .await
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_by_video_id(&self, video_id: &str) -> Result<usize> {
+        let video_id = video_id.to_string();
+        self.blocking(move |store| store.delete_by_video_id_blocking(&video_id)).await
+    }
+
+    #[instrument(skip(self))]
+    async fn list_videos(&self) -> Result<Vec<IndexedVideo>> {
+        self.blocking(move |store| store.list_videos_blocking()).await
+    }
+
+    #[instrument(skip(self))]
+    async fn get_video(&self, video_id: &str) -> Result<Option<IndexedVideo>> {
+        let video_id = video_id.to_string();
+        self.blocking(move |store| store.get_video_blocking(&video_id)).await
+    }
+
+    async fn is_video_indexed(&self, video_id: &str) -> Result<bool> {
+        let video = self.get_video(video_id).await?;
+        Ok(video.is_some())
+    }
+
+    #[instrument(skip(self))]
+    async fn get_by_video_id(&self, video_id: &str) -> Result<Vec<Document>> {
+        let video_id = video_id.to_string();
+        self.blocking(move |store| store.get_by_video_id_blocking(&video_id)).await
+    }
+
+    async fn get_neighbors(&self, video_id: &str, chunk_order: i32, window: usize) -> Result<Vec<Document>> {
+        let video_id = video_id.to_string();
+        self.blocking(move |store| store.get_neighbors_blocking(&video_id, chunk_order, window)).await
+    }
+
+    async fn document_count(&self) -> Result<usize> {
+        self.blocking(move |store| store.document_count_blocking()).await
+    }
+
+    async fn record_citations(&self, ids: &[uuid::Uuid]) -> Result<()> {
+        let ids = ids.to_vec();
+        self.blocking(move |store| store.record_citations_blocking(&ids)).await
+    }
+
+    async fn corpus_version(&self) -> Result<Option<i64>> {
+        self.blocking(move |store| store.corpus_version_blocking()).await
+    }
 
-        let embedding_bytes = Self::embedding_to_bytes(&doc.embedding);
+    async fn cached_answer(&self, key: &str, version: i64, max_age: Duration) -> Result<Option<String>> {
+        let key = key.to_string();
+        self.blocking(move |store| store.cached_answer_blocking(&key, version, max_age)).await
+    }
+
+    async fn cache_answer(&self, key: &str, version: i64, answer: &str) -> Result<()> {
+        let key = key.to_string();
+        let answer = answer.to_string();
+        self.blocking(move |store| store.cache_answer_blocking(&key, version, &answer)).await
+    }
+}
+
+// Blocking bodies of the VectorStore methods, run through `blocking`
+impl SqliteVectorStore {
+    fn upsert_blocking(&self, doc: &Document) -> Result<()> {
+        let conn = self.conn()?;
+
+        let embedding_bytes = self.vector_format.encode(&doc.embedding);
+        let provenance = Self::provenance_json(doc)?;
+        let keywords = Self::keywords_json(doc)?;
 
         conn.execute(
             r#"
             INSERT OR REPLACE INTO documents
             (id, video_id, video_title, section_title, content, start_seconds, end_seconds,
-             embedding, chunk_order, source_created_at, indexed_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             embedding, chunk_order, source_created_at, indexed_at, doc_type, provenance, keywords, language, summary,
+             embedding_format, book, author, chapter)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
             "#,
             params![
                 doc.id.to_string(),
@@ -155,30 +572,44 @@ impl VectorStore for SqliteVectorStore {
                 doc.chunk_order,
                 doc.source_created_at.map(|dt| dt.to_rfc3339()),
                 doc.indexed_at.to_rfc3339(),
+                doc.doc_type.as_str(),
+                provenance,
+                keywords,
+                doc.language,
+                doc.summary,
+                self.vector_format.as_str(),
+                doc.book,
+                doc.author,
+                doc.chapter,
             ],
         )?;
 
+        for index in &self.indexes {
+            index.on_upsert(&conn, std::slice::from_ref(doc))?;
+        }
+
+        self.maybe_checkpoint(&conn);
         debug!("Upserted document {}", doc.id);
         Ok(())
     }
 
-    #[instrument(skip(self, docs))]
-    async fn upsert_batch(&self, docs: &[Document]) -> Result<usize> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    fn upsert_batch_blocking(&self, docs: &[Document]) -> Result<usize> {
+        let conn = self.conn()?;
 
         let tx = conn.unchecked_transaction()?;
 
         for doc in docs {
-            let embedding_bytes = Self::embedding_to_bytes(&doc.embedding);
+            let embedding_bytes = self.vector_format.encode(&doc.embedding);
+            let provenance = Self::provenance_json(doc)?;
+            let keywords = Self::keywords_json(doc)?;
 
             tx.execute(
                 r#"
                 INSERT OR REPLACE INTO documents
                 (id, video_id, video_title, section_title, content, start_seconds, end_seconds,
-                 embedding, chunk_order, source_created_at, indexed_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 embedding, chunk_order, source_created_at, indexed_at, doc_type, provenance, keywords, language, summary,
+                 embedding_format, book, author, chapter)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
                 "#,
                 params![
                     doc.id.to_string(),
@@ -192,106 +623,61 @@ impl VectorStore for SqliteVectorStore {
                     doc.chunk_order,
                     doc.source_created_at.map(|dt| dt.to_rfc3339()),
                     doc.indexed_at.to_rfc3339(),
+                    doc.doc_type.as_str(),
+                    provenance,
+                    keywords,
+                    doc.language,
+                    doc.summary,
+                    self.vector_format.as_str(),
+                    doc.book,
+                    doc.author,
+                    doc.chapter,
                 ],
             )?;
         }
 
+        for index in &self.indexes {
+            index.on_upsert(&tx, docs)?;
+        }
+
         tx.commit()?;
+        self.maybe_checkpoint(&conn);
         info!("Batch upserted {} documents", docs.len());
         Ok(docs.len())
     }
 
-    #[instrument(skip(self, query_embedding))]
-    async fn search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
-        self.search_with_threshold(query_embedding, limit, 0.0).await
-    }
+    fn delete_by_video_id_blocking(&self, video_id: &str) -> Result<usize> {
+        let conn = self.conn()?;
 
-    #[instrument(skip(self, query_embedding))]
-    async fn search_with_threshold(
-        &self,
-        query_embedding: &[f32],
-        limit: usize,
-        min_score: f32,
-    ) -> Result<Vec<SearchResult>> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+        let tx = conn.unchecked_transaction()?;
 
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT id, video_id, video_title, section_title, content,
-                   start_seconds, end_seconds, embedding, chunk_order,
-                   source_created_at, indexed_at
-            FROM documents
-            "#,
+        let deleted = tx.execute(
+            "DELETE FROM documents WHERE video_id = ?1",
+            params![video_id],
         )?;
 
-        let docs = stmt.query_map([], |row| {
-            let id_str: String = row.get(0)?;
-            let embedding_bytes: Vec<u8> = row.get(7)?;
-            let source_created_str: Option<String> = row.get(9)?;
-            let indexed_at_str: String = row.get(10)?;
-
-            Ok(Document {
-                id: uuid::Uuid::parse_str(&id_str).unwrap_or_default(),
-                video_id: row.get(1)?,
-                video_title: row.get(2)?,
-                section_title: row.get(3)?,
-                content: row.get(4)?,
-                start_seconds: row.get(5)?,
-                end_seconds: row.get(6)?,
-                embedding: Self::bytes_to_embedding(&embedding_bytes),
-                chunk_order: row.get(8)?,
-                source_created_at: source_created_str.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
-                indexed_at: DateTime::parse_from_rfc3339(&indexed_at_str)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
-
-        let mut results: Vec<SearchResult> = docs
-            .filter_map(|doc_result| doc_result.ok())
-            .map(|doc| {
-                let score = cosine_similarity(query_embedding, &doc.embedding);
-                SearchResult { document: doc, score }
-            })
-            .filter(|r| r.score >= min_score)
-            .collect();
-
-        // Sort by score descending
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-        results.truncate(limit);
-
-        debug!("Found {} matching documents", results.len());
-        Ok(results)
-    }
+        tx.execute("DELETE FROM retrieval_stats WHERE video_id = ?1", params![video_id])?;
 
-    #[instrument(skip(self))]
-    async fn delete_by_video_id(&self, video_id: &str) -> Result<usize> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+        for index in &self.indexes {
+            index.on_delete(&tx, video_id)?;
+        }
 
-        let deleted = conn.execute(
-            "DELETE FROM documents WHERE video_id = ?1",
-            params![video_id],
-        )?;
+        tx.commit()?;
+        self.maybe_checkpoint(&conn);
 
         info!("Deleted {} documents for video {}", deleted, video_id);
         Ok(deleted)
     }
 
-    #[instrument(skip(self))]
-    async fn list_videos(&self) -> Result<Vec<IndexedVideo>> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    fn list_videos_blocking(&self) -> Result<Vec<IndexedVideo>> {
+        let conn = self.conn()?;
 
         let mut stmt = conn.prepare(
             r#"
             SELECT video_id, video_title, COUNT(*) as chunk_count,
-                   MAX(end_seconds) as total_duration, MAX(indexed_at) as indexed_at
+                   MAX(end_seconds) as total_duration, MAX(indexed_at) as indexed_at, MAX(language)
             FROM documents
+            WHERE doc_type = 'source'
             GROUP BY video_id
             ORDER BY indexed_at DESC
             "#,
@@ -307,6 +693,7 @@ impl VectorStore for SqliteVectorStore {
                 indexed_at: DateTime::parse_from_rfc3339(&indexed_at_str)
                     .map(|dt| dt.with_timezone(&Utc))
                     .unwrap_or_else(|_| Utc::now()),
+                language: row.get(5)?,
             })
         })?;
 
@@ -314,18 +701,15 @@ impl VectorStore for SqliteVectorStore {
         Ok(result)
     }
 
-    #[instrument(skip(self))]
-    async fn get_video(&self, video_id: &str) -> Result<Option<IndexedVideo>> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    fn get_video_blocking(&self, video_id: &str) -> Result<Option<IndexedVideo>> {
+        let conn = self.conn()?;
 
         let mut stmt = conn.prepare(
             r#"
             SELECT video_id, video_title, COUNT(*) as chunk_count,
-                   MAX(end_seconds) as total_duration, MAX(indexed_at) as indexed_at
+                   MAX(end_seconds) as total_duration, MAX(indexed_at) as indexed_at, MAX(language)
             FROM documents
-            WHERE video_id = ?1
+            WHERE video_id = ?1 AND doc_type = 'source'
             GROUP BY video_id
             "#,
         )?;
@@ -340,6 +724,7 @@ impl VectorStore for SqliteVectorStore {
                 indexed_at: DateTime::parse_from_rfc3339(&indexed_at_str)
                     .map(|dt| dt.with_timezone(&Utc))
                     .unwrap_or_else(|_| Utc::now()),
+                language: row.get(5)?,
             })
         });
 
@@ -350,164 +735,1855 @@ impl VectorStore for SqliteVectorStore {
         }
     }
 
-    async fn is_video_indexed(&self, video_id: &str) -> Result<bool> {
-        let video = self.get_video(video_id).await?;
-        Ok(video.is_some())
-    }
-
-    #[instrument(skip(self))]
-    async fn get_by_video_id(&self, video_id: &str) -> Result<Vec<Document>> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
-
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT id, video_id, video_title, section_title, content,
-                   start_seconds, end_seconds, embedding, chunk_order,
-                   source_created_at, indexed_at
-            FROM documents
-            WHERE video_id = ?1
-            ORDER BY chunk_order
-            "#,
-        )?;
+    fn get_by_video_id_blocking(&self, video_id: &str) -> Result<Vec<Document>> {
+        let conn = self.conn()?;
 
-        let docs = stmt.query_map(params![video_id], |row| {
-            let id_str: String = row.get(0)?;
-            let embedding_bytes: Vec<u8> = row.get(7)?;
-            let source_created_str: Option<String> = row.get(9)?;
-            let indexed_at_str: String = row.get(10)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM documents WHERE video_id = ?1 ORDER BY chunk_order",
+            DOCUMENT_COLUMNS
+        ))?;
 
-            Ok(Document {
-                id: uuid::Uuid::parse_str(&id_str).unwrap_or_default(),
-                video_id: row.get(1)?,
-                video_title: row.get(2)?,
-                section_title: row.get(3)?,
-                content: row.get(4)?,
-                start_seconds: row.get(5)?,
-                end_seconds: row.get(6)?,
-                embedding: Self::bytes_to_embedding(&embedding_bytes),
-                chunk_order: row.get(8)?,
-                source_created_at: source_created_str.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
-                indexed_at: DateTime::parse_from_rfc3339(&indexed_at_str)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-            })
-        })?;
+        let docs = stmt.query_map(params![video_id], Self::row_to_document)?;
 
         let result: Vec<Document> = docs.filter_map(|d| d.ok()).collect();
         debug!("Found {} documents for video {}", result.len(), video_id);
         Ok(result)
     }
 
-    async fn document_count(&self) -> Result<usize> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    fn get_neighbors_blocking(&self, video_id: &str, chunk_order: i32, window: usize) -> Result<Vec<Document>> {
+        let conn = self.conn()?;
+
+        let window = window as i32;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM documents
+             WHERE video_id = ?1 AND doc_type != 'derived' AND chunk_order BETWEEN ?2 AND ?3 AND chunk_order != ?4
+             ORDER BY chunk_order",
+            DOCUMENT_COLUMNS
+        ))?;
+
+        let docs = stmt.query_map(
+            params![video_id, chunk_order - window, chunk_order + window, chunk_order],
+            Self::row_to_document,
+        )?;
+        Ok(docs.filter_map(|d| d.ok()).collect())
+    }
+
+    fn document_count_blocking(&self) -> Result<usize> {
+        let conn = self.conn()?;
 
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
         Ok(count as usize)
     }
-}
 
-// Transcript storage methods (not part of VectorStore trait)
-impl SqliteVectorStore {
-    /// Store a raw transcript for later rechunking.
-    pub fn store_transcript(
-        &self,
-        video_id: &str,
-        video_title: &str,
-        transcript: &crate::transcription::Transcript,
-    ) -> Result<()> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    fn record_citations_blocking(&self, ids: &[uuid::Uuid]) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
 
-        let transcript_json = serde_json::to_string(transcript)
-            .map_err(|e| LyttError::VectorStore(format!("Failed to serialize transcript: {}", e)))?;
+        let conn = self.conn()?;
 
-        conn.execute(
-            r#"
+        for id in ids {
+            conn.execute(
+                r#"
+                INSERT INTO retrieval_stats (document_id, video_id, retrieval_count, citation_count)
+                SELECT id, video_id, 0, 1 FROM documents WHERE id = ?1
+                ON CONFLICT(document_id) DO UPDATE SET citation_count = citation_count + 1
+                "#,
+                params![id.to_string()],
+            )?;
+        }
+
+        debug!("Recorded {} citations", ids.len());
+        Ok(())
+    }
+
+    fn corpus_version_blocking(&self) -> Result<Option<i64>> {
+        let conn = self.conn()?;
+
+        let version = conn.query_row("SELECT version FROM corpus_version WHERE id = 1", [], |row| row.get(0))?;
+        Ok(Some(version))
+    }
+
+    fn cached_answer_blocking(&self, key: &str, version: i64, max_age: Duration) -> Result<Option<String>> {
+        let conn = self.conn()?;
+
+        let oldest = chrono::Duration::from_std(max_age)
+            .ok()
+            .and_then(|age| Utc::now().checked_sub_signed(age))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let result = conn.query_row(
+            "SELECT answer FROM answer_cache WHERE key = ?1 AND version = ?2 AND created_at >= ?3",
+            params![key, version, oldest.to_rfc3339()],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(answer) => Ok(Some(answer)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn cache_answer_blocking(&self, key: &str, version: i64, answer: &str) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+
+        let conn = self.conn()?;
+
+        // Answers for older corpus versions can never be served again
+        conn.execute("DELETE FROM answer_cache WHERE version < ?1", params![version])?;
+        conn.execute(
+            "INSERT OR REPLACE INTO answer_cache (key, version, answer, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![key, version, answer, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+}
+
+// Search helpers (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Score documents against the query, stopping early once `deadline` passes.
+    fn scan(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+        deadline: Option<Instant>,
+    ) -> Result<PartialResults> {
+        let conn = self.conn()?;
+
+        if let Some(results) = self.ann_search(&conn, query_embedding, limit, min_score, filter)? {
+            if !self.read_only {
+                Self::record_retrievals(&conn, &results)?;
+            }
+            debug!("Found {} matching documents in the ANN index", results.len());
+            return Ok(PartialResults { results, partial: false });
+        }
+
+        if let Some(results) = self.quantized_search(&conn, query_embedding, limit, min_score, filter)? {
+            if !self.read_only {
+                Self::record_retrievals(&conn, &results)?;
+            }
+            debug!("Found {} matching documents in two stages", results.len());
+            return Ok(PartialResults { results, partial: false });
+        }
+
+        let filter_clause = match filter.derived {
+            DerivedFilter::Include => "",
+            DerivedFilter::Exclude => "AND doc_type != 'derived'",
+            DerivedFilter::Only => "AND doc_type = 'derived'",
+        };
+
+        // ?3 to ?5 are always bound so the statement shape stays the same
+        let video_clause = match filter.video_id {
+            Some(_) => "AND video_id = ?3",
+            None => "AND ?3 IS NULL",
+        };
+        let topic_clause = match filter.topic {
+            Some(_) => "AND id IN (SELECT document_id FROM document_topics WHERE topic_id = ?4)",
+            None => "AND ?4 IS NULL",
+        };
+        let series_clause = match filter.series {
+            Some(_) => "AND video_id IN (SELECT video_id FROM series_media WHERE series = ?5)",
+            None => "AND ?5 IS NULL",
+        };
+
+        // Replicated databases are read in pages, each its own short read
+        // transaction, so checkpoints and replica updates aren't held up.
+        let paged = self.read_only || self.replication.is_some();
+        let page_size = if paged { SCAN_PAGE_SIZE } else { -1 };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, rowid FROM documents WHERE rowid > ?1 {} {} {} {} ORDER BY rowid LIMIT ?2",
+            DOCUMENT_COLUMNS, filter_clause, video_clause, topic_clause, series_clause
+        ))?;
+
+        let mut results: Vec<SearchResult> = Vec::new();
+        let mut partial = false;
+        let mut scanned = 0usize;
+        let mut last_rowid = i64::MIN;
+
+        'pages: loop {
+            // Rows are scored as they're read, so the deadline also bounds an unpaged scan
+            let mut rows = stmt.query(params![last_rowid, page_size, filter.video_id, filter.topic, filter.series])?;
+            let mut fetched = 0i64;
+
+            while let Some(row) = rows.next()? {
+                if scanned.is_multiple_of(DEADLINE_CHECK_INTERVAL) && deadline.is_some_and(|d| Instant::now() >= d) {
+                    partial = true;
+                    break 'pages;
+                }
+                let doc = Self::row_to_document(row)?;
+                fetched += 1;
+                scanned += 1;
+                last_rowid = row.get(20)?;
+
+                if !filter.matches(&doc) {
+                    continue;
+                }
+
+                let score = cosine_similarity(query_embedding, &doc.embedding);
+                if score >= min_score {
+                    results.push(SearchResult { document: doc, score });
+                }
+            }
+
+            if !paged || fetched < page_size {
+                break;
+            }
+        }
+        drop(stmt);
+
+        // Sort by score descending
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+
+        if !self.read_only {
+            Self::record_retrievals(&conn, &results)?;
+        }
+
+        if partial {
+            debug!("Search deadline reached, returning {} partial results", results.len());
+        } else {
+            debug!("Found {} matching documents", results.len());
+        }
+        Ok(PartialResults { results, partial })
+    }
+
+    /// Search the in-memory ANN graph. Returns `None` under the same
+    /// conditions as `quantized_search`, and for searches of derived
+    /// documents only, which are too sparse in the graph to find reliably.
+    fn ann_search(
+        &self,
+        conn: &Connection,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Option<Vec<SearchResult>>> {
+        let Some(index) = &self.ann else {
+            return Ok(None);
+        };
+        if filter.is_narrow() || filter.derived == DerivedFilter::Only {
+            return Ok(None);
+        }
+        if !Self::covers_all_documents(conn, index.as_ref())? {
+            debug!("ANN index is out of date, scanning all embeddings");
+            return Ok(None);
+        }
+
+        let candidates = index.search(query_embedding, limit, filter.derived)?;
+        let ids = candidates.into_iter().map(|(id, _)| id).collect();
+        Self::score_candidates(conn, query_embedding, ids, limit, min_score).map(Some)
+    }
+
+    /// Search with the quantized index: preselect candidates by their codes,
+    /// then rescore them exactly. Returns `None` when the index is disabled,
+    /// doesn't cover every document yet, or the filter is narrow enough that
+    /// candidates picked without it might all be filtered out.
+    fn quantized_search(
+        &self,
+        conn: &Connection,
+        query_embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        filter: &SearchFilter,
+    ) -> Result<Option<Vec<SearchResult>>> {
+        let Some(index) = &self.quantized else {
+            return Ok(None);
+        };
+        if filter.is_narrow() {
+            return Ok(None);
+        }
+        if !Self::covers_all_documents(conn, index.as_ref())? {
+            debug!("Quantized index is out of date, scanning all embeddings");
+            return Ok(None);
+        }
+
+        let candidates = index.candidates(conn, query_embedding, index.candidate_count(limit), filter.derived)?;
+        Self::score_candidates(conn, query_embedding, candidates, limit, min_score).map(Some)
+    }
+
+    /// Whether a derived index has an entry for every stored document.
+    fn covers_all_documents(conn: &Connection, index: &dyn DerivedIndex) -> Result<bool> {
+        let documents: i64 = conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+        Ok(index.entry_count(conn)? == documents as usize)
+    }
+
+    /// Load candidate documents and score them exactly against the query.
+    fn score_candidates(
+        conn: &Connection,
+        query_embedding: &[f32],
+        candidates: Vec<String>,
+        limit: usize,
+        min_score: f32,
+    ) -> Result<Vec<SearchResult>> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM documents WHERE id = ?1", DOCUMENT_COLUMNS))?;
+        let mut results = Vec::with_capacity(candidates.len());
+        for id in candidates {
+            let doc = match stmt.query_row(params![id], Self::row_to_document) {
+                Ok(doc) => doc,
+                Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let score = cosine_similarity(query_embedding, &doc.embedding);
+            if score >= min_score {
+                results.push(SearchResult { document: doc, score });
+            }
+        }
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Count the documents returned by a search, for retention and usage stats.
+    fn record_retrievals(conn: &Connection, results: &[SearchResult]) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        for result in results {
+            conn.execute(
+                r#"
+                INSERT INTO retrieval_stats (document_id, video_id, retrieval_count, last_retrieved_at)
+                VALUES (?1, ?2, 1, ?3)
+                ON CONFLICT(document_id) DO UPDATE SET
+                    retrieval_count = retrieval_count + 1,
+                    last_retrieved_at = excluded.last_retrieved_at
+                "#,
+                params![result.document.id.to_string(), result.document.video_id, now],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Derived index methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Kinds of the registered derived indexes.
+    pub fn index_kinds(&self) -> Vec<IndexKind> {
+        self.indexes.iter().map(|i| i.kind()).collect()
+    }
+
+    /// Rebuild a derived index from the stored documents.
+    ///
+    /// `progress` is called with (documents processed, total documents).
+    pub fn rebuild_index(
+        &self,
+        kind: IndexKind,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<IndexStatus> {
+        let index = self
+            .indexes
+            .iter()
+            .find(|i| i.kind() == kind)
+            .ok_or_else(|| LyttError::VectorStore(format!("The {} index is not enabled", kind)))?;
+
+        let conn = self.conn()?;
+
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+        let total = total as usize;
+
+        let tx = conn.unchecked_transaction()?;
+        index.clear(&tx)?;
+
+        let mut stmt = tx.prepare(&format!("SELECT {} FROM documents", DOCUMENT_COLUMNS))?;
+        let docs = stmt.query_map([], Self::row_to_document)?;
+
+        let mut batch = Vec::with_capacity(REBUILD_BATCH_SIZE);
+        let mut processed = 0;
+        progress(processed, total);
+
+        for doc in docs {
+            batch.push(doc?);
+            if batch.len() == REBUILD_BATCH_SIZE {
+                index.on_upsert(&tx, &batch)?;
+                processed += batch.len();
+                progress(processed, total);
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            index.on_upsert(&tx, &batch)?;
+            processed += batch.len();
+            progress(processed, total);
+        }
+
+        drop(stmt);
+        tx.commit()?;
+
+        let status = IndexStatus {
+            kind,
+            entries: index.entry_count(&conn)?,
+            documents: total,
+        };
+        info!("Rebuilt {} index ({} entries)", kind, status.entries);
+        Ok(status)
+    }
+
+    /// Check every registered index against the documents table.
+    pub fn index_status(&self) -> Result<Vec<IndexStatus>> {
+        let conn = self.conn()?;
+
+        let documents: i64 = conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+
+        self.indexes
+            .iter()
+            .map(|index| {
+                Ok(IndexStatus {
+                    kind: index.kind(),
+                    entries: index.entry_count(&conn)?,
+                    documents: documents as usize,
+                })
+            })
+            .collect()
+    }
+}
+
+// Maintenance methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Schema version of the database (see `migrations`).
+    pub fn schema_version(&self) -> Result<u32> {
+        migrations::schema_version(&*self.conn()?)
+    }
+
+    /// Copy the WAL into the database file. A passive checkpoint never waits;
+    /// `truncate` waits for readers to finish and then empties the WAL file.
+    pub fn checkpoint(&self, truncate: bool) -> Result<Checkpoint> {
+        let conn = self.conn()?;
+        let mode = if truncate { "TRUNCATE" } else { "PASSIVE" };
+        // Not in WAL mode (e.g. in memory): the frame counts are -1
+        let (busy, log, checkpointed) = conn.query_row(&format!("PRAGMA wal_checkpoint({})", mode), [], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        Ok(Checkpoint {
+            busy: busy != 0,
+            wal_frames: log.max(0) as u64,
+            checkpointed: checkpointed.max(0) as u64,
+        })
+    }
+
+    /// Rebuild the database file without free pages.
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn()?.execute_batch("VACUUM")?;
+        info!("Vacuumed SQLite vector store");
+        Ok(())
+    }
+
+    /// Refresh the statistics SQLite's query planner uses.
+    pub fn analyze(&self) -> Result<()> {
+        self.conn()?.execute_batch("ANALYZE")?;
+        Ok(())
+    }
+
+    /// Check the database for corruption; returns the problems found.
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut problems = Vec::new();
+        for row in rows {
+            let row = row?;
+            if row != "ok" {
+                problems.push(row);
+            }
+        }
+        Ok(problems)
+    }
+
+    /// Size of the database and WAL files, and the space free pages take up.
+    pub fn database_size(&self) -> Result<DatabaseSize> {
+        let conn = self.conn()?;
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+
+        let wal_bytes = self.config.as_ref().map_or(0, |config| {
+            let mut wal = config.path.clone().into_os_string();
+            wal.push("-wal");
+            std::fs::metadata(wal).map(|m| m.len()).unwrap_or(0)
+        });
+
+        Ok(DatabaseSize {
+            file_bytes: (pages * page_size) as u64,
+            free_bytes: (free * page_size) as u64,
+            wal_bytes,
+        })
+    }
+
+    /// Space used by each table, including its indexes, largest first.
+    pub fn table_sizes(&self) -> Result<Vec<TableSize>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT COALESCE(m.tbl_name, s.name) AS name, SUM(s.pgsize)
+            FROM dbstat s LEFT JOIN sqlite_master m ON m.name = s.name
+            GROUP BY 1
+            ORDER BY 2 DESC
+            "#,
+        )?;
+        let sizes: Vec<(String, i64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        sizes
+            .into_iter()
+            .map(|(name, bytes)| {
+                let rows: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", name), [], |row| row.get(0))?;
+                Ok(TableSize { name, rows: rows as u64, bytes: bytes as u64 })
+            })
+            .collect()
+    }
+
+    /// Truncate the WAL every `interval` in the background. Long-running
+    /// processes whose connections keep reading can stop SQLite's automatic
+    /// checkpoints from ever resetting the WAL, so it would grow unbounded.
+    pub fn spawn_checkpoints(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let store = self.clone();
+                match tokio::task::spawn_blocking(move || store.checkpoint(true)).await {
+                    Ok(Ok(checkpoint)) if checkpoint.busy => debug!("WAL checkpoint incomplete: database busy"),
+                    Ok(Ok(checkpoint)) => debug!("Checkpointed {} WAL frames", checkpoint.checkpointed),
+                    Ok(Err(e)) => warn!("WAL checkpoint failed: {}", e),
+                    Err(e) => warn!("WAL checkpoint task failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+// Retrieval statistics methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Retrieval and citation counts per media item, most cited first.
+    /// Derived documents (rollups, summaries) are not included.
+    pub fn video_retrieval_stats(&self) -> Result<Vec<VideoRetrievalStats>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.video_id, MAX(d.video_title), COUNT(*),
+                   SUM(CASE WHEN COALESCE(r.retrieval_count, 0) > 0 THEN 1 ELSE 0 END),
+                   COALESCE(SUM(r.retrieval_count), 0), COALESCE(SUM(r.citation_count), 0),
+                   MAX(r.last_retrieved_at)
+            FROM documents d
+            LEFT JOIN retrieval_stats r ON r.document_id = d.id
+            WHERE d.doc_type = 'source'
+            GROUP BY d.video_id
+            ORDER BY 6 DESC, 5 DESC, 2
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let last_retrieved_at: Option<String> = row.get(6)?;
+            Ok(VideoRetrievalStats {
+                video_id: row.get(0)?,
+                video_title: row.get(1)?,
+                chunk_count: row.get(2)?,
+                retrieved_chunks: row.get(3)?,
+                retrievals: row.get::<_, i64>(4)? as u64,
+                citations: row.get::<_, i64>(5)? as u64,
+                last_retrieved_at: last_retrieved_at.and_then(|s| parse_datetime(&s)),
+            })
+        })?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+// Series methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Add media to a series, creating the series if needed. Media already
+    /// in another series moves to this one. Without an episode number it
+    /// becomes the last episode; media already in the series keeps its
+    /// number. Returns the episode number.
+    pub fn add_to_series(&self, series: &str, video_id: &str, episode: Option<u32>) -> Result<u32> {
+        let conn = self.conn()?;
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "INSERT OR IGNORE INTO series (name, description, created_at) VALUES (?1, NULL, ?2)",
+            params![series, Utc::now().to_rfc3339()],
+        )?;
+        let current: Option<u32> = tx
+            .query_row(
+                "SELECT episode FROM series_media WHERE video_id = ?1 AND series = ?2",
+                params![video_id, series],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let episode = match episode.or(current) {
+            Some(episode) => episode,
+            None => tx.query_row(
+                "SELECT COALESCE(MAX(episode), 0) + 1 FROM series_media WHERE series = ?1",
+                params![series],
+                |row| row.get(0),
+            )?,
+        };
+        tx.execute(
+            "INSERT OR REPLACE INTO series_media (video_id, series, episode) VALUES (?1, ?2, ?3)",
+            params![video_id, series, episode],
+        )?;
+        // Cached answers filtered by series no longer hold
+        tx.execute("UPDATE corpus_version SET version = version + 1", [])?;
+        tx.commit()?;
+
+        Ok(episode)
+    }
+
+    /// Take media out of its series. Returns the series it was in.
+    pub fn remove_from_series(&self, video_id: &str) -> Result<Option<String>> {
+        let series = self.series_of(video_id)?;
+        if series.is_some() {
+            let conn = self.conn()?;
+            conn.execute("DELETE FROM series_media WHERE video_id = ?1", params![video_id])?;
+            conn.execute("UPDATE corpus_version SET version = version + 1", [])?;
+        }
+        Ok(series)
+    }
+
+    /// Delete a series; its media stays indexed. Returns false if there was
+    /// no such series.
+    pub fn delete_series(&self, series: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM series_media WHERE series = ?1", params![series])?;
+        let deleted = tx.execute("DELETE FROM series WHERE name = ?1", params![series])?;
+        tx.execute("UPDATE corpus_version SET version = version + 1", [])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    /// Set what a series is, creating the series if needed.
+    pub fn describe_series(&self, series: &str, description: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO series (name, description, created_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(name) DO UPDATE SET description = excluded.description",
+            params![series, description, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a series exists.
+    pub fn has_series(&self, series: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM series WHERE name = ?1", params![series], |row| row.get(0))?;
+        Ok(count > 0)
+    }
+
+    /// The series a media item is in.
+    pub fn series_of(&self, video_id: &str) -> Result<Option<String>> {
+        let conn = self.conn()?;
+        match conn.query_row(
+            "SELECT series FROM series_media WHERE video_id = ?1",
+            params![video_id],
+            |row| row.get(0),
+        ) {
+            Ok(series) => Ok(Some(series)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Episodes of a series in order, with their stats.
+    pub fn series_episodes(&self, series: &str) -> Result<Vec<SeriesEpisode>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT m.episode, m.video_id,
+                   COALESCE(
+                       (SELECT MAX(video_title) FROM documents d WHERE d.video_id = m.video_id),
+                       (SELECT video_title FROM transcripts t WHERE t.video_id = m.video_id),
+                       m.video_id
+                   ),
+                   COALESCE((SELECT duration_seconds FROM transcripts t WHERE t.video_id = m.video_id), 0),
+                   (SELECT COUNT(*) FROM documents d WHERE d.video_id = m.video_id AND d.doc_type = 'source'),
+                   COALESCE((SELECT SUM(retrieval_count) FROM retrieval_stats r WHERE r.video_id = m.video_id), 0),
+                   COALESCE((SELECT SUM(citation_count) FROM retrieval_stats r WHERE r.video_id = m.video_id), 0)
+            FROM series_media m
+            WHERE m.series = ?1
+            ORDER BY m.episode, m.video_id
+            "#,
+        )?;
+
+        let episodes = stmt
+            .query_map(params![series], |row| {
+                Ok(SeriesEpisode {
+                    episode: row.get(0)?,
+                    video_id: row.get(1)?,
+                    video_title: row.get(2)?,
+                    duration_seconds: row.get(3)?,
+                    chunk_count: row.get(4)?,
+                    retrievals: row.get::<_, i64>(5)? as u64,
+                    citations: row.get::<_, i64>(6)? as u64,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(episodes)
+    }
+
+    /// Every series with totals over its episodes, by name.
+    pub fn list_series(&self) -> Result<Vec<SeriesStats>> {
+        let series: Vec<(String, Option<String>, String)> = {
+            let conn = self.conn()?;
+            let mut stmt = conn.prepare("SELECT name, description, created_at FROM series ORDER BY name")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            rows
+        };
+
+        series
+            .into_iter()
+            .map(|(name, description, created_at)| {
+                let episodes = self.series_episodes(&name)?;
+                let created_at = parse_datetime(&created_at).unwrap_or_else(Utc::now);
+                Ok(SeriesStats::from_episodes(name, description, created_at, &episodes))
+            })
+            .collect()
+    }
+}
+
+// Retention methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// List transcript chunks with their retrieval stats, for retention planning.
+    pub fn retention_candidates(&self) -> Result<Vec<RetentionCandidate>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.id, d.video_id, d.video_title, COALESCE(d.source_created_at, d.indexed_at),
+                   COALESCE(r.retrieval_count, 0), r.last_retrieved_at
+            FROM documents d
+            LEFT JOIN retrieval_stats r ON r.document_id = d.id
+            WHERE d.doc_type = 'source'
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let id: String = row.get(0)?;
+            let date: String = row.get(3)?;
+            let last_retrieved_at: Option<String> = row.get(5)?;
+
+            Ok(RetentionCandidate {
+                id: uuid::Uuid::parse_str(&id).unwrap_or_default(),
+                video_id: row.get(1)?,
+                video_title: row.get(2)?,
+                date: parse_datetime(&date).unwrap_or_else(Utc::now),
+                retrieval_count: row.get(4)?,
+                last_retrieved_at: last_retrieved_at.and_then(|s| parse_datetime(&s)),
+            })
+        })?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// The embedding model and dimensions the library was indexed with.
+    ///
+    /// Libraries indexed before this was recorded report the dimensions of
+    /// their stored vectors and no model. `None` for an empty library.
+    pub fn embedding_info(&self) -> Result<Option<EmbeddingInfo>> {
+        let conn = self.conn()?;
+
+        let recorded = conn.query_row("SELECT model, dimensions FROM embedding_info WHERE id = 1", [], |row| {
+            Ok(EmbeddingInfo {
+                model: Some(row.get(0)?),
+                dimensions: row.get::<_, i64>(1)? as usize,
+            })
+        });
+        match recorded {
+            Ok(info) => return Ok(Some(info)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let stored = conn.query_row("SELECT length(embedding), embedding_format FROM documents LIMIT 1", [], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        });
+        match stored {
+            Ok((bytes, format)) => {
+                let format: VectorFormat = format.parse().unwrap_or_default();
+                Ok(Some(EmbeddingInfo { model: None, dimensions: bytes as usize / format.bytes_per_value() }))
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record the embedding model and dimensions the library is indexed with.
+    pub fn set_embedding_info(&self, model: &str, dimensions: usize) -> Result<()> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO embedding_info (id, model, dimensions, updated_at) VALUES (1, ?1, ?2, ?3)",
+            params![model, dimensions as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Load up to `limit` documents in ID order, starting after `after_id`.
+    /// IDs survive re-upserts, so this pages through the library even while
+    /// the pages are being rewritten.
+    pub fn documents_after(&self, after_id: Option<&str>, limit: usize) -> Result<Vec<Document>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM documents WHERE id > ?1 ORDER BY id LIMIT ?2",
+            DOCUMENT_COLUMNS
+        ))?;
+        let docs = stmt
+            .query_map(params![after_id.unwrap_or(""), limit as i64], Self::row_to_document)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(docs)
+    }
+
+    /// Load documents by ID.
+    pub fn get_documents(&self, ids: &[uuid::Uuid]) -> Result<Vec<Document>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM documents WHERE id = ?1", DOCUMENT_COLUMNS))?;
+
+        let mut docs = Vec::with_capacity(ids.len());
+        for id in ids {
+            match stmt.query_row(params![id.to_string()], Self::row_to_document) {
+                Ok(doc) => docs.push(doc),
+                Err(rusqlite::Error::QueryReturnedNoRows) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(docs)
+    }
+
+    /// Delete individual documents. Returns the number deleted.
+    pub fn delete_documents(&self, ids: &[uuid::Uuid]) -> Result<usize> {
+        let conn = self.conn()?;
+
+        let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let tx = conn.unchecked_transaction()?;
+
+        let mut deleted = 0;
+        for id in &ids {
+            deleted += tx.execute("DELETE FROM documents WHERE id = ?1", params![id])?;
+            tx.execute("DELETE FROM retrieval_stats WHERE document_id = ?1", params![id])?;
+        }
+
+        for index in &self.indexes {
+            index.on_delete_documents(&tx, &ids)?;
+        }
+
+        tx.commit()?;
+
+        info!("Deleted {} documents", deleted);
+        Ok(deleted)
+    }
+}
+
+// Transcript storage methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Store a raw transcript for later rechunking.
+    pub fn store_transcript(
+        &self,
+        video_id: &str,
+        video_title: &str,
+        transcript: &crate::transcription::Transcript,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+
+        let transcript_json = serde_json::to_string(transcript)
+            .map_err(|e| LyttError::VectorStore(format!("Failed to serialize transcript: {}", e)))?;
+
+        conn.execute(
+            r#"
             INSERT OR REPLACE INTO transcripts (video_id, video_title, transcript_json, duration_seconds, transcribed_at)
             VALUES (?1, ?2, ?3, ?4, ?5)
             "#,
             params![
-                video_id,
-                video_title,
-                transcript_json,
-                transcript.duration_seconds,
-                Utc::now().to_rfc3339(),
+                video_id,
+                video_title,
+                transcript_json,
+                transcript.duration_seconds,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+
+        info!("Stored transcript for video {}", video_id);
+        Ok(())
+    }
+
+    /// Retrieve a stored transcript.
+    pub fn get_transcript(&self, video_id: &str) -> Result<Option<(String, crate::transcription::Transcript)>> {
+        let conn = self.conn()?;
+
+        let result = conn.query_row(
+            "SELECT video_title, transcript_json FROM transcripts WHERE video_id = ?1",
+            params![video_id],
+            |row| {
+                let title: String = row.get(0)?;
+                let json: String = row.get(1)?;
+                Ok((title, json))
+            },
+        );
+
+        match result {
+            Ok((title, json)) => {
+                let transcript: crate::transcription::Transcript = serde_json::from_str(&json)
+                    .map_err(|e| LyttError::VectorStore(format!("Failed to deserialize transcript: {}", e)))?;
+                Ok(Some((title, transcript)))
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record where a media item came from.
+    pub fn set_media_source(&self, video_id: &str, source_type: SourceType, source_url: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO media_sources (video_id, source_type, source_url) VALUES (?1, ?2, ?3)",
+            params![video_id, source_type.to_string(), source_url],
+        )?;
+        Ok(())
+    }
+
+    /// Where a media item came from, if recorded (media indexed by older
+    /// versions has no record).
+    pub fn get_media_source(&self, video_id: &str) -> Result<Option<(SourceType, String)>> {
+        let conn = self.conn()?;
+        let result = conn.query_row(
+            "SELECT source_type, source_url FROM media_sources WHERE video_id = ?1",
+            params![video_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        );
+        match result {
+            Ok((source_type, url)) => {
+                let source_type = source_type.parse().unwrap_or(SourceType::YouTube);
+                Ok(Some((source_type, url)))
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Every recorded source of one type, as (video_id, source_url) pairs.
+    pub fn media_sources(&self, source_type: SourceType) -> Result<Vec<(String, String)>> {
+        let conn = self.conn()?;
+        let mut stmt =
+            conn.prepare("SELECT video_id, source_url FROM media_sources WHERE source_type = ?1 ORDER BY video_id")?;
+        let sources = stmt
+            .query_map(params![source_type.to_string()], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(sources)
+    }
+
+    /// Record that a media item is an audiobook, with the files of the parts
+    /// it was joined from, in order.
+    pub fn store_audiobook(&self, video_id: &str, book: &str, author: Option<&str>, parts: &[String]) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO audiobooks (video_id, book, author, parts) VALUES (?1, ?2, ?3, ?4)",
+            params![video_id, book, author, serde_json::to_string(parts)?],
+        )?;
+        Ok(())
+    }
+
+    /// Book title and author of a media item indexed as an audiobook.
+    pub fn get_audiobook(&self, video_id: &str) -> Result<Option<(String, Option<String>)>> {
+        let conn = self.conn()?;
+        let result = conn.query_row(
+            "SELECT book, author FROM audiobooks WHERE video_id = ?1",
+            params![video_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        );
+        match result {
+            Ok(book) => Ok(Some(book)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record that the local file with this content hash was ingested as
+    /// `video_id`.
+    pub fn record_ingested_file(&self, content_hash: &str, path: &str, video_id: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO ingested_files (content_hash, path, video_id, ingested_at) VALUES (?1, ?2, ?3, ?4)",
+            params![content_hash, path, video_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// The path and media ID a file with this content hash was ingested
+    /// from, if it was.
+    pub fn ingested_file(&self, content_hash: &str) -> Result<Option<(String, String)>> {
+        let conn = self.conn()?;
+        let result = conn.query_row(
+            "SELECT path, video_id FROM ingested_files WHERE content_hash = ?1",
+            params![content_hash],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        );
+        match result {
+            Ok(file) => Ok(Some(file)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record the chunking fingerprint media was just chunked with.
+    pub fn set_chunking_fingerprint(&self, video_id: &str, fingerprint: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO chunking_fingerprints (video_id, fingerprint, chunked_at) VALUES (?1, ?2, ?3)",
+            params![video_id, fingerprint, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Stored transcripts whose media was chunked with a fingerprint other
+    /// than `current`, or before fingerprints were recorded, as (video_id,
+    /// title, duration) like [`Self::list_transcripts`].
+    pub fn stale_transcripts(&self, current: &str) -> Result<Vec<(String, String, f64)>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT t.video_id, t.video_title, t.duration_seconds FROM transcripts t
+             LEFT JOIN chunking_fingerprints f ON f.video_id = t.video_id
+             WHERE f.fingerprint IS NULL OR f.fingerprint != ?1
+             ORDER BY t.transcribed_at DESC",
+        )?;
+        let stale = stmt
+            .query_map(params![current], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(stale)
+    }
+
+    /// Move everything stored under one media ID to another: documents and
+    /// every table with a `video_id` column. Returns the rows changed.
+    ///
+    /// Derived indexes held in memory (ANN) keep the old ID until they are
+    /// rebuilt, e.g. when the store is next opened.
+    pub fn rename_media(&self, old_id: &str, new_id: &str) -> Result<usize> {
+        let conn = self.conn()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let tables: Vec<String> = tx
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut changed = 0;
+        for table in tables {
+            let has_video_id = tx
+                .prepare(&format!("PRAGMA table_info(\"{}\")", table))?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .iter()
+                .any(|column| column == "video_id");
+            if has_video_id {
+                changed += tx.execute(
+                    &format!("UPDATE \"{}\" SET video_id = ?2 WHERE video_id = ?1", table),
+                    params![old_id, new_id],
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(changed)
+    }
+
+    /// Delete everything stored about a media item besides its documents:
+    /// transcript, tags, summary, generated title, source and ingest record.
+    pub fn delete_media_records(&self, video_id: &str) -> Result<()> {
+        let conn = self.conn()?;
+
+        let tx = conn.unchecked_transaction()?;
+        for table in ["transcripts", "media_tags", "summaries", "generated_titles", "glossary_terms", "media_sources", "ingested_files", "chunking_fingerprints", "audiobooks", "series_media"] {
+            tx.execute(&format!("DELETE FROM {} WHERE video_id = ?1", table), params![video_id])?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Check if a transcript is stored.
+    pub fn has_transcript(&self, video_id: &str) -> Result<bool> {
+        let conn = self.conn()?;
+
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM transcripts WHERE video_id = ?1",
+            params![video_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(count > 0)
+    }
+
+    /// List all stored transcripts.
+    pub fn list_transcripts(&self) -> Result<Vec<(String, String, f64)>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT video_id, video_title, duration_seconds FROM transcripts ORDER BY transcribed_at DESC"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+
+        let result: Vec<(String, String, f64)> = rows.filter_map(|r| r.ok()).collect();
+        Ok(result)
+    }
+
+    /// Load stored transcripts as (video ID, title, transcript), oldest first.
+    /// With `containing`, only those whose stored JSON contains that text
+    /// (ASCII case-insensitively when `ignore_case`) are loaded.
+    pub fn transcripts_containing(
+        &self,
+        containing: Option<&str>,
+        ignore_case: bool,
+    ) -> Result<Vec<(String, String, crate::transcription::Transcript)>> {
+        let conn = self.conn()?;
+        let mut sql = "SELECT video_id, video_title, transcript_json FROM transcripts".to_string();
+        let mut values: Vec<String> = Vec::new();
+        if let Some(text) = containing {
+            if ignore_case {
+                sql.push_str(" WHERE instr(lower(transcript_json), ?1) > 0");
+                values.push(text.to_ascii_lowercase());
+            } else {
+                sql.push_str(" WHERE instr(transcript_json, ?1) > 0");
+                values.push(text.to_string());
+            }
+        }
+        sql.push_str(" ORDER BY transcribed_at");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(video_id, title, json)| {
+                let transcript = serde_json::from_str(&json)
+                    .map_err(|e| LyttError::VectorStore(format!("Failed to deserialize transcript: {}", e)))?;
+                Ok((video_id, title, transcript))
+            })
+            .collect()
+    }
+}
+
+// Bulk export methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Call `visit` with every stored document, ordered by media and chunk,
+    /// reading them one at a time rather than all at once. Returns how many
+    /// were visited.
+    pub fn for_each_document(&self, visit: &mut dyn FnMut(Document) -> Result<()>) -> Result<usize> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM documents ORDER BY video_id, chunk_order, start_seconds",
+            DOCUMENT_COLUMNS
+        ))?;
+        let mut count = 0;
+        for doc in stmt.query_map([], Self::row_to_document)? {
+            visit(doc?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+// Usage tracking methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Persist the API usage of a single run (e.g. one transcription or question).
+    pub fn record_usage(
+        &self,
+        operation: &str,
+        video_id: Option<&str>,
+        records: &[UsageRecord],
+        pricing: &Pricing,
+    ) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn()?;
+
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let created_at = Utc::now().to_rfc3339();
+
+        let tx = conn.transaction()?;
+        for record in records {
+            tx.execute(
+                r#"
+                INSERT INTO usage_records
+                (run_id, operation, video_id, stage, model, input_tokens, output_tokens, audio_seconds, cost_usd, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                "#,
+                params![
+                    run_id,
+                    operation,
+                    video_id,
+                    record.stage.to_string(),
+                    record.model,
+                    record.input_tokens as i64,
+                    record.output_tokens as i64,
+                    record.audio_seconds,
+                    record.cost(pricing),
+                    created_at,
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        debug!("Recorded {} usage records for run {}", records.len(), run_id);
+        Ok(())
+    }
+
+    /// Summarize recorded usage, grouped by video, day, or model.
+    pub fn usage_summary(
+        &self,
+        group_by: UsageGroupBy,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageSummary>> {
+        let conn = self.conn()?;
+
+        let key = match group_by {
+            UsageGroupBy::Video => "COALESCE(video_id, operation)",
+            UsageGroupBy::Day => "substr(created_at, 1, 10)",
+            UsageGroupBy::Model => "model",
+        };
+        let since = since.map(|s| s.to_rfc3339()).unwrap_or_default();
+
+        let sql = format!(
+            r#"
+            SELECT {key}, COUNT(DISTINCT run_id), SUM(input_tokens), SUM(output_tokens),
+                   SUM(audio_seconds), SUM(cost_usd)
+            FROM usage_records
+            WHERE created_at >= ?1
+            GROUP BY 1
+            ORDER BY 6 DESC
+            "#
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![since], |row| {
+            Ok(UsageSummary {
+                key: row.get(0)?,
+                runs: row.get(1)?,
+                input_tokens: row.get::<_, i64>(2)? as u64,
+                output_tokens: row.get::<_, i64>(3)? as u64,
+                audio_seconds: row.get(4)?,
+                cost_usd: row.get(5)?,
+            })
+        })?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Log a pipeline run over a media item.
+    pub fn record_processing_run(&self, run: &ProcessingRun) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO processing_log
+            (video_id, operation, started_at, finished_at, status, error, transcription_provider,
+             transcription_model, chunking_strategy, chunking_model, chunking_fingerprint, embedding_model,
+             chunks, input_tokens, output_tokens, audio_seconds, cost_usd, run_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+            "#,
+            params![
+                run.video_id,
+                run.operation,
+                run.started_at.to_rfc3339(),
+                run.finished_at.to_rfc3339(),
+                run.status.to_string(),
+                run.error,
+                run.transcription_provider,
+                run.transcription_model,
+                run.chunking_strategy,
+                run.chunking_model,
+                run.chunking_fingerprint,
+                run.embedding_model,
+                run.chunks as i64,
+                run.input_tokens as i64,
+                run.output_tokens as i64,
+                run.audio_seconds,
+                run.cost_usd,
+                run.run_id,
             ],
         )?;
+        Ok(())
+    }
+
+    /// The logged pipeline runs over a media item, most recent first.
+    pub fn processing_history(&self, video_id: &str, limit: usize) -> Result<Vec<ProcessingRun>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT video_id, operation, started_at, finished_at, status, error, transcription_provider,
+                   transcription_model, chunking_strategy, chunking_model, chunking_fingerprint, embedding_model,
+                   chunks, input_tokens, output_tokens, audio_seconds, cost_usd, run_id
+            FROM processing_log
+            WHERE video_id = ?1
+            ORDER BY started_at DESC, id DESC
+            LIMIT ?2
+            "#,
+        )?;
+        let time = |value: String| {
+            DateTime::parse_from_rfc3339(&value)
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or_default()
+        };
+        let runs = stmt
+            .query_map(params![video_id, limit as i64], |row| {
+                Ok(ProcessingRun {
+                    video_id: row.get(0)?,
+                    operation: row.get(1)?,
+                    started_at: time(row.get(2)?),
+                    finished_at: time(row.get(3)?),
+                    status: row.get::<_, String>(4)?.parse().unwrap_or(RunStatus::Failed),
+                    error: row.get(5)?,
+                    transcription_provider: row.get(6)?,
+                    transcription_model: row.get(7)?,
+                    chunking_strategy: row.get(8)?,
+                    chunking_model: row.get(9)?,
+                    chunking_fingerprint: row.get(10)?,
+                    embedding_model: row.get(11)?,
+                    chunks: row.get::<_, i64>(12)? as usize,
+                    input_tokens: row.get::<_, i64>(13)? as u64,
+                    output_tokens: row.get::<_, i64>(14)? as u64,
+                    audio_seconds: row.get(15)?,
+                    cost_usd: row.get(16)?,
+                    run_id: row.get(17)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(runs)
+    }
+}
+
+// Tag and rollup methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Add tags to a media item (existing tags are kept).
+    pub fn add_tags(&self, video_id: &str, tags: &[String]) -> Result<()> {
+        let conn = self.conn()?;
+
+        for tag in tags {
+            conn.execute(
+                "INSERT OR IGNORE INTO media_tags (video_id, tag) VALUES (?1, ?2)",
+                params![video_id, tag],
+            )?;
+        }
 
-        info!("Stored transcript for video {}", video_id);
         Ok(())
     }
 
-    /// Retrieve a stored transcript.
-    pub fn get_transcript(&self, video_id: &str) -> Result<Option<(String, crate::transcription::Transcript)>> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    /// Remove tags from a media item. Returns the number of tags removed.
+    pub fn remove_tags(&self, video_id: &str, tags: &[String]) -> Result<usize> {
+        let conn = self.conn()?;
+
+        let mut removed = 0;
+        for tag in tags {
+            removed += conn.execute(
+                "DELETE FROM media_tags WHERE video_id = ?1 AND tag = ?2",
+                params![video_id, tag],
+            )?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Get the tags of a media item.
+    pub fn get_tags(&self, video_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare("SELECT tag FROM media_tags WHERE video_id = ?1 ORDER BY tag")?;
+        let rows = stmt.query_map(params![video_id], |row| row.get(0))?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// List all media IDs with a tag.
+    pub fn videos_with_tag(&self, tag: &str) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare("SELECT video_id FROM media_tags WHERE tag = ?1 ORDER BY video_id")?;
+        let rows = stmt.query_map(params![tag], |row| row.get(0))?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Store a rollup, replacing any previous rollup with the same ID.
+    pub fn store_rollup(&self, rollup: &Rollup) -> Result<()> {
+        let conn = self.conn()?;
+
+        let source_ids = serde_json::to_string(&rollup.source_video_ids)?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO rollups
+            (id, tag, period, period_start, period_end, content, source_video_ids, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            params![
+                rollup.id,
+                rollup.tag,
+                rollup.period.to_string(),
+                rollup.period_start.map(|d| d.to_rfc3339()),
+                rollup.period_end.to_rfc3339(),
+                rollup.content,
+                source_ids,
+                rollup.created_at.to_rfc3339(),
+            ],
+        )?;
+
+        info!("Stored rollup {}", rollup.id);
+        Ok(())
+    }
+
+    /// Get a rollup by ID.
+    pub fn get_rollup(&self, id: &str) -> Result<Option<Rollup>> {
+        let conn = self.conn()?;
 
         let result = conn.query_row(
-            "SELECT video_title, transcript_json FROM transcripts WHERE video_id = ?1",
+            r#"
+            SELECT id, tag, period, period_start, period_end, content, source_video_ids, created_at
+            FROM rollups WHERE id = ?1
+            "#,
+            params![id],
+            Self::row_to_rollup,
+        );
+
+        match result {
+            Ok(rollup) => Ok(Some(rollup)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// List all stored rollups, newest first.
+    pub fn list_rollups(&self) -> Result<Vec<Rollup>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, tag, period, period_start, period_end, content, source_video_ids, created_at
+            FROM rollups ORDER BY created_at DESC
+            "#,
+        )?;
+        let rows = stmt.query_map([], Self::row_to_rollup)?;
+
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    fn row_to_rollup(row: &rusqlite::Row) -> rusqlite::Result<Rollup> {
+        let period: String = row.get(2)?;
+        let period_start: Option<String> = row.get(3)?;
+        let period_end: String = row.get(4)?;
+        let source_ids: String = row.get(6)?;
+        let created_at: String = row.get(7)?;
+
+        Ok(Rollup {
+            id: row.get(0)?,
+            tag: row.get(1)?,
+            period: period.parse().unwrap_or(RollupPeriod::All),
+            period_start: period_start.and_then(|s| parse_datetime(&s)),
+            period_end: parse_datetime(&period_end).unwrap_or_else(Utc::now),
+            content: row.get(5)?,
+            source_video_ids: serde_json::from_str(&source_ids).unwrap_or_default(),
+            created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+// Summary methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Store a summary, replacing any previous summary of the same media.
+    pub fn store_summary(&self, summary: &MediaSummary) -> Result<()> {
+        let conn = self.conn()?;
+
+        let chunk_summaries = serde_json::to_string(&summary.chunks)?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO summaries
+            (video_id, video_title, overview, chunk_summaries, model, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                summary.video_id,
+                summary.video_title,
+                summary.overview,
+                chunk_summaries,
+                summary.model,
+                summary.created_at.to_rfc3339(),
+            ],
+        )?;
+
+        info!("Stored summary for {}", summary.video_id);
+        Ok(())
+    }
+
+    /// Get the summary of a media item.
+    pub fn get_summary(&self, video_id: &str) -> Result<Option<MediaSummary>> {
+        let conn = self.conn()?;
+
+        let result = conn.query_row(
+            r#"
+            SELECT video_id, video_title, overview, chunk_summaries, model, created_at
+            FROM summaries WHERE video_id = ?1
+            "#,
             params![video_id],
             |row| {
-                let title: String = row.get(0)?;
-                let json: String = row.get(1)?;
-                Ok((title, json))
+                let chunk_summaries: String = row.get(3)?;
+                let created_at: String = row.get(5)?;
+
+                Ok(MediaSummary {
+                    video_id: row.get(0)?,
+                    video_title: row.get(1)?,
+                    overview: row.get(2)?,
+                    chunks: serde_json::from_str(&chunk_summaries).unwrap_or_default(),
+                    model: row.get(4)?,
+                    created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+                })
             },
         );
 
         match result {
-            Ok((title, json)) => {
-                let transcript: crate::transcription::Transcript = serde_json::from_str(&json)
-                    .map_err(|e| LyttError::VectorStore(format!("Failed to deserialize transcript: {}", e)))?;
-                Ok(Some((title, transcript)))
-            }
+            Ok(summary) => Ok(Some(summary)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
             Err(e) => Err(e.into()),
         }
     }
 
-    /// Check if a transcript is stored.
-    pub fn has_transcript(&self, video_id: &str) -> Result<bool> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    /// Delete the summary of a media item. Returns true if one existed.
+    pub fn delete_summary(&self, video_id: &str) -> Result<bool> {
+        let conn = self.conn()?;
 
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM transcripts WHERE video_id = ?1",
+        let deleted = conn.execute("DELETE FROM summaries WHERE video_id = ?1", params![video_id])?;
+        Ok(deleted > 0)
+    }
+}
+
+// Generated title methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Store a generated title, replacing any previous one for the same media.
+    pub fn store_generated_title(&self, title: &GeneratedTitle) -> Result<()> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO generated_titles
+            (video_id, title, original_title, tags, model, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                title.video_id,
+                title.title,
+                title.original_title,
+                serde_json::to_string(&title.tags)?,
+                title.model,
+                title.created_at.to_rfc3339(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the generated title of a media item, if its title was generated.
+    pub fn get_generated_title(&self, video_id: &str) -> Result<Option<GeneratedTitle>> {
+        let conn = self.conn()?;
+
+        let result = conn.query_row(
+            r#"
+            SELECT video_id, title, original_title, tags, model, created_at
+            FROM generated_titles WHERE video_id = ?1
+            "#,
             params![video_id],
-            |row| row.get(0),
+            |row| {
+                let tags: String = row.get(3)?;
+                let created_at: String = row.get(5)?;
+
+                Ok(GeneratedTitle {
+                    video_id: row.get(0)?,
+                    title: row.get(1)?,
+                    original_title: row.get(2)?,
+                    tags: serde_json::from_str(&tags).unwrap_or_default(),
+                    model: row.get(4)?,
+                    created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+                })
+            },
+        );
+
+        match result {
+            Ok(title) => Ok(Some(title)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+// Glossary methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Store the glossary terms of a media item, replacing its previous terms.
+    pub fn store_glossary(&self, video_id: &str, terms: &[GlossaryTerm]) -> Result<()> {
+        let mut conn = self.conn()?;
+
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM glossary_terms WHERE video_id = ?1", params![video_id])?;
+        for term in terms {
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO glossary_terms
+                (video_id, term, definition, video_title, timestamp, model, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                params![
+                    video_id,
+                    term.term,
+                    term.definition,
+                    term.video_title,
+                    term.timestamp,
+                    term.model,
+                    term.created_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Get the stored glossary terms of a media item (empty if never extracted).
+    pub fn get_glossary(&self, video_id: &str) -> Result<Vec<GlossaryTerm>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT video_id, term, definition, video_title, timestamp, model, created_at
+            FROM glossary_terms WHERE video_id = ?1
+            ORDER BY term COLLATE NOCASE
+            "#,
         )?;
 
-        Ok(count > 0)
+        let terms = stmt
+            .query_map(params![video_id], |row| {
+                let created_at: String = row.get(6)?;
+                Ok(GlossaryTerm {
+                    video_id: row.get(0)?,
+                    term: row.get(1)?,
+                    definition: row.get(2)?,
+                    video_title: row.get(3)?,
+                    timestamp: row.get(4)?,
+                    model: row.get(5)?,
+                    created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(terms)
     }
 
-    /// List all stored transcripts.
-    pub fn list_transcripts(&self) -> Result<Vec<(String, String, f64)>> {
-        let conn = self.conn.lock().map_err(|e| {
-            LyttError::VectorStore(format!("Failed to acquire lock: {}", e))
-        })?;
+    /// Distinct glossary terms across the library, most widely used first,
+    /// for biasing transcription.
+    pub fn glossary_vocabulary(&self, limit: usize) -> Result<Vec<String>> {
+        let conn = self.conn()?;
 
         let mut stmt = conn.prepare(
-            "SELECT video_id, video_title, duration_seconds FROM transcripts ORDER BY transcribed_at DESC"
+            r#"
+            SELECT MIN(term) FROM glossary_terms
+            GROUP BY LOWER(term)
+            ORDER BY COUNT(*) DESC, MIN(term) COLLATE NOCASE
+            LIMIT ?1
+            "#,
         )?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
-        })?;
+        let terms = stmt
+            .query_map(params![limit as i64], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        Ok(terms)
+    }
+}
+
+// Topic methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// Store a topic model, replacing the previous topics and assignments.
+    pub fn store_topics(&self, model: &TopicModel) -> Result<()> {
+        let mut conn = self.conn()?;
+
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM document_topics", [])?;
+        tx.execute("DELETE FROM topics", [])?;
+
+        for (topic, (id, centroid)) in model.map.topics.iter().zip(&model.centroids) {
+            tx.execute(
+                r#"
+                INSERT INTO topics (id, label, description, centroid, model, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                params![
+                    id,
+                    topic.label,
+                    topic.description,
+                    Self::embedding_to_bytes(centroid),
+                    model.map.model,
+                    model.map.created_at.to_rfc3339(),
+                ],
+            )?;
+        }
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO document_topics (document_id, video_id, topic_id) VALUES (?1, ?2, ?3)",
+            )?;
+            for assignment in &model.assignments {
+                stmt.execute(params![assignment.document_id, assignment.video_id, assignment.topic_id])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Get the stored topic map, with up to `media_limit` media per topic
+    /// (`None` if topics were never stored).
+    ///
+    /// Counts reflect the current assignments, so they include documents
+    /// indexed since the topics were computed.
+    pub fn get_topic_map(&self, media_limit: usize) -> Result<Option<TopicMap>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, label, description, model, created_at FROM topics ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let Some((_, _, _, model, created_at)) = rows.first().cloned() else {
+            return Ok(None);
+        };
+
+        let mut media_stmt = conn.prepare(
+            r#"
+            SELECT d.video_id, MAX(d.video_title), COUNT(*)
+            FROM document_topics t JOIN documents d ON d.id = t.document_id
+            WHERE t.topic_id = ?1 AND d.doc_type = 'source'
+            GROUP BY d.video_id
+            ORDER BY COUNT(*) DESC, MAX(d.video_title)
+            "#,
+        )?;
+
+        let mut topics = Vec::with_capacity(rows.len());
+        for (id, label, description, _, _) in rows {
+            let media = media_stmt
+                .query_map(params![id], |row| {
+                    Ok(TopicMedia {
+                        video_id: row.get(0)?,
+                        video_title: row.get(1)?,
+                        chunks: row.get::<_, i64>(2)? as usize,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            topics.push(Topic {
+                id,
+                label,
+                description,
+                chunks: media.iter().map(|m| m.chunks).sum(),
+                media: media.into_iter().take(media_limit).collect(),
+            });
+        }
+
+        let (document_count, media_count): (i64, i64) = conn.query_row(
+            r#"
+            SELECT COUNT(*), COUNT(DISTINCT d.video_id)
+            FROM document_topics t JOIN documents d ON d.id = t.document_id
+            WHERE d.doc_type = 'source'
+            "#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(Some(TopicMap {
+            topics,
+            document_count: document_count as usize,
+            media_count: media_count as usize,
+            model,
+            created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+        }))
+    }
+}
 
-        let result: Vec<(String, String, f64)> = rows.filter_map(|r| r.ok()).collect();
-        Ok(result)
+// Keyword methods (not part of VectorStore trait)
+impl SqliteVectorStore {
+    /// The most common chunk keywords of a media item, most frequent first.
+    pub fn media_keywords(&self, video_id: &str, limit: usize) -> Result<Vec<KeywordCount>> {
+        let conn = self.conn()?;
+
+        let lists: Vec<Vec<String>> = conn
+            .prepare("SELECT keywords FROM documents WHERE video_id = ?1 AND keywords IS NOT NULL")?
+            .query_map(params![video_id], |row| row.get::<_, String>(0))?
+            .filter_map(|k| k.ok())
+            .filter_map(|k| serde_json::from_str(&k).ok())
+            .collect();
+
+        Ok(count_keywords(lists.iter().map(Vec::as_slice), limit))
     }
 }
 
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::usage::UsageStage;
+    use crate::vector_store::{test_vector, DocType};
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_slow_query_does_not_stall_other_requests() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        // A single async worker: a query run on it would hold up everything else
+        let slow = store.blocking(|store| {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            store.document_count_blocking()
+        });
+        let started = std::time::Instant::now();
+        let other = async {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            started.elapsed()
+        };
+
+        let (count, waited) = tokio::join!(slow, other);
+        assert_eq!(count.unwrap(), 0);
+        assert!(waited < std::time::Duration::from_millis(200), "waited {:?}", waited);
+    }
+
+    #[test]
+    fn test_add_to_series_keeps_episode_number() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        assert_eq!(store.add_to_series("course", "a", Some(4)).unwrap(), 4);
+        assert_eq!(store.add_to_series("course", "b", None).unwrap(), 5);
+        assert_eq!(store.add_to_series("course", "a", None).unwrap(), 4);
+    }
 
     #[tokio::test]
     async fn test_sqlite_vector_store() {
@@ -526,10 +2602,26 @@ mod tests {
         );
 
         store.upsert(&doc).await.unwrap();
+        // Summaries and rollups are stored under their own IDs but aren't media
+        let summary = Document::new(
+            "summary-video1".to_string(),
+            "Summary: Test Video".to_string(),
+            Some("Overview".to_string()),
+            "An overview".to_string(),
+            0.0,
+            0.0,
+            vec![0.0, 1.0, 0.0],
+            0,
+            None,
+        )
+        .derived_from(vec![doc.id.to_string()]);
+        store.upsert(&summary).await.unwrap();
 
         let videos = store.list_videos().await.unwrap();
         assert_eq!(videos.len(), 1);
         assert_eq!(videos[0].video_id, "video1");
+        assert!(store.get_video("summary-video1").await.unwrap().is_none());
+        store.delete_by_video_id("summary-video1").await.unwrap();
 
         let results = store.search(&[1.0, 0.0, 0.0], 10).await.unwrap();
         assert_eq!(results.len(), 1);
@@ -541,4 +2633,949 @@ mod tests {
         let videos = store.list_videos().await.unwrap();
         assert!(videos.is_empty());
     }
+
+    #[test]
+    fn test_usage_summary() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let pricing = Pricing::default();
+
+        let records = vec![
+            UsageRecord {
+                stage: UsageStage::Transcription,
+                model: "whisper-1".to_string(),
+                input_tokens: 0,
+                output_tokens: 0,
+                audio_seconds: 600.0,
+            },
+            UsageRecord {
+                stage: UsageStage::Embedding,
+                model: "text-embedding-3-small".to_string(),
+                input_tokens: 1_000_000,
+                output_tokens: 0,
+                audio_seconds: 0.0,
+            },
+        ];
+        store.record_usage("transcribe", Some("video1"), &records, &pricing).unwrap();
+
+        let by_video = store.usage_summary(UsageGroupBy::Video, None).unwrap();
+        assert_eq!(by_video.len(), 1);
+        assert_eq!(by_video[0].key, "video1");
+        assert_eq!(by_video[0].runs, 1);
+        assert!((by_video[0].cost_usd - 0.08).abs() < 1e-9);
+
+        let by_model = store.usage_summary(UsageGroupBy::Model, None).unwrap();
+        assert_eq!(by_model.len(), 2);
+        assert_eq!(by_model[0].key, "whisper-1");
+    }
+
+    #[test]
+    fn test_processing_history() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let started_at = Utc::now();
+        let run = |operation: &str, status: RunStatus, offset: i64| ProcessingRun {
+            video_id: "video1".to_string(),
+            operation: operation.to_string(),
+            started_at: started_at + chrono::Duration::seconds(offset),
+            finished_at: started_at + chrono::Duration::seconds(offset + 90),
+            status,
+            error: (status == RunStatus::Failed).then(|| "rate limited".to_string()),
+            transcription_provider: Some("whisper".to_string()),
+            transcription_model: Some("whisper-1".to_string()),
+            chunking_strategy: "semantic".to_string(),
+            chunking_model: Some("gpt-4o-mini".to_string()),
+            chunking_fingerprint: "abc".to_string(),
+            embedding_model: "text-embedding-3-small".to_string(),
+            chunks: 12,
+            input_tokens: 0,
+            output_tokens: 0,
+            audio_seconds: 0.0,
+            cost_usd: 0.0,
+            run_id: Some(format!("run-{}", offset)),
+        };
+        let records = [UsageRecord {
+            stage: UsageStage::Transcription,
+            model: "whisper-1".to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            audio_seconds: 600.0,
+        }];
+        store
+            .record_processing_run(&run("transcribe", RunStatus::Failed, 0).with_usage(&records, &Pricing::default()))
+            .unwrap();
+        store.record_processing_run(&run("rechunk", RunStatus::Completed, 600)).unwrap();
+
+        let history = store.processing_history("video1", 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!((history[0].operation.as_str(), history[0].status), ("rechunk", RunStatus::Completed));
+        assert_eq!(history[1].error.as_deref(), Some("rate limited"));
+        assert_eq!(history[1].run_id.as_deref(), Some("run-0"));
+        assert!((history[1].cost_usd - 0.06).abs() < 1e-9);
+        assert_eq!(history[1].duration_seconds(), 90.0);
+        assert!(store.processing_history("video2", 10).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tags() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        store
+            .add_tags("video1", &["lectures".to_string(), "physics".to_string()])
+            .unwrap();
+        store.add_tags("video2", &["lectures".to_string()]).unwrap();
+        // Adding an existing tag is a no-op
+        store.add_tags("video2", &["lectures".to_string()]).unwrap();
+
+        assert_eq!(store.videos_with_tag("lectures").unwrap(), vec!["video1", "video2"]);
+        assert_eq!(store.get_tags("video1").unwrap(), vec!["lectures", "physics"]);
+
+        let removed = store.remove_tags("video1", &["lectures".to_string()]).unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(store.videos_with_tag("lectures").unwrap(), vec!["video2"]);
+    }
+
+    #[tokio::test]
+    async fn test_derived_documents() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let source = Document::new(
+            "video1".to_string(),
+            "Lecture".to_string(),
+            None,
+            "Source content".to_string(),
+            0.0,
+            60.0,
+            vec![1.0, 0.0, 0.0],
+            0,
+            None,
+        );
+        let derived = Document::new(
+            "rollup-lectures-month".to_string(),
+            "Rollup: lectures (month)".to_string(),
+            Some("Themes".to_string()),
+            "Derived content".to_string(),
+            0.0,
+            0.0,
+            vec![1.0, 0.0, 0.0],
+            0,
+            None,
+        )
+        .derived_from(vec![source.id.to_string()]);
+
+        store.upsert_batch(&[source.clone(), derived]).await.unwrap();
+
+        let all = store.search(&[1.0, 0.0, 0.0], 10).await.unwrap();
+        assert_eq!(all.len(), 2);
+
+        let exclude = SearchFilter::default().with_derived(DerivedFilter::Exclude);
+        let results = store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &exclude).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.video_id, "video1");
+
+        let only = SearchFilter::default().with_derived(DerivedFilter::Only);
+        let results = store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &only).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.doc_type, DocType::Derived);
+        assert_eq!(results[0].document.provenance, vec![source.id.to_string()]);
+
+        let video = SearchFilter::default().with_video("video1");
+        let results = store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &video).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.video_id, "video1");
+    }
+
+    #[tokio::test]
+    async fn test_media_keywords() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let doc = |keywords: &[&str]| {
+            Document::new("video1".to_string(), "Lecture".to_string(), None, "Content".to_string(), 0.0, 10.0, vec![1.0, 0.0, 0.0], 0, None)
+                .with_keywords(keywords.iter().map(|k| k.to_string()).collect())
+        };
+        store
+            .upsert_batch(&[doc(&["rust", "ownership"]), doc(&["rust", "borrow checker"]), doc(&[])])
+            .await
+            .unwrap();
+
+        let keywords = store.media_keywords("video1", 2).unwrap();
+        assert_eq!(keywords[0], KeywordCount { keyword: "rust".to_string(), count: 2 });
+        assert_eq!(keywords.len(), 2);
+
+        let stored = store.get_by_video_id("video1").await.unwrap();
+        assert!(stored.iter().any(|d| d.keywords == vec!["rust", "ownership"]));
+
+        let filter = SearchFilter::default().with_keyword("Borrow Checker");
+        let results = store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &filter).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_language() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let doc = |video: &str, language: Option<&str>| {
+            Document::new(video.to_string(), format!("Title {}", video), None, "Content".to_string(), 0.0, 10.0, vec![1.0, 0.0, 0.0], 0, None)
+                .with_language(language.map(str::to_string))
+        };
+        store
+            .upsert_batch(&[doc("en1", Some("en")), doc("de1", Some("de")), doc("unknown", None)])
+            .await
+            .unwrap();
+
+        let filter = SearchFilter::default().with_language("DE");
+        let results = store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &filter).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.video_id, "de1");
+
+        let video = store.get_video("en1").await.unwrap().unwrap();
+        assert_eq!(video.language.as_deref(), Some("en"));
+        let videos = store.list_videos().await.unwrap();
+        assert!(videos.iter().any(|v| v.video_id == "unknown" && v.language.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_summary() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let doc = Document::new("video1".to_string(), "Title".to_string(), Some("Intro".to_string()), "Content".to_string(), 0.0, 10.0, vec![1.0, 0.0, 0.0], 0, None)
+            .with_summary(Some("What the video covers.".to_string()));
+        store.upsert(&doc).await.unwrap();
+
+        let stored = store.get_by_video_id("video1").await.unwrap();
+        assert_eq!(stored[0].section_title.as_deref(), Some("Intro"));
+        assert_eq!(stored[0].summary.as_deref(), Some("What the video covers."));
+    }
+
+    #[tokio::test]
+    async fn test_embedding_info() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        assert!(store.embedding_info().unwrap().is_none());
+
+        let doc = |order: i32| Document::new("video1".to_string(), "Title".to_string(), None, "Content".to_string(), 0.0, 10.0, vec![1.0, 0.0, 0.0], order, None);
+        store.upsert_batch(&[doc(0), doc(1), doc(2)]).await.unwrap();
+
+        // Older libraries only reveal their dimensions
+        let inferred = store.embedding_info().unwrap().unwrap();
+        assert_eq!(inferred, EmbeddingInfo { model: None, dimensions: 3 });
+        assert!(inferred.ensure_compatible(&EmbeddingInfo::new("text-embedding-3-small", 3)).is_ok());
+        assert!(inferred.ensure_compatible(&EmbeddingInfo::new("text-embedding-3-small", 1536)).is_err());
+
+        store.set_embedding_info("text-embedding-3-small", 3).unwrap();
+        let recorded = store.embedding_info().unwrap().unwrap();
+        let err = recorded.ensure_compatible(&EmbeddingInfo::new("text-embedding-3-large", 3)).unwrap_err();
+        assert!(err.to_string().contains("lytt reembed"), "{}", err);
+
+        let first = store.documents_after(None, 2).unwrap();
+        assert_eq!(first.len(), 2);
+        let rest = store.documents_after(Some(&first[1].id.to_string()), 2).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert!(first.iter().all(|d| d.id != rest[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_vector_format() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let doc = |video: &str| Document::new(video.to_string(), "Title".to_string(), None, "Content".to_string(), 0.0, 10.0, vec![0.6, 0.0, -0.8, 0.1], 0, None);
+        store.upsert(&doc("full")).await.unwrap();
+
+        // Vectors written before a format switch stay readable
+        let store = store.with_vector_format(VectorFormat::F16);
+        store.upsert(&doc("half")).await.unwrap();
+
+        let full = store.get_by_video_id("full").await.unwrap();
+        assert_eq!(full[0].embedding, vec![0.6, 0.0, -0.8, 0.1]);
+        let half = store.get_by_video_id("half").await.unwrap();
+        assert!(half[0].embedding.iter().zip(&full[0].embedding).all(|(a, b)| (a - b).abs() < 1e-3));
+
+        let conn = store.conn().unwrap();
+        let bytes: i64 = conn
+            .query_row("SELECT length(embedding) FROM documents WHERE video_id = 'half'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(bytes, 8);
+        drop(conn);
+        assert_eq!(store.embedding_info().unwrap().unwrap().dimensions, 4);
+    }
+
+    #[tokio::test]
+    async fn test_quantized_search() {
+        let vector = |seed: u64| test_vector(seed, 16);
+        let docs: Vec<Document> = (0..150)
+            .map(|i| Document::new(format!("video{}", i), "Title".to_string(), None, format!("Content {}", i), 0.0, 10.0, vector(i), 0, None))
+            .collect();
+
+        let store = SqliteVectorStore::in_memory().unwrap();
+        store.upsert_batch(&docs).await.unwrap();
+        let exact = store.search(&vector(42), 5).await.unwrap();
+
+        // Enabled on an existing library: falls back to a full scan until rebuilt
+        let store = store.with_quantization(Quantization::Binary, 10);
+        assert_eq!(store.search(&vector(42), 5).await.unwrap().len(), 5);
+        let status = store.rebuild_index(IndexKind::Quantized, &mut |_, _| {}).unwrap();
+        assert!(status.is_consistent());
+
+        let results = store.search(&vector(42), 5).await.unwrap();
+        assert_eq!(results[0].document.video_id, "video42");
+        assert_eq!(results[0].document.video_id, exact[0].document.video_id);
+        assert!((results[0].score - exact[0].score).abs() < 1e-6);
+
+        store.delete_by_video_id("video42").await.unwrap();
+        let results = store.search(&vector(42), 5).await.unwrap();
+        assert!(results.iter().all(|r| r.document.video_id != "video42"));
+        assert_eq!(store.index_status().unwrap()[0].entries, 149);
+    }
+
+    #[tokio::test]
+    async fn test_ann_search() {
+        let vector = |seed: u64| test_vector(seed, 16);
+        let doc = |i: u64| Document::new(format!("video{}", i), "Title".to_string(), None, format!("Content {}", i), 0.0, 10.0, vector(i), 0, None);
+
+        let store = SqliteVectorStore::in_memory().unwrap();
+        store.upsert_batch(&(0..150).map(doc).collect::<Vec<_>>()).await.unwrap();
+        let exact = store.search(&vector(42), 5).await.unwrap();
+
+        // Empty until loaded: searches scan every embedding
+        let store = store.with_ann();
+        assert_eq!(store.search(&vector(42), 5).await.unwrap().len(), 5);
+        assert!(store.rebuild_index(IndexKind::Ann, &mut |_, _| {}).unwrap().is_consistent());
+
+        let results = store.search(&vector(42), 5).await.unwrap();
+        assert_eq!(results[0].document.video_id, "video42");
+        assert!((results[0].score - exact[0].score).abs() < 1e-6);
+
+        // Kept in sync with upserts and deletes
+        store.upsert(&doc(500)).await.unwrap();
+        assert_eq!(store.search(&vector(500), 1).await.unwrap()[0].document.video_id, "video500");
+        store.delete_by_video_id("video42").await.unwrap();
+        let results = store.search(&vector(42), 5).await.unwrap();
+        assert!(results.iter().all(|r| r.document.video_id != "video42"));
+        assert!(store.index_status().unwrap()[0].is_consistent());
+    }
+
+    #[tokio::test]
+    async fn test_get_neighbors() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let docs: Vec<Document> = (0..5)
+            .map(|i| {
+                Document::new(
+                    "video1".to_string(),
+                    "Lecture".to_string(),
+                    None,
+                    format!("Chunk {}", i),
+                    i as f64 * 10.0,
+                    (i + 1) as f64 * 10.0,
+                    vec![1.0, 0.0, 0.0],
+                    i,
+                    None,
+                )
+            })
+            .collect();
+        store.upsert_batch(&docs).await.unwrap();
+
+        let neighbors = store.get_neighbors("video1", 2, 1).await.unwrap();
+        let orders: Vec<i32> = neighbors.iter().map(|d| d.chunk_order).collect();
+        assert_eq!(orders, vec![1, 3]);
+
+        let neighbors = store.get_neighbors("video1", 0, 2).await.unwrap();
+        let orders: Vec<i32> = neighbors.iter().map(|d| d.chunk_order).collect();
+        assert_eq!(orders, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_topics() {
+        use crate::topics::{TopicAssignment, TopicIndex};
+
+        let store = SqliteVectorStore::in_memory().unwrap().with_index(Arc::new(TopicIndex::new()));
+        let doc = |video: &str, embedding: Vec<f32>| {
+            Document::new(video.to_string(), format!("Title {}", video), None, "Content".to_string(), 0.0, 10.0, embedding, 0, None)
+        };
+        let a = doc("video1", vec![1.0, 0.0, 0.0]);
+        let b = doc("video2", vec![0.0, 1.0, 0.0]);
+        store.upsert_batch(&[a.clone(), b.clone()]).await.unwrap();
+        assert!(store.get_topic_map(5).unwrap().is_none());
+
+        let topic = |id: i64, label: &str| Topic {
+            id,
+            label: label.to_string(),
+            description: String::new(),
+            chunks: 1,
+            media: Vec::new(),
+        };
+        let assign = |d: &Document, topic_id: i64| TopicAssignment {
+            document_id: d.id.to_string(),
+            video_id: d.video_id.clone(),
+            topic_id,
+        };
+        let model = TopicModel {
+            map: TopicMap {
+                topics: vec![topic(1, "First"), topic(2, "Second")],
+                document_count: 2,
+                media_count: 2,
+                model: "gpt-4o-mini".to_string(),
+                created_at: Utc::now(),
+            },
+            centroids: vec![(1, vec![1.0, 0.0, 0.0]), (2, vec![0.0, 1.0, 0.0])],
+            assignments: vec![assign(&a, 1), assign(&b, 2)],
+        };
+        store.store_topics(&model).unwrap();
+
+        // New documents join the nearest stored topic
+        store.upsert(&doc("video3", vec![0.1, 0.9, 0.0])).await.unwrap();
+
+        let map = store.get_topic_map(5).unwrap().unwrap();
+        assert_eq!(map.topics.len(), 2);
+        assert_eq!(map.topics[1].label, "Second");
+        assert_eq!(map.topics[1].chunks, 2);
+        assert_eq!(map.document_count, 3);
+
+        let filter = SearchFilter::default().with_topic(2);
+        let results = store.search_filtered(&[1.0, 0.0, 0.0], 10, 0.0, &filter).await.unwrap();
+        let videos: Vec<&str> = results.iter().map(|r| r.document.video_id.as_str()).collect();
+        assert_eq!(results.len(), 2);
+        assert!(!videos.contains(&"video1"));
+
+        store.delete_by_video_id("video3").await.unwrap();
+        assert!(store.index_status().unwrap()[0].is_consistent());
+    }
+
+    #[tokio::test]
+    async fn test_series() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let doc = |video: &str, embedding: Vec<f32>| {
+            Document::new(video.to_string(), format!("Title {}", video), None, "Content".to_string(), 0.0, 10.0, embedding, 0, None)
+        };
+        store
+            .upsert_batch(&[doc("ep1", vec![1.0, 0.0]), doc("ep2", vec![0.9, 0.1]), doc("other", vec![1.0, 0.0])])
+            .await
+            .unwrap();
+
+        assert_eq!(store.add_to_series("Course", "ep1", None).unwrap(), 1);
+        assert_eq!(store.add_to_series("Course", "ep2", None).unwrap(), 2);
+        // Adding again keeps the episode number
+        assert_eq!(store.add_to_series("Course", "ep1", None).unwrap(), 1);
+        store.describe_series("Course", "Rust course").unwrap();
+
+        let filter = SearchFilter::default().with_series("Course");
+        let results = store.search_filtered(&[1.0, 0.0], 10, 0.0, &filter).await.unwrap();
+        let mut videos: Vec<&str> = results.iter().map(|r| r.document.video_id.as_str()).collect();
+        videos.sort();
+        assert_eq!(videos, vec!["ep1", "ep2"]);
+
+        let series = store.list_series().unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].episodes, 2);
+        assert_eq!(series[0].chunk_count, 2);
+        assert_eq!(series[0].description.as_deref(), Some("Rust course"));
+
+        assert_eq!(store.remove_from_series("ep1").unwrap().as_deref(), Some("Course"));
+        assert_eq!(store.series_episodes("Course").unwrap()[0].video_id, "ep2");
+        assert!(store.delete_series("Course").unwrap());
+        assert!(store.series_of("ep2").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_with_deadline() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let docs: Vec<Document> = (0..10)
+            .map(|i| {
+                Document::new(
+                    "video1".to_string(),
+                    "Lecture".to_string(),
+                    None,
+                    format!("Chunk {}", i),
+                    i as f64 * 10.0,
+                    (i + 1) as f64 * 10.0,
+                    vec![1.0, i as f32, 0.0],
+                    i,
+                    None,
+                )
+            })
+            .collect();
+        store.upsert_batch(&docs).await.unwrap();
+
+        let filter = SearchFilter::default();
+        let query = [1.0, 0.0, 0.0];
+
+        let later = Instant::now() + std::time::Duration::from_secs(60);
+        let complete = store
+            .search_with_deadline(&query, 5, 0.0, &filter, later)
+            .await
+            .unwrap();
+        assert!(!complete.partial);
+        assert_eq!(complete.results.len(), 5);
+        assert_eq!(complete.results[0].document.content, "Chunk 0");
+
+        // A deadline that has already passed stops the scan immediately
+        let expired = store
+            .search_with_deadline(&query, 5, 0.0, &filter, Instant::now())
+            .await
+            .unwrap();
+        assert!(expired.partial);
+        assert!(expired.results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_reports_unreadable_rows() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let doc = Document::new(
+            "video1".to_string(),
+            "Lecture".to_string(),
+            None,
+            "Chunk".to_string(),
+            0.0,
+            10.0,
+            vec![1.0, 0.0, 0.0],
+            0,
+            None,
+        );
+        store.upsert(&doc).await.unwrap();
+        store
+            .conn()
+            .unwrap()
+            .execute("UPDATE documents SET start_seconds = 'soon'", [])
+            .unwrap();
+
+        // A row that can't be decoded fails the search instead of silently dropping out
+        let later = Instant::now() + std::time::Duration::from_secs(60);
+        let result = store
+            .search_with_deadline(&[1.0, 0.0, 0.0], 5, 0.0, &SearchFilter::default(), later)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_summaries() {
+        use crate::summary::ChunkSummary;
+
+        let store = SqliteVectorStore::in_memory().unwrap();
+        assert!(store.get_summary("video1").unwrap().is_none());
+
+        let summary = MediaSummary {
+            video_id: "video1".to_string(),
+            video_title: "Lecture".to_string(),
+            overview: "An overview.".to_string(),
+            chunks: vec![ChunkSummary {
+                chunk_order: 0,
+                section_title: Some("Intro".to_string()),
+                start_seconds: 0.0,
+                end_seconds: 60.0,
+                summary: "The intro.".to_string(),
+            }],
+            model: "gpt-4o-mini".to_string(),
+            created_at: Utc::now(),
+        };
+        store.store_summary(&summary).unwrap();
+
+        let stored = store.get_summary("video1").unwrap().unwrap();
+        assert_eq!(stored.overview, "An overview.");
+        assert_eq!(stored.chunks.len(), 1);
+        assert_eq!(stored.chunks[0].section_title.as_deref(), Some("Intro"));
+
+        assert!(store.delete_summary("video1").unwrap());
+        assert!(store.get_summary("video1").unwrap().is_none());
+    }
+
+    /// Index that mirrors document IDs into a side table.
+    struct MirrorIndex;
+
+    impl DerivedIndex for MirrorIndex {
+        fn kind(&self) -> IndexKind {
+            IndexKind::Fts
+        }
+
+        fn clear(&self, conn: &Connection) -> Result<()> {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS mirror (id TEXT PRIMARY KEY, video_id TEXT); DELETE FROM mirror;",
+            )?;
+            Ok(())
+        }
+
+        fn on_upsert(&self, conn: &Connection, docs: &[Document]) -> Result<()> {
+            conn.execute_batch("CREATE TABLE IF NOT EXISTS mirror (id TEXT PRIMARY KEY, video_id TEXT);")?;
+            for doc in docs {
+                conn.execute(
+                    "INSERT OR REPLACE INTO mirror (id, video_id) VALUES (?1, ?2)",
+                    params![doc.id.to_string(), doc.video_id],
+                )?;
+            }
+            Ok(())
+        }
+
+        fn on_delete(&self, conn: &Connection, video_id: &str) -> Result<()> {
+            conn.execute("DELETE FROM mirror WHERE video_id = ?1", params![video_id])?;
+            Ok(())
+        }
+
+        fn on_delete_documents(&self, conn: &Connection, ids: &[String]) -> Result<()> {
+            for id in ids {
+                conn.execute("DELETE FROM mirror WHERE id = ?1", params![id])?;
+            }
+            Ok(())
+        }
+
+        fn entry_count(&self, conn: &Connection) -> Result<usize> {
+            let count: i64 = conn.query_row("SELECT COUNT(*) FROM mirror", [], |row| row.get(0))?;
+            Ok(count as usize)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_derived_index_maintenance() {
+        let store = SqliteVectorStore::in_memory()
+            .unwrap()
+            .with_index(Arc::new(MirrorIndex));
+        assert_eq!(store.index_kinds(), vec![IndexKind::Fts]);
+
+        let docs: Vec<Document> = ["video1", "video1", "video2"]
+            .iter()
+            .enumerate()
+            .map(|(i, video_id)| {
+                Document::new(
+                    video_id.to_string(),
+                    "Title".to_string(),
+                    None,
+                    format!("Chunk {}", i),
+                    0.0,
+                    10.0,
+                    vec![1.0, 0.0],
+                    i as i32,
+                    None,
+                )
+            })
+            .collect();
+
+        // Upserts and deletes are applied incrementally
+        store.upsert_batch(&docs).await.unwrap();
+        assert!(store.index_status().unwrap()[0].is_consistent());
+        store.delete_by_video_id("video1").await.unwrap();
+        let status = &store.index_status().unwrap()[0];
+        assert_eq!((status.entries, status.documents), (1, 1));
+
+        // A rebuild recovers from drift
+        store.conn().unwrap().execute("DELETE FROM mirror", []).unwrap();
+        assert!(!store.index_status().unwrap()[0].is_consistent());
+
+        let mut calls = 0;
+        let status = store.rebuild_index(IndexKind::Fts, &mut |_, _| calls += 1).unwrap();
+        assert!(status.is_consistent());
+        assert!(calls >= 2);
+
+        assert!(store.rebuild_index(IndexKind::Ann, &mut |_, _| {}).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retention_candidates() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let hit = Document::new(
+            "video1".to_string(),
+            "Lecture".to_string(),
+            None,
+            "Retrieved".to_string(),
+            0.0,
+            10.0,
+            vec![1.0, 0.0],
+            0,
+            None,
+        );
+        let miss = Document::new(
+            "video1".to_string(),
+            "Lecture".to_string(),
+            None,
+            "Never retrieved".to_string(),
+            10.0,
+            20.0,
+            vec![0.0, 1.0],
+            1,
+            None,
+        );
+        store.upsert_batch(&[hit.clone(), miss.clone()]).await.unwrap();
+
+        store.search(&[1.0, 0.0], 1).await.unwrap();
+        store.search(&[1.0, 0.0], 1).await.unwrap();
+
+        let candidates = store.retention_candidates().unwrap();
+        let count = |id| candidates.iter().find(|c| c.id == id).unwrap().retrieval_count;
+        assert_eq!(count(hit.id), 2);
+        assert_eq!(count(miss.id), 0);
+
+        assert_eq!(store.get_documents(&[miss.id]).unwrap()[0].content, "Never retrieved");
+        assert_eq!(store.delete_documents(&[miss.id]).unwrap(), 1);
+        assert_eq!(store.document_count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_video_retrieval_stats() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+
+        let cited = Document::new(
+            "video1".to_string(),
+            "Lecture".to_string(),
+            None,
+            "Cited".to_string(),
+            0.0,
+            10.0,
+            vec![1.0, 0.0],
+            0,
+            None,
+        );
+        let unused = Document::new(
+            "video2".to_string(),
+            "Podcast".to_string(),
+            None,
+            "Never retrieved".to_string(),
+            0.0,
+            10.0,
+            vec![0.0, 1.0],
+            0,
+            None,
+        );
+        store.upsert_batch(&[cited.clone(), unused]).await.unwrap();
+
+        store.search(&[1.0, 0.0], 1).await.unwrap();
+        store.record_citations(&[cited.id]).await.unwrap();
+        store.record_citations(&[cited.id]).await.unwrap();
+
+        let stats = store.video_retrieval_stats().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].video_id, "video1");
+        assert_eq!(stats[0].retrievals, 1);
+        assert_eq!(stats[0].citations, 2);
+        assert_eq!(stats[0].retrieved_chunks, 1);
+        assert!(stats[0].last_retrieved_at.is_some());
+        assert_eq!(stats[1].video_id, "video2");
+        assert_eq!(stats[1].retrievals, 0);
+        assert!(stats[1].last_retrieved_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replication_and_read_only_replica() {
+        let dir = std::env::temp_dir().join(format!("lytt-replica-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("vectors.db");
+
+        let settings = ReplicationSettings {
+            enabled: true,
+            checkpoint_interval_secs: 1,
+            ..Default::default()
+        };
+        let primary = SqliteVectorStore::new(&path).unwrap().with_replication(&settings).unwrap();
+        let count = SCAN_PAGE_SIZE as usize + 5;
+        let docs: Vec<Document> = (0..count)
+            .map(|i| {
+                Document::new(
+                    "video1".to_string(),
+                    "Lecture".to_string(),
+                    None,
+                    format!("Chunk {}", i),
+                    i as f64,
+                    i as f64 + 1.0,
+                    if i + 1 == count { vec![0.0, 1.0] } else { vec![1.0, 0.0] },
+                    i as i32,
+                    None,
+                )
+            })
+            .collect();
+        primary.upsert_batch(&docs).await.unwrap();
+
+        // Paged scans still see every document
+        let results = primary.search(&[1.0, 0.0], count).await.unwrap();
+        assert_eq!(results.len(), count);
+        drop(primary);
+
+        let replica = SqliteVectorStore::open_read_only(&path).unwrap();
+        assert!(replica.is_read_only());
+        let results = replica.search(&[0.0, 1.0], 1).await.unwrap();
+        assert_eq!(results[0].document.content, format!("Chunk {}", count - 1));
+        replica.record_citations(&[docs[0].id]).await.unwrap();
+        assert!(replica.upsert(&docs[0]).await.is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(SqliteVectorStore::open_read_only(&path).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_access() {
+        let dir = std::env::temp_dir().join(format!("lytt-pool-{}", uuid::Uuid::new_v4()));
+        let store = Arc::new(SqliteVectorStore::new(&dir.join("vectors.db")).unwrap().with_pool_size(4).unwrap());
+
+        // Writers and readers on separate pooled connections
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    let docs: Vec<Document> = (0..20)
+                        .map(|j| Document::new(format!("video{}", i), "Title".to_string(), None, format!("Chunk {}", j), 0.0, 1.0, vec![1.0, j as f32], j, None))
+                        .collect();
+                    store.upsert_batch(&docs).await?;
+                    store.search(&[1.0, 0.0], 5).await?;
+                    store.list_videos().await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(store.document_count().await.unwrap(), 160);
+        assert_eq!(store.list_videos().await.unwrap().len(), 8);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_maintenance() {
+        let dir = std::env::temp_dir().join(format!("lytt-maintain-{}", uuid::Uuid::new_v4()));
+        let store = SqliteVectorStore::new(&dir.join("vectors.db")).unwrap();
+        let docs: Vec<Document> = (0..200)
+            .map(|i| Document::new(format!("video{}", i % 2), "Title".to_string(), None, "x".repeat(2000), 0.0, 1.0, vec![1.0; 64], i, None))
+            .collect();
+        store.upsert_batch(&docs).await.unwrap();
+        store.delete_by_video_id("video0").await.unwrap();
+
+        let checkpoint = store.checkpoint(true).unwrap();
+        assert!(!checkpoint.busy);
+        assert_eq!(checkpoint.checkpointed, checkpoint.wal_frames);
+        let before = store.database_size().unwrap();
+        assert!(before.free_bytes > 0);
+        assert_eq!(before.wal_bytes, 0);
+
+        store.vacuum().unwrap();
+        store.analyze().unwrap();
+        let after = store.database_size().unwrap();
+        assert!(after.file_bytes < before.file_bytes);
+        assert_eq!(after.free_bytes, 0);
+
+        assert!(store.integrity_check().unwrap().is_empty());
+        let tables = store.table_sizes().unwrap();
+        let documents = tables.iter().find(|t| t.name == "documents").unwrap();
+        assert_eq!(documents.rows, 100);
+        assert!(!tables.iter().any(|t| t.name.starts_with("idx_")));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_delete_media_records() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let transcript = crate::transcription::Transcript::new(
+            "video1".to_string(),
+            vec![crate::transcription::TranscriptSegment::new(0.0, 5.0, "Hello".to_string())],
+        );
+        store.store_transcript("video1", "Lecture", &transcript).unwrap();
+        store.add_tags("video1", &["physics".to_string()]).unwrap();
+        store.add_tags("video2", &["physics".to_string()]).unwrap();
+        store.set_media_source("video1", SourceType::Local, "/media/lecture.mp3").unwrap();
+        assert_eq!(
+            store.get_media_source("video1").unwrap(),
+            Some((SourceType::Local, "/media/lecture.mp3".to_string()))
+        );
+        store.record_ingested_file("abc123", "/media/lecture.mp3", "video1").unwrap();
+        assert_eq!(
+            store.ingested_file("abc123").unwrap(),
+            Some(("/media/lecture.mp3".to_string(), "video1".to_string()))
+        );
+        store.store_transcript("video3", "Interview", &transcript).unwrap();
+        store.set_chunking_fingerprint("video1", "old").unwrap();
+        let stale = |current: &str| -> Vec<String> {
+            store.stale_transcripts(current).unwrap().into_iter().map(|(id, _, _)| id).collect()
+        };
+        let mut all = stale("new");
+        all.sort();
+        assert_eq!(all, ["video1", "video3"]);
+        assert_eq!(stale("old"), ["video3"]);
+
+        store.delete_media_records("video1").unwrap();
+        assert_eq!(stale("old"), ["video3"]);
+
+        assert!(!store.has_transcript("video1").unwrap());
+        assert!(store.get_media_source("video1").unwrap().is_none());
+        assert!(store.ingested_file("abc123").unwrap().is_none());
+
+        assert!(store.get_tags("video1").unwrap().is_empty());
+        assert_eq!(store.get_tags("video2").unwrap(), vec!["physics"]);
+
+        let doc = Document::new("video2".to_string(), "Talk".to_string(), None, "x".to_string(), 0.0, 1.0, vec![1.0; 4], 0, None);
+        store.upsert(&doc).await.unwrap();
+        assert_eq!(store.rename_media("video2", "local_0123456789abcdef").unwrap(), 2);
+        assert!(store.get_tags("video2").unwrap().is_empty());
+        assert_eq!(store.get_by_video_id("local_0123456789abcdef").await.unwrap().len(), 1);
+        assert_eq!(store.get_tags("local_0123456789abcdef").unwrap(), vec!["physics"]);
+    }
+
+    #[tokio::test]
+    async fn test_for_each_document() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let doc = |video_id: &str, order: i32| {
+            Document::new(video_id.to_string(), "Talk".to_string(), None, format!("{} {}", video_id, order), order as f64, order as f64 + 1.0, vec![1.0; 4], order, None)
+        };
+        let docs = vec![doc("b", 1), doc("a", 0), doc("b", 0)];
+        store.upsert_batch(&docs).await.unwrap();
+
+        let mut seen = Vec::new();
+        let count = store
+            .for_each_document(&mut |doc| {
+                seen.push(doc.content);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(seen, ["a 0", "b 0", "b 1"]);
+    }
+
+    #[test]
+    fn test_glossary_storage() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let term = |video_id: &str, term: &str| GlossaryTerm {
+            term: term.to_string(),
+            definition: format!("Definition of {}", term),
+            video_id: video_id.to_string(),
+            video_title: "Lecture".to_string(),
+            timestamp: Some("01:00".to_string()),
+            model: "gpt-4o-mini".to_string(),
+            created_at: Utc::now(),
+        };
+
+        store.store_glossary("video1", &[term("video1", "Entropy"), term("video1", "Qubit")]).unwrap();
+        store.store_glossary("video2", &[term("video2", "entropy")]).unwrap();
+        assert_eq!(store.get_glossary("video1").unwrap().len(), 2);
+        assert_eq!(store.glossary_vocabulary(10).unwrap(), vec!["Entropy", "Qubit"]);
+
+        // Re-extraction replaces a media item's terms
+        store.store_glossary("video1", &[term("video1", "Bit")]).unwrap();
+        let terms = store.get_glossary("video1").unwrap();
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].timestamp.as_deref(), Some("01:00"));
+
+        store.delete_media_records("video2").unwrap();
+        assert_eq!(store.glossary_vocabulary(10).unwrap(), vec!["Bit"]);
+    }
+
+    #[tokio::test]
+    async fn test_answer_cache() {
+        let store = SqliteVectorStore::in_memory().unwrap();
+        let version = store.corpus_version().await.unwrap().unwrap();
+        let hour = Duration::from_secs(3600);
+
+        store.cache_answer("q", version, "{\"answer\": 1}").await.unwrap();
+        assert_eq!(store.cached_answer("q", version, hour).await.unwrap().as_deref(), Some("{\"answer\": 1}"));
+        assert!(store.cached_answer("other", version, hour).await.unwrap().is_none());
+
+        // Any change to the documents invalidates cached answers
+        let doc = Document::new(
+            "video1".to_string(),
+            "Test Video".to_string(),
+            None,
+            "content".to_string(),
+            0.0,
+            60.0,
+            vec![1.0, 0.0],
+            0,
+            None,
+        );
+        store.upsert(&doc).await.unwrap();
+        let changed = store.corpus_version().await.unwrap().unwrap();
+        assert!(changed > version);
+        assert!(store.cached_answer("q", changed, hour).await.unwrap().is_none());
+
+        store.delete_by_video_id("video1").await.unwrap();
+        assert!(store.corpus_version().await.unwrap().unwrap() > changed);
+    }
 }
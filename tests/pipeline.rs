@@ -0,0 +1,210 @@
+//! End-to-end pipeline tests with mock providers and golden snapshots.
+//!
+//! Run with `cargo test --features e2e`; accept changed output with
+//! `LYTT_UPDATE_SNAPSHOTS=1`.
+
+#![cfg(feature = "e2e")]
+
+use lytt::testing::{
+    assert_snapshot, fixture_path, render_chunks, test_orchestrator, transcript_fixture, MockTranscriber,
+};
+use lytt::transcription::{format_transcript, OutputFormat};
+use std::sync::Arc;
+
+const MEDIA_ID: &str = "fixture-lecture";
+const TITLE: &str = "Information Theory 101";
+
+fn ffmpeg_available() -> bool {
+    ["ffmpeg", "ffprobe"].iter().all(|tool| {
+        std::process::Command::new(tool)
+            .arg("-version")
+            .output()
+            .is_ok_and(|o| o.status.success())
+    })
+}
+
+#[tokio::test]
+async fn rechunk_indexes_stored_transcript() {
+    let dir = tempfile::tempdir().unwrap();
+    let transcript = transcript_fixture("lecture");
+    let orchestrator = test_orchestrator(dir.path(), Arc::new(MockTranscriber::new(transcript.clone()))).unwrap();
+
+    orchestrator
+        .sqlite_store()
+        .store_transcript(MEDIA_ID, TITLE, &transcript)
+        .unwrap();
+    let result = orchestrator.rechunk_media(MEDIA_ID).await.unwrap();
+
+    let documents = orchestrator.vector_store().get_by_video_id(MEDIA_ID).await.unwrap();
+    assert_eq!(result.chunks_indexed, documents.len());
+    assert!(documents.iter().all(|d| d.video_title == TITLE));
+    assert_snapshot("lecture_chunks", &render_chunks(&documents));
+}
+
+#[tokio::test]
+async fn search_finds_relevant_chunk() {
+    let dir = tempfile::tempdir().unwrap();
+    let transcript = transcript_fixture("lecture");
+    let orchestrator = test_orchestrator(dir.path(), Arc::new(MockTranscriber::new(transcript.clone()))).unwrap();
+
+    orchestrator
+        .sqlite_store()
+        .store_transcript(MEDIA_ID, TITLE, &transcript)
+        .unwrap();
+    orchestrator.rechunk_media(MEDIA_ID).await.unwrap();
+
+    let query = orchestrator.embedder().embed("Huffman coding and compression").await.unwrap();
+    let results = orchestrator.vector_store().search(&query, 1).await.unwrap();
+    assert!(results[0].document.content.contains("Huffman"));
+}
+
+#[test]
+fn exports_match_snapshots() {
+    let transcript = transcript_fixture("lecture");
+
+    assert_snapshot("lecture_export.json", &format_transcript(&transcript, OutputFormat::Json));
+    assert_snapshot("lecture_export.srt", &format_transcript(&transcript, OutputFormat::Srt));
+    assert_snapshot("lecture_export.vtt", &format_transcript(&transcript, OutputFormat::Vtt));
+}
+
+#[tokio::test]
+async fn process_local_audio_fixture() {
+    if !ffmpeg_available() {
+        eprintln!("Skipping process_local_audio_fixture: ffmpeg/ffprobe not installed");
+        return;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let audio = dir.path().join("lecture.wav");
+    std::fs::copy(fixture_path("lecture.wav"), &audio).unwrap();
+
+    let transcriber = Arc::new(MockTranscriber::new(transcript_fixture("lecture")));
+    let orchestrator = test_orchestrator(dir.path(), transcriber.clone()).unwrap();
+
+    let input = audio.display().to_string();
+    let result = orchestrator.process_media(&input, false).await.unwrap();
+    assert!(!result.skipped);
+    assert_eq!(transcriber.calls(), 1);
+
+    let documents = orchestrator.vector_store().get_by_video_id(&result.media_id).await.unwrap();
+    assert_snapshot("lecture_chunks", &render_chunks(&documents));
+
+    // A second run is skipped without transcribing again
+    let again = orchestrator.process_media(&input, false).await.unwrap();
+    assert!(again.skipped);
+    assert_eq!(transcriber.calls(), 1);
+}
+
+/// Files left in the temp directory by runs, other than workspace locks.
+fn leftover_files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .flat_map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                leftover_files(&path)
+            } else if path.file_name().is_some_and(|n| n == ".lock") {
+                Vec::new()
+            } else {
+                vec![path]
+            }
+        })
+        .collect()
+}
+
+/// Transcriber whose every call fails, like an API outage.
+struct FailingTranscriber;
+
+#[async_trait::async_trait]
+impl lytt::transcription::Transcriber for FailingTranscriber {
+    async fn transcribe(&self, _audio_path: &std::path::Path) -> lytt::Result<lytt::transcription::Transcript> {
+        Err(lytt::LyttError::Transcription("service unavailable".to_string()))
+    }
+
+    async fn transcribe_with_language(
+        &self,
+        audio_path: &std::path::Path,
+        _language: &str,
+    ) -> lytt::Result<lytt::transcription::Transcript> {
+        self.transcribe(audio_path).await
+    }
+}
+
+#[tokio::test]
+async fn attachment_leaves_no_audio_or_documents_behind() {
+    if !ffmpeg_available() {
+        eprintln!("Skipping attachment_leaves_no_audio_or_documents_behind: ffmpeg/ffprobe not installed");
+        return;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let audio = dir.path().join("lecture.wav");
+    std::fs::copy(fixture_path("lecture.wav"), &audio).unwrap();
+    let input = audio.display().to_string();
+    let temp_dir = dir.path().join("tmp");
+
+    // `ask --attach` transcribes into memory only
+    let orchestrator = test_orchestrator(dir.path(), Arc::new(MockTranscriber::new(transcript_fixture("lecture")))).unwrap();
+    let documents = orchestrator.transcribe_ephemeral(&input).await.unwrap();
+    assert!(!documents.is_empty());
+    assert!(leftover_files(&temp_dir).is_empty(), "{:?}", leftover_files(&temp_dir));
+    assert_eq!(orchestrator.vector_store().document_count().await.unwrap(), 0);
+    assert!(orchestrator.list_rechunkable().await.unwrap().is_empty());
+
+    // The downloaded copy is removed when transcription fails too
+    let settings = lytt::testing::test_settings(dir.path());
+    let store = Arc::new(lytt::vector_store::SqliteVectorStore::new(&settings.sqlite_path()).unwrap());
+    let failing = lytt::orchestrator::Orchestrator::with_components(
+        settings,
+        Default::default(),
+        Arc::new(FailingTranscriber),
+        Arc::new(lytt::testing::MockEmbedder::default()),
+        store,
+    )
+    .unwrap();
+    assert!(failing.transcribe_ephemeral(&input).await.is_err());
+    assert!(leftover_files(&temp_dir).is_empty(), "{:?}", leftover_files(&temp_dir));
+}
+
+#[tokio::test]
+async fn changed_chunking_settings_mark_media_stale() {
+    let dir = tempfile::tempdir().unwrap();
+    let transcript = transcript_fixture("lecture");
+    let orchestrator = test_orchestrator(dir.path(), Arc::new(MockTranscriber::new(transcript.clone()))).unwrap();
+
+    orchestrator
+        .sqlite_store()
+        .store_transcript(MEDIA_ID, TITLE, &transcript)
+        .unwrap();
+    let first = orchestrator.rechunk_media(MEDIA_ID).await.unwrap();
+    assert!(orchestrator.list_stale().await.unwrap().is_empty());
+    drop(orchestrator);
+
+    // Shorter chunks: the media was chunked with other settings
+    let mut settings = lytt::testing::test_settings(dir.path());
+    settings.chunking.target_chunk_seconds = 40;
+    settings.chunking.min_chunk_seconds = 20;
+    settings.chunking.max_chunk_seconds = 60;
+    let store = Arc::new(lytt::vector_store::SqliteVectorStore::new(&settings.sqlite_path()).unwrap());
+    let orchestrator = lytt::orchestrator::Orchestrator::with_components(
+        settings,
+        Default::default(),
+        Arc::new(MockTranscriber::new(transcript)),
+        Arc::new(lytt::testing::MockEmbedder::default()),
+        store,
+    )
+    .unwrap();
+    let stale = orchestrator.list_stale().await.unwrap();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].0, MEDIA_ID);
+
+    // Re-indexing with the new settings brings it up to date
+    let second = orchestrator.rechunk_media(MEDIA_ID).await.unwrap();
+    assert!(second.chunks_indexed > first.chunks_indexed);
+    assert!(orchestrator.list_stale().await.unwrap().is_empty());
+    let documents = orchestrator.vector_store().get_by_video_id(MEDIA_ID).await.unwrap();
+    assert_eq!(documents.len(), second.chunks_indexed);
+}